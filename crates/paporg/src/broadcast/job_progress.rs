@@ -6,6 +6,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
+use crate::categorizer::{CategorizationExplanation, CategoryAlternative};
+
 /// Phase of job processing.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -76,6 +78,18 @@ pub struct JobProgressEvent {
     /// Detected category (set on completion).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
+    /// Score of the chosen rule (set on completion in scoring mode).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Runner-up rules (set on completion in scoring mode).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<CategoryAlternative>,
+    /// Tags from matching non-exclusive rules (set on completion).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Which rule conditions matched, and where (set on completion).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<CategorizationExplanation>,
     /// Error message (set on failure).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
@@ -91,6 +105,9 @@ pub struct JobProgressEvent {
     /// MIME type of the source file.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
+    /// ID of the archive job this document was extracted from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_job_id: Option<String>,
 }
 
 impl JobProgressEvent {
@@ -113,11 +130,16 @@ impl JobProgressEvent {
             archive_path: None,
             symlinks: vec![],
             category: None,
+            confidence: None,
+            alternatives: vec![],
+            tags: vec![],
+            explanation: None,
             error: None,
             ocr_text: None,
             source_path: None,
             source_name: None,
             mime_type: None,
+            parent_job_id: None,
         }
     }
 
@@ -126,7 +148,7 @@ impl JobProgressEvent {
         job_id: &str,
         filename: &str,
         output_path: &str,
-        archive_path: &str,
+        archive_path: Option<&str>,
         symlinks: &[String],
         category: &str,
         ocr_text: &str,
@@ -139,17 +161,45 @@ impl JobProgressEvent {
             message: "Processing completed successfully".to_string(),
             timestamp: Utc::now(),
             output_path: Some(output_path.to_string()),
-            archive_path: Some(archive_path.to_string()),
+            archive_path: archive_path.map(str::to_string),
             symlinks: symlinks.to_vec(),
             category: Some(category.to_string()),
+            confidence: None,
+            alternatives: vec![],
+            tags: vec![],
+            explanation: None,
             error: None,
             ocr_text: Some(ocr_text.to_string()),
             source_path: None,
             source_name: None,
             mime_type: None,
+            parent_job_id: None,
         }
     }
 
+    /// Adds the scoring-mode confidence and runner-up rules.
+    pub fn with_confidence(
+        mut self,
+        confidence: Option<f64>,
+        alternatives: Vec<CategoryAlternative>,
+    ) -> Self {
+        self.confidence = confidence;
+        self.alternatives = alternatives;
+        self
+    }
+
+    /// Adds the tags applied by non-exclusive rules.
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Adds the trace of how the document was categorized.
+    pub fn with_explanation(mut self, explanation: Option<CategorizationExplanation>) -> Self {
+        self.explanation = explanation;
+        self
+    }
+
     /// Creates a failure event.
     pub fn failed(job_id: &str, filename: &str, error: &str) -> Self {
         Self {
@@ -163,11 +213,16 @@ impl JobProgressEvent {
             archive_path: None,
             symlinks: vec![],
             category: None,
+            confidence: None,
+            alternatives: vec![],
+            tags: vec![],
+            explanation: None,
             error: Some(error.to_string()),
             ocr_text: None,
             source_path: None,
             source_name: None,
             mime_type: None,
+            parent_job_id: None,
         }
     }
 }
@@ -251,6 +306,7 @@ pub struct JobProgressTracker {
     source_path: Option<String>,
     source_name: Option<String>,
     mime_type: Option<String>,
+    parent_job_id: Option<String>,
     sender: Arc<broadcast::Sender<JobProgressEvent>>,
 }
 
@@ -267,6 +323,7 @@ impl JobProgressTracker {
             source_path: None,
             source_name: None,
             mime_type: None,
+            parent_job_id: None,
             sender,
         }
     }
@@ -286,15 +343,23 @@ impl JobProgressTracker {
             source_path: Some(source_path.to_string()),
             source_name: source_name.map(|s| s.to_string()),
             mime_type: mime_type.map(|s| s.to_string()),
+            parent_job_id: None,
             sender,
         }
     }
 
+    /// Links events to the archive job this document was extracted from.
+    pub fn with_parent_job(mut self, parent_job_id: Option<&str>) -> Self {
+        self.parent_job_id = parent_job_id.map(|s| s.to_string());
+        self
+    }
+
     /// Adds source information to events.
     fn add_source_info(&self, mut event: JobProgressEvent) -> JobProgressEvent {
         event.source_path = self.source_path.clone();
         event.source_name = self.source_name.clone();
         event.mime_type = self.mime_type.clone();
+        event.parent_job_id = self.parent_job_id.clone();
         event
     }
 
@@ -306,12 +371,17 @@ impl JobProgressTracker {
     }
 
     /// Marks the job as completed with result details.
+    #[allow(clippy::too_many_arguments)]
     pub fn completed(
         &self,
         output_path: &str,
-        archive_path: &str,
+        archive_path: Option<&str>,
         symlinks: &[String],
         category: &str,
+        confidence: Option<f64>,
+        alternatives: &[CategoryAlternative],
+        tags: &[String],
+        explanation: Option<&CategorizationExplanation>,
         ocr_text: &str,
     ) {
         let event = JobProgressEvent::completed(
@@ -322,7 +392,10 @@ impl JobProgressTracker {
             symlinks,
             category,
             ocr_text,
-        );
+        )
+        .with_confidence(confidence, alternatives.to_vec())
+        .with_tags(tags.to_vec())
+        .with_explanation(explanation.cloned());
         let event = self.add_source_info(event);
         let _ = self.sender.send(event);
     }
@@ -391,9 +464,13 @@ mod tests {
 
         tracker.completed(
             "/output/invoices/invoice.pdf",
-            "/archive/invoice.pdf",
+            Some("/archive/invoice.pdf"),
             &["/symlinks/2024/invoice.pdf".to_string()],
             "invoices",
+            None,
+            &[],
+            &[],
+            None,
             "Invoice #123\nTotal: $100.00",
         );
 
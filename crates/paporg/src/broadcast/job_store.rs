@@ -7,8 +7,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::broadcast::job_progress::{JobPhase, JobProgressEvent, JobStatus};
+use crate::categorizer::{CategorizationExplanation, CategoryAlternative, Classifier};
 use crate::db::job_repo::{self, JobFilter, JobRow};
-use crate::db::{stats_repo, Database, DatabaseError};
+use crate::db::{stats_repo, tag_repo, Database, DatabaseError};
 
 // ─── Helpers ────────────────────────────────────────────────────────────────
 
@@ -119,6 +120,18 @@ pub struct StoredJob {
     /// Detected category.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
+    /// Score of the chosen rule in scoring mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Runner-up rules in scoring mode.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<CategoryAlternative>,
+    /// Tags from matching non-exclusive rules.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Which rule conditions matched, and where.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<CategorizationExplanation>,
     /// Error message (if failed).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
@@ -136,6 +149,9 @@ pub struct StoredJob {
     /// MIME type of the source file.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
+    /// ID of the archive job this document was extracted from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_job_id: Option<String>,
 }
 
 impl StoredJob {
@@ -157,12 +173,17 @@ impl StoredJob {
             archive_path: event.archive_path.clone(),
             symlinks: event.symlinks.clone(),
             category: event.category.clone(),
+            confidence: event.confidence,
+            alternatives: event.alternatives.clone(),
+            tags: event.tags.clone(),
+            explanation: event.explanation.clone(),
             error: event.error.clone(),
             message: event.message.clone(),
             source_path: event.source_path.clone(),
             source_name: event.source_name.clone(),
             ignored: false,
             mime_type: event.mime_type.clone(),
+            parent_job_id: event.parent_job_id.clone(),
         }
     }
 
@@ -175,6 +196,15 @@ impl StoredJob {
             .as_ref()
             .and_then(|s| serde_json::from_str(s).ok())
             .unwrap_or_default();
+        let alternatives: Vec<CategoryAlternative> = row
+            .alternatives
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default();
+        let explanation = row
+            .explanation
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok());
         let ignored = row.status == "ignored";
         let started_at = parse_timestamp(&row.created_at);
         let completed_at = row.completed_at.as_ref().map(|s| parse_timestamp(s));
@@ -194,12 +224,17 @@ impl StoredJob {
             } else {
                 Some(row.category.clone())
             },
+            confidence: row.confidence,
+            alternatives,
+            tags: vec![],
+            explanation,
             error: row.error.clone(),
             message: row.message.clone().unwrap_or_default(),
             source_path: Some(row.source_path.clone()),
             source_name: row.source_name.clone(),
             ignored,
             mime_type: row.mime_type.clone(),
+            parent_job_id: row.parent_job_id.clone(),
         }
     }
 
@@ -225,6 +260,12 @@ impl StoredJob {
         if event.category.is_some() {
             self.category = event.category.clone();
         }
+        if matches!(event.status, JobStatus::Completed) {
+            self.confidence = event.confidence;
+            self.alternatives = event.alternatives.clone();
+            self.tags = event.tags.clone();
+            self.explanation = event.explanation.clone();
+        }
         if event.error.is_some() {
             self.error = event.error.clone();
         }
@@ -236,6 +277,22 @@ impl StoredJob {
     }
 }
 
+/// Converts database rows to stored jobs, attaching their tags.
+fn jobs_from_rows(db: &Database, rows: &[JobRow]) -> Vec<StoredJob> {
+    let ids: Vec<&str> = rows.iter().map(|row| row.id.as_str()).collect();
+    let mut tags = tag_repo::find_for_jobs(db, &ids).unwrap_or_else(|e| {
+        log::error!("Failed to load job tags from database: {}", e);
+        HashMap::new()
+    });
+    rows.iter()
+        .map(|row| {
+            let mut job = StoredJob::from_job_row(row);
+            job.tags = tags.remove(&row.id).unwrap_or_default();
+            job
+        })
+        .collect()
+}
+
 // ─── Query types ────────────────────────────────────────────────────────────
 
 /// Query parameters for job listing.
@@ -245,6 +302,7 @@ pub struct JobQueryParams {
     pub status: Option<String>,
     pub category: Option<String>,
     pub source_name: Option<String>,
+    pub tag: Option<String>,
     pub from_date: Option<String>,
     pub to_date: Option<String>,
     pub limit: Option<u64>,
@@ -263,6 +321,15 @@ pub struct JobListResponse {
     pub offset: Option<u64>,
 }
 
+/// Result of correcting a job's category by hand.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryCorrection {
+    pub job: StoredJob,
+    /// Whether the classifier had the job's text and learned the correction.
+    pub learned: bool,
+}
+
 // ─── JobStore ───────────────────────────────────────────────────────────────
 
 /// Persistent job store backed by rusqlite.
@@ -274,6 +341,8 @@ pub struct JobStore {
     db: RwLock<Option<Database>>,
     /// In-memory cache for real-time updates.
     cache: RwLock<HashMap<String, StoredJob>>,
+    /// Classifier trained on completed jobs; available once a database is set.
+    classifier: RwLock<Option<Classifier>>,
 }
 
 impl JobStore {
@@ -282,6 +351,7 @@ impl JobStore {
         Self {
             db: RwLock::new(None),
             cache: RwLock::new(HashMap::new()),
+            classifier: RwLock::new(None),
         }
     }
 
@@ -294,7 +364,28 @@ impl JobStore {
                 poisoned.into_inner()
             }
         };
-        *guard = Some(db);
+        *guard = Some(db.clone());
+        drop(guard);
+
+        let classifier = match Classifier::open(db) {
+            Ok(classifier) => Some(classifier),
+            Err(e) => {
+                log::error!("Failed to load classifier examples: {}", e);
+                None
+            }
+        };
+        match self.classifier.write() {
+            Ok(mut g) => *g = classifier,
+            Err(poisoned) => *poisoned.into_inner() = classifier,
+        }
+    }
+
+    /// Returns the classifier trained on this store's jobs, if a database is set.
+    pub fn classifier(&self) -> Option<Classifier> {
+        match self.classifier.read() {
+            Ok(g) => g.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
     }
 
     /// Gets a cloned database handle if available.
@@ -340,6 +431,15 @@ impl JobStore {
         let status = status_to_str(&event.status);
         let phase = phase_to_str(&event.phase);
         let symlinks_json = serde_json::to_string(&event.symlinks).ok();
+        let alternatives_json = if event.alternatives.is_empty() {
+            None
+        } else {
+            serde_json::to_string(&event.alternatives).ok()
+        };
+        let explanation_json = event
+            .explanation
+            .as_ref()
+            .and_then(|e| serde_json::to_string(e).ok());
 
         let existing = job_repo::find_by_id(db, &event.job_id)?;
 
@@ -365,6 +465,11 @@ impl JobStore {
             if !event.symlinks.is_empty() {
                 row.symlinks = symlinks_json;
             }
+            if matches!(event.status, JobStatus::Completed) {
+                row.confidence = event.confidence;
+                row.alternatives = alternatives_json;
+                row.explanation = explanation_json;
+            }
             if matches!(event.status, JobStatus::Completed | JobStatus::Failed) {
                 row.completed_at = Some(format_timestamp(event.timestamp));
             }
@@ -409,11 +514,20 @@ impl JobStore {
                 current_phase: Some(phase.to_string()),
                 message: Some(event.message.clone()),
                 mime_type: event.mime_type.clone(),
+                parent_job_id: event.parent_job_id.clone(),
+                confidence: event.confidence,
+                alternatives: alternatives_json,
+                explanation: explanation_json,
             };
 
             job_repo::insert(db, &row)?;
         }
 
+        if matches!(event.status, JobStatus::Completed) {
+            tag_repo::replace_for_job(db, &event.job_id, &event.tags)?;
+            self.learn(event);
+        }
+
         // Record statistics on completion/failure
         if matches!(event.status, JobStatus::Completed | JobStatus::Failed) {
             self.record_stats(db, event);
@@ -422,6 +536,26 @@ impl JobStore {
         Ok(())
     }
 
+    /// Keeps the text of a completed job as a classifier example. Only
+    /// categories chosen by a rule are trusted; predicted and unsorted
+    /// documents stay unlabeled until their category is corrected.
+    fn learn(&self, event: &JobProgressEvent) {
+        let Some(classifier) = self.classifier() else {
+            return;
+        };
+        let Some(text) = event.ocr_text.as_deref().filter(|t| !t.trim().is_empty()) else {
+            return;
+        };
+        let label = event
+            .explanation
+            .as_ref()
+            .filter(|e| e.winner.is_some())
+            .and(event.category.as_deref());
+        if let Err(e) = classifier.learn(&event.job_id, label, text) {
+            log::error!("Failed to store classifier example: {}", e);
+        }
+    }
+
     /// Records processing statistics for a completed/failed job.
     fn record_stats(&self, db: &Database, event: &JobProgressEvent) {
         let duration_ms = self
@@ -473,7 +607,7 @@ impl JobStore {
                 ..Default::default()
             };
             match job_repo::query(&db, &filter) {
-                Ok((rows, _)) => return rows.iter().map(StoredJob::from_job_row).collect(),
+                Ok((rows, _)) => return jobs_from_rows(&db, &rows),
                 Err(e) => log::error!("Failed to query jobs from database: {}", e),
             }
         }
@@ -487,6 +621,7 @@ impl JobStore {
                 status: params.status.clone(),
                 category: params.category.clone(),
                 source_name: params.source_name.clone(),
+                tag: params.tag.clone(),
                 from_date: params.from_date.clone(),
                 to_date: params.to_date.clone(),
                 exclude_status: Some("superseded".to_string()),
@@ -494,7 +629,7 @@ impl JobStore {
                 offset: params.offset,
             };
             let (rows, total) = job_repo::query(&db, &filter)?;
-            let jobs = rows.iter().map(StoredJob::from_job_row).collect();
+            let jobs = jobs_from_rows(&db, &rows);
             Ok(JobListResponse {
                 jobs,
                 total,
@@ -523,6 +658,9 @@ impl JobStore {
         if let Some(ref category) = params.category {
             jobs.retain(|j| j.category.as_deref() == Some(category.as_str()));
         }
+        if let Some(ref tag) = params.tag {
+            jobs.retain(|j| j.tags.contains(tag));
+        }
 
         jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
 
@@ -558,7 +696,9 @@ impl JobStore {
         }
         if let Some(db) = self.get_database() {
             if let Ok(Some(row)) = job_repo::find_by_id(&db, job_id) {
-                return Some(StoredJob::from_job_row(&row));
+                let mut job = StoredJob::from_job_row(&row);
+                job.tags = tag_repo::find_by_job(&db, job_id).unwrap_or_default();
+                return Some(job);
             }
         }
         None
@@ -623,6 +763,10 @@ impl JobStore {
             let now = format_timestamp(Utc::now());
             job_repo::update_status(&db, job_id, "superseded", &now)?;
         }
+        // The re-run job takes over as the classifier example.
+        if let Some(classifier) = self.classifier() {
+            classifier.relabel(job_id, None)?;
+        }
 
         if let Ok(mut cache) = self.cache.write() {
             cache.remove(job_id);
@@ -651,6 +795,38 @@ impl JobStore {
         Ok(self.get_with_fallback(job_id))
     }
 
+    /// Sets the category of a job by hand and teaches it to the classifier.
+    /// The document is not moved. Returns `None` if the job does not exist.
+    pub fn correct_category(
+        &self,
+        job_id: &str,
+        category: &str,
+    ) -> Result<Option<CategoryCorrection>, DatabaseError> {
+        if let Some(db) = self.get_database() {
+            let Some(mut row) = job_repo::find_by_id(&db, job_id)? else {
+                return Ok(None);
+            };
+            row.category = category.to_string();
+            row.updated_at = format_timestamp(Utc::now());
+            job_repo::update(&db, &row)?;
+        }
+
+        if let Ok(mut cache) = self.cache.write() {
+            if let Some(job) = cache.get_mut(job_id) {
+                job.category = Some(category.to_string());
+            }
+        }
+
+        let learned = match self.classifier() {
+            Some(classifier) => classifier.relabel(job_id, Some(category))?,
+            None => false,
+        };
+
+        Ok(self
+            .get_with_fallback(job_id)
+            .map(|job| CategoryCorrection { job, learned }))
+    }
+
     /// Inserts a new job directly (for re-run jobs).
     pub fn insert_job(
         &self,
@@ -681,6 +857,10 @@ impl JobStore {
                 current_phase: Some("queued".to_string()),
                 message: Some("Job queued for processing".to_string()),
                 mime_type: mime_type.map(|s| s.to_string()),
+                parent_job_id: None,
+                confidence: None,
+                alternatives: None,
+                explanation: None,
             };
             job_repo::insert(&db, &row)?;
         } else {
@@ -703,12 +883,17 @@ impl JobStore {
                 archive_path: None,
                 symlinks: vec![],
                 category: Some("unsorted".to_string()),
+                confidence: None,
+                alternatives: vec![],
+                tags: vec![],
+                explanation: None,
                 error: None,
                 message: "Job queued for processing".to_string(),
                 source_path: Some(source_path.to_string()),
                 source_name: source_name.map(|s| s.to_string()),
                 ignored: false,
                 mime_type: mime_type.map(|s| s.to_string()),
+                parent_job_id: None,
             };
             cache.insert(job_id.to_string(), job);
         }
@@ -745,17 +930,15 @@ impl JobStore {
         let mut loaded = 0;
         if let Ok(mut cache) = self.cache.write() {
             if let Ok((rows, _)) = processing_result {
-                for row in &rows {
-                    let job = StoredJob::from_job_row(row);
+                for job in jobs_from_rows(&db, &rows) {
                     cache.insert(job.job_id.clone(), job);
                     loaded += 1;
                 }
             }
 
             if let Ok((rows, _)) = recent_result {
-                for row in &rows {
-                    if !cache.contains_key(&row.id) {
-                        let job = StoredJob::from_job_row(row);
+                for job in jobs_from_rows(&db, &rows) {
+                    if !cache.contains_key(&job.job_id) {
                         cache.insert(job.job_id.clone(), job);
                         loaded += 1;
                     }
@@ -828,6 +1011,10 @@ mod tests {
             current_phase: Some("completed".to_string()),
             message: Some("Done".to_string()),
             mime_type: Some("application/pdf".to_string()),
+            parent_job_id: None,
+            confidence: None,
+            alternatives: None,
+            explanation: None,
         };
 
         let job = StoredJob::from_job_row(&row);
@@ -859,6 +1046,10 @@ mod tests {
             current_phase: None,
             message: None,
             mime_type: None,
+            parent_job_id: None,
+            confidence: None,
+            alternatives: None,
+            explanation: None,
         };
 
         let job = StoredJob::from_job_row(&row);
@@ -952,6 +1143,195 @@ mod tests {
         assert!(row.completed_at.is_some());
     }
 
+    #[test]
+    fn test_persist_categorization_confidence() {
+        let db = Database::open_in_memory().expect("open in-memory DB");
+        let store = JobStore::new(10);
+        store.set_database(db.clone());
+
+        let event = create_event_with_source("db-2", JobPhase::Queued, "/tmp/test.pdf");
+        store.update_and_persist(&event);
+
+        let alternatives = vec![CategoryAlternative {
+            rule_id: "health".to_string(),
+            category: "health".to_string(),
+            confidence: 0.61,
+        }];
+        let mut completion = create_event("db-2", JobPhase::Completed)
+            .with_confidence(Some(0.82), alternatives.clone());
+        completion.category = Some("insurance".to_string());
+        store.update_and_persist(&completion);
+
+        let row = job_repo::find_by_id(&db, "db-2").unwrap().unwrap();
+        assert_eq!(row.confidence, Some(0.82));
+
+        let job = StoredJob::from_job_row(&row);
+        assert_eq!(job.category.as_deref(), Some("insurance"));
+        assert_eq!(job.confidence, Some(0.82));
+        assert_eq!(job.alternatives, alternatives);
+
+        let cached = store.get("db-2").unwrap();
+        assert_eq!(cached.confidence, Some(0.82));
+        assert_eq!(cached.alternatives, alternatives);
+    }
+
+    #[test]
+    fn test_persist_tags() {
+        let db = Database::open_in_memory().expect("open in-memory DB");
+        let store = JobStore::new(10);
+        store.set_database(db.clone());
+
+        let event = create_event_with_source("db-3", JobPhase::Queued, "/tmp/test.pdf");
+        store.update_and_persist(&event);
+
+        let tags = vec!["tax".to_string(), "health".to_string()];
+        let completion = create_event("db-3", JobPhase::Completed).with_tags(tags.clone());
+        store.update_and_persist(&completion);
+
+        assert_eq!(store.get("db-3").unwrap().tags, tags);
+        assert_eq!(
+            tag_repo::find_by_job(&db, "db-3").unwrap(),
+            vec!["health".to_string(), "tax".to_string()]
+        );
+
+        let result = store
+            .query(&JobQueryParams {
+                tag: Some("tax".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(result.total, 1);
+        assert_eq!(result.jobs[0].tags, vec!["health", "tax"]);
+    }
+
+    #[test]
+    fn test_persist_explanation() {
+        use crate::categorizer::{ConditionTrace, MatchSpan, RuleExplanation};
+        use crate::config::schema::MatchField;
+
+        let db = Database::open_in_memory().expect("open in-memory DB");
+        let store = JobStore::new(10);
+        store.set_database(db.clone());
+
+        let event = create_event_with_source("db-4", JobPhase::Queued, "/tmp/test.pdf");
+        store.update_and_persist(&event);
+
+        let explanation = CategorizationExplanation {
+            winner: Some(RuleExplanation {
+                rule_id: "invoices".to_string(),
+                category: "invoices".to_string(),
+                matched: true,
+                score: None,
+                condition: ConditionTrace {
+                    kind: "contains".to_string(),
+                    matched: true,
+                    field: None,
+                    values: vec!["Invoice".to_string()],
+                    spans: vec![MatchSpan {
+                        field: Some(MatchField::Text),
+                        start: 4,
+                        end: 11,
+                        text: "Invoice".to_string(),
+                    }],
+                    children: vec![],
+                },
+            }),
+            rejected: vec![],
+            classifier: None,
+            correspondent: None,
+        };
+        let completion =
+            create_event("db-4", JobPhase::Completed).with_explanation(Some(explanation.clone()));
+        store.update_and_persist(&completion);
+
+        let row = job_repo::find_by_id(&db, "db-4").unwrap().unwrap();
+        assert!(row.explanation.is_some());
+        assert_eq!(
+            StoredJob::from_job_row(&row).explanation.as_ref(),
+            Some(&explanation)
+        );
+
+        store.cache.write().unwrap().clear();
+        let job = store.get_with_fallback("db-4").unwrap();
+        assert_eq!(job.explanation, Some(explanation));
+    }
+
+    #[test]
+    fn test_completed_jobs_train_classifier_and_corrections_relabel() {
+        use crate::categorizer::{ConditionTrace, RuleExplanation};
+
+        let db = Database::open_in_memory().expect("open in-memory DB");
+        let store = JobStore::new(10);
+        store.set_database(db.clone());
+
+        let filed_by_rule = CategorizationExplanation {
+            winner: Some(RuleExplanation {
+                rule_id: "invoices".to_string(),
+                category: "invoices".to_string(),
+                matched: true,
+                score: None,
+                condition: ConditionTrace {
+                    kind: "contains".to_string(),
+                    matched: true,
+                    field: None,
+                    values: vec!["Invoice".to_string()],
+                    spans: vec![],
+                    children: vec![],
+                },
+            }),
+            rejected: vec![],
+            classifier: None,
+            correspondent: None,
+        };
+        let completions = [
+            (
+                "cls-1",
+                "invoices",
+                Some(filed_by_rule),
+                "Invoice amount due",
+            ),
+            ("cls-2", "unsorted", None, "Doctor prescription"),
+        ];
+        for (id, category, explanation, text) in completions {
+            store.update_and_persist(&create_event_with_source(
+                id,
+                JobPhase::Queued,
+                "/tmp/test.pdf",
+            ));
+            let mut completion =
+                create_event(id, JobPhase::Completed).with_explanation(explanation);
+            completion.category = Some(category.to_string());
+            completion.ocr_text = Some(text.to_string());
+            store.update_and_persist(&completion);
+        }
+
+        // Only the rule's decision is trained on
+        let classifier = store.classifier().unwrap();
+        assert_eq!(
+            classifier
+                .category_documents()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![("invoices".to_string(), 1)]
+        );
+
+        let correction = store.correct_category("cls-2", "health").unwrap().unwrap();
+        assert!(correction.learned);
+        assert_eq!(correction.job.category.as_deref(), Some("health"));
+        assert_eq!(classifier.category_documents()["health"], 1);
+        let row = job_repo::find_by_id(&db, "cls-2").unwrap().unwrap();
+        assert_eq!(row.category, "health");
+
+        // Superseded jobs no longer count
+        store.mark_superseded("cls-1").unwrap();
+        assert!(!classifier.category_documents().contains_key("invoices"));
+
+        assert!(store
+            .correct_category("missing", "health")
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn test_query_with_db() {
         let db = Database::open_in_memory().expect("open in-memory DB");
@@ -1063,6 +1443,10 @@ mod tests {
             current_phase: Some("completed".to_string()),
             message: Some("Done".to_string()),
             mime_type: None,
+            parent_job_id: None,
+            confidence: None,
+            alternatives: None,
+            explanation: None,
         };
         job_repo::insert(&db, &row).unwrap();
 
@@ -1127,6 +1511,10 @@ mod tests {
             current_phase: None,
             message: None,
             mime_type: None,
+            parent_job_id: None,
+            confidence: None,
+            alternatives: None,
+            explanation: None,
         };
         job_repo::insert(&db, &row).unwrap();
 
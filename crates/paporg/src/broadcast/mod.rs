@@ -12,5 +12,5 @@ pub use git_progress::GitProgressBroadcaster;
 pub use job_progress::{
     JobPhase, JobProgressBroadcaster, JobProgressEvent, JobProgressTracker, JobStatus,
 };
-pub use job_store::{JobListResponse, JobQueryParams, JobStore, StoredJob};
+pub use job_store::{CategoryCorrection, JobListResponse, JobQueryParams, JobStore, StoredJob};
 pub use log_broadcaster::{BroadcastLayer, LogBroadcaster, LogEvent};
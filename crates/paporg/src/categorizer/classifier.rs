@@ -0,0 +1,437 @@
+//! Naive Bayes classifier trained on the job history.
+//!
+//! Every completed job with extracted text is kept as an example. Examples
+//! filed by a rule, or whose category was corrected by hand, are labeled and
+//! trained on; the rest wait unlabeled until they are corrected. When no rule
+//! matches a document, the classifier predicts a category from its words.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::db::classifier_repo::{self, ClassifierExampleRow};
+use crate::db::{Database, DatabaseError};
+
+/// Shortest token that is counted, in characters.
+const MIN_TOKEN_LEN: usize = 2;
+
+/// Longest token that is counted, in characters. Longer runs are usually
+/// OCR noise or identifiers.
+const MAX_TOKEN_LEN: usize = 32;
+
+/// Number of occurrences of each token in a document.
+pub type TokenCounts = BTreeMap<String, u32>;
+
+/// Splits text into lowercase words, ignoring numbers and very short or long runs.
+pub fn tokenize(text: &str) -> TokenCounts {
+    let mut counts = TokenCounts::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let len = word.chars().count();
+        if !(MIN_TOKEN_LEN..=MAX_TOKEN_LEN).contains(&len) {
+            continue;
+        }
+        if word.chars().all(|c| c.is_numeric()) {
+            continue;
+        }
+        *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// A category predicted by the classifier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifierPrediction {
+    pub category: String,
+    /// Probability of the category among the trained ones, between 0 and 1.
+    pub confidence: f64,
+    /// Whether the document was filed under the category rather than only
+    /// given it as a suggestion.
+    pub filed: bool,
+}
+
+/// Training counts of one category.
+#[derive(Debug, Clone, Default)]
+struct CategoryCounts {
+    documents: u64,
+    tokens: u64,
+    counts: HashMap<String, u64>,
+}
+
+/// Multinomial naive Bayes model with add-one smoothing.
+#[derive(Debug, Clone, Default)]
+pub struct NaiveBayes {
+    categories: HashMap<String, CategoryCounts>,
+    /// Occurrences of each token over all categories.
+    vocabulary: HashMap<String, u64>,
+}
+
+impl NaiveBayes {
+    /// Adds a document of the given category.
+    pub fn add(&mut self, category: &str, tokens: &TokenCounts) {
+        let entry = self.categories.entry(category.to_string()).or_default();
+        entry.documents += 1;
+        for (token, &count) in tokens {
+            let count = u64::from(count);
+            entry.tokens += count;
+            *entry.counts.entry(token.clone()).or_insert(0) += count;
+            *self.vocabulary.entry(token.clone()).or_insert(0) += count;
+        }
+    }
+
+    /// Removes a document previously added with the same category and tokens.
+    pub fn remove(&mut self, category: &str, tokens: &TokenCounts) {
+        let Some(entry) = self.categories.get_mut(category) else {
+            return;
+        };
+        entry.documents = entry.documents.saturating_sub(1);
+        for (token, &count) in tokens {
+            let count = u64::from(count);
+            entry.tokens = entry.tokens.saturating_sub(count);
+            if let Some(c) = entry.counts.get_mut(token) {
+                *c = c.saturating_sub(count);
+                if *c == 0 {
+                    entry.counts.remove(token);
+                }
+            }
+            if let Some(c) = self.vocabulary.get_mut(token) {
+                *c = c.saturating_sub(count);
+                if *c == 0 {
+                    self.vocabulary.remove(token);
+                }
+            }
+        }
+        if entry.documents == 0 {
+            self.categories.remove(category);
+        }
+    }
+
+    /// Number of training documents.
+    pub fn documents(&self) -> u64 {
+        self.categories.values().map(|c| c.documents).sum()
+    }
+
+    /// Number of training documents of each category.
+    pub fn category_documents(&self) -> BTreeMap<String, u64> {
+        self.categories
+            .iter()
+            .map(|(category, counts)| (category.clone(), counts.documents))
+            .collect()
+    }
+
+    /// Predicts the most likely category and its probability. Only categories
+    /// with at least `min_examples` documents compete; at least two are needed,
+    /// and the document must share a token with the training data.
+    pub fn predict(&self, tokens: &TokenCounts, min_examples: usize) -> Option<(String, f64)> {
+        let eligible: Vec<(&String, &CategoryCounts)> = self
+            .categories
+            .iter()
+            .filter(|(_, counts)| counts.documents >= min_examples.max(1) as u64)
+            .collect();
+        if eligible.len() < 2 {
+            return None;
+        }
+
+        let known: Vec<(&String, f64)> = tokens
+            .iter()
+            .filter(|(token, _)| self.vocabulary.contains_key(*token))
+            .map(|(token, &count)| (token, f64::from(count)))
+            .collect();
+        if known.is_empty() {
+            return None;
+        }
+
+        let total_documents: u64 = eligible.iter().map(|(_, c)| c.documents).sum();
+        let vocabulary_size = self.vocabulary.len() as f64;
+
+        let mut log_scores: Vec<(&String, f64)> = eligible
+            .iter()
+            .map(|(category, counts)| {
+                let prior = (counts.documents as f64 / total_documents as f64).ln();
+                let denominator = counts.tokens as f64 + vocabulary_size;
+                let likelihood: f64 = known
+                    .iter()
+                    .map(|(token, count)| {
+                        let occurrences = counts.counts.get(*token).copied().unwrap_or(0);
+                        count * ((occurrences as f64 + 1.0) / denominator).ln()
+                    })
+                    .sum();
+                (*category, prior + likelihood)
+            })
+            .collect();
+        // Ties go to the alphabetically first category so predictions are stable.
+        log_scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let best = log_scores[0].1;
+        let normalizer: f64 = log_scores.iter().map(|(_, s)| (s - best).exp()).sum();
+        Some((log_scores[0].0.clone(), 1.0 / normalizer))
+    }
+}
+
+/// Shared handle to the classifier and its stored examples.
+///
+/// Cloning is cheap; clones share the model, so examples learned by the job
+/// store are used by every pipeline at once.
+#[derive(Clone)]
+pub struct Classifier {
+    model: Arc<RwLock<NaiveBayes>>,
+    db: Database,
+}
+
+impl Classifier {
+    /// Loads the classifier from the labeled examples in the database.
+    pub fn open(db: Database) -> Result<Self, DatabaseError> {
+        let mut model = NaiveBayes::default();
+        for example in classifier_repo::find_labeled(&db)? {
+            if let Some(category) = &example.category {
+                model.add(category, &parse_tokens(&example.tokens));
+            }
+        }
+
+        Ok(Self {
+            model: Arc::new(RwLock::new(model)),
+            db,
+        })
+    }
+
+    /// Stores the text of a completed job as an example. Labeled examples are
+    /// trained on; any earlier example of the job is replaced.
+    pub fn learn(
+        &self,
+        job_id: &str,
+        category: Option<&str>,
+        text: &str,
+    ) -> Result<(), DatabaseError> {
+        let tokens = tokenize(text);
+        let previous = classifier_repo::find_by_job(&self.db, job_id)?;
+
+        classifier_repo::upsert(
+            &self.db,
+            &ClassifierExampleRow {
+                job_id: job_id.to_string(),
+                category: category.map(String::from),
+                tokens: serde_json::to_string(&tokens).unwrap_or_else(|_| "{}".to_string()),
+                updated_at: Utc::now().to_rfc3339(),
+            },
+        )?;
+
+        let mut model = self.write();
+        if let Some(previous) = previous {
+            if let Some(previous_category) = &previous.category {
+                model.remove(previous_category, &parse_tokens(&previous.tokens));
+            }
+        }
+        if let Some(category) = category {
+            model.add(category, &tokens);
+        }
+        Ok(())
+    }
+
+    /// Changes the category of a job's example, or removes its label when
+    /// `category` is `None`. Returns false if the job has no example.
+    pub fn relabel(&self, job_id: &str, category: Option<&str>) -> Result<bool, DatabaseError> {
+        let Some(example) = classifier_repo::find_by_job(&self.db, job_id)? else {
+            return Ok(false);
+        };
+        if example.category.as_deref() == category {
+            return Ok(true);
+        }
+
+        let now = Utc::now().to_rfc3339();
+        classifier_repo::set_category(&self.db, job_id, category, &now)?;
+
+        let tokens = parse_tokens(&example.tokens);
+        let mut model = self.write();
+        if let Some(previous) = &example.category {
+            model.remove(previous, &tokens);
+        }
+        if let Some(category) = category {
+            model.add(category, &tokens);
+        }
+        Ok(true)
+    }
+
+    /// Predicts a category for the text. See [`NaiveBayes::predict`].
+    pub fn predict(&self, text: &str, min_examples: usize) -> Option<(String, f64)> {
+        self.read().predict(&tokenize(text), min_examples)
+    }
+
+    /// Number of training documents of each category.
+    pub fn category_documents(&self) -> BTreeMap<String, u64> {
+        self.read().category_documents()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, NaiveBayes> {
+        match self.model.read() {
+            Ok(g) => g,
+            Err(poisoned) => {
+                log::warn!("Classifier lock was poisoned, recovering");
+                poisoned.into_inner()
+            }
+        }
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, NaiveBayes> {
+        match self.model.write() {
+            Ok(g) => g,
+            Err(poisoned) => {
+                log::warn!("Classifier lock was poisoned, recovering");
+                poisoned.into_inner()
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Classifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Classifier")
+            .field("documents", &self.read().documents())
+            .finish()
+    }
+}
+
+fn parse_tokens(json: &str) -> TokenCounts {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::job_repo::{self, JobRow};
+
+    fn insert_job(db: &Database, id: &str) {
+        let row = JobRow {
+            id: id.to_string(),
+            filename: "test.pdf".to_string(),
+            source_path: "/tmp/test.pdf".to_string(),
+            archive_path: None,
+            output_path: None,
+            category: "unsorted".to_string(),
+            source_name: None,
+            status: "completed".to_string(),
+            error: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            completed_at: None,
+            symlinks: None,
+            current_phase: None,
+            message: None,
+            mime_type: None,
+            parent_job_id: None,
+            confidence: None,
+            alternatives: None,
+            explanation: None,
+        };
+        job_repo::insert(db, &row).unwrap();
+    }
+
+    fn trained_model() -> NaiveBayes {
+        let mut model = NaiveBayes::default();
+        for text in [
+            "Invoice total amount due payment",
+            "Invoice number amount due",
+            "Payment reminder invoice overdue",
+        ] {
+            model.add("invoices", &tokenize(text));
+        }
+        for text in [
+            "Doctor appointment prescription",
+            "Hospital prescription patient",
+            "Patient doctor diagnosis",
+        ] {
+            model.add("health", &tokenize(text));
+        }
+        model
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("Invoice INVOICE 2026 a total-amount: 12,50 €");
+
+        assert_eq!(tokens.get("invoice"), Some(&2));
+        assert_eq!(tokens.get("total"), Some(&1));
+        assert_eq!(tokens.get("amount"), Some(&1));
+        assert!(!tokens.contains_key("2026"));
+        assert!(!tokens.contains_key("a"));
+        assert_eq!(tokens.len(), 3);
+    }
+
+    #[test]
+    fn test_predict_picks_likely_category() {
+        let model = trained_model();
+
+        let (category, confidence) = model
+            .predict(&tokenize("Your invoice: amount due"), 1)
+            .unwrap();
+        assert_eq!(category, "invoices");
+        assert!(confidence > 0.9, "{}", confidence);
+
+        let (category, _) = model
+            .predict(&tokenize("Prescription from your doctor"), 1)
+            .unwrap();
+        assert_eq!(category, "health");
+    }
+
+    #[test]
+    fn test_predict_needs_examples_and_known_tokens() {
+        let model = trained_model();
+
+        assert_eq!(model.predict(&tokenize("invoice"), 4), None);
+        assert_eq!(
+            model.predict(&tokenize("completely unrelated words"), 1),
+            None
+        );
+
+        let mut single = NaiveBayes::default();
+        single.add("invoices", &tokenize("invoice"));
+        assert_eq!(single.predict(&tokenize("invoice"), 1), None);
+    }
+
+    #[test]
+    fn test_remove_undoes_add() {
+        let mut model = trained_model();
+        let tokens = tokenize("Invoice from a new vendor");
+        model.add("health", &tokens);
+        model.remove("health", &tokens);
+
+        assert_eq!(model.documents(), 6);
+        assert_eq!(model.category_documents()["health"], 3);
+        assert!(!model.vocabulary.contains_key("vendor"));
+    }
+
+    #[test]
+    fn test_learn_relabel_and_reload() {
+        let db = Database::open_in_memory().unwrap();
+        let classifier = Classifier::open(db.clone()).unwrap();
+
+        for (id, category, text) in [
+            ("job-1", Some("invoices"), "Invoice amount due"),
+            ("job-2", Some("health"), "Doctor prescription"),
+            ("job-3", None, "Prescription for patient"),
+        ] {
+            insert_job(&db, id);
+            classifier.learn(id, category, text).unwrap();
+        }
+
+        assert_eq!(classifier.category_documents().len(), 2);
+        assert!(classifier.relabel("job-3", Some("health")).unwrap());
+        assert!(!classifier.relabel("missing", Some("health")).unwrap());
+        assert_eq!(classifier.category_documents()["health"], 2);
+
+        // Learning a job again replaces its earlier example
+        classifier
+            .learn("job-1", Some("invoices"), "Invoice total")
+            .unwrap();
+        assert_eq!(classifier.category_documents()["invoices"], 1);
+
+        let reloaded = Classifier::open(db).unwrap();
+        assert_eq!(
+            reloaded.category_documents(),
+            classifier.category_documents()
+        );
+        let (category, _) = reloaded.predict("patient prescription", 1).unwrap();
+        assert_eq!(category, "health");
+    }
+}
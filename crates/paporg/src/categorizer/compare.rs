@@ -0,0 +1,239 @@
+//! Comparisons between extracted variable values and rule values.
+//!
+//! Extracted values are text, so both sides are interpreted before comparing:
+//! as dates when both parse as one, then as numbers, and otherwise only
+//! equality is defined, as text.
+
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+
+use crate::config::schema::{CompareValue, VariableMatch};
+
+/// Date formats accepted on both sides, tried in order.
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%Y/%m/%d"];
+
+/// Returns true if the variable value satisfies every operator that is set.
+/// A missing variable only satisfies `exists: false`.
+pub fn matches_variable(
+    condition: &VariableMatch,
+    value: Option<&str>,
+    case_sensitive: bool,
+) -> bool {
+    let value = value.filter(|v| !v.trim().is_empty());
+    if let Some(exists) = condition.exists {
+        if exists != value.is_some() {
+            return false;
+        }
+    }
+    let Some(value) = value else {
+        return condition.exists == Some(false);
+    };
+
+    if let Some(expected) = &condition.eq {
+        if !equals(value, expected, case_sensitive) {
+            return false;
+        }
+    }
+    if let Some(expected) = &condition.gt {
+        if compare(value, expected) != Some(Ordering::Greater) {
+            return false;
+        }
+    }
+    if let Some(expected) = &condition.lt {
+        if compare(value, expected) != Some(Ordering::Less) {
+            return false;
+        }
+    }
+    if let Some([low, high]) = &condition.between {
+        let above = matches!(
+            compare(value, low),
+            Some(Ordering::Greater | Ordering::Equal)
+        );
+        let below = matches!(compare(value, high), Some(Ordering::Less | Ordering::Equal));
+        if !above || !below {
+            return false;
+        }
+    }
+    if let Some(values) = &condition.one_of {
+        if !values.iter().any(|v| equals(value, v, case_sensitive)) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Orders a value against an expected value, or `None` if they are not
+/// both dates or both numbers.
+pub fn compare(value: &str, expected: &CompareValue) -> Option<Ordering> {
+    match expected {
+        CompareValue::Number(number) => parse_number(value)?.partial_cmp(number),
+        CompareValue::Text(text) => {
+            if let (Some(a), Some(b)) = (parse_date(value), parse_date(text)) {
+                return Some(a.cmp(&b));
+            }
+            parse_number(value)?.partial_cmp(&parse_number(text)?)
+        }
+    }
+}
+
+fn equals(value: &str, expected: &CompareValue, case_sensitive: bool) -> bool {
+    if let Some(ordering) = compare(value, expected) {
+        return ordering == Ordering::Equal;
+    }
+    match expected {
+        CompareValue::Number(_) => false,
+        CompareValue::Text(text) if case_sensitive => value.trim() == text.trim(),
+        CompareValue::Text(text) => value.trim().to_lowercase() == text.trim().to_lowercase(),
+    }
+}
+
+/// Parses a date in one of the supported formats.
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+/// Parses an amount such as `1234.50`, `1'234.50`, `1,234.50` or `1.234,50`.
+/// When a single separator is ambiguous, a comma followed by exactly three
+/// digits groups thousands and otherwise marks decimals.
+pub fn parse_number(value: &str) -> Option<f64> {
+    let cleaned: String = value
+        .trim()
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '\'' | '\u{2019}' | '_'))
+        .collect();
+
+    let normalized = match (cleaned.rfind('.'), cleaned.rfind(',')) {
+        // The later separator marks decimals
+        (Some(dot), Some(comma)) if comma > dot => cleaned.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => cleaned.replace(',', ""),
+        (None, Some(comma)) => {
+            let decimals = cleaned.len() - comma - 1;
+            if cleaned.matches(',').count() == 1 && decimals != 3 {
+                cleaned.replace(',', ".")
+            } else {
+                cleaned.replace(',', "")
+            }
+        }
+        (Some(_), None) if cleaned.matches('.').count() > 1 => cleaned.replace('.', ""),
+        _ => cleaned,
+    };
+
+    normalized.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(n: f64) -> CompareValue {
+        CompareValue::Number(n)
+    }
+
+    fn text(s: &str) -> CompareValue {
+        CompareValue::Text(s.to_string())
+    }
+
+    fn condition(name: &str) -> VariableMatch {
+        VariableMatch {
+            name: name.to_string(),
+            eq: None,
+            gt: None,
+            lt: None,
+            between: None,
+            exists: None,
+            one_of: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_number_formats() {
+        assert_eq!(parse_number("1234.50"), Some(1234.5));
+        assert_eq!(parse_number("1'234.50"), Some(1234.5));
+        assert_eq!(parse_number("1,234.50"), Some(1234.5));
+        assert_eq!(parse_number("1.234,50"), Some(1234.5));
+        assert_eq!(parse_number("12,50"), Some(12.5));
+        assert_eq!(parse_number("1,234"), Some(1234.0));
+        assert_eq!(parse_number("1.234.567"), Some(1234567.0));
+        assert_eq!(parse_number(" -42 "), Some(-42.0));
+        assert_eq!(parse_number("CHF 12"), None);
+        assert_eq!(parse_number("inf"), None);
+        assert_eq!(parse_number(""), None);
+    }
+
+    #[test]
+    fn test_parse_date_formats() {
+        let expected = NaiveDate::from_ymd_opt(2019, 12, 31);
+        assert_eq!(parse_date("2019-12-31"), expected);
+        assert_eq!(parse_date("31.12.2019"), expected);
+        assert_eq!(parse_date("31/12/2019"), expected);
+        assert_eq!(parse_date("2019/12/31"), expected);
+        assert_eq!(parse_date("2019"), None);
+    }
+
+    #[test]
+    fn test_compare_numbers_and_dates() {
+        assert_eq!(
+            compare("1'500.00", &number(1000.0)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare("999", &text("1000")), Some(Ordering::Less));
+        assert_eq!(
+            compare("15.03.2019", &text("2020-01-01")),
+            Some(Ordering::Less)
+        );
+        assert_eq!(compare("ACME", &number(1.0)), None);
+        assert_eq!(compare("2019-03-15", &number(2020.0)), None);
+    }
+
+    #[test]
+    fn test_operators() {
+        let mut large = condition("amount");
+        large.gt = Some(number(1000.0));
+        assert!(matches_variable(&large, Some("1'200.00"), false));
+        assert!(!matches_variable(&large, Some("1000"), false));
+        assert!(!matches_variable(&large, Some("n/a"), false));
+        assert!(!matches_variable(&large, None, false));
+
+        let mut range = condition("amount");
+        range.between = Some([number(100.0), number(200.0)]);
+        assert!(matches_variable(&range, Some("100"), false));
+        assert!(matches_variable(&range, Some("200.00"), false));
+        assert!(!matches_variable(&range, Some("200.01"), false));
+
+        let mut old = condition("date");
+        old.lt = Some(text("2020-01-01"));
+        assert!(matches_variable(&old, Some("31.12.2019"), false));
+        assert!(!matches_variable(&old, Some("2020-01-01"), false));
+
+        let mut vendor = condition("vendor");
+        vendor.one_of = Some(vec![text("Swisscom"), text("Sunrise")]);
+        assert!(matches_variable(&vendor, Some("swisscom"), false));
+        assert!(!matches_variable(&vendor, Some("swisscom"), true));
+
+        let mut amount = condition("amount");
+        amount.eq = Some(number(50.0));
+        assert!(matches_variable(&amount, Some("50.00"), false));
+    }
+
+    #[test]
+    fn test_exists() {
+        let mut present = condition("iban");
+        present.exists = Some(true);
+        assert!(matches_variable(&present, Some("CH93 0076"), false));
+        assert!(!matches_variable(&present, Some("  "), false));
+        assert!(!matches_variable(&present, None, false));
+
+        let mut absent = condition("iban");
+        absent.exists = Some(false);
+        assert!(matches_variable(&absent, None, false));
+        assert!(!matches_variable(&absent, Some("CH93 0076"), false));
+
+        // Without operators a present variable matches
+        assert!(matches_variable(&condition("iban"), Some("CH93"), false));
+        assert!(!matches_variable(&condition("iban"), None, false));
+    }
+}
@@ -0,0 +1,270 @@
+//! Detection of the correspondent a document comes from.
+//!
+//! Identifiers are checked before names: the email sender's domain first,
+//! then IBANs and VAT numbers in the text, and only then names and aliases.
+//! IBANs printed on QR bills are found the same way, as long as the
+//! extracted text contains them.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::document::DocumentView;
+use crate::config::schema::Correspondent;
+
+/// The correspondent detected for a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedCorrespondent {
+    pub id: String,
+    /// Display name, exposed as `$correspondent`.
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_category: Option<String>,
+    /// What identified the correspondent.
+    pub evidence: CorrespondentEvidence,
+}
+
+/// The detail of a document that identified its correspondent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum CorrespondentEvidence {
+    EmailDomain(String),
+    Iban(String),
+    VatNumber(String),
+    Name(String),
+}
+
+pub struct CorrespondentDetector {
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    correspondent: Correspondent,
+    /// Email domains, lowercased.
+    email_domains: Vec<String>,
+    /// IBANs and VAT numbers as uppercase letters and digits.
+    ibans: Vec<String>,
+    vat_numbers: Vec<String>,
+    /// Display name and aliases with their compiled patterns.
+    names: Vec<(String, Regex)>,
+}
+
+impl CorrespondentDetector {
+    pub fn new(correspondents: &[Correspondent]) -> Self {
+        let entries = correspondents
+            .iter()
+            .map(|c| Entry {
+                email_domains: c
+                    .email_domains
+                    .iter()
+                    .map(|d| d.trim().trim_start_matches('@').to_lowercase())
+                    .filter(|d| !d.is_empty())
+                    .collect(),
+                ibans: c.ibans.iter().map(|i| normalize_identifier(i)).collect(),
+                vat_numbers: c
+                    .vat_numbers
+                    .iter()
+                    .map(|v| normalize_identifier(v))
+                    .collect(),
+                names: std::iter::once(&c.name)
+                    .chain(&c.aliases)
+                    .filter_map(|name| Some((name.clone(), name_pattern(name)?)))
+                    .collect(),
+                correspondent: c.clone(),
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Returns the correspondent of a document, if any is recognized.
+    pub fn detect(&self, document: &DocumentView) -> Option<DetectedCorrespondent> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        if let Some(domain) = document.email_from.and_then(sender_domain) {
+            for entry in &self.entries {
+                let matched = entry.email_domains.iter().find(|d| {
+                    domain == **d
+                        || domain
+                            .strip_suffix(d.as_str())
+                            .is_some_and(|rest| rest.ends_with('.'))
+                });
+                if let Some(d) = matched {
+                    return Some(entry.detected(CorrespondentEvidence::EmailDomain(d.clone())));
+                }
+            }
+        }
+
+        let identifiers = normalize_identifier(document.text);
+        for entry in &self.entries {
+            if let Some(iban) = find_identifier(&entry.ibans, &identifiers) {
+                return Some(entry.detected(CorrespondentEvidence::Iban(iban)));
+            }
+        }
+        for entry in &self.entries {
+            if let Some(vat) = find_identifier(&entry.vat_numbers, &identifiers) {
+                return Some(entry.detected(CorrespondentEvidence::VatNumber(vat)));
+            }
+        }
+
+        for entry in &self.entries {
+            let matched = entry
+                .names
+                .iter()
+                .find(|(_, pattern)| pattern.is_match(document.text));
+            if let Some((name, _)) = matched {
+                return Some(entry.detected(CorrespondentEvidence::Name(name.clone())));
+            }
+        }
+
+        None
+    }
+}
+
+impl Entry {
+    fn detected(&self, evidence: CorrespondentEvidence) -> DetectedCorrespondent {
+        DetectedCorrespondent {
+            id: self.correspondent.id.clone(),
+            name: self.correspondent.name.clone(),
+            default_category: self.correspondent.default_category.clone(),
+            evidence,
+        }
+    }
+}
+
+/// Keeps only letters and digits, uppercased, so identifiers match however
+/// they are grouped or punctuated.
+fn normalize_identifier(value: &str) -> String {
+    value
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn find_identifier(identifiers: &[String], text: &str) -> Option<String> {
+    identifiers
+        .iter()
+        .find(|id| !id.is_empty() && text.contains(id.as_str()))
+        .cloned()
+}
+
+/// Returns the lowercased domain of a sender like `Name <user@example.com>`.
+fn sender_domain(from: &str) -> Option<String> {
+    let (_, domain) = from.rsplit_once('@')?;
+    let domain: String = domain
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '.' | '-'))
+        .collect();
+    (!domain.is_empty()).then(|| domain.to_lowercase())
+}
+
+/// Matches a name case-insensitively as whole words.
+fn name_pattern(name: &str) -> Option<Regex> {
+    let name = name.trim();
+    let first = name.chars().next()?;
+    let last = name.chars().next_back()?;
+    let boundary = |c: char| if c.is_alphanumeric() { r"\b" } else { "" };
+    Regex::new(&format!(
+        "(?i){}{}{}",
+        boundary(first),
+        regex::escape(name),
+        boundary(last)
+    ))
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn helsana() -> Correspondent {
+        Correspondent {
+            id: "helsana".to_string(),
+            name: "Helsana".to_string(),
+            aliases: vec!["Helsana Versicherungen AG".to_string()],
+            ibans: vec!["CH93 0076 2011 6238 5295 7".to_string()],
+            vat_numbers: vec!["CHE-105.953.932".to_string()],
+            email_domains: vec!["helsana.ch".to_string()],
+            default_category: Some("insurance".to_string()),
+        }
+    }
+
+    fn swisscom() -> Correspondent {
+        Correspondent {
+            id: "swisscom".to_string(),
+            name: "Swisscom".to_string(),
+            aliases: vec![],
+            ibans: vec![],
+            vat_numbers: vec![],
+            email_domains: vec!["swisscom.com".to_string()],
+            default_category: None,
+        }
+    }
+
+    fn detect(document: &DocumentView) -> Option<DetectedCorrespondent> {
+        CorrespondentDetector::new(&[helsana(), swisscom()]).detect(document)
+    }
+
+    #[test]
+    fn test_detect_by_email_domain() {
+        let document = DocumentView {
+            email_from: Some("Helsana <noreply@mail.helsana.ch>"),
+            ..DocumentView::new("Your monthly statement")
+        };
+        let detected = detect(&document).unwrap();
+        assert_eq!(detected.id, "helsana");
+        assert_eq!(detected.default_category.as_deref(), Some("insurance"));
+        assert_eq!(
+            detected.evidence,
+            CorrespondentEvidence::EmailDomain("helsana.ch".to_string())
+        );
+
+        // A domain merely ending in the same letters is someone else
+        let document = DocumentView {
+            email_from: Some("billing@nothelsana.ch"),
+            ..DocumentView::new("Invoice")
+        };
+        assert_eq!(detect(&document), None);
+    }
+
+    #[test]
+    fn test_detect_by_identifier() {
+        let detected = detect(&DocumentView::new("Konto: ch93-0076-2011-6238-5295-7")).unwrap();
+        assert_eq!(detected.id, "helsana");
+        assert!(matches!(detected.evidence, CorrespondentEvidence::Iban(_)));
+
+        let detected = detect(&DocumentView::new("UID CHE 105 953 932 MWST")).unwrap();
+        assert_eq!(
+            detected.evidence,
+            CorrespondentEvidence::VatNumber("CHE105953932".to_string())
+        );
+    }
+
+    #[test]
+    fn test_identifiers_before_names() {
+        // Mentions Swisscom by name, but the IBAN belongs to Helsana
+        let text = "Swisscom Mobile reimbursement, paid to CH9300762011623852957";
+        assert_eq!(detect(&DocumentView::new(text)).unwrap().id, "helsana");
+    }
+
+    #[test]
+    fn test_detect_by_name() {
+        let detected = detect(&DocumentView::new("HELSANA VERSICHERUNGEN AG, Zürich")).unwrap();
+        assert_eq!(detected.id, "helsana");
+
+        let detected = detect(&DocumentView::new("Rechnung von Swisscom (Schweiz) AG")).unwrap();
+        assert_eq!(detected.name, "Swisscom");
+        assert_eq!(
+            detected.evidence,
+            CorrespondentEvidence::Name("Swisscom".to_string())
+        );
+
+        // Names only match as whole words
+        assert_eq!(detect(&DocumentView::new("Swisscomfort Heizungen")), None);
+        assert!(CorrespondentDetector::new(&[])
+            .detect(&DocumentView::new("Helsana"))
+            .is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::correspondent::DetectedCorrespondent;
+use crate::config::schema::MatchField;
+
+/// The parts of a document that match conditions can target.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DocumentView<'a> {
+    /// The extracted text, searched by conditions without a field. Email
+    /// headers are only searched through their own fields.
+    pub text: &'a str,
+    /// Byte range of each page in `text`; without pages the text is one page.
+    pub pages: &'a [Range<usize>],
+    pub filename: Option<&'a str>,
+    /// Name of the import source that discovered the document.
+    pub source: Option<&'a str>,
+    pub mime_type: Option<&'a str>,
+    pub email_from: Option<&'a str>,
+    pub email_subject: Option<&'a str>,
+    pub email_to: Option<&'a str>,
+    /// Path of the file below its import source directory.
+    pub source_path: Option<&'a str>,
+    /// The document information of a PDF as `Key: value` lines.
+    pub pdf_metadata: Option<&'a str>,
+    /// Variables extracted from the matching text.
+    pub variables: Option<&'a HashMap<String, String>>,
+    /// The correspondent detected for the document.
+    pub correspondent: Option<&'a DetectedCorrespondent>,
+}
+
+impl<'a> DocumentView<'a> {
+    /// A view of plain text with no metadata.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the value of a field; missing metadata reads as empty text.
+    pub fn field(&self, field: Option<MatchField>) -> &'a str {
+        let value = match field {
+            None | Some(MatchField::Text) => Some(self.text),
+            Some(MatchField::Filename) => self.filename,
+            Some(MatchField::Source) => self.source,
+            Some(MatchField::MimeType) => self.mime_type,
+            Some(MatchField::EmailFrom) => self.email_from,
+            Some(MatchField::EmailSubject) => self.email_subject,
+            Some(MatchField::EmailTo) => self.email_to,
+            Some(MatchField::FirstPage) => self
+                .pages
+                .first()
+                .map_or(Some(self.text), |page| self.text.get(page.clone())),
+        };
+        value.unwrap_or_default()
+    }
+
+    /// Returns an extracted variable, if it was found.
+    pub fn variable(&self, name: &str) -> Option<&'a str> {
+        self.variables?.get(name).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields() {
+        let view = DocumentView {
+            text: "Page one\n\nPage two",
+            pages: &[0..8, 10..18],
+            filename: Some("scan.pdf"),
+            email_from: Some("billing@example.com"),
+            ..Default::default()
+        };
+
+        assert_eq!(view.field(None), "Page one\n\nPage two");
+        assert_eq!(view.field(Some(MatchField::Text)), "Page one\n\nPage two");
+        assert_eq!(view.field(Some(MatchField::FirstPage)), "Page one");
+        assert_eq!(view.field(Some(MatchField::Filename)), "scan.pdf");
+        assert_eq!(
+            view.field(Some(MatchField::EmailFrom)),
+            "billing@example.com"
+        );
+        assert_eq!(view.field(Some(MatchField::EmailSubject)), "");
+        assert_eq!(view.field(Some(MatchField::Source)), "");
+    }
+
+    #[test]
+    fn test_plain_text_view() {
+        let view = DocumentView::new("only text");
+        assert_eq!(view.field(None), "only text");
+        assert_eq!(view.field(Some(MatchField::FirstPage)), "only text");
+        assert_eq!(view.field(Some(MatchField::MimeType)), "");
+        assert_eq!(view.variable("amount"), None);
+    }
+
+    #[test]
+    fn test_variables() {
+        let variables = HashMap::from([("amount".to_string(), "120.50".to_string())]);
+        let view = DocumentView {
+            variables: Some(&variables),
+            ..DocumentView::new("Total 120.50")
+        };
+        assert_eq!(view.variable("amount"), Some("120.50"));
+        assert_eq!(view.variable("vendor"), None);
+    }
+}
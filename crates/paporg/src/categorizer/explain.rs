@@ -0,0 +1,113 @@
+//! Explanations of why rules did or did not match a document.
+//!
+//! A trace mirrors the rule's condition tree: each node records whether it
+//! matched and, for leaves, where in the document the searched text was found.
+
+use serde::{Deserialize, Serialize};
+
+use super::classifier::ClassifierPrediction;
+use super::correspondent::DetectedCorrespondent;
+use crate::config::schema::MatchField;
+
+/// Most spans recorded for a single condition.
+pub const MAX_SPANS: usize = 10;
+
+/// How a document was categorized.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategorizationExplanation {
+    /// The rule that categorized the document, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner: Option<RuleExplanation>,
+    /// Rules evaluated without winning: those ahead of the winner in
+    /// first-match mode, every other rule in scoring mode.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<RuleExplanation>,
+    /// The classifier's prediction, when no rule matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classifier: Option<ClassifierPrediction>,
+    /// The correspondent detected for the document. Its default category
+    /// applies when neither a rule nor the classifier filed the document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correspondent: Option<DetectedCorrespondent>,
+}
+
+/// The evaluation of one rule against a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleExplanation {
+    pub rule_id: String,
+    pub category: String,
+    /// Whether the rule's condition matched.
+    pub matched: bool,
+    /// Score of the rule, in scoring mode only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    pub condition: ConditionTrace,
+}
+
+/// The evaluation of one node of a condition tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionTrace {
+    /// Condition type, such as `contains`, `pattern` or `all`.
+    pub kind: String,
+    pub matched: bool,
+    /// Field the condition sets; inherited fields are not repeated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<MatchField>,
+    /// Values searched for, or the variable name of a variable condition.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+    /// Where the values were found, at most [`MAX_SPANS`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spans: Vec<MatchSpan>,
+    /// Traces of the children of `all`, `any` and `not`, up to the one that
+    /// decided the result.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ConditionTrace>,
+}
+
+/// Text found by a condition, as a byte range of a document field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchSpan {
+    /// Field the range refers to. Matches in the extracted text always refer
+    /// to `text`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<MatchField>,
+    pub start: usize,
+    pub end: usize,
+    /// The matched text.
+    pub text: String,
+}
+
+impl CategorizationExplanation {
+    /// Returns the explanation of a rule, whether it won or not.
+    pub fn rule(&self, rule_id: &str) -> Option<&RuleExplanation> {
+        self.winner
+            .iter()
+            .chain(&self.rejected)
+            .find(|rule| rule.rule_id == rule_id)
+    }
+}
+
+impl ConditionTrace {
+    /// Returns the spans of every matching leaf, in tree order. Leaves
+    /// below a `not` are skipped, since they matched against the rule.
+    pub fn matched_spans(&self) -> Vec<&MatchSpan> {
+        let mut spans = Vec::new();
+        self.collect_matched_spans(&mut spans);
+        spans
+    }
+
+    fn collect_matched_spans<'a>(&'a self, spans: &mut Vec<&'a MatchSpan>) {
+        if !self.matched || self.kind == "not" {
+            return;
+        }
+        spans.extend(&self.spans);
+        for child in &self.children {
+            child.collect_matched_spans(spans);
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! OCR-tolerant approximate substring matching.
+//!
+//! Both the document text and the searched value are normalized first:
+//! lowercased, whitespace removed, and characters OCR commonly confuses
+//! folded together (`0`/`o`, `1`/`l`/`i`, `5`/`s`, `8`/`b`, `rn`/`m`, `vv`/`w`).
+//! The remaining differences are bounded by an edit distance, searched with
+//! Myers' bit-parallel algorithm so a rule costs one linear pass over the text.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Longest normalized value handled by the bit-parallel search.
+const WORD_BITS: usize = 64;
+
+/// Normalizes text for fuzzy comparison.
+pub fn normalize(text: &str) -> Vec<char> {
+    normalize_with_spans(text).0
+}
+
+/// Normalizes text, also returning the byte range in `text` that each
+/// normalized character came from.
+fn normalize_with_spans(text: &str) -> (Vec<char>, Vec<Range<usize>>) {
+    let mut out: Vec<char> = Vec::with_capacity(text.len());
+    let mut spans: Vec<Range<usize>> = Vec::with_capacity(text.len());
+    for (start, source) in text.char_indices() {
+        let span = start..start + source.len_utf8();
+        for c in source.to_lowercase() {
+            if c.is_whitespace() || matches!(c, '\u{00AD}' | '\u{200B}') {
+                continue;
+            }
+            let folded = match c {
+                '0' => 'o',
+                '1' | 'i' | '|' | '!' => 'l',
+                '5' => 's',
+                '8' => 'b',
+                c => c,
+            };
+            let merged = match (out.last(), folded) {
+                (Some('r'), 'n') => Some('m'),
+                (Some('v'), 'v') => Some('w'),
+                _ => None,
+            };
+            match merged {
+                Some(m) => {
+                    out.pop();
+                    out.push(m);
+                    if let Some(last) = spans.last_mut() {
+                        last.end = span.end;
+                    }
+                }
+                None => {
+                    out.push(folded);
+                    spans.push(span.clone());
+                }
+            }
+        }
+    }
+    (out, spans)
+}
+
+/// Edit distance allowed when a rule does not set one: none for short values,
+/// one error from five characters and two from twelve.
+pub fn default_max_distance(normalized_len: usize) -> usize {
+    match normalized_len {
+        0..=4 => 0,
+        5..=11 => 1,
+        _ => 2,
+    }
+}
+
+/// Document text normalized once for all fuzzy conditions.
+pub struct FuzzyText {
+    chars: Vec<char>,
+    string: String,
+    /// Byte range in the original text of each normalized character.
+    spans: Vec<Range<usize>>,
+}
+
+impl FuzzyText {
+    pub fn new(text: &str) -> Self {
+        let (chars, spans) = normalize_with_spans(text);
+        let string = chars.iter().collect();
+        Self {
+            chars,
+            string,
+            spans,
+        }
+    }
+}
+
+/// A value prepared for repeated fuzzy searches.
+#[derive(Debug, Clone)]
+pub struct FuzzyPattern {
+    chars: Vec<char>,
+    string: String,
+    max_distance: usize,
+    /// Per-character position masks for the bit-parallel search.
+    masks: Option<CharMasks>,
+}
+
+#[derive(Debug, Clone)]
+struct CharMasks {
+    ascii: [u64; 128],
+    other: HashMap<char, u64>,
+}
+
+impl CharMasks {
+    fn get(&self, c: char) -> u64 {
+        if c.is_ascii() {
+            self.ascii[c as usize]
+        } else {
+            self.other.get(&c).copied().unwrap_or(0)
+        }
+    }
+}
+
+impl FuzzyPattern {
+    /// Prepares `value`; the distance is capped so the value can never match empty text.
+    pub fn new(value: &str, max_distance: Option<usize>) -> Self {
+        let chars = normalize(value);
+        let max_distance = max_distance
+            .unwrap_or_else(|| default_max_distance(chars.len()))
+            .min(chars.len().saturating_sub(1));
+
+        let masks = (!chars.is_empty() && chars.len() <= WORD_BITS).then(|| {
+            let mut masks = CharMasks {
+                ascii: [0; 128],
+                other: HashMap::new(),
+            };
+            for (i, &c) in chars.iter().enumerate() {
+                let bit = 1u64 << i;
+                if c.is_ascii() {
+                    masks.ascii[c as usize] |= bit;
+                } else {
+                    *masks.other.entry(c).or_default() |= bit;
+                }
+            }
+            masks
+        });
+
+        Self {
+            string: chars.iter().collect(),
+            chars,
+            max_distance,
+            masks,
+        }
+    }
+
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    /// Returns true if the text contains the value within the allowed distance.
+    pub fn is_match(&self, text: &FuzzyText) -> bool {
+        if self.chars.is_empty() {
+            return false;
+        }
+        if text.string.contains(&self.string) {
+            return true;
+        }
+        if self.max_distance == 0 {
+            return false;
+        }
+        self.search(&text.chars).is_some()
+    }
+
+    /// Returns the byte range in the original text of the first match.
+    /// Approximate matches span the substring closest to the value.
+    pub fn find(&self, text: &FuzzyText) -> Option<Range<usize>> {
+        let m = self.chars.len();
+        if m == 0 || text.chars.len() < m.saturating_sub(self.max_distance) {
+            return None;
+        }
+        let (start, end) = match text.chars.windows(m).position(|w| w == self.chars) {
+            Some(start) => (start, start + m),
+            None if self.max_distance == 0 => return None,
+            None => {
+                let end = self.search(&text.chars)? + 1;
+                (self.best_start(&text.chars, end), end)
+            }
+        };
+        Some(text.spans[start].start..text.spans[end - 1].end)
+    }
+
+    /// Searches the normalized text, returning the index of the last
+    /// character of the first match.
+    fn search(&self, text: &[char]) -> Option<usize> {
+        match &self.masks {
+            Some(masks) => self.search_bit_parallel(masks, text),
+            None => self.search_dynamic(text),
+        }
+    }
+
+    /// Finds where the closest substring ending at `end` starts, preferring
+    /// a length nearest the value's.
+    fn best_start(&self, text: &[char], end: usize) -> usize {
+        let m = self.chars.len();
+        let lowest = end.saturating_sub(m + self.max_distance);
+        let highest = end
+            .saturating_sub(m.saturating_sub(self.max_distance))
+            .min(end - 1);
+        (lowest..=highest)
+            .min_by_key(|&start| {
+                let distance = edit_distance(&self.chars, &text[start..end]);
+                (distance, (end - start).abs_diff(m))
+            })
+            .unwrap_or(lowest)
+    }
+
+    /// Myers (1999) approximate search: the lowest edit distance of the
+    /// pattern against any substring ending at each text position.
+    fn search_bit_parallel(&self, masks: &CharMasks, text: &[char]) -> Option<usize> {
+        let m = self.chars.len();
+        let high = 1u64 << (m - 1);
+        let mut pv = if m == WORD_BITS { !0 } else { (1u64 << m) - 1 };
+        let mut mv = 0u64;
+        let mut score = m;
+
+        for (position, &c) in text.iter().enumerate() {
+            let eq = masks.get(c);
+            let xv = eq | mv;
+            let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+            let mut ph = mv | !(xh | pv);
+            let mut mh = pv & xh;
+            if ph & high != 0 {
+                score += 1;
+            } else if mh & high != 0 {
+                score -= 1;
+            }
+            // No carry into the first row: a match may start anywhere
+            ph <<= 1;
+            mh <<= 1;
+            pv = mh | !(xv | ph);
+            mv = ph & xv;
+
+            if score <= self.max_distance {
+                return Some(position);
+            }
+        }
+        None
+    }
+
+    /// Sellers' dynamic-programming search for values longer than a machine word.
+    fn search_dynamic(&self, text: &[char]) -> Option<usize> {
+        let m = self.chars.len();
+        let mut column: Vec<usize> = (0..=m).collect();
+        for (position, &c) in text.iter().enumerate() {
+            let mut diagonal = 0;
+            column[0] = 0;
+            for i in 1..=m {
+                let cost = usize::from(self.chars[i - 1] != c);
+                let next = (column[i] + 1).min(column[i - 1] + 1).min(diagonal + cost);
+                diagonal = column[i];
+                column[i] = next;
+            }
+            if column[m] <= self.max_distance {
+                return Some(position);
+            }
+        }
+        None
+    }
+}
+
+/// Levenshtein distance between two character sequences.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let next = (row[j + 1] + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(ca != cb));
+            diagonal = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(value: &str, max_distance: Option<usize>, text: &str) -> bool {
+        FuzzyPattern::new(value, max_distance).is_match(&FuzzyText::new(text))
+    }
+
+    #[test]
+    fn test_normalize_folds_ocr_confusions() {
+        let normalized: String = normalize("Sw1ss Com RN0 vv").into_iter().collect();
+        assert_eq!(normalized, "swlsscommow");
+        assert_eq!(normalize("Il|1"), normalize("llll"));
+    }
+
+    #[test]
+    fn test_ocr_variants_match_exactly_after_normalization() {
+        assert!(matches("Swisscom", Some(0), "Your Sw1sscom bill"));
+        assert!(matches("Swisscom", Some(0), "Swiss com AG"));
+        assert!(matches("Swisscom", Some(0), "SWISSC0M"));
+        assert!(matches("Hammer", Some(0), "Harnmer GmbH"));
+    }
+
+    #[test]
+    fn test_edit_distance_is_bounded() {
+        assert!(matches("Swisscom", Some(1), "Swiscom"));
+        assert!(matches("Swisscom", Some(1), "Swixscom"));
+        assert!(!matches("Swisscom", Some(1), "Swxscom"));
+        assert!(matches("Swisscom", Some(2), "Swxscom"));
+        assert!(!matches("Swisscom", Some(2), "Swxxcom"));
+        assert!(!matches("Swisscom", Some(0), "Swiscom"));
+    }
+
+    #[test]
+    fn test_default_distance_scales_with_length() {
+        assert!(!matches("UBS", None, "UBX"));
+        assert!(matches("Krankenkasse", None, "Krankenkase"));
+        assert!(matches("Versicherung", None, "Vrsicherug"));
+        assert!(!matches("Versicherung", None, "Vrsicheug AG"));
+    }
+
+    #[test]
+    fn test_distance_never_matches_everything() {
+        let pattern = FuzzyPattern::new("ab", Some(10));
+        assert_eq!(pattern.max_distance(), 1);
+        assert!(!pattern.is_match(&FuzzyText::new("xyz")));
+        assert!(!matches("", Some(3), "anything"));
+    }
+
+    #[test]
+    fn test_long_values_use_dynamic_search() {
+        let value = "Eidgenössische Steuerverwaltung Hauptabteilung Mehrwertsteuer Bern Schweiz";
+        assert!(normalize(value).len() > WORD_BITS);
+
+        let ocr = "Eidgenossische Steuerverwaltung Hauptabteilung Mehrwertsteuer Bem Schweiz";
+        assert!(matches(value, Some(3), ocr));
+        assert!(!matches(
+            value,
+            Some(3),
+            "Kantonale Steuerverwaltung Zürich"
+        ));
+    }
+
+    #[test]
+    fn test_find_returns_source_span() {
+        let find = |value: &str, max_distance, text: &str| {
+            FuzzyPattern::new(value, max_distance)
+                .find(&FuzzyText::new(text))
+                .map(|span| text[span].to_string())
+        };
+        assert_eq!(
+            find("Swisscom", Some(0), "Your Sw1ss com bill"),
+            Some("Sw1ss com".into())
+        );
+        assert_eq!(
+            find("Hammer", Some(0), "Harnmer GmbH"),
+            Some("Harnmer".into())
+        );
+        assert_eq!(
+            find("Swisscom", Some(1), "Bill: Swiscom AG"),
+            Some("Swiscom".into())
+        );
+        assert_eq!(find("Zürich", Some(0), "in ZÜRICH"), Some("ZÜRICH".into()));
+        assert_eq!(find("Swisscom", Some(1), "Sunrise"), None);
+    }
+
+    #[test]
+    fn test_bit_parallel_agrees_with_dynamic_search() {
+        let texts = ["the quick brown fox", "qiuck brwn", "xx quack xx", "", "k"];
+        for value in ["quick", "brown fox", "quack"] {
+            for k in 0..3 {
+                let pattern = FuzzyPattern::new(value, Some(k));
+                for text in texts {
+                    let text = FuzzyText::new(text);
+                    let masks = pattern.masks.as_ref().unwrap();
+                    assert_eq!(
+                        pattern.search_bit_parallel(masks, &text.chars),
+                        pattern.search_dynamic(&text.chars),
+                        "value {:?} k {} text {:?}",
+                        value,
+                        k,
+                        text.string
+                    );
+                }
+            }
+        }
+    }
+}
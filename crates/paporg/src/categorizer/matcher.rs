@@ -1,15 +1,41 @@
 use regex::Regex;
+use std::cell::OnceCell;
 use std::collections::HashMap;
+use std::ops::Range;
+
+use super::classifier::{Classifier, ClassifierPrediction};
+use super::compare::matches_variable;
+use super::document::DocumentView;
+use super::explain::{
+    CategorizationExplanation, ConditionTrace, MatchSpan, RuleExplanation, MAX_SPANS,
+};
+use super::fuzzy::{FuzzyPattern, FuzzyText};
+use serde::{Deserialize, Serialize};
 
 use crate::config::schema::{
-    CompoundMatch, DefaultsConfig, MatchCondition, OutputConfig, Rule, SimpleMatch, SymlinkConfig,
+    CategorizationConfig, CategorizationMode, CompoundMatch, DefaultsConfig, MatchCondition,
+    MatchField, OutputConfig, PageRegion, PageSelection, Rule, SimpleMatch, SymlinkConfig,
 };
+use crate::processor::pages::ScopedText;
+
+/// Key of a prepared fuzzy pattern: the value and its explicit max distance.
+type FuzzyKey = (String, Option<usize>);
 
 pub struct Categorizer {
     rules: Vec<Rule>,
     defaults: DefaultsConfig,
     /// Pre-compiled regex patterns, indexed by pattern string
     compiled_patterns: HashMap<String, Regex>,
+    /// Case-insensitive searches for `contains*` values, indexed by value,
+    /// to locate them in the explanation
+    literal_patterns: HashMap<String, Regex>,
+    /// Prepared fuzzy patterns, indexed by value and max distance
+    fuzzy_patterns: HashMap<FuzzyKey, FuzzyPattern>,
+    /// Distinct page scopes of all conditions, cached per document
+    scopes: Vec<Scope>,
+    config: CategorizationConfig,
+    /// Predicts a category when no rule matches.
+    classifier: Option<Classifier>,
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +44,23 @@ pub struct CategorizationResult {
     pub category: String,
     pub output: OutputConfig,
     pub symlinks: Vec<SymlinkConfig>,
+    /// Score of the chosen rule, in scoring mode only.
+    pub confidence: Option<f64>,
+    /// Best-scoring rules that were not chosen, highest first.
+    pub alternatives: Vec<CategoryAlternative>,
+    /// Categories of the matching non-exclusive rules, in priority order.
+    pub tags: Vec<String>,
+    /// Which conditions of the evaluated rules matched, and where.
+    pub explanation: CategorizationExplanation,
+}
+
+/// A runner-up rule in scoring mode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryAlternative {
+    pub rule_id: String,
+    pub category: String,
+    pub confidence: f64,
 }
 
 impl Categorizer {
@@ -27,22 +70,79 @@ impl Categorizer {
 
         // Pre-compile all regex patterns
         let mut compiled_patterns = HashMap::new();
+        let mut literal_patterns = HashMap::new();
+        let mut fuzzy_patterns = HashMap::new();
+        let mut scopes = Vec::new();
         for rule in &rules {
-            Self::collect_patterns(&rule.match_condition, &mut compiled_patterns);
+            Self::collect_patterns(
+                &rule.match_condition,
+                &mut compiled_patterns,
+                &mut literal_patterns,
+                &mut fuzzy_patterns,
+            );
+            Self::collect_scopes(&rule.match_condition, Target::default(), &mut scopes);
         }
 
         Self {
             rules,
             defaults,
             compiled_patterns,
+            literal_patterns,
+            fuzzy_patterns,
+            scopes,
+            config: CategorizationConfig::default(),
+            classifier: None,
         }
     }
 
+    /// Sets how rules are chosen; the default is the first match in priority order.
+    pub fn with_config(mut self, config: CategorizationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the classifier consulted when no rule matches.
+    pub fn with_classifier(mut self, classifier: Classifier) -> Self {
+        self.classifier = Some(classifier);
+        self
+    }
+
     /// Recursively collects and compiles regex patterns from match conditions.
     /// For each pattern, also compiles a case-insensitive variant prefixed with `(?i)`.
-    fn collect_patterns(condition: &MatchCondition, patterns: &mut HashMap<String, Regex>) {
+    /// Fuzzy values and the searches for `contains*` values are prepared
+    /// once here rather than per document.
+    fn collect_patterns(
+        condition: &MatchCondition,
+        patterns: &mut HashMap<String, Regex>,
+        literal_patterns: &mut HashMap<String, Regex>,
+        fuzzy_patterns: &mut HashMap<FuzzyKey, FuzzyPattern>,
+    ) {
         match condition {
             MatchCondition::Simple(simple) => {
+                let literals = simple
+                    .contains
+                    .iter()
+                    .chain(simple.contains_any.iter().flatten())
+                    .chain(simple.contains_all.iter().flatten());
+                for value in literals {
+                    if let std::collections::hash_map::Entry::Vacant(e) =
+                        literal_patterns.entry(value.clone())
+                    {
+                        if let Ok(regex) = Regex::new(&format!("(?i){}", regex::escape(value))) {
+                            e.insert(regex);
+                        }
+                    }
+                }
+                if let Some(fuzzy) = &simple.fuzzy {
+                    fuzzy_patterns
+                        .entry((fuzzy.value.clone(), fuzzy.max_distance))
+                        .or_insert_with(|| FuzzyPattern::new(&fuzzy.value, fuzzy.max_distance));
+                }
+                for value in simple.contains_any_fuzzy.iter().flatten() {
+                    fuzzy_patterns
+                        .entry((value.clone(), None))
+                        .or_insert_with(|| FuzzyPattern::new(value, None));
+                }
                 if let Some(pattern) = &simple.pattern {
                     if !patterns.contains_key(pattern) {
                         if let Ok(regex) = Regex::new(pattern) {
@@ -62,109 +162,536 @@ impl Categorizer {
             MatchCondition::Compound(compound) => {
                 if let Some(all) = &compound.all {
                     for cond in all {
-                        Self::collect_patterns(cond, patterns);
+                        Self::collect_patterns(cond, patterns, literal_patterns, fuzzy_patterns);
                     }
                 }
                 if let Some(any) = &compound.any {
                     for cond in any {
-                        Self::collect_patterns(cond, patterns);
+                        Self::collect_patterns(cond, patterns, literal_patterns, fuzzy_patterns);
                     }
                 }
                 if let Some(not) = &compound.not {
-                    Self::collect_patterns(not, patterns);
+                    Self::collect_patterns(not, patterns, literal_patterns, fuzzy_patterns);
                 }
             }
         }
     }
 
+    /// Collects the page scopes conditions resolve to, inheriting them from
+    /// their compound parents like [`Self::matches`] does.
+    fn collect_scopes<'c>(
+        condition: &'c MatchCondition,
+        parent: Target<'c>,
+        scopes: &mut Vec<Scope>,
+    ) {
+        let target = parent.of(condition);
+        if let Some(scope) = target.scope() {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        if let MatchCondition::Compound(compound) = condition {
+            let children = compound.all.iter().chain(&compound.any).flatten();
+            for child in children.chain(compound.not.as_deref()) {
+                Self::collect_scopes(child, target, scopes);
+            }
+        }
+    }
+
     pub fn categorize(&self, text: &str) -> CategorizationResult {
-        // Pre-compute lowercase text once for case-insensitive matching
-        let text_lower = text.to_lowercase();
-
-        // Find first matching rule (default: case-insensitive)
-        for rule in &self.rules {
-            if self.matches(&rule.match_condition, text, &text_lower, false) {
-                return CategorizationResult {
-                    rule_id: Some(rule.id.clone()),
-                    category: rule.category.clone(),
-                    output: rule.output.clone(),
-                    symlinks: rule.symlinks.clone(),
-                };
+        self.categorize_document(&DocumentView::new(text))
+    }
+
+    /// Categorizes a document, letting conditions target individual fields.
+    pub fn categorize_document(&self, document: &DocumentView) -> CategorizationResult {
+        let input = MatchInput::new(document, &self.scopes);
+
+        let mut result = match self.config.mode {
+            CategorizationMode::FirstMatch => self.categorize_by_first_match(&input),
+            CategorizationMode::Scoring => self.categorize_by_score(&input),
+        };
+        let prediction = match result.rule_id {
+            Some(_) => None,
+            None => self.classify(document, &mut result),
+        };
+        let filed = result.rule_id.is_some() || prediction.as_ref().is_some_and(|p| p.filed);
+        if !filed {
+            let default_category = document
+                .correspondent
+                .and_then(|c| c.default_category.as_deref());
+            if let Some(category) = default_category {
+                self.file_as(category, &mut result);
             }
         }
+        self.apply_tags(&input, &mut result);
+        result.explanation.classifier = prediction;
+        result.explanation.correspondent = document.correspondent.cloned();
+        result
+    }
+
+    /// Files a document no rule matched under a category, like the
+    /// highest-priority exclusive rule of that category, or with the
+    /// default output if there is none.
+    fn file_as(&self, category: &str, result: &mut CategorizationResult) {
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| rule.exclusive && rule.category == category);
+        if let Some(rule) = rule {
+            result.output = rule.output.clone();
+            result.symlinks = rule.symlinks.clone();
+        }
+        result.category = category.to_string();
+    }
+
+    /// Asks the classifier for a category. A prediction that reaches the
+    /// auto-filing threshold files the document under that category.
+    fn classify(
+        &self,
+        document: &DocumentView,
+        result: &mut CategorizationResult,
+    ) -> Option<ClassifierPrediction> {
+        let settings = &self.config.classifier;
+        if !settings.enabled {
+            return None;
+        }
+        let (category, confidence) = self
+            .classifier
+            .as_ref()?
+            .predict(document.text, settings.min_examples)?;
+
+        let filed = settings.auto_file && confidence >= settings.threshold;
+        if filed {
+            self.file_as(&category, result);
+            result.confidence = Some(confidence);
+        }
+
+        Some(ClassifierPrediction {
+            category,
+            confidence,
+            filed,
+        })
+    }
+
+    /// Picks the first matching rule, tracing the rules evaluated up to it.
+    fn categorize_by_first_match(&self, input: &MatchInput) -> CategorizationResult {
+        let mut explanation = CategorizationExplanation::default();
+
+        // Find first matching rule (default: case-insensitive, whole matching text)
+        for rule in self.rules.iter().filter(|rule| rule.exclusive) {
+            let rule_explanation = self.explain_rule(rule, input, None);
+            if rule_explanation.matched {
+                explanation.winner = Some(rule_explanation);
+                return self.rule_result(rule, None, vec![], explanation);
+            }
+            explanation.rejected.push(rule_explanation);
+        }
 
         // Return defaults if no rule matches
+        self.default_result(None, vec![], explanation)
+    }
+
+    /// Adds the tag and symlinks of every matching non-exclusive rule.
+    fn apply_tags(&self, input: &MatchInput, result: &mut CategorizationResult) {
+        for rule in self.rules.iter().filter(|rule| !rule.exclusive) {
+            let matched = match self.config.mode {
+                CategorizationMode::FirstMatch => {
+                    self.matches(&rule.match_condition, input, false, Target::default())
+                }
+                CategorizationMode::Scoring => {
+                    self.score(&rule.match_condition, input, false, Target::default())
+                        >= self.config.threshold
+                }
+            };
+            if !matched {
+                continue;
+            }
+            if !result.tags.contains(&rule.category) {
+                result.tags.push(rule.category.clone());
+            }
+            result.symlinks.extend(rule.symlinks.iter().cloned());
+        }
+    }
+
+    /// Scores every rule and picks the best one at or above the threshold,
+    /// tracing all of them. Rules are already in priority order, so the
+    /// stable sort breaks ties by priority.
+    fn categorize_by_score(&self, input: &MatchInput) -> CategorizationResult {
+        let evaluated: Vec<(f64, &Rule, RuleExplanation)> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.exclusive)
+            .map(|rule| {
+                let score = self.score(&rule.match_condition, input, false, Target::default());
+                (score, rule, self.explain_rule(rule, input, Some(score)))
+            })
+            .collect();
+        let mut scored: Vec<(f64, &Rule)> = evaluated
+            .iter()
+            .map(|(score, rule, _)| (*score, *rule))
+            .filter(|(score, _)| *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let winner = scored
+            .first()
+            .filter(|(score, _)| *score >= self.config.threshold)
+            .copied();
+        let alternatives = scored
+            .iter()
+            .skip(usize::from(winner.is_some()))
+            .take(self.config.alternatives)
+            .map(|(score, rule)| CategoryAlternative {
+                rule_id: rule.id.clone(),
+                category: rule.category.clone(),
+                confidence: *score,
+            })
+            .collect();
+
+        let mut explanation = CategorizationExplanation::default();
+        for (_, rule, rule_explanation) in evaluated {
+            if winner.is_some_and(|(_, winner)| winner.id == rule.id) {
+                explanation.winner = Some(rule_explanation);
+            } else {
+                explanation.rejected.push(rule_explanation);
+            }
+        }
+
+        match winner {
+            Some((score, rule)) => self.rule_result(rule, Some(score), alternatives, explanation),
+            None => self.default_result(None, alternatives, explanation),
+        }
+    }
+
+    fn rule_result(
+        &self,
+        rule: &Rule,
+        confidence: Option<f64>,
+        alternatives: Vec<CategoryAlternative>,
+        explanation: CategorizationExplanation,
+    ) -> CategorizationResult {
+        CategorizationResult {
+            rule_id: Some(rule.id.clone()),
+            category: rule.category.clone(),
+            output: rule.output.clone(),
+            symlinks: rule.symlinks.clone(),
+            confidence,
+            alternatives,
+            tags: vec![],
+            explanation,
+        }
+    }
+
+    fn default_result(
+        &self,
+        confidence: Option<f64>,
+        alternatives: Vec<CategoryAlternative>,
+        explanation: CategorizationExplanation,
+    ) -> CategorizationResult {
         CategorizationResult {
             rule_id: None,
             category: "unsorted".to_string(),
             output: self.defaults.output.clone(),
             symlinks: vec![],
+            confidence,
+            alternatives,
+            tags: vec![],
+            explanation,
         }
     }
 
-    fn matches(
+    /// Traces the condition of a rule. Its `matched` flag decides the rule
+    /// in first-match mode, so the explanation cannot differ from the result.
+    fn explain_rule(&self, rule: &Rule, input: &MatchInput, score: Option<f64>) -> RuleExplanation {
+        let condition = self.trace(&rule.match_condition, input, false, Target::default());
+        RuleExplanation {
+            rule_id: rule.id.clone(),
+            category: rule.category.clone(),
+            matched: condition.matched,
+            score,
+            condition,
+        }
+    }
+
+    /// Evaluates a condition like [`Self::matches`], recording every node
+    /// it evaluates. Like there, `all` and `any` stop at the child that
+    /// decides them, so later children are left out of the trace.
+    fn trace<'c>(
         &self,
-        condition: &MatchCondition,
-        text: &str,
-        text_lower: &str,
+        condition: &'c MatchCondition,
+        input: &MatchInput,
+        inherited_case_sensitive: bool,
+        inherited_target: Target<'c>,
+    ) -> ConditionTrace {
+        let compound = match condition {
+            MatchCondition::Simple(simple) => {
+                return self.trace_simple(simple, input, inherited_case_sensitive, inherited_target)
+            }
+            MatchCondition::Compound(compound) => compound,
+        };
+        let case_sensitive = compound.case_sensitive.unwrap_or(inherited_case_sensitive);
+        let target = inherited_target.of(condition);
+        // Traces children until one of them matches, or fails, as `decides`
+        let trace_until = |conditions: &'c [MatchCondition], decides: bool| {
+            let mut children = Vec::new();
+            for cond in conditions {
+                let child = self.trace(cond, input, case_sensitive, target);
+                let decided = child.matched == decides;
+                children.push(child);
+                if decided {
+                    break;
+                }
+            }
+            children
+        };
+
+        let (kind, children) = if let Some(all) = &compound.all {
+            ("all", trace_until(all, false))
+        } else if let Some(any) = &compound.any {
+            ("any", trace_until(any, true))
+        } else if let Some(not) = &compound.not {
+            ("not", vec![self.trace(not, input, case_sensitive, target)])
+        } else {
+            ("empty", vec![])
+        };
+        let matched = match kind {
+            "all" => children.iter().all(|child| child.matched),
+            "any" => children.iter().any(|child| child.matched),
+            "not" => !children[0].matched,
+            _ => false,
+        };
+
+        ConditionTrace {
+            kind: kind.to_string(),
+            matched,
+            field: compound.field,
+            values: vec![],
+            spans: vec![],
+            children,
+        }
+    }
+
+    fn trace_simple<'c>(
+        &self,
+        simple: &'c SimpleMatch,
+        input: &MatchInput,
+        inherited_case_sensitive: bool,
+        inherited_target: Target<'c>,
+    ) -> ConditionTrace {
+        let matched =
+            self.matches_simple(simple, input, inherited_case_sensitive, inherited_target);
+        let case_sensitive = simple.case_sensitive.unwrap_or(inherited_case_sensitive);
+        let target = inherited_target.with_simple(simple);
+        // Only `containsAll` can find values without matching
+        let locate = matched || simple.contains_all.is_some();
+        let find_each = |values: &[String]| -> Vec<Range<usize>> {
+            if !locate {
+                return vec![];
+            }
+            values
+                .iter()
+                .flat_map(|value| self.find(input, value, case_sensitive, target))
+                .collect()
+        };
+
+        let (kind, values, mut ranges) = if let Some(contains) = &simple.contains {
+            let ranges = find_each(std::slice::from_ref(contains));
+            ("contains", vec![contains.clone()], ranges)
+        } else if let Some(values) = &simple.contains_any {
+            ("containsAny", values.clone(), find_each(values))
+        } else if let Some(values) = &simple.contains_all {
+            ("containsAll", values.clone(), find_each(values))
+        } else if let Some(fuzzy) = &simple.fuzzy {
+            let range = locate
+                .then(|| self.find_fuzzy(&fuzzy.value, fuzzy.max_distance, input.fuzzy(target)))
+                .flatten();
+            (
+                "fuzzy",
+                vec![fuzzy.value.clone()],
+                range.into_iter().collect(),
+            )
+        } else if let Some(values) = &simple.contains_any_fuzzy {
+            let ranges = if locate {
+                let text = input.fuzzy(target);
+                values
+                    .iter()
+                    .filter_map(|value| self.find_fuzzy(value, None, text))
+                    .collect()
+            } else {
+                vec![]
+            };
+            ("containsAnyFuzzy", values.clone(), ranges)
+        } else if let Some(variable) = &simple.variable {
+            ("variable", vec![variable.name.clone()], vec![])
+        } else if let Some(correspondent) = &simple.correspondent {
+            ("correspondent", vec![correspondent.clone()], vec![])
+        } else if let Some(pattern) = &simple.pattern {
+            let key = if case_sensitive {
+                pattern.clone()
+            } else {
+                format!("(?i){}", pattern)
+            };
+            let ranges = match self.compiled_patterns.get(&key) {
+                Some(regex) if locate => regex
+                    .find_iter(input.text(target))
+                    .filter(|m| !m.is_empty())
+                    .take(MAX_SPANS)
+                    .map(|m| m.range())
+                    .collect(),
+                _ => vec![],
+            };
+            ("pattern", vec![pattern.clone()], ranges)
+        } else {
+            ("empty", vec![], vec![])
+        };
+
+        ranges.sort_by_key(|range| range.start);
+        ranges.truncate(MAX_SPANS);
+        ConditionTrace {
+            kind: kind.to_string(),
+            matched,
+            field: simple.field,
+            values,
+            spans: ranges
+                .into_iter()
+                .map(|range| input.span(target, range))
+                .collect(),
+            children: vec![],
+        }
+    }
+
+    /// Scores a condition between 0 and 1. Simple conditions score 1 or 0,
+    /// except `containsAll`, which earns the share of values found. `all`
+    /// takes the weighted mean of its children, `any` the best child and
+    /// `not` the complement.
+    fn score<'c>(
+        &self,
+        condition: &'c MatchCondition,
+        input: &MatchInput,
+        inherited_case_sensitive: bool,
+        inherited_target: Target<'c>,
+    ) -> f64 {
+        match condition {
+            MatchCondition::Compound(compound) => {
+                let case_sensitive = compound.case_sensitive.unwrap_or(inherited_case_sensitive);
+                let target = inherited_target.of(condition);
+
+                if let Some(all) = &compound.all {
+                    if all.is_empty() {
+                        return 1.0;
+                    }
+                    let (total, weights) = all.iter().fold((0.0, 0.0), |(total, weights), cond| {
+                        let weight = condition_weight(cond);
+                        let score = self.score(cond, input, case_sensitive, target);
+                        (total + weight * score, weights + weight)
+                    });
+                    return if weights > 0.0 { total / weights } else { 0.0 };
+                }
+
+                if let Some(any) = &compound.any {
+                    return any
+                        .iter()
+                        .map(|cond| self.score(cond, input, case_sensitive, target))
+                        .fold(0.0, f64::max);
+                }
+
+                if let Some(not) = &compound.not {
+                    return 1.0 - self.score(not, input, case_sensitive, target);
+                }
+
+                0.0
+            }
+            MatchCondition::Simple(simple) => {
+                if simple.contains.is_none() && simple.contains_any.is_none() {
+                    if let Some(contains_all) = &simple.contains_all {
+                        if contains_all.is_empty() {
+                            return 1.0;
+                        }
+                        let found = input.count_contained(
+                            contains_all,
+                            simple.case_sensitive.unwrap_or(inherited_case_sensitive),
+                            inherited_target.with_simple(simple),
+                        );
+                        return found as f64 / contains_all.len() as f64;
+                    }
+                }
+                let matched =
+                    self.matches_simple(simple, input, inherited_case_sensitive, inherited_target);
+                if matched {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    fn matches<'c>(
+        &self,
+        condition: &'c MatchCondition,
+        input: &MatchInput,
         case_sensitive: bool,
+        target: Target<'c>,
     ) -> bool {
         match condition {
             MatchCondition::Compound(compound) => {
-                self.matches_compound(compound, text, text_lower, case_sensitive)
+                self.matches_compound(compound, input, case_sensitive, target)
             }
             MatchCondition::Simple(simple) => {
-                self.matches_simple(simple, text, text_lower, case_sensitive)
+                self.matches_simple(simple, input, case_sensitive, target)
             }
         }
     }
 
-    fn matches_compound(
+    fn matches_compound<'c>(
         &self,
-        compound: &CompoundMatch,
-        text: &str,
-        text_lower: &str,
+        compound: &'c CompoundMatch,
+        input: &MatchInput,
         inherited_case_sensitive: bool,
+        inherited_target: Target<'c>,
     ) -> bool {
         let case_sensitive = compound.case_sensitive.unwrap_or(inherited_case_sensitive);
+        let target = inherited_target.with_compound(compound);
 
         // Handle 'all' - all conditions must match
         if let Some(all) = &compound.all {
             return all
                 .iter()
-                .all(|cond| self.matches(cond, text, text_lower, case_sensitive));
+                .all(|cond| self.matches(cond, input, case_sensitive, target));
         }
 
         // Handle 'any' - at least one condition must match
         if let Some(any) = &compound.any {
             return any
                 .iter()
-                .any(|cond| self.matches(cond, text, text_lower, case_sensitive));
+                .any(|cond| self.matches(cond, input, case_sensitive, target));
         }
 
         // Handle 'not' - condition must not match
         if let Some(not) = &compound.not {
-            return !self.matches(not, text, text_lower, case_sensitive);
+            return !self.matches(not, input, case_sensitive, target);
         }
 
         false
     }
 
-    fn matches_simple(
+    fn matches_simple<'c>(
         &self,
-        simple: &SimpleMatch,
-        text: &str,
-        text_lower: &str,
+        simple: &'c SimpleMatch,
+        input: &MatchInput,
         inherited_case_sensitive: bool,
+        inherited_target: Target<'c>,
     ) -> bool {
         let case_sensitive = simple.case_sensitive.unwrap_or(inherited_case_sensitive);
+        let target = inherited_target.with_simple(simple);
+        let text = input.text(target);
 
         // 'contains' - text contains the string
         if let Some(contains) = &simple.contains {
             if case_sensitive {
                 return text.contains(contains.as_str());
             } else {
-                return text_lower.contains(&contains.to_lowercase());
+                return input.lower(target).contains(&contains.to_lowercase());
             }
         }
 
@@ -173,6 +700,7 @@ impl Categorizer {
             if case_sensitive {
                 return contains_any.iter().any(|s| text.contains(s.as_str()));
             } else {
+                let text_lower = input.lower(target);
                 return contains_any
                     .iter()
                     .any(|s| text_lower.contains(&s.to_lowercase()));
@@ -181,13 +709,38 @@ impl Categorizer {
 
         // 'containsAll' - text contains all of the strings
         if let Some(contains_all) = &simple.contains_all {
-            if case_sensitive {
-                return contains_all.iter().all(|s| text.contains(s.as_str()));
-            } else {
-                return contains_all
-                    .iter()
-                    .all(|s| text_lower.contains(&s.to_lowercase()));
-            }
+            return input.count_contained(contains_all, case_sensitive, target)
+                == contains_all.len();
+        }
+
+        // 'fuzzy' - text contains the value, tolerating OCR errors
+        if let Some(fuzzy) = &simple.fuzzy {
+            return self.matches_fuzzy(&fuzzy.value, fuzzy.max_distance, input.fuzzy(target));
+        }
+
+        // 'containsAnyFuzzy' - text contains at least one value, tolerating OCR errors
+        if let Some(values) = &simple.contains_any_fuzzy {
+            let text = input.fuzzy(target);
+            return values
+                .iter()
+                .any(|value| self.matches_fuzzy(value, None, text));
+        }
+
+        // 'variable' - an extracted variable satisfies the comparison
+        if let Some(variable) = &simple.variable {
+            return matches_variable(
+                variable,
+                input.document.variable(&variable.name),
+                case_sensitive,
+            );
+        }
+
+        // 'correspondent' - the document comes from this correspondent
+        if let Some(correspondent) = &simple.correspondent {
+            return input
+                .document
+                .correspondent
+                .is_some_and(|c| c.id.eq_ignore_ascii_case(correspondent));
         }
 
         // 'pattern' - regex pattern matches (use pre-compiled regex)
@@ -206,12 +759,257 @@ impl Categorizer {
 
         false
     }
+
+    /// Returns the byte ranges where the field contains the value.
+    fn find(
+        &self,
+        input: &MatchInput,
+        value: &str,
+        case_sensitive: bool,
+        target: Target,
+    ) -> Vec<Range<usize>> {
+        if value.is_empty() {
+            return vec![];
+        }
+        let text = input.text(target);
+        if case_sensitive {
+            return text
+                .match_indices(value)
+                .take(MAX_SPANS)
+                .map(|(start, found)| start..start + found.len())
+                .collect();
+        }
+        // Search the original text so offsets are not shifted by lowercasing
+        match self.literal_patterns.get(value) {
+            Some(regex) => regex
+                .find_iter(text)
+                .take(MAX_SPANS)
+                .map(|m| m.range())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    fn matches_fuzzy(&self, value: &str, max_distance: Option<usize>, text: &FuzzyText) -> bool {
+        match self.fuzzy_patterns.get(&(value.to_string(), max_distance)) {
+            Some(pattern) => pattern.is_match(text),
+            None => FuzzyPattern::new(value, max_distance).is_match(text),
+        }
+    }
+
+    fn find_fuzzy(
+        &self,
+        value: &str,
+        max_distance: Option<usize>,
+        text: &FuzzyText,
+    ) -> Option<Range<usize>> {
+        match self.fuzzy_patterns.get(&(value.to_string(), max_distance)) {
+            Some(pattern) => pattern.find(text),
+            None => FuzzyPattern::new(value, max_distance).find(text),
+        }
+    }
+}
+
+/// Weight of a condition inside `all`; unset weights count as 1.
+fn condition_weight(condition: &MatchCondition) -> f64 {
+    let weight = match condition {
+        MatchCondition::Simple(simple) => simple.weight,
+        MatchCondition::Compound(compound) => compound.weight,
+    };
+    weight.unwrap_or(1.0).max(0.0)
+}
+
+/// What a condition is evaluated against: a field, optionally narrowed to
+/// pages and a page region. Each is inherited from the enclosing compound
+/// unless the condition sets its own.
+#[derive(Debug, Clone, Copy, Default)]
+struct Target<'c> {
+    field: Option<MatchField>,
+    pages: Option<&'c PageSelection>,
+    region: Option<PageRegion>,
+}
+
+impl<'c> Target<'c> {
+    fn of(self, condition: &'c MatchCondition) -> Self {
+        match condition {
+            MatchCondition::Simple(simple) => self.with_simple(simple),
+            MatchCondition::Compound(compound) => self.with_compound(compound),
+        }
+    }
+
+    fn with_simple(self, simple: &'c SimpleMatch) -> Self {
+        Self {
+            field: simple.field.or(self.field),
+            pages: simple.pages.as_ref().or(self.pages),
+            region: simple.region.or(self.region),
+        }
+    }
+
+    fn with_compound(self, compound: &'c CompoundMatch) -> Self {
+        Self {
+            field: compound.field.or(self.field),
+            pages: compound.pages.as_ref().or(self.pages),
+            region: compound.region.or(self.region),
+        }
+    }
+
+    /// The page scope, if the target narrows the extracted text. Scopes only
+    /// apply to the text fields.
+    fn scope(&self) -> Option<Scope> {
+        if self.pages.is_none() && self.region.is_none() {
+            return None;
+        }
+        let field = match self.field {
+            None | Some(MatchField::Text) => MatchField::Text,
+            Some(MatchField::FirstPage) => MatchField::FirstPage,
+            Some(_) => return None,
+        };
+        Some(Scope {
+            field,
+            pages: self.pages.cloned(),
+            region: self.region,
+        })
+    }
+}
+
+/// Pages and a region of the extracted text or its first page.
+#[derive(Debug, Clone, PartialEq)]
+struct Scope {
+    field: MatchField,
+    pages: Option<PageSelection>,
+    region: Option<PageRegion>,
+}
+
+/// Number of distinct match targets: the default matching text plus each field.
+const FIELD_SLOTS: usize = MatchField::FirstPage as usize + 2;
+
+/// A page scope of a document with its derived forms, computed on first use.
+#[derive(Default)]
+struct ScopedInput {
+    text: OnceCell<ScopedText>,
+    lower: OnceCell<String>,
+    fuzzy: OnceCell<FuzzyText>,
+}
+
+/// A document together with the derived forms conditions compare against.
+struct MatchInput<'a> {
+    document: &'a DocumentView<'a>,
+    /// Lowercased and fuzzy-normalized field values, computed on first use
+    lower: [OnceCell<String>; FIELD_SLOTS],
+    fuzzy: [OnceCell<FuzzyText>; FIELD_SLOTS],
+    /// The categorizer's page scopes, and their derived forms
+    scopes: &'a [Scope],
+    scoped: Vec<ScopedInput>,
+}
+
+impl<'a> MatchInput<'a> {
+    fn new(document: &'a DocumentView<'a>, scopes: &'a [Scope]) -> Self {
+        Self {
+            document,
+            lower: Default::default(),
+            fuzzy: Default::default(),
+            scopes,
+            scoped: scopes.iter().map(|_| ScopedInput::default()).collect(),
+        }
+    }
+
+    fn slot(field: Option<MatchField>) -> usize {
+        field.map_or(0, |f| f as usize + 1)
+    }
+
+    /// Returns the scope of a target with its cache, if the target is scoped.
+    fn scoped(&self, target: Target) -> Option<(&Scope, &ScopedInput)> {
+        let scope = target.scope()?;
+        let index = self.scopes.iter().position(|known| *known == scope)?;
+        Some((&self.scopes[index], &self.scoped[index]))
+    }
+
+    fn scoped_text<'s>(&self, scope: &Scope, scoped: &'s ScopedInput) -> &'s ScopedText {
+        scoped.text.get_or_init(|| {
+            let text = self.document.field(Some(scope.field));
+            // The first page is a single page
+            let pages = match scope.field {
+                MatchField::Text => self.document.pages,
+                _ => &[],
+            };
+            ScopedText::new(text, pages, scope.pages.as_ref(), scope.region)
+        })
+    }
+
+    fn text(&self, target: Target) -> &str {
+        match self.scoped(target) {
+            Some((scope, scoped)) => self.scoped_text(scope, scoped).text(),
+            None => self.document.field(target.field),
+        }
+    }
+
+    fn lower(&self, target: Target) -> &str {
+        match self.scoped(target) {
+            Some((_, scoped)) => scoped
+                .lower
+                .get_or_init(|| self.text(target).to_lowercase()),
+            None => self.lower[Self::slot(target.field)]
+                .get_or_init(|| self.text(target).to_lowercase()),
+        }
+    }
+
+    fn fuzzy(&self, target: Target) -> &FuzzyText {
+        match self.scoped(target) {
+            Some((_, scoped)) => scoped
+                .fuzzy
+                .get_or_init(|| FuzzyText::new(self.text(target))),
+            None => self.fuzzy[Self::slot(target.field)]
+                .get_or_init(|| FuzzyText::new(self.text(target))),
+        }
+    }
+
+    /// Converts a range of a field into a span. Ranges in the extracted
+    /// text, whether searched by default, whole or by page, are reported
+    /// against the `text` field.
+    fn span(&self, target: Target, range: Range<usize>) -> MatchSpan {
+        let text = self.text(target)[range.clone()].to_string();
+        if let Some((scope, scoped)) = self.scoped(target) {
+            // Scopes cover the text or its first page, which starts it
+            let range = self.scoped_text(scope, scoped).source_range(range);
+            return MatchSpan {
+                field: Some(MatchField::Text),
+                start: range.start,
+                end: range.end,
+                text,
+            };
+        }
+        let field = match target.field {
+            None | Some(MatchField::FirstPage) => Some(MatchField::Text),
+            field => field,
+        };
+        MatchSpan {
+            field,
+            start: range.start,
+            end: range.end,
+            text,
+        }
+    }
+
+    /// Counts how many of the values the field contains.
+    fn count_contained(&self, values: &[String], case_sensitive: bool, target: Target) -> usize {
+        if case_sensitive {
+            let text = self.text(target);
+            values.iter().filter(|s| text.contains(s.as_str())).count()
+        } else {
+            let text_lower = self.lower(target);
+            values
+                .iter()
+                .filter(|s| text_lower.contains(&s.to_lowercase()))
+                .count()
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::schema::OutputConfig;
+    use crate::config::schema::{FuzzyMatch, OutputConfig, PagePosition};
+    use crate::processor::pages::join_pages;
 
     fn create_default_output() -> OutputConfig {
         OutputConfig {
@@ -232,7 +1030,15 @@ mod tests {
             contains_any: None,
             contains_all: None,
             pattern: None,
+            fuzzy: None,
+            contains_any_fuzzy: None,
+            variable: None,
+            correspondent: None,
             case_sensitive,
+            field: None,
+            pages: None,
+            region: None,
+            weight: None,
         }
     }
 
@@ -241,6 +1047,7 @@ mod tests {
             id: id.to_string(),
             name: id.to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: condition,
             category: id.to_string(),
             output: create_default_output(),
@@ -254,12 +1061,21 @@ mod tests {
             id: "invoice".to_string(),
             name: "Invoice".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("invoice".to_string()),
                 contains_any: None,
                 contains_all: None,
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "invoices".to_string(),
             output: OutputConfig {
@@ -282,6 +1098,7 @@ mod tests {
             id: "invoice".to_string(),
             name: "Invoice".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: None,
                 contains_any: Some(vec![
@@ -291,7 +1108,15 @@ mod tests {
                 ]),
                 contains_all: None,
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "invoices".to_string(),
             output: OutputConfig {
@@ -320,12 +1145,21 @@ mod tests {
             id: "tax-invoice".to_string(),
             name: "Tax Invoice".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: None,
                 contains_any: None,
                 contains_all: Some(vec!["Invoice".to_string(), "VAT".to_string()]),
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "tax-invoices".to_string(),
             output: OutputConfig {
@@ -351,12 +1185,21 @@ mod tests {
             id: "invoice-number".to_string(),
             name: "Invoice with number".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: None,
                 contains_any: None,
                 contains_all: None,
                 pattern: Some(r"INV-\d{4,}".to_string()),
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "numbered-invoices".to_string(),
             output: OutputConfig {
@@ -386,6 +1229,7 @@ mod tests {
             id: "tax-invoice".to_string(),
             name: "Tax Invoice".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Compound(CompoundMatch {
                 all: Some(vec![
                     MatchCondition::Simple(SimpleMatch {
@@ -393,19 +1237,39 @@ mod tests {
                         contains: None,
                         contains_all: None,
                         pattern: None,
+                        fuzzy: None,
+                        contains_any_fuzzy: None,
+                        variable: None,
+                        correspondent: None,
                         case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
                     }),
                     MatchCondition::Simple(SimpleMatch {
                         contains_any: Some(vec!["VAT".to_string(), "MwSt".to_string()]),
                         contains: None,
                         contains_all: None,
                         pattern: None,
+                        fuzzy: None,
+                        contains_any_fuzzy: None,
+                        variable: None,
+                        correspondent: None,
                         case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
                     }),
                 ]),
                 any: None,
                 not: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "tax-invoices".to_string(),
             output: OutputConfig {
@@ -435,6 +1299,7 @@ mod tests {
             id: "non-draft".to_string(),
             name: "Non-Draft Invoice".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Compound(CompoundMatch {
                 all: Some(vec![
                     MatchCondition::Simple(SimpleMatch {
@@ -442,7 +1307,15 @@ mod tests {
                         contains_any: None,
                         contains_all: None,
                         pattern: None,
+                        fuzzy: None,
+                        contains_any_fuzzy: None,
+                        variable: None,
+                        correspondent: None,
                         case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
                     }),
                     MatchCondition::Compound(CompoundMatch {
                         not: Some(Box::new(MatchCondition::Simple(SimpleMatch {
@@ -450,16 +1323,32 @@ mod tests {
                             contains_any: None,
                             contains_all: None,
                             pattern: None,
+                            fuzzy: None,
+                            contains_any_fuzzy: None,
+                            variable: None,
+                            correspondent: None,
                             case_sensitive: None,
+                            field: None,
+                            pages: None,
+                            region: None,
+                            weight: None,
                         }))),
                         all: None,
                         any: None,
                         case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
                     }),
                 ]),
                 any: None,
                 not: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "invoices".to_string(),
             output: OutputConfig {
@@ -485,12 +1374,21 @@ mod tests {
                 id: "low-priority".to_string(),
                 name: "Low Priority".to_string(),
                 priority: 10,
+                exclusive: true,
                 match_condition: MatchCondition::Simple(SimpleMatch {
                     contains: Some("invoice".to_string()),
                     contains_any: None,
                     contains_all: None,
                     pattern: None,
+                    fuzzy: None,
+                    contains_any_fuzzy: None,
+                    variable: None,
+                    correspondent: None,
                     case_sensitive: None,
+                    field: None,
+                    pages: None,
+                    region: None,
+                    weight: None,
                 }),
                 category: "low".to_string(),
                 output: OutputConfig {
@@ -503,12 +1401,21 @@ mod tests {
                 id: "high-priority".to_string(),
                 name: "High Priority".to_string(),
                 priority: 100,
+                exclusive: true,
                 match_condition: MatchCondition::Simple(SimpleMatch {
                     contains: Some("invoice".to_string()),
                     contains_any: None,
                     contains_all: None,
                     pattern: None,
+                    fuzzy: None,
+                    contains_any_fuzzy: None,
+                    variable: None,
+                    correspondent: None,
                     case_sensitive: None,
+                    field: None,
+                    pages: None,
+                    region: None,
+                    weight: None,
                 }),
                 category: "high".to_string(),
                 output: OutputConfig {
@@ -533,12 +1440,21 @@ mod tests {
             id: "invoice".to_string(),
             name: "Invoice".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("invoice".to_string()),
                 contains_any: None,
                 contains_all: None,
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "invoices".to_string(),
             output: OutputConfig {
@@ -563,12 +1479,21 @@ mod tests {
             id: "test".to_string(),
             name: "Test".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("Invoice".to_string()),
                 contains_any: None,
                 contains_all: None,
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "test".to_string(),
             output: create_default_output(),
@@ -598,12 +1523,21 @@ mod tests {
             id: "test".to_string(),
             name: "Test".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: None,
                 contains_any: Some(vec![]),
                 contains_all: None,
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "test".to_string(),
             output: create_default_output(),
@@ -622,12 +1556,21 @@ mod tests {
             id: "test".to_string(),
             name: "Test".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: None,
                 contains_any: None,
                 contains_all: Some(vec![]),
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "test".to_string(),
             output: create_default_output(),
@@ -647,6 +1590,7 @@ mod tests {
             id: "nested".to_string(),
             name: "Nested".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Compound(CompoundMatch {
                 all: Some(vec![
                     MatchCondition::Compound(CompoundMatch {
@@ -656,27 +1600,55 @@ mod tests {
                                 contains_any: None,
                                 contains_all: None,
                                 pattern: None,
+                                fuzzy: None,
+                                contains_any_fuzzy: None,
+                                variable: None,
+                                correspondent: None,
                                 case_sensitive: None,
+                                field: None,
+                                pages: None,
+                                region: None,
+                                weight: None,
                             }))),
                             all: None,
                             any: None,
                             case_sensitive: None,
+                            field: None,
+                            pages: None,
+                            region: None,
+                            weight: None,
                         })]),
                         all: None,
                         not: None,
                         case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
                     }),
                     MatchCondition::Simple(SimpleMatch {
                         contains: Some("include".to_string()),
                         contains_any: None,
                         contains_all: None,
                         pattern: None,
+                        fuzzy: None,
+                        contains_any_fuzzy: None,
+                        variable: None,
+                        correspondent: None,
                         case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
                     }),
                 ]),
                 any: None,
                 not: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "nested".to_string(),
             output: create_default_output(),
@@ -697,13 +1669,22 @@ mod tests {
             id: "special".to_string(),
             name: "Special".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: None,
                 contains_any: None,
                 contains_all: None,
                 // Match literal "Price: $100.00" with escaped special chars
                 pattern: Some(r"Price:\s+\$\d+\.\d{2}".to_string()),
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "price".to_string(),
             output: create_default_output(),
@@ -723,6 +1704,7 @@ mod tests {
             id: "any-compound".to_string(),
             name: "Any Compound".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Compound(CompoundMatch {
                 any: Some(vec![
                     MatchCondition::Simple(SimpleMatch {
@@ -730,19 +1712,39 @@ mod tests {
                         contains_any: None,
                         contains_all: None,
                         pattern: None,
+                        fuzzy: None,
+                        contains_any_fuzzy: None,
+                        variable: None,
+                        correspondent: None,
                         case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
                     }),
                     MatchCondition::Simple(SimpleMatch {
                         contains: Some("beta".to_string()),
                         contains_any: None,
                         contains_all: None,
                         pattern: None,
+                        fuzzy: None,
+                        contains_any_fuzzy: None,
+                        variable: None,
+                        correspondent: None,
                         case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
                     }),
                 ]),
                 all: None,
                 not: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "compound-any".to_string(),
             output: create_default_output(),
@@ -766,12 +1768,21 @@ mod tests {
             id: "bad-regex".to_string(),
             name: "Bad Regex".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: None,
                 contains_any: None,
                 contains_all: None,
                 pattern: Some("[invalid".to_string()), // Invalid regex
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "bad".to_string(),
             output: create_default_output(),
@@ -791,12 +1802,21 @@ mod tests {
             id: "empty".to_string(),
             name: "Empty".to_string(),
             priority: 0,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: None,
                 contains_any: None,
                 contains_all: None,
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: "empty".to_string(),
             output: create_default_output(),
@@ -854,7 +1874,15 @@ mod tests {
                 contains_any: Some(vec!["Invoice".to_string(), "Bill".to_string()]),
                 contains_all: None,
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
         );
         let categorizer = Categorizer::new(vec![rule], create_defaults());
@@ -876,7 +1904,15 @@ mod tests {
                 contains_any: None,
                 contains_all: Some(vec!["Invoice".to_string(), "VAT".to_string()]),
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
         );
         let categorizer = Categorizer::new(vec![rule], create_defaults());
@@ -901,7 +1937,15 @@ mod tests {
                 contains_any: None,
                 contains_all: None,
                 pattern: Some(r"INV-\d+".to_string()),
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
         );
         let categorizer = Categorizer::new(vec![rule], create_defaults());
@@ -919,7 +1963,15 @@ mod tests {
                 contains_any: None,
                 contains_all: None,
                 pattern: Some(r"INV-\d+".to_string()),
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
+                correspondent: None,
                 case_sensitive: Some(true),
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
         );
         let categorizer = Categorizer::new(vec![rule], create_defaults());
@@ -941,6 +1993,10 @@ mod tests {
                 any: None,
                 not: None,
                 case_sensitive: Some(true),
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
         );
         let categorizer = Categorizer::new(vec![rule], create_defaults());
@@ -965,6 +2021,10 @@ mod tests {
                 any: None,
                 not: None,
                 case_sensitive: Some(true),
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
         );
         let categorizer = Categorizer::new(vec![rule], create_defaults());
@@ -975,4 +2035,744 @@ mod tests {
         // "world" doesn't match (case-sensitive inherited)
         assert!(categorizer.categorize("hello world").rule_id.is_none());
     }
+
+    #[test]
+    fn test_fuzzy_match_tolerates_ocr_errors() {
+        let rule = make_rule(
+            "swisscom",
+            MatchCondition::Simple(SimpleMatch {
+                fuzzy: Some(FuzzyMatch {
+                    value: "Swisscom".to_string(),
+                    max_distance: Some(1),
+                }),
+                ..simple(None, Some(true))
+            }),
+        );
+        let categorizer = Categorizer::new(vec![rule], create_defaults());
+
+        assert!(categorizer
+            .categorize("Rechnung SW1SS C0M")
+            .rule_id
+            .is_some());
+        assert!(categorizer
+            .categorize("Rechnung Swiscom AG")
+            .rule_id
+            .is_some());
+        assert!(categorizer.categorize("Rechnung Sunrise").rule_id.is_none());
+    }
+
+    #[test]
+    fn test_contains_any_fuzzy_in_compound() {
+        let rule = make_rule(
+            "insurance",
+            MatchCondition::Compound(CompoundMatch {
+                all: Some(vec![
+                    MatchCondition::Simple(SimpleMatch {
+                        contains_any_fuzzy: Some(vec![
+                            "Krankenkasse".to_string(),
+                            "Versicherung".to_string(),
+                        ]),
+                        ..simple(None, None)
+                    }),
+                    MatchCondition::Simple(simple(Some("Police"), None)),
+                ]),
+                any: None,
+                not: None,
+                case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
+            }),
+        );
+        let categorizer = Categorizer::new(vec![rule], create_defaults());
+
+        assert!(categorizer
+            .categorize("Ihre Krankenkase - Police 2024")
+            .rule_id
+            .is_some());
+        assert!(categorizer.categorize("Ihre Krankenkase").rule_id.is_none());
+        assert!(categorizer
+            .categorize("Ihre Bank - Police 2024")
+            .rule_id
+            .is_none());
+    }
+
+    #[test]
+    fn test_field_scoped_match() {
+        let rule = make_rule(
+            "foo-billing",
+            MatchCondition::Simple(SimpleMatch {
+                field: Some(MatchField::EmailFrom),
+                ..simple(Some("billing@foo.com"), None)
+            }),
+        );
+        let categorizer = Categorizer::new(vec![rule], create_defaults());
+
+        let body = "Please contact billing@foo.com with questions";
+        let mentioned = DocumentView {
+            text: body,
+            email_from: Some("news@bar.com"),
+            ..Default::default()
+        };
+        assert!(categorizer
+            .categorize_document(&mentioned)
+            .rule_id
+            .is_none());
+
+        let sent = DocumentView {
+            email_from: Some("Foo Billing <Billing@Foo.com>"),
+            ..mentioned
+        };
+        assert!(categorizer.categorize_document(&sent).rule_id.is_some());
+    }
+
+    #[test]
+    fn test_compound_field_is_inherited() {
+        let rule = make_rule(
+            "scan",
+            MatchCondition::Compound(CompoundMatch {
+                all: Some(vec![
+                    MatchCondition::Simple(SimpleMatch {
+                        pattern: Some(r"^scan_\d+\.pdf$".to_string()),
+                        ..simple(None, None)
+                    }),
+                    // Overrides the inherited filename field
+                    MatchCondition::Simple(SimpleMatch {
+                        field: Some(MatchField::FirstPage),
+                        ..simple(Some("Kontoauszug"), None)
+                    }),
+                ]),
+                any: None,
+                not: None,
+                case_sensitive: None,
+                field: Some(MatchField::Filename),
+                pages: None,
+                region: None,
+                weight: None,
+            }),
+        );
+        let categorizer = Categorizer::new(vec![rule], create_defaults());
+
+        let (text, pages) = join_pages(&["Kontoauszug Januar", "Seite 2"]);
+        let document = DocumentView {
+            filename: Some("scan_0042.pdf"),
+            pages: &pages,
+            ..DocumentView::new(&text)
+        };
+        assert!(categorizer.categorize_document(&document).rule_id.is_some());
+
+        let (text, pages) = join_pages(&["Seite 1", "Kontoauszug Januar"]);
+        let later_page = DocumentView {
+            filename: Some("scan_0042.pdf"),
+            pages: &pages,
+            ..DocumentView::new(&text)
+        };
+        assert!(categorizer
+            .categorize_document(&later_page)
+            .rule_id
+            .is_none());
+
+        let renamed = DocumentView {
+            filename: Some("statement.pdf"),
+            ..document
+        };
+        assert!(categorizer.categorize_document(&renamed).rule_id.is_none());
+    }
+
+    #[test]
+    fn test_page_and_region_scoped_match() {
+        let mut letterhead = make_rule(
+            "acme",
+            MatchCondition::Compound(CompoundMatch {
+                all: Some(vec![
+                    MatchCondition::Simple(simple(Some("Acme GmbH"), None)),
+                    // Replaces the inherited pages and region with its own
+                    MatchCondition::Simple(SimpleMatch {
+                        pattern: Some(r"Total CHF \d+".to_string()),
+                        pages: Some(PageSelection::Position(PagePosition::Last)),
+                        region: Some(PageRegion::Bottom(2)),
+                        ..simple(None, None)
+                    }),
+                ]),
+                any: None,
+                not: None,
+                case_sensitive: None,
+                field: None,
+                pages: Some(PageSelection::Numbers(vec![1])),
+                region: Some(PageRegion::Top(2)),
+                weight: None,
+            }),
+        );
+        letterhead.priority = 10;
+        let categorizer = Categorizer::new(vec![letterhead], create_defaults());
+
+        let (text, pages) = join_pages(&[
+            "Acme GmbH\n\nInvoice 42\nItems",
+            "Terms\nTotal CHF 120\nThanks\n",
+        ]);
+        let letter = DocumentView {
+            email_from: Some("Acme GmbH <a@acme.ch>"),
+            text: &text,
+            pages: &pages,
+            ..Default::default()
+        };
+        let result = categorizer.categorize_document(&letter);
+        assert_eq!(result.rule_id.as_deref(), Some("acme"));
+
+        // Spans in a scoped text are reported against the whole text
+        let winner = result.explanation.winner.unwrap();
+        let spans = winner.condition.matched_spans();
+        assert_eq!(spans.len(), 2);
+        for span in &spans {
+            assert_eq!(span.field, Some(MatchField::Text));
+            assert_eq!(&text[span.start..span.end], span.text);
+        }
+
+        // A reference deep inside the document, or only in the email
+        // headers, is outside the scope
+        let (quoted, pages) = join_pages(&[
+            "Contract\nParties\nSee Acme GmbH",
+            "Total CHF 120\nThanks\n",
+        ]);
+        let contract = DocumentView {
+            email_from: Some("Acme GmbH <a@acme.ch>"),
+            text: &quoted,
+            pages: &pages,
+            ..Default::default()
+        };
+        assert!(categorizer.categorize_document(&contract).rule_id.is_none());
+
+        // The total must be on the last page, not just anywhere
+        let (early_total, pages) = join_pages(&["Acme GmbH\nTotal CHF 120", "Terms\nThanks\n"]);
+        let early_total = DocumentView {
+            pages: &pages,
+            ..DocumentView::new(&early_total)
+        };
+        assert!(categorizer
+            .categorize_document(&early_total)
+            .rule_id
+            .is_none());
+    }
+
+    fn weighted(condition: SimpleMatch, weight: f64) -> MatchCondition {
+        MatchCondition::Simple(SimpleMatch {
+            pages: None,
+            region: None,
+            weight: Some(weight),
+            ..condition
+        })
+    }
+
+    fn scoring_rules() -> Vec<Rule> {
+        let insurance = make_rule(
+            "insurance",
+            MatchCondition::Compound(CompoundMatch {
+                all: Some(vec![
+                    weighted(simple(Some("Versicherung"), None), 3.0),
+                    weighted(simple(Some("Police"), None), 1.0),
+                    weighted(
+                        SimpleMatch {
+                            contains_all: Some(vec!["Prämie".to_string(), "Franchise".to_string()]),
+                            ..simple(None, None)
+                        },
+                        1.0,
+                    ),
+                ]),
+                any: None,
+                not: None,
+                case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
+            }),
+        );
+        let health = Rule {
+            priority: 10,
+            ..make_rule(
+                "health",
+                MatchCondition::Compound(CompoundMatch {
+                    all: Some(vec![
+                        MatchCondition::Simple(simple(Some("Arzt"), None)),
+                        MatchCondition::Simple(simple(Some("Versicherung"), None)),
+                    ]),
+                    any: None,
+                    not: None,
+                    case_sensitive: None,
+                    field: None,
+                    pages: None,
+                    region: None,
+                    weight: None,
+                }),
+            )
+        };
+        vec![insurance, health]
+    }
+
+    fn scoring(threshold: f64, alternatives: usize) -> CategorizationConfig {
+        CategorizationConfig {
+            mode: CategorizationMode::Scoring,
+            threshold,
+            alternatives,
+            classifier: Default::default(),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_scoring_picks_best_rule_with_alternatives() {
+        let text = "Versicherung Police Prämie";
+
+        // Neither rule matches outright
+        let first_match = Categorizer::new(scoring_rules(), create_defaults());
+        let result = first_match.categorize(text);
+        assert_eq!(result.rule_id, None);
+        assert_eq!(result.confidence, None);
+        assert!(result.alternatives.is_empty());
+
+        let categorizer =
+            Categorizer::new(scoring_rules(), create_defaults()).with_config(scoring(0.5, 3));
+        let result = categorizer.categorize(text);
+        assert_eq!(result.rule_id, Some("insurance".to_string()));
+        // (3 + 1 + 0.5) / 5
+        assert_close(result.confidence.unwrap(), 0.9);
+        assert_eq!(result.alternatives.len(), 1);
+        assert_eq!(result.alternatives[0].category, "health");
+        assert_close(result.alternatives[0].confidence, 0.5);
+
+        // The explanation carries the scores the decision was made with
+        let winner = result.explanation.winner.as_ref().unwrap();
+        assert_eq!(winner.rule_id, "insurance");
+        assert_eq!(winner.score, result.confidence);
+        assert_eq!(result.explanation.rejected.len(), 1);
+        assert_eq!(
+            result.explanation.rejected[0].score,
+            Some(result.alternatives[0].confidence)
+        );
+    }
+
+    #[test]
+    fn test_scoring_below_threshold_is_unsorted() {
+        let categorizer =
+            Categorizer::new(scoring_rules(), create_defaults()).with_config(scoring(0.95, 1));
+        let result = categorizer.categorize("Versicherung Police Prämie");
+
+        assert_eq!(result.rule_id, None);
+        assert_eq!(result.category, "unsorted");
+        assert_eq!(result.confidence, None);
+        assert_eq!(result.alternatives.len(), 1);
+        assert_eq!(result.alternatives[0].rule_id, "insurance");
+    }
+
+    #[test]
+    fn test_scoring_ties_go_to_priority() {
+        let categorizer =
+            Categorizer::new(scoring_rules(), create_defaults()).with_config(scoring(0.5, 3));
+        let result = categorizer.categorize("Arzt Versicherung Police Prämie Franchise");
+
+        assert_eq!(result.rule_id, Some("health".to_string()));
+        assert_close(result.confidence.unwrap(), 1.0);
+        assert_eq!(result.alternatives[0].rule_id, "insurance");
+        assert_close(result.alternatives[0].confidence, 1.0);
+    }
+
+    #[test]
+    fn test_scoring_any_and_not() {
+        let compound = |all, any, not| CompoundMatch {
+            all,
+            any,
+            not,
+            case_sensitive: None,
+            field: None,
+            pages: None,
+            region: None,
+            weight: None,
+        };
+        let rule = make_rule(
+            "receipt",
+            MatchCondition::Compound(compound(
+                Some(vec![
+                    MatchCondition::Compound(compound(
+                        None,
+                        Some(vec![
+                            MatchCondition::Simple(simple(Some("Quittung"), None)),
+                            MatchCondition::Simple(SimpleMatch {
+                                contains_all: Some(vec!["Beleg".to_string(), "bar".to_string()]),
+                                ..simple(None, None)
+                            }),
+                        ]),
+                        None,
+                    )),
+                    MatchCondition::Compound(compound(
+                        None,
+                        None,
+                        Some(Box::new(MatchCondition::Simple(simple(
+                            Some("Rechnung"),
+                            None,
+                        )))),
+                    )),
+                ]),
+                None,
+                None,
+            )),
+        );
+        let categorizer =
+            Categorizer::new(vec![rule], create_defaults()).with_config(scoring(0.5, 3));
+
+        // 'any' takes its best child, half of the containsAll; 'not' scores 1
+        let result = categorizer.categorize("Beleg Nr. 4");
+        assert_close(result.confidence.unwrap(), 0.75);
+
+        let result = categorizer.categorize("Rechnung Quittung");
+        assert_close(result.confidence.unwrap(), 0.5);
+
+        let result = categorizer.categorize("Rechnung Nr. 4");
+        assert_eq!(result.rule_id, None);
+        assert!(result.alternatives.is_empty());
+    }
+
+    fn tag_rule(id: &str, priority: i32, contains: &str) -> Rule {
+        Rule {
+            priority,
+            exclusive: false,
+            output: OutputConfig::default(),
+            symlinks: vec![SymlinkConfig {
+                target: format!("tags/{}", id),
+            }],
+            ..make_rule(id, MatchCondition::Simple(simple(Some(contains), None)))
+        }
+    }
+
+    #[test]
+    fn test_non_exclusive_rules_add_tags() {
+        let mut rules = vec![
+            make_rule(
+                "insurance",
+                MatchCondition::Simple(simple(Some("Police"), None)),
+            ),
+            tag_rule("tax", 5, "Steuer"),
+            tag_rule("health", 10, "Arzt"),
+        ];
+        // A tag rule never wins the primary category, even at higher priority
+        rules[0].priority = 1;
+        let categorizer = Categorizer::new(rules.clone(), create_defaults());
+
+        let result = categorizer.categorize("Police Arzt Steuer");
+        assert_eq!(result.category, "insurance");
+        assert_eq!(result.tags, vec!["health", "tax"]);
+        let targets: Vec<&str> = result.symlinks.iter().map(|s| s.target.as_str()).collect();
+        assert_eq!(targets, vec!["tags/health", "tags/tax"]);
+
+        let result = categorizer.categorize("Steuer only");
+        assert_eq!(result.category, "unsorted");
+        assert_eq!(result.tags, vec!["tax"]);
+
+        let result = categorizer.categorize("Police");
+        assert!(result.tags.is_empty());
+        assert!(result.symlinks.is_empty());
+
+        // In scoring mode tag rules apply above the threshold and are never alternatives
+        let categorizer = Categorizer::new(rules, create_defaults()).with_config(scoring(0.5, 3));
+        let result = categorizer.categorize("Police Steuer");
+        assert_eq!(result.category, "insurance");
+        assert_eq!(result.tags, vec!["tax"]);
+        assert!(result.alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_explanation_traces_leaves_and_spans() {
+        let mut pattern = simple(None, None);
+        pattern.pattern = Some(r"CHF \d+".to_string());
+        let mut fuzzy = simple(None, None);
+        fuzzy.fuzzy = Some(FuzzyMatch {
+            value: "Swisscom".to_string(),
+            max_distance: Some(0),
+        });
+        let mut invoice = make_rule(
+            "invoice",
+            MatchCondition::Compound(CompoundMatch {
+                all: Some(vec![
+                    MatchCondition::Simple(simple(Some("invoice"), None)),
+                    MatchCondition::Compound(CompoundMatch {
+                        all: None,
+                        any: Some(vec![
+                            MatchCondition::Simple(pattern),
+                            MatchCondition::Simple(fuzzy),
+                        ]),
+                        not: None,
+                        case_sensitive: None,
+                        field: None,
+                        pages: None,
+                        region: None,
+                        weight: None,
+                    }),
+                ]),
+                any: None,
+                not: None,
+                case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
+            }),
+        );
+        invoice.priority = 10;
+        let mut receipt = make_rule(
+            "receipt",
+            MatchCondition::Simple(simple(Some("Receipt"), None)),
+        );
+        receipt.priority = 20;
+        let fallback = make_rule("other", MatchCondition::Simple(simple(Some("x"), None)));
+        let categorizer = Categorizer::new(vec![invoice, receipt, fallback], create_defaults());
+
+        let text = "Sw1ss com Invoice";
+        let document = DocumentView {
+            email_from: Some("a@b.ch"),
+            ..DocumentView::new(text)
+        };
+        let explanation = categorizer.categorize_document(&document).explanation;
+
+        // Higher-priority rules that failed are listed; lower ones were never evaluated
+        let rejected: Vec<&str> = explanation
+            .rejected
+            .iter()
+            .map(|r| r.rule_id.as_str())
+            .collect();
+        assert_eq!(rejected, vec!["receipt"]);
+        assert!(!explanation.rejected[0].matched);
+
+        let winner = explanation.winner.as_ref().unwrap();
+        assert_eq!(winner.rule_id, "invoice");
+        let tree = &winner.condition;
+        assert_eq!(tree.kind, "all");
+        assert!(tree.matched);
+        let any = &tree.children[1];
+        assert_eq!(any.kind, "any");
+        assert!(!any.children[0].matched);
+        assert!(any.children[1].matched);
+
+        // Spans in the default text are reported against the text field
+        let spans = tree.matched_spans();
+        assert_eq!(spans.len(), 2);
+        for span in &spans {
+            assert_eq!(span.field, Some(MatchField::Text));
+            assert_eq!(&text[span.start..span.end], span.text);
+        }
+        assert_eq!(spans[0].text, "Invoice");
+        assert_eq!(spans[1].text, "Sw1ss com");
+    }
+
+    #[test]
+    fn test_explanation_stops_at_deciding_child() {
+        let compound = |all, any| {
+            MatchCondition::Compound(CompoundMatch {
+                all,
+                any,
+                not: None,
+                case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
+            })
+        };
+        let children = || {
+            vec![
+                MatchCondition::Simple(simple(Some("missing"), None)),
+                MatchCondition::Simple(simple(Some("invoice"), None)),
+                MatchCondition::Simple(simple(Some("total"), None)),
+            ]
+        };
+        let mut all = make_rule("all", compound(Some(children()), None));
+        all.priority = 20;
+        let any = make_rule("any", compound(None, Some(children())));
+        let categorizer = Categorizer::new(vec![all, any], create_defaults());
+
+        let explanation = categorizer.categorize("Invoice total").explanation;
+
+        // `all` fails at its first child, `any` matches at its second
+        let all = &explanation.rejected[0].condition;
+        assert_eq!(all.children.len(), 1);
+        assert!(!all.children[0].matched);
+        let any = &explanation.winner.as_ref().unwrap().condition;
+        assert_eq!(any.children.len(), 2);
+        assert!(any.children[1].matched);
+        assert_eq!(any.children[1].spans[0].text, "Invoice");
+    }
+
+    fn trained_classifier() -> Classifier {
+        use crate::db::job_repo::{self, JobRow};
+        use crate::db::Database;
+
+        let db = Database::open_in_memory().unwrap();
+        let classifier = Classifier::open(db.clone()).unwrap();
+        let examples = [
+            ("invoices", "Invoice amount due"),
+            ("invoices", "Invoice payment total"),
+            ("health", "Doctor prescription"),
+            ("health", "Patient prescription"),
+        ];
+        for (i, (category, text)) in examples.iter().enumerate() {
+            let id = format!("job-{}", i);
+            let row = JobRow {
+                id: id.clone(),
+                filename: "test.pdf".to_string(),
+                source_path: "/tmp/test.pdf".to_string(),
+                archive_path: None,
+                output_path: None,
+                category: category.to_string(),
+                source_name: None,
+                status: "completed".to_string(),
+                error: None,
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                updated_at: "2026-01-01T00:00:00Z".to_string(),
+                completed_at: None,
+                symlinks: None,
+                current_phase: None,
+                message: None,
+                mime_type: None,
+                parent_job_id: None,
+                confidence: None,
+                alternatives: None,
+                explanation: None,
+            };
+            job_repo::insert(&db, &row).unwrap();
+            classifier.learn(&id, Some(category), text).unwrap();
+        }
+        classifier
+    }
+
+    #[test]
+    fn test_classifier_suggests_and_files_unmatched_documents() {
+        let mut invoices = make_rule(
+            "invoices",
+            MatchCondition::Simple(simple(Some("Rechnung"), None)),
+        );
+        invoices.output.directory = "$y/invoices".to_string();
+        let mut config = CategorizationConfig::default();
+        config.classifier.min_examples = 2;
+
+        // Suggested only
+        let categorizer = Categorizer::new(vec![invoices.clone()], create_defaults())
+            .with_config(config.clone())
+            .with_classifier(trained_classifier());
+        let result = categorizer.categorize("Invoice amount due");
+        assert_eq!(result.category, "unsorted");
+        assert_eq!(result.confidence, None);
+        let prediction = result.explanation.classifier.unwrap();
+        assert_eq!(prediction.category, "invoices");
+        assert!(!prediction.filed);
+
+        // Filed like the rule of the predicted category
+        config.classifier.auto_file = true;
+        config.classifier.threshold = 0.6;
+        let categorizer = Categorizer::new(vec![invoices], create_defaults())
+            .with_config(config.clone())
+            .with_classifier(trained_classifier());
+        let result = categorizer.categorize("Invoice amount due");
+        assert_eq!(result.category, "invoices");
+        assert_eq!(result.rule_id, None);
+        assert_eq!(result.output.directory, "$y/invoices");
+        assert!(result.explanation.classifier.unwrap().filed);
+
+        // Categories without a rule use the default output
+        let result = categorizer.categorize("Doctor prescription");
+        assert_eq!(result.category, "health");
+        assert_eq!(result.output.directory, "$y/unsorted");
+
+        // Documents a rule matches are never classified
+        let result = categorizer.categorize("Rechnung prescription");
+        assert_eq!(result.rule_id.as_deref(), Some("invoices"));
+        assert_eq!(result.explanation.classifier, None);
+
+        // Below the threshold, or when disabled, nothing is filed
+        config.classifier.threshold = 1.0;
+        let categorizer = Categorizer::new(vec![], create_defaults())
+            .with_config(config.clone())
+            .with_classifier(trained_classifier());
+        assert_eq!(categorizer.categorize("Invoice due").category, "unsorted");
+        config.classifier.enabled = false;
+        let categorizer = Categorizer::new(vec![], create_defaults())
+            .with_config(config)
+            .with_classifier(trained_classifier());
+        assert_eq!(
+            categorizer.categorize("Invoice due").explanation.classifier,
+            None
+        );
+    }
+
+    #[test]
+    fn test_correspondent_condition_and_default_category() {
+        use crate::categorizer::{CorrespondentEvidence, DetectedCorrespondent};
+
+        let helsana = DetectedCorrespondent {
+            id: "helsana".to_string(),
+            name: "Helsana".to_string(),
+            default_category: Some("insurance".to_string()),
+            evidence: CorrespondentEvidence::Name("Helsana".to_string()),
+        };
+        let mut premiums = make_rule(
+            "premiums",
+            MatchCondition::Compound(CompoundMatch {
+                all: Some(vec![
+                    MatchCondition::Simple(SimpleMatch {
+                        correspondent: Some("Helsana".to_string()),
+                        ..simple(None, None)
+                    }),
+                    MatchCondition::Simple(simple(Some("Prämie"), None)),
+                ]),
+                any: None,
+                not: None,
+                case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
+            }),
+        );
+        premiums.category = "premiums".to_string();
+        let mut insurance = make_rule(
+            "insurance",
+            MatchCondition::Simple(simple(Some("Police"), None)),
+        );
+        insurance.category = "insurance".to_string();
+        insurance.output.directory = "$y/insurance".to_string();
+        let categorizer = Categorizer::new(vec![premiums, insurance], create_defaults());
+
+        let document = DocumentView {
+            correspondent: Some(&helsana),
+            ..DocumentView::new("Prämienrechnung 2026")
+        };
+        let result = categorizer.categorize_document(&document);
+        assert_eq!(result.rule_id.as_deref(), Some("premiums"));
+        let trace = &result.explanation.winner.unwrap().condition.children[0];
+        assert_eq!(trace.kind, "correspondent");
+        assert!(trace.matched);
+
+        // Without the correspondent the rule does not match
+        assert_eq!(
+            categorizer.categorize("Prämienrechnung 2026").category,
+            "unsorted"
+        );
+
+        // Unmatched documents fall back to the correspondent's category
+        let document = DocumentView {
+            correspondent: Some(&helsana),
+            ..DocumentView::new("Leistungsabrechnung")
+        };
+        let result = categorizer.categorize_document(&document);
+        assert_eq!(result.rule_id, None);
+        assert_eq!(result.category, "insurance");
+        assert_eq!(result.output.directory, "$y/insurance");
+        assert_eq!(result.explanation.correspondent, Some(helsana));
+    }
 }
@@ -1,3 +1,13 @@
+pub mod classifier;
+pub mod compare;
+pub mod correspondent;
+pub mod document;
+pub mod explain;
+pub mod fuzzy;
 pub mod matcher;
 
-pub use matcher::{CategorizationResult, Categorizer};
+pub use classifier::{Classifier, ClassifierPrediction, NaiveBayes};
+pub use correspondent::{CorrespondentDetector, CorrespondentEvidence, DetectedCorrespondent};
+pub use document::DocumentView;
+pub use explain::{CategorizationExplanation, ConditionTrace, MatchSpan, RuleExplanation};
+pub use matcher::{CategorizationResult, Categorizer, CategoryAlternative};
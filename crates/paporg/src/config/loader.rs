@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use crate::config::schema::Config;
+use crate::config::variables::{computed_order, extract_variable_names, PIPELINE_VARIABLES};
 use crate::error::ConfigError;
 
 const SCHEMA_JSON: &str = include_str!("../../../../schema/config-v1.json");
@@ -59,6 +60,23 @@ fn validate_config(config: &Config) -> Result<(), ConfigError> {
         });
     }
 
+    let names = config
+        .variables
+        .extracted
+        .iter()
+        .map(|var| &var.name)
+        .chain(config.variables.computed.iter().map(|var| &var.name));
+    for name in names {
+        if PIPELINE_VARIABLES.contains(&name.as_str()) {
+            return Err(ConfigError::Validation {
+                message: format!(
+                    "Variable '{}' conflicts with the built-in variable '${}'",
+                    name, name
+                ),
+            });
+        }
+    }
+
     // Validate extracted variable patterns
     for var in &config.variables.extracted {
         if let Err(e) = regex::Regex::new(&var.pattern) {
@@ -82,6 +100,30 @@ fn validate_config(config: &Config) -> Result<(), ConfigError> {
         }
     }
 
+    // Validate computed variables
+    for var in &config.variables.computed {
+        if let Some(Err(e)) = var.pattern.as_deref().map(regex::Regex::new) {
+            return Err(ConfigError::InvalidPattern {
+                name: var.name.clone(),
+                reason: e.to_string(),
+            });
+        }
+    }
+    let references: Vec<(&str, Vec<String>)> = config
+        .variables
+        .computed
+        .iter()
+        .map(|var| (var.name.as_str(), extract_variable_names(&var.template)))
+        .collect();
+    if let Some(cycle) = computed_order(&references).1.first() {
+        return Err(ConfigError::Validation {
+            message: format!(
+                "Computed variables reference each other in a cycle: {}",
+                cycle.join(" -> ")
+            ),
+        });
+    }
+
     // Validate rules
     let mut rule_ids = std::collections::HashSet::new();
     for rule in &config.rules {
@@ -347,4 +389,30 @@ mod tests {
         let result = load_config_from_str(config_json);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_pipeline_variable_names_are_reserved() {
+        for name in PIPELINE_VARIABLES {
+            let config_json = format!(
+                r#"
+                {{
+                    "version": "1.0",
+                    "input_directory": "/input",
+                    "output_directory": "/output",
+                    "variables": {{
+                        "computed": [{{ "name": "{}", "template": "$y" }}]
+                    }},
+                    "rules": [],
+                    "defaults": {{
+                        "output": {{ "directory": "$y/unsorted", "filename": "$original" }}
+                    }}
+                }}
+                "#,
+                name
+            );
+
+            let error = load_config_from_str(&config_json).unwrap_err();
+            assert!(error.to_string().contains("conflicts with the built-in"));
+        }
+    }
 }
@@ -4,7 +4,8 @@ pub mod variables;
 
 pub use loader::{load_config, load_config_from_str};
 pub use schema::{
-    Config, DefaultsConfig, DocumentFormat, DocumentMetadata, ExtractedVariable, MatchCondition,
-    OcrConfig, OutputConfig, Rule, SymlinkConfig, VariablesConfig,
+    CategorizationConfig, CategorizationMode, CompareValue, Config, DefaultsConfig, DocumentFormat,
+    DocumentMetadata, ExtractedVariable, FuzzyMatch, LimitsConfig, MatchCondition, MatchField,
+    OcrConfig, OutputConfig, Rule, SymlinkConfig, VariableMatch, VariablesConfig,
 };
 pub use variables::VariableEngine;
@@ -18,6 +18,14 @@ pub struct Config {
     pub defaults: DefaultsConfig,
     #[serde(default)]
     pub ai: AiConfig,
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    #[serde(default)]
+    pub categorization: CategorizationConfig,
+    #[serde(default)]
+    pub correspondents: Vec<Correspondent>,
+    #[serde(default)]
+    pub lookup_tables: Vec<LookupTable>,
 }
 
 fn default_worker_count() -> usize {
@@ -56,10 +64,156 @@ impl Default for OcrConfig {
     }
 }
 
+/// Per-document resource limits. A value of 0 disables the limit; a missing
+/// value takes the default, so configurations without limits are limited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitsConfig {
+    #[serde(default = "default_max_file_size_mb")]
+    pub max_file_size_mb: u64,
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+    #[serde(default = "default_job_timeout_secs")]
+    pub job_timeout_secs: u64,
+    #[serde(default = "default_ocr_page_timeout_secs")]
+    pub ocr_page_timeout_secs: u64,
+    #[serde(default = "default_max_archive_entries")]
+    pub max_archive_entries: usize,
+    #[serde(default = "default_max_archive_size_mb")]
+    pub max_archive_size_mb: u64,
+    #[serde(default = "default_max_archive_depth")]
+    pub max_archive_depth: u32,
+}
+
+fn default_max_file_size_mb() -> u64 {
+    100
+}
+
+fn default_max_pages() -> usize {
+    500
+}
+
+fn default_job_timeout_secs() -> u64 {
+    900
+}
+
+fn default_ocr_page_timeout_secs() -> u64 {
+    120
+}
+
+fn default_max_archive_entries() -> usize {
+    1000
+}
+
+fn default_max_archive_size_mb() -> u64 {
+    500
+}
+
+fn default_max_archive_depth() -> u32 {
+    3
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size_mb: default_max_file_size_mb(),
+            max_pages: default_max_pages(),
+            job_timeout_secs: default_job_timeout_secs(),
+            ocr_page_timeout_secs: default_ocr_page_timeout_secs(),
+            max_archive_entries: default_max_archive_entries(),
+            max_archive_size_mb: default_max_archive_size_mb(),
+            max_archive_depth: default_max_archive_depth(),
+        }
+    }
+}
+
+/// How a document's rule is chosen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategorizationConfig {
+    #[serde(default)]
+    pub mode: CategorizationMode,
+    /// Lowest score, between 0 and 1, that a rule needs to win in scoring mode.
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+    /// Number of runner-up rules reported in scoring mode.
+    #[serde(default = "default_alternatives")]
+    pub alternatives: usize,
+    /// Statistical fallback for documents no rule matches.
+    #[serde(default)]
+    pub classifier: ClassifierConfig,
+}
+
+/// Naive Bayes classifier trained on previously filed documents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassifierConfig {
+    /// Predict a category for documents no rule matches.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// File documents under the predicted category instead of only suggesting it.
+    #[serde(default)]
+    pub auto_file: bool,
+    /// Lowest confidence, between 0 and 1, a prediction needs to be filed.
+    #[serde(default = "default_classifier_threshold")]
+    pub threshold: f64,
+    /// Fewest training documents a category needs before it is predicted.
+    #[serde(default = "default_min_examples")]
+    pub min_examples: usize,
+}
+
+fn default_classifier_threshold() -> f64 {
+    0.9
+}
+
+fn default_min_examples() -> usize {
+    5
+}
+
+impl Default for ClassifierConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_file: false,
+            threshold: default_classifier_threshold(),
+            min_examples: default_min_examples(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CategorizationMode {
+    /// The first matching rule in priority order wins.
+    #[default]
+    #[serde(rename = "firstMatch")]
+    FirstMatch,
+    /// Every rule is scored from its weighted conditions and the best one wins.
+    #[serde(rename = "scoring")]
+    Scoring,
+}
+
+fn default_threshold() -> f64 {
+    0.5
+}
+
+fn default_alternatives() -> usize {
+    3
+}
+
+impl Default for CategorizationConfig {
+    fn default() -> Self {
+        Self {
+            mode: CategorizationMode::default(),
+            threshold: default_threshold(),
+            alternatives: default_alternatives(),
+            classifier: ClassifierConfig::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct VariablesConfig {
     #[serde(default)]
     pub extracted: Vec<ExtractedVariable>,
+    #[serde(default)]
+    pub computed: Vec<ComputedVariable>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +224,45 @@ pub struct ExtractedVariable {
     pub transform: Option<VariableTransform>,
     #[serde(default)]
     pub default: Option<String>,
+    #[serde(default)]
+    pub pages: Option<PageSelection>,
+    #[serde(default)]
+    pub region: Option<PageRegion>,
+    /// The input the pattern is searched in; the document text by default.
+    #[serde(default)]
+    pub from: Option<VariableSource>,
+}
+
+/// A variable built from other variables after extraction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComputedVariable {
+    pub name: String,
+    /// Text with `$name` references to other variables.
+    pub template: String,
+    /// Searched in the computed text; the named group becomes the value.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub transform: Option<VariableTransform>,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// The input of a document a variable pattern is searched in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VariableSource {
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "filename")]
+    Filename,
+    #[serde(rename = "sourcePath")]
+    SourcePath,
+    #[serde(rename = "email.subject")]
+    EmailSubject,
+    #[serde(rename = "email.from")]
+    EmailFrom,
+    #[serde(rename = "pdfMetadata")]
+    PdfMetadata,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +272,53 @@ pub enum VariableTransform {
     Uppercase,
     Lowercase,
     Trim,
+    /// Maps the value through the lookup table of this name.
+    Lookup(String),
+}
+
+/// Maps extracted values to canonical ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookupTable {
+    pub name: String,
+    #[serde(default)]
+    pub mode: LookupMode,
+    #[serde(default)]
+    pub entries: Vec<LookupEntry>,
+    /// Value for keys no entry matches.
+    #[serde(default)]
+    pub fallback: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LookupMode {
+    #[default]
+    Exact,
+    Regex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookupEntry {
+    pub key: String,
+    pub value: String,
+}
+
+/// A known sender of documents and the details that identify it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Correspondent {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub ibans: Vec<String>,
+    #[serde(default)]
+    pub vat_numbers: Vec<String>,
+    #[serde(default)]
+    pub email_domains: Vec<String>,
+    /// Category for documents no rule matches.
+    #[serde(default)]
+    pub default_category: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,9 +327,14 @@ pub struct Rule {
     pub name: String,
     #[serde(default)]
     pub priority: i32,
+    /// Exclusive rules compete for the document's category. Other rules add
+    /// their category as a tag, and their symlinks, whenever they match.
+    #[serde(default = "default_true")]
+    pub exclusive: bool,
     #[serde(rename = "match")]
     pub match_condition: MatchCondition,
     pub category: String,
+    #[serde(default)]
     pub output: OutputConfig,
     #[serde(default)]
     pub symlinks: Vec<SymlinkConfig>,
@@ -141,9 +386,17 @@ pub struct CompoundMatch {
     pub not: Option<Box<MatchCondition>>,
     #[serde(rename = "caseSensitive", default)]
     pub case_sensitive: Option<bool>,
+    #[serde(default)]
+    pub field: Option<MatchField>,
+    #[serde(default)]
+    pub pages: Option<PageSelection>,
+    #[serde(default)]
+    pub region: Option<PageRegion>,
+    #[serde(default)]
+    pub weight: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SimpleMatch {
     #[serde(default)]
     pub contains: Option<String>,
@@ -153,11 +406,112 @@ pub struct SimpleMatch {
     pub contains_all: Option<Vec<String>>,
     #[serde(default)]
     pub pattern: Option<String>,
+    #[serde(default)]
+    pub fuzzy: Option<FuzzyMatch>,
+    #[serde(rename = "containsAnyFuzzy", default)]
+    pub contains_any_fuzzy: Option<Vec<String>>,
+    #[serde(default)]
+    pub variable: Option<Box<VariableMatch>>,
+    /// Matches documents detected as coming from the correspondent with this id.
+    #[serde(default)]
+    pub correspondent: Option<String>,
     #[serde(rename = "caseSensitive", default)]
     pub case_sensitive: Option<bool>,
+    #[serde(default)]
+    pub field: Option<MatchField>,
+    #[serde(default)]
+    pub pages: Option<PageSelection>,
+    #[serde(default)]
+    pub region: Option<PageRegion>,
+    #[serde(default)]
+    pub weight: Option<f64>,
 }
 
+/// Comparison against an extracted variable. Every operator that is set must
+/// hold. Values compare as dates or numbers when both sides parse as one, and
+/// as text otherwise.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariableMatch {
+    pub name: String,
+    #[serde(default)]
+    pub eq: Option<CompareValue>,
+    #[serde(default)]
+    pub gt: Option<CompareValue>,
+    #[serde(default)]
+    pub lt: Option<CompareValue>,
+    /// Inclusive lower and upper bound.
+    #[serde(default)]
+    pub between: Option<[CompareValue; 2]>,
+    #[serde(default)]
+    pub exists: Option<bool>,
+    #[serde(rename = "in", default)]
+    pub one_of: Option<Vec<CompareValue>>,
+}
+
+/// A value a variable is compared with, written as a YAML number or string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CompareValue {
+    Number(f64),
+    Text(String),
+}
+
+/// The part of a document a match condition is evaluated against. Conditions
+/// without a field search the extracted text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MatchField {
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "filename")]
+    Filename,
+    #[serde(rename = "source")]
+    Source,
+    #[serde(rename = "mimeType")]
+    MimeType,
+    #[serde(rename = "email.from")]
+    EmailFrom,
+    #[serde(rename = "email.subject")]
+    EmailSubject,
+    #[serde(rename = "email.to")]
+    EmailTo,
+    #[serde(rename = "firstPage")]
+    FirstPage,
+}
+
+/// The pages of the extracted text a condition or variable pattern is
+/// limited to: `first`, `last` or a list of page numbers starting at 1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PageSelection {
+    Position(PagePosition),
+    Numbers(Vec<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PagePosition {
+    First,
+    Last,
+}
+
+/// The top or bottom lines of each selected page. Blank lines are not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageRegion {
+    Top(usize),
+    Bottom(usize),
+}
+
+/// OCR-tolerant containment check. Always case-insensitive; without
+/// `maxDistance` the allowed edit distance grows with the value's length.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzyMatch {
+    pub value: String,
+    #[serde(rename = "maxDistance", default)]
+    pub max_distance: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OutputConfig {
     pub directory: String,
     pub filename: String,
@@ -259,12 +613,15 @@ impl Default for AiConfig {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DocumentFormat {
     Pdf,
     Docx,
     Text,
     Image,
+    Html,
+    Rtf,
+    Odt,
 }
 
 impl DocumentFormat {
@@ -273,7 +630,11 @@ impl DocumentFormat {
             "pdf" => Some(Self::Pdf),
             "docx" => Some(Self::Docx),
             "txt" | "text" | "md" => Some(Self::Text),
-            "png" | "jpg" | "jpeg" | "tiff" | "tif" | "bmp" | "gif" | "webp" => Some(Self::Image),
+            "png" | "jpg" | "jpeg" | "tiff" | "tif" | "bmp" | "gif" | "webp" | "heic" | "heif"
+            | "avif" => Some(Self::Image),
+            "html" | "htm" | "xhtml" => Some(Self::Html),
+            "rtf" => Some(Self::Rtf),
+            "odt" => Some(Self::Odt),
             _ => None,
         }
     }
@@ -284,16 +645,22 @@ impl DocumentFormat {
             Self::Docx => "docx",
             Self::Text => "txt",
             Self::Image => "png",
+            Self::Html => "html",
+            Self::Rtf => "rtf",
+            Self::Odt => "odt",
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentMetadata {
     pub original_filename: String,
     pub format: DocumentFormat,
     pub extracted_variables: HashMap<String, String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// The document information of a PDF (Title, Author, ...) as
+    /// `Key: value` lines.
+    pub pdf_metadata: Option<String>,
 }
 
 impl DocumentMetadata {
@@ -303,6 +670,7 @@ impl DocumentMetadata {
             format,
             extracted_variables: HashMap::new(),
             created_at: chrono::Utc::now(),
+            pdf_metadata: None,
         }
     }
 }
@@ -393,6 +761,18 @@ mod tests {
             DocumentFormat::from_extension("webp"),
             Some(DocumentFormat::Image)
         );
+        assert_eq!(
+            DocumentFormat::from_extension("heic"),
+            Some(DocumentFormat::Image)
+        );
+        assert_eq!(
+            DocumentFormat::from_extension("HEIF"),
+            Some(DocumentFormat::Image)
+        );
+        assert_eq!(
+            DocumentFormat::from_extension("avif"),
+            Some(DocumentFormat::Image)
+        );
     }
 
     #[test]
@@ -423,7 +803,6 @@ mod tests {
     fn test_from_extension_unknown() {
         assert_eq!(DocumentFormat::from_extension("xyz"), None);
         assert_eq!(DocumentFormat::from_extension("doc"), None); // Not docx
-        assert_eq!(DocumentFormat::from_extension("html"), None);
         assert_eq!(DocumentFormat::from_extension("csv"), None);
         assert_eq!(DocumentFormat::from_extension(""), None);
     }
@@ -434,6 +813,29 @@ mod tests {
         assert_eq!(DocumentFormat::Docx.extension(), "docx");
         assert_eq!(DocumentFormat::Text.extension(), "txt");
         assert_eq!(DocumentFormat::Image.extension(), "png");
+        assert_eq!(DocumentFormat::Html.extension(), "html");
+        assert_eq!(DocumentFormat::Rtf.extension(), "rtf");
+        assert_eq!(DocumentFormat::Odt.extension(), "odt");
+    }
+
+    #[test]
+    fn test_from_extension_markup_formats() {
+        assert_eq!(
+            DocumentFormat::from_extension("HTM"),
+            Some(DocumentFormat::Html)
+        );
+        assert_eq!(
+            DocumentFormat::from_extension("xhtml"),
+            Some(DocumentFormat::Html)
+        );
+        assert_eq!(
+            DocumentFormat::from_extension("rtf"),
+            Some(DocumentFormat::Rtf)
+        );
+        assert_eq!(
+            DocumentFormat::from_extension("odt"),
+            Some(DocumentFormat::Odt)
+        );
     }
 
     #[test]
@@ -1,12 +1,31 @@
 use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::LazyLock;
 
 use chrono::{Datelike, Timelike, Utc};
 use regex::Regex;
 
-use crate::config::schema::{ExtractedVariable, VariableTransform};
+use crate::categorizer::document::DocumentView;
+use crate::config::schema::{
+    ComputedVariable, ExtractedVariable, LookupMode, LookupTable, PageRegion, PageSelection,
+    VariableSource, VariableTransform,
+};
+use crate::processor::pages::ScopedText;
+
+// Pre-compiled regex for extracting variable names from templates
+static RE_VARIABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$([a-zA-Z_][a-zA-Z0-9_]*)").unwrap());
+
+/// Variables the pipeline sets after extraction, from the detected
+/// correspondent and the matching rules. Variables of the same name would be
+/// replaced, so they are rejected when the config is loaded.
+pub const PIPELINE_VARIABLES: &[&str] = &["correspondent", "tags"];
 
 pub struct VariableEngine {
     extracted_patterns: Vec<CompiledPattern>,
+    /// In evaluation order.
+    computed: Vec<CompiledComputed>,
+    lookup_tables: HashMap<String, CompiledLookup>,
 }
 
 struct CompiledPattern {
@@ -14,6 +33,28 @@ struct CompiledPattern {
     regex: Regex,
     transform: Option<VariableTransform>,
     default: Option<String>,
+    pages: Option<PageSelection>,
+    region: Option<PageRegion>,
+    from: Option<VariableSource>,
+}
+
+struct CompiledComputed {
+    name: String,
+    template: String,
+    regex: Option<Regex>,
+    transform: Option<VariableTransform>,
+    default: Option<String>,
+}
+
+struct CompiledLookup {
+    entries: Vec<(LookupKey, String)>,
+    fallback: Option<String>,
+}
+
+enum LookupKey {
+    /// Normalized with `normalize_lookup_key`.
+    Exact(String),
+    Pattern(Regex),
 }
 
 impl VariableEngine {
@@ -26,23 +67,117 @@ impl VariableEngine {
                     regex,
                     transform: var.transform.clone(),
                     default: var.default.clone(),
+                    pages: var.pages.clone(),
+                    region: var.region,
+                    from: var.from,
                 })
             })
             .collect();
 
-        Self { extracted_patterns }
+        Self {
+            extracted_patterns,
+            computed: Vec::new(),
+            lookup_tables: HashMap::new(),
+        }
+    }
+
+    /// Sets the variables computed from other variables after extraction.
+    /// Variables in a reference cycle or with a pattern that fails to
+    /// compile are skipped.
+    pub fn with_computed_variables(mut self, computed: &[ComputedVariable]) -> Self {
+        let references: Vec<(&str, Vec<String>)> = computed
+            .iter()
+            .map(|var| (var.name.as_str(), extract_variable_names(&var.template)))
+            .collect();
+        let (order, _) = computed_order(&references);
+
+        self.computed = order
+            .into_iter()
+            .map(|i| &computed[i])
+            .filter_map(|var| {
+                let regex = match &var.pattern {
+                    Some(pattern) => Some(Regex::new(pattern).ok()?),
+                    None => None,
+                };
+                Some(CompiledComputed {
+                    name: var.name.clone(),
+                    template: var.template.clone(),
+                    regex,
+                    transform: var.transform.clone(),
+                    default: var.default.clone(),
+                })
+            })
+            .collect();
+        self
+    }
+
+    /// Sets the tables used by `lookup` transforms. Regex keys that fail
+    /// to compile are skipped.
+    pub fn with_lookup_tables(mut self, tables: &[LookupTable]) -> Self {
+        self.lookup_tables = tables
+            .iter()
+            .map(|table| {
+                let entries = table
+                    .entries
+                    .iter()
+                    .filter_map(|entry| {
+                        let key = match table.mode {
+                            LookupMode::Exact => LookupKey::Exact(normalize_lookup_key(&entry.key)),
+                            LookupMode::Regex => {
+                                LookupKey::Pattern(Regex::new(&format!("(?i){}", entry.key)).ok()?)
+                            }
+                        };
+                        Some((key, entry.value.clone()))
+                    })
+                    .collect();
+                let lookup = CompiledLookup {
+                    entries,
+                    fallback: table.fallback.clone(),
+                };
+                (table.name.clone(), lookup)
+            })
+            .collect();
+        self
     }
 
     pub fn extract_variables(&self, text: &str) -> HashMap<String, String> {
+        self.extract_document_variables(&DocumentView::new(text))
+    }
+
+    /// Extracts variables from a document. Patterns with a `from` search
+    /// that input, where missing metadata reads as empty text. The others
+    /// search the extracted text, or the pages or page region they are
+    /// limited to. Computed variables
+    /// are evaluated last.
+    pub fn extract_document_variables(&self, document: &DocumentView) -> HashMap<String, String> {
         let mut variables = HashMap::new();
 
         for pattern in &self.extracted_patterns {
-            if let Some(caps) = pattern.regex.captures(text) {
+            let scoped = (pattern.pages.is_some() || pattern.region.is_some()).then(|| {
+                ScopedText::new(
+                    document.text,
+                    document.pages,
+                    pattern.pages.as_ref(),
+                    pattern.region,
+                )
+            });
+            let haystack = match pattern.from {
+                None | Some(VariableSource::Text) => {
+                    scoped.as_ref().map_or(document.text, ScopedText::text)
+                }
+                Some(VariableSource::Filename) => document.filename.unwrap_or_default(),
+                Some(VariableSource::SourcePath) => document.source_path.unwrap_or_default(),
+                Some(VariableSource::EmailSubject) => document.email_subject.unwrap_or_default(),
+                Some(VariableSource::EmailFrom) => document.email_from.unwrap_or_default(),
+                Some(VariableSource::PdfMetadata) => document.pdf_metadata.unwrap_or_default(),
+            };
+
+            if let Some(caps) = pattern.regex.captures(haystack) {
                 if let Some(matched) = caps.name(&pattern.name) {
                     let mut value = matched.as_str().to_string();
 
                     if let Some(transform) = &pattern.transform {
-                        value = apply_transform(&value, transform);
+                        value = self.apply_transform(&value, transform);
                     }
 
                     variables.insert(pattern.name.clone(), value);
@@ -52,9 +187,47 @@ impl VariableEngine {
             }
         }
 
+        self.compute_variables(&mut variables, document.filename.unwrap_or_default());
         variables
     }
 
+    /// Evaluates the computed variables in order, so each sees the ones
+    /// before it. Templates may also reference built-in variables.
+    fn compute_variables(&self, variables: &mut HashMap<String, String>, filename: &str) {
+        if self.computed.is_empty() {
+            return;
+        }
+        let builtins = self.get_builtin_variables(filename, &Utc::now());
+
+        for computed in &self.computed {
+            let rendered = render_template(&computed.template, |name| {
+                variables.get(name).or_else(|| builtins.get(name)).cloned()
+            });
+            let value = match (rendered, &computed.regex) {
+                (Some(text), Some(regex)) => regex
+                    .captures(&text)
+                    .and_then(|caps| caps.name(&computed.name))
+                    .map(|matched| matched.as_str().to_string()),
+                (rendered, None) => rendered,
+                (None, Some(_)) => None,
+            };
+
+            match value {
+                Some(mut value) => {
+                    if let Some(transform) = &computed.transform {
+                        value = self.apply_transform(&value, transform);
+                    }
+                    variables.insert(computed.name.clone(), value);
+                }
+                None => {
+                    if let Some(default) = &computed.default {
+                        variables.insert(computed.name.clone(), default.clone());
+                    }
+                }
+            }
+        }
+    }
+
     pub fn substitute(
         &self,
         template: &str,
@@ -109,15 +282,166 @@ impl VariableEngine {
 
         vars
     }
+
+    fn apply_transform(&self, value: &str, transform: &VariableTransform) -> String {
+        match transform {
+            VariableTransform::Slugify => slugify(value),
+            VariableTransform::Uppercase => value.to_uppercase(),
+            VariableTransform::Lowercase => value.to_lowercase(),
+            VariableTransform::Trim => value.trim().to_string(),
+            VariableTransform::Lookup(table) => self.lookup(table, value),
+        }
+    }
+
+    /// Returns the value of the first entry whose key matches, else the
+    /// table's fallback. Values of unknown tables are kept as they are.
+    fn lookup(&self, table: &str, value: &str) -> String {
+        let Some(table) = self.lookup_tables.get(table) else {
+            return value.to_string();
+        };
+
+        let normalized = normalize_lookup_key(value);
+        table
+            .entries
+            .iter()
+            .find(|(key, _)| match key {
+                LookupKey::Exact(key) => *key == normalized,
+                LookupKey::Pattern(regex) => regex.is_match(value),
+            })
+            .map(|(_, mapped)| mapped)
+            .or(table.fallback.as_ref())
+            .map_or_else(|| value.to_string(), Clone::clone)
+    }
+}
+
+/// Extracts variable names from a template string.
+///
+/// Variable names start with `$` followed by an identifier. When variables
+/// are concatenated with underscores (e.g., `$day_$original`), we need to
+/// properly split them. The underscore before a `$` belongs to the separator,
+/// not the variable name.
+pub fn extract_variable_names(template: &str) -> Vec<String> {
+    variable_references(template)
+        .map(|(_, name)| name.to_string())
+        .collect()
 }
 
-fn apply_transform(value: &str, transform: &VariableTransform) -> String {
-    match transform {
-        VariableTransform::Slugify => slugify(value),
-        VariableTransform::Uppercase => value.to_uppercase(),
-        VariableTransform::Lowercase => value.to_lowercase(),
-        VariableTransform::Trim => value.trim().to_string(),
+/// Returns the byte range and name of each `$name` reference in a template.
+fn variable_references(template: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    RE_VARIABLE.captures_iter(template).map(move |cap| {
+        let name = cap.get(1).unwrap();
+        let mut range = cap.get(0).unwrap().range();
+        // If the match ends with underscore and is followed by $ in the original,
+        // strip the trailing underscore (it's a separator, not part of the variable name)
+        if name.as_str().ends_with('_') && template[range.end..].starts_with('$') {
+            range.end -= 1;
+        }
+        (range.clone(), &template[range.start + 1..range.end])
+    })
+}
+
+/// Replaces the `$name` references of a template with their values, or
+/// returns `None` if one of them has no value.
+fn render_template(template: &str, value_of: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let mut rendered = String::new();
+    let mut last = 0;
+    for (range, name) in variable_references(template) {
+        rendered.push_str(&template[last..range.start]);
+        rendered.push_str(&value_of(name)?);
+        last = range.end;
     }
+    rendered.push_str(&template[last..]);
+    Some(rendered)
+}
+
+/// Orders computed variables so each comes after the computed variables
+/// its template references. `variables` holds each name with its
+/// references. Returns the evaluation order as indices into `variables`,
+/// and each cycle as the chain of names around it; variables in or
+/// depending on a cycle are left out of the order.
+pub fn computed_order(variables: &[(&str, Vec<String>)]) -> (Vec<usize>, Vec<Vec<String>>) {
+    let mut ordering = Ordering {
+        variables,
+        index: variables
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (*name, i))
+            .collect(),
+        visits: vec![Visit::New; variables.len()],
+        stack: Vec::new(),
+        order: Vec::new(),
+        cycles: Vec::new(),
+    };
+    for i in 0..variables.len() {
+        ordering.visit(i);
+    }
+    (ordering.order, ordering.cycles)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    /// Its references are being visited.
+    Active,
+    Ordered,
+    /// In or depending on a cycle.
+    Failed,
+}
+
+struct Ordering<'a> {
+    variables: &'a [(&'a str, Vec<String>)],
+    index: HashMap<&'a str, usize>,
+    visits: Vec<Visit>,
+    stack: Vec<usize>,
+    order: Vec<usize>,
+    cycles: Vec<Vec<String>>,
+}
+
+impl Ordering<'_> {
+    /// Orders a variable after its references; returns false if it is in
+    /// or depends on a cycle.
+    fn visit(&mut self, i: usize) -> bool {
+        match self.visits[i] {
+            Visit::Ordered => return true,
+            Visit::Failed => return false,
+            Visit::Active => {
+                let start = self.stack.iter().position(|&j| j == i).unwrap_or(0);
+                let chain = self.stack[start..]
+                    .iter()
+                    .chain(std::iter::once(&i))
+                    .map(|&j| self.variables[j].0.to_string())
+                    .collect();
+                self.cycles.push(chain);
+                return false;
+            }
+            Visit::New => {}
+        }
+
+        self.visits[i] = Visit::Active;
+        self.stack.push(i);
+        let mut ok = true;
+        for reference in &self.variables[i].1 {
+            if let Some(&j) = self.index.get(reference.as_str()) {
+                ok &= self.visit(j);
+            }
+        }
+        self.stack.pop();
+
+        self.visits[i] = if ok { Visit::Ordered } else { Visit::Failed };
+        if ok {
+            self.order.push(i);
+        }
+        ok
+    }
+}
+
+/// Lowercases and collapses whitespace, so "SBB  cff ffs " equals "SBB CFF FFS".
+fn normalize_lookup_key(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
 }
 
 fn slugify(value: &str) -> String {
@@ -149,6 +473,8 @@ fn sanitize_filename(name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::schema::PagePosition;
+    use crate::processor::pages::join_pages;
 
     #[test]
     fn test_extract_variables() {
@@ -157,6 +483,9 @@ mod tests {
             pattern: r"(?i)from[:\s]+(?P<vendor>[A-Za-z]+)".to_string(),
             transform: None,
             default: None,
+            pages: None,
+            region: None,
+            from: None,
         }];
 
         let engine = VariableEngine::new(&extracted);
@@ -165,6 +494,105 @@ mod tests {
         assert_eq!(vars.get("vendor"), Some(&"Acme".to_string()));
     }
 
+    #[test]
+    fn test_extract_document_variables_by_page() {
+        let extracted = vec![
+            ExtractedVariable {
+                name: "sender".to_string(),
+                pattern: r"(?m)^(?P<sender>.+ GmbH)$".to_string(),
+                transform: None,
+                default: None,
+                pages: Some(PageSelection::Numbers(vec![1])),
+                region: Some(PageRegion::Top(1)),
+                from: None,
+            },
+            ExtractedVariable {
+                name: "total".to_string(),
+                pattern: r"Total (?P<total>\d+)".to_string(),
+                transform: None,
+                default: None,
+                pages: Some(PageSelection::Position(PagePosition::Last)),
+                region: None,
+                from: None,
+            },
+            ExtractedVariable {
+                name: "from".to_string(),
+                pattern: r"From: (?P<from>\S+)".to_string(),
+                transform: None,
+                default: None,
+                pages: None,
+                region: None,
+                from: None,
+            },
+        ];
+        let (text, pages) = join_pages(&[
+            "Acme GmbH\nTotal 10 (previous)\nSee Other GmbH",
+            "Total 120\n",
+        ]);
+        let engine = VariableEngine::new(&extracted);
+        let vars = engine.extract_document_variables(&DocumentView {
+            email_from: Some("billing@acme.ch"),
+            text: &text,
+            pages: &pages,
+            ..Default::default()
+        });
+
+        assert_eq!(vars.get("sender"), Some(&"Acme GmbH".to_string()));
+        assert_eq!(vars.get("total"), Some(&"120".to_string()));
+        // Email headers are only searched with `from: email.from`
+        assert_eq!(vars.get("from"), None);
+    }
+
+    #[test]
+    fn test_extract_variables_from_metadata() {
+        let variable = |name: &str, pattern: &str, from: VariableSource| ExtractedVariable {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            transform: None,
+            default: Some("unknown".to_string()),
+            pages: None,
+            region: None,
+            from: Some(from),
+        };
+        let extracted = vec![
+            variable("policy", r"^(?P<policy>\d+)_", VariableSource::Filename),
+            variable(
+                "insurer",
+                r"^(?P<insurer>[^/]+)/",
+                VariableSource::SourcePath,
+            ),
+            variable(
+                "order",
+                r"Order (?P<order>\d+)",
+                VariableSource::EmailSubject,
+            ),
+            variable(
+                "author",
+                r"(?m)^Author: (?P<author>.+)$",
+                VariableSource::PdfMetadata,
+            ),
+            variable("sender", r"(?P<sender>\S+@\S+)", VariableSource::EmailFrom),
+        ];
+        let engine = VariableEngine::new(&extracted);
+
+        let document = DocumentView {
+            filename: Some("4711_policy.pdf"),
+            source_path: Some("Helsana/2024/4711_policy.pdf"),
+            email_subject: Some("Order 123 confirmed"),
+            pdf_metadata: Some("Title: Policy\nAuthor: Helsana AG"),
+            // The text mentions other values that must not be picked up
+            ..DocumentView::new("Order 999 by Someone\nAuthor: Nobody\n0815_")
+        };
+        let vars = engine.extract_document_variables(&document);
+
+        assert_eq!(vars.get("policy"), Some(&"4711".to_string()));
+        assert_eq!(vars.get("insurer"), Some(&"Helsana".to_string()));
+        assert_eq!(vars.get("order"), Some(&"123".to_string()));
+        assert_eq!(vars.get("author"), Some(&"Helsana AG".to_string()));
+        // Missing metadata falls back to the default
+        assert_eq!(vars.get("sender"), Some(&"unknown".to_string()));
+    }
+
     #[test]
     fn test_extract_variables_with_transform() {
         let extracted = vec![ExtractedVariable {
@@ -172,6 +600,9 @@ mod tests {
             pattern: r"(?i)from[:\s]+(?P<vendor>[A-Za-z\s]+?)(?:\s+Corporation|\s*$)".to_string(),
             transform: Some(VariableTransform::Slugify),
             default: None,
+            pages: None,
+            region: None,
+            from: None,
         }];
 
         let engine = VariableEngine::new(&extracted);
@@ -187,6 +618,9 @@ mod tests {
             pattern: r"(?i)from[:\s]+(?P<vendor>[A-Za-z]+)".to_string(),
             transform: None,
             default: Some("unknown".to_string()),
+            pages: None,
+            region: None,
+            from: None,
         }];
 
         let engine = VariableEngine::new(&extracted);
@@ -244,24 +678,177 @@ mod tests {
 
     #[test]
     fn test_apply_transforms() {
+        let engine = VariableEngine::new(&[]);
         assert_eq!(
-            apply_transform("Hello World", &VariableTransform::Uppercase),
+            engine.apply_transform("Hello World", &VariableTransform::Uppercase),
             "HELLO WORLD"
         );
         assert_eq!(
-            apply_transform("Hello World", &VariableTransform::Lowercase),
+            engine.apply_transform("Hello World", &VariableTransform::Lowercase),
             "hello world"
         );
         assert_eq!(
-            apply_transform("  trimmed  ", &VariableTransform::Trim),
+            engine.apply_transform("  trimmed  ", &VariableTransform::Trim),
             "trimmed"
         );
         assert_eq!(
-            apply_transform("Hello World", &VariableTransform::Slugify),
+            engine.apply_transform("Hello World", &VariableTransform::Slugify),
             "hello-world"
         );
     }
 
+    fn computed(name: &str, template: &str, pattern: Option<&str>) -> ComputedVariable {
+        ComputedVariable {
+            name: name.to_string(),
+            template: template.to_string(),
+            pattern: pattern.map(str::to_string),
+            transform: None,
+            default: None,
+        }
+    }
+
+    #[test]
+    fn test_computed_variables() {
+        let extract = |name: &str, pattern: &str| ExtractedVariable {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            transform: None,
+            default: None,
+            pages: None,
+            region: None,
+            from: None,
+        };
+        let extracted = vec![
+            extract("doc_y", r"Date: \d+\.\d+\.(?P<doc_y>\d{4})"),
+            extract("quarter", r"Q(?P<quarter>[1-4])"),
+            extract("vendor", r"From: (?P<vendor>.+)"),
+            extract("missing", r"Policy (?P<missing>\d+)"),
+        ];
+        let mut vendor_slug = computed("vendor_slug", "$vendor_short", None);
+        vendor_slug.transform = Some(VariableTransform::Slugify);
+        let mut policy = computed("policy", "P-$missing", None);
+        policy.default = Some("none".to_string());
+        // Declared before the variable it references
+        let computed_vars = vec![
+            vendor_slug,
+            computed("period", "$doc_y-Q$quarter", None),
+            computed("vendor_short", "$vendor", Some(r"^(?P<vendor_short>\S+)")),
+            policy,
+            computed("unset", "$missing", None),
+        ];
+        let engine = VariableEngine::new(&extracted).with_computed_variables(&computed_vars);
+
+        let vars = engine.extract_variables("Date: 12.05.2024 Q2\nFrom: Acme Trading AG");
+        assert_eq!(vars.get("period"), Some(&"2024-Q2".to_string()));
+        assert_eq!(vars.get("vendor_short"), Some(&"Acme".to_string()));
+        assert_eq!(vars.get("vendor_slug"), Some(&"acme".to_string()));
+        // A missing reference falls back to the default, or leaves it unset
+        assert_eq!(vars.get("policy"), Some(&"none".to_string()));
+        assert_eq!(vars.get("unset"), None);
+    }
+
+    #[test]
+    fn test_computed_variables_with_builtins_and_cycles() {
+        let engine = VariableEngine::new(&[]).with_computed_variables(&[
+            computed("name", "$original-copy", None),
+            computed("a", "$b", None),
+            computed("b", "$a", None),
+        ]);
+        let vars = engine.extract_document_variables(&DocumentView {
+            filename: Some("scan.pdf"),
+            ..DocumentView::new("")
+        });
+        assert_eq!(vars.get("name"), Some(&"scan-copy".to_string()));
+        assert_eq!(vars.get("a"), None);
+
+        let references = [
+            ("a", vec!["b".to_string()]),
+            ("b", vec!["c".to_string(), "y".to_string()]),
+            ("c", vec!["a".to_string()]),
+            ("d", vec!["a".to_string()]),
+            ("e", vec![]),
+        ];
+        let (order, cycles) = computed_order(&references);
+        assert_eq!(order, vec![4]);
+        assert_eq!(cycles, vec![vec!["a", "b", "c", "a"]]);
+    }
+
+    fn lookup_table(name: &str, mode: LookupMode, fallback: Option<&str>) -> LookupTable {
+        let entry = |key: &str, value: &str| crate::config::schema::LookupEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        LookupTable {
+            name: name.to_string(),
+            mode,
+            entries: vec![
+                entry("SBB CFF FFS", "SBB"),
+                entry("Schweizerische Bundesbahnen|Bundesbahn", "SBB"),
+                entry("Swisscom", "Swisscom"),
+            ],
+            fallback: fallback.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_lookup_transform() {
+        let extracted = vec![ExtractedVariable {
+            name: "vendor".to_string(),
+            pattern: r"(?m)^From: (?P<vendor>.+)$".to_string(),
+            transform: Some(VariableTransform::Lookup("vendors".to_string())),
+            default: None,
+            pages: None,
+            region: None,
+            from: None,
+        }];
+        let engine = VariableEngine::new(&extracted).with_lookup_tables(&[lookup_table(
+            "vendors",
+            LookupMode::Exact,
+            None,
+        )]);
+
+        let vars = engine.extract_variables("From: sbb  cff ffs\nInvoice");
+        assert_eq!(vars.get("vendor"), Some(&"SBB".to_string()));
+
+        // Exact keys must equal the whole value, and unmatched values are kept
+        let vars = engine.extract_variables("From: Schweizerische Bundesbahnen\nInvoice");
+        assert_eq!(
+            vars.get("vendor"),
+            Some(&"Schweizerische Bundesbahnen".to_string())
+        );
+    }
+
+    #[test]
+    fn test_lookup_regex_keys_and_fallback() {
+        let engine = VariableEngine::new(&[]).with_lookup_tables(&[
+            lookup_table("vendors", LookupMode::Regex, Some("other")),
+            lookup_table("exact", LookupMode::Exact, Some("other")),
+        ]);
+        let vendors = VariableTransform::Lookup("vendors".to_string());
+
+        assert_eq!(
+            engine.apply_transform("Schweizerische Bundesbahnen SBB", &vendors),
+            "SBB"
+        );
+        assert_eq!(engine.apply_transform("DB Bundesbahn", &vendors), "SBB");
+        assert_eq!(engine.apply_transform("Post CH AG", &vendors), "other");
+        assert_eq!(
+            engine.apply_transform(
+                "Bundesbahn",
+                &VariableTransform::Lookup("exact".to_string())
+            ),
+            "other"
+        );
+        // Unknown tables leave the value alone
+        assert_eq!(
+            engine.apply_transform(
+                "Post CH AG",
+                &VariableTransform::Lookup("missing".to_string())
+            ),
+            "Post CH AG"
+        );
+    }
+
     #[test]
     fn test_unicode_in_extracted_text() {
         let extracted = vec![ExtractedVariable {
@@ -269,6 +856,9 @@ mod tests {
             pattern: r"(?P<vendor>\p{L}+)".to_string(), // Unicode letter
             transform: None,
             default: None,
+            pages: None,
+            region: None,
+            from: None,
         }];
 
         let engine = VariableEngine::new(&extracted);
@@ -285,6 +875,9 @@ mod tests {
             pattern: r"prefix(?P<optional>.*?)suffix".to_string(),
             transform: None,
             default: None,
+            pages: None,
+            region: None,
+            from: None,
         }];
 
         let engine = VariableEngine::new(&extracted);
@@ -367,6 +960,9 @@ mod tests {
             pattern: r"(?P<missing>WONT_MATCH)".to_string(),
             transform: None,
             default: None,
+            pages: None,
+            region: None,
+            from: None,
         }];
 
         let engine = VariableEngine::new(&extracted);
@@ -482,12 +1078,18 @@ mod tests {
                 pattern: r"first:(?P<first>\w+)".to_string(),
                 transform: None,
                 default: None,
+                pages: None,
+                region: None,
+                from: None,
             },
             ExtractedVariable {
                 name: "second".to_string(),
                 pattern: r"second:(?P<second>\w+)".to_string(),
                 transform: None,
                 default: None,
+                pages: None,
+                region: None,
+                from: None,
             },
         ];
 
@@ -0,0 +1,166 @@
+//! Classifier example repository — operations for the `classifier_examples` table.
+
+use rusqlite::params;
+
+use super::{Database, DatabaseError};
+
+/// The token counts of a completed job, used to train the classifier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassifierExampleRow {
+    pub job_id: String,
+    /// Trusted category of the job; examples without one are not trained on.
+    pub category: Option<String>,
+    /// JSON object of token counts.
+    pub tokens: String,
+    pub updated_at: String,
+}
+
+impl ClassifierExampleRow {
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            job_id: row.get("job_id")?,
+            category: row.get("category")?,
+            tokens: row.get("tokens")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+/// Inserts or replaces the example of a job.
+pub fn upsert(db: &Database, example: &ClassifierExampleRow) -> Result<(), DatabaseError> {
+    db.with_conn(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO classifier_examples (job_id, category, tokens, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                example.job_id,
+                example.category,
+                example.tokens,
+                example.updated_at,
+            ],
+        )?;
+        Ok(())
+    })
+}
+
+/// Returns the example of a job.
+pub fn find_by_job(
+    db: &Database,
+    job_id: &str,
+) -> Result<Option<ClassifierExampleRow>, DatabaseError> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare("SELECT * FROM classifier_examples WHERE job_id = ?1")?;
+        let mut rows = stmt.query_map(params![job_id], ClassifierExampleRow::from_row)?;
+        match rows.next() {
+            Some(Ok(row)) => Ok(Some(row)),
+            Some(Err(e)) => Err(DatabaseError::Sqlite(e)),
+            None => Ok(None),
+        }
+    })
+}
+
+/// Sets the category of a job's example. Returns false if the job has none.
+pub fn set_category(
+    db: &Database,
+    job_id: &str,
+    category: Option<&str>,
+    updated_at: &str,
+) -> Result<bool, DatabaseError> {
+    db.with_conn(|conn| {
+        let updated = conn.execute(
+            "UPDATE classifier_examples SET category = ?2, updated_at = ?3 WHERE job_id = ?1",
+            params![job_id, category, updated_at],
+        )?;
+        Ok(updated > 0)
+    })
+}
+
+/// Returns every example that has a category.
+pub fn find_labeled(db: &Database) -> Result<Vec<ClassifierExampleRow>, DatabaseError> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT * FROM classifier_examples WHERE category IS NOT NULL ORDER BY job_id",
+        )?;
+        let examples = stmt
+            .query_map([], ClassifierExampleRow::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(examples)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::job_repo::{self, JobRow};
+
+    fn test_db() -> Database {
+        Database::open_in_memory().expect("Failed to create test database")
+    }
+
+    fn insert_job(db: &Database, id: &str) {
+        let row = JobRow {
+            id: id.to_string(),
+            filename: "test.pdf".to_string(),
+            source_path: "/tmp/test.pdf".to_string(),
+            archive_path: None,
+            output_path: None,
+            category: "unsorted".to_string(),
+            source_name: None,
+            status: "completed".to_string(),
+            error: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            completed_at: None,
+            symlinks: None,
+            current_phase: None,
+            message: None,
+            mime_type: None,
+            parent_job_id: None,
+            confidence: None,
+            alternatives: None,
+            explanation: None,
+        };
+        job_repo::insert(db, &row).unwrap();
+    }
+
+    fn example(job_id: &str, category: Option<&str>) -> ClassifierExampleRow {
+        ClassifierExampleRow {
+            job_id: job_id.to_string(),
+            category: category.map(String::from),
+            tokens: r#"{"invoice":2}"#.to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_upsert_and_find_labeled() {
+        let db = test_db();
+        insert_job(&db, "job-1");
+        insert_job(&db, "job-2");
+
+        upsert(&db, &example("job-1", Some("invoices"))).unwrap();
+        upsert(&db, &example("job-2", None)).unwrap();
+
+        let labeled = find_labeled(&db).unwrap();
+        assert_eq!(labeled, vec![example("job-1", Some("invoices"))]);
+        assert_eq!(
+            find_by_job(&db, "job-2").unwrap(),
+            Some(example("job-2", None))
+        );
+        assert_eq!(find_by_job(&db, "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_category() {
+        let db = test_db();
+        insert_job(&db, "job-1");
+        upsert(&db, &example("job-1", None)).unwrap();
+
+        assert!(set_category(&db, "job-1", Some("receipts"), "2026-01-02T00:00:00Z").unwrap());
+        assert!(!set_category(&db, "missing", Some("receipts"), "2026-01-02T00:00:00Z").unwrap());
+
+        let found = find_by_job(&db, "job-1").unwrap().unwrap();
+        assert_eq!(found.category.as_deref(), Some("receipts"));
+        assert_eq!(found.updated_at, "2026-01-02T00:00:00Z");
+    }
+}
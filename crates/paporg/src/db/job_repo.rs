@@ -1,5 +1,7 @@
 //! Job repository — CRUD operations for the `jobs` table.
 
+use std::collections::HashMap;
+
 use rusqlite::{params, Row};
 
 use super::{Database, DatabaseError};
@@ -23,6 +25,11 @@ pub struct JobRow {
     pub current_phase: Option<String>,
     pub message: Option<String>,
     pub mime_type: Option<String>,
+    pub parent_job_id: Option<String>,
+    pub confidence: Option<f64>,
+    pub alternatives: Option<String>,
+    /// JSON trace of how the job was categorized.
+    pub explanation: Option<String>,
 }
 
 impl JobRow {
@@ -44,6 +51,10 @@ impl JobRow {
             current_phase: row.get("current_phase")?,
             message: row.get("message")?,
             mime_type: row.get("mime_type")?,
+            parent_job_id: row.get("parent_job_id")?,
+            confidence: row.get("confidence")?,
+            alternatives: row.get("alternatives")?,
+            explanation: row.get("explanation")?,
         })
     }
 }
@@ -54,6 +65,8 @@ pub struct JobFilter {
     pub status: Option<String>,
     pub category: Option<String>,
     pub source_name: Option<String>,
+    /// Only jobs carrying this tag.
+    pub tag: Option<String>,
     pub from_date: Option<String>,
     pub to_date: Option<String>,
     pub exclude_status: Option<String>,
@@ -67,8 +80,10 @@ pub fn insert(db: &Database, job: &JobRow) -> Result<(), DatabaseError> {
         conn.execute(
             "INSERT INTO jobs (id, filename, source_path, archive_path, output_path, category,
              source_name, status, error, created_at, updated_at, completed_at, symlinks,
-             current_phase, message, mime_type)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+             current_phase, message, mime_type, parent_job_id, confidence, alternatives,
+             explanation)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+             ?18, ?19, ?20)",
             params![
                 job.id,
                 job.filename,
@@ -86,6 +101,10 @@ pub fn insert(db: &Database, job: &JobRow) -> Result<(), DatabaseError> {
                 job.current_phase,
                 job.message,
                 job.mime_type,
+                job.parent_job_id,
+                job.confidence,
+                job.alternatives,
+                job.explanation,
             ],
         )?;
         Ok(())
@@ -98,7 +117,8 @@ pub fn update(db: &Database, job: &JobRow) -> Result<(), DatabaseError> {
         conn.execute(
             "UPDATE jobs SET filename=?2, source_path=?3, archive_path=?4, output_path=?5,
              category=?6, source_name=?7, status=?8, error=?9, updated_at=?10,
-             completed_at=?11, symlinks=?12, current_phase=?13, message=?14, mime_type=?15
+             completed_at=?11, symlinks=?12, current_phase=?13, message=?14, mime_type=?15,
+             parent_job_id=?16, confidence=?17, alternatives=?18, explanation=?19
              WHERE id=?1",
             params![
                 job.id,
@@ -116,6 +136,10 @@ pub fn update(db: &Database, job: &JobRow) -> Result<(), DatabaseError> {
                 job.current_phase,
                 job.message,
                 job.mime_type,
+                job.parent_job_id,
+                job.confidence,
+                job.alternatives,
+                job.explanation,
             ],
         )?;
         Ok(())
@@ -135,6 +159,18 @@ pub fn find_by_id(db: &Database, id: &str) -> Result<Option<JobRow>, DatabaseErr
     })
 }
 
+/// Finds all jobs extracted from the given archive job.
+pub fn find_children(db: &Database, parent_id: &str) -> Result<Vec<JobRow>, DatabaseError> {
+    db.with_conn(|conn| {
+        let mut stmt =
+            conn.prepare("SELECT * FROM jobs WHERE parent_job_id = ?1 ORDER BY created_at ASC")?;
+        let rows = stmt
+            .query_map(params![parent_id], JobRow::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+}
+
 /// Queries jobs with filters, returning (rows, total_count).
 pub fn query(db: &Database, filter: &JobFilter) -> Result<(Vec<JobRow>, u64), DatabaseError> {
     db.with_conn(|conn| {
@@ -153,6 +189,13 @@ pub fn query(db: &Database, filter: &JobFilter) -> Result<(Vec<JobRow>, u64), Da
             conditions.push(format!("source_name = ?{}", param_values.len() + 1));
             param_values.push(Box::new(source_name.clone()));
         }
+        if let Some(ref tag) = filter.tag {
+            conditions.push(format!(
+                "id IN (SELECT job_id FROM job_tags WHERE tag = ?{})",
+                param_values.len() + 1
+            ));
+            param_values.push(Box::new(tag.clone()));
+        }
         if let Some(ref from_date) = filter.from_date {
             conditions.push(format!("created_at >= ?{}", param_values.len() + 1));
             param_values.push(Box::new(from_date.clone()));
@@ -213,6 +256,33 @@ pub fn count_by_status(db: &Database, status: &str) -> Result<u64, DatabaseError
     })
 }
 
+/// Returns when each rule last categorized a completed job, keyed by rule
+/// name. The rule is read from the winner of the stored match explanation.
+pub fn last_match_by_rule(db: &Database) -> Result<HashMap<String, String>, DatabaseError> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT json_extract(explanation, '$.winner.ruleId') AS rule_id,
+                    MAX(COALESCE(completed_at, updated_at))
+             FROM jobs
+             WHERE status = 'completed' AND rule_id IS NOT NULL
+             GROUP BY rule_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+    })
+}
+
+/// Returns the creation time of the oldest job, if any.
+pub fn oldest_created_at(db: &Database) -> Result<Option<String>, DatabaseError> {
+    db.with_conn(|conn| {
+        let oldest: Option<String> =
+            conn.query_row("SELECT MIN(created_at) FROM jobs", [], |r| r.get(0))?;
+        Ok(oldest)
+    })
+}
+
 /// Updates only the status and updated_at of a job.
 pub fn update_status(
     db: &Database,
@@ -255,6 +325,10 @@ mod tests {
             current_phase: Some("queued".to_string()),
             message: Some("Queued".to_string()),
             mime_type: Some("application/pdf".to_string()),
+            parent_job_id: None,
+            confidence: None,
+            alternatives: None,
+            explanation: None,
         }
     }
 
@@ -298,6 +372,25 @@ mod tests {
         assert!(found.completed_at.is_some());
     }
 
+    #[test]
+    fn test_find_children() {
+        let db = test_db();
+        insert(&db, &sample_job("parent")).unwrap();
+
+        for (i, id) in ["child-1", "child-2"].iter().enumerate() {
+            let mut child = sample_job(id);
+            child.parent_job_id = Some("parent".to_string());
+            child.created_at = format!("2026-01-01T00:00:0{}Z", i + 1);
+            insert(&db, &child).unwrap();
+        }
+
+        let children = find_children(&db, "parent").unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].id, "child-1");
+        assert_eq!(children[1].parent_job_id.as_deref(), Some("parent"));
+        assert!(find_children(&db, "child-1").unwrap().is_empty());
+    }
+
     #[test]
     fn test_query_no_filter() {
         let db = test_db();
@@ -400,4 +493,43 @@ mod tests {
         let found = find_by_id(&db, "us1").unwrap().unwrap();
         assert_eq!(found.status, "completed");
     }
+
+    #[test]
+    fn test_last_match_by_rule() {
+        let db = test_db();
+        let explanation = |rule: &str| Some(format!(r#"{{"winner":{{"ruleId":"{}"}}}}"#, rule));
+
+        let mut early = sample_job("m1");
+        early.status = "completed".to_string();
+        early.completed_at = Some("2026-01-02T00:00:00Z".to_string());
+        early.explanation = explanation("invoices");
+        insert(&db, &early).unwrap();
+
+        let mut late = sample_job("m2");
+        late.status = "completed".to_string();
+        late.completed_at = Some("2026-02-01T00:00:00Z".to_string());
+        late.explanation = explanation("invoices");
+        insert(&db, &late).unwrap();
+
+        let mut failed = sample_job("m3");
+        failed.status = "failed".to_string();
+        failed.explanation = explanation("receipts");
+        insert(&db, &failed).unwrap();
+
+        let mut unsorted = sample_job("m4");
+        unsorted.status = "completed".to_string();
+        unsorted.explanation = Some("{}".to_string());
+        insert(&db, &unsorted).unwrap();
+
+        let last = last_match_by_rule(&db).unwrap();
+        assert_eq!(last.len(), 1);
+        assert_eq!(
+            last.get("invoices").map(String::as_str),
+            Some("2026-02-01T00:00:00Z")
+        );
+        assert_eq!(
+            oldest_created_at(&db).unwrap().as_deref(),
+            Some("2026-01-01T00:00:00Z")
+        );
+    }
 }
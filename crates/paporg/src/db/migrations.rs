@@ -77,6 +77,45 @@ const MIGRATIONS: &[Migration] = &[
         sql: include_str!("sql/006_create_processing_stats.sql"),
         kind: MigrationKind::Standard,
     },
+    Migration {
+        version: 7,
+        description: "add_parent_job_id_to_jobs",
+        sql: include_str!("sql/007_add_parent_job_id.sql"),
+        kind: MigrationKind::AddColumn {
+            table: "jobs",
+            column: "parent_job_id",
+        },
+    },
+    Migration {
+        version: 8,
+        description: "add_categorization_confidence_to_jobs",
+        sql: include_str!("sql/008_add_categorization_confidence.sql"),
+        kind: MigrationKind::AddColumn {
+            table: "jobs",
+            column: "confidence",
+        },
+    },
+    Migration {
+        version: 9,
+        description: "create_job_tags_table",
+        sql: include_str!("sql/009_create_job_tags.sql"),
+        kind: MigrationKind::Standard,
+    },
+    Migration {
+        version: 10,
+        description: "add_match_explanation_to_jobs",
+        sql: include_str!("sql/010_add_match_explanation.sql"),
+        kind: MigrationKind::AddColumn {
+            table: "jobs",
+            column: "explanation",
+        },
+    },
+    Migration {
+        version: 11,
+        description: "create_classifier_examples_table",
+        sql: include_str!("sql/011_create_classifier_examples.sql"),
+        kind: MigrationKind::Standard,
+    },
 ];
 
 /// Runs all pending migrations on the given connection.
@@ -210,6 +249,55 @@ mod tests {
         assert!(column_exists(&conn, "jobs", "mime_type").unwrap());
     }
 
+    #[test]
+    fn test_jobs_table_has_parent_job_id() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        run_all(&conn).unwrap();
+
+        assert!(column_exists(&conn, "jobs", "parent_job_id").unwrap());
+    }
+
+    #[test]
+    fn test_jobs_table_has_categorization_confidence() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        run_all(&conn).unwrap();
+
+        assert!(column_exists(&conn, "jobs", "confidence").unwrap());
+        assert!(column_exists(&conn, "jobs", "alternatives").unwrap());
+    }
+
+    #[test]
+    fn test_jobs_table_has_explanation() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        run_all(&conn).unwrap();
+
+        assert!(column_exists(&conn, "jobs", "explanation").unwrap());
+    }
+
+    #[test]
+    fn test_job_tags_table_exists() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        run_all(&conn).unwrap();
+
+        assert!(column_exists(&conn, "job_tags", "job_id").unwrap());
+        assert!(column_exists(&conn, "job_tags", "tag").unwrap());
+    }
+
+    #[test]
+    fn test_classifier_examples_table_exists() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        run_all(&conn).unwrap();
+
+        assert!(column_exists(&conn, "classifier_examples", "job_id").unwrap());
+        assert!(column_exists(&conn, "classifier_examples", "category").unwrap());
+        assert!(column_exists(&conn, "classifier_examples", "tokens").unwrap());
+    }
+
     #[test]
     fn test_processing_stats_table_exists() {
         let conn = Connection::open_in_memory().unwrap();
@@ -8,12 +8,14 @@ use std::sync::{Arc, Mutex};
 
 use rusqlite::Connection;
 
+pub mod classifier_repo;
 pub mod email_repo;
 pub mod error;
 pub mod job_repo;
 pub mod migrations;
 pub mod oauth_repo;
 pub mod stats_repo;
+pub mod tag_repo;
 
 pub use error::DatabaseError;
 
@@ -0,0 +1,4 @@
+-- Add parent_job_id column if it does not already exist.
+-- Jobs extracted from an archive reference the archive's job.
+ALTER TABLE jobs ADD COLUMN parent_job_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_jobs_parent_job_id ON jobs(parent_job_id);
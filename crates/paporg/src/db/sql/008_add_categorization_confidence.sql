@@ -0,0 +1,4 @@
+-- Add scoring-mode categorization results if they do not already exist.
+-- alternatives holds a JSON array of runner-up rules and their scores.
+ALTER TABLE jobs ADD COLUMN confidence REAL;
+ALTER TABLE jobs ADD COLUMN alternatives TEXT;
@@ -0,0 +1,3 @@
+-- Add the categorization explanation if it does not already exist.
+-- explanation holds a JSON trace of the winning and rejected rules.
+ALTER TABLE jobs ADD COLUMN explanation TEXT;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS classifier_examples (
+    job_id TEXT PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+    category TEXT,
+    tokens TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_classifier_examples_category ON classifier_examples(category);
@@ -0,0 +1,190 @@
+//! Job tag repository — operations for the `job_tags` table.
+
+use std::collections::HashMap;
+
+use rusqlite::params;
+
+use super::{Database, DatabaseError};
+
+/// Replaces all tags of a job.
+pub fn replace_for_job(db: &Database, job_id: &str, tags: &[String]) -> Result<(), DatabaseError> {
+    db.with_conn(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM job_tags WHERE job_id = ?1", params![job_id])?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO job_tags (job_id, tag) VALUES (?1, ?2)",
+                params![job_id, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    })
+}
+
+/// Returns the tags of a job in alphabetical order.
+pub fn find_by_job(db: &Database, job_id: &str) -> Result<Vec<String>, DatabaseError> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare("SELECT tag FROM job_tags WHERE job_id = ?1 ORDER BY tag")?;
+        let tags = stmt
+            .query_map(params![job_id], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tags)
+    })
+}
+
+/// Returns the tags of several jobs, keyed by job ID. Jobs without tags are omitted.
+pub fn find_for_jobs(
+    db: &Database,
+    job_ids: &[&str],
+) -> Result<HashMap<String, Vec<String>>, DatabaseError> {
+    if job_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    db.with_conn(|conn| {
+        // Build IN clause with positional params.
+        let placeholders: Vec<String> = (0..job_ids.len()).map(|i| format!("?{}", i + 1)).collect();
+        let sql = format!(
+            "SELECT job_id, tag FROM job_tags WHERE job_id IN ({}) ORDER BY job_id, tag",
+            placeholders.join(", ")
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(job_ids), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut result: HashMap<String, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (job_id, tag) = row?;
+            result.entry(job_id).or_default().push(tag);
+        }
+        Ok(result)
+    })
+}
+
+/// Returns when each tag was last added to a completed job.
+pub fn last_use_by_tag(db: &Database) -> Result<HashMap<String, String>, DatabaseError> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT t.tag, MAX(COALESCE(j.completed_at, j.updated_at))
+             FROM job_tags t JOIN jobs j ON j.id = t.job_id
+             WHERE j.status = 'completed'
+             GROUP BY t.tag",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::job_repo::{self, JobRow};
+
+    fn test_db() -> Database {
+        Database::open_in_memory().expect("Failed to create test database")
+    }
+
+    fn insert_job(db: &Database, id: &str) {
+        let row = JobRow {
+            id: id.to_string(),
+            filename: "test.pdf".to_string(),
+            source_path: "/tmp/test.pdf".to_string(),
+            archive_path: None,
+            output_path: None,
+            category: "unsorted".to_string(),
+            source_name: None,
+            status: "completed".to_string(),
+            error: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            completed_at: None,
+            symlinks: None,
+            current_phase: None,
+            message: None,
+            mime_type: None,
+            parent_job_id: None,
+            confidence: None,
+            alternatives: None,
+            explanation: None,
+        };
+        job_repo::insert(db, &row).unwrap();
+    }
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_replace_and_find() {
+        let db = test_db();
+        insert_job(&db, "job-1");
+
+        replace_for_job(&db, "job-1", &tags(&["tax", "insurance", "tax"])).unwrap();
+        assert_eq!(
+            find_by_job(&db, "job-1").unwrap(),
+            tags(&["insurance", "tax"])
+        );
+
+        replace_for_job(&db, "job-1", &tags(&["health"])).unwrap();
+        assert_eq!(find_by_job(&db, "job-1").unwrap(), tags(&["health"]));
+
+        replace_for_job(&db, "job-1", &[]).unwrap();
+        assert!(find_by_job(&db, "job-1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_last_use_by_tag() {
+        let db = test_db();
+        insert_job(&db, "a");
+        insert_job(&db, "b");
+        replace_for_job(&db, "a", &tags(&["tax"])).unwrap();
+        replace_for_job(&db, "b", &tags(&["tax", "health"])).unwrap();
+
+        let last = last_use_by_tag(&db).unwrap();
+        assert_eq!(last.len(), 2);
+        assert_eq!(
+            last.get("tax").map(String::as_str),
+            Some("2026-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_find_for_jobs() {
+        let db = test_db();
+        insert_job(&db, "a");
+        insert_job(&db, "b");
+        insert_job(&db, "c");
+        replace_for_job(&db, "a", &tags(&["tax"])).unwrap();
+        replace_for_job(&db, "b", &tags(&["tax", "health"])).unwrap();
+
+        let found = find_for_jobs(&db, &["a", "b", "c"]).unwrap();
+        assert_eq!(found.get("a"), Some(&tags(&["tax"])));
+        assert_eq!(found.get("b"), Some(&tags(&["health", "tax"])));
+        assert!(!found.contains_key("c"));
+        assert!(find_for_jobs(&db, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tag_filter_in_job_query() {
+        let db = test_db();
+        insert_job(&db, "a");
+        insert_job(&db, "b");
+        replace_for_job(&db, "b", &tags(&["tax"])).unwrap();
+
+        let (rows, total) = job_repo::query(
+            &db,
+            &job_repo::JobFilter {
+                tag: Some("tax".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(rows[0].id, "b");
+    }
+}
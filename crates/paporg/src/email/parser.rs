@@ -382,11 +382,14 @@ fn mime_to_extension(mime_type: &str) -> &'static str {
         "application/pdf" => "pdf",
         "application/msword" => "doc",
         "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.oasis.opendocument.text" => "odt",
+        "application/rtf" | "text/rtf" => "rtf",
         "application/vnd.ms-excel" => "xls",
         "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
         "application/vnd.ms-powerpoint" => "ppt",
         "application/vnd.openxmlformats-officedocument.presentationml.presentation" => "pptx",
-        "application/zip" => "zip",
+        "application/zip" | "application/x-zip-compressed" => "zip",
+        "application/x-7z-compressed" => "7z",
         "application/x-gzip" | "application/gzip" => "gz",
         "application/x-tar" => "tar",
         "application/json" => "json",
@@ -397,6 +400,9 @@ fn mime_to_extension(mime_type: &str) -> &'static str {
         "image/webp" => "webp",
         "image/svg+xml" => "svg",
         "image/tiff" => "tiff",
+        "image/heic" => "heic",
+        "image/heif" => "heif",
+        "image/avif" => "avif",
         "image/bmp" => "bmp",
         "text/plain" => "txt",
         "text/html" => "html",
@@ -457,6 +463,14 @@ mod tests {
     fn test_mime_to_extension() {
         assert_eq!(mime_to_extension("application/pdf"), "pdf");
         assert_eq!(mime_to_extension("image/jpeg"), "jpg");
+        assert_eq!(mime_to_extension("image/heic"), "heic");
+        assert_eq!(mime_to_extension("image/avif"), "avif");
+        assert_eq!(mime_to_extension("text/rtf"), "rtf");
+        assert_eq!(mime_to_extension("application/x-7z-compressed"), "7z");
+        assert_eq!(
+            mime_to_extension("application/vnd.oasis.opendocument.text"),
+            "odt"
+        );
         assert_eq!(mime_to_extension("APPLICATION/PDF"), "pdf");
         assert_eq!(mime_to_extension("unknown/type"), "bin");
     }
@@ -1,4 +1,6 @@
 use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -62,6 +64,12 @@ pub enum ProcessError {
     #[error("Failed to process DOCX: {0}")]
     DocxProcessing(String),
 
+    #[error("Failed to process RTF: {0}")]
+    RtfProcessing(String),
+
+    #[error("Failed to process ODT: {0}")]
+    OdtProcessing(String),
+
     #[error("Failed to process image: {0}")]
     ImageProcessing(String),
 
@@ -70,6 +78,28 @@ pub enum ProcessError {
 
     #[error("Text extraction failed: {0}")]
     TextExtraction(String),
+
+    #[error("Resource limit exceeded: {0} (raise the limit in the settings, or set it to 0 to disable it)")]
+    LimitExceeded(LimitViolation),
+
+    #[error("Sandboxed processing failed: {0}")]
+    Sandbox(String),
+}
+
+/// A per-document resource limit that was exceeded.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitViolation {
+    #[error("file is {size} bytes, limit is {limit} bytes")]
+    FileSize { size: u64, limit: u64 },
+
+    #[error("document has {pages} pages, limit is {limit}")]
+    Pages { pages: usize, limit: usize },
+
+    #[error("processing took longer than {seconds}s")]
+    JobTimeout { seconds: u64 },
+
+    #[error("OCR of page {page} took longer than {seconds}s")]
+    OcrPageTimeout { page: usize, seconds: u64 },
 }
 
 #[derive(Error, Debug)]
@@ -131,6 +161,44 @@ pub enum WorkerError {
 
     #[error("Scan error: {0}")]
     ScanError(String),
+
+    #[error("Archive error: {0}")]
+    Archive(#[from] ArchiveError),
+}
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Failed to read archive '{path}': {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to extract '{path}': {source}")]
+    Extract {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid archive: {0}")]
+    InvalidArchive(String),
+
+    #[error("Archive entry '{0}' escapes the extraction directory")]
+    UnsafePath(String),
+
+    #[error("Archive contains more than {limit} files")]
+    TooManyEntries { limit: usize },
+
+    #[error("Archive entry '{name}' exceeds the {limit} byte size limit")]
+    EntryTooLarge { name: String, limit: u64 },
+
+    #[error("Archive exceeds the {limit} byte total size limit")]
+    TooLarge { limit: u64 },
+
+    #[error("Archive nesting exceeds the maximum depth of {limit}")]
+    TooDeep { limit: u32 },
 }
 
 pub type Result<T> = std::result::Result<T, PaporgError>;
@@ -0,0 +1,905 @@
+//! Static analysis of rules.
+//!
+//! Finds rules that can never categorize a document because a rule
+//! evaluated before them matches everything they match, rules with the same
+//! condition, repeated or empty condition lists and, given the job history,
+//! rules that have stopped matching documents.
+//!
+//! Implication between conditions is checked conservatively: a finding is
+//! only reported when it holds for every document, so some overlaps, such as
+//! between two different regex patterns, go unreported.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::loader::LoadedConfig;
+use super::resource::{
+    CategorizationMode, CompoundMatch, MatchCondition, MatchField, PageRegion, PageSelection,
+    RuleResource, SimpleMatch,
+};
+use crate::db::{job_repo, tag_repo, Database, DatabaseError};
+
+/// Days without a match after which a rule is reported as unused.
+pub const DEFAULT_UNUSED_AFTER_DAYS: u32 = 90;
+
+/// The kind of problem found with a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleFindingKind {
+    /// A rule evaluated earlier matches every document the rule matches.
+    Shadowed,
+    /// Another rule has an equivalent condition.
+    Duplicate,
+    /// A condition list names the same condition more than once.
+    RepeatedCondition,
+    /// An `all` or `any` list is empty.
+    EmptyCondition,
+    /// The rule has not matched a document within the history window.
+    Unused,
+}
+
+/// A problem found with a rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleFinding {
+    pub kind: RuleFindingKind,
+    /// Name of the rule the finding is about.
+    pub rule: String,
+    /// Name of the other rule involved in a shadowed or duplicate rule.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other: Option<String>,
+    pub message: String,
+}
+
+/// When rules last matched a document, from the job history.
+#[derive(Debug, Clone, Default)]
+pub struct RuleHistory {
+    /// Creation time of the oldest job. Rules are only reported unused once
+    /// the history covers the whole window.
+    pub since: Option<DateTime<Utc>>,
+    /// Last time each exclusive rule categorized a document, by rule name.
+    pub last_matched: HashMap<String, DateTime<Utc>>,
+    /// Last time each tag was added to a document.
+    pub last_tagged: HashMap<String, DateTime<Utc>>,
+    /// Days without a match after which a rule is unused.
+    pub unused_after_days: u32,
+}
+
+impl RuleHistory {
+    /// Reads the rule history from the job database.
+    pub fn from_database(db: &Database, unused_after_days: u32) -> Result<Self, DatabaseError> {
+        Ok(Self {
+            since: job_repo::oldest_created_at(db)?
+                .as_deref()
+                .and_then(parse_timestamp),
+            last_matched: parse_timestamps(job_repo::last_match_by_rule(db)?),
+            last_tagged: parse_timestamps(tag_repo::last_use_by_tag(db)?),
+            unused_after_days,
+        })
+    }
+
+    /// Returns when the rule last matched. Non-exclusive rules are tracked
+    /// by the tag they add.
+    fn last_match(&self, rule: &RuleResource) -> Option<DateTime<Utc>> {
+        if rule.spec.exclusive {
+            self.last_matched.get(&rule.metadata.name).copied()
+        } else {
+            self.last_tagged.get(&rule.spec.category).copied()
+        }
+    }
+}
+
+/// Analyzes the rules of a loaded configuration.
+pub struct RuleAnalyzer<'a> {
+    config: &'a LoadedConfig,
+    history: Option<&'a RuleHistory>,
+}
+
+impl<'a> RuleAnalyzer<'a> {
+    /// Creates an analyzer for the rules of `config`.
+    pub fn new(config: &'a LoadedConfig) -> Self {
+        Self {
+            config,
+            history: None,
+        }
+    }
+
+    /// Also reports rules that have not matched within the history window.
+    pub fn with_history(mut self, history: &'a RuleHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Returns the findings, in rule evaluation order.
+    pub fn analyze(&self) -> Vec<RuleFinding> {
+        self.analyze_at(Utc::now())
+    }
+
+    fn analyze_at(&self, now: DateTime<Utc>) -> Vec<RuleFinding> {
+        // Same order as the categorizer: priority descending, ties in load order
+        let mut rules: Vec<&RuleResource> = self.config.rules.iter().map(|r| &r.resource).collect();
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.spec.priority));
+
+        let mut findings = Vec::new();
+        for rule in &rules {
+            collect_list_findings(
+                &rule.metadata.name,
+                &rule.spec.match_condition,
+                false,
+                &Target::default(),
+                &mut findings,
+            );
+        }
+
+        self.collect_overlaps(&rules, &mut findings);
+
+        if let Some(history) = self.history {
+            collect_unused(&rules, history, now, &mut findings);
+        }
+
+        findings
+    }
+
+    /// Reports rules that repeat or are shadowed by a rule evaluated earlier.
+    fn collect_overlaps(&self, rules: &[&RuleResource], findings: &mut Vec<RuleFinding>) {
+        let first_match = self.config.settings.resource.spec.categorization.mode
+            == CategorizationMode::FirstMatch;
+        let terms: Vec<Term> = rules
+            .iter()
+            .map(|rule| Term::new(&rule.spec.match_condition, false, &Target::default()))
+            .collect();
+
+        for (i, rule) in rules.iter().enumerate() {
+            let name = &rule.metadata.name;
+            // Only exclusive rules compete; a tag rule never hides another rule
+            let competes = first_match && rule.spec.exclusive;
+
+            for (earlier, earlier_term) in rules.iter().zip(&terms).take(i) {
+                let other = &earlier.metadata.name;
+                let same_kind = earlier.spec.exclusive == rule.spec.exclusive;
+
+                if same_kind && equivalent(&terms[i], earlier_term) {
+                    let message = if competes {
+                        format!(
+                            "Rule '{}' never matches: it has the same condition as rule '{}', which is evaluated first",
+                            name, other
+                        )
+                    } else {
+                        format!("Rule '{}' has the same condition as rule '{}'", name, other)
+                    };
+                    findings.push(RuleFinding {
+                        kind: RuleFindingKind::Duplicate,
+                        rule: name.clone(),
+                        other: Some(other.clone()),
+                        message,
+                    });
+                    break;
+                }
+
+                if competes && earlier.spec.exclusive && implies(&terms[i], earlier_term) {
+                    findings.push(RuleFinding {
+                        kind: RuleFindingKind::Shadowed,
+                        rule: name.clone(),
+                        other: Some(other.clone()),
+                        message: format!(
+                            "Rule '{}' never matches: rule '{}' (priority {}) matches every document it matches and is evaluated first",
+                            name, other, earlier.spec.priority
+                        ),
+                    });
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Reports empty `all`/`any` lists and lists that repeat a condition.
+fn collect_list_findings(
+    rule: &str,
+    condition: &MatchCondition,
+    inherited_case_sensitive: bool,
+    inherited_target: &Target,
+    findings: &mut Vec<RuleFinding>,
+) {
+    match condition {
+        MatchCondition::Compound(compound) => {
+            let case_sensitive = compound.case_sensitive.unwrap_or(inherited_case_sensitive);
+            let target = inherited_target.with_compound(compound);
+
+            for (key, list) in [("all", &compound.all), ("any", &compound.any)] {
+                let Some(list) = list else { continue };
+
+                if list.is_empty() {
+                    let effect = if key == "all" {
+                        "matches every document"
+                    } else {
+                        "never matches"
+                    };
+                    findings.push(RuleFinding {
+                        kind: RuleFindingKind::EmptyCondition,
+                        rule: rule.to_string(),
+                        other: None,
+                        message: format!("Rule '{}': '{}' is empty and {}", rule, key, effect),
+                    });
+                }
+
+                let terms: Vec<Term> = list
+                    .iter()
+                    .map(|c| Term::new(c, case_sensitive, &target))
+                    .collect();
+                let repeated = terms
+                    .iter()
+                    .enumerate()
+                    .any(|(i, term)| terms[..i].iter().any(|t| equivalent(t, term)));
+                if repeated {
+                    findings.push(RuleFinding {
+                        kind: RuleFindingKind::RepeatedCondition,
+                        rule: rule.to_string(),
+                        other: None,
+                        message: format!(
+                            "Rule '{}': '{}' lists the same condition more than once",
+                            rule, key
+                        ),
+                    });
+                }
+
+                for child in list {
+                    collect_list_findings(rule, child, case_sensitive, &target, findings);
+                }
+            }
+
+            if let Some(not) = &compound.not {
+                collect_list_findings(rule, not, case_sensitive, &target, findings);
+            }
+        }
+        MatchCondition::Simple(simple) => {
+            let case_sensitive = simple.case_sensitive.unwrap_or(inherited_case_sensitive);
+            let lists = [
+                ("containsAny", &simple.contains_any),
+                ("containsAll", &simple.contains_all),
+            ];
+            for (key, list) in lists {
+                let Some(list) = list else { continue };
+                let values: Vec<String> = list.iter().map(|v| cased(v, case_sensitive)).collect();
+                let repeated = values
+                    .iter()
+                    .enumerate()
+                    .find(|(i, value)| values[..*i].contains(value));
+                if let Some((i, _)) = repeated {
+                    findings.push(RuleFinding {
+                        kind: RuleFindingKind::RepeatedCondition,
+                        rule: rule.to_string(),
+                        other: None,
+                        message: format!(
+                            "Rule '{}': '{}' lists '{}' more than once",
+                            rule, key, list[i]
+                        ),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Reports rules without a match in the history window.
+fn collect_unused(
+    rules: &[&RuleResource],
+    history: &RuleHistory,
+    now: DateTime<Utc>,
+    findings: &mut Vec<RuleFinding>,
+) {
+    let cutoff = now - Duration::days(i64::from(history.unused_after_days));
+    if history.since.is_none_or(|since| since > cutoff) {
+        return;
+    }
+
+    for rule in rules {
+        let last = history.last_match(rule);
+        if last.is_some_and(|last| last >= cutoff) {
+            continue;
+        }
+
+        let name = &rule.metadata.name;
+        let message = match last {
+            Some(last) => format!(
+                "Rule '{}' has not matched a document in {} days (last match {})",
+                name,
+                history.unused_after_days,
+                last.format("%Y-%m-%d")
+            ),
+            None => format!(
+                "Rule '{}' has not matched a document in {} days",
+                name, history.unused_after_days
+            ),
+        };
+        findings.push(RuleFinding {
+            kind: RuleFindingKind::Unused,
+            rule: name.clone(),
+            other: None,
+            message,
+        });
+    }
+}
+
+// ============================================================================
+// Condition implication
+// ============================================================================
+
+/// A match condition with inherited settings resolved, as evaluated by the
+/// categorizer.
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    /// Matches every document, like an empty `all`.
+    True,
+    /// Matches no document, like an empty `any`.
+    False,
+    Atom(Atom),
+    All(Vec<Term>),
+    Any(Vec<Term>),
+    Not(Box<Term>),
+}
+
+/// A single text test.
+#[derive(Debug, Clone, PartialEq)]
+struct Atom {
+    target: Target,
+    case_sensitive: bool,
+    test: AtomTest,
+}
+
+/// The part of a document a condition tests, inherited from its parents.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Target {
+    field: Option<MatchField>,
+    pages: Option<PageSelection>,
+    region: Option<PageRegion>,
+}
+
+impl Target {
+    fn with_simple(&self, simple: &SimpleMatch) -> Self {
+        Self {
+            field: simple.field.or(self.field),
+            pages: simple.pages.clone().or_else(|| self.pages.clone()),
+            region: simple.region.or(self.region),
+        }
+    }
+
+    fn with_compound(&self, compound: &CompoundMatch) -> Self {
+        Self {
+            field: compound.field.or(self.field),
+            pages: compound.pages.clone().or_else(|| self.pages.clone()),
+            region: compound.region.or(self.region),
+        }
+    }
+
+    fn is_scoped(&self) -> bool {
+        self.pages.is_some() || self.region.is_some()
+    }
+
+    /// The field tested; conditions without one test the text.
+    fn field(&self) -> MatchField {
+        self.field.unwrap_or(MatchField::Text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AtomTest {
+    /// The field contains the string, lowercased unless case-sensitive.
+    Contains(String),
+    /// Any other condition, keyed by its serialized form. Only equal
+    /// conditions imply each other.
+    Other(String),
+}
+
+impl Term {
+    fn new(
+        condition: &MatchCondition,
+        inherited_case_sensitive: bool,
+        inherited_target: &Target,
+    ) -> Self {
+        match condition {
+            MatchCondition::Compound(compound) => {
+                let case_sensitive = compound.case_sensitive.unwrap_or(inherited_case_sensitive);
+                let target = inherited_target.with_compound(compound);
+                let terms = |list: &[MatchCondition]| {
+                    list.iter()
+                        .map(|c| Term::new(c, case_sensitive, &target))
+                        .collect::<Vec<_>>()
+                };
+
+                // Same precedence as the categorizer: all, then any, then not
+                if let Some(all) = &compound.all {
+                    Term::all(terms(all))
+                } else if let Some(any) = &compound.any {
+                    Term::any(terms(any))
+                } else if let Some(not) = &compound.not {
+                    Term::Not(Box::new(Term::new(not, case_sensitive, &target)))
+                } else {
+                    Term::False
+                }
+            }
+            MatchCondition::Simple(simple) => {
+                let case_sensitive = simple.case_sensitive.unwrap_or(inherited_case_sensitive);
+                let target = inherited_target.with_simple(simple);
+                let contains = |value: &String| {
+                    Term::Atom(Atom {
+                        target: target.clone(),
+                        case_sensitive,
+                        test: AtomTest::Contains(cased(value, case_sensitive)),
+                    })
+                };
+
+                if let Some(value) = &simple.contains {
+                    contains(value)
+                } else if let Some(values) = &simple.contains_any {
+                    Term::any(values.iter().map(contains).collect())
+                } else if let Some(values) = &simple.contains_all {
+                    Term::all(values.iter().map(contains).collect())
+                } else if simple.fuzzy.is_some()
+                    || simple.contains_any_fuzzy.is_some()
+                    || simple.variable.is_some()
+                    || simple.correspondent.is_some()
+                    || simple.pattern.is_some()
+                {
+                    Term::Atom(Atom {
+                        target,
+                        case_sensitive,
+                        test: AtomTest::Other(other_key(simple)),
+                    })
+                } else {
+                    Term::False
+                }
+            }
+        }
+    }
+
+    fn all(terms: Vec<Term>) -> Self {
+        if terms.is_empty() {
+            Term::True
+        } else {
+            Term::All(terms)
+        }
+    }
+
+    fn any(terms: Vec<Term>) -> Self {
+        if terms.is_empty() {
+            Term::False
+        } else {
+            Term::Any(terms)
+        }
+    }
+}
+
+/// Returns true if every document matching `inner` also matches `outer`.
+/// False negatives are possible; false positives are not.
+fn implies(inner: &Term, outer: &Term) -> bool {
+    if inner == outer || *outer == Term::True || *inner == Term::False {
+        return true;
+    }
+    if let Term::All(items) = outer {
+        return items.iter().all(|item| implies(inner, item));
+    }
+    if let Term::Any(items) = inner {
+        return items.iter().all(|item| implies(item, outer));
+    }
+    if let Term::Any(items) = outer {
+        if items.iter().any(|item| implies(inner, item)) {
+            return true;
+        }
+    }
+    if let Term::All(items) = inner {
+        if items.iter().any(|item| implies(item, outer)) {
+            return true;
+        }
+    }
+
+    match (inner, outer) {
+        (Term::Atom(inner), Term::Atom(outer)) => atom_implies(inner, outer),
+        (Term::Not(inner), Term::Not(outer)) => implies(outer, inner),
+        _ => false,
+    }
+}
+
+fn equivalent(a: &Term, b: &Term) -> bool {
+    implies(a, b) && implies(b, a)
+}
+
+fn atom_implies(inner: &Atom, outer: &Atom) -> bool {
+    match (&inner.test, &outer.test) {
+        (AtomTest::Contains(inner_value), AtomTest::Contains(outer_value)) => {
+            if !target_within(&inner.target, &outer.target) {
+                return false;
+            }
+            if outer.case_sensitive {
+                inner.case_sensitive && inner_value.contains(outer_value.as_str())
+            } else {
+                inner_value.to_lowercase().contains(outer_value.as_str())
+            }
+        }
+        _ => inner == outer,
+    }
+}
+
+/// Returns true if the text of `inner` is always part of the text of `outer`:
+/// the first page is part of the text.
+fn field_within(inner: MatchField, outer: MatchField) -> bool {
+    inner == outer || (inner == MatchField::FirstPage && outer == MatchField::Text)
+}
+
+fn cased(value: &str, case_sensitive: bool) -> String {
+    if case_sensitive {
+        value.to_string()
+    } else {
+        value.to_lowercase()
+    }
+}
+
+/// Returns true if the text `inner` tests is always part of the text `outer`
+/// tests. Pages and regions are only compared for equality; a scoped text
+/// is part of the same field unscoped.
+fn target_within(inner: &Target, outer: &Target) -> bool {
+    if !outer.is_scoped() {
+        return field_within(inner.field(), outer.field());
+    }
+    inner.field() == outer.field() && inner.pages == outer.pages && inner.region == outer.region
+}
+
+/// Identifies a condition that is not a plain substring test, ignoring the
+/// settings resolved separately.
+fn other_key(simple: &SimpleMatch) -> String {
+    let bare = SimpleMatch {
+        case_sensitive: None,
+        field: None,
+        pages: None,
+        region: None,
+        weight: None,
+        ..simple.clone()
+    };
+    serde_json::to_string(&bare).unwrap_or_default()
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn parse_timestamps(values: HashMap<String, String>) -> HashMap<String, DateTime<Utc>> {
+    values
+        .into_iter()
+        .filter_map(|(key, value)| parse_timestamp(&value).map(|t| (key, t)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gitops::resource::*;
+
+    fn settings(mode: CategorizationMode) -> SettingsResource {
+        Resource::new(
+            ResourceKind::Settings,
+            "default",
+            SettingsSpec {
+                input_directory: "/inbox".to_string(),
+                output_directory: "/output".to_string(),
+                worker_count: 1,
+                ocr: OcrSettings::default(),
+                limits: LimitsSettings::default(),
+                categorization: CategorizationSettings {
+                    mode,
+                    ..Default::default()
+                },
+                defaults: DefaultOutputSettings::default(),
+                git: GitSettings::default(),
+                ai: AiSettings::default(),
+                release_channel: ReleaseChannel::default(),
+                overlay: None,
+            },
+        )
+    }
+
+    fn rule(name: &str, priority: i32, condition: MatchCondition) -> RuleResource {
+        Resource::new(
+            ResourceKind::Rule,
+            name,
+            RuleSpec {
+                priority,
+                extends: None,
+                category: name.to_string(),
+                exclusive: true,
+                match_condition: condition,
+                output: OutputSettings {
+                    directory: name.to_string(),
+                    filename: "$original".to_string(),
+                },
+                symlinks: Vec::new(),
+            },
+        )
+    }
+
+    fn contains(value: &str) -> MatchCondition {
+        MatchCondition::Simple(SimpleMatch {
+            contains: Some(value.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn contains_any(values: &[&str]) -> MatchCondition {
+        MatchCondition::Simple(SimpleMatch {
+            contains_any: Some(values.iter().map(|v| v.to_string()).collect()),
+            ..Default::default()
+        })
+    }
+
+    fn compound(
+        all: Option<Vec<MatchCondition>>,
+        any: Option<Vec<MatchCondition>>,
+    ) -> MatchCondition {
+        MatchCondition::Compound(CompoundMatch {
+            all,
+            any,
+            not: None,
+            case_sensitive: None,
+            field: None,
+            pages: None,
+            region: None,
+            weight: None,
+        })
+    }
+
+    fn config(mode: CategorizationMode, rules: Vec<RuleResource>) -> LoadedConfig {
+        LoadedConfig {
+            rules: rules
+                .into_iter()
+                .map(|r| {
+                    let path = format!("rules/{}.yaml", r.metadata.name);
+                    ResourceWithPath::new(r, path)
+                })
+                .collect(),
+            ..LoadedConfig::new(ResourceWithPath::new(settings(mode), "settings.yaml"))
+        }
+    }
+
+    fn kinds(findings: &[RuleFinding]) -> Vec<(RuleFindingKind, &str)> {
+        findings.iter().map(|f| (f.kind, f.rule.as_str())).collect()
+    }
+
+    #[test]
+    fn test_shadowed_by_higher_priority_contains_any() {
+        let config = config(
+            CategorizationMode::FirstMatch,
+            vec![
+                rule("invoices", 100, contains_any(&["Invoice", "Rechnung"])),
+                rule(
+                    "tax-invoices",
+                    50,
+                    compound(Some(vec![contains("INVOICE"), contains("VAT")]), None),
+                ),
+                rule("receipts", 10, contains("Receipt")),
+            ],
+        );
+
+        let findings = RuleAnalyzer::new(&config).analyze();
+
+        assert_eq!(
+            kinds(&findings),
+            vec![(RuleFindingKind::Shadowed, "tax-invoices")]
+        );
+        assert_eq!(findings[0].other.as_deref(), Some("invoices"));
+    }
+
+    #[test]
+    fn test_substring_and_field_implication() {
+        let config = config(
+            CategorizationMode::FirstMatch,
+            vec![
+                rule("bank", 20, contains("Bank")),
+                rule(
+                    "statements",
+                    10,
+                    MatchCondition::Simple(SimpleMatch {
+                        contains: Some("Bank Statement".to_string()),
+                        field: Some(MatchField::FirstPage),
+                        ..Default::default()
+                    }),
+                ),
+                rule(
+                    "bank-files",
+                    5,
+                    MatchCondition::Simple(SimpleMatch {
+                        contains: Some("bank".to_string()),
+                        field: Some(MatchField::Filename),
+                        ..Default::default()
+                    }),
+                ),
+            ],
+        );
+
+        let findings = RuleAnalyzer::new(&config).analyze();
+
+        // A filename match says nothing about the text
+        assert_eq!(
+            kinds(&findings),
+            vec![(RuleFindingKind::Shadowed, "statements")]
+        );
+    }
+
+    #[test]
+    fn test_page_scoped_implication() {
+        let scoped = |name: &str, priority: i32, value: &str, region: Option<PageRegion>| {
+            rule(
+                name,
+                priority,
+                MatchCondition::Simple(SimpleMatch {
+                    contains: Some(value.to_string()),
+                    pages: Some(PageSelection::Numbers(vec![1])),
+                    region,
+                    ..Default::default()
+                }),
+            )
+        };
+        let config = config(
+            CategorizationMode::FirstMatch,
+            vec![
+                scoped("letterhead", 30, "Acme", Some(PageRegion::Top(5))),
+                scoped("first-page", 20, "Acme", None),
+                rule("anywhere", 10, contains("Acme")),
+                scoped("acme-gmbh", 5, "Acme GmbH", None),
+            ],
+        );
+
+        let findings = RuleAnalyzer::new(&config).analyze();
+
+        // A narrower scope does not cover a wider one, but the whole text covers both
+        assert_eq!(
+            kinds(&findings),
+            vec![(RuleFindingKind::Shadowed, "acme-gmbh")]
+        );
+    }
+
+    #[test]
+    fn test_case_sensitive_outer_is_not_implied() {
+        let config = config(
+            CategorizationMode::FirstMatch,
+            vec![
+                rule(
+                    "exact",
+                    20,
+                    MatchCondition::Simple(SimpleMatch {
+                        contains: Some("ACME".to_string()),
+                        case_sensitive: Some(true),
+                        ..Default::default()
+                    }),
+                ),
+                rule("acme", 10, contains("acme")),
+            ],
+        );
+
+        assert!(RuleAnalyzer::new(&config).analyze().is_empty());
+    }
+
+    #[test]
+    fn test_duplicates_and_scoring_mode() {
+        let rules = || {
+            vec![
+                rule("a", 20, contains_any(&["Invoice", "Bill"])),
+                rule("b", 10, contains_any(&["bill", "invoice"])),
+                rule("c", 5, contains("Invoice total")),
+            ]
+        };
+
+        let findings =
+            RuleAnalyzer::new(&config(CategorizationMode::FirstMatch, rules())).analyze();
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (RuleFindingKind::Duplicate, "b"),
+                (RuleFindingKind::Shadowed, "c"),
+            ]
+        );
+        assert!(findings[0].message.contains("never matches"));
+
+        // Scoring mode has no shadowing, but duplicates remain
+        let findings = RuleAnalyzer::new(&config(CategorizationMode::Scoring, rules())).analyze();
+        assert_eq!(kinds(&findings), vec![(RuleFindingKind::Duplicate, "b")]);
+        assert!(!findings[0].message.contains("never matches"));
+    }
+
+    #[test]
+    fn test_non_exclusive_rules_do_not_shadow() {
+        let mut tag = rule("tax", 100, contains("Tax"));
+        tag.spec.exclusive = false;
+        let config = config(
+            CategorizationMode::FirstMatch,
+            vec![tag, rule("tax-return", 10, contains("Tax return"))],
+        );
+
+        assert!(RuleAnalyzer::new(&config).analyze().is_empty());
+    }
+
+    #[test]
+    fn test_empty_and_repeated_lists() {
+        let config = config(
+            CategorizationMode::FirstMatch,
+            vec![
+                rule("empty-any", 10, compound(None, Some(vec![]))),
+                rule(
+                    "repeated",
+                    5,
+                    compound(Some(vec![contains("Invoice"), contains("invoice")]), None),
+                ),
+                rule(
+                    "repeated-values",
+                    1,
+                    contains_any(&["Tax", "Receipt", "tax"]),
+                ),
+            ],
+        );
+
+        let findings = RuleAnalyzer::new(&config).analyze();
+
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (RuleFindingKind::EmptyCondition, "empty-any"),
+                (RuleFindingKind::RepeatedCondition, "repeated"),
+                (RuleFindingKind::RepeatedCondition, "repeated-values"),
+            ]
+        );
+        assert!(findings[0].message.contains("never matches"));
+        assert!(findings[2].message.contains("'tax'"));
+    }
+
+    #[test]
+    fn test_unused_rules() {
+        let now = Utc::now();
+        let mut tag = rule("insurance", 1, contains("Policy"));
+        tag.spec.exclusive = false;
+        let config = config(
+            CategorizationMode::FirstMatch,
+            vec![
+                rule("invoices", 30, contains("Invoice")),
+                rule("receipts", 20, contains("Receipt")),
+                rule("payslips", 10, contains("Payslip")),
+                tag,
+            ],
+        );
+
+        let mut history = RuleHistory {
+            since: Some(now - Duration::days(365)),
+            unused_after_days: 90,
+            ..Default::default()
+        };
+        history
+            .last_matched
+            .insert("invoices".to_string(), now - Duration::days(3));
+        history
+            .last_matched
+            .insert("receipts".to_string(), now - Duration::days(200));
+        history
+            .last_tagged
+            .insert("insurance".to_string(), now - Duration::days(1));
+
+        let findings = RuleAnalyzer::new(&config)
+            .with_history(&history)
+            .analyze_at(now);
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (RuleFindingKind::Unused, "receipts"),
+                (RuleFindingKind::Unused, "payslips"),
+            ]
+        );
+        assert!(findings[0].message.contains("last match"));
+
+        // A history shorter than the window reports nothing
+        history.since = Some(now - Duration::days(30));
+        assert!(RuleAnalyzer::new(&config)
+            .with_history(&history)
+            .analyze_at(now)
+            .is_empty());
+    }
+}
@@ -0,0 +1,562 @@
+//! Structured diagnostics about configuration resources.
+//!
+//! Validation reports each problem as a [`Diagnostic`] naming the resource,
+//! its file and the YAML path of the offending field. [`locate`] then finds
+//! the line and column of that path in the file, so that editors can point
+//! at it. Warnings are reported the same way but do not fail validation.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+use super::error::GitOpsError;
+use super::resource::ResourceKind;
+
+/// How severe a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// The configuration cannot be used.
+    Error,
+    /// The configuration works, but likely not as intended.
+    Warning,
+}
+
+/// What a diagnostic is about, for tools that handle some problems alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticCode {
+    /// A YAML file could not be parsed.
+    ParseError,
+    /// The configuration could not be loaded for another reason.
+    LoadError,
+    /// A resource does not match the schema of its kind, such as a field
+    /// of the wrong type or an unknown field.
+    Schema,
+    /// A required field is missing or empty.
+    Required,
+    /// A resource name is not a valid identifier.
+    InvalidName,
+    /// A variable name is taken by a built-in variable.
+    ReservedName,
+    /// Two resources of a kind share a name.
+    DuplicateName,
+    /// A regular expression, glob or MIME pattern does not parse.
+    InvalidPattern,
+    /// A field has a value outside of what it accepts.
+    InvalidValue,
+    /// A template or condition names a variable that is not defined.
+    UndefinedVariable,
+    /// A field names a rule, correspondent or lookup table that is not
+    /// defined.
+    UndefinedReference,
+    /// Computed variables reference each other in a cycle.
+    ReferenceCycle,
+    /// A path template leaves the output directory.
+    PathTraversal,
+    /// A path template is absolute.
+    AbsolutePath,
+    /// Input, output and import directories overlap.
+    DirectoryOverlap,
+    /// A rule test case failed.
+    RuleTestFailed,
+    /// A rule template is not extended by any rule.
+    UnusedTemplate,
+    /// A lookup table key appears more than once.
+    DuplicateKey,
+    /// An earlier rule matches every document a rule matches.
+    ShadowedRule,
+    /// Two rules have equivalent conditions.
+    DuplicateRule,
+    /// A condition list names the same condition more than once.
+    RepeatedCondition,
+    /// A rule has not matched a document for a while.
+    UnusedRule,
+}
+
+/// A problem found in the configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    /// Kind of the resource the diagnostic is about.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ResourceKind>,
+    /// Name of the resource the diagnostic is about.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The file, relative to the config directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Path of the field within the resource, such as `spec.match.all[1]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yaml_path: Option<String>,
+    /// Line in the file, counting from 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Column in the line, counting from 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub message: String,
+    /// How to fix the problem, where there is an obvious way.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Diagnostic {
+    /// Creates an error diagnostic.
+    pub fn error(code: DiagnosticCode, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    /// Creates a warning diagnostic.
+    pub fn warning(code: DiagnosticCode, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    fn new(severity: Severity, code: DiagnosticCode, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code,
+            kind: None,
+            name: None,
+            path: None,
+            yaml_path: None,
+            line: None,
+            column: None,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    /// Sets the path of the offending field within the resource.
+    pub fn at(mut self, yaml_path: impl Into<String>) -> Self {
+        self.yaml_path = Some(yaml_path.into());
+        self
+    }
+
+    /// Sets how to fix the problem.
+    pub fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+
+    /// Sets the resource the diagnostic is about.
+    pub fn for_resource(
+        mut self,
+        kind: ResourceKind,
+        name: impl Into<String>,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        self.kind = Some(kind);
+        self.name = Some(name.into());
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Sets the line and column from the content of the resource's file.
+    pub fn locate_in(&mut self, content: &str) {
+        let tree = Tree::parse(content);
+        let document = match (self.kind, &self.name) {
+            (Some(kind), Some(name)) => tree.find_document(kind, name),
+            _ => tree.documents.first(),
+        };
+        let Some(document) = document else {
+            return;
+        };
+
+        let (line, column) = self
+            .yaml_path
+            .as_deref()
+            .and_then(|path| tree.locate(document, path))
+            .unwrap_or((document.line, document.column));
+        self.line = Some(line);
+        self.column = Some(column);
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<&GitOpsError> for Diagnostic {
+    /// Describes an error that stopped the configuration from loading.
+    fn from(error: &GitOpsError) -> Self {
+        match error {
+            GitOpsError::ParseYaml {
+                path, line, column, ..
+            } => Self {
+                path: Some(path.clone()),
+                line: *line,
+                column: *column,
+                ..Self::error(DiagnosticCode::ParseError, error.to_string())
+            },
+            GitOpsError::InvalidResource { path, .. }
+            | GitOpsError::InvalidLookupFile { path, .. }
+            | GitOpsError::ReadFile { path, .. } => Self {
+                path: Some(path.clone()),
+                ..Self::error(DiagnosticCode::LoadError, error.to_string())
+            },
+            _ => Self::error(DiagnosticCode::LoadError, error.to_string()),
+        }
+    }
+}
+
+/// Returns the line and column, counting from 1, of the field at a YAML
+/// path such as `spec.match.all[1].pattern` in the first document of `text`.
+///
+/// Mapping entries are located at their key and sequence items at their
+/// value. Where the path leads to a field the document does not set, the
+/// position of the deepest field found is returned.
+pub fn locate(text: &str, yaml_path: &str) -> Option<(usize, usize)> {
+    let tree = Tree::parse(text);
+    tree.locate(tree.documents.first()?, yaml_path)
+}
+
+/// Returns the YAML path and line, counting from 1, of every mapping entry
+/// and sequence item in the first document of `text`, parents first.
+pub(crate) fn field_lines(text: &str) -> Vec<(String, usize)> {
+    fn collect(node: &Node, path: &str, fields: &mut Vec<(String, usize)>) {
+        match &node.value {
+            NodeValue::Mapping(entries) => {
+                for pair in entries.chunks_exact(2) {
+                    let NodeValue::Scalar(key) = &pair[0].value else {
+                        continue;
+                    };
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    fields.push((path.clone(), pair[0].line));
+                    collect(&pair[1], &path, fields);
+                }
+            }
+            NodeValue::Sequence(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let path = format!("{}[{}]", path, index);
+                    fields.push((path.clone(), item.line));
+                    collect(item, &path, fields);
+                }
+            }
+            NodeValue::Scalar(_) | NodeValue::Alias(_) => {}
+        }
+    }
+
+    let mut fields = Vec::new();
+    if let Some(document) = Tree::parse(text).documents.first() {
+        collect(document, "", &mut fields);
+    }
+    fields
+}
+
+/// A YAML node and the position where it starts.
+#[derive(Debug, Clone)]
+struct Node {
+    line: usize,
+    column: usize,
+    value: NodeValue,
+}
+
+#[derive(Debug, Clone)]
+enum NodeValue {
+    Scalar(String),
+    Sequence(Vec<Node>),
+    /// Keys and values, alternating.
+    Mapping(Vec<Node>),
+    /// Reference to the node with this anchor id.
+    Alias(usize),
+}
+
+/// The documents of a YAML stream, built from the parser's marked events.
+#[derive(Default)]
+struct Tree {
+    documents: Vec<Node>,
+    anchors: HashMap<usize, Node>,
+    /// Collections being built, with their anchor ids.
+    open: Vec<(Node, usize)>,
+}
+
+impl Tree {
+    /// Parses every document of `text`. On a syntax error the documents
+    /// parsed so far are kept.
+    fn parse(text: &str) -> Self {
+        let mut tree = Self::default();
+        let _ = Parser::new_from_str(text).load(&mut tree, true);
+        tree
+    }
+
+    /// Returns the document of a resource.
+    fn find_document(&self, kind: ResourceKind, name: &str) -> Option<&Node> {
+        self.documents.iter().find(|document| {
+            let scalar = |path: &[&str]| {
+                let node = path
+                    .iter()
+                    .try_fold(*document, |node, key| self.get(node, key))?;
+                match &self.resolve(node).value {
+                    NodeValue::Scalar(value) => Some(value.as_str()),
+                    _ => None,
+                }
+            };
+            scalar(&["kind"]).and_then(|k| k.parse().ok()) == Some(kind)
+                && scalar(&["metadata", "name"]) == Some(name)
+        })
+    }
+
+    fn locate(&self, document: &Node, yaml_path: &str) -> Option<(usize, usize)> {
+        let mut node = document;
+        let mut found = None;
+        for segment in path_segments(yaml_path) {
+            let next = match segment {
+                Segment::Key(key) => self.entry(node, key),
+                Segment::Index(n) => match &self.resolve(node).value {
+                    NodeValue::Sequence(items) => items.get(n).map(|item| (item, item)),
+                    _ => None,
+                },
+            };
+            let Some((position, value)) = next else {
+                return found;
+            };
+            found = Some((position.line, position.column));
+            node = value;
+        }
+        found
+    }
+
+    fn get<'a>(&'a self, node: &'a Node, key: &str) -> Option<&'a Node> {
+        self.entry(node, key).map(|(_, value)| value)
+    }
+
+    /// Returns the key and value of a mapping entry, following `<<` merge keys.
+    fn entry<'a>(&'a self, node: &'a Node, key: &str) -> Option<(&'a Node, &'a Node)> {
+        let NodeValue::Mapping(entries) = &self.resolve(node).value else {
+            return None;
+        };
+        let pairs = || entries.chunks_exact(2).map(|pair| (&pair[0], &pair[1]));
+        let is_key = |node: &Node, key: &str| matches!(&self.resolve(node).value, NodeValue::Scalar(k) if k == key);
+
+        pairs().find(|(k, _)| is_key(k, key)).or_else(|| {
+            pairs()
+                .filter(|(k, _)| is_key(k, "<<"))
+                .find_map(|(_, merged)| match &self.resolve(merged).value {
+                    NodeValue::Sequence(sources) => {
+                        sources.iter().find_map(|source| self.entry(source, key))
+                    }
+                    _ => self.entry(merged, key),
+                })
+        })
+    }
+
+    /// Follows an alias to the anchored node.
+    fn resolve<'a>(&'a self, node: &'a Node) -> &'a Node {
+        match node.value {
+            NodeValue::Alias(id) => self.anchors.get(&id).unwrap_or(node),
+            _ => node,
+        }
+    }
+
+    fn push(&mut self, node: Node, anchor: usize) {
+        if anchor != 0 {
+            self.anchors.insert(anchor, node.clone());
+        }
+        match self.open.last_mut() {
+            Some((
+                Node {
+                    value: NodeValue::Sequence(children) | NodeValue::Mapping(children),
+                    ..
+                },
+                _,
+            )) => children.push(node),
+            _ => self.documents.push(node),
+        }
+    }
+}
+
+impl MarkedEventReceiver for Tree {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let node = |value| Node {
+            line: mark.line(),
+            column: mark.col() + 1,
+            value,
+        };
+        match event {
+            Event::Scalar(value, _, anchor, _) => self.push(node(NodeValue::Scalar(value)), anchor),
+            Event::Alias(id) => self.push(node(NodeValue::Alias(id)), 0),
+            Event::SequenceStart(anchor, _) => self
+                .open
+                .push((node(NodeValue::Sequence(Vec::new())), anchor)),
+            Event::MappingStart(anchor, _) => self
+                .open
+                .push((node(NodeValue::Mapping(Vec::new())), anchor)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some((mut collection, anchor)) = self.open.pop() {
+                    // Block mappings are reported at the first `:`; they start
+                    // at their first key.
+                    if let NodeValue::Sequence(children) | NodeValue::Mapping(children) =
+                        &collection.value
+                    {
+                        if let Some(first) = children.first().filter(|first| {
+                            (first.line, first.column) < (collection.line, collection.column)
+                        }) {
+                            (collection.line, collection.column) = (first.line, first.column);
+                        }
+                    }
+                    self.push(collection, anchor);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn path_segments(yaml_path: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    for part in yaml_path.split('.').filter(|p| !p.is_empty()) {
+        let (key, indices) = part.split_once('[').unwrap_or((part, ""));
+        if !key.is_empty() {
+            segments.push(Segment::Key(key));
+        }
+        segments.extend(
+            indices
+                .split(['[', ']'])
+                .filter_map(|i| i.parse().ok())
+                .map(Segment::Index),
+        );
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULE: &str = "apiVersion: paporg.io/v1
+kind: Rule
+metadata:
+  name: invoices
+spec:
+  category: invoices
+  match:
+    all:
+      - contains: Invoice
+      - pattern: \"(unclosed\"
+        field: text
+    any: [a, b]
+  symlinks:
+  - target: by-vendor
+  - target: \"../escape\"
+";
+
+    #[test]
+    fn test_locate() {
+        assert_eq!(locate(RULE, "spec.category"), Some((6, 3)));
+        assert_eq!(locate(RULE, "spec.match.all[0].contains"), Some((9, 9)));
+        assert_eq!(locate(RULE, "spec.match.all[1]"), Some((10, 9)));
+        assert_eq!(locate(RULE, "spec.match.all[1].field"), Some((11, 9)));
+        assert_eq!(locate(RULE, "spec.symlinks[1].target"), Some((15, 5)));
+        assert_eq!(locate(RULE, "spec.match.any[1]"), Some((12, 14)));
+
+        // Missing fields give the deepest field found
+        assert_eq!(locate(RULE, "spec.output.directory"), Some((5, 1)));
+        assert_eq!(locate(RULE, "status"), None);
+    }
+
+    #[test]
+    fn test_locate_flow_and_quoted_keys() {
+        let text = "kind: Rule
+spec: {category: invoices, \"match\": {any: [{contains: a}, {'contains': b}]}}
+";
+        assert_eq!(locate(text, "spec.category"), Some((2, 8)));
+        assert_eq!(locate(text, "spec.match.any[1].contains"), Some((2, 60)));
+    }
+
+    #[test]
+    fn test_locate_follows_anchors_and_merge_keys() {
+        let text = "base: &base
+  category: invoices
+  symlinks: &links
+    - target: a
+rule:
+  <<: *base
+  links: *links
+";
+        assert_eq!(locate(text, "rule.category"), Some((2, 3)));
+        assert_eq!(locate(text, "rule.links[0].target"), Some((4, 7)));
+    }
+
+    #[test]
+    fn test_locate_in_multi_document_file() {
+        let content = format!(
+            "{}---\n{}",
+            RULE,
+            RULE.replace("name: invoices", "name: receipts")
+        );
+        let mut diagnostic = Diagnostic::error(DiagnosticCode::InvalidPattern, "bad pattern")
+            .for_resource(ResourceKind::Rule, "receipts", "rules.yaml")
+            .at("spec.category");
+        diagnostic.locate_in(&content);
+
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(22), Some(3)));
+    }
+
+    #[test]
+    fn test_locate_in_file() {
+        let content = format!("# Rules\n---\n{}", RULE);
+        let mut diagnostic = Diagnostic::error(DiagnosticCode::InvalidPattern, "bad pattern")
+            .for_resource(ResourceKind::Rule, "invoices", "rules.yaml")
+            .at("spec.match.all[1].pattern");
+        diagnostic.locate_in(&content);
+
+        assert_eq!(diagnostic.line, Some(12));
+        assert_eq!(diagnostic.column, Some(9));
+    }
+
+    #[test]
+    fn test_field_lines() {
+        let fields = field_lines("kind: Rule\nspec:\n  all:\n    - contains: a\n    - [b]\n");
+        assert_eq!(
+            fields,
+            [
+                ("kind".to_string(), 1),
+                ("spec".to_string(), 2),
+                ("spec.all".to_string(), 3),
+                ("spec.all[0]".to_string(), 4),
+                ("spec.all[0].contains".to_string(), 4),
+                ("spec.all[1]".to_string(), 5),
+                ("spec.all[1][0]".to_string(), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_from_parse_error() {
+        let error = GitOpsError::ParseYaml {
+            path: PathBuf::from("rules/a.yaml"),
+            message: "invalid type".to_string(),
+            line: Some(3),
+            column: Some(5),
+        };
+        let diagnostic = Diagnostic::from(&error);
+
+        assert_eq!(diagnostic.code, DiagnosticCode::ParseError);
+        assert_eq!(diagnostic.path, Some(PathBuf::from("rules/a.yaml")));
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(3), Some(5)));
+        assert!(diagnostic.is_error());
+    }
+}
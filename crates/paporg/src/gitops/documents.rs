@@ -0,0 +1,465 @@
+//! Resource files holding several YAML documents.
+//!
+//! Documents are separated by `---` lines. Documents holding nothing but
+//! comments and blank lines, such as a schema comment before the first
+//! separator, are not resources and are skipped.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+
+use super::diagnostics::field_lines;
+use super::error::{GitOpsError, Result};
+use super::resource::{ResourceHeader, ResourceKind};
+
+/// A document of a resource file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlDocument<'a> {
+    /// Line of the file the document starts at, counting from 0.
+    pub line: usize,
+    /// Byte range of the document in the file.
+    pub range: Range<usize>,
+    pub text: &'a str,
+}
+
+impl YamlDocument<'_> {
+    /// The document preceded by blank lines, so that parse errors report
+    /// line numbers of the whole file.
+    pub fn padded_text(&self) -> String {
+        let mut text = "\n".repeat(self.line);
+        text.push_str(self.text);
+        text
+    }
+
+    /// Parses the kind and name of the document, if it has them.
+    pub fn header(&self) -> Option<ResourceHeader> {
+        serde_yaml::from_str(self.text).ok()
+    }
+}
+
+/// Splits a file into its documents.
+pub fn split_documents(content: &str) -> Vec<YamlDocument<'_>> {
+    let mut documents = Vec::new();
+    let mut start = 0;
+    let mut start_line = 0;
+    let mut offset = 0;
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if is_separator(line) {
+            push_document(content, start..offset, start_line, &mut documents);
+            start = offset + line.len();
+            start_line = index + 1;
+        }
+        offset += line.len();
+    }
+    push_document(content, start..content.len(), start_line, &mut documents);
+
+    documents
+}
+
+fn push_document<'a>(
+    content: &'a str,
+    range: Range<usize>,
+    line: usize,
+    documents: &mut Vec<YamlDocument<'a>>,
+) {
+    let text = &content[range.clone()];
+    let has_content = text.lines().any(|l| {
+        let l = l.trim();
+        !l.is_empty() && !l.starts_with('#')
+    });
+    if has_content {
+        documents.push(YamlDocument { line, range, text });
+    }
+}
+
+/// Returns the document of a resource in a file.
+pub fn find_document<'a, 'b>(
+    documents: &'b [YamlDocument<'a>],
+    kind: ResourceKind,
+    name: &str,
+) -> Option<&'b YamlDocument<'a>> {
+    documents.iter().find(|d| {
+        d.header()
+            .is_some_and(|h| h.kind == kind && h.metadata.name == name)
+    })
+}
+
+/// Returns the file with a document replaced by `text`.
+pub fn replace_document(content: &str, document: &YamlDocument, text: &str) -> String {
+    format!(
+        "{}{}{}",
+        &content[..document.range.start],
+        text,
+        &content[document.range.end..]
+    )
+}
+
+/// Returns the document with the version on its `apiVersion` line
+/// replaced, keeping the rest of the text, or None if it has no such line.
+pub fn replace_api_version(text: &str, from: &str, to: &str) -> Option<String> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if let Some(value) = line.strip_prefix("apiVersion:") {
+            let start = offset + "apiVersion:".len() + value.find(from)?;
+            return Some(format!(
+                "{}{}{}",
+                &text[..start],
+                to,
+                &text[start + from.len()..]
+            ));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Returns `rewritten`, a document written anew from the value of
+/// `original`, with the comments of `original` put back. A comment stays
+/// with the field on or below it, or with the closest parent of that field
+/// still in the document. Comments after the last field end the document.
+pub fn carry_comments(original: &str, rewritten: &str) -> String {
+    // The outermost field starting on each line
+    let mut starts = HashMap::new();
+    for (path, line) in field_lines(original) {
+        starts.entry(line).or_insert(path);
+    }
+
+    // Comments on their own lines, with the field below them, and comments
+    // at the end of a line, with the field on it
+    let mut above: Vec<(Option<String>, Vec<String>)> = Vec::new();
+    let mut after: Vec<(Option<String>, String)> = Vec::new();
+    let mut pending = Vec::new();
+    let mut field: Option<String> = None;
+    let mut block_scalar: Option<usize> = None;
+    for (index, line) in original.lines().enumerate() {
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+        if let Some(block_indent) = block_scalar {
+            if trimmed.is_empty() || indent > block_indent {
+                continue;
+            }
+            block_scalar = None;
+        }
+        if trimmed.starts_with('#') {
+            pending.push(trimmed.to_string());
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(path) = starts.get(&(index + 1)) {
+            if !pending.is_empty() {
+                above.push((Some(path.clone()), std::mem::take(&mut pending)));
+            }
+            field = Some(path.clone());
+        }
+        let content = match comment_start(line) {
+            Some(start) => {
+                after.push((field.clone(), line[start..].trim_end().to_string()));
+                &line[..start]
+            }
+            None => line,
+        };
+        // The lines of `key: |` and `key: >` scalars cannot hold comments
+        let value = content
+            .trim_end()
+            .trim_end_matches(|c: char| c.is_ascii_digit() || c == '+' || c == '-');
+        if value.ends_with(['|', '>']) && value[..value.len() - 1].ends_with([' ', ':']) {
+            block_scalar = Some(indent);
+        }
+    }
+
+    let lines: HashMap<String, usize> = field_lines(rewritten).into_iter().rev().collect();
+    let find = |path: &Option<String>| {
+        let mut path = path.as_deref()?;
+        loop {
+            if let Some(&line) = lines.get(path) {
+                return Some(line);
+            }
+            path = match path.strip_suffix(']') {
+                Some(indexed) => &indexed[..indexed.rfind('[')?],
+                None => &path[..path.rfind('.')?],
+            };
+        }
+    };
+
+    let mut header = Vec::new();
+    let mut before_line: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    let mut after_line: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for (path, comments) in above {
+        match find(&path) {
+            Some(line) => before_line.entry(line).or_default().extend(comments),
+            None => header.extend(comments),
+        }
+    }
+    for (path, comment) in after {
+        match find(&path) {
+            Some(line) => after_line.entry(line).or_default().push(comment),
+            None => header.push(comment),
+        }
+    }
+
+    let mut result = String::new();
+    for comment in header {
+        result.push_str(&comment);
+        result.push('\n');
+    }
+    for (index, line) in rewritten.lines().enumerate() {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        for comment in before_line.get(&(index + 1)).into_iter().flatten() {
+            result.push_str(indent);
+            result.push_str(comment);
+            result.push('\n');
+        }
+        result.push_str(line);
+        for comment in after_line.get(&(index + 1)).into_iter().flatten() {
+            result.push(' ');
+            result.push_str(comment);
+        }
+        result.push('\n');
+    }
+    for comment in pending {
+        result.push_str(&comment);
+        result.push('\n');
+    }
+    result
+}
+
+/// Returns where the comment at the end of a line starts, if it has one.
+fn comment_start(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '#' && previous.is_whitespace() => return Some(index),
+            // Quotes only start a scalar, not in the middle of one
+            None if matches!(c, '"' | '\'')
+                && (previous.is_whitespace() || matches!(previous, '[' | '{' | ',')) =>
+            {
+                quote = Some(c)
+            }
+            None => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// Returns the file with `text` appended as a document of its own.
+pub fn append_document(content: &str, text: &str) -> String {
+    let mut result = content.to_string();
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+    result.push_str("---\n");
+    result.push_str(text);
+    result
+}
+
+/// Returns the file without a document and the separator before it, or
+/// after it for the first document.
+pub fn remove_document(content: &str, document: &YamlDocument) -> String {
+    let before = &content[..document.range.start];
+    let separator_start = before
+        .strip_suffix('\n')
+        .map_or(0, |b| b.rfind('\n').map_or(0, |i| i + 1));
+    if is_separator(&before[separator_start..]) {
+        return format!(
+            "{}{}",
+            &before[..separator_start],
+            &content[document.range.end..]
+        );
+    }
+
+    let after = &content[document.range.end..];
+    let separator_end = after.find('\n').map_or(after.len(), |i| i + 1);
+    if is_separator(&after[..separator_end]) {
+        return format!("{}{}", before, &after[separator_end..]);
+    }
+    format!("{}{}", before, after)
+}
+
+fn is_separator(line: &str) -> bool {
+    let line = line.trim_end();
+    line == "---" || line.starts_with("--- ")
+}
+
+/// A resource document read from the config directory, possibly patched by
+/// an overlay.
+#[derive(Debug, Clone)]
+pub(crate) struct Document {
+    /// The file path relative to the config directory.
+    pub path: PathBuf,
+    pub source: DocumentSource,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum DocumentSource {
+    /// The document as written, padded to its line in the file.
+    Text(String),
+    /// The document merged with overlay patches.
+    Value(serde_yaml::Value),
+}
+
+impl Document {
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T> {
+        let result = match &self.source {
+            DocumentSource::Text(text) => serde_yaml::from_str(text),
+            DocumentSource::Value(value) => serde_yaml::from_value(value.clone()),
+        };
+        result.map_err(|e| GitOpsError::ParseYaml {
+            path: self.path.clone(),
+            message: e.to_string(),
+            line: e.location().map(|l| l.line()),
+            column: e.location().map(|l| l.column()),
+        })
+    }
+
+    /// The document as a YAML value, for merging overlay patches into it.
+    pub fn value(&self) -> Result<serde_yaml::Value> {
+        match &self.source {
+            DocumentSource::Text(_) => self.deserialize(),
+            DocumentSource::Value(value) => Ok(value.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_documents() {
+        let content = "# yaml-language-server: $schema=x\n\
+                       ---\n\
+                       kind: Variable\n\
+                       ---   \n\
+                       \n\
+                       # only a comment\n\
+                       --- # second\n\
+                       kind: Rule\n\
+                       name: a\n";
+        let documents = split_documents(content);
+
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].text, "kind: Variable\n");
+        assert_eq!(documents[0].line, 2);
+        assert_eq!(documents[1].text, "kind: Rule\nname: a\n");
+        assert_eq!(documents[1].line, 7);
+        assert_eq!(&content[documents[1].range.clone()], documents[1].text);
+        assert!(documents[1].padded_text().starts_with("\n\n\n\n\n\n\nkind"));
+
+        // A file without separators is one document
+        let documents = split_documents("kind: Settings\n");
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[0].range, 0..15);
+        assert!(split_documents("# nothing\n").is_empty());
+    }
+
+    #[test]
+    fn test_edit_documents() {
+        let content = "# schema\n\
+                       apiVersion: paporg.io/v1\n\
+                       kind: Variable\n\
+                       metadata:\n  name: a\n\
+                       ---\n\
+                       apiVersion: paporg.io/v1\n\
+                       kind: Variable\n\
+                       metadata:\n  name: b\n";
+        let documents = split_documents(content);
+        let b = find_document(&documents, ResourceKind::Variable, "b").unwrap();
+        assert!(find_document(&documents, ResourceKind::Rule, "b").is_none());
+
+        assert_eq!(
+            replace_document(content, b, "kind: Rule\n"),
+            "# schema\napiVersion: paporg.io/v1\nkind: Variable\nmetadata:\n  name: a\n\
+             ---\nkind: Rule\n"
+        );
+        assert_eq!(
+            remove_document(content, b),
+            "# schema\napiVersion: paporg.io/v1\nkind: Variable\nmetadata:\n  name: a\n"
+        );
+        assert_eq!(
+            remove_document(content, &documents[0]),
+            "apiVersion: paporg.io/v1\nkind: Variable\nmetadata:\n  name: b\n"
+        );
+        assert_eq!(
+            append_document("kind: Rule", "kind: Variable\n"),
+            "kind: Rule\n---\nkind: Variable\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_api_version() {
+        assert_eq!(
+            replace_api_version(
+                "# old\napiVersion: \"paporg.io/v1beta1\" # quoted\nkind: Rule\n",
+                "paporg.io/v1beta1",
+                "paporg.io/v1"
+            )
+            .unwrap(),
+            "# old\napiVersion: \"paporg.io/v1\" # quoted\nkind: Rule\n"
+        );
+        assert!(replace_api_version("kind: Rule\n", "paporg.io/v1beta1", "paporg.io/v1").is_none());
+    }
+
+    #[test]
+    fn test_comment_start() {
+        assert_eq!(comment_start("# schema"), Some(0));
+        assert_eq!(comment_start("kind: Rule # a rule"), Some(11));
+        assert_eq!(comment_start("tags: ['a', \"b\"] # tags"), Some(17));
+        assert_eq!(comment_start("kind: Rule"), None);
+        assert_eq!(comment_start("pattern: \"\\\" #1\""), None);
+        assert_eq!(comment_start("id: 'No. #2'"), None);
+        assert_eq!(comment_start("url: a.io/#top"), None);
+    }
+
+    #[test]
+    fn test_carry_comments() {
+        let original = "# Receipts\n\
+                        apiVersion: paporg.io/v1beta1 # old\n\
+                        kind: Rule\n\
+                        spec:\n  \
+                          match:\n    \
+                            # either word\n    \
+                            any: [Receipt, Quittung] # German too\n  \
+                          output:\n    \
+                            dir: Receipts # by year?\n    \
+                            script: |\n      \
+                              # not a comment\n\
+                        # end\n";
+        let rewritten = "apiVersion: paporg.io/v1\n\
+                         kind: Rule\n\
+                         spec:\n  \
+                           match:\n    \
+                             any:\n    \
+                             - Receipt\n    \
+                             - Quittung\n  \
+                           output:\n    \
+                             directory: Receipts\n";
+        assert_eq!(
+            carry_comments(original, rewritten),
+            "# Receipts\n\
+             apiVersion: paporg.io/v1 # old\n\
+             kind: Rule\n\
+             spec:\n  \
+               match:\n    \
+                 # either word\n    \
+                 any: # German too\n    \
+                 - Receipt\n    \
+                 - Quittung\n  \
+               output: # by year?\n    \
+                 directory: Receipts\n\
+             # end\n"
+        );
+    }
+}
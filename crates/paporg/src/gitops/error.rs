@@ -3,6 +3,8 @@
 use std::path::PathBuf;
 use thiserror::Error;
 
+use super::diagnostics::Diagnostic;
+
 /// Errors that can occur during GitOps operations.
 #[derive(Error, Debug)]
 pub enum GitOpsError {
@@ -28,7 +30,13 @@ pub enum GitOpsError {
     },
 
     #[error("Failed to parse YAML in '{path}': {message}")]
-    ParseYaml { path: PathBuf, message: String },
+    ParseYaml {
+        path: PathBuf,
+        message: String,
+        /// Line of the error, counting from 1.
+        line: Option<usize>,
+        column: Option<usize>,
+    },
 
     #[error("Failed to serialize YAML: {0}")]
     SerializeYaml(String),
@@ -45,8 +53,8 @@ pub enum GitOpsError {
     #[error("Missing required resource: {0}")]
     MissingRequired(String),
 
-    #[error("Validation error: {0}")]
-    Validation(String),
+    #[error("Validation error: {}", join_errors(.0))]
+    Validation(Vec<Diagnostic>),
 
     #[error("Invalid API version '{version}', expected '{expected}'")]
     InvalidApiVersion { version: String, expected: String },
@@ -57,6 +65,22 @@ pub enum GitOpsError {
     #[error("Duplicate resource name '{name}' for kind '{kind}'")]
     DuplicateName { kind: String, name: String },
 
+    #[error("{kind} '{name}' extends unknown rule or template '{parent}'")]
+    UnknownParent {
+        kind: String,
+        name: String,
+        parent: String,
+    },
+
+    #[error("Rule inheritance cycle: {0}")]
+    InheritanceCycle(String),
+
+    #[error("Invalid lookup file '{path}': {message}")]
+    InvalidLookupFile { path: PathBuf, message: String },
+
+    #[error("Invalid overlay '{name}': {message}")]
+    InvalidOverlay { name: String, message: String },
+
     #[error("Invalid regex pattern '{pattern}': {reason}")]
     InvalidPattern { pattern: String, reason: String },
 
@@ -102,6 +126,8 @@ impl From<serde_yaml::Error> for GitOpsError {
         GitOpsError::ParseYaml {
             path: PathBuf::new(),
             message: err.to_string(),
+            line: err.location().map(|l| l.line()),
+            column: err.location().map(|l| l.column()),
         }
     }
 }
@@ -120,6 +146,14 @@ impl GitOpsError {
             GitOpsError::GitNetworkError(_) | GitOpsError::GitTimeout(_)
         )
     }
+
+    /// Returns the diagnostics describing the error, one per problem.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            GitOpsError::Validation(diagnostics) => diagnostics.clone(),
+            _ => vec![Diagnostic::from(self)],
+        }
+    }
 }
 
 /// Classifies a git stderr string into a more specific error variant.
@@ -152,5 +186,15 @@ pub fn classify_git_error(stderr: &str) -> GitOpsError {
     GitOpsError::GitOperation(stderr.trim().to_string())
 }
 
+/// Joins the messages of the error diagnostics.
+fn join_errors(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Result type for GitOps operations.
 pub type Result<T> = std::result::Result<T, GitOpsError>;
@@ -0,0 +1,296 @@
+//! Resolves rules extending other rules or rule templates.
+//!
+//! A rule inherits the category and each output template of its parent
+//! unless it sets its own, adds its symlinks to the inherited ones, and
+//! only matches documents the parent's match condition accepts as well.
+
+use std::collections::HashMap;
+
+use super::error::{GitOpsError, Result};
+use super::resource::{
+    CompoundMatch, MatchCondition, OutputSettings, ResourceKind, ResourceWithPath, RuleResource,
+    RuleSpec, RuleTemplateResource, RuleTemplateSpec,
+};
+
+/// Returns the rules with inheritance resolved, in the given order.
+///
+/// Fails if a rule or template extends an unknown name or if `extends`
+/// forms a cycle.
+pub fn resolve_rules(
+    rules: &[ResourceWithPath<RuleResource>],
+    templates: &[ResourceWithPath<RuleTemplateResource>],
+) -> Result<Vec<ResourceWithPath<RuleResource>>> {
+    let mut resolver = Resolver {
+        rules: rules
+            .iter()
+            .map(|r| (r.resource.metadata.name.as_str(), &r.resource.spec))
+            .collect(),
+        templates: templates
+            .iter()
+            .map(|t| (t.resource.metadata.name.as_str(), &t.resource.spec))
+            .collect(),
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+    };
+
+    rules
+        .iter()
+        .map(|rule| {
+            let spec = resolver.resolve(ResourceKind::Rule, &rule.resource.metadata.name)?;
+            let mut resource = rule.resource.clone();
+            resource.spec = spec;
+            Ok(ResourceWithPath::new(resource, rule.path.clone()))
+        })
+        .collect()
+}
+
+/// Merges a child spec into the effective spec of its parent.
+pub fn merge_rule_spec(parent: &RuleSpec, child: &RuleSpec) -> RuleSpec {
+    let category = if child.category.is_empty() {
+        parent.category.clone()
+    } else {
+        child.category.clone()
+    };
+
+    let output = OutputSettings {
+        directory: if child.output.directory.is_empty() {
+            parent.output.directory.clone()
+        } else {
+            child.output.directory.clone()
+        },
+        filename: if child.output.filename.is_empty() {
+            parent.output.filename.clone()
+        } else {
+            child.output.filename.clone()
+        },
+    };
+
+    let mut symlinks = parent.symlinks.clone();
+    for symlink in &child.symlinks {
+        if !symlinks.iter().any(|s| s.target == symlink.target) {
+            symlinks.push(symlink.clone());
+        }
+    }
+
+    let match_condition = if parent.match_condition.is_empty() {
+        child.match_condition.clone()
+    } else if child.match_condition.is_empty() {
+        parent.match_condition.clone()
+    } else {
+        MatchCondition::Compound(CompoundMatch {
+            all: Some(vec![
+                parent.match_condition.clone(),
+                child.match_condition.clone(),
+            ]),
+            ..Default::default()
+        })
+    };
+
+    RuleSpec {
+        priority: child.priority,
+        extends: child.extends.clone(),
+        category,
+        exclusive: child.exclusive,
+        match_condition,
+        output,
+        symlinks,
+    }
+}
+
+/// A template as the spec of a rule, so rules and templates merge alike.
+fn template_as_rule_spec(template: &RuleTemplateSpec) -> RuleSpec {
+    RuleSpec {
+        priority: 0,
+        extends: template.extends.clone(),
+        category: template.category.clone(),
+        exclusive: true,
+        match_condition: template.match_condition.clone(),
+        output: template.output.clone(),
+        symlinks: template.symlinks.clone(),
+    }
+}
+
+struct Resolver<'a> {
+    rules: HashMap<&'a str, &'a RuleSpec>,
+    templates: HashMap<&'a str, &'a RuleTemplateSpec>,
+    resolved: HashMap<(ResourceKind, &'a str), RuleSpec>,
+    /// Rules and templates whose parents are being resolved.
+    stack: Vec<(ResourceKind, &'a str)>,
+}
+
+impl<'a> Resolver<'a> {
+    fn resolve(&mut self, kind: ResourceKind, name: &'a str) -> Result<RuleSpec> {
+        if let Some(spec) = self.resolved.get(&(kind, name)) {
+            return Ok(spec.clone());
+        }
+
+        if let Some(start) = self.stack.iter().position(|entry| *entry == (kind, name)) {
+            let chain: Vec<&str> = self.stack[start..]
+                .iter()
+                .map(|(_, n)| *n)
+                .chain(std::iter::once(name))
+                .collect();
+            return Err(GitOpsError::InheritanceCycle(chain.join(" -> ")));
+        }
+
+        let (spec, extends) = match kind {
+            ResourceKind::RuleTemplate => {
+                let template = self.templates[name];
+                (template_as_rule_spec(template), template.extends.as_deref())
+            }
+            _ => {
+                let rule = self.rules[name];
+                (rule.clone(), rule.extends.as_deref())
+            }
+        };
+
+        let effective = match extends {
+            Some(parent) => {
+                // Rules and templates share one namespace, so the name is unambiguous
+                let parent_kind = if self.rules.contains_key(parent) {
+                    ResourceKind::Rule
+                } else if self.templates.contains_key(parent) {
+                    ResourceKind::RuleTemplate
+                } else {
+                    return Err(GitOpsError::UnknownParent {
+                        kind: kind.to_string(),
+                        name: name.to_string(),
+                        parent: parent.to_string(),
+                    });
+                };
+
+                self.stack.push((kind, name));
+                let parent_spec = self.resolve(parent_kind, parent);
+                self.stack.pop();
+                merge_rule_spec(&parent_spec?, &spec)
+            }
+            None => spec,
+        };
+
+        self.resolved.insert((kind, name), effective.clone());
+        Ok(effective)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gitops::resource::{Resource, SimpleMatch, SymlinkSettings};
+
+    fn contains(value: &str) -> MatchCondition {
+        MatchCondition::Simple(SimpleMatch {
+            contains: Some(value.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn rule(name: &str, extends: Option<&str>, category: &str) -> ResourceWithPath<RuleResource> {
+        let spec = RuleSpec {
+            priority: 10,
+            extends: extends.map(str::to_string),
+            category: category.to_string(),
+            exclusive: true,
+            match_condition: contains(name),
+            output: OutputSettings::default(),
+            symlinks: vec![],
+        };
+        ResourceWithPath::new(
+            Resource::new(ResourceKind::Rule, name, spec),
+            format!("rules/{}.yaml", name),
+        )
+    }
+
+    fn vendor_template() -> ResourceWithPath<RuleTemplateResource> {
+        let spec = RuleTemplateSpec {
+            extends: None,
+            category: "vendors".to_string(),
+            match_condition: MatchCondition::default(),
+            output: OutputSettings {
+                directory: "vendors/$y".to_string(),
+                filename: "$original".to_string(),
+            },
+            symlinks: vec![SymlinkSettings {
+                target: "by-year/$y".to_string(),
+            }],
+        };
+        ResourceWithPath::new(
+            Resource::new(ResourceKind::RuleTemplate, "vendor", spec),
+            "templates/vendor.yaml",
+        )
+    }
+
+    #[test]
+    fn test_rule_extends_template() {
+        let mut acme = rule("acme", Some("vendor"), "");
+        acme.resource.spec.output.filename = "acme-$original".to_string();
+        acme.resource.spec.symlinks = vec![
+            SymlinkSettings {
+                target: "by-year/$y".to_string(),
+            },
+            SymlinkSettings {
+                target: "acme".to_string(),
+            },
+        ];
+
+        let resolved = resolve_rules(&[acme], &[vendor_template()]).unwrap();
+        let spec = &resolved[0].resource.spec;
+
+        assert_eq!(spec.category, "vendors");
+        assert_eq!(spec.priority, 10);
+        assert_eq!(spec.extends.as_deref(), Some("vendor"));
+        assert_eq!(spec.output.directory, "vendors/$y");
+        assert_eq!(spec.output.filename, "acme-$original");
+        let targets: Vec<&str> = spec.symlinks.iter().map(|s| s.target.as_str()).collect();
+        assert_eq!(targets, vec!["by-year/$y", "acme"]);
+        // The template matches nothing itself, so only the rule's condition remains
+        assert!(matches!(
+            &spec.match_condition,
+            MatchCondition::Simple(s) if s.contains.as_deref() == Some("acme")
+        ));
+    }
+
+    #[test]
+    fn test_match_conditions_are_combined() {
+        let base = rule("base", Some("vendor"), "");
+        let child = rule("child", Some("base"), "special");
+
+        let resolved = resolve_rules(&[base, child], &[vendor_template()]).unwrap();
+        let child = &resolved[1].resource.spec;
+
+        assert_eq!(child.category, "special");
+        assert_eq!(child.output.directory, "vendors/$y");
+        let MatchCondition::Compound(compound) = &child.match_condition else {
+            panic!("expected an all condition");
+        };
+        let all = compound.all.as_ref().unwrap();
+        assert_eq!(all.len(), 2);
+        assert!(matches!(
+            &all[0],
+            MatchCondition::Simple(s) if s.contains.as_deref() == Some("base")
+        ));
+    }
+
+    #[test]
+    fn test_unknown_parent() {
+        let err = resolve_rules(&[rule("acme", Some("missing"), "")], &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            GitOpsError::UnknownParent { ref name, ref parent, .. }
+                if name == "acme" && parent == "missing"
+        ));
+    }
+
+    #[test]
+    fn test_inheritance_cycle() {
+        let rules = [
+            rule("a", Some("b"), "A"),
+            rule("b", Some("c"), "B"),
+            rule("c", Some("a"), "C"),
+        ];
+        let err = resolve_rules(&rules, &[]).unwrap_err();
+        assert_eq!(err.to_string(), "Rule inheritance cycle: a -> b -> c -> a");
+
+        let err = resolve_rules(&[rule("self", Some("self"), "S")], &[]).unwrap_err();
+        assert!(matches!(err, GitOpsError::InheritanceCycle(_)));
+    }
+}
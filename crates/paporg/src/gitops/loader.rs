@@ -1,22 +1,44 @@
 //! Configuration loader for multi-file YAML configurations.
 
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::config::schema::{
-    CompoundMatch as LegacyCompoundMatch, Config as LegacyConfig, DefaultsConfig,
-    ExtractedVariable, MatchCondition as LegacyMatchCondition, OcrConfig, OutputConfig,
-    Rule as LegacyRule, SimpleMatch as LegacySimpleMatch, SymlinkConfig,
-    VariableTransform as LegacyTransform, VariablesConfig,
+    CategorizationConfig, CategorizationMode as LegacyCategorizationMode, ClassifierConfig,
+    CompareValue as LegacyCompareValue, CompoundMatch as LegacyCompoundMatch, ComputedVariable,
+    Config as LegacyConfig, Correspondent as LegacyCorrespondent, DefaultsConfig,
+    ExtractedVariable, FuzzyMatch as LegacyFuzzyMatch, LimitsConfig,
+    LookupEntry as LegacyLookupEntry, LookupMode as LegacyLookupMode,
+    LookupTable as LegacyLookupTable, MatchCondition as LegacyMatchCondition,
+    MatchField as LegacyMatchField, OcrConfig, OutputConfig, PagePosition as LegacyPagePosition,
+    PageRegion as LegacyPageRegion, PageSelection as LegacyPageSelection, Rule as LegacyRule,
+    SimpleMatch as LegacySimpleMatch, SymlinkConfig, VariableMatch as LegacyVariableMatch,
+    VariableSource as LegacyVariableSource, VariableTransform as LegacyTransform, VariablesConfig,
 };
 
+use super::diagnostics::{Diagnostic, Severity};
+use super::documents::{
+    append_document, carry_comments, find_document, remove_document, replace_api_version,
+    replace_document, split_documents, Document, DocumentSource,
+};
 use super::error::{GitOpsError, Result};
+use super::inheritance::resolve_rules;
+use super::lookup::read_lookup_file;
+use super::overlay::{
+    apply_overlay, overlay_directory, select_overlay, upgrade_patch, AppliedOverlay, FieldOrigin,
+    OVERLAYS_DIRECTORY,
+};
 use super::resource::{
-    AnyResource, ImportSourceResource, MatchCondition, ResourceHeader, ResourceKind,
-    ResourceWithPath, RuleResource, SettingsResource, VariableResource, VariableTransform,
-    API_VERSION,
+    AnyResource, CategorizationMode, CompareValue, CorrespondentResource, ImportSourceResource,
+    LookupEntry, LookupMode, LookupTableResource, MatchCondition, MatchField, PagePosition,
+    PageRegion, PageSelection, ResourceHeader, ResourceKind, ResourceWithPath, RuleResource,
+    RuleTemplateResource, RuleTestResource, SettingsResource, VariableMatch, VariableResource,
+    VariableSource, VariableTransform, API_VERSION,
 };
+use super::schema::{check_resource, schema_url};
+use super::version::ConversionRegistry;
 
 /// Loaded configuration from the config directory.
 #[derive(Debug, Clone)]
@@ -25,13 +47,48 @@ pub struct LoadedConfig {
     pub settings: ResourceWithPath<SettingsResource>,
     /// All variable resources.
     pub variables: Vec<ResourceWithPath<VariableResource>>,
-    /// All rule resources.
+    /// All rule resources, with inheritance resolved.
     pub rules: Vec<ResourceWithPath<RuleResource>>,
     /// All import source resources.
     pub import_sources: Vec<ResourceWithPath<ImportSourceResource>>,
+    /// All rule test resources.
+    pub rule_tests: Vec<ResourceWithPath<RuleTestResource>>,
+    /// All rule template resources.
+    pub rule_templates: Vec<ResourceWithPath<RuleTemplateResource>>,
+    /// All rule resources as written, in the order of `rules`.
+    pub authored_rules: Vec<ResourceWithPath<RuleResource>>,
+    /// All correspondent resources.
+    pub correspondents: Vec<ResourceWithPath<CorrespondentResource>>,
+    /// All lookup table resources.
+    pub lookup_tables: Vec<ResourceWithPath<LookupTableResource>>,
+    /// Rows of the CSV files of lookup tables, by table name.
+    pub lookup_file_entries: HashMap<String, Vec<LookupEntry>>,
+    /// The overlay the resources were patched with.
+    pub overlay: Option<AppliedOverlay>,
+    /// Schema violations of resources that loaded nonetheless, such as
+    /// unknown fields.
+    pub warnings: Vec<Diagnostic>,
 }
 
 impl LoadedConfig {
+    /// Creates a configuration with only the settings resource.
+    pub fn new(settings: ResourceWithPath<SettingsResource>) -> Self {
+        Self {
+            settings,
+            variables: Vec::new(),
+            rules: Vec::new(),
+            import_sources: Vec::new(),
+            rule_tests: Vec::new(),
+            rule_templates: Vec::new(),
+            authored_rules: Vec::new(),
+            correspondents: Vec::new(),
+            lookup_tables: Vec::new(),
+            lookup_file_entries: HashMap::new(),
+            overlay: None,
+            warnings: Vec::new(),
+        }
+    }
+
     /// Returns all resources as a flat list.
     pub fn all_resources(&self) -> Vec<(&ResourceKind, &str, &Path)> {
         let mut resources = Vec::new();
@@ -61,26 +118,74 @@ impl LoadedConfig {
                 source.path.as_path(),
             ));
         }
+        for test in &self.rule_tests {
+            resources.push((
+                &ResourceKind::RuleTest,
+                test.resource.metadata.name.as_str(),
+                test.path.as_path(),
+            ));
+        }
+        for template in &self.rule_templates {
+            resources.push((
+                &ResourceKind::RuleTemplate,
+                template.resource.metadata.name.as_str(),
+                template.path.as_path(),
+            ));
+        }
+        for correspondent in &self.correspondents {
+            resources.push((
+                &ResourceKind::Correspondent,
+                correspondent.resource.metadata.name.as_str(),
+                correspondent.path.as_path(),
+            ));
+        }
+        for table in &self.lookup_tables {
+            resources.push((
+                &ResourceKind::LookupTable,
+                table.resource.metadata.name.as_str(),
+                table.path.as_path(),
+            ));
+        }
         resources
     }
 
+    /// Returns a rule as written in its file. Falls back to `rules` for
+    /// configs built without authored rules.
+    pub fn authored_rule(&self, name: &str) -> Option<&ResourceWithPath<RuleResource>> {
+        self.authored_rules
+            .iter()
+            .chain(&self.rules)
+            .find(|r| r.resource.metadata.name == name)
+    }
+
     /// Converts the loaded config to the legacy Config format.
     pub fn to_legacy_config(&self) -> LegacyConfig {
         let settings = &self.settings.resource.spec;
 
-        // Convert variables
-        let extracted: Vec<ExtractedVariable> = self
+        // Convert variables; those with a template are computed
+        let (computed, extracted): (Vec<_>, Vec<_>) = self
             .variables
             .iter()
+            .partition(|v| v.resource.spec.template.is_some());
+        let extracted: Vec<ExtractedVariable> = extracted
+            .into_iter()
             .map(|v| ExtractedVariable {
                 name: v.resource.metadata.name.clone(),
                 pattern: v.resource.spec.pattern.clone(),
-                transform: v.resource.spec.transform.map(|t| match t {
-                    VariableTransform::Slugify => LegacyTransform::Slugify,
-                    VariableTransform::Uppercase => LegacyTransform::Uppercase,
-                    VariableTransform::Lowercase => LegacyTransform::Lowercase,
-                    VariableTransform::Trim => LegacyTransform::Trim,
-                }),
+                transform: v.resource.spec.transform.as_ref().map(convert_transform),
+                default: v.resource.spec.default.clone(),
+                pages: v.resource.spec.pages.as_ref().map(convert_page_selection),
+                region: v.resource.spec.region.map(convert_page_region),
+                from: v.resource.spec.from.map(convert_variable_source),
+            })
+            .collect();
+        let computed: Vec<ComputedVariable> = computed
+            .into_iter()
+            .map(|v| ComputedVariable {
+                name: v.resource.metadata.name.clone(),
+                template: v.resource.spec.template.clone().unwrap_or_default(),
+                pattern: Some(v.resource.spec.pattern.clone()).filter(|p| !p.is_empty()),
+                transform: v.resource.spec.transform.as_ref().map(convert_transform),
                 default: v.resource.spec.default.clone(),
             })
             .collect();
@@ -93,6 +198,7 @@ impl LoadedConfig {
                 id: r.resource.metadata.name.clone(),
                 name: r.resource.metadata.name.clone(),
                 priority: r.resource.spec.priority,
+                exclusive: r.resource.spec.exclusive,
                 match_condition: convert_match_condition(&r.resource.spec.match_condition),
                 category: r.resource.spec.category.clone(),
                 output: OutputConfig {
@@ -111,6 +217,52 @@ impl LoadedConfig {
             })
             .collect();
 
+        // Convert correspondents
+        let correspondents: Vec<LegacyCorrespondent> = self
+            .correspondents
+            .iter()
+            .map(|c| {
+                let name = &c.resource.metadata.name;
+                let spec = &c.resource.spec;
+                LegacyCorrespondent {
+                    id: name.clone(),
+                    name: spec.display_name_or(name).to_string(),
+                    aliases: spec.aliases.clone(),
+                    ibans: spec.ibans.clone(),
+                    vat_numbers: spec.vat_numbers.clone(),
+                    email_domains: spec.email_domains.clone(),
+                    default_category: spec.default_category.clone(),
+                }
+            })
+            .collect();
+
+        // Convert lookup tables, inline entries before those of the CSV file
+        let lookup_tables: Vec<LegacyLookupTable> = self
+            .lookup_tables
+            .iter()
+            .map(|t| {
+                let name = &t.resource.metadata.name;
+                let spec = &t.resource.spec;
+                LegacyLookupTable {
+                    name: name.clone(),
+                    mode: match spec.mode {
+                        LookupMode::Exact => LegacyLookupMode::Exact,
+                        LookupMode::Regex => LegacyLookupMode::Regex,
+                    },
+                    entries: spec
+                        .entries
+                        .iter()
+                        .chain(self.lookup_file_entries.get(name).into_iter().flatten())
+                        .map(|e| LegacyLookupEntry {
+                            key: e.key.clone(),
+                            value: e.value.clone(),
+                        })
+                        .collect(),
+                    fallback: spec.fallback.clone(),
+                }
+            })
+            .collect();
+
         LegacyConfig {
             version: "1.0".to_string(),
             input_directory: settings.input_directory.clone(),
@@ -121,8 +273,36 @@ impl LoadedConfig {
                 languages: settings.ocr.languages.clone(),
                 dpi: settings.ocr.dpi,
             },
-            variables: VariablesConfig { extracted },
+            limits: LimitsConfig {
+                max_file_size_mb: settings.limits.max_file_size_mb,
+                max_pages: settings.limits.max_pages,
+                job_timeout_secs: settings.limits.job_timeout_secs,
+                ocr_page_timeout_secs: settings.limits.ocr_page_timeout_secs,
+                max_archive_entries: settings.limits.max_archive_entries,
+                max_archive_size_mb: settings.limits.max_archive_size_mb,
+                max_archive_depth: settings.limits.max_archive_depth,
+            },
+            categorization: CategorizationConfig {
+                mode: match settings.categorization.mode {
+                    CategorizationMode::FirstMatch => LegacyCategorizationMode::FirstMatch,
+                    CategorizationMode::Scoring => LegacyCategorizationMode::Scoring,
+                },
+                threshold: settings.categorization.threshold,
+                alternatives: settings.categorization.alternatives,
+                classifier: ClassifierConfig {
+                    enabled: settings.categorization.classifier.enabled,
+                    auto_file: settings.categorization.classifier.auto_file,
+                    threshold: settings.categorization.classifier.threshold,
+                    min_examples: settings.categorization.classifier.min_examples,
+                },
+            },
+            variables: VariablesConfig {
+                extracted,
+                computed,
+            },
             rules,
+            correspondents,
+            lookup_tables,
             defaults: DefaultsConfig {
                 output: OutputConfig {
                     directory: settings.defaults.output.directory.clone(),
@@ -147,7 +327,21 @@ fn convert_match_condition(cond: &MatchCondition) -> LegacyMatchCondition {
             contains_any: s.contains_any.clone(),
             contains_all: s.contains_all.clone(),
             pattern: s.pattern.clone(),
+            fuzzy: s.fuzzy.as_ref().map(|f| LegacyFuzzyMatch {
+                value: f.value.clone(),
+                max_distance: f.max_distance,
+            }),
+            contains_any_fuzzy: s.contains_any_fuzzy.clone(),
+            variable: s
+                .variable
+                .as_ref()
+                .map(|v| Box::new(convert_variable_match(v))),
+            correspondent: s.correspondent.clone(),
             case_sensitive: s.case_sensitive,
+            field: s.field.map(convert_match_field),
+            pages: s.pages.as_ref().map(convert_page_selection),
+            region: s.region.map(convert_page_region),
+            weight: s.weight,
         }),
         MatchCondition::Compound(c) => LegacyMatchCondition::Compound(LegacyCompoundMatch {
             all: c
@@ -160,14 +354,101 @@ fn convert_match_condition(cond: &MatchCondition) -> LegacyMatchCondition {
                 .map(|v| v.iter().map(convert_match_condition).collect()),
             not: c.not.as_ref().map(|n| Box::new(convert_match_condition(n))),
             case_sensitive: c.case_sensitive,
+            field: c.field.map(convert_match_field),
+            pages: c.pages.as_ref().map(convert_page_selection),
+            region: c.region.map(convert_page_region),
+            weight: c.weight,
         }),
     }
 }
 
+fn convert_variable_match(variable: &VariableMatch) -> LegacyVariableMatch {
+    LegacyVariableMatch {
+        name: variable.name.clone(),
+        eq: variable.eq.as_ref().map(convert_compare_value),
+        gt: variable.gt.as_ref().map(convert_compare_value),
+        lt: variable.lt.as_ref().map(convert_compare_value),
+        between: variable
+            .between
+            .as_ref()
+            .map(|[low, high]| [convert_compare_value(low), convert_compare_value(high)]),
+        exists: variable.exists,
+        one_of: variable
+            .one_of
+            .as_ref()
+            .map(|values| values.iter().map(convert_compare_value).collect()),
+    }
+}
+
+fn convert_compare_value(value: &CompareValue) -> LegacyCompareValue {
+    match value {
+        CompareValue::Number(n) => LegacyCompareValue::Number(*n),
+        CompareValue::Text(s) => LegacyCompareValue::Text(s.clone()),
+    }
+}
+
+fn convert_match_field(field: MatchField) -> LegacyMatchField {
+    match field {
+        MatchField::Text => LegacyMatchField::Text,
+        MatchField::Filename => LegacyMatchField::Filename,
+        MatchField::Source => LegacyMatchField::Source,
+        MatchField::MimeType => LegacyMatchField::MimeType,
+        MatchField::EmailFrom => LegacyMatchField::EmailFrom,
+        MatchField::EmailSubject => LegacyMatchField::EmailSubject,
+        MatchField::EmailTo => LegacyMatchField::EmailTo,
+        MatchField::FirstPage => LegacyMatchField::FirstPage,
+    }
+}
+
+fn convert_page_selection(pages: &PageSelection) -> LegacyPageSelection {
+    match pages {
+        PageSelection::Position(PagePosition::First) => {
+            LegacyPageSelection::Position(LegacyPagePosition::First)
+        }
+        PageSelection::Position(PagePosition::Last) => {
+            LegacyPageSelection::Position(LegacyPagePosition::Last)
+        }
+        PageSelection::Numbers(numbers) => LegacyPageSelection::Numbers(numbers.clone()),
+    }
+}
+
+fn convert_page_region(region: PageRegion) -> LegacyPageRegion {
+    match region {
+        PageRegion::Top(lines) => LegacyPageRegion::Top(lines),
+        PageRegion::Bottom(lines) => LegacyPageRegion::Bottom(lines),
+    }
+}
+
+fn convert_transform(transform: &VariableTransform) -> LegacyTransform {
+    match transform {
+        VariableTransform::Slugify => LegacyTransform::Slugify,
+        VariableTransform::Uppercase => LegacyTransform::Uppercase,
+        VariableTransform::Lowercase => LegacyTransform::Lowercase,
+        VariableTransform::Trim => LegacyTransform::Trim,
+        VariableTransform::Lookup(table) => LegacyTransform::Lookup(table.clone()),
+    }
+}
+
+fn convert_variable_source(source: VariableSource) -> LegacyVariableSource {
+    match source {
+        VariableSource::Text => LegacyVariableSource::Text,
+        VariableSource::Filename => LegacyVariableSource::Filename,
+        VariableSource::SourcePath => LegacyVariableSource::SourcePath,
+        VariableSource::EmailSubject => LegacyVariableSource::EmailSubject,
+        VariableSource::EmailFrom => LegacyVariableSource::EmailFrom,
+        VariableSource::PdfMetadata => LegacyVariableSource::PdfMetadata,
+    }
+}
+
 /// Configuration loader for the GitOps system.
 pub struct ConfigLoader {
     /// Root directory for configuration files.
     config_dir: PathBuf,
+    /// Overlay to apply instead of the one selected by the environment or
+    /// settings.
+    overlay: Option<String>,
+    /// Conversions of documents written in older API versions.
+    conversions: ConversionRegistry,
 }
 
 impl ConfigLoader {
@@ -175,9 +456,24 @@ impl ConfigLoader {
     pub fn new(config_dir: impl Into<PathBuf>) -> Self {
         Self {
             config_dir: config_dir.into(),
+            overlay: None,
+            conversions: ConversionRegistry::default(),
         }
     }
 
+    /// Applies the given overlay of the `overlays` directory.
+    pub fn with_overlay(mut self, name: impl Into<String>) -> Self {
+        self.overlay = Some(name.into());
+        self
+    }
+
+    /// Converts documents of older API versions with the given conversions
+    /// instead of the built-in ones.
+    pub fn with_conversions(mut self, conversions: ConversionRegistry) -> Self {
+        self.conversions = conversions;
+        self
+    }
+
     /// Returns the config directory path.
     pub fn config_dir(&self) -> &Path {
         &self.config_dir
@@ -193,46 +489,21 @@ impl ConfigLoader {
         let mut variables: Vec<ResourceWithPath<VariableResource>> = Vec::new();
         let mut rules: Vec<ResourceWithPath<RuleResource>> = Vec::new();
         let mut import_sources: Vec<ResourceWithPath<ImportSourceResource>> = Vec::new();
+        let mut rule_tests: Vec<ResourceWithPath<RuleTestResource>> = Vec::new();
+        let mut rule_templates: Vec<ResourceWithPath<RuleTemplateResource>> = Vec::new();
+        let mut correspondents: Vec<ResourceWithPath<CorrespondentResource>> = Vec::new();
+        let mut lookup_tables: Vec<ResourceWithPath<LookupTableResource>> = Vec::new();
 
-        // Walk the config directory
-        for entry in WalkDir::new(&self.config_dir)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-
-            // Only process YAML files
-            if !path.is_file() {
-                continue;
-            }
-
-            // Skip files in hidden directories or hidden files themselves
-            // Check the relative path for any component starting with '.'
-            if let Ok(relative) = path.strip_prefix(&self.config_dir) {
-                let has_hidden_component = relative.components().any(|c| {
-                    c.as_os_str()
-                        .to_str()
-                        .map(|s| s.starts_with('.'))
-                        .unwrap_or(false)
-                });
-                if has_hidden_component {
-                    continue;
-                }
-            }
-
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            if ext != "yaml" && ext != "yml" {
-                continue;
-            }
+        // Read all documents, patched by the overlay
+        let mut documents = self.read_documents(&self.config_dir)?;
+        let overlay = self.load_overlay(&mut documents)?;
 
+        let mut warnings = Vec::new();
+        for document in &documents {
             // Parse the resource
-            match self.load_file(path) {
+            match self.parse_document(document, &mut warnings) {
                 Ok(resource) => {
-                    let relative_path = path
-                        .strip_prefix(&self.config_dir)
-                        .unwrap_or(path)
-                        .to_path_buf();
+                    let relative_path = document.path.clone();
 
                     match resource {
                         AnyResource::Settings(r) => {
@@ -283,10 +554,62 @@ impl ConfigLoader {
                             }
                             import_sources.push(ResourceWithPath::new(r, relative_path));
                         }
+                        AnyResource::RuleTest(r) => {
+                            // Check for duplicate names
+                            if rule_tests
+                                .iter()
+                                .any(|t| t.resource.metadata.name == r.metadata.name)
+                            {
+                                return Err(GitOpsError::DuplicateName {
+                                    kind: "RuleTest".to_string(),
+                                    name: r.metadata.name.clone(),
+                                });
+                            }
+                            rule_tests.push(ResourceWithPath::new(r, relative_path));
+                        }
+                        AnyResource::RuleTemplate(r) => {
+                            // Check for duplicate names
+                            if rule_templates
+                                .iter()
+                                .any(|t| t.resource.metadata.name == r.metadata.name)
+                            {
+                                return Err(GitOpsError::DuplicateName {
+                                    kind: "RuleTemplate".to_string(),
+                                    name: r.metadata.name.clone(),
+                                });
+                            }
+                            rule_templates.push(ResourceWithPath::new(r, relative_path));
+                        }
+                        AnyResource::Correspondent(r) => {
+                            // Check for duplicate names
+                            if correspondents
+                                .iter()
+                                .any(|c| c.resource.metadata.name == r.metadata.name)
+                            {
+                                return Err(GitOpsError::DuplicateName {
+                                    kind: "Correspondent".to_string(),
+                                    name: r.metadata.name.clone(),
+                                });
+                            }
+                            correspondents.push(ResourceWithPath::new(r, relative_path));
+                        }
+                        AnyResource::LookupTable(r) => {
+                            // Check for duplicate names
+                            if lookup_tables
+                                .iter()
+                                .any(|t| t.resource.metadata.name == r.metadata.name)
+                            {
+                                return Err(GitOpsError::DuplicateName {
+                                    kind: "LookupTable".to_string(),
+                                    name: r.metadata.name.clone(),
+                                });
+                            }
+                            lookup_tables.push(ResourceWithPath::new(r, relative_path));
+                        }
                     }
                 }
                 Err(e) => {
-                    log::warn!("Failed to load {}: {}", path.display(), e);
+                    log::warn!("Failed to load {}: {}", document.path.display(), e);
                     return Err(e);
                 }
             }
@@ -301,14 +624,148 @@ impl ConfigLoader {
         // Sort import sources by name
         import_sources.sort_by(|a, b| a.resource.metadata.name.cmp(&b.resource.metadata.name));
 
+        // Sort rule tests by name
+        rule_tests.sort_by(|a, b| a.resource.metadata.name.cmp(&b.resource.metadata.name));
+
+        // Sort rule templates by name
+        rule_templates.sort_by(|a, b| a.resource.metadata.name.cmp(&b.resource.metadata.name));
+
+        // Sort correspondents by name
+        correspondents.sort_by(|a, b| a.resource.metadata.name.cmp(&b.resource.metadata.name));
+
+        // Sort lookup tables by name
+        lookup_tables.sort_by(|a, b| a.resource.metadata.name.cmp(&b.resource.metadata.name));
+
+        // Read the CSV files of lookup tables
+        let mut lookup_file_entries = HashMap::new();
+        for table in &lookup_tables {
+            if let Some(file) = &table.resource.spec.file {
+                let entries = read_lookup_file(&self.config_dir, file)?;
+                lookup_file_entries.insert(table.resource.metadata.name.clone(), entries);
+            }
+        }
+
+        // `extends` names either a rule or a template, so they share one namespace
+        if let Some(template) = rule_templates.iter().find(|t| {
+            rules
+                .iter()
+                .any(|r| r.resource.metadata.name == t.resource.metadata.name)
+        }) {
+            return Err(GitOpsError::DuplicateName {
+                kind: "Rule and RuleTemplate".to_string(),
+                name: template.resource.metadata.name.clone(),
+            });
+        }
+
+        // Resolve inheritance
+        let authored_rules = rules;
+        let rules = resolve_rules(&authored_rules, &rule_templates)?;
+
         Ok(LoadedConfig {
             settings,
             variables,
             rules,
             import_sources,
+            rule_tests,
+            rule_templates,
+            authored_rules,
+            correspondents,
+            lookup_tables,
+            lookup_file_entries,
+            overlay,
+            warnings,
         })
     }
 
+    /// Reads the documents of the YAML files in a directory, skipping
+    /// hidden files and the overlays of the config directory.
+    fn read_documents(&self, dir: &Path) -> Result<Vec<Document>> {
+        let mut documents = Vec::new();
+        for (path, relative_path) in self.yaml_files(dir, false) {
+            let content =
+                fs::read_to_string(&path).map_err(|e| GitOpsError::ReadFile { path, source: e })?;
+            documents.extend(split_documents(&content).iter().map(|d| Document {
+                path: relative_path.clone(),
+                source: DocumentSource::Text(d.padded_text()),
+            }));
+        }
+
+        Ok(documents)
+    }
+
+    /// Returns the YAML files in a directory with their path relative to
+    /// the config directory, skipping hidden files.
+    fn yaml_files(&self, dir: &Path, include_overlays: bool) -> Vec<(PathBuf, PathBuf)> {
+        let overlays = self.config_dir.join(OVERLAYS_DIRECTORY);
+        let mut files = Vec::new();
+
+        for entry in WalkDir::new(dir)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| include_overlays || dir != self.config_dir || e.path() != overlays)
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+
+            // Only process YAML files
+            if !path.is_file() {
+                continue;
+            }
+
+            // Skip files in hidden directories or hidden files themselves
+            // Check the relative path for any component starting with '.'
+            let relative_path = path.strip_prefix(&self.config_dir).unwrap_or(path);
+            let has_hidden_component = relative_path.components().any(|c| {
+                c.as_os_str()
+                    .to_str()
+                    .map(|s| s.starts_with('.'))
+                    .unwrap_or(false)
+            });
+            if has_hidden_component {
+                continue;
+            }
+
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if ext != "yaml" && ext != "yml" {
+                continue;
+            }
+
+            files.push((path.to_path_buf(), relative_path.to_path_buf()));
+        }
+
+        files
+    }
+
+    /// Patches the documents with the selected overlay, if any.
+    fn load_overlay(&self, documents: &mut Vec<Document>) -> Result<Option<AppliedOverlay>> {
+        let setting = documents
+            .iter()
+            .find(|d| {
+                d.deserialize::<ResourceHeader>()
+                    .is_ok_and(|h| h.kind == ResourceKind::Settings)
+            })
+            .and_then(|d| d.value().ok())
+            .and_then(|v| v["spec"]["overlay"].as_str().map(str::to_string));
+
+        let Some(selection) = select_overlay(self.overlay.as_deref(), setting.as_deref()) else {
+            return Ok(None);
+        };
+        let directory = overlay_directory(&self.config_dir, &selection.name)?;
+        if !directory.is_dir() {
+            if selection.required {
+                return Err(GitOpsError::InvalidOverlay {
+                    name: selection.name,
+                    message: format!("directory '{}' not found", directory.display()),
+                });
+            }
+            return Ok(None);
+        }
+
+        let patches = self.read_documents(&directory)?;
+        apply_overlay(documents, patches, &selection.name).map(Some)
+    }
+
     /// Loads a single resource file.
     pub fn load_file(&self, path: &Path) -> Result<AnyResource> {
         let content = fs::read_to_string(path).map_err(|e| GitOpsError::ReadFile {
@@ -319,61 +776,285 @@ impl ConfigLoader {
         self.parse_resource(&content, path)
     }
 
-    /// Parses a resource from YAML content.
+    /// Loads the resources of a file holding one or more documents.
+    pub fn load_resources(&self, path: &Path) -> Result<Vec<AnyResource>> {
+        let content = fs::read_to_string(path).map_err(|e| GitOpsError::ReadFile {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        self.parse_resources(&content, path)
+    }
+
+    /// Loads a resource as written in its file, before overlay patches.
+    pub fn load_authored(
+        &self,
+        path: &Path,
+        kind: ResourceKind,
+        name: &str,
+    ) -> Result<AnyResource> {
+        self.load_resources(&self.config_dir.join(path))?
+            .into_iter()
+            .find(|r| r.kind() == kind && r.name() == name)
+            .ok_or_else(|| GitOpsError::ResourceNotFound {
+                kind: kind.to_string(),
+                name: name.to_string(),
+            })
+    }
+
+    /// Parses a resource from YAML content holding a single document.
     pub fn parse_resource(&self, content: &str, path: &Path) -> Result<AnyResource> {
-        // First, parse the header to determine the kind
-        let header: ResourceHeader =
-            serde_yaml::from_str(content).map_err(|e| GitOpsError::ParseYaml {
+        let mut resources = self.parse_resources(content, path)?;
+        if resources.len() != 1 {
+            return Err(GitOpsError::InvalidResource {
                 path: path.to_path_buf(),
+                message: format!("expected a single resource, found {}", resources.len()),
+            });
+        }
+        Ok(resources.remove(0))
+    }
+
+    /// Parses the resources of YAML content holding one or more documents.
+    pub fn parse_resources(&self, content: &str, path: &Path) -> Result<Vec<AnyResource>> {
+        split_documents(content)
+            .iter()
+            .map(|document| {
+                self.parse_document(
+                    &Document {
+                        path: path.to_path_buf(),
+                        source: DocumentSource::Text(document.padded_text()),
+                    },
+                    &mut Vec::new(),
+                )
+            })
+            .collect()
+    }
+
+    /// Parses a document, adding its schema violations to `warnings`. When
+    /// the document cannot be read, its schema violations are the error.
+    fn parse_document(
+        &self,
+        document: &Document,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> Result<AnyResource> {
+        // First, parse the header to determine the kind
+        let header: ResourceHeader = document.deserialize()?;
+
+        // Convert documents written in an older API version
+        let converted;
+        let document = if header.api_version == API_VERSION {
+            document
+        } else {
+            let mut value = document.value()?;
+            self.conversions
+                .upgrade(header.kind, &mut value, &document.path)?;
+            converted = Document {
+                path: document.path.clone(),
+                source: DocumentSource::Value(value),
+            };
+            &converted
+        };
+
+        let diagnostics = self.check_schema(document, &header)?;
+
+        match Self::deserialize_resource(document, header.kind) {
+            Ok(resource) => {
+                warnings.extend(diagnostics);
+                Ok(resource)
+            }
+            Err(_) if !diagnostics.is_empty() => Err(GitOpsError::Validation(
+                diagnostics
+                    .into_iter()
+                    .map(|diagnostic| Diagnostic {
+                        severity: Severity::Error,
+                        ..diagnostic
+                    })
+                    .collect(),
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn deserialize_resource(document: &Document, kind: ResourceKind) -> Result<AnyResource> {
+        Ok(match kind {
+            ResourceKind::Settings => AnyResource::Settings(document.deserialize()?),
+            ResourceKind::Variable => AnyResource::Variable(document.deserialize()?),
+            ResourceKind::Rule => AnyResource::Rule(document.deserialize()?),
+            ResourceKind::ImportSource => AnyResource::ImportSource(document.deserialize()?),
+            ResourceKind::RuleTest => AnyResource::RuleTest(document.deserialize()?),
+            ResourceKind::RuleTemplate => AnyResource::RuleTemplate(document.deserialize()?),
+            ResourceKind::Correspondent => AnyResource::Correspondent(document.deserialize()?),
+            ResourceKind::LookupTable => AnyResource::LookupTable(document.deserialize()?),
+        })
+    }
+
+    /// Checks a document against the schema of its kind, locating each
+    /// violation at its line in the file.
+    fn check_schema(
+        &self,
+        document: &Document,
+        header: &ResourceHeader,
+    ) -> Result<Vec<Diagnostic>> {
+        let value =
+            serde_json::to_value(document.value()?).map_err(|e| GitOpsError::InvalidResource {
+                path: document.path.clone(),
                 message: e.to_string(),
             })?;
+        let diagnostics = check_resource(header.kind, &value);
+        if diagnostics.is_empty() {
+            return Ok(diagnostics);
+        }
 
-        // Validate API version
-        if header.api_version != API_VERSION {
-            return Err(GitOpsError::InvalidApiVersion {
-                version: header.api_version,
-                expected: API_VERSION.to_string(),
-            });
+        // Documents patched by an overlay are located in their base file
+        let content = match &document.source {
+            DocumentSource::Text(text) => Some(text.clone()),
+            DocumentSource::Value(_) => {
+                fs::read_to_string(self.config_dir.join(&document.path)).ok()
+            }
+        };
+        let mut diagnostics: Vec<Diagnostic> = diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                let message = format!(
+                    "{} '{}': {}",
+                    header.kind, header.metadata.name, diagnostic.message
+                );
+                let mut diagnostic = Diagnostic {
+                    message,
+                    ..diagnostic
+                }
+                .for_resource(
+                    header.kind,
+                    header.metadata.name.clone(),
+                    document.path.clone(),
+                );
+                if let Some(content) = &content {
+                    diagnostic.locate_in(content);
+                }
+                diagnostic
+            })
+            .collect();
+        diagnostics.sort_by_key(|d| (d.line, d.column));
+        Ok(diagnostics)
+    }
+
+    /// Rewrites every document of an older API version, overlay patches
+    /// included, in the current version. Returns the files changed,
+    /// relative to the config directory. Nothing is written if a document
+    /// cannot be converted.
+    ///
+    /// When a conversion changes nothing but the version, only the
+    /// `apiVersion` line is rewritten. Otherwise the document is written
+    /// from its converted value, with its comments carried over to the
+    /// fields they were written at.
+    pub fn upgrade_api_version(&self) -> Result<Vec<PathBuf>> {
+        let overlays = self.config_dir.join(OVERLAYS_DIRECTORY);
+        let mut files = Vec::new();
+        for (path, relative_path) in self.yaml_files(&self.config_dir, true) {
+            let content = fs::read_to_string(&path).map_err(|e| GitOpsError::ReadFile {
+                path: path.clone(),
+                source: e,
+            })?;
+            files.push((path, relative_path, content));
         }
 
-        // Parse based on kind
-        match header.kind {
-            ResourceKind::Settings => {
-                let resource: SettingsResource =
-                    serde_yaml::from_str(content).map_err(|e| GitOpsError::ParseYaml {
-                        path: path.to_path_buf(),
-                        message: e.to_string(),
-                    })?;
-                Ok(AnyResource::Settings(resource))
+        // Overlay patches are written against the base documents
+        let mut bases = Vec::new();
+        for (path, relative_path, content) in &files {
+            if path.starts_with(&overlays) {
+                continue;
             }
-            ResourceKind::Variable => {
-                let resource: VariableResource =
-                    serde_yaml::from_str(content).map_err(|e| GitOpsError::ParseYaml {
-                        path: path.to_path_buf(),
-                        message: e.to_string(),
-                    })?;
-                Ok(AnyResource::Variable(resource))
+            for document in split_documents(content) {
+                if let Some(header) = document.header() {
+                    let value = Document {
+                        path: relative_path.clone(),
+                        source: DocumentSource::Text(document.padded_text()),
+                    }
+                    .value()?;
+                    bases.push((header, value));
+                }
             }
-            ResourceKind::Rule => {
-                let resource: RuleResource =
-                    serde_yaml::from_str(content).map_err(|e| GitOpsError::ParseYaml {
-                        path: path.to_path_buf(),
-                        message: e.to_string(),
-                    })?;
-                Ok(AnyResource::Rule(resource))
+        }
+
+        let mut upgraded = Vec::new();
+        for (path, relative_path, original) in files {
+            let documents = split_documents(&original);
+            let mut content = original.clone();
+
+            // Later documents first, so the ranges of earlier ones stay valid
+            for document in documents.iter().rev() {
+                let Some(header) = document.header() else {
+                    continue;
+                };
+                let base = if path.starts_with(&overlays) {
+                    bases.iter().find(|(base, _)| {
+                        base.kind == header.kind
+                            && (header.kind == ResourceKind::Settings
+                                || base.metadata.name == header.metadata.name)
+                    })
+                } else {
+                    None
+                };
+                let version = base.map_or(&header.api_version, |(base, _)| &base.api_version);
+                if header.api_version == API_VERSION && *version == API_VERSION {
+                    continue;
+                }
+
+                let mut value = Document {
+                    path: relative_path.clone(),
+                    source: DocumentSource::Text(document.padded_text()),
+                }
+                .value()?;
+                let converted = match base {
+                    Some((_, base)) if *version != API_VERSION => {
+                        upgrade_patch(&self.conversions, header.kind, base, &value, &relative_path)?
+                    }
+                    _ => {
+                        let mut converted = value.clone();
+                        if base.is_none() {
+                            self.conversions.upgrade(
+                                header.kind,
+                                &mut converted,
+                                &relative_path,
+                            )?;
+                        }
+                        converted["apiVersion"] = API_VERSION.into();
+                        converted
+                    }
+                };
+
+                value["apiVersion"] = API_VERSION.into();
+                let patched = if value == converted {
+                    replace_api_version(document.text, &header.api_version, API_VERSION)
+                } else {
+                    None
+                };
+                let text = match patched {
+                    Some(text) => text,
+                    None => {
+                        let text = serde_yaml::to_string(&converted)
+                            .map_err(|e| GitOpsError::SerializeYaml(e.to_string()))?;
+                        carry_comments(document.text, &text)
+                    }
+                };
+                content = replace_document(&content, document, &text);
             }
-            ResourceKind::ImportSource => {
-                let resource: ImportSourceResource =
-                    serde_yaml::from_str(content).map_err(|e| GitOpsError::ParseYaml {
-                        path: path.to_path_buf(),
-                        message: e.to_string(),
-                    })?;
-                Ok(AnyResource::ImportSource(resource))
+
+            if content != original {
+                upgraded.push((path, relative_path, content));
             }
         }
+
+        let mut files = Vec::with_capacity(upgraded.len());
+        for (path, relative_path, content) in upgraded {
+            fs::write(&path, content).map_err(|e| GitOpsError::WriteFile { path, source: e })?;
+            files.push(relative_path);
+        }
+        Ok(files)
     }
 
-    /// Writes a resource to a file.
+    /// Writes a resource to a file. In files holding several resources, the
+    /// document of the resource is replaced, or appended if missing.
     pub fn write_resource(&self, resource: &AnyResource, path: &Path) -> Result<()> {
         let full_path = self.config_dir.join(path);
 
@@ -393,10 +1074,23 @@ impl ConfigLoader {
             AnyResource::Variable(r) => serde_yaml::to_string(r),
             AnyResource::Rule(r) => serde_yaml::to_string(r),
             AnyResource::ImportSource(r) => serde_yaml::to_string(r),
+            AnyResource::RuleTest(r) => serde_yaml::to_string(r),
+            AnyResource::RuleTemplate(r) => serde_yaml::to_string(r),
+            AnyResource::Correspondent(r) => serde_yaml::to_string(r),
+            AnyResource::LookupTable(r) => serde_yaml::to_string(r),
         }
         .map_err(|e| GitOpsError::SerializeYaml(e.to_string()))?;
 
-        let content = format!("{}{}", schema_comment, yaml_content);
+        let existing = fs::read_to_string(&full_path).unwrap_or_default();
+        let documents = split_documents(&existing);
+        let content = if documents.len() > 1 {
+            match find_document(&documents, resource.kind(), resource.name()) {
+                Some(document) => replace_document(&existing, document, &yaml_content),
+                None => append_document(&existing, &yaml_content),
+            }
+        } else {
+            format!("{}{}", schema_comment, yaml_content)
+        };
 
         fs::write(&full_path, content).map_err(|e| GitOpsError::WriteFile {
             path: full_path,
@@ -408,16 +1102,7 @@ impl ConfigLoader {
 
     /// Returns the schema comment for a given resource kind.
     fn get_schema_comment(kind: ResourceKind) -> String {
-        let schema_name = match kind {
-            ResourceKind::Settings => "settings",
-            ResourceKind::Variable => "variable",
-            ResourceKind::Rule => "rule",
-            ResourceKind::ImportSource => "import-source",
-        };
-        format!(
-            "# yaml-language-server: $schema=https://paporg.io/schemas/{}.json\n",
-            schema_name
-        )
+        format!("# yaml-language-server: $schema={}\n", schema_url(kind))
     }
 
     /// Deletes a resource file.
@@ -439,6 +1124,32 @@ impl ConfigLoader {
         Ok(())
     }
 
+    /// Removes a resource from its file, deleting the file unless it holds
+    /// other resources.
+    pub fn remove_resource(&self, path: &Path, kind: ResourceKind, name: &str) -> Result<()> {
+        let full_path = self.config_dir.join(path);
+        let existing = fs::read_to_string(&full_path).map_err(|e| GitOpsError::ReadFile {
+            path: full_path.clone(),
+            source: e,
+        })?;
+        let documents = split_documents(&existing);
+        if documents.len() <= 1 {
+            return self.delete_resource(path);
+        }
+
+        let document =
+            find_document(&documents, kind, name).ok_or_else(|| GitOpsError::ResourceNotFound {
+                kind: kind.to_string(),
+                name: name.to_string(),
+            })?;
+        fs::write(&full_path, remove_document(&existing, document)).map_err(|e| {
+            GitOpsError::WriteFile {
+                path: full_path,
+                source: e,
+            }
+        })
+    }
+
     /// Gets the default path for a resource.
     pub fn default_path_for_resource(&self, kind: ResourceKind, name: &str) -> PathBuf {
         match kind {
@@ -446,15 +1157,79 @@ impl ConfigLoader {
             ResourceKind::Variable => PathBuf::from(format!("variables/{}.yaml", name)),
             ResourceKind::Rule => PathBuf::from(format!("rules/{}.yaml", name)),
             ResourceKind::ImportSource => PathBuf::from(format!("sources/{}.yaml", name)),
+            ResourceKind::RuleTest => PathBuf::from(format!("tests/{}.yaml", name)),
+            ResourceKind::RuleTemplate => PathBuf::from(format!("templates/{}.yaml", name)),
+            ResourceKind::Correspondent => PathBuf::from(format!("correspondents/{}.yaml", name)),
+            ResourceKind::LookupTable => PathBuf::from(format!("lookups/{}.yaml", name)),
         }
     }
 
     /// Returns the file tree structure of the config directory.
+    /// Resources patched by an overlay list where each of their fields comes
+    /// from.
     pub fn get_file_tree(&self) -> Result<FileTreeNode> {
-        self.build_file_tree(&self.config_dir, "")
+        let overlay = self.load().ok().and_then(|config| config.overlay);
+        self.build_file_tree(&self.config_dir, "", overlay.as_ref())
+    }
+
+    /// Returns the resources of a file. Overlay documents are patches, so
+    /// only their headers are read.
+    fn file_resources(
+        &self,
+        path: &Path,
+        relative: &str,
+        overlay: Option<&AppliedOverlay>,
+    ) -> Vec<ResourceInfo> {
+        let relative = Path::new(relative);
+        let resources: Vec<_> = if relative.starts_with(OVERLAYS_DIRECTORY) {
+            fs::read_to_string(path)
+                .map(|content| {
+                    split_documents(&content)
+                        .iter()
+                        .filter_map(|d| d.header())
+                        .map(|h| (h.kind, h.metadata.name))
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            self.load_resources(path)
+                .map(|resources| {
+                    resources
+                        .iter()
+                        .map(|r| (r.kind(), r.name().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        resources
+            .into_iter()
+            .map(|(kind, name)| {
+                // Base files list all fields, overlay files those they set
+                let fields = overlay
+                    .into_iter()
+                    .flat_map(|o| &o.patches)
+                    .filter(|p| {
+                        p.kind == kind && (p.name == name || kind == ResourceKind::Settings)
+                    })
+                    .flat_map(|p| {
+                        p.fields
+                            .iter()
+                            .filter(move |f| p.path == relative || f.path == relative)
+                    })
+                    .cloned()
+                    .collect();
+                ResourceInfo { kind, name, fields }
+            })
+            .collect()
     }
 
-    fn build_file_tree(&self, path: &Path, relative: &str) -> Result<FileTreeNode> {
+    fn build_file_tree(
+        &self,
+        path: &Path,
+        relative: &str,
+        overlay: Option<&AppliedOverlay>,
+    ) -> Result<FileTreeNode> {
         let name = path
             .file_name()
             .and_then(|n| n.to_str())
@@ -463,21 +1238,23 @@ impl ConfigLoader {
 
         if path.is_file() {
             let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            let resource_info = if ext == "yaml" || ext == "yml" {
-                self.load_file(path).ok().map(|r| ResourceInfo {
-                    kind: r.kind(),
-                    name: r.name().to_string(),
-                })
+            let mut resources = if ext == "yaml" || ext == "yml" {
+                self.file_resources(path, relative, overlay)
             } else {
-                None
+                Vec::new()
             };
+            let resource = resources.first().cloned();
+            if resources.len() == 1 {
+                resources.clear();
+            }
 
             Ok(FileTreeNode {
                 name,
                 path: relative.to_string(),
                 is_directory: false,
                 children: Vec::new(),
-                resource: resource_info,
+                resource,
+                resources,
             })
         } else {
             let mut children = Vec::new();
@@ -524,14 +1301,14 @@ impl ConfigLoader {
                     if ext != "yaml" && ext != "yml" {
                         continue;
                     }
-                    children.push(self.build_file_tree(&entry_path, &child_relative)?);
+                    children.push(self.build_file_tree(&entry_path, &child_relative, overlay)?);
                 } else if entry_path.is_dir() {
                     // Include empty directories that are within resource type folders
                     let is_resource_subfolder = child_relative.starts_with("sources/")
                         || child_relative.starts_with("variables/")
                         || child_relative.starts_with("rules/");
 
-                    let child_node = self.build_file_tree(&entry_path, &child_relative)?;
+                    let child_node = self.build_file_tree(&entry_path, &child_relative, overlay)?;
 
                     // Keep folder if it has children OR it's a subfolder of a resource type directory
                     if !child_node.children.is_empty() || is_resource_subfolder {
@@ -546,6 +1323,7 @@ impl ConfigLoader {
                 is_directory: true,
                 children,
                 resource: None,
+                resources: Vec::new(),
             })
         }
     }
@@ -556,6 +1334,9 @@ impl ConfigLoader {
 pub struct ResourceInfo {
     pub kind: ResourceKind,
     pub name: String,
+    /// Where each field comes from, for resources patched by an overlay.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldOrigin>,
 }
 
 /// A node in the file tree.
@@ -566,8 +1347,12 @@ pub struct FileTreeNode {
     pub path: String,
     pub is_directory: bool,
     pub children: Vec<FileTreeNode>,
+    /// The first resource of the file.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resource: Option<ResourceInfo>,
+    /// All resources of files holding several.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resources: Vec<ResourceInfo>,
 }
 
 // ============================================================================
@@ -577,6 +1362,7 @@ pub struct FileTreeNode {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gitops::version::v1beta1;
     use tempfile::TempDir;
 
     fn create_test_settings() -> String {
@@ -681,6 +1467,246 @@ spec:
         assert_eq!(config.rules[0].resource.metadata.name, "tax-invoices");
     }
 
+    #[test]
+    fn test_load_rule_test() {
+        let dir = setup_test_config_dir();
+        fs::create_dir_all(dir.path().join("tests")).unwrap();
+        fs::write(
+            dir.path().join("tests/tax-invoices.yaml"),
+            r#"
+apiVersion: paporg.io/v1
+kind: RuleTest
+metadata:
+  name: tax-invoices
+spec:
+  cases:
+    - name: german invoice
+      text: "Rechnung inkl. MwSt"
+      expect:
+        category: Tax
+        rule: tax-invoices
+    - name: scanned invoice
+      file: tests/fixtures/invoice.pdf
+      source: scanner
+      expect:
+        output: Tax/invoice.pdf
+"#,
+        )
+        .unwrap();
+
+        let config = ConfigLoader::new(dir.path()).load().unwrap();
+
+        assert_eq!(config.rule_tests.len(), 1);
+        let test = &config.rule_tests[0];
+        assert_eq!(test.path, PathBuf::from("tests/tax-invoices.yaml"));
+        assert_eq!(test.resource.spec.cases.len(), 2);
+        assert_eq!(
+            test.resource.spec.cases[0].expect.rule.as_deref(),
+            Some("tax-invoices")
+        );
+        assert_eq!(
+            test.resource.spec.cases[1].file.as_deref(),
+            Some("tests/fixtures/invoice.pdf")
+        );
+        assert!(config
+            .all_resources()
+            .iter()
+            .any(|(kind, name, _)| **kind == ResourceKind::RuleTest && *name == "tax-invoices"));
+    }
+
+    #[test]
+    fn test_load_rule_template() {
+        let dir = setup_test_config_dir();
+        fs::create_dir_all(dir.path().join("templates")).unwrap();
+        fs::write(
+            dir.path().join("templates/vendor.yaml"),
+            r#"
+apiVersion: paporg.io/v1
+kind: RuleTemplate
+metadata:
+  name: vendor
+spec:
+  category: vendors
+  match:
+    containsAny: ["Rechnung", "Invoice"]
+  output:
+    directory: "vendors/$y"
+    filename: "$original"
+  symlinks:
+    - target: "by-year/$y"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("rules/acme.yaml"),
+            r#"
+apiVersion: paporg.io/v1
+kind: Rule
+metadata:
+  name: acme
+spec:
+  extends: vendor
+  match:
+    contains: "Acme GmbH"
+  output:
+    directory: "vendors/acme/$y"
+"#,
+        )
+        .unwrap();
+
+        let config = ConfigLoader::new(dir.path()).load().unwrap();
+
+        assert_eq!(config.rule_templates.len(), 1);
+        assert!(config
+            .all_resources()
+            .iter()
+            .any(|(kind, name, _)| **kind == ResourceKind::RuleTemplate && *name == "vendor"));
+
+        // The template itself never becomes a rule
+        assert_eq!(config.rules.len(), 2);
+        let acme = config
+            .rules
+            .iter()
+            .find(|r| r.resource.metadata.name == "acme")
+            .unwrap();
+        assert_eq!(acme.resource.spec.category, "vendors");
+        assert_eq!(acme.resource.spec.output.directory, "vendors/acme/$y");
+        assert_eq!(acme.resource.spec.output.filename, "$original");
+        assert_eq!(acme.resource.spec.symlinks.len(), 1);
+        assert!(matches!(
+            acme.resource.spec.match_condition,
+            MatchCondition::Compound(_)
+        ));
+
+        let authored = config.authored_rule("acme").unwrap();
+        assert_eq!(authored.path, PathBuf::from("rules/acme.yaml"));
+        assert!(authored.resource.spec.category.is_empty());
+        assert!(authored.resource.spec.output.filename.is_empty());
+
+        let legacy = config.to_legacy_config();
+        assert_eq!(legacy.rules.len(), 2);
+        assert!(legacy
+            .rules
+            .iter()
+            .any(|r| r.id == "acme" && r.category == "vendors"));
+    }
+
+    #[test]
+    fn test_load_correspondent() {
+        let dir = setup_test_config_dir();
+        fs::create_dir_all(dir.path().join("correspondents")).unwrap();
+        fs::write(
+            dir.path().join("correspondents/helsana.yaml"),
+            r#"
+apiVersion: paporg.io/v1
+kind: Correspondent
+metadata:
+  name: helsana
+spec:
+  displayName: Helsana
+  aliases: ["Helsana Versicherungen AG"]
+  ibans: ["CH93 0076 2011 6238 5295 7"]
+  vatNumbers: ["CHE-105.953.932"]
+  emailDomains: ["helsana.ch"]
+  defaultCategory: insurance
+"#,
+        )
+        .unwrap();
+
+        let config = ConfigLoader::new(dir.path()).load().unwrap();
+
+        assert_eq!(config.correspondents.len(), 1);
+        let spec = &config.correspondents[0].resource.spec;
+        assert_eq!(spec.display_name, "Helsana");
+        assert_eq!(spec.vat_numbers, vec!["CHE-105.953.932"]);
+        assert!(config
+            .all_resources()
+            .iter()
+            .any(|(kind, name, _)| **kind == ResourceKind::Correspondent && *name == "helsana"));
+
+        let legacy = config.to_legacy_config();
+        assert_eq!(legacy.correspondents.len(), 1);
+        assert_eq!(legacy.correspondents[0].id, "helsana");
+        assert_eq!(legacy.correspondents[0].name, "Helsana");
+        assert_eq!(
+            legacy.correspondents[0].default_category.as_deref(),
+            Some("insurance")
+        );
+    }
+
+    #[test]
+    fn test_load_lookup_table() {
+        let dir = setup_test_config_dir();
+        fs::create_dir_all(dir.path().join("lookups")).unwrap();
+        fs::write(
+            dir.path().join("lookups/vendors.yaml"),
+            r#"
+apiVersion: paporg.io/v1
+kind: LookupTable
+metadata:
+  name: vendors
+spec:
+  entries:
+    - key: SBB CFF FFS
+      value: SBB
+  file: lookups/vendors.csv
+  fallback: other
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("lookups/vendors.csv"),
+            "key,value\nSchweizerische Bundesbahnen,SBB\n",
+        )
+        .unwrap();
+
+        let config = ConfigLoader::new(dir.path()).load().unwrap();
+
+        assert_eq!(config.lookup_tables.len(), 1);
+        assert_eq!(config.lookup_tables[0].resource.spec.entries.len(), 1);
+        assert_eq!(config.lookup_file_entries["vendors"].len(), 1);
+
+        let legacy = config.to_legacy_config();
+        let keys: Vec<&str> = legacy.lookup_tables[0]
+            .entries
+            .iter()
+            .map(|e| e.key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["SBB CFF FFS", "Schweizerische Bundesbahnen"]);
+        assert_eq!(legacy.lookup_tables[0].fallback.as_deref(), Some("other"));
+
+        // A missing CSV file fails the load like an unreadable resource
+        fs::remove_file(dir.path().join("lookups/vendors.csv")).unwrap();
+        assert!(matches!(
+            ConfigLoader::new(dir.path()).load(),
+            Err(GitOpsError::ReadFile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_inheritance_errors() {
+        let dir = setup_test_config_dir();
+        let write_rule = |name: &str, extends: &str| {
+            fs::write(
+                dir.path().join(format!("rules/{}.yaml", name)),
+                format!(
+                    "apiVersion: paporg.io/v1\nkind: Rule\nmetadata:\n  name: {}\nspec:\n  extends: {}\n",
+                    name, extends
+                ),
+            )
+            .unwrap();
+        };
+
+        write_rule("acme", "missing");
+        let result = ConfigLoader::new(dir.path()).load();
+        assert!(matches!(result, Err(GitOpsError::UnknownParent { .. })));
+
+        write_rule("acme", "globex");
+        write_rule("globex", "acme");
+        let result = ConfigLoader::new(dir.path()).load();
+        assert!(matches!(result, Err(GitOpsError::InheritanceCycle(_))));
+    }
+
     #[test]
     fn test_load_missing_settings() {
         let dir = TempDir::new().unwrap();
@@ -730,6 +1756,41 @@ spec:
         assert_eq!(legacy.rules[0].id, "tax-invoices");
     }
 
+    #[test]
+    fn test_to_legacy_config_computed_variables() {
+        let dir = setup_test_config_dir();
+        fs::write(
+            dir.path().join("variables/vendor_short.yaml"),
+            r#"
+apiVersion: paporg.io/v1
+kind: Variable
+metadata:
+  name: vendor_short
+spec:
+  template: "$vendor"
+  pattern: "^(?P<vendor_short>\\S+)"
+  transform: uppercase
+"#,
+        )
+        .unwrap();
+
+        let legacy = ConfigLoader::new(dir.path())
+            .load()
+            .unwrap()
+            .to_legacy_config();
+
+        assert_eq!(legacy.variables.extracted.len(), 1);
+        assert_eq!(legacy.variables.computed.len(), 1);
+        let computed = &legacy.variables.computed[0];
+        assert_eq!(computed.name, "vendor_short");
+        assert_eq!(computed.template, "$vendor");
+        assert_eq!(computed.pattern.as_deref(), Some(r"^(?P<vendor_short>\S+)"));
+        assert!(matches!(
+            computed.transform,
+            Some(LegacyTransform::Uppercase)
+        ));
+    }
+
     #[test]
     fn test_get_file_tree() {
         let dir = setup_test_config_dir();
@@ -772,6 +1833,22 @@ spec:
             loader.default_path_for_resource(ResourceKind::ImportSource, "local-documents"),
             PathBuf::from("sources/local-documents.yaml")
         );
+        assert_eq!(
+            loader.default_path_for_resource(ResourceKind::RuleTest, "invoices"),
+            PathBuf::from("tests/invoices.yaml")
+        );
+        assert_eq!(
+            loader.default_path_for_resource(ResourceKind::RuleTemplate, "vendor"),
+            PathBuf::from("templates/vendor.yaml")
+        );
+        assert_eq!(
+            loader.default_path_for_resource(ResourceKind::Correspondent, "helsana"),
+            PathBuf::from("correspondents/helsana.yaml")
+        );
+        assert_eq!(
+            loader.default_path_for_resource(ResourceKind::LookupTable, "vendors"),
+            PathBuf::from("lookups/vendors.yaml")
+        );
     }
 
     #[test]
@@ -789,10 +1866,13 @@ spec:
                 output_directory: "/output".to_string(),
                 worker_count: 4,
                 ocr: super::super::resource::OcrSettings::default(),
+                limits: super::super::resource::LimitsSettings::default(),
+                categorization: super::super::resource::CategorizationSettings::default(),
                 defaults: super::super::resource::DefaultOutputSettings::default(),
                 git: super::super::resource::GitSettings::default(),
                 ai: super::super::resource::AiSettings::default(),
                 release_channel: super::super::resource::ReleaseChannel::default(),
+                overlay: None,
             },
         };
 
@@ -814,6 +1894,298 @@ spec:
         assert!(!dir.path().join("settings.yaml").exists());
     }
 
+    fn multi_document_variables() -> String {
+        r#"# Variables
+apiVersion: paporg.io/v1
+kind: Variable
+metadata:
+  name: year
+spec:
+  pattern: "(?P<year>\\d{4})"
+---
+apiVersion: paporg.io/v1
+kind: Variable
+metadata:
+  name: month
+spec:
+  pattern: "(?P<month>\\d{2})"
+"#
+        .to_string()
+    }
+
+    #[test]
+    fn test_load_multi_document_file() {
+        let dir = setup_test_config_dir();
+        fs::write(
+            dir.path().join("variables/dates.yaml"),
+            multi_document_variables(),
+        )
+        .unwrap();
+        let loader = ConfigLoader::new(dir.path());
+
+        let config = loader.load().unwrap();
+        let dates: Vec<_> = config
+            .variables
+            .iter()
+            .filter(|v| v.path == Path::new("variables/dates.yaml"))
+            .map(|v| v.resource.metadata.name.as_str())
+            .collect();
+        assert_eq!(dates, vec!["year", "month"]);
+
+        // A single resource is expected where only one can be handled
+        let result = loader.parse_resource(&multi_document_variables(), Path::new("x.yaml"));
+        assert!(matches!(result, Err(GitOpsError::InvalidResource { .. })));
+
+        // Parse errors report lines of the whole file
+        let result = loader.parse_resources("# Settings\n---\nkind: [\n", Path::new("x.yaml"));
+        let Err(GitOpsError::ParseYaml { message, .. }) = result else {
+            panic!("expected a parse error");
+        };
+        assert!(message.contains("line 3"), "{}", message);
+
+        let tree = loader.get_file_tree().unwrap();
+        let variables = tree
+            .children
+            .iter()
+            .find(|c| c.name == "variables")
+            .unwrap();
+        let dates = variables
+            .children
+            .iter()
+            .find(|c| c.name == "dates.yaml")
+            .unwrap();
+        assert_eq!(dates.resource.as_ref().unwrap().name, "year");
+        assert_eq!(dates.resources.len(), 2);
+    }
+
+    #[test]
+    fn test_write_and_remove_in_multi_document_file() {
+        let dir = setup_test_config_dir();
+        let path = PathBuf::from("variables/dates.yaml");
+        fs::write(dir.path().join(&path), multi_document_variables()).unwrap();
+        let loader = ConfigLoader::new(dir.path());
+
+        let AnyResource::Variable(mut month) = loader
+            .load_authored(&path, ResourceKind::Variable, "month")
+            .unwrap()
+        else {
+            panic!("expected a variable");
+        };
+        month.spec.pattern = "(?P<month>[a-z]+)".to_string();
+        loader
+            .write_resource(&AnyResource::Variable(month.clone()), &path)
+            .unwrap();
+        month.metadata.name = "day".to_string();
+        loader
+            .write_resource(&AnyResource::Variable(month), &path)
+            .unwrap();
+
+        let resources = loader.load_resources(&dir.path().join(&path)).unwrap();
+        let names: Vec<_> = resources.iter().map(|r| r.name()).collect();
+        assert_eq!(names, vec!["year", "month", "day"]);
+        let AnyResource::Variable(month) = &resources[1] else {
+            panic!("expected a variable");
+        };
+        assert_eq!(month.spec.pattern, "(?P<month>[a-z]+)");
+
+        loader
+            .remove_resource(&path, ResourceKind::Variable, "month")
+            .unwrap();
+        let resources = loader.load_resources(&dir.path().join(&path)).unwrap();
+        let names: Vec<_> = resources.iter().map(|r| r.name()).collect();
+        assert_eq!(names, vec!["year", "day"]);
+        assert!(loader
+            .remove_resource(&path, ResourceKind::Variable, "month")
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_overlay() {
+        let dir = setup_test_config_dir();
+        fs::create_dir_all(dir.path().join("overlays/nas")).unwrap();
+        fs::write(
+            dir.path().join("overlays/nas/settings.yaml"),
+            "apiVersion: paporg.io/v1\nkind: Settings\nmetadata:\n  name: nas\n\
+             spec:\n  inputDirectory: /volume1/inbox\n  ocr: null\n",
+        )
+        .unwrap();
+
+        // Without an overlay selected, the overlays directory is ignored
+        let config = ConfigLoader::new(dir.path()).load().unwrap();
+        assert!(config.overlay.is_none());
+        assert_eq!(config.settings.resource.spec.input_directory, "/data/inbox");
+
+        let loader = ConfigLoader::new(dir.path()).with_overlay("nas");
+        let config = loader.load().unwrap();
+        let settings = &config.settings.resource.spec;
+        assert_eq!(settings.input_directory, "/volume1/inbox");
+        assert_eq!(settings.output_directory, "/data/documents");
+        assert_eq!(settings.ocr.languages, vec!["eng"]);
+        assert_eq!(config.settings.path, Path::new("settings.yaml"));
+        assert_eq!(config.overlay.as_ref().unwrap().name, "nas");
+
+        let tree = loader.get_file_tree().unwrap();
+        let settings = tree
+            .children
+            .iter()
+            .find(|c| c.name == "settings.yaml")
+            .unwrap();
+        let fields = &settings.resource.as_ref().unwrap().fields;
+        let origin = |field: &str| {
+            fields
+                .iter()
+                .find(|f| f.field == field)
+                .map(|f| f.path.clone())
+        };
+        assert_eq!(
+            origin("spec.inputDirectory"),
+            Some(PathBuf::from("overlays/nas/settings.yaml"))
+        );
+        assert_eq!(
+            origin("spec.outputDirectory"),
+            Some(PathBuf::from("settings.yaml"))
+        );
+        assert_eq!(origin("spec.ocr.dpi"), None);
+
+        let overlays = tree.children.iter().find(|c| c.name == "overlays").unwrap();
+        let patch = &overlays.children[0].children[0];
+        assert_eq!(patch.resource.as_ref().unwrap().fields.len(), 1);
+
+        // An overlay named explicitly must exist
+        let result = ConfigLoader::new(dir.path()).with_overlay("laptop").load();
+        assert!(matches!(result, Err(GitOpsError::InvalidOverlay { .. })));
+    }
+
+    #[test]
+    fn test_schema_violations_are_located() {
+        let dir = setup_test_config_dir();
+        fs::write(
+            dir.path().join("variables/dates.yaml"),
+            "apiVersion: paporg.io/v1\nkind: Variable\nmetadata:\n  name: year\n\
+             spec:\n  pattern: (?P<year>\\d{4})\n  transfrom: uppercase\n  \
+             pages: [one]\n",
+        )
+        .unwrap();
+
+        let error = ConfigLoader::new(dir.path()).load().unwrap_err();
+        let diagnostics = error.diagnostics();
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+
+        let typo = &diagnostics[0];
+        assert_eq!(typo.kind, Some(ResourceKind::Variable));
+        assert_eq!(typo.path, Some(PathBuf::from("variables/dates.yaml")));
+        assert_eq!(typo.yaml_path.as_deref(), Some("spec.transfrom"));
+        assert_eq!((typo.line, typo.column), (Some(7), Some(3)));
+        assert!(typo.message.starts_with("Variable 'year': spec.transfrom:"));
+
+        assert_eq!(diagnostics[1].yaml_path.as_deref(), Some("spec.pages"));
+        assert_eq!(diagnostics[1].line, Some(8));
+        assert!(diagnostics.iter().all(Diagnostic::is_error));
+    }
+
+    #[test]
+    fn test_unknown_fields_are_warnings() {
+        let dir = setup_test_config_dir();
+        fs::write(
+            dir.path().join("variables/dates.yaml"),
+            "apiVersion: paporg.io/v1\nkind: Variable\nmetadata:\n  name: year\n\
+             spec:\n  pattern: (?P<year>\\d{4})\n  transfrom: uppercase\n",
+        )
+        .unwrap();
+
+        let config = ConfigLoader::new(dir.path()).load().unwrap();
+        assert!(config.variables.iter().any(|v| v.resource.name() == "year"));
+        assert_eq!(config.warnings.len(), 1, "{:?}", config.warnings);
+
+        let typo = &config.warnings[0];
+        assert!(!typo.is_error());
+        assert_eq!(typo.yaml_path.as_deref(), Some("spec.transfrom"));
+        assert_eq!((typo.line, typo.column), (Some(7), Some(3)));
+
+        let mut validator = crate::gitops::validation::ConfigValidator::new();
+        validator.validate(&config).unwrap();
+        assert!(validator.diagnostics().contains(typo));
+    }
+
+    #[test]
+    fn test_load_and_upgrade_older_api_version() {
+        let dir = setup_test_config_dir();
+        let rules = "# Receipts\n\
+                     apiVersion: paporg.io/v1beta1\nkind: Rule\nmetadata:\n  name: receipts\n\
+                     spec:\n  category: Receipts\n  match:\n    contains: Receipt\n  \
+                     output:\n    dir: Receipts/$y\n    filename: $original\n\
+                     ---\n\
+                     apiVersion: paporg.io/v1\nkind: Rule\nmetadata:\n  name: misc\n\
+                     spec:\n  category: Misc\n  match:\n    contains: Misc\n";
+        fs::write(dir.path().join("rules/receipts.yaml"), rules).unwrap();
+        let legacy = "# Written before v1\n\
+                      apiVersion: paporg.io/v1beta1 # old\nkind: Rule\nmetadata:\n  name: legacy\n\
+                      spec:\n  category: Legacy\n  match:\n    contains: Legacy # any page\n";
+        fs::write(dir.path().join("rules/legacy.yaml"), legacy).unwrap();
+
+        // Without a conversion the version is rejected
+        let result = ConfigLoader::new(dir.path()).load();
+        assert!(matches!(result, Err(GitOpsError::InvalidApiVersion { .. })));
+
+        // Patches are read in the version of their base
+        fs::create_dir_all(dir.path().join("overlays/nas")).unwrap();
+        let patch = "apiVersion: paporg.io/v1\nkind: Rule\nmetadata:\n  name: receipts\n\
+                     spec:\n  output:\n    dir: /volume1/Receipts # NAS share\n";
+        fs::write(dir.path().join("overlays/nas/receipts.yaml"), patch).unwrap();
+
+        let conversions = ConversionRegistry::empty().register(v1beta1::CONVERSION);
+        let loader = ConfigLoader::new(dir.path()).with_conversions(conversions);
+        let config = loader.load().unwrap();
+        let receipts = config.authored_rule("receipts").unwrap();
+        assert_eq!(receipts.resource.api_version, API_VERSION);
+        assert_eq!(receipts.resource.spec.output.directory, "Receipts/$y");
+        let config = ConfigLoader::new(dir.path())
+            .with_conversions(ConversionRegistry::empty().register(v1beta1::CONVERSION))
+            .with_overlay("nas")
+            .load()
+            .unwrap();
+        let receipts = config.authored_rule("receipts").unwrap();
+        assert_eq!(receipts.resource.spec.output.directory, "/volume1/Receipts");
+
+        assert_eq!(
+            loader.upgrade_api_version().unwrap(),
+            vec![
+                PathBuf::from("overlays/nas/receipts.yaml"),
+                PathBuf::from("rules/legacy.yaml"),
+                PathBuf::from("rules/receipts.yaml")
+            ]
+        );
+
+        // Rewritten documents keep their comments, at the parent of fields
+        // that were renamed
+        let content = fs::read_to_string(dir.path().join("rules/legacy.yaml")).unwrap();
+        assert_eq!(
+            content,
+            "# Written before v1\n\
+             apiVersion: paporg.io/v1 # old\nkind: Rule\nmetadata:\n  name: legacy\n\
+             spec:\n  priority: 0\n  category: Legacy\n  match:\n    contains: Legacy # any page\n"
+        );
+        let content = fs::read_to_string(dir.path().join("rules/receipts.yaml")).unwrap();
+        assert!(content.starts_with("# Receipts\napiVersion: paporg.io/v1\n"));
+        assert!(content.contains("directory: Receipts/$y"));
+        assert!(content.ends_with("---\napiVersion: paporg.io/v1\nkind: Rule\nmetadata:\n  name: misc\nspec:\n  category: Misc\n  match:\n    contains: Misc\n"));
+        let content = fs::read_to_string(dir.path().join("overlays/nas/receipts.yaml")).unwrap();
+        assert_eq!(
+            content,
+            "apiVersion: paporg.io/v1\nkind: Rule\nmetadata:\n  name: receipts\n\
+             spec:\n  output: # NAS share\n    directory: /volume1/Receipts\n"
+        );
+
+        // Upgraded files load without conversions, and are not touched again
+        let config = ConfigLoader::new(dir.path())
+            .with_overlay("nas")
+            .load()
+            .unwrap();
+        let receipts = config.authored_rule("receipts").unwrap();
+        assert_eq!(receipts.resource.spec.output.directory, "/volume1/Receipts");
+        assert!(loader.upgrade_api_version().unwrap().is_empty());
+    }
+
     #[test]
     fn test_duplicate_resource_name() {
         let dir = TempDir::new().unwrap();
@@ -891,6 +2263,22 @@ jobs:
             ConfigLoader::get_schema_comment(ResourceKind::ImportSource),
             "# yaml-language-server: $schema=https://paporg.io/schemas/import-source.json\n"
         );
+        assert_eq!(
+            ConfigLoader::get_schema_comment(ResourceKind::RuleTest),
+            "# yaml-language-server: $schema=https://paporg.io/schemas/rule-test.json\n"
+        );
+        assert_eq!(
+            ConfigLoader::get_schema_comment(ResourceKind::RuleTemplate),
+            "# yaml-language-server: $schema=https://paporg.io/schemas/rule-template.json\n"
+        );
+        assert_eq!(
+            ConfigLoader::get_schema_comment(ResourceKind::Correspondent),
+            "# yaml-language-server: $schema=https://paporg.io/schemas/correspondent.json\n"
+        );
+        assert_eq!(
+            ConfigLoader::get_schema_comment(ResourceKind::LookupTable),
+            "# yaml-language-server: $schema=https://paporg.io/schemas/lookup-table.json\n"
+        );
     }
 
     #[test]
@@ -908,10 +2296,13 @@ jobs:
                 output_directory: "/output".to_string(),
                 worker_count: 4,
                 ocr: super::super::resource::OcrSettings::default(),
+                limits: super::super::resource::LimitsSettings::default(),
+                categorization: super::super::resource::CategorizationSettings::default(),
                 defaults: super::super::resource::DefaultOutputSettings::default(),
                 git: super::super::resource::GitSettings::default(),
                 ai: super::super::resource::AiSettings::default(),
                 release_channel: super::super::resource::ReleaseChannel::default(),
+                overlay: None,
             },
         };
 
@@ -0,0 +1,155 @@
+//! Reads the CSV files of lookup tables.
+//!
+//! Each row holds a key and the value it maps to. Fields may be quoted with
+//! double quotes to contain commas, and a first row of `key,value` is taken
+//! as a header. Empty lines and lines starting with `#` are skipped.
+
+use std::fs;
+use std::path::{Component, Path};
+
+use super::error::{GitOpsError, Result};
+use super::resource::LookupEntry;
+
+/// Reads the entries of a lookup table's CSV file. `file` is relative to
+/// the config directory and must stay inside it.
+pub fn read_lookup_file(config_dir: &Path, file: &str) -> Result<Vec<LookupEntry>> {
+    let relative = Path::new(file);
+    let invalid = |message: String| GitOpsError::InvalidLookupFile {
+        path: relative.to_path_buf(),
+        message,
+    };
+
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(invalid(
+            "must be a relative path inside the config directory".to_string(),
+        ));
+    }
+
+    let path = config_dir.join(relative);
+    let content =
+        fs::read_to_string(&path).map_err(|e| GitOpsError::ReadFile { path, source: e })?;
+    parse_lookup_csv(&content).map_err(invalid)
+}
+
+/// Parses `key,value` rows.
+pub fn parse_lookup_csv(content: &str) -> std::result::Result<Vec<LookupEntry>, String> {
+    let mut entries = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields = parse_row(line).map_err(|e| format!("line {}: {}", index + 1, e))?;
+        let [key, value] = <[String; 2]>::try_from(fields).map_err(|fields| {
+            format!(
+                "line {}: expected 2 columns, found {}",
+                index + 1,
+                fields.len()
+            )
+        })?;
+
+        if entries.is_empty()
+            && key.eq_ignore_ascii_case("key")
+            && value.eq_ignore_ascii_case("value")
+        {
+            continue;
+        }
+        entries.push(LookupEntry { key, value });
+    }
+
+    Ok(entries)
+}
+
+/// Splits a row at commas outside of double quotes.
+fn parse_row(line: &str) -> std::result::Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("unterminated quote".to_string());
+    }
+    fields.push(field.trim().to_string());
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(key: &str, value: &str) -> LookupEntry {
+        LookupEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_lookup_csv() {
+        let content = "key,value\n\
+                       # Swiss railways\n\
+                       SBB CFF FFS,SBB\n\
+                       \n\
+                       \"Schweizerische Bundesbahnen, SBB\", SBB\n\
+                       \"Quote \"\"Inc\"\"\",Quote\n";
+        assert_eq!(
+            parse_lookup_csv(content).unwrap(),
+            vec![
+                entry("SBB CFF FFS", "SBB"),
+                entry("Schweizerische Bundesbahnen, SBB", "SBB"),
+                entry("Quote \"Inc\"", "Quote"),
+            ]
+        );
+
+        assert_eq!(
+            parse_lookup_csv("a,b\nonly one column\n").unwrap_err(),
+            "line 2: expected 2 columns, found 1"
+        );
+        assert_eq!(
+            parse_lookup_csv("\"open,b\n").unwrap_err(),
+            "line 1: unterminated quote"
+        );
+    }
+
+    #[test]
+    fn test_read_lookup_file() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("lookups")).unwrap();
+        fs::write(dir.path().join("lookups/vendors.csv"), "SBB CFF FFS,SBB\n").unwrap();
+
+        let entries = read_lookup_file(dir.path(), "lookups/vendors.csv").unwrap();
+        assert_eq!(entries, vec![entry("SBB CFF FFS", "SBB")]);
+
+        assert!(matches!(
+            read_lookup_file(dir.path(), "lookups/missing.csv"),
+            Err(GitOpsError::ReadFile { .. })
+        ));
+        assert!(matches!(
+            read_lookup_file(dir.path(), "../outside.csv"),
+            Err(GitOpsError::InvalidLookupFile { .. })
+        ));
+    }
+}
@@ -0,0 +1,687 @@
+//! Migration of legacy JSON configs into a resource tree.
+//!
+//! [`convert_legacy_config`] is the reverse of
+//! [`LoadedConfig::to_legacy_config`](super::loader::LoadedConfig::to_legacy_config):
+//! it turns a legacy [`Config`](LegacyConfig) into Settings, Variable, Rule,
+//! Correspondent and LookupTable resources. Resource names are derived from
+//! the legacy ids, so migrating the same config twice gives the same names.
+//! Whatever has no exact counterpart in the resources is listed as a
+//! [`MigrationNote`].
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{GitOpsError, Result};
+use super::loader::ConfigLoader;
+use super::resource::{
+    AiSettings, AnyResource, CategorizationMode, CategorizationSettings, ClassifierSettings,
+    CompareValue, CompoundMatch, CorrespondentSpec, DefaultOutputSettings, FuzzyMatch,
+    LimitsSettings, LookupEntry, LookupMode, LookupTableSpec, MatchCondition, MatchField,
+    OcrSettings, OutputSettings, PagePosition, PageRegion, PageSelection, Resource, ResourceKind,
+    RuleSpec, SettingsSpec, SimpleMatch, SymlinkSettings, VariableMatch, VariableSource,
+    VariableSpec, VariableTransform,
+};
+use crate::config::schema::{
+    CategorizationMode as LegacyCategorizationMode, CompareValue as LegacyCompareValue,
+    Config as LegacyConfig, LookupMode as LegacyLookupMode, MatchCondition as LegacyMatchCondition,
+    MatchField as LegacyMatchField, PagePosition as LegacyPagePosition,
+    PageRegion as LegacyPageRegion, PageSelection as LegacyPageSelection,
+    VariableMatch as LegacyVariableMatch, VariableSource as LegacyVariableSource,
+    VariableTransform as LegacyTransform,
+};
+
+/// Annotation keeping the display name of a migrated rule, which Rule
+/// resources have no field for.
+pub const DISPLAY_NAME_ANNOTATION: &str = "paporg.io/display-name";
+
+/// Name of the migrated Settings resource.
+const SETTINGS_NAME: &str = "default";
+
+/// Something of a legacy config that did not map one-to-one onto a resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationNote {
+    pub kind: ResourceKind,
+    /// Name of the resource the note is about.
+    pub name: String,
+    pub message: String,
+}
+
+/// Resources converted from a legacy config.
+#[derive(Debug, Clone)]
+pub struct LegacyMigration {
+    pub resources: Vec<AnyResource>,
+    pub notes: Vec<MigrationNote>,
+}
+
+/// Outcome of writing a migrated config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// Files written, relative to the config directory.
+    pub files: Vec<PathBuf>,
+    pub notes: Vec<MigrationNote>,
+}
+
+impl LegacyMigration {
+    /// Writes every resource to its default path in the config directory of
+    /// `loader`. Nothing is written if any of the files already exists.
+    pub fn write(self, loader: &ConfigLoader) -> Result<MigrationReport> {
+        let targets: Vec<_> = self
+            .resources
+            .iter()
+            .map(|r| (r, loader.default_path_for_resource(r.kind(), r.name())))
+            .collect();
+
+        if let Some((resource, _)) = targets
+            .iter()
+            .find(|(_, path)| loader.config_dir().join(path).exists())
+        {
+            return Err(GitOpsError::ResourceAlreadyExists {
+                kind: resource.kind().to_string(),
+                name: resource.name().to_string(),
+            });
+        }
+
+        let mut files = Vec::with_capacity(targets.len());
+        for (resource, path) in targets {
+            loader.write_resource(resource, &path)?;
+            files.push(path);
+        }
+
+        Ok(MigrationReport {
+            files,
+            notes: self.notes,
+        })
+    }
+}
+
+/// Converts a legacy config and writes the resources to the config
+/// directory of `loader`.
+pub fn migrate_legacy_config(
+    config: &LegacyConfig,
+    loader: &ConfigLoader,
+) -> Result<MigrationReport> {
+    convert_legacy_config(config).write(loader)
+}
+
+/// Converts a legacy config into resources.
+pub fn convert_legacy_config(config: &LegacyConfig) -> LegacyMigration {
+    let mut names = Names::default();
+    let mut resources = vec![settings_resource(config)];
+
+    // Correspondents and lookup tables come first, so that references to
+    // renamed ones can be rewritten
+    for correspondent in &config.correspondents {
+        let name = names.assign(
+            ResourceKind::Correspondent,
+            &correspondent.id,
+            "correspondent",
+        );
+        let display_name = if correspondent.name == name {
+            String::new()
+        } else {
+            correspondent.name.clone()
+        };
+        resources.push(AnyResource::Correspondent(Resource::new(
+            ResourceKind::Correspondent,
+            name,
+            CorrespondentSpec {
+                display_name,
+                aliases: correspondent.aliases.clone(),
+                ibans: correspondent.ibans.clone(),
+                vat_numbers: correspondent.vat_numbers.clone(),
+                email_domains: correspondent.email_domains.clone(),
+                default_category: correspondent.default_category.clone(),
+            },
+        )));
+    }
+
+    for table in &config.lookup_tables {
+        let name = names.assign(ResourceKind::LookupTable, &table.name, "lookup");
+        resources.push(AnyResource::LookupTable(Resource::new(
+            ResourceKind::LookupTable,
+            name,
+            LookupTableSpec {
+                mode: match table.mode {
+                    LegacyLookupMode::Exact => LookupMode::Exact,
+                    LegacyLookupMode::Regex => LookupMode::Regex,
+                },
+                entries: table
+                    .entries
+                    .iter()
+                    .map(|e| LookupEntry {
+                        key: e.key.clone(),
+                        value: e.value.clone(),
+                    })
+                    .collect(),
+                file: None,
+                fallback: table.fallback.clone(),
+            },
+        )));
+    }
+
+    // Variable names are referenced by templates as written, so they are
+    // only changed to tell apart an extracted and a computed variable
+    for variable in &config.variables.extracted {
+        let name = names.assign_verbatim(ResourceKind::Variable, &variable.name);
+        resources.push(AnyResource::Variable(Resource::new(
+            ResourceKind::Variable,
+            name,
+            VariableSpec {
+                pattern: variable.pattern.clone(),
+                template: None,
+                transform: variable.transform.as_ref().map(|t| names.transform(t)),
+                default: variable.default.clone(),
+                pages: variable.pages.as_ref().map(convert_page_selection),
+                region: variable.region.map(convert_page_region),
+                from: variable.from.map(convert_variable_source),
+            },
+        )));
+    }
+
+    for variable in &config.variables.computed {
+        let name = names.assign_verbatim(ResourceKind::Variable, &variable.name);
+        resources.push(AnyResource::Variable(Resource::new(
+            ResourceKind::Variable,
+            name,
+            VariableSpec {
+                pattern: variable.pattern.clone().unwrap_or_default(),
+                template: Some(variable.template.clone()),
+                transform: variable.transform.as_ref().map(|t| names.transform(t)),
+                default: variable.default.clone(),
+                pages: None,
+                region: None,
+                from: None,
+            },
+        )));
+    }
+
+    for rule in &config.rules {
+        let name = names.assign(ResourceKind::Rule, &rule.id, "rule");
+        let mut resource = Resource::new(
+            ResourceKind::Rule,
+            name.clone(),
+            RuleSpec {
+                priority: rule.priority,
+                extends: None,
+                category: rule.category.clone(),
+                exclusive: rule.exclusive,
+                match_condition: names.match_condition(&rule.match_condition),
+                output: OutputSettings {
+                    directory: rule.output.directory.clone(),
+                    filename: rule.output.filename.clone(),
+                },
+                symlinks: rule
+                    .symlinks
+                    .iter()
+                    .map(|s| SymlinkSettings {
+                        target: s.target.clone(),
+                    })
+                    .collect(),
+            },
+        );
+
+        if rule.name != name {
+            resource
+                .metadata
+                .annotations
+                .insert(DISPLAY_NAME_ANNOTATION.to_string(), rule.name.clone());
+            names.note(
+                ResourceKind::Rule,
+                &name,
+                format!(
+                    "Display name '{}' is kept as the {} annotation",
+                    rule.name, DISPLAY_NAME_ANNOTATION
+                ),
+            );
+        }
+        resources.push(AnyResource::Rule(resource));
+    }
+
+    LegacyMigration {
+        resources,
+        notes: names.notes,
+    }
+}
+
+fn settings_resource(config: &LegacyConfig) -> AnyResource {
+    let categorization = &config.categorization;
+    AnyResource::Settings(Resource::new(
+        ResourceKind::Settings,
+        SETTINGS_NAME,
+        SettingsSpec {
+            input_directory: config.input_directory.clone(),
+            output_directory: config.output_directory.clone(),
+            worker_count: config.worker_count,
+            ocr: OcrSettings {
+                enabled: config.ocr.enabled,
+                languages: config.ocr.languages.clone(),
+                dpi: config.ocr.dpi,
+            },
+            limits: LimitsSettings {
+                max_file_size_mb: config.limits.max_file_size_mb,
+                max_pages: config.limits.max_pages,
+                job_timeout_secs: config.limits.job_timeout_secs,
+                ocr_page_timeout_secs: config.limits.ocr_page_timeout_secs,
+                max_archive_entries: config.limits.max_archive_entries,
+                max_archive_size_mb: config.limits.max_archive_size_mb,
+                max_archive_depth: config.limits.max_archive_depth,
+            },
+            categorization: CategorizationSettings {
+                mode: match categorization.mode {
+                    LegacyCategorizationMode::FirstMatch => CategorizationMode::FirstMatch,
+                    LegacyCategorizationMode::Scoring => CategorizationMode::Scoring,
+                },
+                threshold: categorization.threshold,
+                alternatives: categorization.alternatives,
+                classifier: ClassifierSettings {
+                    enabled: categorization.classifier.enabled,
+                    auto_file: categorization.classifier.auto_file,
+                    threshold: categorization.classifier.threshold,
+                    min_examples: categorization.classifier.min_examples,
+                },
+            },
+            defaults: DefaultOutputSettings {
+                output: OutputSettings {
+                    directory: config.defaults.output.directory.clone(),
+                    filename: config.defaults.output.filename.clone(),
+                },
+            },
+            git: Default::default(),
+            ai: AiSettings {
+                enabled: config.ai.enabled,
+                model_cache_dir: config.ai.model_cache_dir.clone(),
+                model_repo: config.ai.model_repo.clone(),
+                model_file: config.ai.model_file.clone(),
+                timeout_secs: config.ai.timeout_secs,
+            },
+            release_channel: Default::default(),
+            overlay: None,
+        },
+    ))
+}
+
+/// Resource names given so far, and the notes about them.
+#[derive(Default)]
+struct Names {
+    taken: HashSet<(ResourceKind, String)>,
+    /// Legacy ids of correspondents and lookup tables, by the name they got.
+    renamed: HashMap<(ResourceKind, String), String>,
+    notes: Vec<MigrationNote>,
+}
+
+impl Names {
+    /// Gives a legacy id a resource name that is a valid identifier and
+    /// safe as a filename.
+    fn assign(&mut self, kind: ResourceKind, id: &str, prefix: &str) -> String {
+        let slug = slug(id, prefix);
+        let name = self.unique(kind, &slug, id);
+        if slug != id {
+            self.note(
+                kind,
+                &name,
+                format!("Renamed from '{}' to a valid resource name", id),
+            );
+        }
+        self.renamed
+            .entry((kind, id.to_string()))
+            .or_insert_with(|| name.clone());
+        name
+    }
+
+    /// Keeps a legacy name unless it is taken.
+    fn assign_verbatim(&mut self, kind: ResourceKind, name: &str) -> String {
+        self.unique(kind, name, name)
+    }
+
+    fn unique(&mut self, kind: ResourceKind, base: &str, id: &str) -> String {
+        let mut name = base.to_string();
+        let mut n = 1;
+        while self.taken.contains(&(kind, name.clone())) {
+            n += 1;
+            name = format!("{}-{}", base, n);
+        }
+        if n > 1 {
+            self.note(
+                kind,
+                &name,
+                format!(
+                    "'{}' is used more than once; this one is named '{}'",
+                    id, name
+                ),
+            );
+        }
+        self.taken.insert((kind, name.clone()));
+        name
+    }
+
+    fn note(&mut self, kind: ResourceKind, name: &str, message: String) {
+        self.notes.push(MigrationNote {
+            kind,
+            name: name.to_string(),
+            message,
+        });
+    }
+
+    /// The name given to a legacy id, or the id itself if it names nothing.
+    fn resolve(&self, kind: ResourceKind, id: &str) -> String {
+        self.renamed
+            .get(&(kind, id.to_string()))
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn transform(&self, transform: &LegacyTransform) -> VariableTransform {
+        match transform {
+            LegacyTransform::Slugify => VariableTransform::Slugify,
+            LegacyTransform::Uppercase => VariableTransform::Uppercase,
+            LegacyTransform::Lowercase => VariableTransform::Lowercase,
+            LegacyTransform::Trim => VariableTransform::Trim,
+            LegacyTransform::Lookup(table) => {
+                VariableTransform::Lookup(self.resolve(ResourceKind::LookupTable, table))
+            }
+        }
+    }
+
+    fn match_condition(&self, cond: &LegacyMatchCondition) -> MatchCondition {
+        match cond {
+            LegacyMatchCondition::Simple(s) => MatchCondition::Simple(SimpleMatch {
+                contains: s.contains.clone(),
+                contains_any: s.contains_any.clone(),
+                contains_all: s.contains_all.clone(),
+                pattern: s.pattern.clone(),
+                fuzzy: s.fuzzy.as_ref().map(|f| FuzzyMatch {
+                    value: f.value.clone(),
+                    max_distance: f.max_distance,
+                }),
+                contains_any_fuzzy: s.contains_any_fuzzy.clone(),
+                variable: s
+                    .variable
+                    .as_ref()
+                    .map(|v| Box::new(convert_variable_match(v))),
+                correspondent: s
+                    .correspondent
+                    .as_ref()
+                    .map(|c| self.resolve(ResourceKind::Correspondent, c)),
+                case_sensitive: s.case_sensitive,
+                field: s.field.map(convert_match_field),
+                pages: s.pages.as_ref().map(convert_page_selection),
+                region: s.region.map(convert_page_region),
+                weight: s.weight,
+            }),
+            LegacyMatchCondition::Compound(c) => MatchCondition::Compound(CompoundMatch {
+                all: c
+                    .all
+                    .as_ref()
+                    .map(|v| v.iter().map(|m| self.match_condition(m)).collect()),
+                any: c
+                    .any
+                    .as_ref()
+                    .map(|v| v.iter().map(|m| self.match_condition(m)).collect()),
+                not: c.not.as_ref().map(|n| Box::new(self.match_condition(n))),
+                case_sensitive: c.case_sensitive,
+                field: c.field.map(convert_match_field),
+                pages: c.pages.as_ref().map(convert_page_selection),
+                region: c.region.map(convert_page_region),
+                weight: c.weight,
+            }),
+        }
+    }
+}
+
+/// Lowercases an id and replaces runs of other characters than letters,
+/// digits and `_` by `-`. Ids that do not start with a letter or `_` are
+/// prefixed, such as `rule-2024` for `2024`.
+fn slug(id: &str, prefix: &str) -> String {
+    let mut slug = String::with_capacity(id.len());
+    for c in id.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || c == '_' {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    match slug.chars().next() {
+        Some(c) if c.is_alphabetic() || c == '_' => slug.to_string(),
+        Some(_) => format!("{}-{}", prefix, slug),
+        None => prefix.to_string(),
+    }
+}
+
+fn convert_variable_match(variable: &LegacyVariableMatch) -> VariableMatch {
+    VariableMatch {
+        name: variable.name.clone(),
+        eq: variable.eq.as_ref().map(convert_compare_value),
+        gt: variable.gt.as_ref().map(convert_compare_value),
+        lt: variable.lt.as_ref().map(convert_compare_value),
+        between: variable
+            .between
+            .as_ref()
+            .map(|[low, high]| [convert_compare_value(low), convert_compare_value(high)]),
+        exists: variable.exists,
+        one_of: variable
+            .one_of
+            .as_ref()
+            .map(|values| values.iter().map(convert_compare_value).collect()),
+    }
+}
+
+fn convert_compare_value(value: &LegacyCompareValue) -> CompareValue {
+    match value {
+        LegacyCompareValue::Number(n) => CompareValue::Number(*n),
+        LegacyCompareValue::Text(s) => CompareValue::Text(s.clone()),
+    }
+}
+
+fn convert_match_field(field: LegacyMatchField) -> MatchField {
+    match field {
+        LegacyMatchField::Text => MatchField::Text,
+        LegacyMatchField::Filename => MatchField::Filename,
+        LegacyMatchField::Source => MatchField::Source,
+        LegacyMatchField::MimeType => MatchField::MimeType,
+        LegacyMatchField::EmailFrom => MatchField::EmailFrom,
+        LegacyMatchField::EmailSubject => MatchField::EmailSubject,
+        LegacyMatchField::EmailTo => MatchField::EmailTo,
+        LegacyMatchField::FirstPage => MatchField::FirstPage,
+    }
+}
+
+fn convert_page_selection(pages: &LegacyPageSelection) -> PageSelection {
+    match pages {
+        LegacyPageSelection::Position(LegacyPagePosition::First) => {
+            PageSelection::Position(PagePosition::First)
+        }
+        LegacyPageSelection::Position(LegacyPagePosition::Last) => {
+            PageSelection::Position(PagePosition::Last)
+        }
+        LegacyPageSelection::Numbers(numbers) => PageSelection::Numbers(numbers.clone()),
+    }
+}
+
+fn convert_page_region(region: LegacyPageRegion) -> PageRegion {
+    match region {
+        LegacyPageRegion::Top(lines) => PageRegion::Top(lines),
+        LegacyPageRegion::Bottom(lines) => PageRegion::Bottom(lines),
+    }
+}
+
+fn convert_variable_source(source: LegacyVariableSource) -> VariableSource {
+    match source {
+        LegacyVariableSource::Text => VariableSource::Text,
+        LegacyVariableSource::Filename => VariableSource::Filename,
+        LegacyVariableSource::SourcePath => VariableSource::SourcePath,
+        LegacyVariableSource::EmailSubject => VariableSource::EmailSubject,
+        LegacyVariableSource::EmailFrom => VariableSource::EmailFrom,
+        LegacyVariableSource::PdfMetadata => VariableSource::PdfMetadata,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn legacy_config(json: &str) -> LegacyConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("outgoing-invoices", "rule"), "outgoing-invoices");
+        assert_eq!(slug("Tax Invoices (2024)", "rule"), "tax-invoices-2024");
+        assert_eq!(slug("  --a__b--  ", "rule"), "a__b");
+        assert_eq!(slug("2024", "rule"), "rule-2024");
+        assert_eq!(slug("!!!", "lookup"), "lookup");
+    }
+
+    #[test]
+    fn test_migrate_examples() {
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples");
+        for entry in std::fs::read_dir(examples).unwrap() {
+            let path = entry.unwrap().path();
+            let legacy = load_config(&path).unwrap();
+            let dir = TempDir::new().unwrap();
+            let loader = ConfigLoader::new(dir.path());
+
+            let report = migrate_legacy_config(&legacy, &loader).unwrap();
+            assert!(report.files.contains(&PathBuf::from("settings.yaml")));
+
+            let loaded = loader.load().unwrap().to_legacy_config();
+            let mut ids: Vec<_> = legacy.rules.iter().map(|r| r.id.as_str()).collect();
+            let mut names: Vec<_> = loaded.rules.iter().map(|r| r.id.as_str()).collect();
+            ids.sort();
+            names.sort();
+            assert_eq!(names, ids, "{}", path.display());
+            assert_eq!(loaded.output_directory, legacy.output_directory);
+            assert_eq!(
+                loaded.variables.extracted.len() + loaded.variables.computed.len(),
+                legacy.variables.extracted.len() + legacy.variables.computed.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_convert_renames_and_rewrites_references() {
+        let config = legacy_config(
+            r#"{
+                "version": "1.0",
+                "input_directory": "/in",
+                "output_directory": "/out",
+                "variables": {
+                    "extracted": [{
+                        "name": "vendor",
+                        "pattern": "From: (?P<vendor>.+)",
+                        "transform": {"lookup": "Vendor Names"}
+                    }],
+                    "computed": [{"name": "vendor", "template": "$vendor"}]
+                },
+                "rules": [
+                    {
+                        "id": "Tax Invoices",
+                        "name": "Tax Invoices",
+                        "match": {"correspondent": "ACME Corp"},
+                        "category": "tax"
+                    },
+                    {
+                        "id": "tax-invoices",
+                        "name": "tax-invoices",
+                        "match": {"contains": "tax"},
+                        "category": "tax"
+                    }
+                ],
+                "correspondents": [{"id": "ACME Corp", "name": "ACME Corp"}],
+                "lookup_tables": [{
+                    "name": "Vendor Names",
+                    "entries": [{"key": "acme", "value": "ACME"}]
+                }]
+            }"#,
+        );
+        let migration = convert_legacy_config(&config);
+
+        let names: Vec<_> = migration
+            .resources
+            .iter()
+            .map(|r| format!("{}/{}", r.kind(), r.name()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Settings/default",
+                "Correspondent/acme-corp",
+                "LookupTable/vendor-names",
+                "Variable/vendor",
+                "Variable/vendor-2",
+                "Rule/tax-invoices",
+                "Rule/tax-invoices-2",
+            ]
+        );
+
+        let AnyResource::Correspondent(acme) = &migration.resources[1] else {
+            panic!("expected a correspondent");
+        };
+        assert_eq!(acme.spec.display_name, "ACME Corp");
+        let AnyResource::Variable(vendor) = &migration.resources[3] else {
+            panic!("expected a variable");
+        };
+        assert_eq!(
+            vendor.spec.transform,
+            Some(VariableTransform::Lookup("vendor-names".to_string()))
+        );
+        let AnyResource::Rule(rule) = &migration.resources[5] else {
+            panic!("expected a rule");
+        };
+        let MatchCondition::Simple(condition) = &rule.spec.match_condition else {
+            panic!("expected a simple condition");
+        };
+        assert_eq!(condition.correspondent.as_deref(), Some("acme-corp"));
+        assert_eq!(
+            rule.metadata.annotations.get(DISPLAY_NAME_ANNOTATION),
+            Some(&"Tax Invoices".to_string())
+        );
+
+        let notes: Vec<_> = migration
+            .notes
+            .iter()
+            .map(|n| format!("{}/{}", n.kind, n.name))
+            .collect();
+        assert_eq!(
+            notes,
+            [
+                "Correspondent/acme-corp",
+                "LookupTable/vendor-names",
+                "Variable/vendor-2",
+                "Rule/tax-invoices",
+                "Rule/tax-invoices",
+                "Rule/tax-invoices-2",
+                "Rule/tax-invoices-2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migration_does_not_overwrite() {
+        let config = legacy_config(
+            r#"{"version": "1.0", "input_directory": "/in", "output_directory": "/out"}"#,
+        );
+        let dir = TempDir::new().unwrap();
+        let loader = ConfigLoader::new(dir.path());
+        std::fs::write(dir.path().join("settings.yaml"), "# mine\n").unwrap();
+
+        let result = migrate_legacy_config(&config, &loader);
+        assert!(matches!(
+            result,
+            Err(GitOpsError::ResourceAlreadyExists { .. })
+        ));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("settings.yaml")).unwrap(),
+            "# mine\n"
+        );
+    }
+}
@@ -1,35 +1,66 @@
 //! GitOps configuration system for paporg.
 //!
 //! This module provides a Kubernetes-style configuration system with:
-//! - Multi-file YAML configurations
-//! - Three resource kinds: Settings, Variable, Rule
+//! - Multi-file YAML configurations, with several resources per file
+//! - Overlays patching resources per machine
+//! - Resource kinds: Settings, Variable, Rule, ImportSource, RuleTest, RuleTemplate,
+//!   Correspondent, LookupTable
+//! - Rules extending other rules or templates
+//! - Rule regression tests run against sample documents
+//! - Static analysis of overlapping and unused rules
 //! - File system watching for real-time updates
 //! - Git integration for version control
-//! - Cross-resource validation
+//! - Cross-resource validation with diagnostics pointing at files and lines
+//! - Migration of legacy JSON configs into resources
+//! - Conversion of resources written in older API versions
 
+pub mod analysis;
+pub mod diagnostics;
+pub mod documents;
 pub mod error;
 pub mod git;
+pub mod inheritance;
 pub mod loader;
+pub mod lookup;
+pub mod migration;
+pub mod overlay;
 pub mod progress;
 pub mod reconciler;
 pub mod resource;
+pub mod rule_test;
+pub mod schema;
 pub mod sync_scheduler;
 pub mod validation;
+pub mod version;
 pub mod watcher;
 
+pub use analysis::{
+    RuleAnalyzer, RuleFinding, RuleFindingKind, RuleHistory, DEFAULT_UNUSED_AFTER_DAYS,
+};
+pub use diagnostics::{Diagnostic, DiagnosticCode, Severity};
 pub use error::{GitOpsError, Result};
 pub use git::types::*;
 pub use git::GitRepository;
-pub use loader::FileTreeNode;
 pub use loader::{ConfigLoader, LoadedConfig};
+pub use loader::{FileTreeNode, ResourceInfo};
+pub use migration::{
+    convert_legacy_config, migrate_legacy_config, LegacyMigration, MigrationNote, MigrationReport,
+};
+pub use overlay::{AppliedOverlay, FieldOrigin, OverlayPatch, OVERLAY_ENV_VAR};
 pub use reconciler::GitReconciler;
 pub use resource::{
-    AnyResource, CompoundMatch, FileFilters, GitAuthSettings, GitAuthType, GitSettings,
-    ImportSourceResource, ImportSourceSpec, ImportSourceType, LocalSourceConfig, MatchCondition,
-    ObjectMeta, OcrSettings, OutputSettings, Resource, ResourceKind, ResourceWithPath,
-    RuleResource, RuleSpec, SettingsResource, SettingsSpec, SimpleMatch, SymlinkSettings,
-    VariableResource, VariableSpec, VariableTransform, API_VERSION,
+    AnyResource, CategorizationMode, CategorizationSettings, CompareValue, CompoundMatch,
+    CorrespondentResource, CorrespondentSpec, FileFilters, FuzzyMatch, GitAuthSettings,
+    GitAuthType, GitSettings, ImportSourceResource, ImportSourceSpec, ImportSourceType,
+    LocalSourceConfig, LookupEntry, LookupMode, LookupTableResource, LookupTableSpec,
+    MatchCondition, MatchField, ObjectMeta, OcrSettings, OutputSettings, Resource, ResourceKind,
+    ResourceWithPath, RuleResource, RuleSpec, RuleTemplateResource, RuleTemplateSpec, RuleTestCase,
+    RuleTestExpectation, RuleTestResource, RuleTestSpec, SettingsResource, SettingsSpec,
+    SimpleMatch, SymlinkSettings, VariableMatch, VariableResource, VariableSpec, VariableTransform,
+    API_VERSION,
 };
+pub use rule_test::{RuleTestCaseResult, RuleTestReport, RuleTestRunner};
 pub use sync_scheduler::SyncScheduler;
 pub use validation::ConfigValidator;
+pub use version::{Conversion, ConversionRegistry, SpecConversion};
 pub use watcher::{ConfigChangeEvent, ConfigWatcher};
@@ -0,0 +1,495 @@
+//! Overlays patching resources per machine.
+//!
+//! An overlay is a directory `overlays/<name>/` of resource files. A
+//! document with the kind and name of a base resource is merged into it as
+//! a JSON merge patch: mappings merge key by key, `null` removes a key, and
+//! any other value, lists included, replaces the base value. The Settings
+//! resource is matched by kind alone. Documents matching no base resource
+//! are added as resources of their own.
+//!
+//! The overlay is picked by `ConfigLoader::with_overlay`, else by the
+//! `PAPORG_OVERLAY` environment variable, else by the `overlay` setting,
+//! in which `$hostname` stands for the name of the machine.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+use super::documents::{Document, DocumentSource};
+use super::error::{GitOpsError, Result};
+use super::resource::{ResourceHeader, ResourceKind, API_VERSION};
+use super::version::ConversionRegistry;
+
+/// Directory of the config directory holding the overlays.
+pub const OVERLAYS_DIRECTORY: &str = "overlays";
+
+/// Environment variable naming the overlay to apply.
+pub const OVERLAY_ENV_VAR: &str = "PAPORG_OVERLAY";
+
+/// The overlay applied to a loaded config.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedOverlay {
+    pub name: String,
+    /// The base resources the overlay patched.
+    pub patches: Vec<OverlayPatch>,
+}
+
+impl AppliedOverlay {
+    /// Returns the patch of a resource, if the overlay changed it.
+    pub fn patch(&self, kind: ResourceKind, name: &str) -> Option<&OverlayPatch> {
+        self.patches
+            .iter()
+            .find(|p| p.kind == kind && p.name == name)
+    }
+}
+
+/// A base resource patched by an overlay.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayPatch {
+    pub kind: ResourceKind,
+    pub name: String,
+    /// The file of the base resource, relative to the config directory.
+    pub path: PathBuf,
+    /// Where each field of the patched resource comes from.
+    pub fields: Vec<FieldOrigin>,
+}
+
+/// The file a field of a resource comes from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldOrigin {
+    /// Dotted path of the field, such as `spec.inputDirectory`. Lists are
+    /// fields of their own, as overlays replace them whole.
+    pub field: String,
+    /// Relative to the config directory.
+    pub path: PathBuf,
+}
+
+/// An overlay name and whether its directory must exist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlaySelection {
+    pub name: String,
+    /// False for names derived from the hostname, so that machines without
+    /// an overlay of their own use the base config.
+    pub required: bool,
+}
+
+/// Picks the overlay from an explicit name, the environment variable or
+/// the `overlay` setting, in that order.
+pub fn select_overlay(explicit: Option<&str>, setting: Option<&str>) -> Option<OverlaySelection> {
+    let from_env = std::env::var(OVERLAY_ENV_VAR).ok();
+    if let Some(name) = explicit
+        .or(from_env.as_deref())
+        .filter(|n| !n.trim().is_empty())
+    {
+        return Some(OverlaySelection {
+            name: name.trim().to_string(),
+            required: true,
+        });
+    }
+
+    let setting = setting.map(str::trim).filter(|s| !s.is_empty())?;
+    if setting.contains("$hostname") {
+        return Some(OverlaySelection {
+            name: setting.replace("$hostname", &hostname()?),
+            required: false,
+        });
+    }
+    Some(OverlaySelection {
+        name: setting.to_string(),
+        required: true,
+    })
+}
+
+/// Returns the lowercased name of this machine without its domain, from
+/// the `HOSTNAME` or `COMPUTERNAME` environment variable or the `hostname`
+/// command.
+pub fn hostname() -> Option<String> {
+    let name = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| {
+            let output = std::process::Command::new("hostname").output().ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        })?;
+    let name = name.trim().split('.').next().unwrap_or_default();
+    (!name.is_empty()).then(|| name.to_lowercase())
+}
+
+/// Returns the directory of an overlay. Names must be a single path
+/// component.
+pub fn overlay_directory(config_dir: &Path, name: &str) -> Result<PathBuf> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(config_dir.join(OVERLAYS_DIRECTORY).join(name)),
+        _ => Err(GitOpsError::InvalidOverlay {
+            name: name.to_string(),
+            message: "must be the name of a directory in overlays/".to_string(),
+        }),
+    }
+}
+
+/// Merges the overlay documents into the base documents, adding those that
+/// match no base resource.
+pub(crate) fn apply_overlay(
+    documents: &mut Vec<Document>,
+    overlay: Vec<Document>,
+    name: &str,
+) -> Result<AppliedOverlay> {
+    let identity = |document: &Document| {
+        document
+            .deserialize::<ResourceHeader>()
+            .ok()
+            .map(|h| (h.kind, h.metadata.name))
+    };
+    let identities: Vec<_> = documents.iter().map(identity).collect();
+
+    // Base document index, and the patches applied to it
+    let mut patched: Vec<(usize, Vec<AppliedPatch>)> = Vec::new();
+
+    for document in overlay {
+        let header: ResourceHeader = document.deserialize()?;
+        let target = identities.iter().position(|id| {
+            id.as_ref().is_some_and(|(kind, name)| {
+                *kind == header.kind
+                    && (header.kind == ResourceKind::Settings || *name == header.metadata.name)
+            })
+        });
+        let Some(index) = target else {
+            documents.push(document);
+            continue;
+        };
+
+        let mut patch = document.value()?;
+        strip_identity(&mut patch);
+        let mut merged = documents[index].value()?;
+        merge_patch(&mut merged, &patch);
+        documents[index].source = DocumentSource::Value(merged);
+
+        let applied = AppliedPatch {
+            fields: leaf_fields(&patch),
+            path: document.path,
+        };
+        match patched.iter_mut().find(|(i, _)| *i == index) {
+            Some((_, patches)) => patches.push(applied),
+            None => patched.push((index, vec![applied])),
+        }
+    }
+
+    let mut patches = Vec::new();
+    for (index, applied) in patched {
+        let base = &documents[index];
+        let Some((kind, resource_name)) = identities[index].clone() else {
+            continue;
+        };
+        let mut value = base.value()?;
+        strip_identity(&mut value);
+
+        let fields = leaf_fields(&value)
+            .into_iter()
+            .map(|field| {
+                let path = applied
+                    .iter()
+                    .rev()
+                    .find(|p| p.fields.contains(&field))
+                    .map_or(&base.path, |p| &p.path);
+                FieldOrigin {
+                    field,
+                    path: path.clone(),
+                }
+            })
+            .collect();
+        patches.push(OverlayPatch {
+            kind,
+            name: resource_name,
+            path: base.path.clone(),
+            fields,
+        });
+    }
+
+    Ok(AppliedOverlay {
+        name: name.to_string(),
+        patches,
+    })
+}
+
+/// The fields an overlay file set on a resource.
+struct AppliedPatch {
+    fields: Vec<String>,
+    path: PathBuf,
+}
+
+/// Applies a JSON merge patch (RFC 7386) to a YAML value.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Mapping(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_mapping() {
+        *target = Value::Mapping(Mapping::new());
+    }
+    let Value::Mapping(target) = target else {
+        return;
+    };
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Returns the merge patch turning `base` into `target`, the inverse of
+/// [`merge_patch`].
+pub fn diff_patch(base: &Value, target: &Value) -> Value {
+    let (Value::Mapping(base), Value::Mapping(target)) = (base, target) else {
+        return target.clone();
+    };
+    let mut patch = Mapping::new();
+    for (key, value) in target {
+        match base.get(key) {
+            Some(old) if old == value => {}
+            Some(old) if old.is_mapping() && value.is_mapping() => {
+                patch.insert(key.clone(), diff_patch(old, value));
+            }
+            _ => {
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    for key in base.keys() {
+        if !target.contains_key(key) {
+            patch.insert(key.clone(), Value::Null);
+        }
+    }
+    Value::Mapping(patch)
+}
+
+/// Converts an overlay patch of a base document written in an older API
+/// version. Patches are read in the version of their base, so both are
+/// converted: the patch is merged into the base, and the converted patch
+/// is what tells the converted base and the converted merge apart.
+pub(crate) fn upgrade_patch(
+    conversions: &ConversionRegistry,
+    kind: ResourceKind,
+    base: &Value,
+    patch: &Value,
+    path: &Path,
+) -> Result<Value> {
+    let mut changes = patch.clone();
+    strip_identity(&mut changes);
+    let mut merged = base.clone();
+    merge_patch(&mut merged, &changes);
+    let mut base = base.clone();
+    conversions.upgrade(kind, &mut base, path)?;
+    conversions.upgrade(kind, &mut merged, path)?;
+
+    let Value::Mapping(mut changes) = diff_patch(&base, &merged) else {
+        unreachable!("the difference of two mappings is a mapping");
+    };
+    let mut upgraded = Mapping::new();
+    upgraded.insert("apiVersion".into(), API_VERSION.into());
+    upgraded.insert("kind".into(), patch["kind"].clone());
+    let mut metadata = Mapping::new();
+    if let Some(name) = patch.get("metadata").and_then(|m| m.get("name")) {
+        metadata.insert("name".into(), name.clone());
+    }
+    if let Some(Value::Mapping(changed)) = changes.remove("metadata") {
+        metadata.extend(changed);
+    }
+    if !metadata.is_empty() {
+        upgraded.insert("metadata".into(), Value::Mapping(metadata));
+    }
+    upgraded.extend(changes);
+    Ok(Value::Mapping(upgraded))
+}
+
+/// Removes the fields identifying a resource, which overlays cannot change.
+fn strip_identity(value: &mut Value) {
+    let Value::Mapping(mapping) = value else {
+        return;
+    };
+    mapping.remove("apiVersion");
+    mapping.remove("kind");
+    if let Some(Value::Mapping(metadata)) = mapping.get_mut("metadata") {
+        metadata.remove("name");
+        if metadata.is_empty() {
+            mapping.remove("metadata");
+        }
+    }
+}
+
+/// Returns the dotted paths of the values set in a document.
+fn leaf_fields(value: &Value) -> Vec<String> {
+    fn collect(value: &Value, prefix: &str, fields: &mut Vec<String>) {
+        match value {
+            Value::Mapping(mapping) if !mapping.is_empty() => {
+                for (key, value) in mapping {
+                    let Some(key) = key.as_str() else {
+                        continue;
+                    };
+                    let field = if prefix.is_empty() {
+                        key.to_string()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    collect(value, &field, fields);
+                }
+            }
+            Value::Null => {}
+            _ if !prefix.is_empty() => fields.push(prefix.to_string()),
+            _ => {}
+        }
+    }
+
+    let mut fields = Vec::new();
+    collect(value, "", &mut fields);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> Value {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    fn document(path: &str, text: &str) -> Document {
+        Document {
+            path: PathBuf::from(path),
+            source: DocumentSource::Text(text.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut target = yaml("a: 1\nb: {c: 2, d: 3}\nlist: [1, 2]\n");
+        merge_patch(
+            &mut target,
+            &yaml("a: null\nb: {c: 5, e: 6}\nlist: [3]\nnew: x\n"),
+        );
+        assert_eq!(target, yaml("b: {c: 5, d: 3, e: 6}\nlist: [3]\nnew: x\n"));
+    }
+
+    #[test]
+    fn test_diff_patch() {
+        let base = yaml("a: 1\nb: {c: 2, d: 3}\nlist: [1, 2]\nsame: {x: 1}\n");
+        let target = yaml("b: {c: 5, d: 3, e: 6}\nlist: [3]\nnew: x\nsame: {x: 1}\n");
+        let patch = diff_patch(&base, &target);
+        assert_eq!(patch, yaml("a: null\nb: {c: 5, e: 6}\nlist: [3]\nnew: x\n"));
+
+        let mut merged = base;
+        merge_patch(&mut merged, &patch);
+        assert_eq!(merged, target);
+    }
+
+    #[test]
+    fn test_upgrade_patch() {
+        use crate::gitops::version::v1beta1;
+
+        let base = yaml(
+            "apiVersion: paporg.io/v1beta1\nkind: Rule\nmetadata:\n  name: receipts\n\
+             spec:\n  category: Receipts\n  output:\n    dir: Receipts\n",
+        );
+        let patch = yaml(
+            "apiVersion: paporg.io/v1beta1\nkind: Rule\nmetadata:\n  name: receipts\n\
+             spec:\n  output:\n    dir: /volume1/Receipts\n",
+        );
+        let conversions = ConversionRegistry::empty().register(v1beta1::CONVERSION);
+        let upgraded = upgrade_patch(
+            &conversions,
+            ResourceKind::Rule,
+            &base,
+            &patch,
+            Path::new("overlays/nas/receipts.yaml"),
+        )
+        .unwrap();
+        assert_eq!(
+            upgraded,
+            yaml(
+                "apiVersion: paporg.io/v1\nkind: Rule\nmetadata:\n  name: receipts\n\
+                 spec:\n  output:\n    directory: /volume1/Receipts\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_apply_overlay() {
+        let mut documents = vec![
+            document(
+                "settings.yaml",
+                "apiVersion: paporg.io/v1\nkind: Settings\nmetadata:\n  name: default\n\
+                 spec:\n  inputDirectory: /inbox\n  outputDirectory: /documents\n",
+            ),
+            document(
+                "sources/scans.yaml",
+                "apiVersion: paporg.io/v1\nkind: ImportSource\nmetadata:\n  name: scans\n\
+                 spec:\n  type: local\n  enabled: true\n",
+            ),
+        ];
+        let overlay = vec![
+            document(
+                "overlays/nas/settings.yaml",
+                "apiVersion: paporg.io/v1\nkind: Settings\nmetadata:\n  name: nas\n\
+                 spec:\n  inputDirectory: /volume1/inbox\n",
+            ),
+            document(
+                "overlays/nas/extra.yaml",
+                "apiVersion: paporg.io/v1\nkind: Variable\nmetadata:\n  name: extra\n\
+                 spec:\n  pattern: x\n",
+            ),
+        ];
+
+        let applied = apply_overlay(&mut documents, overlay, "nas").unwrap();
+
+        assert_eq!(applied.name, "nas");
+        assert_eq!(documents.len(), 3);
+        let settings = documents[0].value().unwrap();
+        assert_eq!(settings["spec"]["inputDirectory"], "/volume1/inbox");
+        assert_eq!(settings["spec"]["outputDirectory"], "/documents");
+        assert_eq!(settings["metadata"]["name"], "default");
+
+        assert_eq!(applied.patches.len(), 1);
+        let patch = applied.patch(ResourceKind::Settings, "default").unwrap();
+        assert_eq!(patch.path, PathBuf::from("settings.yaml"));
+        assert_eq!(
+            patch.fields,
+            vec![
+                FieldOrigin {
+                    field: "spec.inputDirectory".to_string(),
+                    path: PathBuf::from("overlays/nas/settings.yaml"),
+                },
+                FieldOrigin {
+                    field: "spec.outputDirectory".to_string(),
+                    path: PathBuf::from("settings.yaml"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_overlay() {
+        assert_eq!(
+            select_overlay(Some("laptop"), Some("nas")),
+            Some(OverlaySelection {
+                name: "laptop".to_string(),
+                required: true,
+            })
+        );
+        assert_eq!(select_overlay(None, Some("  ")), None);
+        assert!(overlay_directory(Path::new("/config"), "../etc").is_err());
+        assert_eq!(
+            overlay_directory(Path::new("/config"), "nas").unwrap(),
+            PathBuf::from("/config/overlays/nas")
+        );
+    }
+}
@@ -1,8 +1,11 @@
 //! K8s-style resource types for GitOps configuration.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::schema::alias;
+
 /// The API version for all paporg resources.
 pub const API_VERSION: &str = "paporg.io/v1";
 
@@ -13,6 +16,10 @@ pub enum ResourceKind {
     Variable,
     Rule,
     ImportSource,
+    RuleTest,
+    RuleTemplate,
+    Correspondent,
+    LookupTable,
 }
 
 impl ResourceKind {
@@ -23,6 +30,10 @@ impl ResourceKind {
             ResourceKind::Variable => Some("variables"),
             ResourceKind::Rule => Some("rules"),
             ResourceKind::ImportSource => Some("sources"),
+            ResourceKind::RuleTest => Some("tests"),
+            ResourceKind::RuleTemplate => Some("templates"),
+            ResourceKind::Correspondent => Some("correspondents"),
+            ResourceKind::LookupTable => Some("lookups"),
         }
     }
 
@@ -33,6 +44,10 @@ impl ResourceKind {
             ResourceKind::Variable,
             ResourceKind::Rule,
             ResourceKind::ImportSource,
+            ResourceKind::RuleTest,
+            ResourceKind::RuleTemplate,
+            ResourceKind::Correspondent,
+            ResourceKind::LookupTable,
         ]
     }
 }
@@ -44,6 +59,10 @@ impl std::fmt::Display for ResourceKind {
             ResourceKind::Variable => write!(f, "Variable"),
             ResourceKind::Rule => write!(f, "Rule"),
             ResourceKind::ImportSource => write!(f, "ImportSource"),
+            ResourceKind::RuleTest => write!(f, "RuleTest"),
+            ResourceKind::RuleTemplate => write!(f, "RuleTemplate"),
+            ResourceKind::Correspondent => write!(f, "Correspondent"),
+            ResourceKind::LookupTable => write!(f, "LookupTable"),
         }
     }
 }
@@ -57,13 +76,17 @@ impl std::str::FromStr for ResourceKind {
             "variable" => Ok(ResourceKind::Variable),
             "rule" => Ok(ResourceKind::Rule),
             "importsource" => Ok(ResourceKind::ImportSource),
+            "ruletest" => Ok(ResourceKind::RuleTest),
+            "ruletemplate" => Ok(ResourceKind::RuleTemplate),
+            "correspondent" => Ok(ResourceKind::Correspondent),
+            "lookuptable" => Ok(ResourceKind::LookupTable),
             _ => Err(format!("Unknown resource kind: {}", s)),
         }
     }
 }
 
 /// Metadata for a resource, following K8s conventions.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ObjectMeta {
     /// The unique name of the resource within its kind.
     pub name: String,
@@ -133,7 +156,7 @@ impl<T> Resource<T> {
 // ============================================================================
 
 /// Release channel for automatic updates.
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum ReleaseChannel {
     #[default]
@@ -146,7 +169,7 @@ fn default_release_channel() -> ReleaseChannel {
 }
 
 /// Settings specification - global configuration for paporg.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SettingsSpec {
     /// Directory to watch for incoming documents.
@@ -163,6 +186,14 @@ pub struct SettingsSpec {
     #[serde(default)]
     pub ocr: OcrSettings,
 
+    /// Per-document resource limits.
+    #[serde(default)]
+    pub limits: LimitsSettings,
+
+    /// How rules are chosen for a document.
+    #[serde(default)]
+    pub categorization: CategorizationSettings,
+
     /// Default output settings.
     #[serde(default)]
     pub defaults: DefaultOutputSettings,
@@ -178,6 +209,12 @@ pub struct SettingsSpec {
     /// Release channel for automatic updates.
     #[serde(default = "default_release_channel")]
     pub release_channel: ReleaseChannel,
+
+    /// Overlay of the `overlays` directory to apply, where `$hostname`
+    /// stands for the name of the machine. The `PAPORG_OVERLAY`
+    /// environment variable takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overlay: Option<String>,
 }
 
 fn default_worker_count() -> usize {
@@ -185,7 +222,7 @@ fn default_worker_count() -> usize {
 }
 
 /// OCR settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OcrSettings {
     /// Whether OCR is enabled.
     #[serde(default = "default_true")]
@@ -204,6 +241,10 @@ fn default_true() -> bool {
     true
 }
 
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
 fn default_languages() -> Vec<String> {
     vec!["eng".to_string()]
 }
@@ -222,8 +263,183 @@ impl Default for OcrSettings {
     }
 }
 
+/// Per-document resource limits. A value of 0 disables the limit; a missing
+/// value takes the default, so settings without limits are limited.
+///
+/// Settings written before limits existed get these defaults too; to keep the
+/// old unlimited behavior, set every limit to 0.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitsSettings {
+    /// Largest accepted input file in megabytes (default 100).
+    #[serde(default = "default_max_file_size_mb")]
+    pub max_file_size_mb: u64,
+
+    /// Largest accepted page count (default 500).
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+
+    /// Wall-clock timeout for processing a single document, in seconds
+    /// (default 900).
+    #[serde(default = "default_job_timeout_secs")]
+    pub job_timeout_secs: u64,
+
+    /// Timeout for rendering and OCR of a single page, in seconds (default 120).
+    #[serde(default = "default_ocr_page_timeout_secs")]
+    pub ocr_page_timeout_secs: u64,
+
+    /// Largest number of files unpacked from a single archive (default 1000).
+    #[serde(default = "default_max_archive_entries")]
+    pub max_archive_entries: usize,
+
+    /// Largest total uncompressed size of a single archive in megabytes
+    /// (default 500).
+    #[serde(default = "default_max_archive_size_mb")]
+    pub max_archive_size_mb: u64,
+
+    /// Deepest nesting of archives inside archives (default 3).
+    #[serde(default = "default_max_archive_depth")]
+    pub max_archive_depth: u32,
+}
+
+fn default_max_file_size_mb() -> u64 {
+    100
+}
+
+fn default_max_pages() -> usize {
+    500
+}
+
+fn default_job_timeout_secs() -> u64 {
+    900 // 15 minutes
+}
+
+fn default_ocr_page_timeout_secs() -> u64 {
+    120 // 2 minutes
+}
+
+fn default_max_archive_entries() -> usize {
+    1000
+}
+
+fn default_max_archive_size_mb() -> u64 {
+    500
+}
+
+fn default_max_archive_depth() -> u32 {
+    3
+}
+
+impl Default for LimitsSettings {
+    fn default() -> Self {
+        Self {
+            max_file_size_mb: default_max_file_size_mb(),
+            max_pages: default_max_pages(),
+            job_timeout_secs: default_job_timeout_secs(),
+            ocr_page_timeout_secs: default_ocr_page_timeout_secs(),
+            max_archive_entries: default_max_archive_entries(),
+            max_archive_size_mb: default_max_archive_size_mb(),
+            max_archive_depth: default_max_archive_depth(),
+        }
+    }
+}
+
+/// Rule selection settings.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategorizationSettings {
+    /// Selection mode. Default is `firstMatch`.
+    #[serde(default)]
+    pub mode: CategorizationMode,
+
+    /// Lowest score, between 0 and 1, a rule needs to win in scoring mode.
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+
+    /// Number of runner-up rules reported with the result in scoring mode.
+    #[serde(default = "default_alternatives")]
+    pub alternatives: usize,
+
+    /// Classifier that predicts a category when no rule matches.
+    #[serde(default)]
+    pub classifier: ClassifierSettings,
+}
+
+/// Naive Bayes classifier trained on the job history: documents filed by a
+/// rule and documents whose category was corrected by hand.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifierSettings {
+    /// Whether to predict a category for unmatched documents. Default is true.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// File documents under the predicted category when its confidence
+    /// reaches the threshold. Otherwise the prediction is only suggested.
+    #[serde(default)]
+    pub auto_file: bool,
+
+    /// Lowest confidence, between 0 and 1, for auto-filing. Default is 0.9.
+    #[serde(default = "default_classifier_threshold")]
+    pub threshold: f64,
+
+    /// Fewest training documents a category needs before it is predicted.
+    #[serde(default = "default_min_examples")]
+    pub min_examples: usize,
+}
+
+fn default_classifier_threshold() -> f64 {
+    0.9
+}
+
+fn default_min_examples() -> usize {
+    5
+}
+
+impl Default for ClassifierSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_file: false,
+            threshold: default_classifier_threshold(),
+            min_examples: default_min_examples(),
+        }
+    }
+}
+
+/// How a rule is chosen for a document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CategorizationMode {
+    /// The first matching rule in priority order wins.
+    #[default]
+    FirstMatch,
+    /// Every rule is scored from its weighted conditions; the best score
+    /// above the threshold wins, ties going to the higher priority.
+    Scoring,
+}
+
+fn default_threshold() -> f64 {
+    0.5
+}
+
+fn default_alternatives() -> usize {
+    3
+}
+
+impl Default for CategorizationSettings {
+    fn default() -> Self {
+        Self {
+            mode: CategorizationMode::default(),
+            threshold: default_threshold(),
+            alternatives: default_alternatives(),
+            classifier: ClassifierSettings::default(),
+        }
+    }
+}
+
 /// Default output settings for documents that don't match any rule.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DefaultOutputSettings {
     /// Output path configuration.
     pub output: OutputSettings,
@@ -241,7 +457,7 @@ impl Default for DefaultOutputSettings {
 }
 
 /// Git synchronization settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GitSettings {
     /// Whether git sync is enabled.
@@ -313,8 +529,9 @@ fn default_user_email() -> String {
 }
 
 /// Git authentication settings.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
+#[schemars(transform = alias("tokenInsecure", "token"))]
 pub struct GitAuthSettings {
     /// Authentication type: none, token, or ssh-key.
     #[serde(default, rename = "type")]
@@ -340,7 +557,7 @@ pub struct GitAuthSettings {
 }
 
 /// Git authentication type.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum GitAuthType {
     #[default]
@@ -350,7 +567,7 @@ pub enum GitAuthType {
 }
 
 /// AI settings for rule suggestions.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AiSettings {
     /// Whether AI suggestions are enabled.
@@ -435,24 +652,77 @@ pub type SettingsResource = Resource<SettingsSpec>;
 // Variable Resource
 // ============================================================================
 
-/// Variable specification - defines how to extract variables from document text.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Variable specification - defines how to extract variables from document
+/// text, or how to compute them from other variables.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VariableSpec {
     /// Regex pattern to extract the variable value.
     /// Use named capture groups like `(?P<value>...)`.
+    /// Optional for computed variables, where it searches the computed text.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub pattern: String,
 
-    /// Optional transformation to apply to the extracted value.
+    /// Computes the value from other variables instead of the document,
+    /// such as `$doc_y-Q$quarter`. Computed variables are evaluated after
+    /// extraction, each after the variables it references; the default
+    /// applies when a referenced variable has no value.
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// Optional transformation to apply to the extracted value.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_yaml::with::singleton_map"
+    )]
+    #[schemars(with = "Option<VariableTransform>")]
     pub transform: Option<VariableTransform>,
 
     /// Default value if the pattern doesn't match.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default: Option<String>,
+
+    /// Only search these pages of the extracted text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<PageSelection>,
+
+    /// Only search the top or bottom lines of each page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<PageRegion>,
+
+    /// Input to search instead of the document text. Pages and regions
+    /// only apply to the text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<VariableSource>,
+}
+
+/// The input of a document a variable pattern is searched in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum VariableSource {
+    /// The extracted document text. The default.
+    #[serde(rename = "text")]
+    Text,
+    /// The original filename, such as `2024-03-12_Scan_0042.pdf`.
+    #[serde(rename = "filename")]
+    Filename,
+    /// The path of the file below its import source directory, with `/`
+    /// separators, such as `Insurance/2024/scan.pdf`.
+    #[serde(rename = "sourcePath")]
+    SourcePath,
+    /// The Subject header of the source email.
+    #[serde(rename = "email.subject")]
+    EmailSubject,
+    /// The From header of the source email.
+    #[serde(rename = "email.from")]
+    EmailFrom,
+    /// The document information of a PDF as `Key: value` lines, such as
+    /// `Title: Invoice 42`.
+    #[serde(rename = "pdfMetadata")]
+    PdfMetadata,
 }
 
 /// Transformation to apply to extracted variable values.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum VariableTransform {
     /// Convert to URL-friendly slug.
@@ -463,6 +733,9 @@ pub enum VariableTransform {
     Lowercase,
     /// Trim whitespace.
     Trim,
+    /// Replace the value by its entry in the named LookupTable, written as
+    /// `transform: { lookup: vendors }`.
+    Lookup(String),
 }
 
 /// Type alias for Variable resource.
@@ -473,20 +746,42 @@ pub type VariableResource = Resource<VariableSpec>;
 // ============================================================================
 
 /// Rule specification - defines how to categorize and organize documents.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RuleSpec {
     /// Priority of this rule (higher = more important).
     #[serde(default)]
     pub priority: i32,
 
-    /// Category name for matched documents.
+    /// Name of a rule or rule template to inherit from. The category and
+    /// each output template are inherited unless set here, symlinks are
+    /// added to the inherited ones, and the match condition must hold
+    /// together with the inherited one. Priority and exclusivity are never
+    /// inherited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// Category name for matched documents, or the tag added by a
+    /// non-exclusive rule.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub category: String,
 
+    /// Whether this rule competes for the document's category. A
+    /// non-exclusive rule tags every document it matches and adds its
+    /// symlinks, alongside the category chosen by the exclusive rules.
+    /// Default is true.
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub exclusive: bool,
+
     /// Match conditions for this rule.
-    #[serde(rename = "match")]
+    #[serde(
+        rename = "match",
+        default,
+        skip_serializing_if = "MatchCondition::is_empty"
+    )]
     pub match_condition: MatchCondition,
 
-    /// Output path configuration.
+    /// Output path configuration. Not used by non-exclusive rules.
+    #[serde(default, skip_serializing_if = "OutputSettings::is_empty")]
     pub output: OutputSettings,
 
     /// Additional symlinks to create.
@@ -495,7 +790,7 @@ pub struct RuleSpec {
 }
 
 /// Match condition for rules.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum MatchCondition {
     /// Simple match condition.
@@ -504,8 +799,87 @@ pub enum MatchCondition {
     Compound(CompoundMatch),
 }
 
+impl Default for MatchCondition {
+    fn default() -> Self {
+        MatchCondition::Simple(SimpleMatch::default())
+    }
+}
+
+impl MatchCondition {
+    /// Returns true if the condition checks nothing, as when a rule
+    /// inheriting its match condition leaves `match` out.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            MatchCondition::Simple(simple) => {
+                simple.contains.is_none()
+                    && simple.contains_any.is_none()
+                    && simple.contains_all.is_none()
+                    && simple.pattern.is_none()
+                    && simple.fuzzy.is_none()
+                    && simple.contains_any_fuzzy.is_none()
+                    && simple.variable.is_none()
+                    && simple.correspondent.is_none()
+            }
+            MatchCondition::Compound(compound) => {
+                compound.all.is_none() && compound.any.is_none() && compound.not.is_none()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MatchCondition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde_json::Value;
+
+        // Every field of both variants is optional, so an untagged match would
+        // always pick the simple variant. Decide by the compound keys instead.
+        let value = Value::deserialize(deserializer)?;
+        let Value::Object(map) = &value else {
+            return Err(D::Error::custom("match condition must be an object"));
+        };
+
+        if map.contains_key("all") || map.contains_key("any") || map.contains_key("not") {
+            serde_json::from_value(value)
+                .map(MatchCondition::Compound)
+                .map_err(|e| D::Error::custom(format!("invalid compound match: {}", e)))
+        } else {
+            serde_json::from_value(value)
+                .map(MatchCondition::Simple)
+                .map_err(|e| D::Error::custom(format!("invalid simple match: {}", e)))
+        }
+    }
+}
+
+impl JsonSchema for MatchCondition {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "MatchCondition".into()
+    }
+
+    /// Conditions with `all`, `any` or `not` are compound, like in the
+    /// deserializer.
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "object",
+            "description": "Match condition for rules.",
+            "if": {
+                "anyOf": [
+                    { "required": ["all"] },
+                    { "required": ["any"] },
+                    { "required": ["not"] }
+                ]
+            },
+            "then": generator.subschema_for::<CompoundMatch>(),
+            "else": generator.subschema_for::<SimpleMatch>()
+        })
+    }
+}
+
 /// A simple match condition.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SimpleMatch {
     /// Match if text contains this string.
@@ -524,13 +898,167 @@ pub struct SimpleMatch {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pattern: Option<String>,
 
+    /// Match if text contains this value, tolerating OCR errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuzzy: Option<FuzzyMatch>,
+
+    /// Match if text contains any of these values, tolerating OCR errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contains_any_fuzzy: Option<Vec<String>>,
+
+    /// Match by comparing an extracted variable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variable: Option<Box<VariableMatch>>,
+
+    /// Match documents detected as coming from this correspondent, by the
+    /// name of its Correspondent resource.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correspondent: Option<String>,
+
     /// Whether matching is case-sensitive. Default is false (case-insensitive).
+    /// Fuzzy conditions are always case-insensitive.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub case_sensitive: Option<bool>,
+
+    /// The part of the document to match against. Default is the extracted
+    /// text; email headers are only searched through their own fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<MatchField>,
+
+    /// Limits the condition to these pages of the extracted text. Only for
+    /// conditions on the text, the first page or the default field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<PageSelection>,
+
+    /// Limits the condition to the top or bottom lines of each page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<PageRegion>,
+
+    /// Weight of this condition among the siblings of an `all` in scoring
+    /// mode. Default is 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+}
+
+/// The part of a document a match condition is evaluated against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum MatchField {
+    /// The extracted document text, also searched without a field.
+    #[serde(rename = "text")]
+    Text,
+    /// The original filename.
+    #[serde(rename = "filename")]
+    Filename,
+    /// The name of the import source that discovered the document.
+    #[serde(rename = "source")]
+    Source,
+    /// The MIME type of the source file.
+    #[serde(rename = "mimeType")]
+    MimeType,
+    /// The From header of the source email.
+    #[serde(rename = "email.from")]
+    EmailFrom,
+    /// The Subject header of the source email.
+    #[serde(rename = "email.subject")]
+    EmailSubject,
+    /// The To header of the source email.
+    #[serde(rename = "email.to")]
+    EmailTo,
+    /// The extracted text of the first page.
+    #[serde(rename = "firstPage")]
+    FirstPage,
+}
+
+/// Pages of the extracted text, numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PageSelection {
+    /// The first or the last page.
+    Position(PagePosition),
+    /// A list of page numbers, such as `[1, 2]`.
+    Numbers(Vec<usize>),
+}
+
+/// A page identified by its position in the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PagePosition {
+    /// The first page, where letterheads and sender addresses sit.
+    First,
+    /// The last page, where totals and signatures sit.
+    Last,
+}
+
+/// Lines of a page, written as `top: 10` or `bottom: 5`. Blank lines are
+/// not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PageRegion {
+    /// The first N lines of the page.
+    Top(usize),
+    /// The last N lines of the page.
+    Bottom(usize),
+}
+
+/// A comparison against an extracted variable. Every operator that is set
+/// must hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableMatch {
+    /// Name of the extracted variable.
+    pub name: String,
+
+    /// Match if the value equals this one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eq: Option<CompareValue>,
+
+    /// Match if the value is greater than this one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gt: Option<CompareValue>,
+
+    /// Match if the value is less than this one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lt: Option<CompareValue>,
+
+    /// Match if the value lies between both bounds, inclusive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub between: Option<[CompareValue; 2]>,
+
+    /// Match if the variable was (true) or was not (false) extracted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exists: Option<bool>,
+
+    /// Match if the value equals any of these.
+    #[serde(rename = "in", default, skip_serializing_if = "Option::is_none")]
+    pub one_of: Option<Vec<CompareValue>>,
+}
+
+/// A value a variable is compared with. Dates are written as text
+/// (`2020-01-01` or `31.12.2019`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum CompareValue {
+    /// A number.
+    Number(f64),
+    /// Text, a date or a number written as text.
+    Text(String),
+}
+
+/// An OCR-tolerant containment check.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FuzzyMatch {
+    /// The value to look for.
+    pub value: String,
+
+    /// Maximum edit distance after normalization. Defaults to 0 for values
+    /// shorter than 5 characters, 1 up to 11 and 2 beyond.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_distance: Option<usize>,
 }
 
 /// A compound match condition.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CompoundMatch {
     /// All conditions must match.
@@ -548,20 +1076,46 @@ pub struct CompoundMatch {
     /// Whether matching is case-sensitive for all children. Default is false (case-insensitive).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub case_sensitive: Option<bool>,
+
+    /// The document field all children match against unless they set their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<MatchField>,
+
+    /// The pages all children match against unless they set their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<PageSelection>,
+
+    /// The page region all children match against unless they set their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<PageRegion>,
+
+    /// Weight of this condition among the siblings of an `all` in scoring
+    /// mode. Default is 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
 }
 
 /// Output path settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct OutputSettings {
     /// Directory path template.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub directory: String,
 
     /// Filename template.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub filename: String,
 }
 
+impl OutputSettings {
+    /// Returns true if neither template is set.
+    pub fn is_empty(&self) -> bool {
+        self.directory.is_empty() && self.filename.is_empty()
+    }
+}
+
 /// Symlink configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SymlinkSettings {
     /// Target directory template for the symlink.
     pub target: String,
@@ -575,7 +1129,7 @@ pub type RuleResource = Resource<RuleSpec>;
 // ============================================================================
 
 /// Type of import source.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ImportSourceType {
     Local,
@@ -583,7 +1137,7 @@ pub enum ImportSourceType {
 }
 
 /// ImportSource specification - defines where to import documents from.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportSourceSpec {
     /// The type of import source.
@@ -604,7 +1158,7 @@ pub struct ImportSourceSpec {
 }
 
 /// Configuration for a local directory import source.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalSourceConfig {
     /// Path to the local directory to watch.
@@ -628,7 +1182,7 @@ fn default_poll_interval() -> u64 {
 }
 
 /// Configuration for an email attachment import source.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EmailSourceConfig {
     /// IMAP server hostname (e.g., "imap.gmail.com").
@@ -698,8 +1252,9 @@ fn default_batch_size() -> u32 {
 }
 
 /// Email authentication settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
+#[schemars(transform = alias("passwordInsecure", "password"))]
 pub struct EmailAuthSettings {
     /// Authentication type.
     #[serde(rename = "type")]
@@ -730,7 +1285,7 @@ pub struct EmailAuthSettings {
 }
 
 /// Email authentication type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum EmailAuthType {
     #[default]
@@ -744,7 +1299,7 @@ pub enum EmailAuthType {
 /// OAuth2 provider. Use `gmail` or `outlook` for well-known providers with
 /// pre-configured endpoints, or `custom` for other providers (requires
 /// explicit `deviceAuthUrl` and `tokenUrl` configuration).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum OAuth2Provider {
     Gmail,
@@ -754,8 +1309,13 @@ pub enum OAuth2Provider {
 }
 
 /// OAuth2 authentication settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
+#[schemars(
+    transform = alias("clientIdInsecure", "client_id"),
+    transform = alias("clientSecretInsecure", "client_secret"),
+    transform = alias("refreshTokenInsecure", "refresh_token")
+)]
 pub struct OAuth2Settings {
     /// OAuth2 provider preset (gmail, outlook, custom).
     /// If not specified, defaults to custom (requires explicit URLs).
@@ -821,7 +1381,7 @@ pub struct OAuth2Settings {
 }
 
 /// Attachment filters based on MIME types and filenames.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AttachmentFilters {
     /// MIME type patterns to include (e.g., "application/pdf", "image/*").
@@ -842,7 +1402,7 @@ pub struct AttachmentFilters {
 }
 
 /// File filters for import sources.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct FileFilters {
     /// Glob patterns to include (default: ["*"]).
     #[serde(default = "default_include_patterns")]
@@ -860,6 +1420,207 @@ fn default_include_patterns() -> Vec<String> {
 /// Type alias for ImportSource resource.
 pub type ImportSourceResource = Resource<ImportSourceSpec>;
 
+// ============================================================================
+// RuleTest Resource
+// ============================================================================
+
+/// Specification for a RuleTest resource: sample documents and how the
+/// rules are expected to handle them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestSpec {
+    /// The test cases.
+    pub cases: Vec<RuleTestCase>,
+}
+
+/// A sample document and its expected categorization.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestCase {
+    /// Name of the case, unique within the test.
+    pub name: String,
+
+    /// Sample document text. Exactly one of `text` and `file` is required.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    /// Sample document path, relative to the config directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+
+    /// Original filename used for `$original`. Defaults to the name of
+    /// `file`, or `sample.pdf` for text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+
+    /// Import source name, for conditions on the `source` field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// The expected outcome. Only the fields that are set are checked.
+    pub expect: RuleTestExpectation,
+}
+
+/// Expected outcome of a rule test case.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestExpectation {
+    /// Expected category; `unsorted` when no rule should match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    /// Name of the rule expected to categorize the document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+
+    /// Expected output path relative to the output directory, such as
+    /// `invoices/bill.pdf`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl RuleTestExpectation {
+    /// Returns true if no expectation is set.
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none() && self.rule.is_none() && self.output.is_none()
+    }
+}
+
+/// Type alias for RuleTest resource.
+pub type RuleTestResource = Resource<RuleTestSpec>;
+
+// ============================================================================
+// RuleTemplate Resource
+// ============================================================================
+
+/// Specification for a RuleTemplate resource: the shared parts of rules
+/// that extend it. Templates never categorize documents themselves.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTemplateSpec {
+    /// Name of a rule or rule template to inherit from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// Category for extending rules that do not set their own.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub category: String,
+
+    /// Match conditions every extending rule must satisfy as well as its own.
+    #[serde(
+        rename = "match",
+        default,
+        skip_serializing_if = "MatchCondition::is_empty"
+    )]
+    pub match_condition: MatchCondition,
+
+    /// Output templates for extending rules that do not set their own.
+    #[serde(default, skip_serializing_if = "OutputSettings::is_empty")]
+    pub output: OutputSettings,
+
+    /// Symlinks created for documents of every extending rule.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symlinks: Vec<SymlinkSettings>,
+}
+
+/// Type alias for RuleTemplate resource.
+pub type RuleTemplateResource = Resource<RuleTemplateSpec>;
+
+// ============================================================================
+// Correspondent Resource
+// ============================================================================
+
+/// Specification for a Correspondent resource: a sender of documents and
+/// the details that identify it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrespondentSpec {
+    /// Name used for `$correspondent`. Defaults to the resource name.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub display_name: String,
+
+    /// Other names the correspondent appears under in document text.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// IBANs of the correspondent, matched ignoring spaces and case.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ibans: Vec<String>,
+
+    /// VAT or UID numbers, matched ignoring spaces, dots, dashes and case.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vat_numbers: Vec<String>,
+
+    /// Email domains the correspondent sends from, including subdomains.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub email_domains: Vec<String>,
+
+    /// Category for documents from this correspondent that no rule matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_category: Option<String>,
+}
+
+impl CorrespondentSpec {
+    /// Returns the display name, or `name` if none is set.
+    pub fn display_name_or<'a>(&'a self, name: &'a str) -> &'a str {
+        if self.display_name.is_empty() {
+            name
+        } else {
+            &self.display_name
+        }
+    }
+}
+
+/// Type alias for Correspondent resource.
+pub type CorrespondentResource = Resource<CorrespondentSpec>;
+
+// ============================================================================
+// LookupTable Resource
+// ============================================================================
+
+/// Specification for a LookupTable resource: maps the messy values of an
+/// extracted variable to canonical ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupTableSpec {
+    /// How entry keys are compared with values.
+    #[serde(default)]
+    pub mode: LookupMode,
+
+    /// Entries, checked in order before those of `file`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<LookupEntry>,
+
+    /// CSV file with `key,value` rows, relative to the config directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+
+    /// Value for keys no entry matches. Without it, the value is kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+}
+
+/// How the keys of a lookup table are compared with values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LookupMode {
+    /// Keys equal the value, ignoring case and surrounding whitespace.
+    #[default]
+    Exact,
+    /// Keys are regex patterns searched in the value, ignoring case.
+    Regex,
+}
+
+/// A key of a lookup table and the value it maps to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LookupEntry {
+    pub key: String,
+    pub value: String,
+}
+
+/// Type alias for LookupTable resource.
+pub type LookupTableResource = Resource<LookupTableSpec>;
+
 // ============================================================================
 // Any Resource (for generic handling)
 // ============================================================================
@@ -873,6 +1634,10 @@ pub enum AnyResource {
     Variable(VariableResource),
     Rule(RuleResource),
     ImportSource(ImportSourceResource),
+    RuleTest(RuleTestResource),
+    RuleTemplate(RuleTemplateResource),
+    Correspondent(CorrespondentResource),
+    LookupTable(LookupTableResource),
 }
 
 impl AnyResource {
@@ -883,6 +1648,10 @@ impl AnyResource {
             AnyResource::Variable(_) => ResourceKind::Variable,
             AnyResource::Rule(_) => ResourceKind::Rule,
             AnyResource::ImportSource(_) => ResourceKind::ImportSource,
+            AnyResource::RuleTest(_) => ResourceKind::RuleTest,
+            AnyResource::RuleTemplate(_) => ResourceKind::RuleTemplate,
+            AnyResource::Correspondent(_) => ResourceKind::Correspondent,
+            AnyResource::LookupTable(_) => ResourceKind::LookupTable,
         }
     }
 
@@ -893,6 +1662,10 @@ impl AnyResource {
             AnyResource::Variable(r) => &r.metadata.name,
             AnyResource::Rule(r) => &r.metadata.name,
             AnyResource::ImportSource(r) => &r.metadata.name,
+            AnyResource::RuleTest(r) => &r.metadata.name,
+            AnyResource::RuleTemplate(r) => &r.metadata.name,
+            AnyResource::Correspondent(r) => &r.metadata.name,
+            AnyResource::LookupTable(r) => &r.metadata.name,
         }
     }
 
@@ -903,6 +1676,10 @@ impl AnyResource {
             AnyResource::Variable(r) => &r.api_version,
             AnyResource::Rule(r) => &r.api_version,
             AnyResource::ImportSource(r) => &r.api_version,
+            AnyResource::RuleTest(r) => &r.api_version,
+            AnyResource::RuleTemplate(r) => &r.api_version,
+            AnyResource::Correspondent(r) => &r.api_version,
+            AnyResource::LookupTable(r) => &r.api_version,
         }
     }
 
@@ -913,6 +1690,10 @@ impl AnyResource {
             AnyResource::Variable(r) => &r.metadata,
             AnyResource::Rule(r) => &r.metadata,
             AnyResource::ImportSource(r) => &r.metadata,
+            AnyResource::RuleTest(r) => &r.metadata,
+            AnyResource::RuleTemplate(r) => &r.metadata,
+            AnyResource::Correspondent(r) => &r.metadata,
+            AnyResource::LookupTable(r) => &r.metadata,
         }
     }
 }
@@ -1018,10 +1799,13 @@ mod tests {
             output_directory: "/data/documents".to_string(),
             worker_count: 4,
             ocr: OcrSettings::default(),
+            limits: LimitsSettings::default(),
+            categorization: CategorizationSettings::default(),
             defaults: DefaultOutputSettings::default(),
             git: GitSettings::default(),
             ai: AiSettings::default(),
             release_channel: ReleaseChannel::default(),
+            overlay: None,
         };
         let resource: SettingsResource = Resource::new(ResourceKind::Settings, "default", spec);
 
@@ -1037,6 +1821,10 @@ mod tests {
             pattern: r"(?i)from[\s:]+(?P<vendor>.+)".to_string(),
             transform: Some(VariableTransform::Slugify),
             default: Some("unknown".to_string()),
+            pages: None,
+            region: None,
+            from: None,
+            template: None,
         };
         let resource: VariableResource = Resource::new(ResourceKind::Variable, "vendor", spec);
 
@@ -1049,7 +1837,9 @@ mod tests {
     fn test_rule_resource_creation() {
         let spec = RuleSpec {
             priority: 100,
+            extends: None,
             category: "Tax".to_string(),
+            exclusive: true,
             match_condition: MatchCondition::Compound(CompoundMatch {
                 all: Some(vec![
                     MatchCondition::Simple(SimpleMatch {
@@ -1086,10 +1876,13 @@ mod tests {
             output_directory: "/data/documents".to_string(),
             worker_count: 4,
             ocr: OcrSettings::default(),
+            limits: LimitsSettings::default(),
+            categorization: CategorizationSettings::default(),
             defaults: DefaultOutputSettings::default(),
             git: GitSettings::default(),
             ai: AiSettings::default(),
             release_channel: ReleaseChannel::default(),
+            overlay: None,
         };
         let resource: SettingsResource = Resource::new(ResourceKind::Settings, "default", spec);
 
@@ -1127,6 +1920,106 @@ spec:
         assert_eq!(resource.metadata.name, "default");
         assert_eq!(resource.spec.input_directory, "/data/inbox");
         assert_eq!(resource.spec.ocr.languages, vec!["eng", "deu"]);
+        assert_eq!(
+            resource.spec.categorization.mode,
+            CategorizationMode::FirstMatch
+        );
+    }
+
+    #[test]
+    fn test_deserialize_scoring() {
+        let yaml = r#"
+mode: scoring
+threshold: 0.7
+"#;
+        let settings: CategorizationSettings = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(settings.mode, CategorizationMode::Scoring);
+        assert_eq!(settings.threshold, 0.7);
+        assert_eq!(settings.alternatives, 3);
+
+        let yaml = r#"
+all:
+  - contains: Versicherung
+    weight: 3
+  - containsAny: [Police, Prämie]
+"#;
+        let condition: MatchCondition = serde_yaml::from_str(yaml).unwrap();
+        let MatchCondition::Compound(compound) = condition else {
+            panic!("expected compound match");
+        };
+        let all = compound.all.unwrap();
+        assert!(matches!(
+            &all[0],
+            MatchCondition::Simple(SimpleMatch {
+                weight: Some(w),
+                ..
+            }) if *w == 3.0
+        ));
+    }
+
+    #[test]
+    fn test_extending_rule_round_trip() {
+        let yaml = r#"
+priority: 5
+extends: vendor
+category: Acme
+"#;
+        let spec: RuleSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(spec.extends.as_deref(), Some("vendor"));
+        assert!(spec.match_condition.is_empty());
+        assert!(spec.output.is_empty());
+
+        // Inherited parts stay out of the written file
+        let written = serde_yaml::to_string(&spec).unwrap();
+        assert!(!written.contains("match"));
+        assert!(!written.contains("output"));
+        assert!(written.contains("extends: vendor"));
+    }
+
+    #[test]
+    fn test_deserialize_page_scope() {
+        let yaml = r#"
+any:
+  - contains: Acme GmbH
+    pages: first
+    region:
+      top: 10
+  - pattern: "Total (?P<total>\\d+)"
+    pages: [1, 2]
+  - contains: Summe
+    pages: last
+    region: { bottom: 5 }
+"#;
+        let condition: MatchCondition = serde_yaml::from_str(yaml).unwrap();
+        let MatchCondition::Compound(compound) = condition else {
+            panic!("expected compound match");
+        };
+        let scopes: Vec<_> = compound
+            .any
+            .unwrap()
+            .into_iter()
+            .map(|condition| match condition {
+                MatchCondition::Simple(simple) => (simple.pages, simple.region),
+                MatchCondition::Compound(_) => panic!("expected simple match"),
+            })
+            .collect();
+        assert_eq!(
+            scopes,
+            vec![
+                (
+                    Some(PageSelection::Position(PagePosition::First)),
+                    Some(PageRegion::Top(10))
+                ),
+                (Some(PageSelection::Numbers(vec![1, 2])), None),
+                (
+                    Some(PageSelection::Position(PagePosition::Last)),
+                    Some(PageRegion::Bottom(5))
+                ),
+            ]
+        );
+
+        let json = serde_json::to_value(PageRegion::Top(3)).unwrap();
+        assert_eq!(json, serde_json::json!({ "top": 3 }));
     }
 
     #[test]
@@ -1147,6 +2040,38 @@ spec:
         assert_eq!(resource.spec.transform, Some(VariableTransform::Slugify));
     }
 
+    #[test]
+    fn test_deserialize_lookup_table() {
+        let yaml = r#"
+apiVersion: paporg.io/v1
+kind: LookupTable
+metadata:
+  name: vendors
+spec:
+  mode: regex
+  entries:
+    - key: "SBB|CFF|FFS|Bundesbahn"
+      value: SBB
+  file: lookups/vendors.csv
+  fallback: other
+"#;
+        let resource: LookupTableResource = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(resource.kind, ResourceKind::LookupTable);
+        assert_eq!(resource.spec.mode, LookupMode::Regex);
+        assert_eq!(resource.spec.entries[0].value, "SBB");
+        assert_eq!(resource.spec.file.as_deref(), Some("lookups/vendors.csv"));
+        assert_eq!(resource.spec.fallback.as_deref(), Some("other"));
+
+        let spec: VariableSpec =
+            serde_yaml::from_str("pattern: x\ntransform:\n  lookup: vendors\n").unwrap();
+        assert_eq!(
+            spec.transform,
+            Some(VariableTransform::Lookup("vendors".to_string()))
+        );
+        let yaml = serde_yaml::to_string(&spec).unwrap();
+        assert!(yaml.contains("transform:\n  lookup: vendors\n"));
+    }
+
     #[test]
     fn test_deserialize_rule() {
         let yaml = r#"
@@ -1177,6 +2102,107 @@ spec:
             Some(&"finance".to_string())
         );
         assert_eq!(resource.spec.priority, 100);
+        assert!(resource.spec.exclusive);
+        assert!(matches!(
+            resource.spec.match_condition,
+            MatchCondition::Compound(CompoundMatch { all: Some(ref all), .. }) if all.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_variable_match() {
+        let yaml = r#"
+all:
+  - variable: { name: amount, gt: 1000 }
+  - variable: { name: date, between: ["2020-01-01", "31.12.2020"] }
+  - variable: { name: vendor, in: [Swisscom, Sunrise] }
+  - variable: { name: iban, exists: false }
+"#;
+        let condition: MatchCondition = serde_yaml::from_str(yaml).unwrap();
+        let MatchCondition::Compound(CompoundMatch { all: Some(all), .. }) = condition else {
+            panic!("expected compound match");
+        };
+        let variables: Vec<&VariableMatch> = all
+            .iter()
+            .map(|c| match c {
+                MatchCondition::Simple(SimpleMatch {
+                    variable: Some(v), ..
+                }) => v.as_ref(),
+                _ => panic!("expected variable condition"),
+            })
+            .collect();
+
+        assert_eq!(variables[0].gt, Some(CompareValue::Number(1000.0)));
+        assert_eq!(
+            variables[1].between,
+            Some([
+                CompareValue::Text("2020-01-01".to_string()),
+                CompareValue::Text("31.12.2020".to_string()),
+            ])
+        );
+        assert_eq!(variables[2].one_of.as_ref().map(Vec::len), Some(2));
+        assert_eq!(variables[3].exists, Some(false));
+
+        let yaml = serde_yaml::to_string(&all[2]).unwrap();
+        assert!(yaml.contains("in:"));
+    }
+
+    #[test]
+    fn test_deserialize_tag_rule() {
+        let yaml = r#"
+apiVersion: paporg.io/v1
+kind: Rule
+metadata:
+  name: tax-relevant
+spec:
+  category: tax
+  exclusive: false
+  match:
+    containsAny: [Steuer, Spende]
+  symlinks:
+    - target: "Tax/$y"
+"#;
+        let resource: RuleResource = serde_yaml::from_str(yaml).unwrap();
+        assert!(!resource.spec.exclusive);
+        assert!(resource.spec.output.directory.is_empty());
+        assert_eq!(resource.spec.symlinks.len(), 1);
+
+        let yaml = serde_yaml::to_string(&resource).unwrap();
+        assert!(yaml.contains("exclusive: false"));
+    }
+
+    #[test]
+    fn test_deserialize_match_fields() {
+        let yaml = r#"
+any:
+  - contains: billing@example.com
+    field: email.from
+  - containsAny: [Rechnung, Invoice]
+    field: firstPage
+field: text
+"#;
+        let condition: MatchCondition = serde_yaml::from_str(yaml).unwrap();
+        let MatchCondition::Compound(compound) = condition else {
+            panic!("expected compound match");
+        };
+        assert_eq!(compound.field, Some(MatchField::Text));
+        let any = compound.any.unwrap();
+        assert!(matches!(
+            &any[0],
+            MatchCondition::Simple(SimpleMatch {
+                field: Some(MatchField::EmailFrom),
+                ..
+            })
+        ));
+        assert!(matches!(
+            &any[1],
+            MatchCondition::Simple(SimpleMatch {
+                field: Some(MatchField::FirstPage),
+                ..
+            })
+        ));
+
+        assert!(serde_yaml::from_str::<MatchCondition>("contains: x\nfield: body\n").is_err());
     }
 
     #[test]
@@ -0,0 +1,387 @@
+//! Runs RuleTest resources against the rules of a configuration.
+//!
+//! Each case is categorized by the same pipeline that processes real
+//! documents, up to the resolved output path; nothing is written to the
+//! output directory.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::loader::LoadedConfig;
+use super::resource::{ResourceWithPath, RuleTestCase, RuleTestResource};
+use crate::config::schema::{DocumentFormat, DocumentMetadata};
+use crate::pipeline::{Pipeline, PipelineConfig, PipelineContext};
+use crate::processor::ProcessedContent;
+use crate::worker::job::Job;
+
+/// Filename given to text cases that do not set one.
+const DEFAULT_SAMPLE_FILENAME: &str = "sample.pdf";
+
+/// Outcome of every case of a set of rule tests.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub results: Vec<RuleTestCaseResult>,
+}
+
+/// Outcome of a single rule test case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestCaseResult {
+    /// Name of the RuleTest resource.
+    pub test: String,
+    /// Name of the case.
+    pub case: String,
+    /// Path of the RuleTest resource, relative to the config directory.
+    pub path: PathBuf,
+    pub passed: bool,
+    /// One message per expectation that was not met.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+    /// Category the document was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Rule that categorized the document, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Resolved output path, relative to the output directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Why the document could not be categorized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RuleTestReport {
+    /// Returns true if every case passed.
+    pub fn is_success(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// Returns the failed cases.
+    pub fn failures(&self) -> impl Iterator<Item = &RuleTestCaseResult> {
+        self.results.iter().filter(|r| !r.passed)
+    }
+}
+
+/// Runs rule test cases against a loaded configuration.
+pub struct RuleTestRunner {
+    pipeline: Pipeline,
+    config_dir: PathBuf,
+}
+
+impl RuleTestRunner {
+    /// Creates a runner for the rules of `config`. Sample files are resolved
+    /// relative to `config_dir`.
+    pub fn new(config: &LoadedConfig, config_dir: impl Into<PathBuf>) -> Self {
+        let legacy = config.to_legacy_config();
+        let pipeline = Pipeline::from_config(Arc::new(PipelineConfig::from_config(&legacy)));
+
+        Self {
+            pipeline,
+            config_dir: config_dir.into(),
+        }
+    }
+
+    /// Runs every case of the given rule tests.
+    pub fn run(&self, tests: &[ResourceWithPath<RuleTestResource>]) -> RuleTestReport {
+        let mut report = RuleTestReport::default();
+
+        for test in tests {
+            for case in &test.resource.spec.cases {
+                let result = self.run_case(&test.resource.metadata.name, &test.path, case);
+                if result.passed {
+                    report.passed += 1;
+                } else {
+                    report.failed += 1;
+                }
+                report.results.push(result);
+            }
+        }
+
+        report
+    }
+
+    /// Runs a single case of the rule test `test`, stored at `path`.
+    pub fn run_case(&self, test: &str, path: &Path, case: &RuleTestCase) -> RuleTestCaseResult {
+        let mut result = RuleTestCaseResult {
+            test: test.to_string(),
+            case: case.name.clone(),
+            path: path.to_path_buf(),
+            passed: false,
+            failures: Vec::new(),
+            category: None,
+            rule: None,
+            output: None,
+            error: None,
+        };
+
+        let mut ctx = match self.context_for(case) {
+            Some(ctx) => ctx,
+            None => {
+                result.error = Some("case must set exactly one of text and file".to_string());
+                return result;
+            }
+        };
+
+        let output = self.pipeline.dry_run(&mut ctx);
+        if let Some(categorization) = &ctx.categorization {
+            result.category = Some(categorization.category.clone());
+            result.rule = categorization.rule_id.clone();
+        }
+        match output {
+            Ok(output) => result.output = Some(output.to_string_lossy().replace('\\', "/")),
+            Err(e) => result.error = Some(e.to_string()),
+        }
+
+        let expect = &case.expect;
+        if let Some(category) = &expect.category {
+            if result.category.as_ref() != Some(category) {
+                result.failures.push(format!(
+                    "expected category '{}', got '{}'",
+                    category,
+                    result.category.as_deref().unwrap_or("none")
+                ));
+            }
+        }
+        if let Some(rule) = &expect.rule {
+            if result.rule.as_ref() != Some(rule) {
+                result.failures.push(format!(
+                    "expected rule '{}', got '{}'",
+                    rule,
+                    result.rule.as_deref().unwrap_or("none")
+                ));
+            }
+        }
+        if let Some(output) = &expect.output {
+            if result.output.as_ref() != Some(output) {
+                result.failures.push(format!(
+                    "expected output '{}', got '{}'",
+                    output,
+                    result.output.as_deref().unwrap_or("none")
+                ));
+            }
+        }
+
+        result.passed = result.error.is_none() && result.failures.is_empty();
+        result
+    }
+
+    /// Builds the pipeline context for a case. Text cases skip document
+    /// processing; file cases are processed like any imported document.
+    fn context_for(&self, case: &RuleTestCase) -> Option<PipelineContext> {
+        let (source_path, processed) = match (&case.text, &case.file) {
+            (Some(text), None) => {
+                let filename = case
+                    .filename
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_SAMPLE_FILENAME.to_string());
+                let processed = ProcessedContent::new(
+                    text.clone(),
+                    Vec::new(),
+                    DocumentMetadata::new(filename.clone(), DocumentFormat::Text),
+                );
+                (PathBuf::from(filename), Some(processed))
+            }
+            (None, Some(file)) => (self.config_dir.join(file), None),
+            _ => return None,
+        };
+
+        let job = match &case.source {
+            Some(source) => Job::new_with_source(source_path, source.clone()),
+            None => Job::new(source_path),
+        };
+
+        let mut ctx = PipelineContext::new(job);
+        ctx.processed = processed;
+        if let (Some(filename), Some(processed)) = (&case.filename, ctx.processed.as_mut()) {
+            processed.metadata.original_filename = filename.clone();
+        }
+        Some(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gitops::resource::*;
+    use tempfile::TempDir;
+
+    fn create_config(tests: Vec<RuleTestCase>) -> LoadedConfig {
+        let settings = Resource::new(
+            ResourceKind::Settings,
+            "default",
+            SettingsSpec {
+                input_directory: "/inbox".to_string(),
+                output_directory: "/output".to_string(),
+                worker_count: 1,
+                ocr: OcrSettings::default(),
+                limits: LimitsSettings::default(),
+                categorization: CategorizationSettings::default(),
+                defaults: DefaultOutputSettings::default(),
+                git: GitSettings::default(),
+                ai: AiSettings::default(),
+                release_channel: ReleaseChannel::default(),
+                overlay: None,
+            },
+        );
+        let rule = Resource::new(
+            ResourceKind::Rule,
+            "invoices",
+            RuleSpec {
+                priority: 10,
+                extends: None,
+                category: "invoices".to_string(),
+                exclusive: true,
+                match_condition: MatchCondition::Simple(SimpleMatch {
+                    contains: Some("Invoice".to_string()),
+                    ..Default::default()
+                }),
+                output: OutputSettings {
+                    directory: "$vendor".to_string(),
+                    filename: "$original".to_string(),
+                },
+                symlinks: Vec::new(),
+            },
+        );
+        let vendor = Resource::new(
+            ResourceKind::Variable,
+            "vendor",
+            VariableSpec {
+                pattern: r"From: (?P<vendor>\w+)".to_string(),
+                transform: None,
+                default: Some("unknown".to_string()),
+                pages: None,
+                region: None,
+                from: None,
+                template: None,
+            },
+        );
+        let test = Resource::new(
+            ResourceKind::RuleTest,
+            "invoices",
+            RuleTestSpec { cases: tests },
+        );
+
+        LoadedConfig {
+            variables: vec![ResourceWithPath::new(vendor, "variables/vendor.yaml")],
+            rules: vec![ResourceWithPath::new(rule, "rules/invoices.yaml")],
+            rule_tests: vec![ResourceWithPath::new(test, "tests/invoices.yaml")],
+            ..LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"))
+        }
+    }
+
+    fn text_case(name: &str, text: &str, expect: RuleTestExpectation) -> RuleTestCase {
+        RuleTestCase {
+            name: name.to_string(),
+            text: Some(text.to_string()),
+            filename: Some("bill.pdf".to_string()),
+            expect,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_text_cases_pass_and_fail() {
+        let config = create_config(vec![
+            text_case(
+                "acme invoice",
+                "Invoice\nFrom: acme",
+                RuleTestExpectation {
+                    category: Some("invoices".to_string()),
+                    rule: Some("invoices".to_string()),
+                    output: Some("acme/bill.pdf".to_string()),
+                },
+            ),
+            text_case(
+                "letter",
+                "Dear customer",
+                RuleTestExpectation {
+                    category: Some("invoices".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let runner = RuleTestRunner::new(&config, "/config");
+        let report = runner.run(&config.rule_tests);
+
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.failed, 1);
+        assert!(report.results[0].passed);
+        assert_eq!(report.results[0].output.as_deref(), Some("acme/bill.pdf"));
+
+        let failed = &report.results[1];
+        assert!(!failed.passed);
+        assert_eq!(failed.category.as_deref(), Some("unsorted"));
+        assert_eq!(failed.rule, None);
+        assert_eq!(failed.failures.len(), 1);
+        assert!(failed.failures[0].contains("expected category 'invoices'"));
+    }
+
+    #[test]
+    fn test_output_mismatch_fails() {
+        let config = create_config(vec![text_case(
+            "wrong vendor",
+            "Invoice\nFrom: globex",
+            RuleTestExpectation {
+                output: Some("acme/bill.pdf".to_string()),
+                ..Default::default()
+            },
+        )]);
+
+        let report = RuleTestRunner::new(&config, "/config").run(&config.rule_tests);
+
+        assert!(!report.is_success());
+        let result = report.failures().next().unwrap();
+        assert_eq!(result.output.as_deref(), Some("globex/bill.pdf"));
+        assert!(result.failures[0].contains("expected output"));
+    }
+
+    #[test]
+    fn test_file_case_is_processed() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("tests/fixtures")).unwrap();
+        std::fs::write(
+            dir.path().join("tests/fixtures/acme.txt"),
+            "Invoice\nFrom: acme",
+        )
+        .unwrap();
+
+        let config = create_config(vec![RuleTestCase {
+            name: "fixture".to_string(),
+            file: Some("tests/fixtures/acme.txt".to_string()),
+            expect: RuleTestExpectation {
+                output: Some("acme/acme.pdf".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }]);
+
+        let report = RuleTestRunner::new(&config, dir.path()).run(&config.rule_tests);
+
+        assert!(report.is_success(), "{:?}", report.results);
+    }
+
+    #[test]
+    fn test_missing_file_reports_error() {
+        let config = create_config(vec![RuleTestCase {
+            name: "missing".to_string(),
+            file: Some("tests/fixtures/missing.pdf".to_string()),
+            expect: RuleTestExpectation {
+                category: Some("invoices".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }]);
+
+        let report = RuleTestRunner::new(&config, "/nonexistent").run(&config.rule_tests);
+
+        assert_eq!(report.failed, 1);
+        assert!(report.results[0].error.is_some());
+    }
+}
@@ -0,0 +1,363 @@
+//! JSON Schemas of the resource kinds.
+//!
+//! The schemas are generated from the types in [`resource`](super::resource)
+//! and their serde attributes, with doc comments as descriptions. Every
+//! object is closed, so editors can complete fields and flag typos. Value
+//! checks such as valid regexes or defined variables are left to
+//! [`ConfigValidator`](super::ConfigValidator).
+//!
+//! The schemas are published as `schema/<name>.json`, the files named by
+//! the `yaml-language-server` comment of written resources.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use jsonschema::error::ValidationErrorKind;
+use jsonschema::paths::PathChunk;
+use jsonschema::JSONSchema;
+use schemars::generate::SchemaSettings;
+use schemars::transform::RecursiveTransform;
+use schemars::Schema;
+use serde_json::{json, Value};
+
+use super::diagnostics::{Diagnostic, DiagnosticCode};
+use super::resource::{
+    CorrespondentSpec, ImportSourceSpec, LookupTableSpec, ObjectMeta, ResourceKind, RuleSpec,
+    RuleTemplateSpec, RuleTestSpec, SettingsSpec, VariableSpec, API_VERSION,
+};
+
+/// Returns the file name of the schema of a kind, without extension.
+pub fn schema_name(kind: ResourceKind) -> &'static str {
+    match kind {
+        ResourceKind::Settings => "settings",
+        ResourceKind::Variable => "variable",
+        ResourceKind::Rule => "rule",
+        ResourceKind::ImportSource => "import-source",
+        ResourceKind::RuleTest => "rule-test",
+        ResourceKind::RuleTemplate => "rule-template",
+        ResourceKind::Correspondent => "correspondent",
+        ResourceKind::LookupTable => "lookup-table",
+    }
+}
+
+/// Returns the URL the schema of a kind is published at.
+pub fn schema_url(kind: ResourceKind) -> String {
+    format!("https://paporg.io/schemas/{}.json", schema_name(kind))
+}
+
+/// Returns the JSON Schema of a resource kind.
+pub fn resource_schema(kind: ResourceKind) -> Value {
+    let mut generator = SchemaSettings::draft2020_12()
+        .for_deserialize()
+        .with_transform(RecursiveTransform(without_null))
+        .with_transform(RecursiveTransform(deny_unknown_fields))
+        .into_generator();
+    let (spec, description) = match kind {
+        ResourceKind::Settings => (
+            generator.subschema_for::<SettingsSpec>(),
+            "Global configuration of paporg",
+        ),
+        ResourceKind::Variable => (
+            generator.subschema_for::<VariableSpec>(),
+            "A variable extracted from documents or computed from other variables",
+        ),
+        ResourceKind::Rule => (
+            generator.subschema_for::<RuleSpec>(),
+            "A rule categorizing and filing documents",
+        ),
+        ResourceKind::ImportSource => (
+            generator.subschema_for::<ImportSourceSpec>(),
+            "A source documents are imported from",
+        ),
+        ResourceKind::RuleTest => (
+            generator.subschema_for::<RuleTestSpec>(),
+            "Sample documents and how the rules are expected to handle them",
+        ),
+        ResourceKind::RuleTemplate => (
+            generator.subschema_for::<RuleTemplateSpec>(),
+            "The shared parts of rules extending it",
+        ),
+        ResourceKind::Correspondent => (
+            generator.subschema_for::<CorrespondentSpec>(),
+            "A sender of documents and the details that identify it",
+        ),
+        ResourceKind::LookupTable => (
+            generator.subschema_for::<LookupTableSpec>(),
+            "Maps extracted values to canonical ones",
+        ),
+    };
+    let metadata = generator.subschema_for::<ObjectMeta>();
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": schema_url(kind),
+        "title": format!("Paporg {}", kind),
+        "description": description,
+        "type": "object",
+        "required": ["apiVersion", "kind", "metadata", "spec"],
+        "properties": {
+            "apiVersion": {
+                "const": API_VERSION,
+                "description": "API version of the resource"
+            },
+            "kind": {
+                "const": kind.to_string(),
+                "description": "Kind of the resource"
+            },
+            "metadata": metadata,
+            "spec": spec
+        },
+        "additionalProperties": false,
+        "$defs": generator.take_definitions(true)
+    })
+}
+
+/// Checks a resource against the schema of its kind. Each violation is
+/// reported as a warning at the YAML path of the offending field.
+pub fn check_resource(kind: ResourceKind, resource: &Value) -> Vec<Diagnostic> {
+    let Some(schema) = SCHEMAS.get(&kind) else {
+        return Vec::new();
+    };
+    let Err(errors) = schema.validate(resource) else {
+        return Vec::new();
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = errors
+        .map(|error| {
+            let mut path = yaml_path(error.instance_path.iter());
+            // Point at the unexpected field rather than the object holding it
+            if let ValidationErrorKind::AdditionalProperties { unexpected } = &error.kind {
+                if let Some(field) = unexpected.first() {
+                    path = join_path(&path, field);
+                }
+            }
+            let message = if path.is_empty() {
+                error.to_string()
+            } else {
+                format!("{}: {}", path, error)
+            };
+            Diagnostic::warning(DiagnosticCode::Schema, message).at(path)
+        })
+        .collect();
+    diagnostics.dedup();
+    diagnostics
+}
+
+/// The compiled schemas, built on first use.
+static SCHEMAS: LazyLock<HashMap<ResourceKind, JSONSchema>> = LazyLock::new(|| {
+    ResourceKind::all()
+        .iter()
+        .map(|&kind| {
+            let schema =
+                JSONSchema::compile(&resource_schema(kind)).expect("resource schemas are valid");
+            (kind, schema)
+        })
+        .collect()
+});
+
+/// Converts a JSON pointer into a YAML path such as `spec.match.all[1]`.
+fn yaml_path<'a>(chunks: impl Iterator<Item = &'a PathChunk>) -> String {
+    let mut path = String::new();
+    for chunk in chunks {
+        match chunk {
+            PathChunk::Property(name) => path = join_path(&path, name),
+            PathChunk::Index(index) => path.push_str(&format!("[{}]", index)),
+            PathChunk::Keyword(_) => {}
+        }
+    }
+    path
+}
+
+fn join_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+/// Closes objects that do not say otherwise, so unknown fields such as
+/// typos are reported.
+fn deny_unknown_fields(schema: &mut Schema) {
+    if schema.get("properties").is_some() && schema.get("additionalProperties").is_none() {
+        schema.insert("additionalProperties".to_string(), Value::Bool(false));
+    }
+}
+
+/// Describes optional objects by their type alone, as a null alternative
+/// would hide the errors inside them behind a single `anyOf` error.
+fn without_null(schema: &mut Schema) {
+    let null = json!({ "type": "null" });
+    if let Some(Value::Array(any_of)) = schema.get("anyOf") {
+        if any_of.len() == 2 && any_of.contains(&null) {
+            let inner = any_of.iter().find(|s| **s != null).cloned();
+            schema.remove("anyOf");
+            if let Some(Value::Object(inner)) = inner {
+                for (key, value) in inner {
+                    schema.insert(key, value);
+                }
+            }
+        }
+    }
+}
+
+/// Adds the serde alias `alias` of the property `field` to an object schema.
+/// Used as `#[schemars(transform = alias("field", "alias"))]`.
+pub(super) fn alias(field: &'static str, alias: &'static str) -> impl Fn(&mut Schema) {
+    move |schema: &mut Schema| {
+        let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) else {
+            return;
+        };
+        if let Some(mut property) = properties.get(field).cloned() {
+            property["description"] = json!(format!("Alias of {}", field));
+            properties.insert(alias.to_string(), property);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gitops::resource::{ImportSourceResource, RuleResource};
+
+    const RULE: &str = r#"
+apiVersion: paporg.io/v1
+kind: Rule
+metadata:
+  name: invoices
+  labels:
+    team: finance
+spec:
+  priority: 10
+  extends: base
+  category: invoices
+  exclusive: false
+  match:
+    all:
+      - containsAny: [Invoice, Rechnung]
+        field: text
+        pages: first
+        region: { top: 10 }
+        weight: 2
+      - fuzzy: { value: Swisscom, maxDistance: 1 }
+      - variable: { name: amount, between: [10, "100"], in: [1, 2] }
+      - not:
+          correspondent: acme
+    caseSensitive: false
+  output:
+    directory: invoices/$y
+    filename: $original
+  symlinks:
+    - target: by-vendor/$vendor
+"#;
+
+    const IMPORT_SOURCE: &str = r#"
+apiVersion: paporg.io/v1
+kind: ImportSource
+metadata:
+  name: mail
+spec:
+  type: email
+  email:
+    host: imap.example.com
+    port: 993
+    username: me@example.com
+    auth:
+      type: oauth2
+      oauth2:
+        provider: gmail
+        clientIdEnvVar: CLIENT_ID
+    mimeFilters:
+      include: [application/pdf]
+    sinceDate:
+"#;
+
+    fn json(yaml: &str) -> Value {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_schemas_accept_resources() {
+        // Everything serde reads and writes is accepted
+        let rule = json(RULE);
+        let written =
+            serde_json::to_value(serde_json::from_value::<RuleResource>(rule.clone()).unwrap())
+                .unwrap();
+        assert_eq!(check_resource(ResourceKind::Rule, &rule), Vec::new());
+        assert_eq!(check_resource(ResourceKind::Rule, &written), Vec::new());
+
+        let source = json(IMPORT_SOURCE);
+        let written = serde_json::to_value(
+            serde_json::from_value::<ImportSourceResource>(source.clone()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            check_resource(ResourceKind::ImportSource, &source),
+            Vec::new()
+        );
+        assert_eq!(
+            check_resource(ResourceKind::ImportSource, &written),
+            Vec::new()
+        );
+
+        let fixtures = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../tests/fixtures/gitops/valid"
+        );
+        for entry in walkdir::WalkDir::new(fixtures) {
+            let entry = entry.unwrap();
+            if entry.path().extension().is_some_and(|e| e == "yaml") {
+                let value = json(&std::fs::read_to_string(entry.path()).unwrap());
+                let kind = serde_json::from_value(value["kind"].clone()).unwrap();
+                assert_eq!(
+                    check_resource(kind, &value),
+                    Vec::new(),
+                    "{:?}",
+                    entry.path()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_resource_reports_paths() {
+        let mut value = json(RULE);
+        value["spec"]["priority"] = json!("high");
+        value["spec"]["match"]["all"][1]["fuzzy"]["maxDistanse"] = json!(1);
+        value["spec"]["match"]["all"][2]["variable"]
+            .as_object_mut()
+            .unwrap()
+            .remove("name");
+
+        let diagnostics = check_resource(ResourceKind::Rule, &value);
+        let paths: Vec<_> = diagnostics
+            .iter()
+            .map(|d| d.yaml_path.as_deref().unwrap())
+            .collect();
+        assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+        assert!(paths.contains(&"spec.priority"));
+        assert!(paths.contains(&"spec.match.all[1].fuzzy.maxDistanse"));
+        assert!(paths.contains(&"spec.match.all[2].variable"));
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.code == DiagnosticCode::Schema && !d.is_error()));
+    }
+
+    #[test]
+    fn test_published_schemas_are_current() {
+        let directory = concat!(env!("CARGO_MANIFEST_DIR"), "/../../schema");
+        for &kind in ResourceKind::all() {
+            let path = format!("{}/{}.json", directory, schema_name(kind));
+            let generated = serde_json::to_string_pretty(&resource_schema(kind)).unwrap() + "\n";
+            if std::env::var_os("PAPORG_UPDATE_SCHEMAS").is_some() {
+                std::fs::write(&path, &generated).unwrap();
+            }
+            let published = std::fs::read_to_string(&path).unwrap_or_default();
+            assert!(
+                published == generated,
+                "{} is out of date; run the tests with PAPORG_UPDATE_SCHEMAS=1",
+                path
+            );
+        }
+    }
+}
@@ -1,12 +1,8 @@
 //! Cross-resource validation for GitOps configuration.
 
 use regex::Regex;
-use std::collections::HashSet;
-use std::sync::LazyLock;
-
-// Pre-compiled regex for extracting variable names from templates
-static RE_VARIABLE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"\$([a-zA-Z_][a-zA-Z0-9_]*)").unwrap());
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 /// Names reserved for built-in variables. Extracted variables must not use these names.
 /// These must exactly match the keys registered in `VariableEngine::get_builtin_variables()`.
@@ -21,98 +17,292 @@ const BUILTIN_VARIABLE_NAMES: &[&str] = &[
     "original",
     "timestamp",
     "uuid",
+    // Set by the pipeline from the categories of matching non-exclusive rules
+    "tags",
+    // Set by the pipeline from the detected correspondent
+    "correspondent",
 ];
 
+use super::analysis::{RuleAnalyzer, RuleFindingKind, RuleHistory};
+use super::diagnostics::{Diagnostic, DiagnosticCode};
 use super::error::{GitOpsError, Result};
 use super::loader::LoadedConfig;
 use super::resource::{
-    EmailAuthType, EmailSourceConfig, ImportSourceResource, MatchCondition, RuleResource,
-    SettingsResource, VariableResource,
+    CompareValue, CorrespondentResource, EmailAuthType, EmailSourceConfig, ImportSourceResource,
+    LookupEntry, LookupMode, LookupTableResource, MatchCondition, MatchField, PageRegion,
+    PageSelection, Resource, ResourceKind, ResourceWithPath, RuleResource, RuleTemplateResource,
+    RuleTestResource, SettingsResource, VariableMatch, VariableResource, VariableSource,
+    VariableTransform,
 };
+use super::rule_test::RuleTestRunner;
+use crate::categorizer::{compare, fuzzy};
+use crate::config::variables::{computed_order, extract_variable_names};
 
 /// Validator for GitOps configuration.
 pub struct ConfigValidator {
-    /// Collected validation errors.
-    errors: Vec<String>,
+    /// Collected errors, and warnings which do not fail validation.
+    diagnostics: Vec<Diagnostic>,
+    /// The resource being validated, which diagnostics are attributed to.
+    resource: Option<(ResourceKind, String, PathBuf)>,
+    /// Config directory to run rule tests from; rule tests are not run
+    /// without one.
+    config_dir: Option<PathBuf>,
+    /// Job history used to report unused rules.
+    rule_history: Option<RuleHistory>,
 }
 
 impl ConfigValidator {
     /// Creates a new validator.
     pub fn new() -> Self {
-        Self { errors: Vec::new() }
+        Self {
+            diagnostics: Vec::new(),
+            resource: None,
+            config_dir: None,
+            rule_history: None,
+        }
+    }
+
+    /// Runs rule tests as part of validation, resolving sample files
+    /// relative to `config_dir`.
+    pub fn with_config_dir(mut self, config_dir: impl Into<PathBuf>) -> Self {
+        self.config_dir = Some(config_dir.into());
+        self
+    }
+
+    /// Warns about rules that have not matched a document within the
+    /// history window.
+    pub fn with_rule_history(mut self, history: RuleHistory) -> Self {
+        self.rule_history = Some(history);
+        self
     }
 
     /// Validates the entire loaded configuration.
     pub fn validate(&mut self, config: &LoadedConfig) -> Result<()> {
-        self.errors.clear();
+        self.diagnostics.clear();
+        self.diagnostics.extend(config.warnings.iter().cloned());
 
         // Validate settings
+        self.enter(&config.settings);
         self.validate_settings(&config.settings.resource);
 
         // Validate variables
         for var in &config.variables {
+            self.enter(var);
             self.validate_variable(&var.resource);
         }
 
         // Validate rules
         for rule in &config.rules {
+            self.enter(rule);
             self.validate_rule(&rule.resource);
         }
 
         // Validate import sources
         for source in &config.import_sources {
+            self.enter(source);
             self.validate_import_source(&source.resource);
         }
 
+        // Validate rule tests
+        for test in &config.rule_tests {
+            self.enter(test);
+            self.validate_rule_test(&test.resource, config);
+        }
+
+        // Validate rule templates
+        for template in &config.rule_templates {
+            self.enter(template);
+            self.validate_rule_template(&template.resource, config);
+        }
+
+        // Validate correspondents
+        for correspondent in &config.correspondents {
+            self.enter(correspondent);
+            self.validate_correspondent(&correspondent.resource);
+        }
+
+        // Validate lookup tables
+        for table in &config.lookup_tables {
+            self.enter(table);
+            let file_entries = config
+                .lookup_file_entries
+                .get(&table.resource.metadata.name);
+            self.validate_lookup_table(&table.resource, file_entries);
+        }
+
         // Cross-resource validation
         self.validate_variable_references(config);
+        self.validate_correspondent_references(config);
+        self.validate_lookup_references(config);
         self.validate_unique_names(config);
         self.validate_directory_separation(config);
         self.validate_path_security(config);
+        self.analyze_rules(config);
 
-        if self.errors.is_empty() {
-            Ok(())
+        // Rule tests only run against an otherwise valid configuration
+        if !self.has_errors() {
+            self.run_rule_tests(config);
+        }
+
+        self.locate_diagnostics(config);
+
+        if self.has_errors() {
+            Err(GitOpsError::Validation(self.diagnostics.clone()))
         } else {
-            Err(GitOpsError::Validation(self.errors.join("; ")))
+            Ok(())
+        }
+    }
+
+    /// Attributes the following diagnostics to a resource.
+    fn enter<T>(&mut self, resource: &ResourceWithPath<Resource<T>>) {
+        self.resource = Some((
+            resource.resource.kind,
+            resource.resource.metadata.name.clone(),
+            resource.path.clone(),
+        ));
+    }
+
+    /// Adds a diagnostic about the resource being validated.
+    fn report(&mut self, mut diagnostic: Diagnostic) {
+        if diagnostic.kind.is_none() {
+            if let Some((kind, name, path)) = &self.resource {
+                diagnostic = diagnostic.for_resource(*kind, name.clone(), path.clone());
+            }
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// Sets the lines and columns of the diagnostics from the files of
+    /// their resources. Fields set by an overlay are located in its file.
+    fn locate_diagnostics(&mut self, config: &LoadedConfig) {
+        let Some(config_dir) = &self.config_dir else {
+            return;
+        };
+
+        let mut files: HashMap<PathBuf, Option<String>> = HashMap::new();
+        for diagnostic in &mut self.diagnostics {
+            let (Some(kind), Some(name)) = (diagnostic.kind, &diagnostic.name) else {
+                continue;
+            };
+            if let (Some(overlay), Some(yaml_path)) = (&config.overlay, &diagnostic.yaml_path) {
+                let origin = overlay
+                    .patch(kind, name)
+                    .and_then(|p| p.fields.iter().find(|f| &f.field == yaml_path));
+                if let Some(origin) = origin {
+                    diagnostic.path = Some(origin.path.clone());
+                }
+            }
+
+            let Some(path) = &diagnostic.path else {
+                continue;
+            };
+            let content = files
+                .entry(path.clone())
+                .or_insert_with(|| std::fs::read_to_string(config_dir.join(path)).ok());
+            if let Some(content) = content {
+                diagnostic.locate_in(content);
+            }
         }
     }
 
     /// Validates the settings resource.
     fn validate_settings(&mut self, settings: &SettingsResource) {
         if settings.spec.input_directory.is_empty() {
-            self.errors
-                .push("Settings: inputDirectory is required".to_string());
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    "Settings: inputDirectory is required",
+                )
+                .at("spec.inputDirectory"),
+            );
         }
 
         if settings.spec.output_directory.is_empty() {
-            self.errors
-                .push("Settings: outputDirectory is required".to_string());
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    "Settings: outputDirectory is required",
+                )
+                .at("spec.outputDirectory"),
+            );
         }
 
         if settings.spec.worker_count == 0 {
-            self.errors
-                .push("Settings: workerCount must be greater than 0".to_string());
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    "Settings: workerCount must be greater than 0",
+                )
+                .at("spec.workerCount"),
+            );
         }
 
         if settings.spec.ocr.dpi == 0 {
-            self.errors
-                .push("Settings: ocr.dpi must be greater than 0".to_string());
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    "Settings: ocr.dpi must be greater than 0",
+                )
+                .at("spec.ocr.dpi"),
+            );
         }
 
         if settings.spec.defaults.output.directory.is_empty() {
-            self.errors
-                .push("Settings: defaults.output.directory is required".to_string());
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    "Settings: defaults.output.directory is required",
+                )
+                .at("spec.defaults.output.directory"),
+            );
         }
 
         if settings.spec.defaults.output.filename.is_empty() {
-            self.errors
-                .push("Settings: defaults.output.filename is required".to_string());
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    "Settings: defaults.output.filename is required",
+                )
+                .at("spec.defaults.output.filename"),
+            );
+        }
+
+        let threshold = settings.spec.categorization.threshold;
+        if !(0.0..=1.0).contains(&threshold) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    "Settings: categorization.threshold must be between 0 and 1",
+                )
+                .at("spec.categorization.threshold"),
+            );
+        }
+
+        let classifier_threshold = settings.spec.categorization.classifier.threshold;
+        if !(0.0..=1.0).contains(&classifier_threshold) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    "Settings: categorization.classifier.threshold must be between 0 and 1",
+                )
+                .at("spec.categorization.classifier.threshold"),
+            );
         }
 
         // Validate git settings if enabled
         if settings.spec.git.enabled && settings.spec.git.repository.is_empty() {
-            self.errors
-                .push("Settings: git.repository is required when git is enabled".to_string());
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    "Settings: git.repository is required when git is enabled",
+                )
+                .at("spec.git.repository")
+                .with_fix("Set git.repository or set git.enabled to false"),
+            );
         }
     }
 
@@ -121,35 +311,137 @@ impl ConfigValidator {
         let name = &variable.metadata.name;
 
         if name.is_empty() {
-            self.errors.push("Variable: name is required".to_string());
+            self.report(
+                Diagnostic::error(DiagnosticCode::Required, "Variable: name is required")
+                    .at("metadata.name"),
+            );
             return;
         }
 
         if !is_valid_identifier(name) {
-            self.errors.push(format!(
-                "Variable '{}': name must be a valid identifier (letters, numbers, underscores, hyphens)",
-                name
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidName,
+                    format!(
+                        "Variable '{}': name must be a valid identifier (letters, numbers, underscores, hyphens)",
+                        name
+                    ),
+                )
+                .at("metadata.name"),
+            );
         }
 
         // Check for collision with built-in variable names
         if BUILTIN_VARIABLE_NAMES.contains(&name.as_str()) {
-            self.errors.push(format!(
-                "Variable '{}': metadata.name '{}' conflicts with built-in variable '${}'; choose a different name",
-                name, name, name
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::ReservedName,
+                    format!(
+                        "Variable '{}': metadata.name '{}' conflicts with built-in variable '${}'; choose a different name",
+                        name, name, name
+                    ),
+                )
+                .at("metadata.name")
+                .with_fix(format!("Rename the variable, e.g. to '{}_value'", name)),
+            );
+        }
+
+        if let Some(template) = &variable.spec.template {
+            self.validate_computed_variable(variable, template);
+            return;
         }
 
         if variable.spec.pattern.is_empty() {
-            self.errors
-                .push(format!("Variable '{}': pattern is required", name));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!("Variable '{}': pattern is required", name),
+                )
+                .at("spec.pattern")
+                .with_fix(format!(
+                    "Set a pattern with a named group, e.g. (?P<{}>...)",
+                    name
+                )),
+            );
             return;
         }
 
         // Validate regex pattern
         if let Err(e) = Regex::new(&variable.spec.pattern) {
-            self.errors
-                .push(format!("Variable '{}': invalid regex pattern: {}", name, e));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidPattern,
+                    format!("Variable '{}': invalid regex pattern: {}", name, e),
+                )
+                .at("spec.pattern"),
+            );
+        }
+
+        self.validate_page_scope(
+            variable.spec.pages.as_ref(),
+            variable.spec.region,
+            &format!("Variable '{}'", name),
+            "spec",
+        );
+
+        let from_text = matches!(variable.spec.from, None | Some(VariableSource::Text));
+        if !from_text && (variable.spec.pages.is_some() || variable.spec.region.is_some()) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!(
+                        "Variable '{}': pages and region only apply to variables from text",
+                        name
+                    ),
+                )
+                .at("spec.from")
+                .with_fix("Remove pages and region"),
+            );
+        }
+    }
+
+    /// Validates a variable computed from a template. References are
+    /// checked with the other variables in `validate_variable_references`.
+    fn validate_computed_variable(&mut self, variable: &VariableResource, template: &str) {
+        let name = &variable.metadata.name;
+
+        if template.trim().is_empty() {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!("Variable '{}': template must not be empty", name),
+                )
+                .at("spec.template"),
+            );
+        }
+
+        if !variable.spec.pattern.is_empty() {
+            if let Err(e) = Regex::new(&variable.spec.pattern) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidPattern,
+                        format!("Variable '{}': invalid regex pattern: {}", name, e),
+                    )
+                    .at("spec.pattern"),
+                );
+            }
+        }
+
+        if variable.spec.from.is_some()
+            || variable.spec.pages.is_some()
+            || variable.spec.region.is_some()
+        {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!(
+                        "Variable '{}': from, pages and region do not apply to computed variables",
+                        name
+                    ),
+                )
+                .at("spec.template")
+                .with_fix("Remove from, pages and region"),
+            );
         }
     }
 
@@ -158,135 +450,415 @@ impl ConfigValidator {
         let name = &rule.metadata.name;
 
         if name.is_empty() {
-            self.errors.push("Rule: name is required".to_string());
+            self.report(
+                Diagnostic::error(DiagnosticCode::Required, "Rule: name is required")
+                    .at("metadata.name"),
+            );
             return;
         }
 
         if !is_valid_identifier(name) {
-            self.errors
-                .push(format!("Rule '{}': name must be a valid identifier", name));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidName,
+                    format!("Rule '{}': name must be a valid identifier", name),
+                )
+                .at("metadata.name"),
+            );
         }
 
         if rule.spec.category.is_empty() {
-            self.errors
-                .push(format!("Rule '{}': category is required", name));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!("Rule '{}': category is required", name),
+                )
+                .at("spec.category"),
+            );
         }
 
-        if rule.spec.output.directory.is_empty() {
-            self.errors
-                .push(format!("Rule '{}': output.directory is required", name));
-        }
+        // Non-exclusive rules only tag documents, so they never decide the output path
+        if rule.spec.exclusive {
+            if rule.spec.output.directory.is_empty() {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::Required,
+                        format!("Rule '{}': output.directory is required", name),
+                    )
+                    .at("spec.output.directory"),
+                );
+            }
 
-        if rule.spec.output.filename.is_empty() {
-            self.errors
-                .push(format!("Rule '{}': output.filename is required", name));
+            if rule.spec.output.filename.is_empty() {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::Required,
+                        format!("Rule '{}': output.filename is required", name),
+                    )
+                    .at("spec.output.filename"),
+                );
+            }
         }
 
         // Validate match condition
-        self.validate_match_condition(&rule.spec.match_condition, name);
+        self.validate_match_condition(&rule.spec.match_condition, name, "spec.match");
 
         // Validate symlinks
         for (i, symlink) in rule.spec.symlinks.iter().enumerate() {
             if symlink.target.is_empty() {
-                self.errors.push(format!(
-                    "Rule '{}': symlink[{}].target is required",
-                    name, i
-                ));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::Required,
+                        format!("Rule '{}': symlink[{}].target is required", name, i),
+                    )
+                    .at(format!("spec.symlinks[{}].target", i)),
+                );
             }
         }
     }
 
     /// Validates a match condition.
-    fn validate_match_condition(&mut self, condition: &MatchCondition, rule_name: &str) {
+    fn validate_match_condition(
+        &mut self,
+        condition: &MatchCondition,
+        rule_name: &str,
+        path: &str,
+    ) {
+        let weight = match condition {
+            MatchCondition::Simple(simple) => simple.weight,
+            MatchCondition::Compound(compound) => compound.weight,
+        };
+        if weight.is_some_and(|w| !w.is_finite() || w < 0.0) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!(
+                        "Rule '{}': weight must be a number of at least 0",
+                        rule_name
+                    ),
+                )
+                .at(format!("{}.weight", path)),
+            );
+        }
+
+        let (field, pages, region) = match condition {
+            MatchCondition::Simple(simple) => (simple.field, simple.pages.as_ref(), simple.region),
+            MatchCondition::Compound(compound) => {
+                (compound.field, compound.pages.as_ref(), compound.region)
+            }
+        };
+        let context = format!("Rule '{}'", rule_name);
+        self.validate_page_scope(pages, region, &context, path);
+        let text_field = matches!(
+            field,
+            None | Some(MatchField::Text) | Some(MatchField::FirstPage)
+        );
+        if (pages.is_some() || region.is_some()) && !text_field {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!(
+                        "{}: pages and region only apply to the text and firstPage fields",
+                        context
+                    ),
+                )
+                .at(format!("{}.field", path))
+                .with_fix("Remove pages and region, or match the text or firstPage field"),
+            );
+        }
+
         match condition {
             MatchCondition::Simple(simple) => {
                 // At least one condition type should be set
                 let has_condition = simple.contains.is_some()
                     || simple.contains_any.is_some()
                     || simple.contains_all.is_some()
-                    || simple.pattern.is_some();
+                    || simple.pattern.is_some()
+                    || simple.fuzzy.is_some()
+                    || simple.contains_any_fuzzy.is_some()
+                    || simple.variable.is_some()
+                    || simple.correspondent.is_some();
 
                 if !has_condition {
-                    self.errors.push(format!(
-                        "Rule '{}': match condition must specify at least one of: contains, containsAny, containsAll, pattern",
-                        rule_name
-                    ));
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::Required,
+                            format!(
+                                "Rule '{}': match condition must specify at least one of: contains, containsAny, containsAll, pattern, fuzzy, containsAnyFuzzy, variable, correspondent",
+                                rule_name
+                            ),
+                        )
+                        .at(path),
+                    );
                 }
 
                 // Validate regex pattern if specified
                 if let Some(pattern) = &simple.pattern {
                     if let Err(e) = Regex::new(pattern) {
-                        self.errors.push(format!(
-                            "Rule '{}': invalid match pattern: {}",
-                            rule_name, e
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidPattern,
+                                format!("Rule '{}': invalid match pattern: {}", rule_name, e),
+                            )
+                            .at(format!("{}.pattern", path)),
+                        );
                     }
                 }
 
                 // Validate containsAny is not empty
                 if let Some(list) = &simple.contains_any {
                     if list.is_empty() {
-                        self.errors.push(format!(
-                            "Rule '{}': containsAny must have at least one value",
-                            rule_name
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidValue,
+                                format!(
+                                    "Rule '{}': containsAny must have at least one value",
+                                    rule_name
+                                ),
+                            )
+                            .at(format!("{}.containsAny", path)),
+                        );
                     }
                 }
 
                 // Validate containsAll is not empty
                 if let Some(list) = &simple.contains_all {
                     if list.is_empty() {
-                        self.errors.push(format!(
-                            "Rule '{}': containsAll must have at least one value",
-                            rule_name
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidValue,
+                                format!(
+                                    "Rule '{}': containsAll must have at least one value",
+                                    rule_name
+                                ),
+                            )
+                            .at(format!("{}.containsAll", path)),
+                        );
                     }
                 }
+
+                // Validate fuzzy value and distance
+                if let Some(fuzzy) = &simple.fuzzy {
+                    let length = fuzzy::normalize(&fuzzy.value).len();
+                    if length == 0 {
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::Required,
+                                format!("Rule '{}': fuzzy value must not be empty", rule_name),
+                            )
+                            .at(format!("{}.fuzzy.value", path)),
+                        );
+                    } else if fuzzy.max_distance.is_some_and(|d| d >= length) {
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidValue,
+                                format!(
+                            "Rule '{}': fuzzy maxDistance must be smaller than the length of '{}'",
+                            rule_name, fuzzy.value
+                        ),
+                            )
+                            .at(format!("{}.fuzzy.maxDistance", path)),
+                        );
+                    }
+                }
+
+                // Validate containsAnyFuzzy is not empty
+                if let Some(list) = &simple.contains_any_fuzzy {
+                    if list.is_empty() || list.iter().any(|v| fuzzy::normalize(v).is_empty()) {
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidValue,
+                                format!(
+                                    "Rule '{}': containsAnyFuzzy must have at least one value and no empty values",
+                                    rule_name
+                                ),
+                            )
+                            .at(format!("{}.containsAnyFuzzy", path)),
+                        );
+                    }
+                }
+
+                // Validate variable comparison
+                if let Some(variable) = &simple.variable {
+                    self.validate_variable_match(
+                        variable,
+                        rule_name,
+                        &format!("{}.variable", path),
+                    );
+                }
             }
             MatchCondition::Compound(compound) => {
                 let has_condition =
                     compound.all.is_some() || compound.any.is_some() || compound.not.is_some();
 
                 if !has_condition {
-                    self.errors.push(format!(
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::Required,
+                            format!(
                         "Rule '{}': compound match must specify at least one of: all, any, not",
                         rule_name
-                    ));
+                    ),
+                        )
+                        .at(path),
+                    );
                 }
 
                 // Recursively validate nested conditions
                 if let Some(all) = &compound.all {
                     if all.is_empty() {
-                        self.errors.push(format!(
-                            "Rule '{}': 'all' must have at least one condition",
-                            rule_name
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidValue,
+                                format!(
+                                    "Rule '{}': 'all' must have at least one condition",
+                                    rule_name
+                                ),
+                            )
+                            .at(format!("{}.all", path)),
+                        );
                     }
-                    for cond in all {
-                        self.validate_match_condition(cond, rule_name);
+                    for (i, cond) in all.iter().enumerate() {
+                        self.validate_match_condition(
+                            cond,
+                            rule_name,
+                            &format!("{}.all[{}]", path, i),
+                        );
                     }
                 }
 
                 if let Some(any) = &compound.any {
                     if any.is_empty() {
-                        self.errors.push(format!(
-                            "Rule '{}': 'any' must have at least one condition",
-                            rule_name
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidValue,
+                                format!(
+                                    "Rule '{}': 'any' must have at least one condition",
+                                    rule_name
+                                ),
+                            )
+                            .at(format!("{}.any", path)),
+                        );
                     }
-                    for cond in any {
-                        self.validate_match_condition(cond, rule_name);
+                    for (i, cond) in any.iter().enumerate() {
+                        self.validate_match_condition(
+                            cond,
+                            rule_name,
+                            &format!("{}.any[{}]", path, i),
+                        );
                     }
                 }
 
                 if let Some(not) = &compound.not {
-                    self.validate_match_condition(not, rule_name);
+                    self.validate_match_condition(not, rule_name, &format!("{}.not", path));
                 }
             }
         }
     }
 
-    /// Validates that variable references in rules exist.
+    /// Validates the pages and region a condition or variable is limited to.
+    fn validate_page_scope(
+        &mut self,
+        pages: Option<&PageSelection>,
+        region: Option<PageRegion>,
+        context: &str,
+        path: &str,
+    ) {
+        if let Some(PageSelection::Numbers(numbers)) = pages {
+            if numbers.is_empty() || numbers.contains(&0) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidValue,
+                        format!(
+                            "{}: pages must list at least one page number, counting from 1",
+                            context
+                        ),
+                    )
+                    .at(format!("{}.pages", path)),
+                );
+            }
+        }
+        if let Some(PageRegion::Top(0) | PageRegion::Bottom(0)) = region {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!("{}: region must span at least one line", context),
+                )
+                .at(format!("{}.region", path)),
+            );
+        }
+    }
+
+    fn validate_variable_match(&mut self, variable: &VariableMatch, rule_name: &str, path: &str) {
+        if variable.name.is_empty() {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!("Rule '{}': variable condition requires a name", rule_name),
+                )
+                .at(format!("{}.name", path)),
+            );
+        }
+
+        if let Some(values) = &variable.one_of {
+            if values.is_empty() {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidValue,
+                        format!(
+                            "Rule '{}': variable '{}' 'in' must have at least one value",
+                            rule_name, variable.name
+                        ),
+                    )
+                    .at(format!("{}.in", path)),
+                );
+            }
+        }
+
+        if let Some([low, high]) = &variable.between {
+            if let (CompareValue::Number(low), CompareValue::Number(high)) = (low, high) {
+                if low > high {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::InvalidValue,
+                            format!(
+                        "Rule '{}': variable '{}' between bounds must be in ascending order",
+                        rule_name, variable.name
+                    ),
+                        )
+                        .at(format!("{}.between", path))
+                        .with_fix("Swap the bounds"),
+                    );
+                }
+            }
+        }
+
+        // Ordering operators need values that parse as numbers or dates
+        let ordered = [variable.gt.as_ref(), variable.lt.as_ref()]
+            .into_iter()
+            .flatten()
+            .chain(variable.between.iter().flatten());
+        for value in ordered {
+            if let CompareValue::Text(text) = value {
+                if compare::parse_date(text).is_none() && compare::parse_number(text).is_none() {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::InvalidValue,
+                            format!(
+                        "Rule '{}': variable '{}' cannot order by '{}'; use a number or a date",
+                        rule_name, variable.name, text
+                    ),
+                        )
+                        .at(path),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Validates that variable references in rules and computed variables
+    /// exist, and that computed variables do not reference each other in
+    /// a cycle.
     fn validate_variable_references(&mut self, config: &LoadedConfig) {
         let variable_names: HashSet<&str> = config
             .variables
@@ -298,16 +870,24 @@ impl ConfigValidator {
         let builtin_vars: HashSet<&str> = BUILTIN_VARIABLE_NAMES.iter().copied().collect();
 
         for rule in &config.rules {
+            self.enter(rule);
             let rule_name = &rule.resource.metadata.name;
 
             // Check directory template
             let dir_vars = extract_variable_names(&rule.resource.spec.output.directory);
             for var in &dir_vars {
                 if !builtin_vars.contains(var.as_str()) && !variable_names.contains(var.as_str()) {
-                    self.errors.push(format!(
-                        "Rule '{}': output.directory references undefined variable '${}'. Define it in variables/ or use a built-in variable.",
-                        rule_name, var
-                    ));
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::UndefinedVariable,
+                            format!(
+                                "Rule '{}': output.directory references undefined variable '${}'. Define it in variables/ or use a built-in variable.",
+                                rule_name, var
+                            ),
+                        )
+                        .at("spec.output.directory")
+                        .with_fix(format!("Define a Variable named '{}' in variables/", var)),
+                    );
                 }
             }
 
@@ -315,10 +895,36 @@ impl ConfigValidator {
             let file_vars = extract_variable_names(&rule.resource.spec.output.filename);
             for var in &file_vars {
                 if !builtin_vars.contains(var.as_str()) && !variable_names.contains(var.as_str()) {
-                    self.errors.push(format!(
-                        "Rule '{}': output.filename references undefined variable '${}'. Define it in variables/ or use a built-in variable.",
-                        rule_name, var
-                    ));
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::UndefinedVariable,
+                            format!(
+                                "Rule '{}': output.filename references undefined variable '${}'. Define it in variables/ or use a built-in variable.",
+                                rule_name, var
+                            ),
+                        )
+                        .at("spec.output.filename")
+                        .with_fix(format!("Define a Variable named '{}' in variables/", var)),
+                    );
+                }
+            }
+
+            // Check variable comparisons; only extracted variables exist at match time
+            let mut match_vars = Vec::new();
+            collect_match_variables(&rule.resource.spec.match_condition, &mut match_vars);
+            for var in match_vars {
+                if !variable_names.contains(var) {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::UndefinedVariable,
+                            format!(
+                                "Rule '{}': match condition references undefined variable '{}'. Define it in variables/.",
+                                rule_name, var
+                            ),
+                        )
+                        .at("spec.match")
+                        .with_fix(format!("Define a Variable named '{}' in variables/", var)),
+                    );
                 }
             }
 
@@ -329,14 +935,148 @@ impl ConfigValidator {
                     if !builtin_vars.contains(var.as_str())
                         && !variable_names.contains(var.as_str())
                     {
-                        self.errors.push(format!(
-                            "Rule '{}': symlinks[{}].target references undefined variable '${}'. Define it in variables/ or use a built-in variable.",
-                            rule_name, i, var
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::UndefinedVariable,
+                                format!(
+                                    "Rule '{}': symlinks[{}].target references undefined variable '${}'. Define it in variables/ or use a built-in variable.",
+                                    rule_name, i, var
+                                ),
+                            )
+                            .at(format!("spec.symlinks[{}].target", i))
+                            .with_fix(format!("Define a Variable named '{}' in variables/", var)),
+                        );
                     }
                 }
             }
         }
+
+        // Computed variables are evaluated with the extracted ones, before
+        // tags and the correspondent are known
+        let mut computed = Vec::new();
+        for var in &config.variables {
+            let Some(template) = &var.resource.spec.template else {
+                continue;
+            };
+            self.enter(var);
+            let name = var.resource.metadata.name.as_str();
+            let references = extract_variable_names(template);
+            for reference in &references {
+                if matches!(reference.as_str(), "tags" | "correspondent") {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::InvalidValue,
+                            format!(
+                                "Variable '{}': template references '${}', which is only set after variables are computed",
+                                name, reference
+                            ),
+                        )
+                        .at("spec.template"),
+                    );
+                } else if !builtin_vars.contains(reference.as_str())
+                    && !variable_names.contains(reference.as_str())
+                {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::UndefinedVariable,
+                            format!(
+                                "Variable '{}': template references undefined variable '${}'. Define it in variables/ or use a built-in variable.",
+                                name, reference
+                            ),
+                        )
+                        .at("spec.template")
+                        .with_fix(format!("Define a Variable named '{}' in variables/", reference)),
+                    );
+                }
+            }
+            computed.push((name, references));
+        }
+
+        let (_, cycles) = computed_order(&computed);
+        for cycle in cycles {
+            if let Some(var) = config
+                .variables
+                .iter()
+                .find(|v| v.resource.metadata.name == cycle[0])
+            {
+                self.enter(var);
+            }
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::ReferenceCycle,
+                    format!(
+                        "Variable '{}': template references form a cycle: {}",
+                        cycle[0],
+                        cycle.join(" -> ")
+                    ),
+                )
+                .at("spec.template"),
+            );
+        }
+    }
+
+    /// Validates that correspondents named by match conditions exist.
+    fn validate_correspondent_references(&mut self, config: &LoadedConfig) {
+        let correspondent_names: HashSet<&str> = config
+            .correspondents
+            .iter()
+            .map(|c| c.resource.metadata.name.as_str())
+            .collect();
+
+        for rule in &config.rules {
+            self.enter(rule);
+            let mut names = Vec::new();
+            collect_match_correspondents(&rule.resource.spec.match_condition, &mut names);
+            for name in names {
+                if !correspondent_names
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(name))
+                {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::UndefinedReference,
+                            format!(
+                                "Rule '{}': match condition references unknown correspondent '{}'. Define it in correspondents/.",
+                                rule.resource.metadata.name, name
+                            ),
+                        )
+                        .at("spec.match")
+                        .with_fix(format!(
+                            "Define a Correspondent named '{}' in correspondents/",
+                            name
+                        )),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Validates that lookup tables named by variable transforms exist.
+    fn validate_lookup_references(&mut self, config: &LoadedConfig) {
+        let table_names: HashSet<&str> = config
+            .lookup_tables
+            .iter()
+            .map(|t| t.resource.metadata.name.as_str())
+            .collect();
+
+        for var in &config.variables {
+            if let Some(VariableTransform::Lookup(table)) = &var.resource.spec.transform {
+                self.enter(var);
+                if !table_names.contains(table.as_str()) {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::UndefinedReference,
+                            format!(
+                                "Variable '{}': transform references unknown lookup table '{}'. Define it in lookups/.",
+                                var.resource.metadata.name, table
+                            ),
+                        )
+                        .at("spec.transform")
+                        .with_fix(format!("Define a LookupTable named '{}' in lookups/", table)),
+                    );
+                }
+            }
+        }
     }
 
     /// Validates that resource names are unique within their kind.
@@ -344,30 +1084,529 @@ impl ConfigValidator {
         let mut variable_names: HashSet<&str> = HashSet::new();
         for var in &config.variables {
             let name = var.resource.metadata.name.as_str();
+            self.enter(var);
             if !variable_names.insert(name) {
-                self.errors
-                    .push(format!("Duplicate variable name: '{}'", name));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::DuplicateName,
+                        format!("Duplicate variable name: '{}'", name),
+                    )
+                    .at("metadata.name"),
+                );
             }
         }
 
         let mut rule_names: HashSet<&str> = HashSet::new();
         for rule in &config.rules {
             let name = rule.resource.metadata.name.as_str();
+            self.enter(rule);
             if !rule_names.insert(name) {
-                self.errors.push(format!("Duplicate rule name: '{}'", name));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::DuplicateName,
+                        format!("Duplicate rule name: '{}'", name),
+                    )
+                    .at("metadata.name"),
+                );
             }
         }
 
         let mut import_source_names: HashSet<&str> = HashSet::new();
         for source in &config.import_sources {
             let name = source.resource.metadata.name.as_str();
+            self.enter(source);
             if !import_source_names.insert(name) {
-                self.errors
-                    .push(format!("Duplicate import source name: '{}'", name));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::DuplicateName,
+                        format!("Duplicate import source name: '{}'", name),
+                    )
+                    .at("metadata.name"),
+                );
+            }
+        }
+
+        let mut rule_test_names: HashSet<&str> = HashSet::new();
+        for test in &config.rule_tests {
+            let name = test.resource.metadata.name.as_str();
+            self.enter(test);
+            if !rule_test_names.insert(name) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::DuplicateName,
+                        format!("Duplicate rule test name: '{}'", name),
+                    )
+                    .at("metadata.name"),
+                );
+            }
+        }
+
+        // Templates share the rule namespace, since `extends` names either
+        let mut template_names: HashSet<&str> = HashSet::new();
+        for template in &config.rule_templates {
+            let name = template.resource.metadata.name.as_str();
+            self.enter(template);
+            if !template_names.insert(name) || rule_names.contains(name) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::DuplicateName,
+                        format!("Duplicate rule template name: '{}'", name),
+                    )
+                    .at("metadata.name"),
+                );
+            }
+        }
+
+        let mut correspondent_names: HashSet<&str> = HashSet::new();
+        for correspondent in &config.correspondents {
+            let name = correspondent.resource.metadata.name.as_str();
+            self.enter(correspondent);
+            if !correspondent_names.insert(name) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::DuplicateName,
+                        format!("Duplicate correspondent name: '{}'", name),
+                    )
+                    .at("metadata.name"),
+                );
+            }
+        }
+
+        let mut lookup_table_names: HashSet<&str> = HashSet::new();
+        for table in &config.lookup_tables {
+            let name = table.resource.metadata.name.as_str();
+            self.enter(table);
+            if !lookup_table_names.insert(name) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::DuplicateName,
+                        format!("Duplicate lookup table name: '{}'", name),
+                    )
+                    .at("metadata.name"),
+                );
+            }
+        }
+    }
+
+    /// Validates a RuleTemplate resource. Its match condition and output are
+    /// checked as part of every rule extending it.
+    fn validate_rule_template(&mut self, template: &RuleTemplateResource, config: &LoadedConfig) {
+        let name = &template.metadata.name;
+
+        if name.is_empty() {
+            self.report(
+                Diagnostic::error(DiagnosticCode::Required, "RuleTemplate: name is required")
+                    .at("metadata.name"),
+            );
+            return;
+        }
+
+        if !is_valid_identifier(name) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidName,
+                    format!("RuleTemplate '{}': name must be a valid identifier", name),
+                )
+                .at("metadata.name"),
+            );
+        }
+
+        for (i, symlink) in template.spec.symlinks.iter().enumerate() {
+            if symlink.target.is_empty() {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::Required,
+                        format!("RuleTemplate '{}': symlink[{}].target is required", name, i),
+                    )
+                    .at(format!("spec.symlinks[{}].target", i)),
+                );
+            }
+        }
+
+        let extended = config
+            .rules
+            .iter()
+            .map(|r| r.resource.spec.extends.as_deref())
+            .chain(
+                config
+                    .rule_templates
+                    .iter()
+                    .map(|t| t.resource.spec.extends.as_deref()),
+            )
+            .any(|extends| extends == Some(name.as_str()));
+        if !extended {
+            self.report(
+                Diagnostic::warning(
+                    DiagnosticCode::UnusedTemplate,
+                    format!("RuleTemplate '{}': not extended by any rule", name),
+                )
+                .at("metadata.name")
+                .with_fix("Extend it from a rule or delete it"),
+            );
+        }
+    }
+
+    /// Validates a Correspondent resource.
+    fn validate_correspondent(&mut self, correspondent: &CorrespondentResource) {
+        let name = &correspondent.metadata.name;
+        let spec = &correspondent.spec;
+
+        if name.is_empty() {
+            self.report(
+                Diagnostic::error(DiagnosticCode::Required, "Correspondent: name is required")
+                    .at("metadata.name"),
+            );
+            return;
+        }
+
+        if !is_valid_identifier(name) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidName,
+                    format!("Correspondent '{}': name must be a valid identifier", name),
+                )
+                .at("metadata.name"),
+            );
+        }
+
+        if spec.aliases.iter().any(|a| a.trim().is_empty()) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!("Correspondent '{}': aliases must not be empty", name),
+                )
+                .at("spec.aliases"),
+            );
+        }
+
+        for iban in &spec.ibans {
+            if !is_valid_iban(iban) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidValue,
+                        format!("Correspondent '{}': invalid IBAN '{}'", name, iban),
+                    )
+                    .at("spec.ibans"),
+                );
+            }
+        }
+
+        let has_alphanumeric = |v: &String| v.chars().any(|c| c.is_ascii_alphanumeric());
+        if !spec.vat_numbers.iter().all(has_alphanumeric) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!("Correspondent '{}': VAT numbers must not be empty", name),
+                )
+                .at("spec.vatNumbers"),
+            );
+        }
+
+        for domain in &spec.email_domains {
+            let domain = domain.trim_start_matches('@');
+            if domain.is_empty() || domain.contains(['@', ' ']) || !domain.contains('.') {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidValue,
+                        format!(
+                            "Correspondent '{}': invalid email domain '{}'",
+                            name, domain
+                        ),
+                    )
+                    .at("spec.emailDomains"),
+                );
+            }
+        }
+
+        if spec.default_category.as_ref().is_some_and(|c| c.is_empty()) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!(
+                        "Correspondent '{}': defaultCategory must not be empty",
+                        name
+                    ),
+                )
+                .at("spec.defaultCategory"),
+            );
+        }
+    }
+
+    /// Validates a LookupTable resource together with the rows of its CSV file.
+    fn validate_lookup_table(
+        &mut self,
+        table: &LookupTableResource,
+        file_entries: Option<&Vec<LookupEntry>>,
+    ) {
+        let name = &table.metadata.name;
+        let spec = &table.spec;
+
+        if name.is_empty() {
+            self.report(
+                Diagnostic::error(DiagnosticCode::Required, "LookupTable: name is required")
+                    .at("metadata.name"),
+            );
+            return;
+        }
+
+        if !is_valid_identifier(name) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidName,
+                    format!("LookupTable '{}': name must be a valid identifier", name),
+                )
+                .at("metadata.name"),
+            );
+        }
+
+        if spec.entries.is_empty() && spec.file.is_none() {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!(
+                        "LookupTable '{}': at least one entry or a file is required",
+                        name
+                    ),
+                )
+                .at("spec.entries"),
+            );
+        }
+
+        if spec.fallback.as_ref().is_some_and(|f| f.trim().is_empty()) {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!("LookupTable '{}': fallback must not be empty", name),
+                )
+                .at("spec.fallback"),
+            );
+        }
+
+        let mut keys: HashSet<String> = HashSet::new();
+        for entry in spec
+            .entries
+            .iter()
+            .chain(file_entries.into_iter().flatten())
+        {
+            if entry.key.trim().is_empty() {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::Required,
+                        format!("LookupTable '{}': entry keys must not be empty", name),
+                    )
+                    .at("spec.entries"),
+                );
+                continue;
+            }
+
+            match spec.mode {
+                LookupMode::Exact => {
+                    let key = entry.key.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !keys.insert(key.to_lowercase()) {
+                        self.report(
+                            Diagnostic::warning(
+                                DiagnosticCode::DuplicateKey,
+                                format!(
+                                    "LookupTable '{}': key '{}' appears more than once; the first entry wins",
+                                    name, entry.key
+                                ),
+                            )
+                            .at("spec.entries"),
+                        );
+                    }
+                }
+                LookupMode::Regex => {
+                    if let Err(e) = Regex::new(&entry.key) {
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidPattern,
+                                format!(
+                                    "LookupTable '{}': invalid key pattern '{}': {}",
+                                    name, entry.key, e
+                                ),
+                            )
+                            .at("spec.entries"),
+                        );
+                    }
+                }
             }
         }
     }
 
+    /// Validates a RuleTest resource.
+    fn validate_rule_test(&mut self, test: &RuleTestResource, config: &LoadedConfig) {
+        let name = &test.metadata.name;
+
+        if name.is_empty() {
+            self.report(
+                Diagnostic::error(DiagnosticCode::Required, "RuleTest: name is required")
+                    .at("metadata.name"),
+            );
+            return;
+        }
+
+        if test.spec.cases.is_empty() {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!("RuleTest '{}': at least one case is required", name),
+                )
+                .at("spec.cases"),
+            );
+        }
+
+        let mut case_names: HashSet<&str> = HashSet::new();
+        for (i, case) in test.spec.cases.iter().enumerate() {
+            if case.name.is_empty() {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::Required,
+                        format!("RuleTest '{}': cases[{}].name is required", name, i),
+                    )
+                    .at(format!("spec.cases[{}].name", i)),
+                );
+            } else if !case_names.insert(&case.name) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::DuplicateName,
+                        format!("RuleTest '{}': duplicate case name '{}'", name, case.name),
+                    )
+                    .at(format!("spec.cases[{}].name", i)),
+                );
+            }
+
+            match (&case.text, &case.file) {
+                (Some(_), Some(_)) | (None, None) => self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidValue,
+                        format!(
+                            "RuleTest '{}': case '{}' must set exactly one of text and file",
+                            name, case.name
+                        ),
+                    )
+                    .at(format!("spec.cases[{}]", i)),
+                ),
+                (None, Some(file)) => {
+                    if std::path::Path::new(file).is_absolute() || contains_path_traversal(file) {
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::InvalidValue,
+                                format!(
+                                    "RuleTest '{}': case '{}' file must be relative to the config directory",
+                                    name, case.name
+                                ),
+                            )
+                            .at(format!("spec.cases[{}].file", i)),
+                        );
+                    }
+                }
+                (Some(_), None) => {}
+            }
+
+            if case.expect.is_empty() {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::Required,
+                        format!(
+                            "RuleTest '{}': case '{}' must expect a category, rule or output",
+                            name, case.name
+                        ),
+                    )
+                    .at(format!("spec.cases[{}].expect", i)),
+                );
+            }
+
+            if let Some(rule) = &case.expect.rule {
+                if !config
+                    .rules
+                    .iter()
+                    .any(|r| &r.resource.metadata.name == rule)
+                {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::UndefinedReference,
+                            format!(
+                                "RuleTest '{}': case '{}' expects undefined rule '{}'",
+                                name, case.name, rule
+                            ),
+                        )
+                        .at(format!("spec.cases[{}].expect.rule", i)),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Warns about shadowed, duplicate and unused rules.
+    fn analyze_rules(&mut self, config: &LoadedConfig) {
+        let mut analyzer = RuleAnalyzer::new(config);
+        if let Some(history) = &self.rule_history {
+            analyzer = analyzer.with_history(history);
+        }
+
+        for finding in analyzer.analyze() {
+            let code = match finding.kind {
+                RuleFindingKind::Shadowed => DiagnosticCode::ShadowedRule,
+                RuleFindingKind::Duplicate => DiagnosticCode::DuplicateRule,
+                RuleFindingKind::RepeatedCondition => DiagnosticCode::RepeatedCondition,
+                RuleFindingKind::Unused => DiagnosticCode::UnusedRule,
+                // Empty lists are already errors, see validate_match_condition
+                RuleFindingKind::EmptyCondition => continue,
+            };
+            if let Some(rule) = config
+                .rules
+                .iter()
+                .find(|r| r.resource.metadata.name == finding.rule)
+            {
+                self.enter(rule);
+            }
+            self.report(Diagnostic::warning(code, finding.message).at("spec.match"));
+        }
+    }
+
+    /// Runs the rule tests and reports each failing case as an error.
+    fn run_rule_tests(&mut self, config: &LoadedConfig) {
+        let Some(config_dir) = &self.config_dir else {
+            return;
+        };
+        if config.rule_tests.is_empty() {
+            return;
+        }
+
+        let report = RuleTestRunner::new(config, config_dir).run(&config.rule_tests);
+        for result in report.failures() {
+            let reason = match &result.error {
+                Some(error) => error.clone(),
+                None => result.failures.join(", "),
+            };
+            let Some(test) = config
+                .rule_tests
+                .iter()
+                .find(|t| t.resource.metadata.name == result.test)
+            else {
+                continue;
+            };
+            let case = test
+                .resource
+                .spec
+                .cases
+                .iter()
+                .position(|c| c.name == result.case)
+                .unwrap_or_default();
+            self.enter(test);
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::RuleTestFailed,
+                    format!(
+                        "RuleTest '{}': case '{}' failed: {}",
+                        result.test, result.case, reason
+                    ),
+                )
+                .at(format!("spec.cases[{}].expect", case)),
+            );
+        }
+    }
+
     /// Validates an ImportSource resource.
     fn validate_import_source(&mut self, source: &ImportSourceResource) {
         use super::resource::ImportSourceType;
@@ -376,59 +1615,96 @@ impl ConfigValidator {
 
         // Validate name
         if name.is_empty() {
-            self.errors
-                .push("ImportSource: name is required".to_string());
+            self.report(
+                Diagnostic::error(DiagnosticCode::Required, "ImportSource: name is required")
+                    .at("metadata.name"),
+            );
             return;
         }
 
         if !is_valid_identifier(name) {
-            self.errors.push(format!(
-                "ImportSource '{}': name must be a valid identifier (letters, numbers, underscores, hyphens)",
-                name
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidName,
+                    format!(
+                        "ImportSource '{}': name must be a valid identifier (letters, numbers, underscores, hyphens)",
+                        name
+                    ),
+                )
+                .at("metadata.name"),
+            );
         }
 
         // Check that local config is present when source type is local
         if source.spec.source_type == ImportSourceType::Local && source.spec.local.is_none() {
-            self.errors.push(format!(
-                "ImportSource '{}': local config is required when source type is 'local'",
-                name
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!(
+                        "ImportSource '{}': local config is required when source type is 'local'",
+                        name
+                    ),
+                )
+                .at("spec.local"),
+            );
         }
 
         // Check that email config is present when source type is email
         if source.spec.source_type == ImportSourceType::Email && source.spec.email.is_none() {
-            self.errors.push(format!(
-                "ImportSource '{}': email config is required when source type is 'email'",
-                name
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!(
+                        "ImportSource '{}': email config is required when source type is 'email'",
+                        name
+                    ),
+                )
+                .at("spec.email"),
+            );
         }
 
         // Validate local source config if present
         if let Some(local) = &source.spec.local {
             // Path must not be empty
             if local.path.is_empty() {
-                self.errors
-                    .push(format!("ImportSource '{}': local.path is required", name));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::Required,
+                        format!("ImportSource '{}': local.path is required", name),
+                    )
+                    .at("spec.local.path"),
+                );
             }
 
             // Validate glob patterns in include filters
             for pattern in &local.filters.include {
                 if let Err(e) = glob::Pattern::new(pattern) {
-                    self.errors.push(format!(
-                        "ImportSource '{}': invalid include glob pattern '{}': {}",
-                        name, pattern, e
-                    ));
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::InvalidPattern,
+                            format!(
+                                "ImportSource '{}': invalid include glob pattern '{}': {}",
+                                name, pattern, e
+                            ),
+                        )
+                        .at("spec.local.filters.include"),
+                    );
                 }
             }
 
             // Validate glob patterns in exclude filters
             for pattern in &local.filters.exclude {
                 if let Err(e) = glob::Pattern::new(pattern) {
-                    self.errors.push(format!(
-                        "ImportSource '{}': invalid exclude glob pattern '{}': {}",
-                        name, pattern, e
-                    ));
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::InvalidPattern,
+                            format!(
+                                "ImportSource '{}': invalid exclude glob pattern '{}': {}",
+                                name, pattern, e
+                            ),
+                        )
+                        .at("spec.local.filters.exclude"),
+                    );
                 }
             }
         }
@@ -443,24 +1719,39 @@ impl ConfigValidator {
     fn validate_email_source(&mut self, name: &str, email: &EmailSourceConfig) {
         // Host is required
         if email.host.is_empty() {
-            self.errors
-                .push(format!("ImportSource '{}': email.host is required", name));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!("ImportSource '{}': email.host is required", name),
+                )
+                .at("spec.email.host"),
+            );
         }
 
         // Username is required
         if email.username.is_empty() {
-            self.errors.push(format!(
-                "ImportSource '{}': email.username is required",
-                name
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::Required,
+                    format!("ImportSource '{}': email.username is required", name),
+                )
+                .at("spec.email.username"),
+            );
         }
 
         // TLS is required for security
         if !email.use_tls {
-            self.errors.push(format!(
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!(
                 "ImportSource '{}': email.useTls must be true (TLS is required for security)",
                 name
-            ));
+            ),
+                )
+                .at("spec.email.useTls")
+                .with_fix("Set useTls to true"),
+            );
         }
 
         // Validate authentication settings
@@ -474,18 +1765,30 @@ impl ConfigValidator {
                 );
 
                 if !has_password {
-                    self.errors.push(format!(
-                        "ImportSource '{}': password authentication requires one of: passwordInsecure, passwordFile, or passwordEnvVar",
-                        name
-                    ));
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::Required,
+                            format!(
+                                "ImportSource '{}': password authentication requires one of: passwordInsecure, passwordFile, or passwordEnvVar",
+                                name
+                            ),
+                        )
+                        .at("spec.email.auth"),
+                    );
                 }
             }
             EmailAuthType::OAuth2 => {
                 if email.auth.oauth2.is_none() {
-                    self.errors.push(format!(
-                        "ImportSource '{}': email.auth.oauth2 is required for OAuth2 authentication",
-                        name
-                    ));
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::Required,
+                            format!(
+                                "ImportSource '{}': email.auth.oauth2 is required for OAuth2 authentication",
+                                name
+                            ),
+                        )
+                        .at("spec.email.auth.oauth2"),
+                    );
                 } else if let Some(oauth2) = &email.auth.oauth2 {
                     // Accept any of: direct value, file, or env var for client credentials
                     let has_client_id = crate::secrets::has_secret_source(
@@ -501,26 +1804,44 @@ impl ConfigValidator {
                     );
 
                     if !has_client_id {
-                        self.errors.push(format!(
-                            "ImportSource '{}': OAuth2 requires one of: clientId, clientIdFile, or clientIdEnvVar",
-                            name
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::Required,
+                                format!(
+                                    "ImportSource '{}': OAuth2 requires one of: clientId, clientIdFile, or clientIdEnvVar",
+                                    name
+                                ),
+                            )
+                            .at("spec.email.auth.oauth2"),
+                        );
                     }
                     if !has_client_secret {
-                        self.errors.push(format!(
-                            "ImportSource '{}': OAuth2 requires one of: clientSecret, clientSecretFile, or clientSecretEnvVar",
-                            name
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::Required,
+                                format!(
+                                    "ImportSource '{}': OAuth2 requires one of: clientSecret, clientSecretFile, or clientSecretEnvVar",
+                                    name
+                                ),
+                            )
+                            .at("spec.email.auth.oauth2"),
+                        );
                     }
                     // refresh_token is optional (can use device flow instead)
                     // token_url is only required for custom provider
                     if oauth2.provider == crate::gitops::resource::OAuth2Provider::Custom
                         && oauth2.token_url.as_ref().is_none_or(|url| url.is_empty())
                     {
-                        self.errors.push(format!(
-                            "ImportSource '{}': email.auth.oauth2.tokenUrl is required for custom provider",
-                            name
-                        ));
+                        self.report(
+                            Diagnostic::error(
+                                DiagnosticCode::Required,
+                                format!(
+                                    "ImportSource '{}': email.auth.oauth2.tokenUrl is required for custom provider",
+                                    name
+                                ),
+                            )
+                            .at("spec.email.auth.oauth2.tokenUrl"),
+                        );
                     }
                 }
             }
@@ -529,53 +1850,86 @@ impl ConfigValidator {
         // Validate MIME type patterns
         for pattern in &email.mime_filters.include {
             if !is_valid_mime_pattern(pattern) {
-                self.errors.push(format!(
-                    "ImportSource '{}': invalid MIME pattern '{}' (expected format: type/subtype or type/*)",
-                    name, pattern
-                ));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidPattern,
+                        format!(
+                            "ImportSource '{}': invalid MIME pattern '{}' (expected format: type/subtype or type/*)",
+                            name, pattern
+                        ),
+                    )
+                    .at("spec.email.mimeFilters.include"),
+                );
             }
         }
         for pattern in &email.mime_filters.exclude {
             if !is_valid_mime_pattern(pattern) {
-                self.errors.push(format!(
-                    "ImportSource '{}': invalid MIME pattern '{}' (expected format: type/subtype or type/*)",
-                    name, pattern
-                ));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidPattern,
+                        format!(
+                            "ImportSource '{}': invalid MIME pattern '{}' (expected format: type/subtype or type/*)",
+                            name, pattern
+                        ),
+                    )
+                    .at("spec.email.mimeFilters.exclude"),
+                );
             }
         }
 
         // Validate filename glob patterns
         for pattern in &email.mime_filters.filename_include {
             if let Err(e) = glob::Pattern::new(pattern) {
-                self.errors.push(format!(
-                    "ImportSource '{}': invalid filenameInclude pattern '{}': {}",
-                    name, pattern, e
-                ));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidPattern,
+                        format!(
+                            "ImportSource '{}': invalid filenameInclude pattern '{}': {}",
+                            name, pattern, e
+                        ),
+                    )
+                    .at("spec.email.mimeFilters.filenameInclude"),
+                );
             }
         }
         for pattern in &email.mime_filters.filename_exclude {
             if let Err(e) = glob::Pattern::new(pattern) {
-                self.errors.push(format!(
-                    "ImportSource '{}': invalid filenameExclude pattern '{}': {}",
-                    name, pattern, e
-                ));
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidPattern,
+                        format!(
+                            "ImportSource '{}': invalid filenameExclude pattern '{}': {}",
+                            name, pattern, e
+                        ),
+                    )
+                    .at("spec.email.mimeFilters.filenameExclude"),
+                );
             }
         }
 
         // Validate size constraints
         if email.min_attachment_size > email.max_attachment_size {
-            self.errors.push(format!(
-                "ImportSource '{}': minAttachmentSize ({}) cannot be greater than maxAttachmentSize ({})",
-                name, email.min_attachment_size, email.max_attachment_size
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!(
+                        "ImportSource '{}': minAttachmentSize ({}) cannot be greater than maxAttachmentSize ({})",
+                        name, email.min_attachment_size, email.max_attachment_size
+                    ),
+                )
+                .at("spec.email.minAttachmentSize"),
+            );
         }
 
         // Validate batch size
         if email.batch_size == 0 {
-            self.errors.push(format!(
-                "ImportSource '{}': batchSize must be greater than 0",
-                name
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidValue,
+                    format!("ImportSource '{}': batchSize must be greater than 0", name),
+                )
+                .at("spec.email.batchSize"),
+            );
         }
     }
 
@@ -583,6 +1937,7 @@ impl ConfigValidator {
     fn validate_directory_separation(&mut self, config: &LoadedConfig) {
         use std::path::PathBuf;
 
+        self.enter(&config.settings);
         let settings = &config.settings.resource.spec;
 
         // Expand and try to canonicalize all paths
@@ -596,51 +1951,86 @@ impl ConfigValidator {
 
         // Check if paths are the same
         if input == output {
-            self.errors.push(
-                "Directory overlap: input and output directories cannot be the same".to_string(),
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::DirectoryOverlap,
+                    "Directory overlap: input and output directories cannot be the same",
+                )
+                .at("spec.outputDirectory"),
             );
         }
 
         // Check if output is inside input
         if output.starts_with(&input) && output != input {
-            self.errors.push(format!(
-                "Directory overlap: output directory '{}' is inside input directory '{}'",
-                settings.output_directory, settings.input_directory
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::DirectoryOverlap,
+                    format!(
+                        "Directory overlap: output directory '{}' is inside input directory '{}'",
+                        settings.output_directory, settings.input_directory
+                    ),
+                )
+                .at("spec.outputDirectory"),
+            );
         }
 
         // Check if input is inside output
         if input.starts_with(&output) && input != output {
-            self.errors.push(format!(
-                "Directory overlap: input directory '{}' is inside output directory '{}'",
-                settings.input_directory, settings.output_directory
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::DirectoryOverlap,
+                    format!(
+                        "Directory overlap: input directory '{}' is inside output directory '{}'",
+                        settings.input_directory, settings.output_directory
+                    ),
+                )
+                .at("spec.inputDirectory"),
+            );
         }
 
         // Check ImportSource paths don't overlap with output
         for source in &config.import_sources {
             if let Some(local) = &source.resource.spec.local {
+                self.enter(source);
                 let source_path_expanded = expand_tilde(&local.path);
                 let source_path = std::fs::canonicalize(&source_path_expanded)
                     .unwrap_or_else(|_| PathBuf::from(&source_path_expanded));
 
                 // Check if source path is the same as or inside output directory
                 if source_path == output {
-                    self.errors.push(format!(
-                        "Directory overlap: ImportSource '{}' path '{}' is the same as output directory",
-                        source.resource.metadata.name, local.path
-                    ));
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::DirectoryOverlap,
+                            format!(
+                                "Directory overlap: ImportSource '{}' path '{}' is the same as output directory",
+                                source.resource.metadata.name, local.path
+                            ),
+                        )
+                        .at("spec.local.path"),
+                    );
                 } else if source_path.starts_with(&output) {
-                    self.errors.push(format!(
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::DirectoryOverlap,
+                            format!(
                         "Directory overlap: ImportSource '{}' path '{}' is inside output directory",
                         source.resource.metadata.name, local.path
-                    ));
+                    ),
+                        )
+                        .at("spec.local.path"),
+                    );
                 } else if output.starts_with(&source_path) {
                     // Check if output directory is inside ImportSource path
-                    self.errors.push(format!(
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::DirectoryOverlap,
+                            format!(
                         "Directory overlap: output directory is inside ImportSource '{}' path '{}'",
                         source.resource.metadata.name, local.path
-                    ));
+                    ),
+                        )
+                        .at("spec.local.path"),
+                    );
                 }
             }
         }
@@ -649,6 +2039,7 @@ impl ConfigValidator {
     /// Validates path security (no traversal, no absolute paths in templates).
     fn validate_path_security(&mut self, config: &LoadedConfig) {
         // Validate default output settings
+        self.enter(&config.settings);
         let defaults = &config.settings.resource.spec.defaults;
         self.check_path_security(
             &defaults.output.directory,
@@ -663,6 +2054,7 @@ impl ConfigValidator {
 
         // Validate rule output paths and symlinks
         for rule in &config.rules {
+            self.enter(rule);
             let name = &rule.resource.metadata.name;
             self.check_path_security(
                 &rule.resource.spec.output.directory,
@@ -690,25 +2082,55 @@ impl ConfigValidator {
     fn check_path_security(&mut self, path_template: &str, resource: &str, field: &str) {
         // Check for path traversal (.. sequences)
         if contains_path_traversal(path_template) {
-            self.errors.push(format!(
-                "Path traversal detected in {}.{}: '{}'",
-                resource, field, path_template
-            ));
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::PathTraversal,
+                    format!(
+                        "Path traversal detected in {}.{}: '{}'",
+                        resource, field, path_template
+                    ),
+                )
+                .at(format!("spec.{}", field)),
+            );
         }
 
         // Check for absolute paths (must be relative to output_directory)
         // Uses is_absolute() to handle both Unix (/) and Windows (C:\, \\server\share) paths
         if std::path::Path::new(path_template).is_absolute() {
-            self.errors.push(format!(
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::AbsolutePath,
+                    format!(
                 "Absolute path not allowed in {}.{}: paths must be relative to output directory",
                 resource, field
-            ));
+            ),
+                )
+                .at(format!("spec.{}", field)),
+            );
         }
     }
 
-    /// Returns the collected errors.
-    pub fn errors(&self) -> &[String] {
-        &self.errors
+    /// Returns the collected errors and warnings.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns the messages of the collected errors.
+    pub fn errors(&self) -> Vec<String> {
+        self.messages(true)
+    }
+
+    /// Returns the messages of the collected warnings.
+    pub fn warnings(&self) -> Vec<String> {
+        self.messages(false)
+    }
+
+    fn messages(&self, errors: bool) -> Vec<String> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.is_error() == errors)
+            .map(|d| d.message.clone())
+            .collect()
     }
 }
 
@@ -778,26 +2200,73 @@ fn is_valid_identifier(s: &str) -> bool {
         .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
 }
 
-/// Extracts variable names from a template string.
-///
-/// Variable names start with `$` followed by an identifier. When variables
-/// are concatenated with underscores (e.g., `$day_$original`), we need to
-/// properly split them. The underscore before a `$` belongs to the separator,
-/// not the variable name.
-fn extract_variable_names(template: &str) -> Vec<String> {
-    RE_VARIABLE
-        .captures_iter(template)
-        .map(|cap| {
-            let mut name = cap[1].to_string();
-            // If the match ends with underscore and is followed by $ in the original,
-            // strip the trailing underscore (it's a separator, not part of the variable name)
-            let match_end = cap.get(0).unwrap().end();
-            if name.ends_with('_') && template[match_end..].starts_with('$') {
-                name.pop();
+/// Collects the names of variables compared by a match condition.
+fn collect_match_variables<'a>(condition: &'a MatchCondition, names: &mut Vec<&'a str>) {
+    match condition {
+        MatchCondition::Simple(simple) => {
+            if let Some(variable) = &simple.variable {
+                names.push(&variable.name);
             }
-            name
-        })
-        .collect()
+        }
+        MatchCondition::Compound(compound) => {
+            for cond in compound.all.iter().chain(&compound.any).flatten() {
+                collect_match_variables(cond, names);
+            }
+            if let Some(not) = &compound.not {
+                collect_match_variables(not, names);
+            }
+        }
+    }
+}
+
+/// Collects the correspondents named by a match condition.
+fn collect_match_correspondents<'a>(condition: &'a MatchCondition, names: &mut Vec<&'a str>) {
+    match condition {
+        MatchCondition::Simple(simple) => {
+            if let Some(correspondent) = &simple.correspondent {
+                names.push(correspondent);
+            }
+        }
+        MatchCondition::Compound(compound) => {
+            for cond in compound.all.iter().chain(&compound.any).flatten() {
+                collect_match_correspondents(cond, names);
+            }
+            if let Some(not) = &compound.not {
+                collect_match_correspondents(not, names);
+            }
+        }
+    }
+}
+
+/// Checks an IBAN's length and check digits, ignoring spaces and case.
+fn is_valid_iban(iban: &str) -> bool {
+    let iban: String = iban
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if !(15..=34).contains(&iban.len()) || !iban.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return false;
+    }
+    let (country, rest) = iban.split_at(2);
+    if !country.chars().all(|c| c.is_ascii_alphabetic()) {
+        return false;
+    }
+
+    // Move the country code and check digits to the end and compute mod 97
+    let remainder = rest[2..]
+        .chars()
+        .chain(country.chars())
+        .chain(rest[..2].chars())
+        .fold(0u32, |acc, c| {
+            let value = c.to_digit(36).unwrap_or(0);
+            if value >= 10 {
+                (acc * 100 + value) % 97
+            } else {
+                (acc * 10 + value) % 97
+            }
+        });
+    remainder == 1
 }
 
 /// Checks if a path template could escape the base directory via path traversal.
@@ -853,14 +2322,24 @@ mod tests {
                 output_directory: "/output".to_string(),
                 worker_count: 4,
                 ocr: OcrSettings::default(),
+                limits: LimitsSettings::default(),
+                categorization: CategorizationSettings::default(),
                 defaults: DefaultOutputSettings::default(),
                 git: GitSettings::default(),
                 ai: AiSettings::default(),
                 release_channel: ReleaseChannel::default(),
+                overlay: None,
             },
         )
     }
 
+    fn create_minimal_config() -> LoadedConfig {
+        LoadedConfig::new(ResourceWithPath::new(
+            create_minimal_settings(),
+            "settings.yaml",
+        ))
+    }
+
     fn create_minimal_variable(name: &str, pattern: &str) -> VariableResource {
         Resource::new(
             ResourceKind::Variable,
@@ -869,6 +2348,10 @@ mod tests {
                 pattern: pattern.to_string(),
                 transform: None,
                 default: None,
+                pages: None,
+                region: None,
+                from: None,
+                template: None,
             },
         )
     }
@@ -879,7 +2362,9 @@ mod tests {
             name,
             RuleSpec {
                 priority: 0,
+                extends: None,
                 category: "Test".to_string(),
+                exclusive: true,
                 match_condition: MatchCondition::Simple(SimpleMatch {
                     contains: Some("test".to_string()),
                     ..Default::default()
@@ -894,125 +2379,392 @@ mod tests {
     }
 
     #[test]
-    fn test_valid_config() {
+    fn test_valid_config() {
+        let config = LoadedConfig {
+            variables: vec![ResourceWithPath::new(
+                create_minimal_variable("vendor", r"(?P<vendor>\w+)"),
+                "variables/vendor.yaml",
+            )],
+            rules: vec![ResourceWithPath::new(
+                create_minimal_rule("test-rule"),
+                "rules/test.yaml",
+            )],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        let result = validator.validate(&config);
+        assert!(result.is_ok(), "Errors: {:?}", validator.errors());
+    }
+
+    #[test]
+    fn test_missing_input_directory() {
+        let mut settings = create_minimal_settings();
+        settings.spec.input_directory = "".to_string();
+
+        let config = LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"));
+
+        let mut validator = ConfigValidator::new();
+        let result = validator.validate(&config);
+        assert!(result.is_err());
+        assert!(validator
+            .errors()
+            .iter()
+            .any(|e| e.contains("inputDirectory")));
+    }
+
+    #[test]
+    fn test_invalid_variable_pattern() {
+        let config = LoadedConfig {
+            variables: vec![ResourceWithPath::new(
+                create_minimal_variable("bad", "[invalid(regex"),
+                "variables/bad.yaml",
+            )],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        let result = validator.validate(&config);
+        assert!(result.is_err());
+        assert!(validator
+            .errors()
+            .iter()
+            .any(|e| e.contains("invalid regex")));
+    }
+
+    #[test]
+    fn test_undefined_variable_reference() {
+        let mut rule = create_minimal_rule("test");
+        rule.spec.output.directory = "Test/$undefined_var".to_string();
+
+        let config = LoadedConfig {
+            rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        let result = validator.validate(&config);
+        assert!(result.is_err());
+        assert!(validator
+            .errors()
+            .iter()
+            .any(|e| e.contains("undefined variable")));
+    }
+
+    #[test]
+    fn test_builtin_variable_references() {
+        let mut rule = create_minimal_rule("test");
+        rule.spec.output.directory = "Archive/$y/$m".to_string();
+        rule.spec.output.filename = "$original_$timestamp".to_string();
+
+        let config = LoadedConfig {
+            rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        let result = validator.validate(&config);
+        assert!(result.is_ok(), "Errors: {:?}", validator.errors());
+    }
+
+    #[test]
+    fn test_empty_match_condition() {
+        let mut rule = create_minimal_rule("test");
+        rule.spec.match_condition = MatchCondition::Simple(SimpleMatch::default());
+
+        let config = LoadedConfig {
+            rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        let result = validator.validate(&config);
+        assert!(result.is_err());
+        assert!(validator
+            .errors()
+            .iter()
+            .any(|e| e.contains("must specify at least one")));
+    }
+
+    #[test]
+    fn test_fuzzy_match_validation() {
+        let mut rule = create_minimal_rule("test");
+        rule.spec.match_condition = MatchCondition::Simple(SimpleMatch {
+            fuzzy: Some(FuzzyMatch {
+                value: "UBS".to_string(),
+                max_distance: Some(3),
+            }),
+            ..Default::default()
+        });
+        let mut empty = create_minimal_rule("empty");
+        empty.spec.match_condition = MatchCondition::Simple(SimpleMatch {
+            contains_any_fuzzy: Some(vec!["Swisscom".to_string(), " ".to_string()]),
+            ..Default::default()
+        });
+
+        let config = LoadedConfig {
+            rules: vec![
+                ResourceWithPath::new(rule, "rules/test.yaml"),
+                ResourceWithPath::new(empty, "rules/empty.yaml"),
+            ],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(errors.iter().any(|e| e.contains("maxDistance")));
+        assert!(errors.iter().any(|e| e.contains("containsAnyFuzzy")));
+        assert!(!errors
+            .iter()
+            .any(|e| e.contains("must specify at least one")));
+    }
+
+    #[test]
+    fn test_scoring_validation() {
+        let mut settings = create_minimal_settings();
+        settings.spec.categorization.threshold = 1.5;
+        settings.spec.categorization.classifier.threshold = -0.1;
+        let mut rule = create_minimal_rule("test");
+        rule.spec.match_condition = MatchCondition::Compound(CompoundMatch {
+            all: Some(vec![MatchCondition::Simple(SimpleMatch {
+                contains: Some("invoice".to_string()),
+                weight: Some(-1.0),
+                ..Default::default()
+            })]),
+            ..Default::default()
+        });
+
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![ResourceWithPath::new(
-                create_minimal_variable("vendor", r"(?P<vendor>\w+)"),
-                "variables/vendor.yaml",
-            )],
-            rules: vec![ResourceWithPath::new(
-                create_minimal_rule("test-rule"),
-                "rules/test.yaml",
-            )],
-            import_sources: vec![],
+            rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
+            ..LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"))
         };
 
         let mut validator = ConfigValidator::new();
-        let result = validator.validate(&config);
-        assert!(result.is_ok(), "Errors: {:?}", validator.errors());
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("categorization.threshold")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("categorization.classifier.threshold")));
+        assert!(errors.iter().any(|e| e.contains("weight")));
     }
 
     #[test]
-    fn test_missing_input_directory() {
-        let mut settings = create_minimal_settings();
-        settings.spec.input_directory = "".to_string();
+    fn test_page_scope_validation() {
+        let mut rule = create_minimal_rule("test");
+        rule.spec.match_condition = MatchCondition::Compound(CompoundMatch {
+            any: Some(vec![
+                MatchCondition::Simple(SimpleMatch {
+                    contains: Some("Acme".to_string()),
+                    pages: Some(PageSelection::Numbers(vec![0, 2])),
+                    ..Default::default()
+                }),
+                MatchCondition::Simple(SimpleMatch {
+                    contains: Some("acme.pdf".to_string()),
+                    field: Some(MatchField::Filename),
+                    region: Some(PageRegion::Top(5)),
+                    ..Default::default()
+                }),
+            ]),
+            ..Default::default()
+        });
+        let mut variable = create_minimal_variable("total", r"Total (?P<total>\d+)");
+        variable.spec.region = Some(PageRegion::Bottom(0));
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
-            import_sources: vec![],
+            variables: vec![ResourceWithPath::new(variable, "variables/total.yaml")],
+            rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
-        let result = validator.validate(&config);
-        assert!(result.is_err());
-        assert!(validator
-            .errors()
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(errors
             .iter()
-            .any(|e| e.contains("inputDirectory")));
+            .any(|e| e.starts_with("Rule 'test'") && e.contains("counting from 1")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("only apply to the text and firstPage fields")));
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("Variable 'total'") && e.contains("region")));
     }
 
     #[test]
-    fn test_invalid_variable_pattern() {
+    fn test_computed_variable_validation() {
+        let computed = |name: &str, template: &str| {
+            let mut variable = create_minimal_variable(name, "");
+            variable.spec.template = Some(template.to_string());
+            ResourceWithPath::new(variable, format!("variables/{}.yaml", name))
+        };
+        let mut scoped = computed("scoped", "$y");
+        scoped.resource.spec.region = Some(PageRegion::Top(3));
+
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![ResourceWithPath::new(
-                create_minimal_variable("bad", "[invalid(regex"),
-                "variables/bad.yaml",
-            )],
-            rules: vec![],
-            import_sources: vec![],
+            variables: vec![
+                ResourceWithPath::new(
+                    create_minimal_variable("vendor", r"From: (?P<vendor>\w+)"),
+                    "variables/vendor.yaml",
+                ),
+                computed("period", "$y-$vendor"),
+                computed("a", "$b"),
+                computed("b", "$a_$vendor"),
+                computed("unknown", "$nope"),
+                computed("late", "$correspondent"),
+                scoped,
+            ],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
-        let result = validator.validate(&config);
-        assert!(result.is_err());
-        assert!(validator
-            .errors()
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(!errors.iter().any(|e| e.contains("'period'")));
+        assert!(errors
+            .contains(&"Variable 'a': template references form a cycle: a -> b -> a".to_string()));
+        assert!(errors.iter().any(
+            |e| e.starts_with("Variable 'unknown'") && e.contains("undefined variable '$nope'")
+        ));
+        assert!(errors
             .iter()
-            .any(|e| e.contains("invalid regex")));
+            .any(|e| e.starts_with("Variable 'late'") && e.contains("only set after")));
+        assert!(errors.iter().any(|e| e.starts_with("Variable 'scoped'")
+            && e.contains("do not apply to computed variables")));
+        assert_eq!(errors.len(), 4);
     }
 
     #[test]
-    fn test_undefined_variable_reference() {
-        let mut rule = create_minimal_rule("test");
-        rule.spec.output.directory = "Test/$undefined_var".to_string();
+    fn test_variable_source_validation() {
+        let mut policy = create_minimal_variable("policy", r"^(?P<policy>\d+)_");
+        policy.spec.from = Some(VariableSource::Filename);
+        let mut total = create_minimal_variable("total", r"Total (?P<total>\d+)");
+        total.spec.from = Some(VariableSource::Text);
+        total.spec.region = Some(PageRegion::Bottom(10));
+        let mut author = create_minimal_variable("author", r"Author: (?P<author>.+)");
+        author.spec.from = Some(VariableSource::PdfMetadata);
+        author.spec.pages = Some(PageSelection::Numbers(vec![1]));
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            variables: vec![
+                ResourceWithPath::new(policy, "variables/policy.yaml"),
+                ResourceWithPath::new(total, "variables/total.yaml"),
+                ResourceWithPath::new(author, "variables/author.yaml"),
+            ],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
-        let result = validator.validate(&config);
-        assert!(result.is_err());
-        assert!(validator
-            .errors()
-            .iter()
-            .any(|e| e.contains("undefined variable")));
+        assert!(validator.validate(&config).is_err());
+        assert_eq!(
+            validator.errors(),
+            &["Variable 'author': pages and region only apply to variables from text".to_string()]
+        );
     }
 
     #[test]
-    fn test_builtin_variable_references() {
-        let mut rule = create_minimal_rule("test");
-        rule.spec.output.directory = "Archive/$y/$m".to_string();
-        rule.spec.output.filename = "$original_$timestamp".to_string();
+    fn test_variable_condition_validation() {
+        let variable_condition = |variable: VariableMatch| {
+            MatchCondition::Simple(SimpleMatch {
+                variable: Some(Box::new(variable)),
+                ..Default::default()
+            })
+        };
+        let mut rule = create_minimal_rule("large");
+        rule.spec.match_condition = MatchCondition::Compound(CompoundMatch {
+            all: Some(vec![
+                variable_condition(VariableMatch {
+                    name: "amount".to_string(),
+                    gt: Some(CompareValue::Number(1000.0)),
+                    ..Default::default()
+                }),
+                variable_condition(VariableMatch {
+                    name: "date".to_string(),
+                    between: Some([
+                        CompareValue::Text("2020-01-01".to_string()),
+                        CompareValue::Text("31.12.2020".to_string()),
+                    ]),
+                    ..Default::default()
+                }),
+            ]),
+            ..Default::default()
+        });
 
-        let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+        let mut config = LoadedConfig {
+            variables: vec![
+                ResourceWithPath::new(
+                    create_minimal_variable("amount", r"(?P<amount>[\d.]+)"),
+                    "variables/amount.yaml",
+                ),
+                ResourceWithPath::new(
+                    create_minimal_variable("date", r"(?P<date>[\d.]+)"),
+                    "variables/date.yaml",
+                ),
+            ],
+            rules: vec![ResourceWithPath::new(rule.clone(), "rules/large.yaml")],
+            ..create_minimal_config()
         };
+        let mut validator = ConfigValidator::new();
+        assert!(
+            validator.validate(&config).is_ok(),
+            "Errors: {:?}",
+            validator.errors()
+        );
 
+        // Undefined variables and unordered values are rejected
+        config.variables.truncate(1);
+        rule.metadata.name = "other".to_string();
+        rule.spec.match_condition = variable_condition(VariableMatch {
+            name: "amount".to_string(),
+            lt: Some(CompareValue::Text("lots".to_string())),
+            between: Some([CompareValue::Number(10.0), CompareValue::Number(1.0)]),
+            ..Default::default()
+        });
+        config
+            .rules
+            .push(ResourceWithPath::new(rule, "rules/other.yaml"));
         let mut validator = ConfigValidator::new();
-        let result = validator.validate(&config);
-        assert!(result.is_ok(), "Errors: {:?}", validator.errors());
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("undefined variable 'date'")));
+        assert!(errors.iter().any(|e| e.contains("cannot order by 'lots'")));
+        assert!(errors.iter().any(|e| e.contains("ascending order")));
     }
 
     #[test]
-    fn test_empty_match_condition() {
+    fn test_tag_rule_needs_no_output() {
+        let mut tag_rule = create_minimal_rule("tax");
+        tag_rule.spec.exclusive = false;
+        tag_rule.spec.output = OutputSettings::default();
         let mut rule = create_minimal_rule("test");
-        rule.spec.match_condition = MatchCondition::Simple(SimpleMatch::default());
+        rule.spec.output.directory = "$y/$tags".to_string();
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            rules: vec![
+                ResourceWithPath::new(tag_rule.clone(), "rules/tax.yaml"),
+                ResourceWithPath::new(rule, "rules/test.yaml"),
+            ],
+            ..create_minimal_config()
         };
+        let mut validator = ConfigValidator::new();
+        assert!(validator.validate(&config).is_ok());
 
+        tag_rule.spec.exclusive = true;
+        let config = LoadedConfig {
+            rules: vec![ResourceWithPath::new(tag_rule, "rules/tax.yaml")],
+            ..config
+        };
         let mut validator = ConfigValidator::new();
-        let result = validator.validate(&config);
-        assert!(result.is_err());
+        assert!(validator.validate(&config).is_err());
         assert!(validator
             .errors()
             .iter()
-            .any(|e| e.contains("must specify at least one")));
+            .any(|e| e.contains("output.directory is required")));
     }
 
     #[test]
@@ -1020,10 +2772,8 @@ mod tests {
         let rule = create_minimal_rule("123-invalid");
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
             rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1059,12 +2809,7 @@ mod tests {
         settings.spec.git.enabled = true;
         settings.spec.git.repository = "".to_string();
 
-        let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
-            import_sources: vec![],
-        };
+        let config = LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"));
 
         let mut validator = ConfigValidator::new();
         let result = validator.validate(&config);
@@ -1096,10 +2841,8 @@ mod tests {
         });
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
             rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1132,13 +2875,11 @@ mod tests {
     #[test]
     fn test_valid_import_source() {
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(
                 create_minimal_import_source("local-docs"),
                 "sources/local-docs.yaml",
             )],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1151,10 +2892,8 @@ mod tests {
         let source = create_minimal_import_source("");
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/empty.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1171,10 +2910,8 @@ mod tests {
         let source = create_minimal_import_source("123-invalid");
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/invalid.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1194,10 +2931,8 @@ mod tests {
         }
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/test.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1217,10 +2952,8 @@ mod tests {
         }
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/test.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1240,10 +2973,8 @@ mod tests {
         }
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/test.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1264,10 +2995,8 @@ mod tests {
         }
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/test.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1285,12 +3014,7 @@ mod tests {
         settings.spec.input_directory = "/data".to_string();
         settings.spec.output_directory = "/data".to_string();
 
-        let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
-            import_sources: vec![],
-        };
+        let config = LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"));
 
         let mut validator = ConfigValidator::new();
         let result = validator.validate(&config);
@@ -1307,12 +3031,7 @@ mod tests {
         settings.spec.input_directory = "/data".to_string();
         settings.spec.output_directory = "/data/output".to_string();
 
-        let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
-            import_sources: vec![],
-        };
+        let config = LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"));
 
         let mut validator = ConfigValidator::new();
         let result = validator.validate(&config);
@@ -1329,12 +3048,7 @@ mod tests {
         settings.spec.input_directory = "/data/input".to_string();
         settings.spec.output_directory = "/data".to_string();
 
-        let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
-            import_sources: vec![],
-        };
+        let config = LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"));
 
         let mut validator = ConfigValidator::new();
         let result = validator.validate(&config);
@@ -1353,10 +3067,8 @@ mod tests {
         }
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/test.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1376,10 +3088,8 @@ mod tests {
         }
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/test.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1402,10 +3112,8 @@ mod tests {
         }
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/test.yaml")],
+            ..LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"))
         };
 
         let mut validator = ConfigValidator::new();
@@ -1446,10 +3154,8 @@ mod tests {
         rule.spec.output.directory = "../../../sensitive".to_string();
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
             rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1467,10 +3173,8 @@ mod tests {
         rule.spec.output.filename = "../escape/$original".to_string();
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
             rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1490,10 +3194,8 @@ mod tests {
         }];
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
             rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1511,10 +3213,8 @@ mod tests {
         rule.spec.output.directory = "/absolute/path".to_string();
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
             rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1531,12 +3231,7 @@ mod tests {
         let mut settings = create_minimal_settings();
         settings.spec.defaults.output.directory = "/absolute/default".to_string();
 
-        let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
-            import_sources: vec![],
-        };
+        let config = LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"));
 
         let mut validator = ConfigValidator::new();
         let result = validator.validate(&config);
@@ -1553,13 +3248,11 @@ mod tests {
         let source2 = create_minimal_import_source("duplicate-name");
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![
                 ResourceWithPath::new(source1, "sources/source1.yaml"),
                 ResourceWithPath::new(source2, "sources/source2.yaml"),
             ],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1586,10 +3279,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/missing.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1644,13 +3335,11 @@ mod tests {
     #[test]
     fn test_valid_email_source() {
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(
                 create_minimal_email_source("email-docs"),
                 "sources/email-docs.yaml",
             )],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1672,10 +3361,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/missing.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1724,10 +3411,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/empty-host.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1776,10 +3461,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/no-tls.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1828,10 +3511,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/no-pw.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1880,10 +3561,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/no-oauth2.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1937,10 +3616,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/bad-mime.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -1999,10 +3676,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/valid-mime.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2047,10 +3722,8 @@ mod tests {
         );
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(source, "sources/bad-sizes.yaml")],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2090,13 +3763,11 @@ mod tests {
     fn test_variable_name_conflicts_with_builtin() {
         // "y" is a built-in — should be rejected
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
             variables: vec![ResourceWithPath::new(
                 create_minimal_variable("y", r"(?P<y>\d{4})"),
                 "variables/y.yaml",
             )],
-            rules: vec![],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2108,16 +3779,33 @@ mod tests {
             .any(|e| e.contains("conflicts with built-in variable") && e.contains("'y'")));
     }
 
+    #[test]
+    fn test_variable_name_conflicts_with_pipeline_variables() {
+        for name in ["tags", "correspondent"] {
+            let config = LoadedConfig {
+                variables: vec![ResourceWithPath::new(
+                    create_minimal_variable(name, &format!(r"(?P<{}>\w+)", name)),
+                    format!("variables/{}.yaml", name),
+                )],
+                ..create_minimal_config()
+            };
+
+            let mut validator = ConfigValidator::new();
+            assert!(validator.validate(&config).is_err());
+            assert!(validator.errors().iter().any(|e| {
+                e.contains("conflicts with built-in variable") && e.contains(&format!("'{}'", name))
+            }));
+        }
+    }
+
     #[test]
     fn test_variable_name_conflicts_with_builtin_h() {
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
             variables: vec![ResourceWithPath::new(
                 create_minimal_variable("h", r"(?P<h>\d+)"),
                 "variables/h.yaml",
             )],
-            rules: vec![],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2132,13 +3820,11 @@ mod tests {
     #[test]
     fn test_variable_name_conflicts_with_builtin_i() {
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
             variables: vec![ResourceWithPath::new(
                 create_minimal_variable("i", r"(?P<i>\d+)"),
                 "variables/i.yaml",
             )],
-            rules: vec![],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2153,13 +3839,11 @@ mod tests {
     #[test]
     fn test_variable_name_conflicts_with_builtin_s() {
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
             variables: vec![ResourceWithPath::new(
                 create_minimal_variable("s", r"(?P<s>\d+)"),
                 "variables/s.yaml",
             )],
-            rules: vec![],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2174,13 +3858,11 @@ mod tests {
     #[test]
     fn test_variable_name_conflicts_with_builtin_timestamp() {
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
             variables: vec![ResourceWithPath::new(
                 create_minimal_variable("timestamp", r"(?P<timestamp>\d+)"),
                 "variables/timestamp.yaml",
             )],
-            rules: vec![],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2196,13 +3878,11 @@ mod tests {
     fn test_non_builtin_variable_name_accepted() {
         // "vendor" is NOT a built-in — should be accepted
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
             variables: vec![ResourceWithPath::new(
                 create_minimal_variable("vendor", r"(?P<vendor>\w+)"),
                 "variables/vendor.yaml",
             )],
-            rules: vec![],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2218,10 +3898,8 @@ mod tests {
         rule.spec.output.filename = "$original_$h$i$s".to_string();
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
-            variables: vec![],
             rules: vec![ResourceWithPath::new(rule, "rules/test.yaml")],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
@@ -2233,17 +3911,382 @@ mod tests {
     fn test_non_builtin_alias_name_accepted() {
         // "year" is NOT an implemented built-in (only "y" is) — should be accepted as a user variable
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(create_minimal_settings(), "settings.yaml"),
             variables: vec![ResourceWithPath::new(
                 create_minimal_variable("year", r"(?P<year>\d{4})"),
                 "variables/year.yaml",
             )],
-            rules: vec![],
-            import_sources: vec![],
+            ..create_minimal_config()
         };
 
         let mut validator = ConfigValidator::new();
         let result = validator.validate(&config);
         assert!(result.is_ok(), "Errors: {:?}", validator.errors());
     }
+
+    fn create_rule_test(name: &str, cases: Vec<RuleTestCase>) -> RuleTestResource {
+        Resource::new(ResourceKind::RuleTest, name, RuleTestSpec { cases })
+    }
+
+    #[test]
+    fn test_invalid_rule_test_cases() {
+        let test = create_rule_test(
+            "broken",
+            vec![
+                RuleTestCase {
+                    name: "both".to_string(),
+                    text: Some("test".to_string()),
+                    file: Some("tests/sample.pdf".to_string()),
+                    expect: RuleTestExpectation {
+                        rule: Some("missing".to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                RuleTestCase {
+                    name: "both".to_string(),
+                    file: Some("../outside.pdf".to_string()),
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let config = LoadedConfig {
+            rules: vec![ResourceWithPath::new(
+                create_minimal_rule("test"),
+                "rules/test.yaml",
+            )],
+            rule_tests: vec![ResourceWithPath::new(test, "tests/broken.yaml")],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("exactly one of text and file")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("undefined rule 'missing'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("duplicate case name 'both'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("relative to the config directory")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("must expect a category, rule or output")));
+    }
+
+    #[test]
+    fn test_failing_rule_test_reported_with_config_dir() {
+        let test = create_rule_test(
+            "samples",
+            vec![
+                RuleTestCase {
+                    name: "matches".to_string(),
+                    text: Some("a test document".to_string()),
+                    expect: RuleTestExpectation {
+                        category: Some("Test".to_string()),
+                        rule: Some("test".to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                RuleTestCase {
+                    name: "unsorted".to_string(),
+                    text: Some("a letter".to_string()),
+                    expect: RuleTestExpectation {
+                        category: Some("Test".to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let config = LoadedConfig {
+            rules: vec![ResourceWithPath::new(
+                create_minimal_rule("test"),
+                "rules/test.yaml",
+            )],
+            rule_tests: vec![ResourceWithPath::new(test, "tests/samples.yaml")],
+            ..create_minimal_config()
+        };
+
+        // Without a config directory the tests are not run
+        let mut validator = ConfigValidator::new();
+        assert!(validator.validate(&config).is_ok());
+
+        let mut validator = ConfigValidator::new().with_config_dir("/config");
+        assert!(validator.validate(&config).is_err());
+        assert_eq!(validator.errors().len(), 1);
+        assert!(validator.errors()[0].contains("RuleTest 'samples': case 'unsorted' failed"));
+    }
+
+    #[test]
+    fn test_shadowed_rule_is_a_warning() {
+        let mut broad = create_minimal_rule("broad");
+        broad.spec.priority = 10;
+        let mut narrow = create_minimal_rule("narrow");
+        narrow.spec.match_condition = MatchCondition::Simple(SimpleMatch {
+            contains: Some("Test report".to_string()),
+            ..Default::default()
+        });
+
+        let config = LoadedConfig {
+            rules: vec![
+                ResourceWithPath::new(broad, "rules/broad.yaml"),
+                ResourceWithPath::new(narrow, "rules/narrow.yaml"),
+            ],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        assert!(validator.validate(&config).is_ok());
+        assert_eq!(validator.warnings().len(), 1);
+        assert!(validator.warnings()[0].contains("Rule 'narrow' never matches"));
+
+        let history = RuleHistory {
+            since: Some(chrono::Utc::now() - chrono::Duration::days(400)),
+            unused_after_days: 90,
+            ..Default::default()
+        };
+        let mut validator = ConfigValidator::new().with_rule_history(history);
+        assert!(validator.validate(&config).is_ok());
+        assert_eq!(validator.warnings().len(), 3);
+        assert!(validator
+            .warnings()
+            .iter()
+            .any(|w| w.contains("Rule 'broad' has not matched")));
+    }
+
+    #[test]
+    fn test_rule_template_validation() {
+        let template = |name: &str| {
+            Resource::new(
+                ResourceKind::RuleTemplate,
+                name,
+                RuleTemplateSpec {
+                    symlinks: vec![SymlinkSettings {
+                        target: String::new(),
+                    }],
+                    ..Default::default()
+                },
+            )
+        };
+        let mut rule = create_minimal_rule("acme");
+        rule.spec.extends = Some("vendor".to_string());
+
+        let config = LoadedConfig {
+            rules: vec![ResourceWithPath::new(rule, "rules/acme.yaml")],
+            rule_templates: vec![
+                ResourceWithPath::new(template("vendor"), "templates/vendor.yaml"),
+                ResourceWithPath::new(template("unused"), "templates/unused.yaml"),
+                ResourceWithPath::new(template("acme"), "templates/acme.yaml"),
+            ],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("RuleTemplate 'vendor': symlink[0].target is required")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("Duplicate rule template name: 'acme'")));
+        let warnings = validator.warnings();
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("RuleTemplate 'unused': not extended by any rule")));
+        assert!(!warnings.iter().any(|w| w.contains("'vendor'")));
+    }
+
+    #[test]
+    fn test_correspondent_validation() {
+        let correspondent = |name: &str, spec: CorrespondentSpec| {
+            ResourceWithPath::new(
+                Resource::new(ResourceKind::Correspondent, name, spec),
+                format!("correspondents/{}.yaml", name),
+            )
+        };
+        let mut rule = create_minimal_rule("premiums");
+        rule.spec.match_condition = MatchCondition::Compound(CompoundMatch {
+            any: Some(vec![
+                MatchCondition::Simple(SimpleMatch {
+                    correspondent: Some("Helsana".to_string()),
+                    ..Default::default()
+                }),
+                MatchCondition::Simple(SimpleMatch {
+                    correspondent: Some("css".to_string()),
+                    ..Default::default()
+                }),
+            ]),
+            ..Default::default()
+        });
+
+        let config = LoadedConfig {
+            rules: vec![ResourceWithPath::new(rule, "rules/premiums.yaml")],
+            correspondents: vec![
+                correspondent(
+                    "helsana",
+                    CorrespondentSpec {
+                        ibans: vec!["CH93 0076 2011 6238 5295 7".to_string()],
+                        email_domains: vec!["helsana.ch".to_string()],
+                        ..Default::default()
+                    },
+                ),
+                correspondent(
+                    "broken",
+                    CorrespondentSpec {
+                        ibans: vec!["CH00 0076 2011 6238 5295 7".to_string()],
+                        email_domains: vec!["billing@example.com".to_string()],
+                        default_category: Some(String::new()),
+                        ..Default::default()
+                    },
+                ),
+            ],
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("references unknown correspondent 'css'")));
+        assert!(!errors.iter().any(|e| e.contains("'Helsana'")));
+        assert!(!errors.iter().any(|e| e.contains("Correspondent 'helsana'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("Correspondent 'broken': invalid IBAN")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("invalid email domain 'billing@example.com'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("defaultCategory must not be empty")));
+    }
+
+    #[test]
+    fn test_lookup_table_validation() {
+        let table = |name: &str, spec: LookupTableSpec| {
+            ResourceWithPath::new(
+                Resource::new(ResourceKind::LookupTable, name, spec),
+                format!("lookups/{}.yaml", name),
+            )
+        };
+        let entry = |key: &str, value: &str| LookupEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let mut vendor = create_minimal_variable("vendor", r"(?P<vendor>\w+)");
+        vendor.spec.transform = Some(VariableTransform::Lookup("vendors".to_string()));
+        let mut bank = create_minimal_variable("bank", r"(?P<bank>\w+)");
+        bank.spec.transform = Some(VariableTransform::Lookup("banks".to_string()));
+
+        let config = LoadedConfig {
+            variables: vec![
+                ResourceWithPath::new(vendor, "variables/vendor.yaml"),
+                ResourceWithPath::new(bank, "variables/bank.yaml"),
+            ],
+            lookup_tables: vec![
+                table(
+                    "vendors",
+                    LookupTableSpec {
+                        entries: vec![entry("SBB CFF FFS", "SBB")],
+                        file: Some("lookups/vendors.csv".to_string()),
+                        ..Default::default()
+                    },
+                ),
+                table(
+                    "patterns",
+                    LookupTableSpec {
+                        mode: LookupMode::Regex,
+                        entries: vec![entry("(unclosed", "x")],
+                        fallback: Some(String::new()),
+                        ..Default::default()
+                    },
+                ),
+                table("empty", LookupTableSpec::default()),
+            ],
+            lookup_file_entries: [(
+                "vendors".to_string(),
+                vec![entry("sbb  cff ffs", "SBB"), entry("", "none")],
+            )]
+            .into_iter()
+            .collect(),
+            ..create_minimal_config()
+        };
+
+        let mut validator = ConfigValidator::new();
+        assert!(validator.validate(&config).is_err());
+        let errors = validator.errors();
+        assert!(errors
+            .iter()
+            .any(|e| e
+                .contains("Variable 'bank': transform references unknown lookup table 'banks'")));
+        assert!(!errors
+            .iter()
+            .any(|e| e.contains("unknown lookup table 'vendors'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("LookupTable 'vendors': entry keys must not be empty")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("LookupTable 'patterns': invalid key pattern '(unclosed'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("LookupTable 'patterns': fallback must not be empty")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("LookupTable 'empty': at least one entry or a file is required")));
+        assert!(validator
+            .warnings()
+            .iter()
+            .any(|w| w.contains("key 'sbb  cff ffs' appears more than once")));
+    }
+
+    #[test]
+    fn test_diagnostics_point_at_file_and_line() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("settings.yaml"),
+            "apiVersion: paporg.io/v1\nkind: Settings\nmetadata:\n  name: default\n\
+             spec:\n  inputDirectory: /inbox\n  outputDirectory: /output\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("rules.yaml"),
+            "apiVersion: paporg.io/v1\nkind: Rule\nmetadata:\n  name: first\n\
+             spec:\n  category: a\n  match:\n    contains: a\n  output:\n    \
+             directory: a\n    filename: $original\n\
+             ---\n\
+             apiVersion: paporg.io/v1\nkind: Rule\nmetadata:\n  name: second\n\
+             spec:\n  category: b\n  match:\n    all:\n      - contains: b\n      \
+             - pattern: \"(unclosed\"\n  output:\n    directory: b\n    filename: $original\n",
+        )
+        .unwrap();
+        let config = crate::gitops::ConfigLoader::new(dir.path()).load().unwrap();
+
+        let mut validator = ConfigValidator::new().with_config_dir(dir.path());
+        let Err(GitOpsError::Validation(diagnostics)) = validator.validate(&config) else {
+            panic!("expected validation to fail");
+        };
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code, DiagnosticCode::InvalidPattern);
+        assert_eq!(diagnostic.kind, Some(ResourceKind::Rule));
+        assert_eq!(diagnostic.name.as_deref(), Some("second"));
+        assert_eq!(diagnostic.path, Some(PathBuf::from("rules.yaml")));
+        assert_eq!(
+            diagnostic.yaml_path.as_deref(),
+            Some("spec.match.all[1].pattern")
+        );
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(22), Some(9)));
+    }
 }
@@ -0,0 +1,339 @@
+//! API versions of resources and the conversions between them.
+//!
+//! Resources are deserialized into the types of [`API_VERSION`]. Documents
+//! written in an older version are converted when loaded, by the
+//! conversions registered from each version to the next, so configuration
+//! repositories keep working when a field is renamed or changes meaning.
+//! [`ConfigLoader::upgrade_api_version`](super::loader::ConfigLoader::upgrade_api_version)
+//! rewrites the files themselves.
+//!
+//! To change the format of a resource, say the rules of `paporg.io/v2`:
+//!
+//! 1. Copy `RuleSpec` and the types it changes into [`v1`] as they are,
+//!    replacing the re-exports, so the old format stays readable.
+//! 2. Change the types in [`resource`](super::resource) and bump
+//!    [`API_VERSION`].
+//! 3. Implement `From<v1::RuleSpec> for RuleSpec` and add the step to
+//!    [`BUILTIN_CONVERSIONS`]:
+//!
+//! ```ignore
+//! Conversion {
+//!     from: "paporg.io/v1",
+//!     to: "paporg.io/v2",
+//!     specs: &[SpecConversion::new::<v1::RuleSpec, RuleSpec>(ResourceKind::Rule)],
+//! }
+//! ```
+
+use std::fmt;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_yaml::Value;
+
+use super::error::{GitOpsError, Result};
+use super::resource::{ResourceKind, API_VERSION};
+
+/// The resource types of `paporg.io/v1`.
+///
+/// `paporg.io/v1` is the current version, so these are the types of
+/// [`resource`](super::resource). Once a later version changes a spec, the
+/// `paporg.io/v1` form of it is kept here.
+pub mod v1 {
+    pub use crate::gitops::resource::{
+        CorrespondentSpec, ImportSourceSpec, LookupTableSpec, RuleSpec, RuleTemplateSpec,
+        RuleTestSpec, SettingsSpec, VariableSpec,
+    };
+}
+
+/// Converts a spec of one version to the next.
+pub type ConvertFn = fn(&Value) -> std::result::Result<Value, String>;
+
+/// The conversion of the specs of one kind.
+#[derive(Clone, Copy)]
+pub struct SpecConversion {
+    pub kind: ResourceKind,
+    pub convert: ConvertFn,
+}
+
+impl SpecConversion {
+    /// Converts specs of `kind` by reading them as `Old`, the type of the
+    /// previous version, and converting that into `New`.
+    pub const fn new<Old, New>(kind: ResourceKind) -> Self
+    where
+        Old: DeserializeOwned + Into<New>,
+        New: Serialize,
+    {
+        Self {
+            kind,
+            convert: convert_spec::<Old, New>,
+        }
+    }
+}
+
+fn convert_spec<Old, New>(spec: &Value) -> std::result::Result<Value, String>
+where
+    Old: DeserializeOwned + Into<New>,
+    New: Serialize,
+{
+    let old: Old = serde_yaml::from_value(spec.clone()).map_err(|e| e.to_string())?;
+    serde_yaml::to_value(old.into()).map_err(|e| e.to_string())
+}
+
+/// A conversion from one API version to the next. Specs of kinds without
+/// a conversion of their own are left as they are.
+#[derive(Clone, Copy)]
+pub struct Conversion {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub specs: &'static [SpecConversion],
+}
+
+impl fmt::Debug for Conversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Conversion({} -> {})", self.from, self.to)
+    }
+}
+
+/// Conversions of the API versions paporg has had. `paporg.io/v1` is the
+/// first version, so there are none yet.
+pub const BUILTIN_CONVERSIONS: &[Conversion] = &[];
+
+/// The conversions available to a loader.
+#[derive(Debug, Clone)]
+pub struct ConversionRegistry {
+    conversions: Vec<Conversion>,
+}
+
+impl Default for ConversionRegistry {
+    fn default() -> Self {
+        Self {
+            conversions: BUILTIN_CONVERSIONS.to_vec(),
+        }
+    }
+}
+
+impl ConversionRegistry {
+    /// Creates a registry without any conversions.
+    pub fn empty() -> Self {
+        Self {
+            conversions: Vec::new(),
+        }
+    }
+
+    /// Adds a conversion.
+    pub fn register(mut self, conversion: Conversion) -> Self {
+        self.conversions.push(conversion);
+        self
+    }
+
+    /// Returns the API versions documents can be written in, the current
+    /// one first.
+    pub fn supported_versions(&self) -> Vec<&str> {
+        let mut versions = vec![API_VERSION];
+        versions.extend(
+            self.conversions
+                .iter()
+                .map(|c| c.from)
+                .filter(|&version| self.upgrade_path(version).is_some()),
+        );
+        versions
+    }
+
+    /// Returns the conversions that take a version to [`API_VERSION`].
+    fn upgrade_path(&self, version: &str) -> Option<Vec<&Conversion>> {
+        let mut path = Vec::new();
+        let mut version = version;
+        while version != API_VERSION {
+            // A chain longer than the registry would visit a version twice
+            if path.len() == self.conversions.len() {
+                return None;
+            }
+            let conversion = self.conversions.iter().find(|c| c.from == version)?;
+            path.push(conversion);
+            version = conversion.to;
+        }
+        Some(path)
+    }
+
+    /// Converts a document of the file at `path` to [`API_VERSION`].
+    /// Returns false if it already has that version.
+    pub fn upgrade(&self, kind: ResourceKind, document: &mut Value, path: &Path) -> Result<bool> {
+        let version = document["apiVersion"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        if version == API_VERSION {
+            return Ok(false);
+        }
+
+        let conversions =
+            self.upgrade_path(&version)
+                .ok_or_else(|| GitOpsError::InvalidApiVersion {
+                    version: version.clone(),
+                    expected: API_VERSION.to_string(),
+                })?;
+        for conversion in conversions {
+            let spec_conversion = conversion.specs.iter().find(|s| s.kind == kind);
+            if let (Some(spec_conversion), Some(spec)) = (spec_conversion, document.get_mut("spec"))
+            {
+                *spec = (spec_conversion.convert)(spec).map_err(|message| {
+                    GitOpsError::InvalidResource {
+                        path: path.to_path_buf(),
+                        message: format!(
+                            "cannot convert from {} to {}: {}",
+                            conversion.from, conversion.to, message
+                        ),
+                    }
+                })?;
+            }
+            document["apiVersion"] = Value::from(conversion.to);
+        }
+        Ok(true)
+    }
+}
+
+/// A version before `paporg.io/v1` for tests, whose rules named the
+/// output directory `dir`.
+#[cfg(test)]
+pub(crate) mod v1beta1 {
+    use serde::Deserialize;
+
+    use super::{Conversion, SpecConversion};
+    use crate::gitops::resource::{self, MatchCondition, ResourceKind, API_VERSION};
+
+    pub const VERSION: &str = "paporg.io/v1beta1";
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    pub struct RuleSpec {
+        #[serde(default)]
+        pub category: String,
+        #[serde(rename = "match", default)]
+        pub match_condition: MatchCondition,
+        #[serde(default)]
+        pub output: OutputSettings,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub struct OutputSettings {
+        #[serde(default)]
+        pub dir: String,
+        #[serde(default)]
+        pub filename: String,
+    }
+
+    impl From<RuleSpec> for resource::RuleSpec {
+        fn from(spec: RuleSpec) -> Self {
+            Self {
+                priority: 0,
+                extends: None,
+                category: spec.category,
+                exclusive: true,
+                match_condition: spec.match_condition,
+                output: resource::OutputSettings {
+                    directory: spec.output.dir,
+                    filename: spec.output.filename,
+                },
+                symlinks: Vec::new(),
+            }
+        }
+    }
+
+    pub const CONVERSION: Conversion = Conversion {
+        from: VERSION,
+        to: API_VERSION,
+        specs: &[SpecConversion::new::<RuleSpec, resource::RuleSpec>(
+            ResourceKind::Rule,
+        )],
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_registry() -> ConversionRegistry {
+        ConversionRegistry::empty().register(v1beta1::CONVERSION)
+    }
+
+    fn rule(version: &str, output: &str) -> Value {
+        serde_yaml::from_str(&format!(
+            "apiVersion: {}\nkind: Rule\nmetadata:\n  name: a\nspec:\n  category: A\n  \
+             output:\n    {}\n",
+            version, output
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let registry = test_registry();
+        let path = Path::new("rules/a.yaml");
+
+        let mut document = rule(v1beta1::VERSION, "dir: invoices");
+        assert!(registry
+            .upgrade(ResourceKind::Rule, &mut document, path)
+            .unwrap());
+        let mut expected = rule(API_VERSION, "directory: invoices");
+        expected["spec"]["priority"] = Value::from(0);
+        assert_eq!(document, expected);
+
+        // Documents of the current version are left alone
+        assert!(!registry
+            .upgrade(ResourceKind::Rule, &mut document, path)
+            .unwrap());
+
+        // Specs that are not of the old type cannot be converted
+        let mut document = rule(v1beta1::VERSION, "dir: [invoices]");
+        let error = registry
+            .upgrade(ResourceKind::Rule, &mut document, path)
+            .unwrap_err();
+        assert!(error.to_string().contains("cannot convert from"));
+
+        // Kinds without a conversion of their own only change version
+        let mut document = rule(v1beta1::VERSION, "dir: [invoices]");
+        document["kind"] = Value::from("RuleTemplate");
+        assert!(registry
+            .upgrade(ResourceKind::RuleTemplate, &mut document, path)
+            .unwrap());
+        assert_eq!(document["apiVersion"], Value::from(API_VERSION));
+        assert_eq!(
+            document["spec"]["output"]["dir"][0],
+            Value::from("invoices")
+        );
+
+        let mut document = rule("paporg.io/v0", "dir: invoices");
+        assert!(matches!(
+            registry.upgrade(ResourceKind::Rule, &mut document, path),
+            Err(GitOpsError::InvalidApiVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_supported_versions() {
+        assert_eq!(
+            ConversionRegistry::default().supported_versions(),
+            [API_VERSION]
+        );
+        assert_eq!(
+            test_registry().supported_versions(),
+            [API_VERSION, v1beta1::VERSION]
+        );
+
+        // Conversions in a cycle never reach the current version
+        let registry = ConversionRegistry::empty()
+            .register(Conversion {
+                from: "a",
+                to: "b",
+                specs: &[],
+            })
+            .register(Conversion {
+                from: "b",
+                to: "a",
+                specs: &[],
+            });
+        assert_eq!(registry.supported_versions(), [API_VERSION]);
+    }
+}
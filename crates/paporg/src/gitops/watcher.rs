@@ -158,9 +158,9 @@ impl ConfigWatcher {
     fn process_event(&self, config_dir: &Path, event: DebouncedEvent) -> Option<ConfigChangeEvent> {
         let path = &event.path;
 
-        // Only process YAML files
+        // Only process YAML files and the CSV files of lookup tables
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if ext != "yaml" && ext != "yml" {
+        if ext != "yaml" && ext != "yml" && ext != "csv" {
             // Could be a directory change
             if !path.is_dir() {
                 return None;
@@ -18,7 +18,10 @@ pub mod worker;
 pub use ai::{ModelManager, RuleSuggester, RuleSuggestion};
 pub use broadcast::{GitProgressBroadcaster, JobProgressBroadcaster, JobStore, LogBroadcaster};
 pub use config::{load_config, Config, DocumentFormat, VariableEngine};
-pub use error::{ConfigError, PaporgError, ProcessError, Result, StorageError, WorkerError};
+pub use error::{
+    ArchiveError, ConfigError, LimitViolation, PaporgError, ProcessError, Result, StorageError,
+    WorkerError,
+};
 pub use gitops::{ConfigLoader, GitOpsError, LoadedConfig};
 pub use pipeline::{Pipeline, PipelineConfig, PipelineContext};
 pub use secrets::{resolve_secret, resolve_secret_optional, SecretError, TokenEncryptor};
@@ -1,7 +1,13 @@
 use std::path::PathBuf;
 
-use crate::config::schema::{DefaultsConfig, ExtractedVariable, Rule};
+use crate::categorizer::Classifier;
+use crate::config::schema::{
+    CategorizationConfig, ComputedVariable, Correspondent, DefaultsConfig, ExtractedVariable,
+    LookupTable, Rule,
+};
 use crate::config::Config;
+use crate::processor::ProcessingLimits;
+use crate::worker::archive::ArchiveLimits;
 
 pub struct PipelineConfig {
     pub input_directory: PathBuf,
@@ -9,9 +15,21 @@ pub struct PipelineConfig {
     pub ocr_enabled: bool,
     pub ocr_languages: Vec<String>,
     pub ocr_dpi: u32,
+    pub limits: ProcessingLimits,
+    /// Limits for unpacking archive sources.
+    pub archive_limits: ArchiveLimits,
     pub rules: Vec<Rule>,
     pub defaults: DefaultsConfig,
+    pub categorization: CategorizationConfig,
     pub extracted_variables: Vec<ExtractedVariable>,
+    /// Variables built from other variables after extraction.
+    pub computed_variables: Vec<ComputedVariable>,
+    /// Tables for variables with a `lookup` transform.
+    pub lookup_tables: Vec<LookupTable>,
+    /// Known senders, detected in every document.
+    pub correspondents: Vec<Correspondent>,
+    /// Consulted when no rule matches; shared with the job store that trains it.
+    pub classifier: Option<Classifier>,
 }
 
 impl PipelineConfig {
@@ -22,9 +40,22 @@ impl PipelineConfig {
             ocr_enabled: config.ocr.enabled,
             ocr_languages: config.ocr.languages.clone(),
             ocr_dpi: config.ocr.dpi,
+            limits: ProcessingLimits::from_config(&config.limits),
+            archive_limits: ArchiveLimits::from_config(&config.limits),
             rules: config.rules.clone(),
             defaults: config.defaults.clone(),
+            categorization: config.categorization.clone(),
             extracted_variables: config.variables.extracted.clone(),
+            computed_variables: config.variables.computed.clone(),
+            lookup_tables: config.lookup_tables.clone(),
+            correspondents: config.correspondents.clone(),
+            classifier: None,
         }
     }
+
+    /// Sets the classifier used when no rule matches.
+    pub fn with_classifier(mut self, classifier: Option<Classifier>) -> Self {
+        self.classifier = classifier;
+        self
+    }
 }
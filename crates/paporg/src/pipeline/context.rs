@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use crate::categorizer::CategorizationResult;
+use crate::categorizer::{CategorizationResult, DetectedCorrespondent};
 use crate::processor::ProcessedContent;
 use crate::worker::job::Job;
 
@@ -14,22 +14,20 @@ pub struct PipelineContext {
     // Step 1 result — guaranteed Some after step_process_document
     pub processed: Option<ProcessedContent>,
 
-    // Step 2 result — guaranteed Some after step_prepare_text
-    pub matching_text: Option<String>,
-
-    // Step 3 result
+    // Step 2 result
     pub extracted_variables: HashMap<String, String>,
+    pub correspondent: Option<DetectedCorrespondent>,
 
-    // Step 4 result — guaranteed Some after step_categorize
+    // Step 3 result — guaranteed Some after step_categorize
     pub categorization: Option<CategorizationResult>,
 
-    // Step 5+6 result — the final stored path (FileStorage handles conflict resolution)
+    // Step 4+5 result — the final stored path (FileStorage handles conflict resolution)
     pub output_path: Option<PathBuf>,
 
     // Step 6 results
     pub symlink_paths: Vec<PathBuf>,
 
-    // Step 7 result
+    // Step 7 result — `None` for members of an archive
     pub archive_path: Option<PathBuf>,
 
     // Non-fatal warnings
@@ -41,8 +39,8 @@ impl PipelineContext {
         Self {
             job,
             processed: None,
-            matching_text: None,
             extracted_variables: HashMap::new(),
+            correspondent: None,
             categorization: None,
             output_path: None,
             symlink_paths: Vec::new(),
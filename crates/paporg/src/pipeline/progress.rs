@@ -3,6 +3,7 @@ use std::sync::Arc;
 use tokio::sync::broadcast;
 
 use crate::broadcast::job_progress::{JobPhase, JobProgressEvent, JobProgressTracker};
+use crate::categorizer::{CategorizationExplanation, CategoryAlternative};
 
 /// Events emitted by the pipeline during processing.
 /// OCR text is omitted from broadcast events (can be large).
@@ -13,9 +14,14 @@ pub enum ProgressEvent {
     },
     Completed {
         output_path: String,
-        archive_path: String,
+        /// `None` for members of an archive, whose container is archived instead.
+        archive_path: Option<String>,
         symlinks: Vec<String>,
         category: String,
+        confidence: Option<f64>,
+        alternatives: Vec<CategoryAlternative>,
+        tags: Vec<String>,
+        explanation: Option<Box<CategorizationExplanation>>,
     },
     Failed {
         error: String,
@@ -64,6 +70,12 @@ impl BroadcastProgress {
         }
     }
 
+    /// Links events to the archive job this document was extracted from.
+    pub fn with_parent_job(mut self, parent_job_id: Option<&str>) -> Self {
+        self.tracker = self.tracker.with_parent_job(parent_job_id);
+        self
+    }
+
     /// Store OCR text separately (not sent via broadcast, can be large).
     pub fn set_ocr_text(&self, text: String) {
         if let Ok(mut guard) = self.ocr_text.lock() {
@@ -88,6 +100,10 @@ impl ProgressReporter for BroadcastProgress {
                 archive_path,
                 symlinks,
                 category,
+                confidence,
+                alternatives,
+                tags,
+                explanation,
             } => {
                 let ocr_text = self
                     .ocr_text
@@ -97,9 +113,13 @@ impl ProgressReporter for BroadcastProgress {
                     .unwrap_or_default();
                 self.tracker.completed(
                     &output_path,
-                    &archive_path,
+                    archive_path.as_deref(),
                     &symlinks,
                     &category,
+                    confidence,
+                    &alternatives,
+                    &tags,
+                    explanation.as_deref(),
                     &ocr_text,
                 );
             }
@@ -1,14 +1,15 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use tracing::{debug, info_span, warn};
 
 use crate::broadcast::job_progress::JobPhase;
-use crate::categorizer::Categorizer;
+use crate::categorizer::{Categorizer, CorrespondentDetector, DocumentView};
 use crate::config::VariableEngine;
 use crate::processor::ProcessorRegistry;
 use crate::sanitize;
 use crate::storage::{FileStorage, SymlinkManager};
+use crate::worker::archive::ArchiveLimits;
 use crate::worker::job::JobResult;
 
 use super::config::PipelineConfig;
@@ -20,6 +21,7 @@ pub struct Pipeline {
     config: Arc<PipelineConfig>,
     processor: ProcessorRegistry,
     categorizer: Categorizer,
+    correspondents: CorrespondentDetector,
     variable_engine: VariableEngine,
     storage: FileStorage,
     symlink_manager: SymlinkManager,
@@ -28,10 +30,21 @@ pub struct Pipeline {
 impl Pipeline {
     /// Production constructor — builds all sub-components from config.
     pub fn from_config(config: Arc<PipelineConfig>) -> Self {
-        let processor =
-            ProcessorRegistry::new(config.ocr_enabled, &config.ocr_languages, config.ocr_dpi);
-        let categorizer = Categorizer::new(config.rules.clone(), config.defaults.clone());
-        let variable_engine = VariableEngine::new(&config.extracted_variables);
+        let processor = ProcessorRegistry::with_limits(
+            config.ocr_enabled,
+            &config.ocr_languages,
+            config.ocr_dpi,
+            config.limits,
+        );
+        let mut categorizer = Categorizer::new(config.rules.clone(), config.defaults.clone())
+            .with_config(config.categorization.clone());
+        if let Some(classifier) = &config.classifier {
+            categorizer = categorizer.with_classifier(classifier.clone());
+        }
+        let correspondents = CorrespondentDetector::new(&config.correspondents);
+        let variable_engine = VariableEngine::new(&config.extracted_variables)
+            .with_computed_variables(&config.computed_variables)
+            .with_lookup_tables(&config.lookup_tables);
         let storage = FileStorage::new(&config.output_directory);
         let symlink_manager = SymlinkManager::new(&config.output_directory);
 
@@ -39,6 +52,7 @@ impl Pipeline {
             config,
             processor,
             categorizer,
+            correspondents,
             variable_engine,
             storage,
             symlink_manager,
@@ -55,10 +69,12 @@ impl Pipeline {
         storage: FileStorage,
         symlink_manager: SymlinkManager,
     ) -> Self {
+        let correspondents = CorrespondentDetector::new(&config.correspondents);
         Self {
             config,
             processor,
             categorizer,
+            correspondents,
             variable_engine,
             storage,
             symlink_manager,
@@ -96,13 +112,7 @@ impl Pipeline {
             }
         }
 
-        // Step 2: Prepare matching text
-        {
-            let _step = info_span!("prepare_text").entered();
-            self.step_prepare_text(&mut ctx);
-        }
-
-        // Step 3: Extract variables
+        // Step 2: Extract variables
         {
             let _step = info_span!("extract_variables").entered();
             progress.report(ProgressEvent::Phase {
@@ -110,9 +120,10 @@ impl Pipeline {
                 message: "Extracting variables from document...".to_string(),
             });
             self.step_extract_variables(&mut ctx);
+            self.step_detect_correspondent(&mut ctx);
         }
 
-        // Step 4: Categorize
+        // Step 3: Categorize
         {
             let _step = info_span!("categorize").entered();
             progress.report(ProgressEvent::Phase {
@@ -122,7 +133,7 @@ impl Pipeline {
             self.step_categorize(&mut ctx);
         }
 
-        // Step 5+6: Resolve output path and store
+        // Step 4+5: Resolve output path and store
         {
             let _step = info_span!("resolve_and_store").entered();
             progress.report(ProgressEvent::Phase {
@@ -138,7 +149,7 @@ impl Pipeline {
             }
         }
 
-        // Step 7: Create symlinks
+        // Step 6: Create symlinks
         {
             let _step = info_span!("create_symlinks").entered();
             progress.report(ProgressEvent::Phase {
@@ -148,8 +159,9 @@ impl Pipeline {
             self.step_create_symlinks(&mut ctx);
         }
 
-        // Step 8: Archive source
-        {
+        // Step 7: Archive source. Members of an archive live in a temporary
+        // extraction directory; the container is archived in their place.
+        if ctx.job.parent_job_id.is_none() {
             let _step = info_span!("archive_source").entered();
             progress.report(ProgressEvent::Phase {
                 phase: JobPhase::Archiving,
@@ -165,16 +177,18 @@ impl Pipeline {
         }
 
         // Build success result
-        let category = ctx
-            .categorization
-            .as_ref()
+        let categorization = ctx.categorization.as_ref();
+        let category = categorization
             .map(|c| c.category.clone())
             .unwrap_or_else(|| "unsorted".to_string());
-        let output_path = ctx.output_path.clone().expect("output_path set in step 5");
-        let archive_path = ctx
-            .archive_path
-            .clone()
-            .expect("archive_path set in step 7");
+        let confidence = categorization.and_then(|c| c.confidence);
+        let alternatives = categorization
+            .map(|c| c.alternatives.clone())
+            .unwrap_or_default();
+        let tags = categorization.map(|c| c.tags.clone()).unwrap_or_default();
+        let explanation = categorization.map(|c| Box::new(c.explanation.clone()));
+        let output_path = ctx.output_path.clone().expect("output_path set in step 4");
+        let archive_path = ctx.archive_path.clone();
         let symlink_paths = ctx.symlink_paths.clone();
 
         let symlink_strings: Vec<String> = symlink_paths
@@ -189,9 +203,13 @@ impl Pipeline {
 
         progress.report(ProgressEvent::Completed {
             output_path: output_path.display().to_string(),
-            archive_path: archive_path.display().to_string(),
+            archive_path: archive_path.as_ref().map(|p| p.display().to_string()),
             symlinks: symlink_strings,
             category: category.clone(),
+            confidence,
+            alternatives,
+            tags,
+            explanation,
         });
 
         let result =
@@ -205,36 +223,80 @@ impl Pipeline {
         Ok(())
     }
 
-    fn step_prepare_text(&self, ctx: &mut PipelineContext) {
-        let processed = ctx.processed.as_ref().expect("step 1 completed");
-        let mut text = processed.text.clone();
+    fn step_extract_variables(&self, ctx: &mut PipelineContext) {
+        ctx.extracted_variables = self
+            .variable_engine
+            .extract_document_variables(&Self::document_view(ctx));
+    }
 
-        if let Some(ref email_meta) = ctx.job.email_metadata {
-            if email_meta.has_content() {
-                let header_block = email_meta.to_header_block();
-                text = format!("{}{}", header_block, text);
-            }
-        }
+    /// Detects the correspondent and exposes it as `$correspondent`.
+    fn step_detect_correspondent(&self, ctx: &mut PipelineContext) {
+        let correspondent = self.correspondents.detect(&Self::document_view(ctx));
+        let name = correspondent
+            .as_ref()
+            .map_or_else(|| "unknown".to_string(), |c| c.name.clone());
+        ctx.extracted_variables
+            .insert("correspondent".to_string(), name);
+        ctx.correspondent = correspondent;
+    }
 
-        ctx.matching_text = Some(text);
+    fn step_categorize(&self, ctx: &mut PipelineContext) {
+        let categorization = self
+            .categorizer
+            .categorize_document(&Self::document_view(ctx));
+
+        // Expose tags from non-exclusive rules to output and symlink templates
+        let tags = if categorization.tags.is_empty() {
+            "untagged".to_string()
+        } else {
+            categorization.tags.join("_")
+        };
+        ctx.extracted_variables.insert("tags".to_string(), tags);
+        ctx.categorization = Some(categorization);
     }
 
-    fn step_extract_variables(&self, ctx: &mut PipelineContext) {
-        let text = ctx.matching_text.as_ref().expect("step 2 completed");
-        ctx.extracted_variables = self.variable_engine.extract_variables(text);
+    /// The parts of the document that variables, correspondent detection
+    /// and match conditions look at.
+    fn document_view(ctx: &PipelineContext) -> DocumentView<'_> {
+        let processed = ctx.processed.as_ref().expect("step 1 completed");
+        let job = &ctx.job;
+        let email = job.email_metadata.as_ref();
+
+        DocumentView {
+            text: &processed.text,
+            pages: &processed.pages,
+            filename: Some(&processed.metadata.original_filename),
+            source: job.source_name.as_deref(),
+            mime_type: job.mime_type.as_deref(),
+            email_from: email.and_then(|e| e.from.as_deref()),
+            email_subject: email.and_then(|e| e.subject.as_deref()),
+            email_to: email.and_then(|e| e.to.as_deref()),
+            source_path: job.relative_path.as_deref(),
+            pdf_metadata: processed.metadata.pdf_metadata.as_deref(),
+            variables: Some(&ctx.extracted_variables),
+            correspondent: ctx.correspondent.as_ref(),
+        }
     }
 
-    fn step_categorize(&self, ctx: &mut PipelineContext) {
-        let text = ctx.matching_text.as_ref().expect("step 2 completed");
-        ctx.categorization = Some(self.categorizer.categorize(text));
+    /// Categorizes a document and resolves its output path relative to the
+    /// output directory, without storing anything. A document whose text is
+    /// already in `ctx.processed` is not processed again.
+    pub fn dry_run(&self, ctx: &mut PipelineContext) -> Result<PathBuf, PipelineError> {
+        if ctx.processed.is_none() {
+            self.step_process_document(ctx)?;
+        }
+        self.step_extract_variables(ctx);
+        self.step_detect_correspondent(ctx);
+        self.step_categorize(ctx);
+
+        let (directory, filename) = self.resolve_output(ctx)?;
+        Ok(Path::new(&directory).join(format!("{}.pdf", filename)))
     }
 
-    fn step_resolve_and_store(
-        &self,
-        ctx: &mut PipelineContext,
-        progress: &dyn ProgressReporter,
-    ) -> Result<(), PipelineError> {
-        let categorization = ctx.categorization.as_ref().expect("step 4 completed");
+    /// Substitutes variables into the chosen output templates, rejecting
+    /// paths that would leave the output directory.
+    fn resolve_output(&self, ctx: &PipelineContext) -> Result<(String, String), PipelineError> {
+        let categorization = ctx.categorization.as_ref().expect("step 3 completed");
         let processed = ctx.processed.as_ref().expect("step 1 completed");
 
         let dir_template = &categorization.output.directory;
@@ -297,6 +359,18 @@ impl Pipeline {
             )));
         }
 
+        Ok((output_directory, output_filename))
+    }
+
+    fn step_resolve_and_store(
+        &self,
+        ctx: &mut PipelineContext,
+        progress: &dyn ProgressReporter,
+    ) -> Result<(), PipelineError> {
+        let (output_directory, output_filename) = self.resolve_output(ctx)?;
+        let categorization = ctx.categorization.as_ref().expect("step 3 completed");
+        let processed = ctx.processed.as_ref().expect("step 1 completed");
+
         // Validate: final path stays within output_directory via canonicalization
         let candidate = self.config.output_directory.join(&output_directory);
         if !candidate.exists() {
@@ -339,9 +413,9 @@ impl Pipeline {
     }
 
     fn step_create_symlinks(&self, ctx: &mut PipelineContext) {
-        let categorization = ctx.categorization.as_ref().expect("step 4 completed");
+        let categorization = ctx.categorization.as_ref().expect("step 3 completed");
         let processed = ctx.processed.as_ref().expect("step 1 completed");
-        let output_path = ctx.output_path.as_ref().expect("step 5 completed");
+        let output_path = ctx.output_path.as_ref().expect("step 4 completed");
 
         for symlink_config in &categorization.symlinks {
             let symlink_dir = self.variable_engine.substitute(
@@ -370,25 +444,37 @@ impl Pipeline {
     }
 
     fn step_archive_source(&self, ctx: &mut PipelineContext) -> Result<(), PipelineError> {
-        let archive_path = self
-            .storage
-            .archive_source(&ctx.job.source_path, &self.config.input_directory)
-            .map_err(PipelineError::Archive)?;
+        let archive_path = self.archive_source(&ctx.job.source_path)?;
 
         ctx.archive_path = Some(archive_path);
         Ok(())
     }
+
+    /// Limits for unpacking archive sources.
+    pub fn archive_limits(&self) -> &ArchiveLimits {
+        &self.config.archive_limits
+    }
+
+    /// Moves a source file into the input directory's archive folder.
+    pub fn archive_source(&self, source_path: &Path) -> Result<PathBuf, PipelineError> {
+        self.storage
+            .archive_source(source_path, &self.config.input_directory)
+            .map_err(PipelineError::Archive)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::schema::{
-        DefaultsConfig, ExtractedVariable, MatchCondition, OcrConfig, OutputConfig, Rule,
-        SimpleMatch, SymlinkConfig, VariablesConfig,
+        CategorizationConfig, CompareValue, Correspondent, DefaultsConfig, ExtractedVariable,
+        MatchCondition, MatchField, OcrConfig, OutputConfig, Rule, SimpleMatch, SymlinkConfig,
+        VariableMatch, VariablesConfig,
     };
     use crate::config::Config;
     use crate::pipeline::progress::NoopProgress;
+    use crate::processor::ProcessingLimits;
+    use crate::worker::archive::ArchiveLimits;
     use crate::worker::job::{EmailMetadata, Job};
     use std::io::Write;
     use tempfile::TempDir;
@@ -400,9 +486,16 @@ mod tests {
             ocr_enabled: false,
             ocr_languages: vec![],
             ocr_dpi: 300,
+            limits: ProcessingLimits::default(),
+            archive_limits: ArchiveLimits::default(),
             rules: vec![],
             defaults: DefaultsConfig::default(),
+            categorization: CategorizationConfig::default(),
             extracted_variables: vec![],
+            computed_variables: vec![],
+            lookup_tables: vec![],
+            correspondents: vec![],
+            classifier: None,
         }
     }
 
@@ -417,9 +510,16 @@ mod tests {
             ocr_enabled: false,
             ocr_languages: vec![],
             ocr_dpi: 300,
+            limits: ProcessingLimits::default(),
+            archive_limits: ArchiveLimits::default(),
             rules,
             defaults: DefaultsConfig::default(),
+            categorization: CategorizationConfig::default(),
             extracted_variables: vec![],
+            computed_variables: vec![],
+            lookup_tables: vec![],
+            correspondents: vec![],
+            classifier: None,
         }
     }
 
@@ -459,6 +559,24 @@ mod tests {
         assert!(ctx.warnings.is_empty());
     }
 
+    #[test]
+    fn test_archive_member_source_is_not_archived() {
+        let (_tmp, input, output) = setup_dirs();
+        let parent = Job::new(input.join("bundle.zip"));
+        let member = create_text_file(&input, "member.txt", "Hello, World!");
+
+        let config = Arc::new(test_config(&input, &output));
+        let pipeline = Pipeline::from_config(config);
+        let ctx = PipelineContext::new(Job::child_of(&parent, member.clone()));
+
+        let (result, _) = pipeline.run(ctx, &NoopProgress);
+
+        assert!(result.success, "Pipeline failed: {:?}", result.error);
+        assert!(result.archive_path.is_none());
+        assert!(member.exists());
+        assert!(!input.join("archive").exists());
+    }
+
     #[test]
     fn test_full_pipeline_categorizes_into_rule_matched_directory() {
         let (_tmp, input, output) = setup_dirs();
@@ -468,12 +586,10 @@ mod tests {
             id: "inv".to_string(),
             name: "Invoice".to_string(),
             priority: 10,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("invoice".to_string()),
-                contains_any: None,
-                contains_all: None,
-                pattern: None,
-                case_sensitive: None,
+                ..Default::default()
             }),
             category: "invoices".to_string(),
             output: OutputConfig {
@@ -495,6 +611,84 @@ mod tests {
         assert!(out.to_string_lossy().contains("invoices"));
     }
 
+    #[test]
+    fn test_tags_variable_from_non_exclusive_rules() {
+        let (_tmp, input, output) = setup_dirs();
+        let file_path = create_text_file(&input, "bill.txt", "Invoice for tax year 2025");
+
+        let rule = |id: &str, contains: &str, exclusive: bool, directory: &str| Rule {
+            id: id.to_string(),
+            name: id.to_string(),
+            priority: 0,
+            exclusive,
+            match_condition: MatchCondition::Simple(SimpleMatch {
+                contains: Some(contains.to_string()),
+                ..Default::default()
+            }),
+            category: id.to_string(),
+            output: OutputConfig {
+                directory: directory.to_string(),
+                filename: "$original".to_string(),
+            },
+            symlinks: vec![],
+        };
+        let rules = vec![
+            rule("invoices", "invoice", true, "invoices-$tags"),
+            rule("tax", "tax year", false, ""),
+            rule("medical", "doctor", false, ""),
+        ];
+
+        let config = Arc::new(test_config_with_rules(&input, &output, rules));
+        let pipeline = Pipeline::from_config(config);
+        let ctx = PipelineContext::new(Job::new(file_path));
+
+        let (result, ctx) = pipeline.run(ctx, &NoopProgress);
+
+        assert!(result.success, "Pipeline failed: {:?}", result.error);
+        assert_eq!(result.category, "invoices");
+        assert_eq!(ctx.categorization.unwrap().tags, vec!["tax"]);
+        let out = result.output_path.unwrap();
+        assert!(out.starts_with(output.join("invoices-tax")), "{:?}", out);
+    }
+
+    #[test]
+    fn test_correspondent_variable_and_default_category() {
+        let (_tmp, input, output) = setup_dirs();
+        let file_path = create_text_file(
+            &input,
+            "statement.txt",
+            "Leistungsabrechnung\nIBAN CH93 0076 2011 6238 5295 7",
+        );
+
+        let mut config = test_config(&input, &output);
+        config.defaults.output.directory = "$correspondent".to_string();
+        config.correspondents = vec![Correspondent {
+            id: "helsana".to_string(),
+            name: "Helsana".to_string(),
+            aliases: vec![],
+            ibans: vec!["CH9300762011623852957".to_string()],
+            vat_numbers: vec![],
+            email_domains: vec![],
+            default_category: Some("insurance".to_string()),
+        }];
+        let pipeline = Pipeline::from_config(Arc::new(config));
+        let ctx = PipelineContext::new(Job::new(file_path));
+
+        let (result, ctx) = pipeline.run(ctx, &NoopProgress);
+
+        assert!(result.success, "Pipeline failed: {:?}", result.error);
+        assert_eq!(result.category, "insurance");
+        assert_eq!(ctx.correspondent.unwrap().id, "helsana");
+        assert_eq!(
+            ctx.extracted_variables
+                .get("correspondent")
+                .map(String::as_str),
+            Some("Helsana")
+        );
+        let out = result.output_path.unwrap();
+        assert!(out.starts_with(output.join("Helsana")), "{:?}", out);
+    }
+
     #[test]
     fn test_unsorted_fallback_when_no_rules_match() {
         let (_tmp, input, output) = setup_dirs();
@@ -504,12 +698,10 @@ mod tests {
             id: "inv".to_string(),
             name: "Invoice".to_string(),
             priority: 10,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("invoice".to_string()),
-                contains_any: None,
-                contains_all: None,
-                pattern: None,
-                case_sensitive: None,
+                ..Default::default()
             }),
             category: "invoices".to_string(),
             output: OutputConfig {
@@ -566,48 +758,42 @@ mod tests {
     }
 
     #[test]
-    fn test_step_prepare_text_without_email_metadata() {
-        let (_tmp, input, output) = setup_dirs();
-        let file_path = create_text_file(&input, "doc.txt", "Plain text");
-
-        let config = Arc::new(test_config(&input, &output));
-        let pipeline = Pipeline::from_config(config);
-        let mut ctx = PipelineContext::new(Job::new(file_path));
-        pipeline.step_process_document(&mut ctx).unwrap();
-
-        pipeline.step_prepare_text(&mut ctx);
-
-        let text = ctx.matching_text.as_ref().unwrap();
-        assert!(text.contains("Plain text"));
-        assert!(!text.contains("EMAIL METADATA"));
-    }
-
-    #[test]
-    fn test_step_prepare_text_with_email_metadata() {
+    fn test_step_categorize_ignores_email_headers_without_field() {
         let (_tmp, input, output) = setup_dirs();
         let file_path = create_text_file(&input, "doc.txt", "Attachment content");
 
-        let config = Arc::new(test_config(&input, &output));
+        let rules = vec![Rule {
+            id: "inv".to_string(),
+            name: "Invoice".to_string(),
+            priority: 10,
+            exclusive: true,
+            match_condition: MatchCondition::Simple(SimpleMatch {
+                contains: Some("Invoice #42".to_string()),
+                ..Default::default()
+            }),
+            category: "invoices".to_string(),
+            output: OutputConfig {
+                directory: "invoices".to_string(),
+                filename: "$original".to_string(),
+            },
+            symlinks: vec![],
+        }];
+
+        let config = Arc::new(test_config_with_rules(&input, &output, rules));
         let pipeline = Pipeline::from_config(config);
 
         let mut job = Job::new(file_path);
         job.email_metadata = Some(EmailMetadata {
             subject: Some("Invoice #42".to_string()),
             from: Some("sender@test.com".to_string()),
-            to: None,
-            date: None,
-            message_id: None,
+            ..Default::default()
         });
-
         let mut ctx = PipelineContext::new(job);
         pipeline.step_process_document(&mut ctx).unwrap();
-        pipeline.step_prepare_text(&mut ctx);
+        pipeline.step_categorize(&mut ctx);
 
-        let text = ctx.matching_text.as_ref().unwrap();
-        assert!(text.contains("EMAIL METADATA"));
-        assert!(text.contains("From: sender@test.com"));
-        assert!(text.contains("Subject: Invoice #42"));
-        assert!(text.contains("Attachment content"));
+        // The subject is only searched by conditions with `field: email.subject`
+        assert_eq!(ctx.categorization.as_ref().unwrap().category, "unsorted");
     }
 
     #[test]
@@ -621,6 +807,9 @@ mod tests {
             pattern: r"from (?P<vendor>\w+)".to_string(),
             transform: None,
             default: None,
+            pages: None,
+            region: None,
+            from: None,
         }];
 
         let config = Arc::new(config);
@@ -628,7 +817,6 @@ mod tests {
         let mut ctx = PipelineContext::new(Job::new(file_path));
 
         pipeline.step_process_document(&mut ctx).unwrap();
-        pipeline.step_prepare_text(&mut ctx);
         pipeline.step_extract_variables(&mut ctx);
 
         assert_eq!(
@@ -648,6 +836,9 @@ mod tests {
             pattern: r"WONT_MATCH_(?P<vendor>\w+)".to_string(),
             transform: None,
             default: None,
+            pages: None,
+            region: None,
+            from: None,
         }];
 
         let config = Arc::new(config);
@@ -655,7 +846,6 @@ mod tests {
         let mut ctx = PipelineContext::new(Job::new(file_path));
 
         pipeline.step_process_document(&mut ctx).unwrap();
-        pipeline.step_prepare_text(&mut ctx);
         pipeline.step_extract_variables(&mut ctx);
 
         assert!(ctx.extracted_variables.is_empty());
@@ -671,12 +861,10 @@ mod tests {
                 id: "low".to_string(),
                 name: "Low".to_string(),
                 priority: 10,
+                exclusive: true,
                 match_condition: MatchCondition::Simple(SimpleMatch {
                     contains: Some("invoice".to_string()),
-                    contains_any: None,
-                    contains_all: None,
-                    pattern: None,
-                    case_sensitive: None,
+                    ..Default::default()
                 }),
                 category: "low-priority".to_string(),
                 output: OutputConfig {
@@ -689,12 +877,10 @@ mod tests {
                 id: "high".to_string(),
                 name: "High".to_string(),
                 priority: 100,
+                exclusive: true,
                 match_condition: MatchCondition::Simple(SimpleMatch {
                     contains: Some("invoice".to_string()),
-                    contains_any: None,
-                    contains_all: None,
-                    pattern: None,
-                    case_sensitive: None,
+                    ..Default::default()
                 }),
                 category: "high-priority".to_string(),
                 output: OutputConfig {
@@ -710,7 +896,6 @@ mod tests {
         let mut ctx = PipelineContext::new(Job::new(file_path));
 
         pipeline.step_process_document(&mut ctx).unwrap();
-        pipeline.step_prepare_text(&mut ctx);
         pipeline.step_categorize(&mut ctx);
 
         let cat = ctx.categorization.as_ref().unwrap();
@@ -727,12 +912,10 @@ mod tests {
             id: "inv".to_string(),
             name: "Inv".to_string(),
             priority: 10,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("SPECIFIC_KEYWORD".to_string()),
-                contains_any: None,
-                contains_all: None,
-                pattern: None,
-                case_sensitive: None,
+                ..Default::default()
             }),
             category: "specific".to_string(),
             output: OutputConfig {
@@ -747,7 +930,6 @@ mod tests {
         let mut ctx = PipelineContext::new(Job::new(file_path));
 
         pipeline.step_process_document(&mut ctx).unwrap();
-        pipeline.step_prepare_text(&mut ctx);
         pipeline.step_categorize(&mut ctx);
 
         let cat = ctx.categorization.as_ref().unwrap();
@@ -755,6 +937,111 @@ mod tests {
         assert_eq!(cat.rule_id, None);
     }
 
+    #[test]
+    fn test_step_categorize_matches_email_field() {
+        let (_tmp, input, output) = setup_dirs();
+        let file_path = create_text_file(&input, "doc.txt", "Questions? billing@foo.com");
+
+        let rules = vec![Rule {
+            id: "foo".to_string(),
+            name: "Foo".to_string(),
+            priority: 10,
+            exclusive: true,
+            match_condition: MatchCondition::Simple(SimpleMatch {
+                contains: Some("billing@foo.com".to_string()),
+                field: Some(MatchField::EmailFrom),
+                ..Default::default()
+            }),
+            category: "foo".to_string(),
+            output: OutputConfig {
+                directory: "foo".to_string(),
+                filename: "$original".to_string(),
+            },
+            symlinks: vec![],
+        }];
+
+        let config = Arc::new(test_config_with_rules(&input, &output, rules));
+        let pipeline = Pipeline::from_config(config);
+
+        for (from, expected) in [("news@bar.com", "unsorted"), ("billing@foo.com", "foo")] {
+            let mut job = Job::new(file_path.clone());
+            job.email_metadata = Some(EmailMetadata {
+                from: Some(from.to_string()),
+                ..Default::default()
+            });
+            let mut ctx = PipelineContext::new(job);
+
+            pipeline.step_process_document(&mut ctx).unwrap();
+            pipeline.step_categorize(&mut ctx);
+
+            assert_eq!(ctx.categorization.as_ref().unwrap().category, expected);
+        }
+    }
+
+    #[test]
+    fn test_step_categorize_compares_extracted_variables() {
+        let (_tmp, input, output) = setup_dirs();
+
+        let rules = vec![Rule {
+            id: "large".to_string(),
+            name: "Large".to_string(),
+            priority: 10,
+            exclusive: true,
+            match_condition: MatchCondition::Simple(SimpleMatch {
+                variable: Some(Box::new(VariableMatch {
+                    name: "amount".to_string(),
+                    eq: None,
+                    gt: Some(CompareValue::Number(1000.0)),
+                    lt: None,
+                    between: None,
+                    exists: None,
+                    one_of: None,
+                })),
+                ..Default::default()
+            }),
+            category: "large-expenses".to_string(),
+            output: OutputConfig {
+                directory: "large".to_string(),
+                filename: "$original".to_string(),
+            },
+            symlinks: vec![],
+        }];
+
+        let mut config = test_config_with_rules(&input, &output, rules);
+        config.extracted_variables = vec![ExtractedVariable {
+            name: "amount".to_string(),
+            pattern: r"Total:? (?P<amount>[\d'.,]+)".to_string(),
+            transform: None,
+            default: None,
+            pages: None,
+            region: None,
+            from: None,
+        }];
+        let pipeline = Pipeline::from_config(Arc::new(config));
+
+        for (text, expected) in [
+            ("Total: 1'250.00", "large-expenses"),
+            ("Total: 99.90", "unsorted"),
+            ("No amount here", "unsorted"),
+        ] {
+            let file_path = create_text_file(&input, "doc.txt", text);
+            let mut ctx = PipelineContext::new(Job::new(file_path));
+
+            pipeline.step_process_document(&mut ctx).unwrap();
+            pipeline.step_extract_variables(&mut ctx);
+            pipeline.step_categorize(&mut ctx);
+
+            let categorization = ctx.categorization.as_ref().unwrap();
+            assert_eq!(categorization.category, expected, "{}", text);
+            let explanation = &categorization.explanation;
+            assert_eq!(explanation.winner.is_some(), expected != "unsorted");
+            if let Some(rule) = explanation.rule("large-expenses") {
+                assert_eq!(rule.condition.kind, "variable");
+                assert_eq!(rule.condition.values, vec!["amount"]);
+            }
+        }
+    }
+
     // ── Path validation ──
 
     #[test]
@@ -766,12 +1053,10 @@ mod tests {
             id: "evil".to_string(),
             name: "Evil".to_string(),
             priority: 100,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("Content".to_string()),
-                contains_any: None,
-                contains_all: None,
-                pattern: None,
-                case_sensitive: None,
+                ..Default::default()
             }),
             category: "evil".to_string(),
             output: OutputConfig {
@@ -800,12 +1085,10 @@ mod tests {
             id: "abs".to_string(),
             name: "Abs".to_string(),
             priority: 100,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("Content".to_string()),
-                contains_any: None,
-                contains_all: None,
-                pattern: None,
-                case_sensitive: None,
+                ..Default::default()
             }),
             category: "abs".to_string(),
             output: OutputConfig {
@@ -834,12 +1117,10 @@ mod tests {
             id: "slash".to_string(),
             name: "Slash".to_string(),
             priority: 100,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("Content".to_string()),
-                contains_any: None,
-                contains_all: None,
-                pattern: None,
-                case_sensitive: None,
+                ..Default::default()
             }),
             category: "slash".to_string(),
             output: OutputConfig {
@@ -869,12 +1150,10 @@ mod tests {
             id: "empty".to_string(),
             name: "Empty".to_string(),
             priority: 100,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("Content".to_string()),
-                contains_any: None,
-                contains_all: None,
-                pattern: None,
-                case_sensitive: None,
+                ..Default::default()
             }),
             category: "empty".to_string(),
             output: OutputConfig {
@@ -926,6 +1205,8 @@ mod tests {
             ocr_enabled: false,
             ocr_languages: vec![],
             ocr_dpi: 300,
+            limits: ProcessingLimits::default(),
+            archive_limits: ArchiveLimits::default(),
             rules: vec![],
             defaults: DefaultsConfig {
                 output: OutputConfig {
@@ -933,7 +1214,12 @@ mod tests {
                     filename: "doc".to_string(),
                 },
             },
+            categorization: CategorizationConfig::default(),
             extracted_variables: vec![],
+            computed_variables: vec![],
+            lookup_tables: vec![],
+            correspondents: vec![],
+            classifier: None,
         });
 
         let pipeline = Pipeline::from_config(config);
@@ -953,12 +1239,10 @@ mod tests {
             id: "inv".to_string(),
             name: "Inv".to_string(),
             priority: 10,
+            exclusive: true,
             match_condition: MatchCondition::Simple(SimpleMatch {
                 contains: Some("invoice".to_string()),
-                contains_any: None,
-                contains_all: None,
-                pattern: None,
-                case_sensitive: None,
+                ..Default::default()
             }),
             category: "invoices".to_string(),
             output: OutputConfig {
@@ -996,6 +1280,8 @@ mod tests {
             ocr_enabled: false,
             ocr_languages: vec![],
             ocr_dpi: 300,
+            limits: ProcessingLimits::default(),
+            archive_limits: ArchiveLimits::default(),
             rules: vec![],
             defaults: DefaultsConfig {
                 output: OutputConfig {
@@ -1003,7 +1289,12 @@ mod tests {
                     filename: "$original".to_string(),
                 },
             },
+            categorization: CategorizationConfig::default(),
             extracted_variables: vec![],
+            computed_variables: vec![],
+            lookup_tables: vec![],
+            correspondents: vec![],
+            classifier: None,
         });
 
         let pipeline = Pipeline::from_config(config);
@@ -1030,6 +1321,8 @@ mod tests {
             ocr_enabled: false,
             ocr_languages: vec![],
             ocr_dpi: 300,
+            limits: ProcessingLimits::default(),
+            archive_limits: ArchiveLimits::default(),
             rules: vec![],
             defaults: DefaultsConfig {
                 output: OutputConfig {
@@ -1037,7 +1330,12 @@ mod tests {
                     filename: "same_name".to_string(),
                 },
             },
+            categorization: CategorizationConfig::default(),
             extracted_variables: vec![],
+            computed_variables: vec![],
+            lookup_tables: vec![],
+            correspondents: vec![],
+            classifier: None,
         });
 
         let pipeline = Pipeline::from_config(config);
@@ -1,14 +1,18 @@
+use std::collections::HashMap;
 use std::io::Read;
 use std::path::Path;
 
-use lopdf::{dictionary, Document, Object, Stream};
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 
 use crate::config::schema::{DocumentFormat, DocumentMetadata};
 use crate::error::ProcessError;
+use crate::processor::render::{self, Block, Paragraph, ParagraphStyle, Picture, Span};
 use crate::processor::{DocumentProcessor, ProcessedContent};
 
+/// EMUs (English Metric Units) per PDF point.
+const EMU_PER_POINT: f32 = 12_700.0;
+
 pub struct DocxProcessor;
 
 impl DocxProcessor {
@@ -34,8 +38,8 @@ impl DocumentProcessor for DocxProcessor {
         let mut archive = zip::ZipArchive::new(file)
             .map_err(|e| ProcessError::DocxProcessing(format!("Failed to open DOCX: {}", e)))?;
 
-        // Extract text from document.xml
-        let text = extract_docx_text(&mut archive)?;
+        let blocks = extract_docx_blocks(&mut archive)?;
+        let text = blocks_to_text(&blocks);
 
         let filename = path
             .file_name()
@@ -45,14 +49,10 @@ impl DocumentProcessor for DocxProcessor {
 
         let metadata = DocumentMetadata::new(filename, DocumentFormat::Docx);
 
-        // Create PDF from extracted text
-        let pdf_bytes = create_docx_pdf(&text)?;
+        // Lay the document structure out as a PDF
+        let pdf_bytes = render::blocks_to_pdf(&blocks)?;
 
-        Ok(ProcessedContent {
-            text,
-            pdf_bytes,
-            metadata,
-        })
+        Ok(ProcessedContent::new(text, pdf_bytes, metadata))
     }
 
     fn supports(&self, format: DocumentFormat) -> bool {
@@ -60,9 +60,19 @@ impl DocumentProcessor for DocxProcessor {
     }
 }
 
-fn extract_docx_text<R: Read + std::io::Seek>(
+fn read_entry<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Option<Vec<u8>> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+fn extract_docx_blocks<R: Read + std::io::Seek>(
     archive: &mut zip::ZipArchive<R>,
-) -> Result<String, ProcessError> {
+) -> Result<Vec<Block>, ProcessError> {
     let mut document_xml = archive
         .by_name("word/document.xml")
         .map_err(|e| ProcessError::DocxProcessing(format!("Failed to find document.xml: {}", e)))?;
@@ -71,46 +81,328 @@ fn extract_docx_text<R: Read + std::io::Seek>(
     document_xml
         .read_to_string(&mut xml_content)
         .map_err(|e| ProcessError::DocxProcessing(format!("Failed to read document.xml: {}", e)))?;
+    drop(document_xml);
+
+    // Styles and relationships are optional; without them headings fall back
+    // to their style ids and images are skipped.
+    let headings = read_entry(archive, "word/styles.xml")
+        .map(|xml| parse_heading_styles(&String::from_utf8_lossy(&xml)))
+        .unwrap_or_default();
+    let relationships = read_entry(archive, "word/_rels/document.xml.rels")
+        .map(|xml| parse_relationships(&String::from_utf8_lossy(&xml)))
+        .unwrap_or_default();
+
+    parse_docx_xml(&xml_content, &headings, |id| {
+        let target = relationships.get(id)?;
+        read_entry(archive, target)
+    })
+}
+
+/// Maps paragraph style ids to heading levels using `word/styles.xml`.
+fn parse_heading_styles(xml: &str) -> HashMap<String, u8> {
+    let mut reader = Reader::from_str(xml);
+    let mut headings = HashMap::new();
+    let mut style_id: Option<String> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) if e.local_name().as_ref() == b"style" => {
+                style_id = attribute(e, b"styleId");
+            }
+            Ok(Event::Empty(ref e)) => {
+                let Some(id) = &style_id else { continue };
+                let level = match e.local_name().as_ref() {
+                    b"name" => attribute(e, b"val").and_then(|name| heading_level(&name)),
+                    b"outlineLvl" => attribute(e, b"val")
+                        .and_then(|v| v.parse::<u8>().ok())
+                        .filter(|&v| v < 9)
+                        .map(|v| v + 1),
+                    _ => None,
+                };
+                if let Some(level) = level {
+                    headings.entry(id.clone()).or_insert(level);
+                }
+            }
+            Ok(Event::End(ref e)) if e.local_name().as_ref() == b"style" => style_id = None,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
 
-    parse_docx_xml(&xml_content)
+    headings
+}
+
+/// Heading level for a style name or id such as "heading 2", "Heading2" or "Title".
+fn heading_level(name: &str) -> Option<u8> {
+    let name = name.to_ascii_lowercase();
+    if name == "title" {
+        return Some(1);
+    }
+    name.strip_prefix("heading")?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|level| (1..=9).contains(level))
 }
 
-fn parse_docx_xml(xml: &str) -> Result<String, ProcessError> {
+/// Maps relationship ids to archive paths of internal targets.
+fn parse_relationships(xml: &str) -> HashMap<String, String> {
     let mut reader = Reader::from_str(xml);
-    reader.config_mut().trim_text(true);
+    let mut relationships = HashMap::new();
 
-    let mut text = String::new();
-    let mut in_text_element = false;
-    let mut in_paragraph = false;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e))
+                if e.local_name().as_ref() == b"Relationship" =>
+            {
+                if attribute(e, b"TargetMode").as_deref() == Some("External") {
+                    continue;
+                }
+                if let (Some(id), Some(target)) = (attribute(e, b"Id"), attribute(e, b"Target")) {
+                    relationships.insert(id, resolve_target(&target));
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    relationships
+}
+
+/// Resolves a relationship target against the `word/` directory.
+fn resolve_target(target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut parts = vec!["word"];
+    for part in target.split('/') {
+        match part {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+fn attribute(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == name)
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
+}
+
+/// Whether a toggle property like `<w:b/>` is on; `w:val="0"` turns it off.
+fn toggle_on(e: &BytesStart) -> bool {
+    !matches!(
+        attribute(e, b"val").as_deref(),
+        Some("0" | "false" | "off" | "none")
+    )
+}
+
+/// A paragraph being collected, with its list numbering.
+struct OpenParagraph {
+    paragraph: Paragraph,
+    numbered: bool,
+    list_level: u8,
+}
+
+#[derive(Default)]
+struct DocxParser {
+    blocks: Vec<Block>,
+    /// Open tables, innermost last.
+    tables: Vec<Vec<Vec<Vec<Paragraph>>>>,
+    /// Open paragraphs; text boxes nest paragraphs inside runs.
+    paragraphs: Vec<OpenParagraph>,
+    pictures: Vec<Picture>,
+    bold: bool,
+    in_run_properties: bool,
+    in_text: bool,
+    /// Depth inside elements whose content is skipped (fallbacks, deletions).
+    skip_depth: usize,
+    picture_size: Option<(f32, f32)>,
+    picture_id: Option<String>,
+}
+
+impl DocxParser {
+    fn open_paragraph(&mut self) {
+        self.paragraphs.push(OpenParagraph {
+            paragraph: Paragraph {
+                style: ParagraphStyle::Body,
+                spans: Vec::new(),
+            },
+            numbered: false,
+            list_level: 0,
+        });
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let bold = self.bold;
+        let Some(open) = self.paragraphs.last_mut() else {
+            return;
+        };
+        match open.paragraph.spans.last_mut() {
+            Some(last) if last.bold == bold => last.text.push_str(text),
+            _ => open.paragraph.spans.push(Span::new(text, bold)),
+        }
+    }
+
+    fn close_paragraph(&mut self) {
+        let Some(open) = self.paragraphs.pop() else {
+            return;
+        };
+        let mut paragraph = open.paragraph;
+        if open.numbered && paragraph.style == ParagraphStyle::Body {
+            paragraph.style = ParagraphStyle::ListItem(open.list_level);
+        }
+
+        match self
+            .tables
+            .last_mut()
+            .and_then(|rows| rows.last_mut())
+            .and_then(|cells| cells.last_mut())
+        {
+            Some(cell) => cell.push(paragraph),
+            None => {
+                self.blocks.push(Block::Paragraph(paragraph));
+                self.flush_pictures();
+            }
+        }
+    }
+
+    fn close_table(&mut self) {
+        let Some(rows) = self.tables.pop() else {
+            return;
+        };
+        match self
+            .tables
+            .last_mut()
+            .and_then(|rows| rows.last_mut())
+            .and_then(|cells| cells.last_mut())
+        {
+            // Nested tables are flattened into the enclosing cell, one row per line
+            Some(cell) => {
+                for row in rows {
+                    let spans: Vec<Span> = row
+                        .iter()
+                        .map(|paragraphs| {
+                            let text: Vec<String> =
+                                paragraphs.iter().map(Paragraph::text).collect();
+                            text.join(" ")
+                        })
+                        .enumerate()
+                        .map(|(i, text)| {
+                            Span::new(if i > 0 { format!("\t{}", text) } else { text }, false)
+                        })
+                        .collect();
+                    cell.push(Paragraph {
+                        style: ParagraphStyle::Body,
+                        spans,
+                    });
+                }
+            }
+            None => {
+                self.blocks.push(Block::Table(rows));
+                self.flush_pictures();
+            }
+        }
+    }
+
+    /// Emits pictures found inside the last top-level paragraph or table.
+    fn flush_pictures(&mut self) {
+        self.blocks
+            .extend(self.pictures.drain(..).map(Block::Picture));
+    }
+}
+
+/// Parses `word/document.xml` into layout blocks.
+///
+/// `image` loads the bytes of an embedded image by relationship id.
+fn parse_docx_xml(
+    xml: &str,
+    headings: &HashMap<String, u8>,
+    mut image: impl FnMut(&str) -> Option<Vec<u8>>,
+) -> Result<Vec<Block>, ProcessError> {
+    let mut reader = Reader::from_str(xml);
+    let mut parser = DocxParser::default();
 
     loop {
         match reader.read_event() {
             Ok(Event::Start(ref e)) => {
                 let local_name = e.local_name();
+                if parser.skip_depth > 0 {
+                    parser.skip_depth += 1;
+                    continue;
+                }
                 match local_name.as_ref() {
-                    b"t" => in_text_element = true,
-                    b"p" => in_paragraph = true,
+                    // Tab stops, tracked deletions, field codes and compatibility fallbacks
+                    b"tabs" | b"del" | b"instrText" | b"Fallback" => parser.skip_depth = 1,
+                    b"p" => parser.open_paragraph(),
+                    b"r" => parser.bold = false,
+                    b"rPr" => parser.in_run_properties = true,
+                    b"t" => parser.in_text = true,
+                    b"tbl" => parser.tables.push(Vec::new()),
+                    b"tr" => {
+                        if let Some(rows) = parser.tables.last_mut() {
+                            rows.push(Vec::new());
+                        }
+                    }
+                    b"tc" => {
+                        if let Some(row) = parser.tables.last_mut().and_then(|r| r.last_mut()) {
+                            row.push(Vec::new());
+                        }
+                    }
+                    b"drawing" | b"pict" => {
+                        parser.picture_size = None;
+                        parser.picture_id = None;
+                    }
                     _ => {}
                 }
+                handle_properties(&mut parser, e, headings);
             }
-            Ok(Event::End(ref e)) => {
-                let local_name = e.local_name();
-                match local_name.as_ref() {
-                    b"t" => in_text_element = false,
+            Ok(Event::Empty(ref e)) => {
+                if parser.skip_depth > 0 {
+                    continue;
+                }
+                match e.local_name().as_ref() {
+                    b"tab" => parser.push_text("\t"),
+                    b"br" | b"cr" => parser.push_text("\n"),
+                    b"noBreakHyphen" => parser.push_text("-"),
                     b"p" => {
-                        if in_paragraph {
-                            text.push('\n');
-                            in_paragraph = false;
+                        parser.open_paragraph();
+                        parser.close_paragraph();
+                    }
+                    _ => handle_properties(&mut parser, e, headings),
+                }
+            }
+            Ok(Event::End(ref e)) => {
+                if parser.skip_depth > 0 {
+                    parser.skip_depth -= 1;
+                    continue;
+                }
+                match e.local_name().as_ref() {
+                    b"t" => parser.in_text = false,
+                    b"rPr" => parser.in_run_properties = false,
+                    b"p" => parser.close_paragraph(),
+                    b"tbl" => parser.close_table(),
+                    b"drawing" | b"pict" => {
+                        if let Some(data) = parser.picture_id.take().and_then(|id| image(&id)) {
+                            parser.pictures.push(Picture {
+                                data,
+                                size: parser.picture_size.take(),
+                            });
                         }
                     }
                     _ => {}
                 }
             }
-            Ok(Event::Text(e)) => {
-                if in_text_element {
-                    let decoded = e.unescape().unwrap_or_default();
-                    text.push_str(&decoded);
-                }
+            Ok(Event::Text(e)) if parser.in_text && parser.skip_depth == 0 => {
+                let decoded = e.unescape().unwrap_or_default();
+                parser.push_text(&decoded);
             }
             Ok(Event::Eof) => break,
             Err(e) => {
@@ -123,133 +415,101 @@ fn parse_docx_xml(xml: &str) -> Result<String, ProcessError> {
         }
     }
 
-    Ok(text)
-}
-
-fn create_docx_pdf(text: &str) -> Result<Vec<u8>, ProcessError> {
-    let mut doc = Document::with_version("1.5");
-
-    let pages_id = doc.new_object_id();
-    let font_id = doc.new_object_id();
-    let resources_id = doc.new_object_id();
-
-    // Font
-    doc.objects.insert(
-        font_id,
-        Object::Dictionary(dictionary! {
-            "Type" => "Font",
-            "Subtype" => "Type1",
-            "BaseFont" => "Helvetica",
-        }),
-    );
-
-    // Resources
-    doc.objects.insert(
-        resources_id,
-        Object::Dictionary(dictionary! {
-            "Font" => dictionary! {
-                "F1" => font_id,
-            },
-        }),
-    );
-
-    // Split text into pages (roughly 50 lines per page)
-    let lines: Vec<&str> = text.lines().collect();
-    let lines_per_page = 50;
-    let page_count = lines.len().div_ceil(lines_per_page);
-    let page_count = page_count.max(1);
-
-    let mut page_ids = Vec::new();
-
-    for page_num in 0..page_count {
-        let start_line = page_num * lines_per_page;
-        let end_line = ((page_num + 1) * lines_per_page).min(lines.len());
-        let page_lines = if start_line < lines.len() {
-            &lines[start_line..end_line]
-        } else {
-            &[]
-        };
-
-        let content_id = doc.new_object_id();
-        let page_id = doc.new_object_id();
-
-        // Content stream
-        let content = format_text_for_pdf(page_lines);
-        let content_stream = Stream::new(dictionary! {}, content.into_bytes());
-        doc.objects
-            .insert(content_id, Object::Stream(content_stream));
-
-        // Page
-        doc.objects.insert(
-            page_id,
-            Object::Dictionary(dictionary! {
-                "Type" => "Page",
-                "Parent" => pages_id,
-                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
-                "Resources" => resources_id,
-                "Contents" => content_id,
-            }),
-        );
-
-        page_ids.push(page_id);
+    // Unterminated structures in truncated documents still keep their text
+    while !parser.paragraphs.is_empty() {
+        parser.close_paragraph();
+    }
+    while !parser.tables.is_empty() {
+        parser.close_table();
     }
+    parser.flush_pictures();
 
-    // Pages
-    let kids: Vec<Object> = page_ids.iter().map(|id| (*id).into()).collect();
-    doc.objects.insert(
-        pages_id,
-        Object::Dictionary(dictionary! {
-            "Type" => "Pages",
-            "Kids" => kids,
-            "Count" => page_ids.len() as i64,
-        }),
-    );
-
-    // Catalog
-    let catalog_id = doc.add_object(dictionary! {
-        "Type" => "Catalog",
-        "Pages" => pages_id,
-    });
-    doc.trailer.set("Root", catalog_id);
-
-    let mut buffer = Vec::new();
-    doc.save_to(&mut buffer)
-        .map_err(|e| ProcessError::PdfProcessing(e.to_string()))?;
-
-    Ok(buffer)
+    Ok(parser.blocks)
 }
 
-fn format_text_for_pdf(lines: &[&str]) -> String {
-    let mut content = String::new();
-    content.push_str("BT\n");
-    content.push_str("/F1 11 Tf\n");
-    content.push_str("50 742 Td\n");
-    content.push_str("14 TL\n");
-
-    for line in lines {
-        let escaped = escape_pdf_string(line);
-        content.push_str(&format!("({}) Tj T*\n", escaped));
+/// Applies paragraph, run and drawing properties carried on element attributes.
+fn handle_properties(parser: &mut DocxParser, e: &BytesStart, headings: &HashMap<String, u8>) {
+    match e.local_name().as_ref() {
+        b"b" if parser.in_run_properties => parser.bold = toggle_on(e),
+        b"pStyle" => {
+            let level = attribute(e, b"val")
+                .and_then(|id| headings.get(&id).copied().or_else(|| heading_level(&id)));
+            if let (Some(level), Some(open)) = (level, parser.paragraphs.last_mut()) {
+                open.paragraph.style = ParagraphStyle::Heading(level);
+            }
+        }
+        b"outlineLvl" => {
+            let level = attribute(e, b"val").and_then(|v| v.parse::<u8>().ok());
+            if let (Some(level @ 0..=8), Some(open)) = (level, parser.paragraphs.last_mut()) {
+                open.paragraph.style = ParagraphStyle::Heading(level + 1);
+            }
+        }
+        b"ilvl" => {
+            if let Some(open) = parser.paragraphs.last_mut() {
+                open.list_level = attribute(e, b"val")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0);
+            }
+        }
+        b"numId" => {
+            if let Some(open) = parser.paragraphs.last_mut() {
+                open.numbered = attribute(e, b"val").is_some_and(|v| v != "0");
+            }
+        }
+        b"extent" => {
+            let emu = |name| attribute(e, name).and_then(|v| v.parse::<f32>().ok());
+            if let (Some(cx), Some(cy)) = (emu(b"cx"), emu(b"cy")) {
+                parser.picture_size = Some((cx / EMU_PER_POINT, cy / EMU_PER_POINT));
+            }
+        }
+        b"blip" => parser.picture_id = attribute(e, b"embed"),
+        b"imagedata" => parser.picture_id = attribute(e, b"id"),
+        _ => {}
     }
-
-    content.push_str("ET\n");
-    content
 }
 
-fn escape_pdf_string(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '(' => "\\(".to_string(),
-            ')' => "\\)".to_string(),
-            '\\' => "\\\\".to_string(),
-            c if c.is_ascii() && !c.is_control() => c.to_string(),
-            _ => " ".to_string(),
-        })
-        .collect()
+/// Plain text of the document: one line per paragraph, table cells separated by tabs.
+fn blocks_to_text(blocks: &[Block]) -> String {
+    let mut text = String::new();
+    for block in blocks {
+        match block {
+            Block::Paragraph(paragraph) => {
+                text.push_str(&paragraph.text());
+                text.push('\n');
+            }
+            Block::Table(rows) => {
+                for row in rows {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| {
+                            let lines: Vec<String> = cell.iter().map(Paragraph::text).collect();
+                            lines.join(" ")
+                        })
+                        .collect();
+                    text.push_str(&cells.join("\t"));
+                    text.push('\n');
+                }
+            }
+            Block::Picture(_) => {}
+        }
+    }
+    text
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
+
+    const W_NS: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+
+    fn parse(body: &str) -> Vec<Block> {
+        let xml = format!(
+            "<w:document {}><w:body>{}</w:body></w:document>",
+            W_NS, body
+        );
+        parse_docx_xml(&xml, &HashMap::new(), |_| None).unwrap()
+    }
 
     #[test]
     fn test_supports_docx_format() {
@@ -273,7 +533,184 @@ mod tests {
             </w:body>
         </w:document>"#;
 
-        let text = parse_docx_xml(xml).unwrap();
+        let blocks = parse_docx_xml(xml, &HashMap::new(), |_| None).unwrap();
+        let text = blocks_to_text(&blocks);
         assert!(text.contains("Hello World"));
     }
+
+    #[test]
+    fn test_parse_headings_lists_and_bold() {
+        let headings = HashMap::from([("berschrift1".to_string(), 1)]);
+        let xml = format!(
+            r#"<w:document {}><w:body>
+                <w:p><w:pPr><w:pStyle w:val="berschrift1"/></w:pPr><w:r><w:t>Rechnung</w:t></w:r></w:p>
+                <w:p><w:pPr><w:pStyle w:val="Heading2"/></w:pPr><w:r><w:t>Details</w:t></w:r></w:p>
+                <w:p><w:pPr><w:numPr><w:ilvl w:val="1"/><w:numId w:val="3"/></w:numPr></w:pPr><w:r><w:t>Item</w:t></w:r></w:p>
+                <w:p><w:r><w:t xml:space="preserve">Total: </w:t></w:r><w:r><w:rPr><w:b/></w:rPr><w:t>CHF 12</w:t></w:r><w:r><w:rPr><w:b w:val="0"/></w:rPr><w:tab/><w:t>net</w:t></w:r></w:p>
+            </w:body></w:document>"#,
+            W_NS
+        );
+
+        let blocks = parse_docx_xml(&xml, &headings, |_| None).unwrap();
+        let styles: Vec<ParagraphStyle> = blocks
+            .iter()
+            .map(|b| match b {
+                Block::Paragraph(p) => p.style,
+                other => panic!("unexpected block {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            styles,
+            vec![
+                ParagraphStyle::Heading(1),
+                ParagraphStyle::Heading(2),
+                ParagraphStyle::ListItem(1),
+                ParagraphStyle::Body,
+            ]
+        );
+
+        let Block::Paragraph(total) = &blocks[3] else {
+            unreachable!()
+        };
+        assert_eq!(
+            total.spans,
+            vec![
+                Span::new("Total: ", false),
+                Span::new("CHF 12", true),
+                Span::new("\tnet", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tables() {
+        let blocks = parse(
+            r#"<w:tbl>
+                <w:tr><w:tc><w:p><w:r><w:t>Item</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Price</w:t></w:r></w:p></w:tc></w:tr>
+                <w:tr><w:tc><w:p><w:r><w:t>A</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>1.00</w:t></w:r></w:p></w:tc></w:tr>
+            </w:tbl>
+            <w:p><w:r><w:t>After</w:t></w:r></w:p>"#,
+        );
+
+        assert!(matches!(&blocks[0], Block::Table(rows) if rows.len() == 2 && rows[0].len() == 2));
+        assert_eq!(blocks_to_text(&blocks), "Item\tPrice\nA\t1.00\nAfter\n");
+    }
+
+    #[test]
+    fn test_parse_skips_deleted_text_and_fallbacks() {
+        let blocks = parse(
+            r#"<w:p><w:r><w:t>Kept</w:t></w:r><w:del><w:r><w:delText>Gone</w:delText></w:r></w:del>
+               <mc:AlternateContent xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006">
+                 <mc:Choice><w:r><w:t> once</w:t></w:r></mc:Choice>
+                 <mc:Fallback><w:r><w:t> twice</w:t></w:r></mc:Fallback>
+               </mc:AlternateContent></w:p>"#,
+        );
+        assert_eq!(blocks_to_text(&blocks), "Kept once\n");
+    }
+
+    #[test]
+    fn test_parse_inline_images() {
+        let xml = format!(
+            r#"<w:document {} xmlns:wp="wp" xmlns:a="a" xmlns:r="r"><w:body>
+                <w:p><w:r><w:drawing><wp:inline><wp:extent cx="1270000" cy="635000"/>
+                  <a:graphic><a:graphicData><a:blip r:embed="rId7"/></a:graphicData></a:graphic>
+                </wp:inline></w:drawing></w:r></w:p>
+                <w:p><w:r><w:t>Caption</w:t></w:r></w:p>
+            </w:body></w:document>"#,
+            W_NS
+        );
+
+        let mut requested = Vec::new();
+        let blocks = parse_docx_xml(&xml, &HashMap::new(), |id| {
+            requested.push(id.to_string());
+            Some(vec![1, 2, 3])
+        })
+        .unwrap();
+
+        assert_eq!(requested, vec!["rId7"]);
+        assert!(matches!(
+            &blocks[1],
+            Block::Picture(Picture { data, size: Some((w, h)) })
+                if data == &[1, 2, 3] && *w == 100.0 && *h == 50.0
+        ));
+        assert!(matches!(&blocks[2], Block::Paragraph(p) if p.text() == "Caption"));
+    }
+
+    #[test]
+    fn test_parse_heading_styles() {
+        let xml = r#"<w:styles xmlns:w="w">
+            <w:style w:type="paragraph" w:styleId="berschrift1"><w:name w:val="heading 1"/></w:style>
+            <w:style w:type="paragraph" w:styleId="Custom"><w:name w:val="Custom"/><w:pPr><w:outlineLvl w:val="2"/></w:pPr></w:style>
+            <w:style w:type="paragraph" w:styleId="Normal"><w:name w:val="Normal"/></w:style>
+        </w:styles>"#;
+
+        let headings = parse_heading_styles(xml);
+        assert_eq!(headings.get("berschrift1"), Some(&1));
+        assert_eq!(headings.get("Custom"), Some(&3));
+        assert_eq!(headings.get("Normal"), None);
+    }
+
+    #[test]
+    fn test_parse_relationships() {
+        let xml = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+            <Relationship Id="rId1" Type="image" Target="media/image1.png"/>
+            <Relationship Id="rId2" Type="image" Target="/word/media/image2.jpeg"/>
+            <Relationship Id="rId3" Type="hyperlink" Target="https://example.com" TargetMode="External"/>
+        </Relationships>"#;
+
+        let rels = parse_relationships(xml);
+        assert_eq!(rels.get("rId1").unwrap(), "word/media/image1.png");
+        assert_eq!(rels.get("rId2").unwrap(), "word/media/image2.jpeg");
+        assert!(!rels.contains_key("rId3"));
+        assert_eq!(resolve_target("../media/x.png"), "media/x.png");
+    }
+
+    #[test]
+    fn test_process_docx_with_image() {
+        let mut png = Vec::new();
+        image::RgbImage::from_pixel(2, 2, image::Rgb([0, 0, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let temp_file = tempfile::NamedTempFile::with_suffix(".docx").unwrap();
+        {
+            let mut writer = zip::ZipWriter::new(temp_file.reopen().unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file("word/document.xml", options).unwrap();
+            write!(
+                writer,
+                r#"<w:document {} xmlns:a="a" xmlns:r="r"><w:body>
+                    <w:p><w:r><w:t>Grüße — 日本</w:t></w:r></w:p>
+                    <w:p><w:r><w:drawing><a:blip r:embed="rId1"/></w:drawing></w:r></w:p>
+                </w:body></w:document>"#,
+                W_NS
+            )
+            .unwrap();
+            writer
+                .start_file("word/_rels/document.xml.rels", options)
+                .unwrap();
+            writer
+                .write_all(br#"<Relationships><Relationship Id="rId1" Target="media/image1.png"/></Relationships>"#)
+                .unwrap();
+            writer.start_file("word/media/image1.png", options).unwrap();
+            writer.write_all(&png).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let processed = DocxProcessor::new().process(temp_file.path()).unwrap();
+        assert!(processed.text.contains("Grüße — 日本"));
+
+        let doc = lopdf::Document::load_mem(&processed.pdf_bytes).unwrap();
+        let page = doc.page_iter().next().unwrap();
+        let (_, resource_ids) = doc.get_page_resources(page);
+        let resources = doc.get_dictionary(resource_ids[0]).unwrap();
+        assert_eq!(
+            resources
+                .get(b"XObject")
+                .and_then(lopdf::Object::as_dict)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
 }
@@ -0,0 +1,468 @@
+//! Minimal TrueType support for embedding a Unicode font in rendered PDFs.
+//!
+//! Only what the PDF renderer needs is implemented: character to glyph
+//! mapping, advance widths, vertical metrics and glyph subsetting.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Environment variable naming a TrueType font to embed instead of the defaults.
+pub const FONT_ENV_VAR: &str = "PAPORG_PDF_FONT";
+/// Environment variable naming the bold companion of [`FONT_ENV_VAR`].
+pub const BOLD_FONT_ENV_VAR: &str = "PAPORG_PDF_FONT_BOLD";
+
+const REGULAR_CANDIDATES: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu-sans-fonts/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+];
+
+const BOLD_CANDIDATES: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/dejavu-sans-fonts/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSans-Bold.ttf",
+    "/usr/share/fonts/noto/NotoSans-Bold.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Bold.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Bold.ttf",
+    "C:\\Windows\\Fonts\\arialbd.ttf",
+    "C:\\Windows\\Fonts\\segoeuib.ttf",
+];
+
+/// Tables copied into a subset font. `loca` and `glyf` are rebuilt.
+const SUBSET_TABLES: &[&[u8; 4]] = &[
+    b"cmap", b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp", b"prep",
+];
+
+static REGULAR: LazyLock<Option<TrueTypeFont>> =
+    LazyLock::new(|| load_first(FONT_ENV_VAR, REGULAR_CANDIDATES));
+static BOLD: LazyLock<Option<TrueTypeFont>> =
+    LazyLock::new(|| load_first(BOLD_FONT_ENV_VAR, BOLD_CANDIDATES));
+
+/// Returns the regular Unicode font, if one is installed.
+pub(crate) fn regular() -> Option<&'static TrueTypeFont> {
+    REGULAR.as_ref()
+}
+
+/// Returns the bold Unicode font, if one is installed.
+pub(crate) fn bold() -> Option<&'static TrueTypeFont> {
+    BOLD.as_ref()
+}
+
+fn load_first(env_var: &str, candidates: &[&str]) -> Option<TrueTypeFont> {
+    let configured = std::env::var_os(env_var).map(PathBuf::from);
+    configured
+        .into_iter()
+        .chain(candidates.iter().map(PathBuf::from))
+        .find_map(|path| {
+            let font = TrueTypeFont::load(&path)?;
+            tracing::debug!("Using {} for PDF rendering", path.display());
+            Some(font)
+        })
+}
+
+/// A parsed TrueType font with glyph outlines (`glyf`).
+pub(crate) struct TrueTypeFont {
+    data: Vec<u8>,
+    name: String,
+    tables: BTreeMap<[u8; 4], (usize, usize)>,
+    units_per_em: u16,
+    bbox: [i16; 4],
+    ascender: i16,
+    descender: i16,
+    num_glyphs: u16,
+    num_h_metrics: u16,
+    long_loca: bool,
+    cmap: CmapSubtable,
+}
+
+#[derive(Clone, Copy)]
+enum CmapSubtable {
+    /// Segment mapping to delta values (BMP only).
+    Format4(usize),
+    /// Segmented coverage (full Unicode).
+    Format12(usize),
+}
+
+impl TrueTypeFont {
+    fn load(path: &Path) -> Option<Self> {
+        let data = std::fs::read(path).ok()?;
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(|s| {
+                s.chars()
+                    .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+                    .collect()
+            })
+            .filter(|s: &String| !s.is_empty())
+            .unwrap_or_else(|| "Embedded".to_string());
+        Self::parse(data, name)
+    }
+
+    /// Parses a TrueType font; returns `None` for CFF-based or malformed fonts.
+    pub(crate) fn parse(data: Vec<u8>, name: String) -> Option<Self> {
+        let num_tables = read_u16(&data, 4)? as usize;
+        let mut tables = BTreeMap::new();
+        for i in 0..num_tables {
+            let record = 12 + i * 16;
+            let tag: [u8; 4] = data.get(record..record + 4)?.try_into().ok()?;
+            let offset = read_u32(&data, record + 8)? as usize;
+            let length = read_u32(&data, record + 12)? as usize;
+            if offset.checked_add(length)? > data.len() {
+                return None;
+            }
+            tables.insert(tag, (offset, length));
+        }
+        for required in [
+            b"head", b"hhea", b"hmtx", b"maxp", b"loca", b"glyf", b"cmap",
+        ] {
+            tables.get(required)?;
+        }
+
+        let head = tables[b"head"].0;
+        let hhea = tables[b"hhea"].0;
+        let cmap = find_cmap(&data, tables[b"cmap"].0)?;
+
+        Some(Self {
+            units_per_em: read_u16(&data, head + 18)?.max(1),
+            bbox: [
+                read_i16(&data, head + 36)?,
+                read_i16(&data, head + 38)?,
+                read_i16(&data, head + 40)?,
+                read_i16(&data, head + 42)?,
+            ],
+            long_loca: read_i16(&data, head + 50)? == 1,
+            ascender: read_i16(&data, hhea + 4)?,
+            descender: read_i16(&data, hhea + 6)?,
+            num_h_metrics: read_u16(&data, hhea + 34)?.max(1),
+            num_glyphs: read_u16(&data, tables[b"maxp"].0 + 4)?,
+            cmap,
+            tables,
+            name,
+            data,
+        })
+    }
+
+    /// PostScript-safe font name used as the PDF `BaseFont`.
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Glyph for a character; 0 (`.notdef`) when the font lacks it.
+    pub(crate) fn glyph_id(&self, c: char) -> u16 {
+        let code = c as u32;
+        let d = &self.data;
+        let lookup = match self.cmap {
+            CmapSubtable::Format4(offset) => format4_lookup(d, offset, code),
+            CmapSubtable::Format12(offset) => format12_lookup(d, offset, code),
+        };
+        lookup.filter(|&gid| gid < self.num_glyphs).unwrap_or(0)
+    }
+
+    /// Advance width of a glyph in 1/1000 em.
+    pub(crate) fn advance(&self, gid: u16) -> f32 {
+        let index = gid.min(self.num_h_metrics - 1) as usize;
+        let units = read_u16(&self.data, self.tables[b"hmtx"].0 + index * 4).unwrap_or(0);
+        units as f32 * 1000.0 / self.units_per_em as f32
+    }
+
+    /// Scales a font-unit value to 1/1000 em.
+    fn scale(&self, value: i16) -> i64 {
+        (value as f32 * 1000.0 / self.units_per_em as f32).round() as i64
+    }
+
+    pub(crate) fn ascent(&self) -> i64 {
+        self.scale(self.ascender)
+    }
+
+    pub(crate) fn descent(&self) -> i64 {
+        self.scale(self.descender)
+    }
+
+    /// Font bounding box in 1/1000 em.
+    pub(crate) fn bbox(&self) -> [i64; 4] {
+        self.bbox.map(|v| self.scale(v))
+    }
+
+    fn glyph_range(&self, gid: u16) -> Option<(usize, usize)> {
+        let loca = self.tables[b"loca"].0;
+        let gid = gid as usize;
+        let (start, end) = if self.long_loca {
+            (
+                read_u32(&self.data, loca + gid * 4)? as usize,
+                read_u32(&self.data, loca + gid * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                read_u16(&self.data, loca + gid * 2)? as usize * 2,
+                read_u16(&self.data, loca + gid * 2 + 2)? as usize * 2,
+            )
+        };
+        let (glyf, glyf_len) = self.tables[b"glyf"];
+        (start <= end && end <= glyf_len).then_some((glyf + start, glyf + end))
+    }
+
+    /// Glyphs referenced by a composite glyph.
+    fn components(&self, gid: u16) -> Vec<u16> {
+        let Some((start, end)) = self.glyph_range(gid) else {
+            return Vec::new();
+        };
+        let glyph = &self.data[start..end];
+        if glyph.len() < 10 || read_i16(glyph, 0).unwrap_or(0) >= 0 {
+            return Vec::new();
+        }
+
+        const ARGS_ARE_WORDS: u16 = 0x0001;
+        const HAS_SCALE: u16 = 0x0008;
+        const MORE_COMPONENTS: u16 = 0x0020;
+        const HAS_XY_SCALE: u16 = 0x0040;
+        const HAS_2X2: u16 = 0x0080;
+
+        let mut components = Vec::new();
+        let mut pos = 10;
+        while let (Some(flags), Some(component)) = (read_u16(glyph, pos), read_u16(glyph, pos + 2))
+        {
+            components.push(component);
+            pos += 4;
+            pos += if flags & ARGS_ARE_WORDS != 0 { 4 } else { 2 };
+            if flags & HAS_SCALE != 0 {
+                pos += 2;
+            } else if flags & HAS_XY_SCALE != 0 {
+                pos += 4;
+            } else if flags & HAS_2X2 != 0 {
+                pos += 8;
+            }
+            if flags & MORE_COMPONENTS == 0 {
+                break;
+            }
+        }
+        components
+    }
+
+    /// Builds a font containing only the given glyphs (plus `.notdef`).
+    ///
+    /// Glyph ids are preserved, so text encoded against the full font renders
+    /// unchanged; unused glyphs are left empty.
+    pub(crate) fn subset(&self, glyphs: &BTreeSet<u16>) -> Vec<u8> {
+        let mut keep: BTreeSet<u16> = BTreeSet::from([0]);
+        let mut pending: Vec<u16> = glyphs.iter().copied().collect();
+        while let Some(gid) = pending.pop() {
+            if gid < self.num_glyphs && keep.insert(gid) {
+                pending.extend(self.components(gid));
+            }
+        }
+
+        let mut glyf = Vec::new();
+        let mut loca = Vec::with_capacity((self.num_glyphs as usize + 1) * 4);
+        for gid in 0..self.num_glyphs {
+            loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+            if keep.contains(&gid) {
+                if let Some((start, end)) = self.glyph_range(gid) {
+                    glyf.extend_from_slice(&self.data[start..end]);
+                    while glyf.len() % 4 != 0 {
+                        glyf.push(0);
+                    }
+                }
+            }
+        }
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+
+        let mut tables: Vec<([u8; 4], Vec<u8>)> = Vec::new();
+        for &&tag in SUBSET_TABLES {
+            let Some(&(offset, length)) = self.tables.get(&tag) else {
+                continue;
+            };
+            let data = match &tag {
+                b"glyf" => std::mem::take(&mut glyf),
+                b"loca" => std::mem::take(&mut loca),
+                b"head" => {
+                    let mut head = self.data[offset..offset + length].to_vec();
+                    // Clear checkSumAdjustment and switch to 32-bit loca offsets
+                    head[8..12].fill(0);
+                    head[50..52].copy_from_slice(&1i16.to_be_bytes());
+                    head
+                }
+                _ => self.data[offset..offset + length].to_vec(),
+            };
+            tables.push((tag, data));
+        }
+
+        write_sfnt(&tables)
+    }
+}
+
+fn write_sfnt(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.max(1).leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    out.extend_from_slice(&num_tables.to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in tables {
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&checksum(data).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in tables {
+        out.extend_from_slice(data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    out
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Picks the best Unicode cmap subtable: format 12 if present, else format 4.
+fn find_cmap(data: &[u8], cmap: usize) -> Option<CmapSubtable> {
+    let count = read_u16(data, cmap + 2)? as usize;
+    let mut best = None;
+    for i in 0..count {
+        let record = cmap + 4 + i * 8;
+        let platform = read_u16(data, record)?;
+        let encoding = read_u16(data, record + 2)?;
+        let offset = cmap + read_u32(data, record + 4)? as usize;
+        let unicode = platform == 0 || (platform == 3 && matches!(encoding, 1 | 10));
+        if !unicode {
+            continue;
+        }
+        match read_u16(data, offset)? {
+            12 => return Some(CmapSubtable::Format12(offset)),
+            4 => best = best.or(Some(CmapSubtable::Format4(offset))),
+            _ => {}
+        }
+    }
+    best
+}
+
+fn format4_lookup(d: &[u8], offset: usize, code: u32) -> Option<u16> {
+    let code = u16::try_from(code).ok()?;
+    let seg_count = read_u16(d, offset + 6)? as usize / 2;
+    let ends = offset + 14;
+    let starts = ends + seg_count * 2 + 2;
+    let deltas = starts + seg_count * 2;
+    let range_offsets = deltas + seg_count * 2;
+
+    for seg in 0..seg_count {
+        if read_u16(d, ends + seg * 2)? < code {
+            continue;
+        }
+        let start = read_u16(d, starts + seg * 2)?;
+        if start > code {
+            return None;
+        }
+        let delta = read_u16(d, deltas + seg * 2)?;
+        let range_offset_pos = range_offsets + seg * 2;
+        let range_offset = read_u16(d, range_offset_pos)? as usize;
+        if range_offset == 0 {
+            return Some(code.wrapping_add(delta));
+        }
+        let glyph_pos = range_offset_pos + range_offset + (code - start) as usize * 2;
+        let gid = read_u16(d, glyph_pos)?;
+        return (gid != 0).then(|| gid.wrapping_add(delta));
+    }
+    None
+}
+
+fn format12_lookup(d: &[u8], offset: usize, code: u32) -> Option<u16> {
+    let groups = read_u32(d, offset + 12)? as usize;
+    let (mut lo, mut hi) = (0, groups);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let group = offset + 16 + mid * 12;
+        let start = read_u32(d, group)?;
+        let end = read_u32(d, group + 4)?;
+        if code < start {
+            hi = mid;
+        } else if code > end {
+            lo = mid + 1;
+        } else {
+            let gid = read_u32(d, group + 8)? + (code - start);
+            return u16::try_from(gid).ok();
+        }
+    }
+    None
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Option<i16> {
+    read_u16(data, offset).map(|v| v as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rejects_non_fonts() {
+        assert!(TrueTypeFont::parse(b"not a font".to_vec(), "x".into()).is_none());
+        assert!(TrueTypeFont::parse(Vec::new(), "x".into()).is_none());
+    }
+
+    #[test]
+    fn test_checksum_pads_last_word() {
+        assert_eq!(checksum(&[0, 0, 0, 1, 0, 0, 0, 2]), 3);
+        assert_eq!(checksum(&[1]), 0x0100_0000);
+    }
+
+    #[test]
+    fn test_system_font_subset_round_trips() {
+        // Only meaningful where a Unicode font is installed
+        let Some(font) = regular() else {
+            return;
+        };
+
+        let gid = font.glyph_id('Ä');
+        assert_ne!(gid, 0);
+        assert!(font.advance(gid) > 0.0);
+        assert_eq!(font.glyph_id('\u{10FFFF}'), 0);
+
+        let subset = font.subset(&BTreeSet::from([gid]));
+        assert!(subset.len() < font.data.len());
+
+        let reparsed = TrueTypeFont::parse(subset, "Subset".into()).unwrap();
+        assert_eq!(reparsed.glyph_id('Ä'), gid);
+        assert_eq!(reparsed.advance(gid), font.advance(gid));
+        let glyph = |f: &TrueTypeFont, gid| f.glyph_range(gid).map(|(s, e)| f.data[s..e].to_vec());
+        assert_eq!(glyph(&reparsed, gid), glyph(font, gid));
+        // Unused glyphs are emptied
+        let unused = font.glyph_id('Z');
+        assert_eq!(glyph(&reparsed, unused), Some(Vec::new()));
+    }
+}
@@ -0,0 +1,357 @@
+use std::path::Path;
+
+use mail_parser::decoders::charsets::map::charset_decoder;
+use mail_parser::decoders::html::add_html_token;
+
+use crate::config::schema::{DocumentFormat, DocumentMetadata};
+use crate::error::ProcessError;
+use crate::processor::{render, DocumentProcessor, ProcessedContent};
+
+pub struct HtmlProcessor;
+
+impl HtmlProcessor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for HtmlProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DocumentProcessor for HtmlProcessor {
+    fn process(&self, path: &Path) -> Result<ProcessedContent, ProcessError> {
+        let _span = tracing::info_span!("processor.html").entered();
+        let bytes = std::fs::read(path).map_err(|e| ProcessError::ReadDocument {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        let text = html_to_text(&decode_html_bytes(&bytes));
+
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("document.html")
+            .to_string();
+
+        let metadata = DocumentMetadata::new(filename, DocumentFormat::Html);
+        let pdf_bytes = render::text_to_pdf(&text)?;
+
+        Ok(ProcessedContent::new(text, pdf_bytes, metadata))
+    }
+
+    fn supports(&self, format: DocumentFormat) -> bool {
+        matches!(format, DocumentFormat::Html)
+    }
+}
+
+/// Elements whose content is never readable text.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "object", "iframe",
+];
+
+/// Elements that start a new line in the extracted text.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "tr",
+    "ul",
+];
+
+/// Decodes HTML bytes using the `<meta charset>` declaration, defaulting to UTF-8.
+fn decode_html_bytes(bytes: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(2048)]).to_lowercase();
+    let declared = head.find("charset=").map(|pos| {
+        head[pos + "charset=".len()..]
+            .trim_start_matches(['"', '\''])
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            .collect::<String>()
+    });
+
+    // Undeclared non-UTF-8 HTML is almost always Windows-1252
+    let charset = declared.unwrap_or_else(|| "windows-1252".to_string());
+    match charset_decoder(charset.as_bytes()) {
+        Some(decode) => decode(bytes),
+        None => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Extracts readable text from HTML, dropping scripts, styles and markup.
+pub(crate) fn html_to_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+    let mut in_pre = false;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            push_text(&mut out, rest, in_pre);
+            break;
+        };
+        push_text(&mut out, &rest[..lt], in_pre);
+        rest = &rest[lt..];
+
+        // Comments, doctype and processing instructions
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+
+        let Some(tag_end) = find_tag_end(rest) else {
+            // Unterminated tag: treat the remainder as text
+            push_text(&mut out, rest, in_pre);
+            break;
+        };
+        let tag = &rest[1..tag_end];
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if name.is_empty() {
+            // A lone '<' in text, e.g. "a < b"
+            push_text(&mut out, "<", in_pre);
+            rest = &rest[1..];
+            continue;
+        }
+        rest = &rest[tag_end + 1..];
+
+        if !closing && !tag.ends_with('/') && SKIPPED_ELEMENTS.contains(&name.as_str()) {
+            rest = skip_element(rest, &name);
+            continue;
+        }
+
+        match name.as_str() {
+            "pre" => {
+                in_pre = !closing;
+                push_newline(&mut out);
+            }
+            "td" | "th" if !closing && !out.is_empty() && !out.ends_with('\n') => {
+                out.push_str("  ");
+            }
+            "li" if !closing => {
+                push_newline(&mut out);
+                out.push_str("- ");
+            }
+            "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "table" => {
+                push_newline(&mut out);
+                if closing {
+                    out.push('\n');
+                }
+            }
+            name if BLOCK_ELEMENTS.contains(&name) => push_newline(&mut out),
+            _ => {}
+        }
+    }
+
+    normalize_lines(&out)
+}
+
+/// Finds the `>` closing a tag that starts at `s[0] == '<'`, honouring quoted attributes.
+fn find_tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(i),
+            (None, '<') if i == 1 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Skips past the closing tag of a raw-content element such as `<script>`.
+fn skip_element<'a>(s: &'a str, name: &str) -> &'a str {
+    let closing = format!("</{}", name);
+    let lower = s.to_ascii_lowercase();
+    match lower.find(&closing) {
+        Some(pos) => s[pos..].find('>').map_or("", |end| &s[pos + end + 1..]),
+        None => "",
+    }
+}
+
+/// Appends a text run, collapsing whitespace (outside `<pre>`) and decoding entities.
+fn push_text(out: &mut String, text: &str, preformatted: bool) {
+    let decoded = decode_entities(text);
+    if preformatted {
+        out.push_str(&decoded);
+        return;
+    }
+
+    for (i, word) in decoded.split_whitespace().enumerate() {
+        let starts_with_space = decoded.starts_with(char::is_whitespace);
+        let needs_space = i > 0 || starts_with_space;
+        if needs_space && !out.is_empty() && !out.ends_with([' ', '\n']) {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    if decoded.ends_with(char::is_whitespace)
+        && !decoded.trim().is_empty()
+        && !out.ends_with([' ', '\n'])
+    {
+        out.push(' ');
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        match rest[1..].find(|c: char| c == ';' || c.is_whitespace() || c == '&') {
+            Some(end) if rest.as_bytes()[end + 1] == b';' && end <= 32 => {
+                add_html_token(&mut out, &rest.as_bytes()[..end + 2], false);
+                rest = &rest[end + 2..];
+            }
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.replace('\u{a0}', " ")
+}
+
+fn push_newline(out: &mut String) {
+    while out.ends_with(' ') {
+        out.pop();
+    }
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Trims line ends and collapses runs of blank lines.
+fn normalize_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 || out.is_empty() {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_html_format() {
+        let processor = HtmlProcessor::new();
+        assert!(processor.supports(DocumentFormat::Html));
+        assert!(!processor.supports(DocumentFormat::Text));
+    }
+
+    #[test]
+    fn test_html_to_text_skips_scripts_and_styles() {
+        let html = r#"<!DOCTYPE html>
+            <html><head><title>Bill</title><style>p { color: red; }</style></head>
+            <body>
+              <script type="text/javascript">var total = "<b>99</b>";</script>
+              <h1>Invoice   2024-001</h1>
+              <p>Total: <b>CHF&nbsp;120.50</b></p>
+              <!-- tracking pixel -->
+              <noscript>Enable JavaScript</noscript>
+            </body></html>"#;
+
+        let text = html_to_text(html);
+        assert_eq!(text, "Invoice 2024-001\n\nTotal: CHF 120.50");
+    }
+
+    #[test]
+    fn test_html_to_text_lists_tables_and_entities() {
+        let html = "<ul><li>One &amp; two</li><li>&#8364;5 &lt;3</li></ul>\
+                    <table><tr><td>Item</td><td>Price</td></tr><tr><td>A</td><td>1</td></tr></table>\
+                    a < b";
+
+        let text = html_to_text(html);
+        assert_eq!(text, "- One & two\n- €5 <3\nItem  Price\nA  1\n\na < b");
+    }
+
+    #[test]
+    fn test_html_to_text_preserves_pre() {
+        let text = html_to_text("<pre>col1   col2\n1      2</pre>");
+        assert_eq!(text, "col1   col2\n1      2");
+    }
+
+    #[test]
+    fn test_decode_html_bytes_uses_declared_charset() {
+        let mut bytes = b"<meta charset=\"iso-8859-1\"><p>Gr".to_vec();
+        bytes.push(0xFC);
+        bytes.extend_from_slice(b"ezi</p>");
+
+        assert!(decode_html_bytes(&bytes).contains("Grüezi"));
+    }
+
+    #[test]
+    fn test_process_html_file() {
+        let temp_file = tempfile::NamedTempFile::with_suffix(".html").unwrap();
+        std::fs::write(temp_file.path(), "<p>Hello <em>HTML</em></p>").unwrap();
+
+        let processed = HtmlProcessor::new().process(temp_file.path()).unwrap();
+        assert_eq!(processed.text, "Hello HTML");
+        assert_eq!(processed.metadata.format, DocumentFormat::Html);
+        assert!(processed.pdf_bytes.starts_with(b"%PDF"));
+    }
+}
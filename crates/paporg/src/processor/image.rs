@@ -1,24 +1,38 @@
+use std::io::Cursor;
 use std::path::Path;
 
-use image::GenericImageView;
+use image::{DynamicImage, GenericImageView};
 use lopdf::{dictionary, Document, Object, Stream};
 
 use crate::config::schema::{DocumentFormat, DocumentMetadata};
 use crate::error::ProcessError;
+use crate::processor::limits::ProcessingLimits;
 use crate::processor::ocr::OcrProcessor;
 use crate::processor::{DocumentProcessor, ProcessedContent};
 
 pub struct ImageProcessor {
     ocr: Option<OcrProcessor>,
+    limits: ProcessingLimits,
 }
 
 impl ImageProcessor {
     pub fn new(ocr: OcrProcessor) -> Self {
-        Self { ocr: Some(ocr) }
+        Self {
+            ocr: Some(ocr),
+            limits: ProcessingLimits::default(),
+        }
     }
 
     pub fn new_without_ocr() -> Self {
-        Self { ocr: None }
+        Self {
+            ocr: None,
+            limits: ProcessingLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: ProcessingLimits) -> Self {
+        self.limits = limits;
+        self
     }
 }
 
@@ -31,11 +45,14 @@ impl DocumentProcessor for ImageProcessor {
             source: e,
         })?;
 
+        // Decode every frame (multi-page TIFF yields one frame per page)
+        let frames = decode_frames(&image_data, &self.limits)?;
+
         // Perform OCR if available
-        let text = if let Some(ref ocr) = self.ocr {
-            ocr.process_image_bytes(&image_data)?
+        let pages = if let Some(ref ocr) = self.ocr {
+            ocr_frames(&frames, ocr)?
         } else {
-            String::new()
+            vec![String::new(); frames.len()]
         };
 
         let filename = path
@@ -46,14 +63,14 @@ impl DocumentProcessor for ImageProcessor {
 
         let metadata = DocumentMetadata::new(filename, DocumentFormat::Image);
 
-        // Create PDF with embedded image
-        let pdf_bytes = create_image_pdf(&image_data, path)?;
+        // Embed the original JPEG stream when it is a single-frame JPEG
+        let jpeg_data = (frames.len() == 1 && image_data.starts_with(&[0xFF, 0xD8, 0xFF]))
+            .then_some(image_data.as_slice());
 
-        Ok(ProcessedContent {
-            text,
-            pdf_bytes,
-            metadata,
-        })
+        // Create PDF with one page per frame
+        let pdf_bytes = create_image_pdf(&frames, jpeg_data)?;
+
+        Ok(ProcessedContent::from_pages(&pages, pdf_bytes, metadata))
     }
 
     fn supports(&self, format: DocumentFormat) -> bool {
@@ -61,115 +78,350 @@ impl DocumentProcessor for ImageProcessor {
     }
 }
 
-fn create_image_pdf(image_data: &[u8], path: &Path) -> Result<Vec<u8>, ProcessError> {
+/// Runs OCR on each frame, returning the text of each page. Pages that fail
+/// are left empty as long as one succeeds.
+///
+/// Exceeding the per-page timeout fails the whole document.
+fn ocr_frames(frames: &[DynamicImage], ocr: &OcrProcessor) -> Result<Vec<String>, ProcessError> {
+    if let [frame] = frames {
+        let frame = frame.clone();
+        return Ok(vec![
+            ocr.run_page(1, move |ocr| ocr.process_dynamic_image(&frame))?
+        ]);
+    }
+
+    let mut pages = Vec::with_capacity(frames.len());
+    let mut successes = 0;
+
+    for (index, frame) in frames.iter().enumerate() {
+        let frame = frame.clone();
+        match ocr.run_page(index + 1, move |ocr| ocr.process_dynamic_image(&frame)) {
+            Ok(page_text) => {
+                pages.push(page_text);
+                successes += 1;
+            }
+            Err(e @ ProcessError::LimitExceeded(_)) => return Err(e),
+            Err(e) => {
+                tracing::warn!(page = index + 1, "OCR failed for page: {}", e);
+                pages.push(String::new());
+            }
+        }
+    }
+
+    if successes == 0 && !frames.is_empty() {
+        return Err(ProcessError::OcrFailed(format!(
+            "OCR failed on all {} pages",
+            frames.len()
+        )));
+    }
+
+    Ok(pages)
+}
+
+/// Decodes an image file into its frames, sniffing the container from magic bytes.
+///
+/// The page limit is enforced before each frame is decoded, so an oversized
+/// multi-page file is rejected without decoding pages past the limit.
+fn decode_frames(
+    image_data: &[u8],
+    limits: &ProcessingLimits,
+) -> Result<Vec<DynamicImage>, ProcessError> {
+    if is_tiff(image_data) {
+        return decode_tiff_frames(image_data, limits);
+    }
+    if is_heif(image_data) {
+        return decode_heif_frames(image_data, limits);
+    }
+
+    limits.check_pages(1)?;
     let img = image::load_from_memory(image_data)
         .map_err(|e| ProcessError::ImageProcessing(format!("Failed to load image: {}", e)))?;
+    Ok(vec![img])
+}
 
-    let (width, height) = img.dimensions();
-    let rgb = img.to_rgb8();
+fn is_tiff(data: &[u8]) -> bool {
+    data.starts_with(b"II*\0") || data.starts_with(b"MM\0*")
+}
 
-    let mut doc = Document::with_version("1.5");
+/// `ftyp` brands used by HEIF-family containers (HEIC, HEIF, AVIF).
+const HEIF_BRANDS: &[&[u8]] = &[
+    b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx", b"mif1", b"msf1", b"avif", b"avis",
+];
 
-    let pages_id = doc.new_object_id();
-    let resources_id = doc.new_object_id();
-    let content_id = doc.new_object_id();
-    let page_id = doc.new_object_id();
-    let image_id = doc.new_object_id();
-
-    // Determine image format
-    let extension = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("png")
-        .to_lowercase();
-
-    // Create image XObject
-    let image_stream = if extension == "jpg" || extension == "jpeg" {
-        // Use JPEG data directly
-        Stream::new(
-            dictionary! {
-                "Type" => "XObject",
-                "Subtype" => "Image",
-                "Width" => width as i64,
-                "Height" => height as i64,
-                "ColorSpace" => "DeviceRGB",
-                "BitsPerComponent" => 8,
-                "Filter" => "DCTDecode",
-            },
-            image_data.to_vec(),
-        )
-    } else {
-        // Convert to raw RGB for other formats
-        Stream::new(
-            dictionary! {
-                "Type" => "XObject",
-                "Subtype" => "Image",
-                "Width" => width as i64,
-                "Height" => height as i64,
-                "ColorSpace" => "DeviceRGB",
-                "BitsPerComponent" => 8,
-            },
-            rgb.into_raw(),
-        )
+fn is_heif(data: &[u8]) -> bool {
+    data.len() >= 12 && &data[4..8] == b"ftyp" && HEIF_BRANDS.contains(&&data[8..12])
+}
+
+fn decode_tiff_frames(
+    image_data: &[u8],
+    limits: &ProcessingLimits,
+) -> Result<Vec<DynamicImage>, ProcessError> {
+    let mut decoder = tiff::decoder::Decoder::new(Cursor::new(image_data))
+        .map_err(|e| ProcessError::ImageProcessing(format!("Failed to open TIFF: {}", e)))?;
+
+    let mut frames = Vec::new();
+    loop {
+        let page = frames.len() + 1;
+        limits.check_pages(page)?;
+        let frame = decode_tiff_frame(&mut decoder).map_err(|e| {
+            ProcessError::ImageProcessing(format!("Failed to decode TIFF page {}: {}", page, e))
+        })?;
+        frames.push(frame);
+
+        if !decoder.more_images() {
+            break;
+        }
+        decoder.next_image().map_err(|e| {
+            ProcessError::ImageProcessing(format!("Failed to read TIFF page {}: {}", page + 1, e))
+        })?;
+    }
+
+    Ok(frames)
+}
+
+fn decode_tiff_frame<R: std::io::Read + std::io::Seek>(
+    decoder: &mut tiff::decoder::Decoder<R>,
+) -> Result<DynamicImage, String> {
+    use tiff::decoder::DecodingResult;
+    use tiff::ColorType;
+
+    let (width, height) = decoder.dimensions().map_err(|e| e.to_string())?;
+    let color_type = decoder.colortype().map_err(|e| e.to_string())?;
+    let data = decoder.read_image().map_err(|e| e.to_string())?;
+
+    let img = match (color_type, data) {
+        (ColorType::Gray(8), DecodingResult::U8(buf)) => {
+            image::GrayImage::from_raw(width, height, buf).map(DynamicImage::ImageLuma8)
+        }
+        (ColorType::Gray(bits @ (1 | 2 | 4)), DecodingResult::U8(buf)) => {
+            let pixels = unpack_gray(&buf, width, height, bits);
+            image::GrayImage::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8)
+        }
+        (ColorType::Gray(16), DecodingResult::U16(buf)) => {
+            image::ImageBuffer::from_raw(width, height, buf).map(DynamicImage::ImageLuma16)
+        }
+        (ColorType::GrayA(8), DecodingResult::U8(buf)) => {
+            image::GrayAlphaImage::from_raw(width, height, buf).map(DynamicImage::ImageLumaA8)
+        }
+        (ColorType::RGB(8), DecodingResult::U8(buf)) => {
+            image::RgbImage::from_raw(width, height, buf).map(DynamicImage::ImageRgb8)
+        }
+        (ColorType::RGB(16), DecodingResult::U16(buf)) => {
+            image::ImageBuffer::from_raw(width, height, buf).map(DynamicImage::ImageRgb16)
+        }
+        (ColorType::RGBA(8), DecodingResult::U8(buf)) => {
+            image::RgbaImage::from_raw(width, height, buf).map(DynamicImage::ImageRgba8)
+        }
+        (ColorType::RGBA(16), DecodingResult::U16(buf)) => {
+            image::ImageBuffer::from_raw(width, height, buf).map(DynamicImage::ImageRgba16)
+        }
+        (ColorType::CMYK(8), DecodingResult::U8(buf)) => {
+            let rgb = buf
+                .chunks_exact(4)
+                .flat_map(|px| {
+                    let k = 255 - px[3] as u32;
+                    [px[0], px[1], px[2]].map(|c| ((255 - c as u32) * k / 255) as u8)
+                })
+                .collect();
+            image::RgbImage::from_raw(width, height, rgb).map(DynamicImage::ImageRgb8)
+        }
+        (color_type, _) => {
+            return Err(format!("unsupported color type {:?}", color_type));
+        }
     };
 
-    doc.objects.insert(image_id, Object::Stream(image_stream));
+    img.ok_or_else(|| "pixel data does not match image dimensions".to_string())
+}
 
-    // Resources with image
-    doc.objects.insert(
-        resources_id,
-        Object::Dictionary(dictionary! {
-            "XObject" => dictionary! {
-                "Im1" => image_id,
-            },
-        }),
-    );
+/// Expands packed sub-byte grayscale samples (e.g. 1-bit fax pages) to 8-bit.
+fn unpack_gray(buf: &[u8], width: u32, height: u32, bits: u8) -> Vec<u8> {
+    let width = width as usize;
+    let bits = bits as usize;
+    let row_bytes = (width * bits).div_ceil(8);
+    let max = (1u16 << bits) - 1;
+
+    let mut pixels = Vec::with_capacity(width * height as usize);
+    for row in buf.chunks(row_bytes).take(height as usize) {
+        for x in 0..width {
+            let bit_offset = x * bits;
+            let byte = row.get(bit_offset / 8).copied().unwrap_or(0);
+            let shift = 8 - bits - (bit_offset % 8);
+            let sample = (byte >> shift) as u16 & max;
+            pixels.push((sample * 255 / max) as u8);
+        }
+    }
+    pixels
+}
 
-    // Scale image to fit page (US Letter: 612x792 points)
-    let page_width = 612.0_f64;
-    let page_height = 792.0_f64;
-    let margin = 36.0_f64; // 0.5 inch margin
+#[cfg(feature = "heif")]
+fn decode_heif_frames(
+    image_data: &[u8],
+    limits: &ProcessingLimits,
+) -> Result<Vec<DynamicImage>, ProcessError> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let lib_heif = LibHeif::new();
+    let ctx = HeifContext::read_from_bytes(image_data)
+        .map_err(|e| ProcessError::ImageProcessing(format!("Failed to open HEIF: {}", e)))?;
+    limits.check_pages(ctx.number_of_top_level_images())?;
+
+    let mut frames = Vec::new();
+    for handle in ctx.top_level_image_handles() {
+        let decoded = lib_heif
+            .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgb), None)
+            .map_err(|e| ProcessError::ImageProcessing(format!("Failed to decode HEIF: {}", e)))?;
+
+        let plane = decoded.planes().interleaved.ok_or_else(|| {
+            ProcessError::ImageProcessing("HEIF image has no interleaved RGB plane".to_string())
+        })?;
 
-    let available_width = page_width - 2.0 * margin;
-    let available_height = page_height - 2.0 * margin;
+        // Rows may be padded; copy only the visible pixels
+        let row_len = plane.width as usize * 3;
+        let pixels = plane
+            .data
+            .chunks(plane.stride)
+            .take(plane.height as usize)
+            .flat_map(|row| &row[..row_len])
+            .copied()
+            .collect();
+
+        let img =
+            image::RgbImage::from_raw(plane.width, plane.height, pixels).ok_or_else(|| {
+                ProcessError::ImageProcessing(
+                    "HEIF pixel data does not match dimensions".to_string(),
+                )
+            })?;
+        frames.push(DynamicImage::ImageRgb8(img));
+    }
 
-    let scale_x = available_width / width as f64;
-    let scale_y = available_height / height as f64;
-    let scale = scale_x.min(scale_y);
+    if frames.is_empty() {
+        return Err(ProcessError::ImageProcessing(
+            "HEIF file contains no images".to_string(),
+        ));
+    }
 
-    let img_width = (width as f64 * scale) as i64;
-    let img_height = (height as f64 * scale) as i64;
-    let x = ((page_width - img_width as f64) / 2.0) as i64;
-    let y = ((page_height - img_height as f64) / 2.0) as i64;
+    Ok(frames)
+}
 
-    // Content stream to draw image
-    let content = format!(
-        "q\n{} 0 0 {} {} {} cm\n/Im1 Do\nQ\n",
-        img_width, img_height, x, y
-    );
-    let content_stream = Stream::new(dictionary! {}, content.into_bytes());
-    doc.objects
-        .insert(content_id, Object::Stream(content_stream));
+#[cfg(not(feature = "heif"))]
+fn decode_heif_frames(
+    _image_data: &[u8],
+    _limits: &ProcessingLimits,
+) -> Result<Vec<DynamicImage>, ProcessError> {
+    Err(ProcessError::ImageProcessing(
+        "Cannot read HEIC/AVIF images: paporg was built without HEIF support \
+         (the `heif` feature, which needs libheif)"
+            .to_string(),
+    ))
+}
 
-    // Page
-    doc.objects.insert(
-        page_id,
-        Object::Dictionary(dictionary! {
-            "Type" => "Page",
-            "Parent" => pages_id,
-            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
-            "Resources" => resources_id,
-            "Contents" => content_id,
-        }),
-    );
+fn create_image_pdf(
+    frames: &[DynamicImage],
+    jpeg_data: Option<&[u8]>,
+) -> Result<Vec<u8>, ProcessError> {
+    let mut doc = Document::with_version("1.5");
+
+    let pages_id = doc.new_object_id();
+    let mut page_ids = Vec::with_capacity(frames.len());
+
+    for (index, img) in frames.iter().enumerate() {
+        let (width, height) = img.dimensions();
+
+        let resources_id = doc.new_object_id();
+        let content_id = doc.new_object_id();
+        let page_id = doc.new_object_id();
+        let image_id = doc.new_object_id();
+
+        // Create image XObject
+        let image_stream = match jpeg_data {
+            // Use JPEG data directly
+            Some(jpeg) if index == 0 => Stream::new(
+                dictionary! {
+                    "Type" => "XObject",
+                    "Subtype" => "Image",
+                    "Width" => width as i64,
+                    "Height" => height as i64,
+                    "ColorSpace" => "DeviceRGB",
+                    "BitsPerComponent" => 8,
+                    "Filter" => "DCTDecode",
+                },
+                jpeg.to_vec(),
+            ),
+            // Convert to raw RGB for other formats
+            _ => Stream::new(
+                dictionary! {
+                    "Type" => "XObject",
+                    "Subtype" => "Image",
+                    "Width" => width as i64,
+                    "Height" => height as i64,
+                    "ColorSpace" => "DeviceRGB",
+                    "BitsPerComponent" => 8,
+                },
+                img.to_rgb8().into_raw(),
+            ),
+        };
+
+        doc.objects.insert(image_id, Object::Stream(image_stream));
+
+        // Resources with image
+        doc.objects.insert(
+            resources_id,
+            Object::Dictionary(dictionary! {
+                "XObject" => dictionary! {
+                    "Im1" => image_id,
+                },
+            }),
+        );
+
+        // Scale image to fit page (US Letter: 612x792 points)
+        let page_width = 612.0_f64;
+        let page_height = 792.0_f64;
+        let margin = 36.0_f64; // 0.5 inch margin
+
+        let available_width = page_width - 2.0 * margin;
+        let available_height = page_height - 2.0 * margin;
+
+        let scale_x = available_width / width as f64;
+        let scale_y = available_height / height as f64;
+        let scale = scale_x.min(scale_y);
+
+        let img_width = (width as f64 * scale) as i64;
+        let img_height = (height as f64 * scale) as i64;
+        let x = ((page_width - img_width as f64) / 2.0) as i64;
+        let y = ((page_height - img_height as f64) / 2.0) as i64;
+
+        // Content stream to draw image
+        let content = format!(
+            "q\n{} 0 0 {} {} {} cm\n/Im1 Do\nQ\n",
+            img_width, img_height, x, y
+        );
+        let content_stream = Stream::new(dictionary! {}, content.into_bytes());
+        doc.objects
+            .insert(content_id, Object::Stream(content_stream));
+
+        // Page
+        doc.objects.insert(
+            page_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                "Resources" => resources_id,
+                "Contents" => content_id,
+            }),
+        );
+
+        page_ids.push(page_id);
+    }
 
     // Pages
+    let kids: Vec<Object> = page_ids.iter().map(|id| (*id).into()).collect();
     doc.objects.insert(
         pages_id,
         Object::Dictionary(dictionary! {
             "Type" => "Pages",
-            "Kids" => vec![page_id.into()],
-            "Count" => 1,
+            "Kids" => kids,
+            "Count" => page_ids.len() as i64,
         }),
     );
 
@@ -199,4 +451,146 @@ mod tests {
         assert!(!processor.supports(DocumentFormat::Text));
         assert!(!processor.supports(DocumentFormat::Docx));
     }
+
+    fn encode_gray_tiff(pages: &[(u32, u32, u8)]) -> Vec<u8> {
+        use tiff::encoder::{colortype, TiffEncoder};
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut buffer).unwrap();
+        for &(width, height, value) in pages {
+            let data = vec![value; (width * height) as usize];
+            encoder
+                .write_image::<colortype::Gray8>(width, height, &data)
+                .unwrap();
+        }
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn test_decode_multi_page_tiff() {
+        let data = encode_gray_tiff(&[(10, 20, 0), (30, 40, 255), (5, 5, 128)]);
+
+        let frames = decode_frames(&data, &ProcessingLimits::default()).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].dimensions(), (10, 20));
+        assert_eq!(frames[1].dimensions(), (30, 40));
+        assert_eq!(frames[2].to_luma8().get_pixel(0, 0).0, [128]);
+    }
+
+    #[test]
+    fn test_multi_page_tiff_becomes_multi_page_pdf() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("fax.tif");
+        std::fs::write(&path, encode_gray_tiff(&[(10, 10, 0), (10, 10, 255)])).unwrap();
+
+        let processor = ImageProcessor::new_without_ocr();
+        let result = processor.process(&path).unwrap();
+
+        let doc = Document::load_mem(&result.pdf_bytes).unwrap();
+        assert_eq!(doc.get_pages().len(), 2);
+        assert_eq!(result.metadata.format, DocumentFormat::Image);
+    }
+
+    #[test]
+    fn test_tiff_over_page_limit_is_rejected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("fax.tif");
+        std::fs::write(&path, encode_gray_tiff(&[(10, 10, 0), (10, 10, 255)])).unwrap();
+
+        let processor = ImageProcessor::new_without_ocr().with_limits(ProcessingLimits {
+            max_pages: Some(1),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            processor.process(&path),
+            Err(ProcessError::LimitExceeded(
+                crate::error::LimitViolation::Pages { pages: 2, limit: 1 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_tiff_page_limit_is_checked_before_decoding_the_page() {
+        use tiff::encoder::{colortype, TiffEncoder};
+
+        // The second page uses a color type decode_tiff_frame cannot decode
+        let mut buffer = Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut buffer).unwrap();
+        encoder
+            .write_image::<colortype::Gray8>(2, 2, &[0; 4])
+            .unwrap();
+        encoder
+            .write_image::<colortype::Gray32>(2, 2, &[0; 4])
+            .unwrap();
+        let data = buffer.into_inner();
+
+        let limits = ProcessingLimits {
+            max_pages: Some(1),
+            ..Default::default()
+        };
+        assert!(matches!(
+            decode_frames(&data, &limits),
+            Err(ProcessError::LimitExceeded(
+                crate::error::LimitViolation::Pages { pages: 2, limit: 1 }
+            ))
+        ));
+        assert!(matches!(
+            decode_frames(&data, &ProcessingLimits::default()),
+            Err(ProcessError::ImageProcessing(_))
+        ));
+    }
+
+    #[test]
+    fn test_single_frame_png_is_single_page_pdf() {
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::new_rgb8(4, 4)
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+
+        let frames = decode_frames(png.get_ref(), &ProcessingLimits::default()).unwrap();
+        let pdf = create_image_pdf(&frames, None).unwrap();
+
+        let doc = Document::load_mem(&pdf).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+    }
+
+    #[test]
+    fn test_unpack_one_bit_gray() {
+        // 10 pixels wide: rows are padded to 2 bytes
+        let buf = [0b1010_0000, 0b0100_0000, 0b1111_1111, 0b1100_0000];
+        let pixels = unpack_gray(&buf, 10, 2, 1);
+
+        assert_eq!(pixels.len(), 20);
+        assert_eq!(&pixels[..4], &[255, 0, 255, 0]);
+        assert_eq!(pixels[9], 255);
+        assert!(pixels[10..].iter().all(|&p| p == 255));
+    }
+
+    #[test]
+    fn test_detects_heif_brands() {
+        let heic = b"\0\0\0\x18ftypheic\0\0\0\0";
+        let avif = b"\0\0\0\x18ftypavif\0\0\0\0";
+        let mp4 = b"\0\0\0\x18ftypisom\0\0\0\0";
+
+        assert!(is_heif(heic));
+        assert!(is_heif(avif));
+        assert!(!is_heif(mp4));
+        assert!(!is_heif(b"II*\0"));
+        assert!(is_tiff(b"II*\0rest"));
+        assert!(is_tiff(b"MM\0*rest"));
+    }
+
+    #[cfg(not(feature = "heif"))]
+    #[test]
+    fn test_heif_without_feature_is_rejected() {
+        let result = decode_frames(b"\0\0\0\x18ftypheic\0\0\0\0", &ProcessingLimits::default());
+
+        match result {
+            Err(ProcessError::ImageProcessing(msg)) => {
+                assert!(msg.contains("built without HEIF support"))
+            }
+            _ => panic!("Expected ImageProcessing error"),
+        }
+    }
 }
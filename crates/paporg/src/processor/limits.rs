@@ -0,0 +1,127 @@
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::schema::LimitsConfig;
+use crate::error::{LimitViolation, ProcessError};
+
+/// Resource limits enforced while processing a single document.
+///
+/// `None` disables a limit. `ProcessingLimits::default()` has no limits;
+/// the limits of a configuration come from [`LimitsConfig`], whose defaults
+/// are 100 MB, 500 pages, 15 minutes per document and 2 minutes per page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessingLimits {
+    /// Largest accepted input file in bytes.
+    pub max_file_size: Option<u64>,
+    /// Largest accepted page count.
+    pub max_pages: Option<usize>,
+    /// Wall-clock timeout for the whole document.
+    pub job_timeout: Option<Duration>,
+    /// Timeout for rendering and OCR of a single page.
+    pub ocr_page_timeout: Option<Duration>,
+}
+
+impl ProcessingLimits {
+    pub fn from_config(config: &LimitsConfig) -> Self {
+        let secs = |s: u64| (s > 0).then(|| Duration::from_secs(s));
+        Self {
+            max_file_size: (config.max_file_size_mb > 0)
+                .then(|| config.max_file_size_mb.saturating_mul(1024 * 1024)),
+            max_pages: (config.max_pages > 0).then_some(config.max_pages),
+            job_timeout: secs(config.job_timeout_secs),
+            ocr_page_timeout: secs(config.ocr_page_timeout_secs),
+        }
+    }
+
+    /// Rejects files larger than `max_file_size`.
+    ///
+    /// Unreadable files pass so the processor can report the read error itself.
+    pub fn check_file_size(&self, path: &Path) -> Result<(), ProcessError> {
+        let Some(limit) = self.max_file_size else {
+            return Ok(());
+        };
+        match std::fs::metadata(path) {
+            Ok(meta) if meta.len() > limit => {
+                Err(ProcessError::LimitExceeded(LimitViolation::FileSize {
+                    size: meta.len(),
+                    limit,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects documents with more than `max_pages` pages.
+    pub fn check_pages(&self, pages: usize) -> Result<(), ProcessError> {
+        match self.max_pages {
+            Some(limit) if pages > limit => {
+                Err(ProcessError::LimitExceeded(LimitViolation::Pages {
+                    pages,
+                    limit,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config_zero_disables_limit() {
+        let limits = ProcessingLimits::from_config(&LimitsConfig {
+            max_file_size_mb: 0,
+            max_pages: 10,
+            job_timeout_secs: 0,
+            ocr_page_timeout_secs: 30,
+            ..Default::default()
+        });
+
+        assert_eq!(limits.max_file_size, None);
+        assert_eq!(limits.max_pages, Some(10));
+        assert_eq!(limits.job_timeout, None);
+        assert_eq!(limits.ocr_page_timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_check_file_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.txt");
+        std::fs::write(&path, vec![b'a'; 2048]).unwrap();
+
+        let limits = ProcessingLimits {
+            max_file_size: Some(1024),
+            ..Default::default()
+        };
+        match limits.check_file_size(&path) {
+            Err(ProcessError::LimitExceeded(LimitViolation::FileSize { size, limit })) => {
+                assert_eq!(size, 2048);
+                assert_eq!(limit, 1024);
+            }
+            other => panic!("Expected FileSize violation, got {:?}", other),
+        }
+
+        assert!(ProcessingLimits::default().check_file_size(&path).is_ok());
+        assert!(limits.check_file_size(&dir.path().join("missing")).is_ok());
+    }
+
+    #[test]
+    fn test_check_pages() {
+        let limits = ProcessingLimits {
+            max_pages: Some(5),
+            ..Default::default()
+        };
+        assert!(limits.check_pages(5).is_ok());
+        assert!(matches!(
+            limits.check_pages(6),
+            Err(ProcessError::LimitExceeded(LimitViolation::Pages {
+                pages: 6,
+                limit: 5
+            }))
+        ));
+    }
+}
@@ -1,68 +1,231 @@
 pub mod docx;
+mod font;
+pub mod html;
 pub mod image;
+pub mod limits;
 pub mod ocr;
+pub mod odt;
+pub mod pages;
 pub mod pdf;
+mod render;
+pub mod rtf;
+pub mod sandbox;
 pub mod text;
 
+use std::ops::Range;
 use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
 
 use crate::config::schema::{DocumentFormat, DocumentMetadata};
-use crate::error::ProcessError;
+use crate::error::{LimitViolation, ProcessError};
+
+pub use limits::ProcessingLimits;
+
+/// Separates the text of consecutive pages in [`ProcessedContent::text`].
+pub const PAGE_SEPARATOR: &str = "\n\n";
 
 pub struct ProcessedContent {
+    /// Extracted text, with the pages separated by [`PAGE_SEPARATOR`].
     pub text: String,
+    /// Byte range of each page in `text`.
+    pub pages: Vec<Range<usize>>,
     pub pdf_bytes: Vec<u8>,
     pub metadata: DocumentMetadata,
 }
 
+impl ProcessedContent {
+    /// Content whose text is a single page.
+    pub fn new(text: String, pdf_bytes: Vec<u8>, metadata: DocumentMetadata) -> Self {
+        let page = 0..text.len();
+        Self {
+            text,
+            pages: vec![page],
+            pdf_bytes,
+            metadata,
+        }
+    }
+
+    /// Content with the extracted text of each page.
+    pub fn from_pages<S: AsRef<str>>(
+        pages: &[S],
+        pdf_bytes: Vec<u8>,
+        metadata: DocumentMetadata,
+    ) -> Self {
+        let (text, pages) = pages::join_pages(pages);
+        Self {
+            text,
+            pages,
+            pdf_bytes,
+            metadata,
+        }
+    }
+
+    /// Returns the extracted text of each page.
+    pub fn page_texts(&self) -> impl Iterator<Item = &str> {
+        self.pages.iter().map(|page| &self.text[page.clone()])
+    }
+}
+
 pub trait DocumentProcessor: Send + Sync {
     fn process(&self, path: &Path) -> Result<ProcessedContent, ProcessError>;
     fn supports(&self, format: DocumentFormat) -> bool;
 }
 
 pub struct ProcessorRegistry {
-    processors: Vec<Box<dyn DocumentProcessor>>,
+    processors: Arc<Vec<Box<dyn DocumentProcessor>>>,
+    ocr_enabled: bool,
+    ocr_languages: Vec<String>,
+    ocr_dpi: u32,
+    limits: ProcessingLimits,
 }
 
 impl ProcessorRegistry {
     pub fn new(ocr_enabled: bool, ocr_languages: &[String], ocr_dpi: u32) -> Self {
+        Self::with_limits(
+            ocr_enabled,
+            ocr_languages,
+            ocr_dpi,
+            ProcessingLimits::default(),
+        )
+    }
+
+    /// Creates a registry that enforces per-document resource limits.
+    pub fn with_limits(
+        ocr_enabled: bool,
+        ocr_languages: &[String],
+        ocr_dpi: u32,
+        limits: ProcessingLimits,
+    ) -> Self {
         let mut processors: Vec<Box<dyn DocumentProcessor>> =
             vec![Box::new(text::TextProcessor::new())];
 
         if ocr_enabled {
-            let ocr = ocr::OcrProcessor::new(ocr_languages, ocr_dpi);
-            processors.push(Box::new(image::ImageProcessor::new(ocr.clone())));
-            processors.push(Box::new(pdf::PdfProcessor::new(Some(ocr.clone()))));
+            let ocr = ocr::OcrProcessor::new(ocr_languages, ocr_dpi)
+                .with_page_timeout(limits.ocr_page_timeout);
+            processors.push(Box::new(
+                image::ImageProcessor::new(ocr.clone()).with_limits(limits),
+            ));
+            processors.push(Box::new(
+                pdf::PdfProcessor::new(Some(ocr.clone())).with_limits(limits),
+            ));
             processors.push(Box::new(docx::DocxProcessor::new()));
         } else {
-            processors.push(Box::new(image::ImageProcessor::new_without_ocr()));
-            processors.push(Box::new(pdf::PdfProcessor::new(None)));
+            processors.push(Box::new(
+                image::ImageProcessor::new_without_ocr().with_limits(limits),
+            ));
+            processors.push(Box::new(pdf::PdfProcessor::new(None).with_limits(limits)));
             processors.push(Box::new(docx::DocxProcessor::new()));
         }
+        processors.push(Box::new(html::HtmlProcessor::new()));
+        processors.push(Box::new(rtf::RtfProcessor::new()));
+        processors.push(Box::new(odt::OdtProcessor::new()));
+
+        Self {
+            processors: Arc::new(processors),
+            ocr_enabled,
+            ocr_languages: ocr_languages.to_vec(),
+            ocr_dpi,
+            limits,
+        }
+    }
 
-        Self { processors }
+    pub fn limits(&self) -> &ProcessingLimits {
+        &self.limits
     }
 
+    /// Processes a document, enforcing the configured limits.
+    ///
+    /// Runs in a sandbox child process when [`sandbox::init`] was called,
+    /// otherwise on a helper thread bounded by the job timeout.
     pub fn process(&self, path: &Path) -> Result<ProcessedContent, ProcessError> {
-        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-
-        let format = DocumentFormat::from_extension(extension)
-            .ok_or_else(|| ProcessError::UnsupportedFormat(extension.to_string()))?;
-
-        let filename = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("<unknown>");
-        let _span = tracing::info_span!("processor.dispatch", format = ?format, filename).entered();
+        let format = Self::format_of(path)?;
+        self.limits.check_file_size(path)?;
+
+        if sandbox::is_enabled() {
+            let request = sandbox::SandboxRequest {
+                path: path.to_path_buf(),
+                ocr_enabled: self.ocr_enabled,
+                ocr_languages: self.ocr_languages.clone(),
+                ocr_dpi: self.ocr_dpi,
+                limits: self.limits,
+            };
+            return sandbox::process_isolated(&request);
+        }
 
-        for processor in &self.processors {
-            if processor.supports(format) {
-                return processor.process(path);
+        let Some(timeout) = self.limits.job_timeout else {
+            return self.dispatch(path, format);
+        };
+
+        // Without a sandbox a hung processor cannot be killed; the job still
+        // fails on time and the helper thread finishes in the background.
+        let processors = Arc::clone(&self.processors);
+        let owned_path = path.to_path_buf();
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("document-processor".to_string())
+            .spawn(move || {
+                let _ = tx.send(dispatch_to(&processors, &owned_path, format));
+            })
+            .map_err(|e| {
+                ProcessError::Sandbox(format!("Failed to spawn processing thread: {}", e))
+            })?;
+
+        match rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                tracing::warn!(path = %path.display(), "Document processing timed out");
+                Err(ProcessError::LimitExceeded(LimitViolation::JobTimeout {
+                    seconds: timeout.as_secs(),
+                }))
             }
+            Err(RecvTimeoutError::Disconnected) => Err(ProcessError::Sandbox(
+                "processing thread panicked".to_string(),
+            )),
         }
+    }
 
-        Err(ProcessError::UnsupportedFormat(extension.to_string()))
+    /// Processes a document on the calling thread, without sandbox or job timeout.
+    pub(crate) fn process_in_process(&self, path: &Path) -> Result<ProcessedContent, ProcessError> {
+        let format = Self::format_of(path)?;
+        self.limits.check_file_size(path)?;
+        self.dispatch(path, format)
     }
+
+    fn format_of(path: &Path) -> Result<DocumentFormat, ProcessError> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        DocumentFormat::from_extension(extension)
+            .ok_or_else(|| ProcessError::UnsupportedFormat(extension.to_string()))
+    }
+
+    fn dispatch(
+        &self,
+        path: &Path,
+        format: DocumentFormat,
+    ) -> Result<ProcessedContent, ProcessError> {
+        dispatch_to(&self.processors, path, format)
+    }
+}
+
+fn dispatch_to(
+    processors: &[Box<dyn DocumentProcessor>],
+    path: &Path,
+    format: DocumentFormat,
+) -> Result<ProcessedContent, ProcessError> {
+    let filename = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("<unknown>");
+    let _span = tracing::info_span!("processor.dispatch", format = ?format, filename).entered();
+
+    for processor in processors {
+        if processor.supports(format) {
+            return processor.process(path);
+        }
+    }
+
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    Err(ProcessError::UnsupportedFormat(extension.to_string()))
 }
 
 #[cfg(test)]
@@ -170,4 +333,39 @@ mod tests {
         let result = registry.process(Path::new("/nonexistent/path/file.txt"));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_registry_rejects_oversized_file() {
+        let limits = ProcessingLimits {
+            max_file_size: Some(4),
+            ..Default::default()
+        };
+        let registry = ProcessorRegistry::with_limits(false, &[], 300, limits);
+
+        let mut temp_file = NamedTempFile::with_suffix(".txt").unwrap();
+        writeln!(temp_file, "More than four bytes").unwrap();
+
+        assert!(matches!(
+            registry.process(temp_file.path()),
+            Err(ProcessError::LimitExceeded(LimitViolation::FileSize {
+                limit: 4,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_registry_with_job_timeout_processes_text() {
+        let limits = ProcessingLimits {
+            job_timeout: Some(std::time::Duration::from_secs(30)),
+            ..Default::default()
+        };
+        let registry = ProcessorRegistry::with_limits(false, &[], 300, limits);
+
+        let mut temp_file = NamedTempFile::with_suffix(".txt").unwrap();
+        writeln!(temp_file, "Within the time limit").unwrap();
+
+        let processed = registry.process(temp_file.path()).unwrap();
+        assert!(processed.text.contains("Within the time limit"));
+    }
 }
@@ -1,8 +1,10 @@
 use std::io::Cursor;
 use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::error::ProcessError;
+use crate::error::{LimitViolation, ProcessError};
 
 #[derive(Clone)]
 pub struct OcrProcessor {
@@ -12,6 +14,7 @@ pub struct OcrProcessor {
 struct OcrProcessorInner {
     languages: String,
     dpi: u32,
+    page_timeout: Option<Duration>,
 }
 
 impl OcrProcessor {
@@ -26,6 +29,18 @@ impl OcrProcessor {
             inner: Arc::new(OcrProcessorInner {
                 languages: lang_str,
                 dpi,
+                page_timeout: None,
+            }),
+        }
+    }
+
+    /// Sets the per-page timeout applied by [`OcrProcessor::run_page`].
+    pub fn with_page_timeout(self, page_timeout: Option<Duration>) -> Self {
+        Self {
+            inner: Arc::new(OcrProcessorInner {
+                languages: self.inner.languages.clone(),
+                dpi: self.inner.dpi,
+                page_timeout,
             }),
         }
     }
@@ -34,6 +49,49 @@ impl OcrProcessor {
         self.inner.dpi
     }
 
+    pub fn page_timeout(&self) -> Option<Duration> {
+        self.inner.page_timeout
+    }
+
+    /// Runs OCR work for one page, failing once the per-page timeout elapses.
+    ///
+    /// Tesseract cannot be interrupted, so a timed-out call is left to finish on
+    /// its own thread. Sandboxed processing exits the process, which reclaims it.
+    pub fn run_page<F>(&self, page: usize, ocr_fn: F) -> Result<String, ProcessError>
+    where
+        F: FnOnce(&OcrProcessor) -> Result<String, ProcessError> + Send + 'static,
+    {
+        let Some(timeout) = self.inner.page_timeout else {
+            return ocr_fn(self);
+        };
+
+        let ocr = self.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name(format!("ocr-page-{}", page))
+            .spawn(move || {
+                let _ = tx.send(ocr_fn(&ocr));
+            })
+            .map_err(|e| ProcessError::OcrFailed(format!("Failed to spawn OCR thread: {}", e)))?;
+
+        match rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                tracing::warn!(page, "OCR exceeded page timeout, abandoning");
+                Err(ProcessError::LimitExceeded(
+                    LimitViolation::OcrPageTimeout {
+                        page,
+                        seconds: timeout.as_secs(),
+                    },
+                ))
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(ProcessError::OcrFailed(format!(
+                "OCR thread for page {} panicked",
+                page
+            ))),
+        }
+    }
+
     pub fn process_image(&self, image_path: &Path) -> Result<String, ProcessError> {
         self.process_image_bytes(&std::fs::read(image_path).map_err(|e| {
             ProcessError::ReadDocument {
@@ -44,12 +102,17 @@ impl OcrProcessor {
     }
 
     pub fn process_image_bytes(&self, image_data: &[u8]) -> Result<String, ProcessError> {
-        let _span = tracing::info_span!("processor.ocr").entered();
-
         // Load image
         let img = image::load_from_memory(image_data)
             .map_err(|e| ProcessError::OcrFailed(format!("Failed to load image: {}", e)))?;
 
+        self.process_dynamic_image(&img)
+    }
+
+    /// Runs OCR on an already decoded image (e.g. a single TIFF frame).
+    pub fn process_dynamic_image(&self, img: &image::DynamicImage) -> Result<String, ProcessError> {
+        let _span = tracing::info_span!("processor.ocr").entered();
+
         // Convert to PNG in memory for leptess
         let mut png_data = Vec::new();
         let mut cursor = Cursor::new(&mut png_data);
@@ -154,4 +217,29 @@ mod tests {
         assert_eq!(processor.dpi(), cloned.dpi());
         assert_eq!(processor.inner.languages, cloned.inner.languages);
     }
+
+    #[test]
+    fn test_run_page_without_timeout_runs_inline() {
+        let processor = OcrProcessor::new(&["eng".to_string()], 300);
+        let result = processor.run_page(1, |_| Ok("text".to_string()));
+        assert_eq!(result.unwrap(), "text");
+    }
+
+    #[test]
+    fn test_run_page_timeout() {
+        let processor = OcrProcessor::new(&["eng".to_string()], 300)
+            .with_page_timeout(Some(Duration::from_millis(50)));
+        assert_eq!(processor.dpi(), 300);
+
+        let result = processor.run_page(3, |_| {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(String::new())
+        });
+        match result {
+            Err(ProcessError::LimitExceeded(LimitViolation::OcrPageTimeout { page, .. })) => {
+                assert_eq!(page, 3);
+            }
+            other => panic!("Expected OcrPageTimeout, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,254 @@
+use std::io::Read;
+use std::path::Path;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::config::schema::{DocumentFormat, DocumentMetadata};
+use crate::error::ProcessError;
+use crate::processor::{render, DocumentProcessor, ProcessedContent};
+
+pub struct OdtProcessor;
+
+impl OdtProcessor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for OdtProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DocumentProcessor for OdtProcessor {
+    fn process(&self, path: &Path) -> Result<ProcessedContent, ProcessError> {
+        let _span = tracing::info_span!("processor.odt").entered();
+        let file = std::fs::File::open(path).map_err(|e| ProcessError::ReadDocument {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| ProcessError::OdtProcessing(format!("Failed to open ODT: {}", e)))?;
+
+        let text = extract_odt_text(&mut archive)?;
+
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("document.odt")
+            .to_string();
+
+        let metadata = DocumentMetadata::new(filename, DocumentFormat::Odt);
+        let pdf_bytes = render::text_to_pdf(&text)?;
+
+        Ok(ProcessedContent::new(text, pdf_bytes, metadata))
+    }
+
+    fn supports(&self, format: DocumentFormat) -> bool {
+        matches!(format, DocumentFormat::Odt)
+    }
+}
+
+fn extract_odt_text<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<String, ProcessError> {
+    let mut content_xml = archive
+        .by_name("content.xml")
+        .map_err(|e| ProcessError::OdtProcessing(format!("Failed to find content.xml: {}", e)))?;
+
+    let mut xml_content = String::new();
+    content_xml
+        .read_to_string(&mut xml_content)
+        .map_err(|e| ProcessError::OdtProcessing(format!("Failed to read content.xml: {}", e)))?;
+
+    parse_odt_xml(&xml_content)
+}
+
+/// Number of spaces a `<text:s text:c="N"/>` element stands for.
+fn space_count(e: &BytesStart) -> usize {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == b"c")
+        .and_then(|a| std::str::from_utf8(&a.value).ok()?.parse().ok())
+        .unwrap_or(1)
+}
+
+fn parse_odt_xml(xml: &str) -> Result<String, ProcessError> {
+    let mut reader = Reader::from_str(xml);
+
+    let mut text = String::new();
+    // Set once <office:text> opens; text in styles and fonts is ignored
+    let mut in_body = false;
+    // Depth of elements whose content is not body text (notes, annotations)
+    let mut skip_depth = 0usize;
+    let mut paragraph_depth = 0usize;
+    let mut first_cell = true;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => {
+                let local_name = e.local_name();
+                if skip_depth > 0 {
+                    skip_depth += 1;
+                    continue;
+                }
+                match local_name.as_ref() {
+                    b"text" if !in_body => in_body = true,
+                    b"note" | b"annotation" | b"tracked-changes" => skip_depth = 1,
+                    b"p" | b"h" => paragraph_depth += 1,
+                    b"table-row" => first_cell = true,
+                    b"table-cell" => {
+                        if !first_cell {
+                            text.push('\t');
+                        }
+                        first_cell = false;
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Empty(ref e)) => {
+                if skip_depth > 0 || !in_body {
+                    continue;
+                }
+                match e.local_name().as_ref() {
+                    b"s" => text.push_str(&" ".repeat(space_count(e))),
+                    b"tab" => text.push('\t'),
+                    b"line-break" => text.push('\n'),
+                    b"table-cell" | b"covered-table-cell" => {
+                        if !first_cell {
+                            text.push('\t');
+                        }
+                        first_cell = false;
+                    }
+                    b"p" | b"h" => text.push('\n'),
+                    _ => {}
+                }
+            }
+            Ok(Event::End(ref e)) => {
+                if skip_depth > 0 {
+                    skip_depth -= 1;
+                    continue;
+                }
+                match e.local_name().as_ref() {
+                    b"p" | b"h" => {
+                        paragraph_depth = paragraph_depth.saturating_sub(1);
+                        // Paragraphs inside table cells share the cell's line
+                        if !text.ends_with('\t') {
+                            text.push('\n');
+                        }
+                    }
+                    b"table-row" => {
+                        while text.ends_with('\n') {
+                            text.pop();
+                        }
+                        text.push('\n');
+                    }
+                    b"table-cell" => {
+                        while text.ends_with('\n') {
+                            text.pop();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Text(e)) if in_body && skip_depth == 0 && paragraph_depth > 0 => {
+                let decoded = e.unescape().unwrap_or_default();
+                text.push_str(&decoded);
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(ProcessError::OdtProcessing(format!(
+                    "XML parsing error: {}",
+                    e
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const CONTENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <office:document-content
+            xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
+            xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+          <office:automatic-styles><style:style/></office:automatic-styles>
+          <office:body>
+            <office:text>
+              <text:h text:outline-level="1">Invoice</text:h>
+              <text:p>Amount:<text:s text:c="3"/>CHF 42<text:note><text:note-body><text:p>footnote</text:p></text:note-body></text:note></text:p>
+              <text:p>Line one<text:line-break/>Line two<text:tab/>tabbed</text:p>
+              <table:table>
+                <table:table-row>
+                  <table:table-cell><text:p>Item</text:p></table:table-cell>
+                  <table:table-cell><text:p>Price</text:p></table:table-cell>
+                </table:table-row>
+              </table:table>
+            </office:text>
+          </office:body>
+        </office:document-content>"#;
+
+    #[test]
+    fn test_supports_odt_format() {
+        let processor = OdtProcessor::new();
+        assert!(processor.supports(DocumentFormat::Odt));
+        assert!(!processor.supports(DocumentFormat::Docx));
+    }
+
+    #[test]
+    fn test_parse_odt_xml() {
+        let text = parse_odt_xml(CONTENT_XML).unwrap();
+        assert_eq!(
+            text,
+            "Invoice\nAmount:   CHF 42\nLine one\nLine two\ttabbed\nItem\tPrice\n"
+        );
+    }
+
+    #[test]
+    fn test_process_odt_file() {
+        let temp_file = tempfile::NamedTempFile::with_suffix(".odt").unwrap();
+        {
+            let mut writer = zip::ZipWriter::new(temp_file.reopen().unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file("mimetype", options).unwrap();
+            writer
+                .write_all(b"application/vnd.oasis.opendocument.text")
+                .unwrap();
+            writer.start_file("content.xml", options).unwrap();
+            writer.write_all(CONTENT_XML.as_bytes()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let processed = OdtProcessor::new().process(temp_file.path()).unwrap();
+        assert!(processed.text.contains("Amount:   CHF 42"));
+        assert_eq!(processed.metadata.format, DocumentFormat::Odt);
+        assert!(processed.pdf_bytes.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn test_missing_content_xml_is_an_error() {
+        let temp_file = tempfile::NamedTempFile::with_suffix(".odt").unwrap();
+        {
+            let mut writer = zip::ZipWriter::new(temp_file.reopen().unwrap());
+            writer
+                .start_file("mimetype", zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.finish().unwrap();
+        }
+
+        assert!(matches!(
+            OdtProcessor::new().process(temp_file.path()),
+            Err(ProcessError::OdtProcessing(_))
+        ));
+    }
+}
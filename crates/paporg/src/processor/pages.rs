@@ -0,0 +1,219 @@
+//! Page- and region-scoped views of extracted text.
+
+use std::ops::Range;
+
+use crate::config::schema::{PagePosition, PageRegion, PageSelection};
+use crate::processor::PAGE_SEPARATOR;
+
+/// Joins the text of pages with [`PAGE_SEPARATOR`], returning the text and
+/// the byte range of each page in it.
+pub fn join_pages<S: AsRef<str>>(pages: &[S]) -> (String, Vec<Range<usize>>) {
+    let mut text = String::new();
+    let mut ranges = Vec::with_capacity(pages.len());
+    for page in pages {
+        if !ranges.is_empty() {
+            text.push_str(PAGE_SEPARATOR);
+        }
+        let start = text.len();
+        text.push_str(page.as_ref());
+        ranges.push(start..text.len());
+    }
+    (text, ranges)
+}
+
+/// The part of a text selected by pages and a region, with the offsets of
+/// each piece in the original text.
+#[derive(Debug, Clone, Default)]
+pub struct ScopedText {
+    text: String,
+    /// Start of each piece in the scoped text, and its range in the original.
+    pieces: Vec<(usize, Range<usize>)>,
+}
+
+impl ScopedText {
+    /// Selects the pages, given as byte ranges of `text`, then the region of
+    /// each page. Without pages the whole text is one page. Selected pieces
+    /// are joined with [`PAGE_SEPARATOR`].
+    pub fn new(
+        text: &str,
+        pages: &[Range<usize>],
+        selection: Option<&PageSelection>,
+        region: Option<PageRegion>,
+    ) -> Self {
+        let whole = 0..text.len();
+        let page_ranges = if pages.is_empty() {
+            std::slice::from_ref(&whole)
+        } else {
+            pages
+        };
+
+        let count = page_ranges.len();
+        let selected =
+            page_ranges
+                .iter()
+                .cloned()
+                .enumerate()
+                .filter(|(index, _)| match selection {
+                    None => true,
+                    Some(PageSelection::Position(PagePosition::First)) => *index == 0,
+                    Some(PageSelection::Position(PagePosition::Last)) => *index + 1 == count,
+                    Some(PageSelection::Numbers(numbers)) => numbers.contains(&(index + 1)),
+                });
+
+        let mut scoped = Self::default();
+        for (_, page) in selected {
+            let range = match region {
+                Some(region) => region_of(text, page, region),
+                None => page,
+            };
+            if !scoped.pieces.is_empty() {
+                scoped.text.push_str(PAGE_SEPARATOR);
+            }
+            scoped.pieces.push((scoped.text.len(), range.clone()));
+            scoped.text.push_str(&text[range]);
+        }
+        scoped
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Maps a range of the scoped text back to the original text.
+    pub fn source_range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.source_offset(range.start, false);
+        let end = self.source_offset(range.end, true).max(start);
+        start..end
+    }
+
+    /// Maps an offset, attributing offsets on a piece boundary to the piece
+    /// they start (or, for ends, finish).
+    fn source_offset(&self, offset: usize, is_end: bool) -> usize {
+        let piece = self
+            .pieces
+            .iter()
+            .rev()
+            .find(|(start, _)| {
+                if is_end {
+                    *start < offset
+                } else {
+                    *start <= offset
+                }
+            })
+            .or(self.pieces.first());
+        match piece {
+            Some((start, source)) => (source.start + offset.saturating_sub(*start)).min(source.end),
+            None => offset,
+        }
+    }
+}
+
+/// Narrows a page to its first or last non-blank lines.
+fn region_of(text: &str, page: Range<usize>, region: PageRegion) -> Range<usize> {
+    let mut lines = Vec::new();
+    let mut start = page.start;
+    for line in text[page.clone()].split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if !content.trim().is_empty() {
+            lines.push(start..start + content.len());
+        }
+        start += line.len();
+    }
+
+    match region {
+        PageRegion::Top(0) | PageRegion::Bottom(0) => page.start..page.start,
+        PageRegion::Top(n) if n < lines.len() => page.start..lines[n - 1].end,
+        PageRegion::Bottom(n) if n < lines.len() => lines[lines.len() - n].start..page.end,
+        _ => page,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGES: [&str; 3] = [
+        "Acme GmbH\nInvoice\n\nItems",
+        "Page two\nsee Acme GmbH",
+        "Total 120.00\nThanks\n",
+    ];
+
+    fn scoped(selection: Option<PageSelection>, region: Option<PageRegion>) -> ScopedText {
+        let (text, pages) = join_pages(&PAGES);
+        ScopedText::new(&text, &pages, selection.as_ref(), region)
+    }
+
+    #[test]
+    fn test_join_pages() {
+        let (text, pages) = join_pages(&PAGES);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(&text[pages[1].clone()], PAGES[1]);
+        assert!(!text.contains('\x0c'));
+
+        let (text, pages) = join_pages(&["single"]);
+        assert_eq!(text, "single");
+        assert_eq!(pages, vec![0..6]);
+    }
+
+    #[test]
+    fn test_text_without_pages_is_one_page() {
+        let last = ScopedText::new(
+            "one\ntwo",
+            &[],
+            Some(&PageSelection::Position(PagePosition::Last)),
+            Some(PageRegion::Bottom(1)),
+        );
+        assert_eq!(last.text(), "two");
+    }
+
+    #[test]
+    fn test_page_selection() {
+        let first = scoped(Some(PageSelection::Position(PagePosition::First)), None);
+        assert_eq!(first.text(), "Acme GmbH\nInvoice\n\nItems");
+
+        let last = scoped(Some(PageSelection::Position(PagePosition::Last)), None);
+        assert_eq!(last.text(), "Total 120.00\nThanks\n");
+
+        let numbers = scoped(Some(PageSelection::Numbers(vec![2, 3, 7])), None);
+        assert_eq!(
+            numbers.text(),
+            "Page two\nsee Acme GmbH\n\nTotal 120.00\nThanks\n"
+        );
+
+        assert_eq!(scoped(None, None).text(), join_pages(&PAGES).0);
+    }
+
+    #[test]
+    fn test_regions_skip_blank_lines() {
+        let top = scoped(None, Some(PageRegion::Top(2)));
+        assert_eq!(
+            top.text(),
+            "Acme GmbH\nInvoice\n\nPage two\nsee Acme GmbH\n\nTotal 120.00\nThanks\n"
+        );
+
+        let bottom = scoped(
+            Some(PageSelection::Position(PagePosition::First)),
+            Some(PageRegion::Bottom(2)),
+        );
+        assert_eq!(bottom.text(), "Invoice\n\nItems");
+
+        let top_line = scoped(
+            Some(PageSelection::Position(PagePosition::Last)),
+            Some(PageRegion::Top(1)),
+        );
+        assert_eq!(top_line.text(), "Total 120.00");
+    }
+
+    #[test]
+    fn test_source_range() {
+        let (text, _) = join_pages(&PAGES);
+        let last = scoped(Some(PageSelection::Numbers(vec![2, 3])), None);
+        let start = last.text().find("Total").unwrap();
+        let range = last.source_range(start..start + 5);
+        assert_eq!(&text[range], "Total");
+
+        let acme = last.text().find("Acme").unwrap();
+        let range = last.source_range(acme..acme + 9);
+        assert_eq!(&text[range], "Acme GmbH");
+    }
+}
@@ -1,18 +1,30 @@
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::time::Duration;
 
 use crate::config::schema::{DocumentFormat, DocumentMetadata};
-use crate::error::ProcessError;
+use crate::error::{LimitViolation, ProcessError};
+use crate::processor::limits::ProcessingLimits;
 use crate::processor::ocr::OcrProcessor;
+use crate::processor::sandbox::{run_with_timeout, Completion};
 use crate::processor::{DocumentProcessor, ProcessedContent};
 
 pub struct PdfProcessor {
     ocr: Option<OcrProcessor>,
+    limits: ProcessingLimits,
 }
 
 impl PdfProcessor {
     pub fn new(ocr: Option<OcrProcessor>) -> Self {
-        Self { ocr }
+        Self {
+            ocr,
+            limits: ProcessingLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: ProcessingLimits) -> Self {
+        self.limits = limits;
+        self
     }
 }
 
@@ -25,21 +37,25 @@ impl DocumentProcessor for PdfProcessor {
             source: e,
         })?;
 
-        let text = match lopdf::Document::load_mem(&pdf_bytes) {
+        let mut pdf_metadata = None;
+        let pages = match lopdf::Document::load_mem(&pdf_bytes) {
             Ok(doc) => {
+                self.limits.check_pages(doc.get_pages().len())?;
+                pdf_metadata = document_info(&doc);
+
                 // Extract text from PDF
-                let mut text = extract_text_from_pdf(&doc)?;
+                let mut pages = extract_text_from_pdf(&doc)?;
 
                 // If no usable text was extracted and OCR is available, try OCR
-                if should_use_ocr(&text) {
+                if should_use_ocr(&pages.concat()) {
                     if let Some(ref ocr) = self.ocr {
                         let _ocr_span =
                             tracing::info_span!("processor.ocr_fallback", reason = "text_quality")
                                 .entered();
-                        text = self.ocr_pdf(&pdf_bytes, &doc, ocr)?;
+                        pages = self.ocr_pdf(&pdf_bytes, &doc, ocr)?;
                     }
                 }
-                text
+                pages
             }
             Err(e) => {
                 // lopdf can't parse this PDF (e.g. invalid cross-reference table).
@@ -71,13 +87,10 @@ impl DocumentProcessor for PdfProcessor {
             .unwrap_or("document.pdf")
             .to_string();
 
-        let metadata = DocumentMetadata::new(filename, DocumentFormat::Pdf);
+        let mut metadata = DocumentMetadata::new(filename, DocumentFormat::Pdf);
+        metadata.pdf_metadata = pdf_metadata;
 
-        Ok(ProcessedContent {
-            text,
-            pdf_bytes,
-            metadata,
-        })
+        Ok(ProcessedContent::from_pages(&pages, pdf_bytes, metadata))
     }
 
     fn supports(&self, format: DocumentFormat) -> bool {
@@ -91,7 +104,7 @@ impl PdfProcessor {
         pdf_bytes: &[u8],
         doc: &lopdf::Document,
         ocr: &OcrProcessor,
-    ) -> Result<String, ProcessError> {
+    ) -> Result<Vec<String>, ProcessError> {
         let page_count = doc.get_pages().len();
         self.ocr_pages(pdf_bytes, page_count, ocr)
     }
@@ -101,8 +114,9 @@ impl PdfProcessor {
         &self,
         pdf_bytes: &[u8],
         ocr: &OcrProcessor,
-    ) -> Result<String, ProcessError> {
+    ) -> Result<Vec<String>, ProcessError> {
         let page_count = count_pdf_pages(pdf_bytes)?;
+        self.limits.check_pages(page_count)?;
         self.ocr_pages(pdf_bytes, page_count, ocr)
     }
 
@@ -111,22 +125,25 @@ impl PdfProcessor {
         pdf_bytes: &[u8],
         page_count: usize,
         ocr: &OcrProcessor,
-    ) -> Result<String, ProcessError> {
-        let mut all_text = String::new();
+    ) -> Result<Vec<String>, ProcessError> {
+        let mut pages = Vec::with_capacity(page_count);
         let mut successes = 0;
 
         for page_num in 1..=page_count {
-            match render_pdf_page_to_image(pdf_bytes, page_num as u32, ocr.dpi()) {
-                Ok(image_data) => match ocr.process_image_bytes(&image_data) {
-                    Ok(page_text) => {
-                        all_text.push_str(&page_text);
-                        all_text.push('\n');
-                        successes += 1;
-                    }
-                    Err(e) => tracing::warn!(page = page_num, "OCR failed for page: {}", e),
-                },
+            let page_result =
+                render_pdf_page_to_image(pdf_bytes, page_num as u32, ocr.dpi(), ocr.page_timeout())
+                    .and_then(|image_data| {
+                        ocr.run_page(page_num, move |ocr| ocr.process_image_bytes(&image_data))
+                    });
+            match page_result {
+                Ok(page_text) => {
+                    pages.push(page_text);
+                    successes += 1;
+                }
+                Err(e @ ProcessError::LimitExceeded(_)) => return Err(e),
                 Err(e) => {
-                    tracing::warn!(page = page_num, "Failed to render page to image: {}", e)
+                    tracing::warn!(page = page_num, "OCR failed for page: {}", e);
+                    pages.push(String::new());
                 }
             }
         }
@@ -138,21 +155,52 @@ impl PdfProcessor {
             )));
         }
 
-        Ok(all_text)
+        Ok(pages)
     }
 }
 
-fn extract_text_from_pdf(doc: &lopdf::Document) -> Result<String, ProcessError> {
-    let mut text = String::new();
+/// Extracts the text of each page; pages without extractable text are empty.
+fn extract_text_from_pdf(doc: &lopdf::Document) -> Result<Vec<String>, ProcessError> {
+    Ok(doc
+        .get_pages()
+        .into_keys()
+        .map(|page_num| doc.extract_text(&[page_num]).unwrap_or_default())
+        .collect())
+}
+
+/// Renders the document information dictionary (title, author, producer
+/// and so on) as `Key: value` lines, or `None` if it has no text entries.
+fn document_info(doc: &lopdf::Document) -> Option<String> {
+    let info = doc.trailer.get_deref(b"Info", doc).ok()?.as_dict().ok()?;
+
+    let lines: Vec<String> = info
+        .iter()
+        .filter_map(|(key, value)| {
+            let lopdf::Object::String(bytes, _) = doc.dereference(value).ok()?.1 else {
+                return None;
+            };
+            let value = decode_text_string(bytes);
+            let value = value.trim();
+            (!value.is_empty()).then(|| format!("{}: {}", String::from_utf8_lossy(key), value))
+        })
+        .collect();
+
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
 
-    for (page_num, _) in doc.get_pages() {
-        if let Ok(page_text) = doc.extract_text(&[page_num]) {
-            text.push_str(&page_text);
-            text.push('\n');
+/// Decodes a PDF text string: UTF-16BE when it starts with a byte order
+/// mark, otherwise one character per byte.
+fn decode_text_string(bytes: &[u8]) -> String {
+    match bytes.strip_prefix(&[0xFE, 0xFF]) {
+        Some(utf16) => {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
         }
+        None => bytes.iter().map(|&b| char::from(b)).collect(),
     }
-
-    Ok(text)
 }
 
 /// Pattern for Identity-H Unimplemented errors (common with CID fonts).
@@ -223,42 +271,24 @@ fn count_pdf_pages(pdf_bytes: &[u8]) -> Result<usize, ProcessError> {
 
 /// Runs pdfinfo with a timeout and parses the page count from its output.
 fn run_pdfinfo_with_timeout(pdf_path: &std::path::Path) -> Result<usize, ProcessError> {
-    let mut child = Command::new("pdfinfo")
+    let mut command = Command::new("pdfinfo");
+    command
         .arg(pdf_path)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| {
-            ProcessError::PdfProcessing(format!(
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let output = match run_with_timeout(&mut command, Some(PDFINFO_TIMEOUT)) {
+        Ok(Completion::Finished(output)) => output,
+        Ok(Completion::TimedOut) => {
+            return Err(ProcessError::PdfProcessing("pdfinfo timed out".to_string()))
+        }
+        Err(e) => {
+            return Err(ProcessError::PdfProcessing(format!(
                 "Failed to run pdfinfo: {}. Make sure poppler-utils is installed.",
                 e
-            ))
-        })?;
-
-    let deadline = std::time::Instant::now() + PDFINFO_TIMEOUT;
-    loop {
-        match child.try_wait() {
-            Ok(Some(_)) => break,
-            Ok(None) => {
-                if std::time::Instant::now() >= deadline {
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    return Err(ProcessError::PdfProcessing("pdfinfo timed out".to_string()));
-                }
-                std::thread::sleep(std::time::Duration::from_millis(50));
-            }
-            Err(e) => {
-                return Err(ProcessError::PdfProcessing(format!(
-                    "Failed to wait for pdfinfo: {}",
-                    e
-                )));
-            }
+            )))
         }
-    }
-
-    let output = child.wait_with_output().map_err(|e| {
-        ProcessError::PdfProcessing(format!("Failed to read pdfinfo output: {}", e))
-    })?;
+    };
 
     if !output.status.success() {
         return Err(ProcessError::PdfProcessing(format!(
@@ -281,10 +311,18 @@ fn run_pdfinfo_with_timeout(pdf_path: &std::path::Path) -> Result<usize, Process
     Ok(1)
 }
 
+/// Timeout for rendering a single page when no per-page OCR timeout is configured.
+const PDFTOPPM_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Renders one PDF page to PNG via pdftoppm.
+///
+/// `page_timeout` is the configured per-page limit; exceeding it fails with
+/// [`LimitViolation::OcrPageTimeout`] so the whole job is aborted.
 fn render_pdf_page_to_image(
     pdf_bytes: &[u8],
     page_num: u32,
     dpi: u32,
+    page_timeout: Option<Duration>,
 ) -> Result<Vec<u8>, ProcessError> {
     // Use poppler/pdfimages via command line for rendering
     // This is a fallback approach - in production, you might use pdfium or similar
@@ -298,7 +336,8 @@ fn render_pdf_page_to_image(
         .map_err(|e| ProcessError::PdfProcessing(format!("Failed to write temp PDF: {}", e)))?;
 
     // Use pdftoppm to render page
-    let output = Command::new("pdftoppm")
+    let mut command = Command::new("pdftoppm");
+    command
         .args([
             "-png",
             "-r",
@@ -310,13 +349,35 @@ fn render_pdf_page_to_image(
             pdf_path.to_str().unwrap(),
             output_prefix.to_str().unwrap(),
         ])
-        .output()
-        .map_err(|e| {
-            ProcessError::PdfProcessing(format!(
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    let timeout = page_timeout.unwrap_or(PDFTOPPM_TIMEOUT);
+    let output = match run_with_timeout(&mut command, Some(timeout)) {
+        Ok(Completion::Finished(output)) => output,
+        Ok(Completion::TimedOut) => {
+            let _ = std::fs::remove_file(&pdf_path);
+            for path in page_image_candidates(&output_prefix, page_num) {
+                let _ = std::fs::remove_file(path);
+            }
+            return Err(match page_timeout {
+                Some(t) => ProcessError::LimitExceeded(LimitViolation::OcrPageTimeout {
+                    page: page_num as usize,
+                    seconds: t.as_secs(),
+                }),
+                None => {
+                    ProcessError::PdfProcessing(format!("pdftoppm timed out on page {}", page_num))
+                }
+            });
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&pdf_path);
+            return Err(ProcessError::PdfProcessing(format!(
                 "Failed to run pdftoppm: {}. Make sure poppler-utils is installed.",
                 e
-            ))
-        })?;
+            )));
+        }
+    };
 
     // Clean up temp PDF
     let _ = std::fs::remove_file(&pdf_path);
@@ -329,11 +390,7 @@ fn render_pdf_page_to_image(
     }
 
     // Find the output file (pdftoppm adds page number suffix)
-    let output_path = format!("{}-{}.png", output_prefix.display(), page_num);
-    let output_path_alt = format!("{}-{:02}.png", output_prefix.display(), page_num);
-    let output_path_alt2 = format!("{}-{:03}.png", output_prefix.display(), page_num);
-
-    let paths = [output_path, output_path_alt, output_path_alt2];
+    let paths = page_image_candidates(&output_prefix, page_num);
     let image_path = paths
         .iter()
         .find(|p| std::path::Path::new(p).exists())
@@ -351,6 +408,16 @@ fn render_pdf_page_to_image(
     Ok(image_data)
 }
 
+/// Output paths pdftoppm may use for a page, depending on the zero padding it picks.
+fn page_image_candidates(output_prefix: &Path, page_num: u32) -> [String; 3] {
+    let prefix = output_prefix.display();
+    [
+        format!("{}-{}.png", prefix, page_num),
+        format!("{}-{:02}.png", prefix, page_num),
+        format!("{}-{:03}.png", prefix, page_num),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -425,6 +492,14 @@ mod tests {
             "Pages" => pages_id,
         });
         doc.trailer.set("Root", catalog_id);
+        let info_id = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Policy 2024"),
+            "Author" => Object::String(
+                vec![0xFE, 0xFF, 0x00, 0x5A, 0x00, 0xFC, 0x00, 0x72, 0x00, 0x69],
+                lopdf::StringFormat::Hexadecimal,
+            ),
+        });
+        doc.trailer.set("Info", info_id);
 
         let mut pdf_bytes = Vec::new();
         doc.save_to(&mut pdf_bytes).unwrap();
@@ -440,6 +515,10 @@ mod tests {
         let processed = result.unwrap();
         assert!(!processed.pdf_bytes.is_empty());
         assert_eq!(processed.metadata.format, DocumentFormat::Pdf);
+
+        let info = processed.metadata.pdf_metadata.unwrap();
+        assert!(info.contains("Title: Policy 2024"));
+        assert!(info.contains("Author: Züri"));
     }
 
     #[test]
@@ -478,9 +557,7 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_empty_pdf_minimal() {
-        // Create a minimal empty PDF
+    fn minimal_pdf_bytes() -> Vec<u8> {
         use lopdf::{dictionary, Document, Object};
 
         let mut doc = Document::with_version("1.5");
@@ -513,9 +590,80 @@ mod tests {
 
         let mut pdf_bytes = Vec::new();
         doc.save_to(&mut pdf_bytes).unwrap();
+        pdf_bytes
+    }
+
+    /// A PDF with one page per text, in Courier.
+    fn text_pdf_bytes(texts: &[&str]) -> Vec<u8> {
+        use lopdf::{dictionary, Document, Object, Stream};
+
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let kids: Vec<Object> = texts
+            .iter()
+            .map(|text| {
+                let content = format!("BT /F1 12 Tf 50 700 Td ({}) Tj ET", text);
+                let content_id = doc.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                    "Resources" => resources_id,
+                    "Contents" => content_id,
+                })
+                .into()
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => kids.len() as i64,
+                "Kids" => kids,
+            }),
+        );
 
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut pdf_bytes = Vec::new();
+        doc.save_to(&mut pdf_bytes).unwrap();
+        pdf_bytes
+    }
+
+    #[test]
+    fn test_pdf_text_is_split_into_pages() {
         let temp_file = NamedTempFile::with_suffix(".pdf").unwrap();
-        std::fs::write(temp_file.path(), &pdf_bytes).unwrap();
+        std::fs::write(
+            temp_file.path(),
+            text_pdf_bytes(&["Invoice 42", "Total CHF 120"]),
+        )
+        .unwrap();
+
+        let processed = PdfProcessor::new(None).process(temp_file.path()).unwrap();
+
+        let pages: Vec<&str> = processed.page_texts().map(str::trim).collect();
+        assert_eq!(pages, ["Invoice 42", "Total CHF 120"]);
+        assert!(!processed.text.contains('\x0c'));
+    }
+
+    #[test]
+    fn test_empty_pdf_minimal() {
+        // Create a minimal empty PDF
+        let temp_file = NamedTempFile::with_suffix(".pdf").unwrap();
+        std::fs::write(temp_file.path(), minimal_pdf_bytes()).unwrap();
 
         let processor = PdfProcessor::new(None);
         let result = processor.process(temp_file.path());
@@ -526,6 +674,25 @@ mod tests {
         assert!(processed.text.trim().is_empty());
     }
 
+    #[test]
+    fn test_pdf_over_page_limit_is_rejected() {
+        let temp_file = NamedTempFile::with_suffix(".pdf").unwrap();
+        std::fs::write(temp_file.path(), minimal_pdf_bytes()).unwrap();
+
+        let processor = PdfProcessor::new(None).with_limits(ProcessingLimits {
+            max_pages: Some(0),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            processor.process(temp_file.path()),
+            Err(ProcessError::LimitExceeded(LimitViolation::Pages {
+                pages: 1,
+                limit: 0
+            }))
+        ));
+    }
+
     // ============================================
     // should_use_ocr tests
     // ============================================
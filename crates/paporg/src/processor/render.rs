@@ -0,0 +1,1008 @@
+//! Lays out extracted document content as a paginated, readable PDF.
+//!
+//! Text is set in an embedded Unicode TrueType font when one is installed
+//! (see [`font`]), falling back to the standard Helvetica fonts otherwise.
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use lopdf::{dictionary, Document, Object, ObjectId, Stream, StringFormat};
+
+use crate::error::ProcessError;
+use crate::processor::font::{self, TrueTypeFont};
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 50.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const TOP: f32 = PAGE_HEIGHT - MARGIN;
+
+const BODY_SIZE: f32 = 11.0;
+const BODY_LEADING: f32 = 14.0;
+const TABLE_SIZE: f32 = 10.0;
+const TABLE_LEADING: f32 = 12.0;
+const CELL_PADDING: f32 = 4.0;
+const LIST_INDENT: f32 = 18.0;
+const TAB_WIDTH: usize = 4;
+
+/// Average Helvetica glyph width in 1/1000 em, used when no TrueType font is available.
+const STANDARD_ADVANCE: f32 = 520.0;
+const STANDARD_BOLD_ADVANCE: f32 = 560.0;
+
+/// A run of text sharing one style.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Span {
+    pub text: String,
+    pub bold: bool,
+}
+
+impl Span {
+    pub fn new(text: impl Into<String>, bold: bool) -> Self {
+        Self {
+            text: text.into(),
+            bold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ParagraphStyle {
+    Body,
+    /// Heading level, starting at 1.
+    Heading(u8),
+    /// List item nesting level, starting at 0.
+    ListItem(u8),
+    /// A single line of plain text, laid out without paragraph spacing.
+    Line,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Paragraph {
+    pub style: ParagraphStyle,
+    pub spans: Vec<Span>,
+}
+
+impl Paragraph {
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+}
+
+/// An embedded picture with its intended display size in points, if known.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Picture {
+    pub data: Vec<u8>,
+    pub size: Option<(f32, f32)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Block {
+    Paragraph(Paragraph),
+    /// Rows of cells; each cell holds its paragraphs.
+    Table(Vec<Vec<Vec<Paragraph>>>),
+    Picture(Picture),
+}
+
+/// Lays `text` out line by line, wrapping long lines.
+pub(crate) fn text_to_pdf(text: &str) -> Result<Vec<u8>, ProcessError> {
+    let blocks: Vec<Block> = text
+        .lines()
+        .map(|line| {
+            Block::Paragraph(Paragraph {
+                style: ParagraphStyle::Line,
+                spans: vec![Span::new(line, false)],
+            })
+        })
+        .collect();
+    blocks_to_pdf(&blocks)
+}
+
+/// Lays out structured content on Letter pages.
+pub(crate) fn blocks_to_pdf(blocks: &[Block]) -> Result<Vec<u8>, ProcessError> {
+    let mut layout = Layout::new(Fonts::detect());
+    for block in blocks {
+        match block {
+            Block::Paragraph(paragraph) => layout.paragraph(paragraph),
+            Block::Table(rows) => layout.table(rows),
+            Block::Picture(picture) => layout.picture(picture),
+        }
+    }
+    layout.finish()
+}
+
+/// Where a font's glyphs come from.
+#[derive(Clone, Copy)]
+enum Face {
+    Embedded(&'static TrueTypeFont),
+    Standard { name: &'static str, advance: f32 },
+}
+
+struct FontSlot {
+    face: Face,
+    resource: &'static str,
+    /// Glyphs drawn with this font, mapped back to their characters.
+    used: BTreeMap<u16, char>,
+}
+
+impl FontSlot {
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units: f32 = match self.face {
+            Face::Embedded(font) => text.chars().map(|c| font.advance(font.glyph_id(c))).sum(),
+            Face::Standard { advance, .. } => text.chars().count() as f32 * advance,
+        };
+        units * size / 1000.0
+    }
+
+    /// Encodes text as a PDF string operand for `Tj`.
+    fn encode(&mut self, text: &str) -> Vec<u8> {
+        match self.face {
+            Face::Embedded(font) => {
+                let mut out = vec![b'<'];
+                for c in text.chars() {
+                    let gid = font.glyph_id(c);
+                    self.used.entry(gid).or_insert(c);
+                    out.extend_from_slice(format!("{:04X}", gid).as_bytes());
+                }
+                out.push(b'>');
+                out
+            }
+            Face::Standard { .. } => {
+                let mut out = vec![b'('];
+                out.extend_from_slice(&encode_pdf_string(text));
+                out.push(b')');
+                out
+            }
+        }
+    }
+}
+
+/// Regular and bold fonts; both may share one slot.
+struct Fonts {
+    slots: Vec<FontSlot>,
+    bold: usize,
+}
+
+impl Fonts {
+    fn detect() -> Self {
+        match (font::regular(), font::bold()) {
+            (Some(regular), bold) => {
+                let mut slots = vec![FontSlot {
+                    face: Face::Embedded(regular),
+                    resource: "F1",
+                    used: BTreeMap::new(),
+                }];
+                if let Some(bold) = bold {
+                    slots.push(FontSlot {
+                        face: Face::Embedded(bold),
+                        resource: "F2",
+                        used: BTreeMap::new(),
+                    });
+                }
+                Self {
+                    bold: slots.len() - 1,
+                    slots,
+                }
+            }
+            (None, _) => Self::standard(),
+        }
+    }
+
+    fn standard() -> Self {
+        Self {
+            slots: vec![
+                FontSlot {
+                    face: Face::Standard {
+                        name: "Helvetica",
+                        advance: STANDARD_ADVANCE,
+                    },
+                    resource: "F1",
+                    used: BTreeMap::new(),
+                },
+                FontSlot {
+                    face: Face::Standard {
+                        name: "Helvetica-Bold",
+                        advance: STANDARD_BOLD_ADVANCE,
+                    },
+                    resource: "F2",
+                    used: BTreeMap::new(),
+                },
+            ],
+            bold: 1,
+        }
+    }
+
+    fn slot(&self, bold: bool) -> usize {
+        if bold {
+            self.bold
+        } else {
+            0
+        }
+    }
+
+    fn width(&self, text: &str, bold: bool, size: f32) -> f32 {
+        self.slots[self.slot(bold)].width(text, size)
+    }
+}
+
+/// One wrapped line: styled segments in drawing order.
+type Line = Vec<Span>;
+
+struct StyleMetrics {
+    size: f32,
+    leading: f32,
+    space_before: f32,
+    space_after: f32,
+    indent: f32,
+    bold: bool,
+}
+
+fn metrics(style: ParagraphStyle) -> StyleMetrics {
+    let body = StyleMetrics {
+        size: BODY_SIZE,
+        leading: BODY_LEADING,
+        space_before: 0.0,
+        space_after: 6.0,
+        indent: 0.0,
+        bold: false,
+    };
+    match style {
+        ParagraphStyle::Body => body,
+        ParagraphStyle::Line => StyleMetrics {
+            space_after: 0.0,
+            ..body
+        },
+        ParagraphStyle::ListItem(level) => StyleMetrics {
+            space_after: 3.0,
+            indent: LIST_INDENT * (level as f32 + 1.0),
+            ..body
+        },
+        ParagraphStyle::Heading(level) => {
+            let (size, space_before) = match level {
+                1 => (18.0, 10.0),
+                2 => (15.0, 8.0),
+                3 => (13.0, 6.0),
+                _ => (12.0, 6.0),
+            };
+            StyleMetrics {
+                size,
+                leading: (size * 1.25_f32).round(),
+                space_before,
+                space_after: 4.0,
+                indent: 0.0,
+                bold: true,
+            }
+        }
+    }
+}
+
+struct Layout {
+    fonts: Fonts,
+    pages: Vec<Vec<u8>>,
+    content: Vec<u8>,
+    /// Top of the free space on the current page.
+    y: f32,
+    pictures: Vec<Stream>,
+}
+
+impl Layout {
+    fn new(fonts: Fonts) -> Self {
+        Self {
+            fonts,
+            pages: Vec::new(),
+            content: Vec::new(),
+            y: TOP,
+            pictures: Vec::new(),
+        }
+    }
+
+    fn at_page_top(&self) -> bool {
+        self.y >= TOP
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.content));
+        self.y = TOP;
+    }
+
+    /// Starts a new page unless `height` fits below the cursor.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN && !self.at_page_top() {
+            self.new_page();
+        }
+    }
+
+    fn paragraph(&mut self, paragraph: &Paragraph) {
+        let m = metrics(paragraph.style);
+        if !self.at_page_top() {
+            self.y -= m.space_before;
+        }
+
+        let spans: Vec<Span> = paragraph
+            .spans
+            .iter()
+            .map(|s| Span::new(expand_tabs(&s.text), s.bold || m.bold))
+            .collect();
+        let lines = wrap(&self.fonts, &spans, m.size, CONTENT_WIDTH - m.indent);
+
+        for (i, line) in lines.iter().enumerate() {
+            self.ensure_space(m.leading);
+            let baseline = self.y - m.size;
+            if i == 0 && matches!(paragraph.style, ParagraphStyle::ListItem(_)) {
+                let bullet = [Span::new("\u{2022}", false)];
+                self.draw_line(&bullet, MARGIN + m.indent - 12.0, baseline, m.size);
+            }
+            self.draw_line(line, MARGIN + m.indent, baseline, m.size);
+            self.y -= m.leading;
+        }
+
+        self.y -= m.space_after;
+    }
+
+    fn table(&mut self, rows: &[Vec<Vec<Paragraph>>]) {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let column_width = CONTENT_WIDTH / columns as f32;
+        let text_width = column_width - 2.0 * CELL_PADDING;
+        let max_lines = ((TOP - MARGIN - 2.0 * CELL_PADDING) / TABLE_LEADING) as usize;
+
+        if !self.at_page_top() {
+            self.y -= 4.0;
+        }
+
+        for row in rows {
+            let cells: Vec<Vec<Line>> = row
+                .iter()
+                .map(|cell| {
+                    let mut lines: Vec<Line> = cell
+                        .iter()
+                        .flat_map(|p| {
+                            let bold = matches!(p.style, ParagraphStyle::Heading(_));
+                            let spans: Vec<Span> = p
+                                .spans
+                                .iter()
+                                .map(|s| Span::new(expand_tabs(&s.text), s.bold || bold))
+                                .collect();
+                            wrap(&self.fonts, &spans, TABLE_SIZE, text_width)
+                        })
+                        .collect();
+                    // Rows taller than a page are cut off
+                    lines.truncate(max_lines);
+                    lines
+                })
+                .collect();
+
+            let line_count = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+            let height = line_count as f32 * TABLE_LEADING + 2.0 * CELL_PADDING;
+            self.ensure_space(height);
+
+            self.content.extend_from_slice(b"q 0.5 w 0.6 G\n");
+            for column in 0..columns {
+                let x = MARGIN + column as f32 * column_width;
+                self.content.extend_from_slice(
+                    format!(
+                        "{:.2} {:.2} {:.2} {:.2} re S\n",
+                        x,
+                        self.y - height,
+                        column_width,
+                        height
+                    )
+                    .as_bytes(),
+                );
+            }
+            self.content.extend_from_slice(b"Q\n");
+
+            for (column, lines) in cells.iter().enumerate() {
+                let x = MARGIN + column as f32 * column_width + CELL_PADDING;
+                let mut y = self.y - CELL_PADDING;
+                for line in lines {
+                    self.draw_line(line, x, y - TABLE_SIZE, TABLE_SIZE);
+                    y -= TABLE_LEADING;
+                }
+            }
+
+            self.y -= height;
+        }
+
+        self.y -= 6.0;
+    }
+
+    fn picture(&mut self, picture: &Picture) {
+        let Some((stream, pixel_width, pixel_height)) = image_xobject(&picture.data) else {
+            tracing::debug!("Skipping unsupported embedded image");
+            return;
+        };
+
+        // Pixels are assumed to be 96 dpi when the document gives no size
+        let (mut width, mut height) = picture
+            .size
+            .filter(|(w, h)| *w > 0.0 && *h > 0.0)
+            .unwrap_or((pixel_width as f32 * 0.75, pixel_height as f32 * 0.75));
+        let scale = (CONTENT_WIDTH / width)
+            .min((TOP - MARGIN) / height)
+            .min(1.0);
+        width *= scale;
+        height *= scale;
+
+        self.ensure_space(height);
+        let name = format!("Im{}", self.pictures.len() + 1);
+        self.pictures.push(stream);
+        self.content.extend_from_slice(
+            format!(
+                "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /{} Do Q\n",
+                width,
+                height,
+                MARGIN,
+                self.y - height,
+                name
+            )
+            .as_bytes(),
+        );
+        self.y -= height + 6.0;
+    }
+
+    fn draw_line(&mut self, line: &[Span], x: f32, baseline: f32, size: f32) {
+        if line.iter().all(|s| s.text.is_empty()) {
+            return;
+        }
+        self.content.extend_from_slice(b"BT\n");
+        self.content
+            .extend_from_slice(format!("{:.2} {:.2} Td\n", x, baseline).as_bytes());
+        for segment in line {
+            let slot = self.fonts.slot(segment.bold);
+            let font = &mut self.fonts.slots[slot];
+            self.content
+                .extend_from_slice(format!("/{} {} Tf\n", font.resource, size).as_bytes());
+            self.content.extend_from_slice(&font.encode(&segment.text));
+            self.content.extend_from_slice(b" Tj\n");
+        }
+        self.content.extend_from_slice(b"ET\n");
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, ProcessError> {
+        // Always emit at least one (blank) page
+        if !self.content.is_empty() || self.pages.is_empty() {
+            self.new_page();
+        }
+
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+
+        let mut fonts = lopdf::Dictionary::new();
+        for slot in &self.fonts.slots {
+            let font_id = match slot.face {
+                Face::Standard { name, .. } => doc.add_object(dictionary! {
+                    "Type" => "Font",
+                    "Subtype" => "Type1",
+                    "BaseFont" => name,
+                    "Encoding" => "WinAnsiEncoding",
+                }),
+                Face::Embedded(_) if slot.used.is_empty() => continue,
+                Face::Embedded(font) => embed_font(&mut doc, font, &slot.used)?,
+            };
+            fonts.set(slot.resource, font_id);
+        }
+
+        let mut xobjects = lopdf::Dictionary::new();
+        for (i, stream) in self.pictures.into_iter().enumerate() {
+            let id = doc.add_object(stream);
+            xobjects.set(format!("Im{}", i + 1), id);
+        }
+
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => fonts,
+            "XObject" => xobjects,
+        });
+
+        let mut page_ids = Vec::new();
+        for content in self.pages {
+            let mut stream = Stream::new(dictionary! {}, content);
+            let _ = stream.compress();
+            let content_id = doc.add_object(stream);
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+                "Resources" => resources_id,
+                "Contents" => content_id,
+            });
+            page_ids.push(page_id);
+        }
+
+        // Pages
+        let kids: Vec<Object> = page_ids.iter().map(|id| (*id).into()).collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => page_ids.len() as i64,
+            }),
+        );
+
+        // Catalog
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut buffer = Vec::new();
+        doc.save_to(&mut buffer)
+            .map_err(|e| ProcessError::PdfProcessing(e.to_string()))?;
+
+        Ok(buffer)
+    }
+}
+
+/// Embeds a subset of `font` as a Type0 font with Identity-H encoding.
+fn embed_font(
+    doc: &mut Document,
+    font: &TrueTypeFont,
+    used: &BTreeMap<u16, char>,
+) -> Result<ObjectId, ProcessError> {
+    let subset = font.subset(&used.keys().copied().collect());
+    let mut font_file = Stream::new(dictionary! { "Length1" => subset.len() as i64 }, subset);
+    font_file
+        .compress()
+        .map_err(|e| ProcessError::PdfProcessing(e.to_string()))?;
+    let font_file_id = doc.add_object(font_file);
+
+    // Subset fonts are tagged with six uppercase letters
+    let base_font = format!("PAPORG+{}", font.name());
+    let bbox: Vec<Object> = font.bbox().iter().map(|&v| v.into()).collect();
+    let descriptor_id = doc.add_object(dictionary! {
+        "Type" => "FontDescriptor",
+        "FontName" => Object::Name(base_font.clone().into_bytes()),
+        "Flags" => 32,
+        "FontBBox" => bbox,
+        "ItalicAngle" => 0,
+        "Ascent" => font.ascent(),
+        "Descent" => font.descent(),
+        "CapHeight" => font.ascent(),
+        "StemV" => 80,
+        "FontFile2" => font_file_id,
+    });
+
+    let mut widths = Vec::new();
+    for &gid in used.keys() {
+        widths.push(Object::Integer(gid as i64));
+        widths.push(Object::Array(vec![Object::Integer(
+            font.advance(gid).round() as i64,
+        )]));
+    }
+
+    let cid_font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "CIDFontType2",
+        "BaseFont" => Object::Name(base_font.clone().into_bytes()),
+        "CIDSystemInfo" => dictionary! {
+            "Registry" => Object::String(b"Adobe".to_vec(), StringFormat::Literal),
+            "Ordering" => Object::String(b"Identity".to_vec(), StringFormat::Literal),
+            "Supplement" => 0,
+        },
+        "FontDescriptor" => descriptor_id,
+        "DW" => 1000,
+        "W" => widths,
+        "CIDToGIDMap" => "Identity",
+    });
+
+    let mut to_unicode = Stream::new(dictionary! {}, to_unicode_cmap(used));
+    let _ = to_unicode.compress();
+    let to_unicode_id = doc.add_object(to_unicode);
+
+    Ok(doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type0",
+        "BaseFont" => Object::Name(base_font.into_bytes()),
+        "Encoding" => "Identity-H",
+        "DescendantFonts" => vec![Object::Reference(cid_font_id)],
+        "ToUnicode" => to_unicode_id,
+    }))
+}
+
+/// Builds a ToUnicode CMap so text in the PDF stays searchable and copyable.
+fn to_unicode_cmap(used: &BTreeMap<u16, char>) -> Vec<u8> {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+
+    let entries: Vec<(&u16, &char)> = used.iter().filter(|(gid, _)| **gid != 0).collect();
+    for chunk in entries.chunks(100) {
+        cmap.push_str(&format!("{} beginbfchar\n", chunk.len()));
+        for (gid, c) in chunk {
+            let mut units = [0u16; 2];
+            let hex: String = c
+                .encode_utf16(&mut units)
+                .iter()
+                .map(|u| format!("{:04X}", u))
+                .collect();
+            cmap.push_str(&format!("<{:04X}> <{}>\n", gid, hex));
+        }
+        cmap.push_str("endbfchar\n");
+    }
+
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap.into_bytes()
+}
+
+/// Converts an embedded image into a PDF image XObject.
+///
+/// Baseline JPEGs are stored as-is; everything else is decoded and stored as
+/// compressed RGB, with transparency flattened onto white.
+fn image_xobject(data: &[u8]) -> Option<(Stream, u32, u32)> {
+    let format = image::guess_format(data).ok()?;
+    let mut reader = image::io::Reader::new(Cursor::new(data));
+    reader.set_format(format);
+    let decoded = reader.decode().ok()?;
+    let (width, height) = decoded.dimensions();
+
+    let passthrough = match (format, &decoded) {
+        (ImageFormat::Jpeg, DynamicImage::ImageLuma8(_)) => Some("DeviceGray"),
+        (ImageFormat::Jpeg, DynamicImage::ImageRgb8(_)) => Some("DeviceRGB"),
+        _ => None,
+    };
+    if let Some(color_space) = passthrough {
+        let stream = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => width as i64,
+                "Height" => height as i64,
+                "ColorSpace" => color_space,
+                "BitsPerComponent" => 8,
+                "Filter" => "DCTDecode",
+            },
+            data.to_vec(),
+        );
+        return Some((stream, width, height));
+    }
+
+    let rgba = decoded.to_rgba8();
+    let mut rgb = Vec::with_capacity((width * height * 3) as usize);
+    for pixel in rgba.pixels() {
+        let [r, g, b, a] = pixel.0;
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        rgb.extend_from_slice(&[blend(r), blend(g), blend(b)]);
+    }
+
+    let mut stream = Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => width as i64,
+            "Height" => height as i64,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 8,
+        },
+        rgb,
+    );
+    stream.compress().ok()?;
+    Some((stream, width, height))
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// Word-wraps styled spans to `width` points. `\n` forces a line break.
+fn wrap(fonts: &Fonts, spans: &[Span], size: f32, width: f32) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut line: Line = Vec::new();
+    let mut line_width = 0.0;
+    // Spaces are held back so they can be dropped at wrapped line ends
+    let mut pending_spaces: Option<Span> = None;
+
+    fn push(line: &mut Line, text: &str, bold: bool) {
+        match line.last_mut() {
+            Some(last) if last.bold == bold => last.text.push_str(text),
+            _ => line.push(Span::new(text, bold)),
+        }
+    }
+
+    for span in spans {
+        for (i, segment) in span.text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0.0;
+                pending_spaces = None;
+            }
+
+            for token in split_spaces(segment) {
+                if token.starts_with(' ') {
+                    let spaces = pending_spaces.get_or_insert_with(|| Span::new("", span.bold));
+                    spaces.text.push_str(token);
+                    continue;
+                }
+
+                let word_width = fonts.width(token, span.bold, size);
+                let space_width = pending_spaces
+                    .as_ref()
+                    .map_or(0.0, |s| fonts.width(&s.text, s.bold, size));
+                if !line.is_empty() && line_width + space_width + word_width > width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                } else if let Some(spaces) = pending_spaces.take() {
+                    push(&mut line, &spaces.text, spaces.bold);
+                    line_width += space_width;
+                }
+                pending_spaces = None;
+
+                // Hard-break words that are wider than a whole line
+                let mut rest = token;
+                while !rest.is_empty() {
+                    let fit = fit_chars(fonts, rest, span.bold, size, width - line_width);
+                    let fit = if fit == 0 && line.is_empty() {
+                        rest.chars().next().map_or(0, char::len_utf8)
+                    } else {
+                        fit
+                    };
+                    if fit == 0 {
+                        lines.push(std::mem::take(&mut line));
+                        line_width = 0.0;
+                        continue;
+                    }
+                    let (head, tail) = rest.split_at(fit);
+                    push(&mut line, head, span.bold);
+                    line_width += fonts.width(head, span.bold, size);
+                    rest = tail;
+                    if !rest.is_empty() {
+                        lines.push(std::mem::take(&mut line));
+                        line_width = 0.0;
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(spaces) = pending_spaces {
+        if line.is_empty() {
+            push(&mut line, &spaces.text, spaces.bold);
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// Splits text into alternating runs of spaces and non-spaces.
+fn split_spaces(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_space = None;
+    for (i, c) in text.char_indices() {
+        let space = c == ' ';
+        if in_space.is_some_and(|s| s != space) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        in_space = Some(space);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// Byte length of the longest prefix of `text` that fits in `width` points.
+fn fit_chars(fonts: &Fonts, text: &str, bold: bool, size: f32, width: f32) -> usize {
+    let mut used = 0.0;
+    for (i, c) in text.char_indices() {
+        let mut buf = [0u8; 4];
+        used += fonts.width(c.encode_utf8(&mut buf), bold, size);
+        if used > width {
+            return i;
+        }
+    }
+    text.len()
+}
+
+/// Encodes a line as an escaped WinAnsi PDF string; unmappable characters become `?`.
+fn encode_pdf_string(s: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push(b'\\');
+                out.push(c as u8);
+            }
+            c if c.is_ascii() && !c.is_ascii_control() => out.push(c as u8),
+            c if c.is_control() => out.push(b' '),
+            c => out.push(win_ansi_byte(c).unwrap_or(b'?')),
+        }
+    }
+    out
+}
+
+/// Maps a non-ASCII character to its WinAnsiEncoding byte, if it has one.
+fn win_ansi_byte(c: char) -> Option<u8> {
+    let code = c as u32;
+    if (0xA0..=0xFF).contains(&code) {
+        return Some(code as u8);
+    }
+    Some(match c {
+        '€' => 0x80,
+        '‚' => 0x82,
+        '„' => 0x84,
+        '…' => 0x85,
+        '‰' => 0x89,
+        '‹' => 0x8B,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '™' => 0x99,
+        '›' => 0x9B,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(text: &str) -> Block {
+        Block::Paragraph(Paragraph {
+            style: ParagraphStyle::Body,
+            spans: vec![Span::new(text, false)],
+        })
+    }
+
+    fn line_texts(lines: &[Line]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.iter().map(|s| s.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap_breaks_long_lines() {
+        let fonts = Fonts::standard();
+        let spans = [Span::new("word ".repeat(50), false)];
+        let lines = wrap(&fonts, &spans, 10.0, 100.0);
+
+        assert!(lines.len() > 1);
+        for line in line_texts(&lines) {
+            assert!(fonts.width(&line, false, 10.0) <= 100.0, "{:?}", line);
+            assert!(!line.starts_with(' '));
+        }
+    }
+
+    #[test]
+    fn test_wrap_hard_breaks_long_words() {
+        let fonts = Fonts::standard();
+        // 5.2pt per char at 10pt: 19 chars fit in 100pt
+        let lines = wrap(&fonts, &[Span::new("x".repeat(45), false)], 10.0, 100.0);
+        assert_eq!(
+            line_texts(&lines),
+            vec!["x".repeat(19), "x".repeat(19), "x".repeat(7)]
+        );
+    }
+
+    #[test]
+    fn test_wrap_keeps_styles_and_breaks() {
+        let fonts = Fonts::standard();
+        let spans = [
+            Span::new("Total: ", false),
+            Span::new("CHF 12", true),
+            Span::new("\n  indented", false),
+        ];
+        let lines = wrap(&fonts, &spans, 10.0, 500.0);
+
+        assert_eq!(
+            lines,
+            vec![
+                vec![Span::new("Total: ", false), Span::new("CHF 12", true)],
+                vec![Span::new("  indented", false)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_pdf_string() {
+        assert_eq!(encode_pdf_string("a(b)\\"), b"a\\(b\\)\\\\".to_vec());
+        assert_eq!(
+            encode_pdf_string("Größe €"),
+            vec![b'G', b'r', 0xF6, 0xDF, b'e', b' ', 0x80]
+        );
+        assert_eq!(encode_pdf_string("日"), b"?".to_vec());
+    }
+
+    #[test]
+    fn test_text_to_pdf_paginates() {
+        let lines_per_page = ((TOP - MARGIN) / BODY_LEADING) as usize;
+        let text = "line\n".repeat(lines_per_page * 2 + 1);
+        let pdf = text_to_pdf(&text).unwrap();
+
+        let doc = Document::load_mem(&pdf).unwrap();
+        assert_eq!(doc.get_pages().len(), 3);
+    }
+
+    #[test]
+    fn test_text_to_pdf_empty_has_one_page() {
+        let doc = Document::load_mem(&text_to_pdf("").unwrap()).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+    }
+
+    #[test]
+    fn test_blocks_to_pdf_renders_tables_and_pictures() {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(4, 2, image::Rgba([255, 0, 0, 128]))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let cell = |text: &str| {
+            vec![Paragraph {
+                style: ParagraphStyle::Body,
+                spans: vec![Span::new(text, false)],
+            }]
+        };
+        let blocks = vec![
+            Block::Paragraph(Paragraph {
+                style: ParagraphStyle::Heading(1),
+                spans: vec![Span::new("Rechnung", false)],
+            }),
+            body("Grüße aus Zürich — ČSOB 日本"),
+            Block::Table(vec![
+                vec![cell("Item"), cell("Price")],
+                vec![cell("A"), cell("1.00")],
+            ]),
+            Block::Picture(Picture {
+                data: png,
+                size: Some((100.0, 50.0)),
+            }),
+            Block::Picture(Picture {
+                data: b"not an image".to_vec(),
+                size: None,
+            }),
+        ];
+
+        let pdf = blocks_to_pdf(&blocks).unwrap();
+        let doc = Document::load_mem(&pdf).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+
+        let page = doc.page_iter().next().unwrap();
+        let (_, resource_ids) = doc.get_page_resources(page);
+        let resources = doc.get_dictionary(resource_ids[0]).unwrap();
+        let xobjects = resources.get(b"XObject").unwrap().as_dict().unwrap();
+        assert_eq!(xobjects.len(), 1);
+
+        let fonts = resources.get(b"Font").unwrap().as_dict().unwrap();
+        let f1 = doc
+            .get_dictionary(fonts.get(b"F1").unwrap().as_reference().unwrap())
+            .unwrap();
+        match font::regular() {
+            Some(_) => {
+                assert_eq!(f1.get(b"Subtype").unwrap().as_name_str().unwrap(), "Type0");
+                assert!(f1.has(b"ToUnicode"));
+            }
+            None => assert_eq!(
+                f1.get(b"BaseFont").unwrap().as_name_str().unwrap(),
+                "Helvetica"
+            ),
+        }
+        // Bold heading text uses the second font when one is available
+        assert_eq!(
+            fonts.len(),
+            if font::bold().is_some() || font::regular().is_none() {
+                2
+            } else {
+                1
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_unicode_cmap_maps_glyphs() {
+        let used = BTreeMap::from([(0, '?'), (36, 'A'), (1234, '😀')]);
+        let cmap = String::from_utf8(to_unicode_cmap(&used)).unwrap();
+
+        assert!(cmap.contains("2 beginbfchar"));
+        assert!(cmap.contains("<0024> <0041>"));
+        assert!(cmap.contains("<04D2> <D83DDE00>"));
+    }
+}
@@ -0,0 +1,350 @@
+use std::path::Path;
+
+use mail_parser::decoders::charsets::map::charset_decoder;
+
+use crate::config::schema::{DocumentFormat, DocumentMetadata};
+use crate::error::ProcessError;
+use crate::processor::{render, DocumentProcessor, ProcessedContent};
+
+pub struct RtfProcessor;
+
+impl RtfProcessor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RtfProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DocumentProcessor for RtfProcessor {
+    fn process(&self, path: &Path) -> Result<ProcessedContent, ProcessError> {
+        let _span = tracing::info_span!("processor.rtf").entered();
+        let bytes = std::fs::read(path).map_err(|e| ProcessError::ReadDocument {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        let text = parse_rtf(&bytes)?;
+
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("document.rtf")
+            .to_string();
+
+        let metadata = DocumentMetadata::new(filename, DocumentFormat::Rtf);
+        let pdf_bytes = render::text_to_pdf(&text)?;
+
+        Ok(ProcessedContent::new(text, pdf_bytes, metadata))
+    }
+
+    fn supports(&self, format: DocumentFormat) -> bool {
+        matches!(format, DocumentFormat::Rtf)
+    }
+}
+
+/// Destinations whose content is metadata or binary data rather than body text.
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "annotation",
+    "author",
+    "bkmkend",
+    "bkmkstart",
+    "colorschememapping",
+    "colortbl",
+    "datastore",
+    "fldinst",
+    "fonttbl",
+    "footer",
+    "footerf",
+    "footerl",
+    "footerr",
+    "footnote",
+    "generator",
+    "header",
+    "headerf",
+    "headerl",
+    "headerr",
+    "info",
+    "latentstyles",
+    "listoverridetable",
+    "listtable",
+    "mmathPr",
+    "objdata",
+    "object",
+    "pict",
+    "rsidtbl",
+    "stylesheet",
+    "themedata",
+    "xmlnstbl",
+];
+
+/// Per-group parser state, saved on `{` and restored on `}`.
+#[derive(Clone, Copy)]
+struct GroupState {
+    skip: bool,
+    /// Number of fallback characters following a `\uN` escape.
+    unicode_skip: usize,
+}
+
+struct RtfParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    text: String,
+    /// Codepage bytes awaiting decoding, so multi-byte codepages decode correctly.
+    pending: Vec<u8>,
+    decoder: Option<fn(&[u8]) -> String>,
+    /// Fallback characters still to drop after a `\uN` escape.
+    fallback_left: usize,
+}
+
+/// Extracts plain text from an RTF document.
+fn parse_rtf(input: &[u8]) -> Result<String, ProcessError> {
+    if !input.trim_ascii_start().starts_with(b"{\\rtf") {
+        return Err(ProcessError::RtfProcessing(
+            "Missing {\\rtf header".to_string(),
+        ));
+    }
+
+    let mut parser = RtfParser {
+        input,
+        pos: 0,
+        text: String::new(),
+        pending: Vec::new(),
+        decoder: codepage_decoder(1252),
+        fallback_left: 0,
+    };
+    parser.run()?;
+
+    let lines: Vec<&str> = parser.text.lines().map(str::trim_end).collect();
+    Ok(lines.join("\n").trim().to_string())
+}
+
+/// Looks up a decoder for a Windows codepage number from `\ansicpgN`.
+fn codepage_decoder(codepage: i32) -> Option<fn(&[u8]) -> String> {
+    let name = match codepage {
+        932 => "shift_jis".to_string(),
+        936 => "gbk".to_string(),
+        949 => "euc-kr".to_string(),
+        950 => "big5".to_string(),
+        10000 => "macintosh".to_string(),
+        n => format!("windows-{}", n),
+    };
+    charset_decoder(name.as_bytes())
+}
+
+impl RtfParser<'_> {
+    fn run(&mut self) -> Result<(), ProcessError> {
+        let mut stack: Vec<GroupState> = Vec::new();
+        let mut state = GroupState {
+            skip: false,
+            unicode_skip: 1,
+        };
+
+        while let Some(&byte) = self.input.get(self.pos) {
+            self.pos += 1;
+            match byte {
+                b'{' => {
+                    self.flush();
+                    stack.push(state);
+                }
+                b'}' => {
+                    self.flush();
+                    state = stack.pop().ok_or_else(|| {
+                        ProcessError::RtfProcessing("Unbalanced closing brace".to_string())
+                    })?;
+                }
+                b'\\' => self.control(&mut state),
+                b'\r' | b'\n' => {}
+                _ => self.push_byte(byte, state.skip),
+            }
+        }
+
+        self.flush();
+        Ok(())
+    }
+
+    /// Handles the control word or symbol following a backslash.
+    fn control(&mut self, state: &mut GroupState) {
+        let Some(&next) = self.input.get(self.pos) else {
+            return;
+        };
+
+        if !next.is_ascii_alphabetic() {
+            self.pos += 1;
+            match next {
+                b'\'' => {
+                    let hex = self.input.get(self.pos..self.pos + 2);
+                    self.pos += 2;
+                    if let Some(byte) = hex
+                        .and_then(|h| std::str::from_utf8(h).ok())
+                        .and_then(|h| u8::from_str_radix(h, 16).ok())
+                    {
+                        self.push_byte(byte, state.skip);
+                    }
+                }
+                b'*' => state.skip = true,
+                b'\\' | b'{' | b'}' => self.push_byte(next, state.skip),
+                b'~' => self.push_char(' ', state.skip),
+                b'_' => self.push_char('-', state.skip),
+                b'\r' | b'\n' => self.push_char('\n', state.skip),
+                _ => {}
+            }
+            return;
+        }
+
+        let start = self.pos;
+        while self
+            .input
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_alphabetic)
+        {
+            self.pos += 1;
+        }
+        let word = String::from_utf8_lossy(&self.input[start..self.pos]).into_owned();
+
+        let param_start = self.pos;
+        if self.input.get(self.pos) == Some(&b'-') {
+            self.pos += 1;
+        }
+        while self.input.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+        let param: Option<i32> = std::str::from_utf8(&self.input[param_start..self.pos])
+            .ok()
+            .and_then(|p| p.parse().ok());
+        // A single space delimits the control word and is not part of the text
+        if self.input.get(self.pos) == Some(&b' ') {
+            self.pos += 1;
+        }
+
+        if SKIPPED_DESTINATIONS.contains(&word.as_str()) {
+            state.skip = true;
+            return;
+        }
+
+        match word.as_str() {
+            "par" | "line" | "sect" | "page" | "row" => self.push_char('\n', state.skip),
+            "tab" | "cell" => self.push_char('\t', state.skip),
+            "emdash" => self.push_char('—', state.skip),
+            "endash" => self.push_char('–', state.skip),
+            "bullet" => self.push_char('•', state.skip),
+            "lquote" => self.push_char('‘', state.skip),
+            "rquote" => self.push_char('’', state.skip),
+            "ldblquote" => self.push_char('“', state.skip),
+            "rdblquote" => self.push_char('”', state.skip),
+            "ansicpg" => {
+                if let Some(decoder) = param.and_then(codepage_decoder) {
+                    self.decoder = Some(decoder);
+                }
+            }
+            "uc" => state.unicode_skip = param.unwrap_or(1).max(0) as usize,
+            "u" => {
+                if let Some(code) = param {
+                    // Code points above 32767 are written as negative numbers
+                    let code = if code < 0 { code + 65536 } else { code };
+                    let c = char::from_u32(code as u32).unwrap_or('\u{FFFD}');
+                    self.push_char(c, state.skip);
+                    self.fallback_left = state.unicode_skip;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_byte(&mut self, byte: u8, skip: bool) {
+        if self.fallback_left > 0 {
+            self.fallback_left -= 1;
+            return;
+        }
+        if !skip {
+            self.pending.push(byte);
+        }
+    }
+
+    fn push_char(&mut self, c: char, skip: bool) {
+        if skip {
+            return;
+        }
+        self.flush();
+        self.text.push(c);
+    }
+
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let decoded = match self.decoder {
+            Some(decode) => decode(&self.pending),
+            None => String::from_utf8_lossy(&self.pending).into_owned(),
+        };
+        self.text.push_str(&decoded);
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_rtf_format() {
+        let processor = RtfProcessor::new();
+        assert!(processor.supports(DocumentFormat::Rtf));
+        assert!(!processor.supports(DocumentFormat::Text));
+    }
+
+    #[test]
+    fn test_parse_rtf_skips_metadata_groups() {
+        let rtf = br"{\rtf1\ansi\ansicpg1252\deff0{\fonttbl{\f0\fswiss Helvetica;}}
+{\colortbl;\red0\green0\blue0;}
+{\info{\author Jane}{\title Secret}}
+{\*\generator Msftedit 5.41;}
+\pard\b Invoice\b0\par
+Total:\tab CHF 12\'e4\par
+}";
+
+        let text = parse_rtf(rtf).unwrap();
+        assert_eq!(text, "Invoice\nTotal:\tCHF 12\u{e4}");
+    }
+
+    #[test]
+    fn test_parse_rtf_unicode_escapes() {
+        let rtf = br"{\rtf1\uc1 Gr\u252?ezi \u8364\'80 5 {\uc0\u-3913}}";
+
+        let text = parse_rtf(rtf).unwrap();
+        assert_eq!(text, "Grüezi € 5 \u{f0b7}");
+    }
+
+    #[test]
+    fn test_parse_rtf_escaped_symbols() {
+        let rtf = br"{\rtf1 a\{b\}c\\d\~e\emdash f}";
+        assert_eq!(parse_rtf(rtf).unwrap(), "a{b}c\\d e—f");
+    }
+
+    #[test]
+    fn test_parse_rtf_rejects_non_rtf() {
+        assert!(matches!(
+            parse_rtf(b"plain text"),
+            Err(ProcessError::RtfProcessing(_))
+        ));
+        assert!(matches!(
+            parse_rtf(br"{\rtf1 text}}"),
+            Err(ProcessError::RtfProcessing(_))
+        ));
+    }
+
+    #[test]
+    fn test_process_rtf_file() {
+        let temp_file = tempfile::NamedTempFile::with_suffix(".rtf").unwrap();
+        std::fs::write(temp_file.path(), br"{\rtf1\ansi Hello\par RTF}").unwrap();
+
+        let processed = RtfProcessor::new().process(temp_file.path()).unwrap();
+        assert_eq!(processed.text, "Hello\nRTF");
+        assert_eq!(processed.metadata.format, DocumentFormat::Rtf);
+        assert!(processed.pdf_bytes.starts_with(b"%PDF"));
+    }
+}
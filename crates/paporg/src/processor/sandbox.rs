@@ -0,0 +1,422 @@
+//! Runs document processing in a killable child process.
+//!
+//! Host binaries opt in by calling [`init`] first thing in `main`. The same
+//! executable is then re-launched with [`WORKER_ARG`] for every document, so a
+//! hung renderer or OCR call can be killed without leaking a worker thread.
+
+use std::io::Read;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::schema::DocumentMetadata;
+use crate::error::{LimitViolation, ProcessError};
+use crate::processor::limits::ProcessingLimits;
+use crate::processor::{ProcessedContent, ProcessorRegistry};
+
+/// Command-line flag that turns the host executable into a sandbox worker.
+pub const WORKER_ARG: &str = "--paporg-sandbox-worker";
+
+const REQUEST_FILE: &str = "request.json";
+const RESPONSE_FILE: &str = "response.json";
+const OUTPUT_FILE: &str = "output.pdf";
+
+/// How often a running child process is polled for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enables sandboxed processing, or serves a sandbox request and exits.
+///
+/// Must be called before any other setup in `main`: when the process was
+/// launched as a sandbox worker it never returns.
+pub fn init() {
+    let args: Vec<_> = std::env::args_os().collect();
+    if args.get(1).is_some_and(|arg| arg == WORKER_ARG) {
+        let code = match args.get(2) {
+            Some(work_dir) => run_worker(Path::new(work_dir)),
+            None => 2,
+        };
+        std::process::exit(code);
+    }
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if [`init`] enabled sandboxed processing in this process.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Settings a sandbox worker needs to rebuild the processor registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SandboxRequest {
+    pub path: PathBuf,
+    pub ocr_enabled: bool,
+    pub ocr_languages: Vec<String>,
+    pub ocr_dpi: u32,
+    pub limits: ProcessingLimits,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum SandboxResponse {
+    Processed {
+        text: String,
+        pages: Vec<Range<usize>>,
+        metadata: DocumentMetadata,
+    },
+    LimitExceeded(LimitViolation),
+    Failed(String),
+}
+
+/// Processes a document in a child process, killing it after the job timeout.
+pub(crate) fn process_isolated(request: &SandboxRequest) -> Result<ProcessedContent, ProcessError> {
+    let _span = tracing::info_span!("processor.sandbox").entered();
+
+    let work_dir = std::env::temp_dir().join(format!("paporg_sandbox_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir)
+        .map_err(|e| ProcessError::Sandbox(format!("Failed to create work dir: {}", e)))?;
+
+    let result = run_in_child(request, &work_dir);
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
+fn run_in_child(
+    request: &SandboxRequest,
+    work_dir: &Path,
+) -> Result<ProcessedContent, ProcessError> {
+    let request_json = serde_json::to_vec(request)
+        .map_err(|e| ProcessError::Sandbox(format!("Failed to encode request: {}", e)))?;
+    std::fs::write(work_dir.join(REQUEST_FILE), request_json)
+        .map_err(|e| ProcessError::Sandbox(format!("Failed to write request: {}", e)))?;
+
+    let exe = std::env::current_exe()
+        .map_err(|e| ProcessError::Sandbox(format!("Failed to locate executable: {}", e)))?;
+    let mut command = Command::new(exe);
+    command
+        .arg(WORKER_ARG)
+        .arg(work_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    let output = match run_with_timeout(&mut command, request.limits.job_timeout) {
+        Ok(Completion::Finished(output)) => output,
+        Ok(Completion::TimedOut) => {
+            return Err(ProcessError::LimitExceeded(LimitViolation::JobTimeout {
+                seconds: request.limits.job_timeout.map_or(0, |t| t.as_secs()),
+            }))
+        }
+        Err(e) => {
+            return Err(ProcessError::Sandbox(format!(
+                "Failed to start worker: {}",
+                e
+            )))
+        }
+    };
+
+    if !output.status.success() {
+        return Err(ProcessError::Sandbox(format!(
+            "worker exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    read_response(work_dir)
+}
+
+/// Reads the result a sandbox worker left in `work_dir`.
+fn read_response(work_dir: &Path) -> Result<ProcessedContent, ProcessError> {
+    let response: SandboxResponse = std::fs::read(work_dir.join(RESPONSE_FILE))
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|e| e.to_string()))
+        .map_err(|e| ProcessError::Sandbox(format!("Failed to read worker response: {}", e)))?;
+
+    match response {
+        SandboxResponse::Processed {
+            text,
+            pages,
+            metadata,
+        } => {
+            let pdf_bytes = std::fs::read(work_dir.join(OUTPUT_FILE))
+                .map_err(|e| ProcessError::Sandbox(format!("Failed to read worker PDF: {}", e)))?;
+            Ok(ProcessedContent {
+                text,
+                pages,
+                pdf_bytes,
+                metadata,
+            })
+        }
+        SandboxResponse::LimitExceeded(violation) => Err(ProcessError::LimitExceeded(violation)),
+        SandboxResponse::Failed(message) => Err(ProcessError::Sandbox(message)),
+    }
+}
+
+/// Entry point of the sandbox worker process. Returns the process exit code.
+fn run_worker(work_dir: &Path) -> i32 {
+    let request: SandboxRequest = match std::fs::read(work_dir.join(REQUEST_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    {
+        Some(request) => request,
+        None => {
+            eprintln!("invalid sandbox request in {}", work_dir.display());
+            return 2;
+        }
+    };
+
+    // The parent enforces the job timeout by killing this process.
+    let limits = ProcessingLimits {
+        job_timeout: None,
+        ..request.limits
+    };
+    let registry = ProcessorRegistry::with_limits(
+        request.ocr_enabled,
+        &request.ocr_languages,
+        request.ocr_dpi,
+        limits,
+    );
+
+    let response = match registry.process_in_process(&request.path) {
+        Ok(content) => match std::fs::write(work_dir.join(OUTPUT_FILE), &content.pdf_bytes) {
+            Ok(()) => SandboxResponse::Processed {
+                text: content.text,
+                pages: content.pages,
+                metadata: content.metadata,
+            },
+            Err(e) => SandboxResponse::Failed(format!("Failed to write PDF: {}", e)),
+        },
+        Err(ProcessError::LimitExceeded(violation)) => SandboxResponse::LimitExceeded(violation),
+        Err(e) => SandboxResponse::Failed(e.to_string()),
+    };
+
+    let written = serde_json::to_vec(&response)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| {
+            std::fs::write(work_dir.join(RESPONSE_FILE), bytes).map_err(|e| e.to_string())
+        });
+    match written {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("failed to write sandbox response: {}", e);
+            1
+        }
+    }
+}
+
+/// Result of running a command under a deadline.
+pub(crate) enum Completion {
+    Finished(Output),
+    TimedOut,
+}
+
+/// Runs a command, killing it once `timeout` elapses.
+///
+/// On unix the command runs in its own process group, so the processes it
+/// spawns (e.g. `pdftoppm` under a sandbox worker) are killed along with it.
+/// Piped stdout/stderr are drained on background threads so a chatty child
+/// cannot block on a full pipe.
+pub(crate) fn run_with_timeout(
+    command: &mut Command,
+    timeout: Option<Duration>,
+) -> std::io::Result<Completion> {
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+
+    let mut child = command.spawn()?;
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let status: ExitStatus = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            kill_process_group(&mut child);
+            let _ = child.wait();
+            return Ok(Completion::TimedOut);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    let collect = |handle: Option<std::thread::JoinHandle<Vec<u8>>>| {
+        handle.and_then(|h| h.join().ok()).unwrap_or_default()
+    };
+    Ok(Completion::Finished(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    }))
+}
+
+/// Kills `child` and, on unix, every process left in its process group.
+fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: kill(2) has no memory-safety preconditions; a negative pid
+        // addresses the group `run_with_timeout` created for this child.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+}
+
+fn drain<R: Read + Send + 'static>(mut reader: R) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = reader.read_to_end(&mut buf);
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::DocumentFormat;
+
+    #[test]
+    fn test_sandbox_disabled_by_default() {
+        // Test binaries never call init(), so processing stays in-process.
+        assert!(!is_enabled());
+    }
+
+    /// A one-page PDF with text and a document information dictionary.
+    fn pdf_with_info() -> Vec<u8> {
+        use lopdf::{dictionary, Document, Object, Stream};
+
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let content = b"BT /F1 12 Tf 50 700 Td (Invoice 2024) Tj ET".to_vec();
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+            "Contents" => content_id,
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        let info_id = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Policy 2024"),
+        });
+        doc.trailer.set("Info", info_id);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_worker_response_keeps_metadata() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let path = work_dir.path().join("policy.pdf");
+        std::fs::write(&path, pdf_with_info()).unwrap();
+
+        let request = SandboxRequest {
+            path,
+            ocr_enabled: false,
+            ocr_languages: Vec::new(),
+            ocr_dpi: 300,
+            limits: ProcessingLimits::default(),
+        };
+        std::fs::write(
+            work_dir.path().join(REQUEST_FILE),
+            serde_json::to_vec(&request).unwrap(),
+        )
+        .unwrap();
+
+        // The worker runs in-process here; a child would do the same.
+        assert_eq!(run_worker(work_dir.path()), 0);
+        let content = read_response(work_dir.path()).unwrap();
+
+        assert!(content.text.contains("Invoice 2024"));
+        assert!(!content.pdf_bytes.is_empty());
+        assert_eq!(content.metadata.original_filename, "policy.pdf");
+        assert_eq!(content.metadata.format, DocumentFormat::Pdf);
+        let info = content.metadata.pdf_metadata.unwrap();
+        assert!(info.contains("Title: Policy 2024"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout_collects_output() {
+        let mut command = Command::new("sh");
+        command
+            .args(["-c", "echo out; echo err >&2"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        match run_with_timeout(&mut command, Some(Duration::from_secs(10))).unwrap() {
+            Completion::Finished(output) => {
+                assert!(output.status.success());
+                assert_eq!(output.stdout, b"out\n");
+                assert_eq!(output.stderr, b"err\n");
+            }
+            Completion::TimedOut => panic!("command should not time out"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout_kills_hung_child() {
+        let mut command = Command::new("sleep");
+        command.arg("30");
+
+        let start = Instant::now();
+        let result = run_with_timeout(&mut command, Some(Duration::from_millis(100))).unwrap();
+        assert!(matches!(result, Completion::TimedOut));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_run_with_timeout_kills_grandchildren() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let pid_file = temp.path().join("pid");
+        let mut command = Command::new("sh");
+        command.args([
+            "-c",
+            &format!("sleep 30 & echo $! > {}; wait", pid_file.display()),
+        ]);
+
+        let result = run_with_timeout(&mut command, Some(Duration::from_millis(500))).unwrap();
+        assert!(matches!(result, Completion::TimedOut));
+
+        // The orphaned sleep may linger as a zombie until it is reaped.
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = Path::new("/proc").join(pid.trim()).join("stat");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let alive = || {
+            std::fs::read_to_string(&stat)
+                .is_ok_and(|s| s.rsplit(')').next().is_some_and(|r| !r.starts_with(" Z")))
+        };
+        while alive() && Instant::now() < deadline {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!alive(), "grandchild {} survived the timeout", pid.trim());
+    }
+}
@@ -37,11 +37,7 @@ impl DocumentProcessor for TextProcessor {
         // Create a simple PDF from text
         let pdf_bytes = create_text_pdf(&text)?;
 
-        Ok(ProcessedContent {
-            text,
-            pdf_bytes,
-            metadata,
-        })
+        Ok(ProcessedContent::new(text, pdf_bytes, metadata))
     }
 
     fn supports(&self, format: DocumentFormat) -> bool {
@@ -0,0 +1,650 @@
+//! Safe extraction of archive files (ZIP, 7z) into child jobs.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use tracing::{debug, info_span, warn};
+
+use crate::config::schema::{DocumentFormat, LimitsConfig};
+use crate::error::ArchiveError;
+use crate::worker::job::Job;
+
+/// Supported archive container formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    SevenZ,
+}
+
+impl ArchiveFormat {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "zip" => Some(Self::Zip),
+            "7z" => Some(Self::SevenZ),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(Self::from_extension)
+    }
+}
+
+/// Returns true if the path looks like a supported archive.
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveFormat::from_path(path).is_some()
+}
+
+/// Limits applied while unpacking untrusted archives.
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    /// Maximum number of files extracted from a single archive.
+    pub max_entries: usize,
+    /// Maximum uncompressed size of a single member in bytes.
+    pub max_entry_size: u64,
+    /// Maximum total uncompressed size of a single archive in bytes.
+    pub max_total_size: u64,
+    /// Maximum nesting depth of archives inside archives.
+    pub max_depth: u32,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 1000,
+            max_entry_size: 100 * 1024 * 1024,
+            max_total_size: 500 * 1024 * 1024,
+            max_depth: 3,
+        }
+    }
+}
+
+impl ArchiveLimits {
+    /// Builds the limits from settings. A value of 0 disables a limit; members
+    /// are capped at the largest accepted input file.
+    pub fn from_config(config: &LimitsConfig) -> Self {
+        let mb = |mb: u64| match mb {
+            0 => u64::MAX,
+            mb => mb.saturating_mul(1024 * 1024),
+        };
+        Self {
+            max_entries: match config.max_archive_entries {
+                0 => usize::MAX,
+                entries => entries,
+            },
+            max_entry_size: mb(config.max_file_size_mb),
+            max_total_size: mb(config.max_archive_size_mb),
+            max_depth: match config.max_archive_depth {
+                0 => u32::MAX,
+                depth => depth,
+            },
+        }
+    }
+}
+
+/// Files unpacked from an archive.
+#[derive(Debug, Default)]
+pub struct Extraction {
+    /// Paths of the extracted documents and nested archives.
+    pub members: Vec<PathBuf>,
+    /// Names of members that were skipped because paporg cannot process them.
+    pub skipped: Vec<String>,
+}
+
+/// Child jobs of an archive job.
+#[derive(Debug, Default)]
+pub struct Expansion {
+    pub children: Vec<Job>,
+    /// Names of members that were skipped because paporg cannot process them.
+    pub skipped: Vec<String>,
+}
+
+/// Default directory archives are unpacked into.
+pub fn default_extract_root() -> PathBuf {
+    std::env::temp_dir().join("paporg_archives")
+}
+
+/// Unpacks an archive job into `extract_root/<job id>/` and returns one child job per
+/// supported member.
+///
+/// `depth` is the nesting level of `parent` (0 for archives found by a scanner).
+pub fn expand_job(
+    parent: &Job,
+    extract_root: &Path,
+    limits: &ArchiveLimits,
+    depth: u32,
+) -> Result<Expansion, ArchiveError> {
+    if depth >= limits.max_depth {
+        return Err(ArchiveError::TooDeep {
+            limit: limits.max_depth,
+        });
+    }
+
+    let dest = extract_root.join(&parent.id);
+    let extraction = extract(&parent.source_path, &dest, limits)?;
+
+    Ok(Expansion {
+        children: extraction
+            .members
+            .into_iter()
+            .map(|path| Job::child_of(parent, path))
+            .collect(),
+        skipped: extraction.skipped,
+    })
+}
+
+/// Extracts the members of an archive that paporg can process into `dest`.
+///
+/// Rejects the whole archive if any entry would escape `dest` or a limit is exceeded.
+pub fn extract(
+    archive_path: &Path,
+    dest: &Path,
+    limits: &ArchiveLimits,
+) -> Result<Extraction, ArchiveError> {
+    let format = ArchiveFormat::from_path(archive_path).ok_or_else(|| {
+        ArchiveError::InvalidArchive(format!("unsupported archive: {}", archive_path.display()))
+    })?;
+    let _span = info_span!("archive.extract", format = ?format).entered();
+
+    let file = File::open(archive_path).map_err(|e| ArchiveError::Read {
+        path: archive_path.to_path_buf(),
+        source: e,
+    })?;
+
+    std::fs::create_dir_all(dest).map_err(|e| ArchiveError::Extract {
+        path: dest.to_path_buf(),
+        source: e,
+    })?;
+
+    let mut extractor = Extractor::new(dest, limits);
+    let result = match format {
+        ArchiveFormat::Zip => extract_zip(file, &mut extractor),
+        ArchiveFormat::SevenZ => extract_7z(file, &mut extractor),
+    };
+
+    if let Err(e) = result {
+        // Don't leave partially extracted members behind
+        let _ = std::fs::remove_dir_all(dest);
+        return Err(e);
+    }
+
+    debug!(
+        "Extracted {} members ({} bytes, {} skipped) from {}",
+        extractor.extraction.members.len(),
+        extractor.total_size,
+        extractor.extraction.skipped.len(),
+        archive_path.display()
+    );
+    Ok(extractor.extraction)
+}
+
+fn extract_zip(file: File, extractor: &mut Extractor<'_>) -> Result<(), ArchiveError> {
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| ArchiveError::InvalidArchive(e.to_string()))?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| ArchiveError::InvalidArchive(e.to_string()))?;
+
+        if entry.is_dir() || entry.is_symlink() {
+            continue;
+        }
+
+        let name = entry.name().to_string();
+        let size = entry.size();
+        extractor.extract_entry(&name, size, &mut entry)?;
+    }
+
+    Ok(())
+}
+
+fn extract_7z(file: File, extractor: &mut Extractor<'_>) -> Result<(), ArchiveError> {
+    let len = file
+        .metadata()
+        .map_err(|e| ArchiveError::InvalidArchive(e.to_string()))?
+        .len();
+    let mut reader = sevenz_rust::SevenZReader::new(file, len, sevenz_rust::Password::empty())
+        .map_err(|e| ArchiveError::InvalidArchive(e.to_string()))?;
+
+    // The callback can only signal "stop", so keep the first error aside
+    let mut failure = None;
+    reader
+        .for_each_entries(|entry, data| {
+            if failure.is_some() {
+                return Ok(false);
+            }
+            if entry.is_directory() || entry.is_anti_item() {
+                return Ok(true);
+            }
+            match extractor.extract_entry(entry.name(), entry.size(), data) {
+                Ok(()) => Ok(true),
+                Err(e) => {
+                    failure = Some(e);
+                    Ok(false)
+                }
+            }
+        })
+        .map_err(|e| ArchiveError::InvalidArchive(e.to_string()))?;
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Writes archive members to disk while enforcing limits.
+struct Extractor<'a> {
+    dest: &'a Path,
+    limits: &'a ArchiveLimits,
+    extraction: Extraction,
+    entry_count: usize,
+    total_size: u64,
+}
+
+impl<'a> Extractor<'a> {
+    fn new(dest: &'a Path, limits: &'a ArchiveLimits) -> Self {
+        Self {
+            dest,
+            limits,
+            extraction: Extraction::default(),
+            entry_count: 0,
+            total_size: 0,
+        }
+    }
+
+    fn extract_entry(
+        &mut self,
+        name: &str,
+        declared_size: u64,
+        data: &mut dyn Read,
+    ) -> Result<(), ArchiveError> {
+        let relative =
+            safe_relative_path(name).ok_or_else(|| ArchiveError::UnsafePath(name.to_string()))?;
+
+        self.entry_count += 1;
+        if self.entry_count > self.limits.max_entries {
+            return Err(ArchiveError::TooManyEntries {
+                limit: self.limits.max_entries,
+            });
+        }
+
+        // Reject early on the declared size; the actual size is enforced while copying
+        if declared_size > self.limits.max_entry_size {
+            return Err(ArchiveError::EntryTooLarge {
+                name: name.to_string(),
+                limit: self.limits.max_entry_size,
+            });
+        }
+
+        let junk = is_junk_entry(&relative);
+        if junk || !is_supported_member(&relative) {
+            if junk {
+                debug!("Skipping archive metadata entry: {}", name);
+            } else {
+                debug!("Skipping unsupported archive member: {}", name);
+                self.extraction.skipped.push(name.to_string());
+            }
+            // Drain so solid 7z blocks stay in sync for the following entries
+            io::copy(&mut data.take(self.limits.max_entry_size), &mut io::sink()).map_err(|e| {
+                ArchiveError::Extract {
+                    path: relative.clone(),
+                    source: e,
+                }
+            })?;
+            return Ok(());
+        }
+
+        let target = unique_member_path(self.dest.join(&relative));
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ArchiveError::Extract {
+                path: parent.to_path_buf(),
+                source: e,
+            })?;
+        }
+
+        let remaining = self.limits.max_total_size - self.total_size;
+        let cap = self.limits.max_entry_size.min(remaining);
+
+        let mut out = File::create(&target).map_err(|e| ArchiveError::Extract {
+            path: target.clone(),
+            source: e,
+        })?;
+        let written = io::copy(&mut data.take(cap.saturating_add(1)), &mut out).map_err(|e| {
+            ArchiveError::Extract {
+                path: target.clone(),
+                source: e,
+            }
+        })?;
+
+        if written > cap {
+            drop(out);
+            let _ = std::fs::remove_file(&target);
+            if cap == self.limits.max_entry_size {
+                return Err(ArchiveError::EntryTooLarge {
+                    name: name.to_string(),
+                    limit: self.limits.max_entry_size,
+                });
+            }
+            return Err(ArchiveError::TooLarge {
+                limit: self.limits.max_total_size,
+            });
+        }
+
+        self.total_size += written;
+        self.extraction.members.push(target);
+        Ok(())
+    }
+}
+
+/// Converts an archive entry name into a relative path that stays inside the
+/// extraction directory. Returns `None` for absolute paths and `..` traversal.
+fn safe_relative_path(name: &str) -> Option<PathBuf> {
+    let normalized = name.replace('\\', "/");
+    let mut path = PathBuf::new();
+
+    for component in Path::new(&normalized).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                warn!("Rejecting unsafe archive entry: {}", name);
+                return None;
+            }
+        }
+    }
+
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+    Some(path)
+}
+
+/// Appends `_2`, `_3`, ... to the file stem until `path` is not taken, so two
+/// entries that normalize to the same relative path become separate members.
+fn unique_member_path(path: PathBuf) -> PathBuf {
+    if std::fs::symlink_metadata(&path).is_err() {
+        return path;
+    }
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ext = path.extension().map(|e| e.to_string_lossy().into_owned());
+    (2..)
+        .map(|counter| {
+            let filename = match &ext {
+                Some(ext) => format!("{}_{}.{}", stem, counter, ext),
+                None => format!("{}_{}", stem, counter),
+            };
+            path.with_file_name(filename)
+        })
+        .find(|candidate| std::fs::symlink_metadata(candidate).is_err())
+        .expect("unbounded counter finds a free name")
+}
+
+/// Returns true for OS metadata entries (e.g. `__MACOSX/`, `.DS_Store`).
+fn is_junk_entry(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str() == "__MACOSX")
+        || path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.starts_with('.'))
+            .unwrap_or(true)
+}
+
+/// Returns true for members paporg can process: documents and nested archives.
+fn is_supported_member(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            DocumentFormat::from_extension(ext).is_some()
+                || ArchiveFormat::from_extension(ext).is_some()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    fn create_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        let buffer = writer.finish().unwrap().into_inner();
+        std::fs::write(path, buffer).unwrap();
+    }
+
+    #[test]
+    fn test_archive_format_from_extension() {
+        assert_eq!(
+            ArchiveFormat::from_extension("zip"),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_extension("ZIP"),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_extension("7z"),
+            Some(ArchiveFormat::SevenZ)
+        );
+        assert_eq!(ArchiveFormat::from_extension("pdf"), None);
+        assert!(is_archive(Path::new("/inbox/receipts.zip")));
+        assert!(!is_archive(Path::new("/inbox/receipt.pdf")));
+    }
+
+    #[test]
+    fn test_limits_from_config() {
+        let limits = ArchiveLimits::from_config(&LimitsConfig::default());
+        assert_eq!(limits.max_entries, 1000);
+        assert_eq!(limits.max_entry_size, 100 * 1024 * 1024);
+        assert_eq!(limits.max_total_size, 500 * 1024 * 1024);
+        assert_eq!(limits.max_depth, 3);
+
+        let limits = ArchiveLimits::from_config(&LimitsConfig {
+            max_file_size_mb: 0,
+            max_archive_entries: 0,
+            max_archive_size_mb: 0,
+            max_archive_depth: 0,
+            ..Default::default()
+        });
+        assert_eq!(limits.max_entries, usize::MAX);
+        assert_eq!(limits.max_entry_size, u64::MAX);
+        assert_eq!(limits.max_total_size, u64::MAX);
+        assert_eq!(limits.max_depth, u32::MAX);
+    }
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert_eq!(
+            safe_relative_path("receipts/a.pdf"),
+            Some(PathBuf::from("receipts/a.pdf"))
+        );
+        assert_eq!(safe_relative_path("./a.pdf"), Some(PathBuf::from("a.pdf")));
+        assert_eq!(safe_relative_path("../evil.pdf"), None);
+        assert_eq!(safe_relative_path("a/../../evil.pdf"), None);
+        assert_eq!(safe_relative_path("/etc/passwd"), None);
+        assert_eq!(safe_relative_path("..\\evil.pdf"), None);
+        assert_eq!(safe_relative_path(""), None);
+    }
+
+    #[test]
+    fn test_extract_zip_members() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("receipts.zip");
+        create_zip(
+            &archive,
+            &[
+                ("a.txt", b"Receipt A"),
+                ("nested/b.txt", b"Receipt B"),
+                ("__MACOSX/._a.txt", b"junk"),
+                (".DS_Store", b"junk"),
+                ("setup.exe", b"MZ"),
+                ("README", b"no extension"),
+            ],
+        );
+
+        let dest = temp.path().join("out");
+        let extraction = extract(&archive, &dest, &ArchiveLimits::default()).unwrap();
+
+        assert_eq!(extraction.members.len(), 2);
+        assert_eq!(extraction.skipped, ["setup.exe", "README"]);
+        assert!(!dest.join("setup.exe").exists());
+        assert_eq!(std::fs::read(dest.join("a.txt")).unwrap(), b"Receipt A");
+        assert_eq!(
+            std::fs::read(dest.join("nested/b.txt")).unwrap(),
+            b"Receipt B"
+        );
+        assert!(!dest.join(".DS_Store").exists());
+    }
+
+    #[test]
+    fn test_extract_renames_entries_with_the_same_path() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("receipts.zip");
+        create_zip(
+            &archive,
+            &[("a.txt", b"Receipt A"), ("./a.txt", b"Receipt A, again")],
+        );
+
+        let dest = temp.path().join("out");
+        let extraction = extract(&archive, &dest, &ArchiveLimits::default()).unwrap();
+
+        assert_eq!(
+            extraction.members,
+            [dest.join("a.txt"), dest.join("a_2.txt")]
+        );
+        assert_eq!(std::fs::read(dest.join("a.txt")).unwrap(), b"Receipt A");
+        assert_eq!(
+            std::fs::read(dest.join("a_2.txt")).unwrap(),
+            b"Receipt A, again"
+        );
+    }
+
+    #[test]
+    fn test_extract_rejects_zip_slip() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("evil.zip");
+        create_zip(&archive, &[("ok.txt", b"ok"), ("../../evil.txt", b"evil")]);
+
+        let dest = temp.path().join("out");
+        let result = extract(&archive, &dest, &ArchiveLimits::default());
+
+        assert!(matches!(result, Err(ArchiveError::UnsafePath(_))));
+        assert!(!temp.path().join("evil.txt").exists());
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_extract_enforces_entry_count() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("many.zip");
+        create_zip(
+            &archive,
+            &[("a.txt", b"a"), ("b.txt", b"b"), ("c.txt", b"c")],
+        );
+
+        let limits = ArchiveLimits {
+            max_entries: 2,
+            ..Default::default()
+        };
+        let result = extract(&archive, &temp.path().join("out"), &limits);
+
+        assert!(matches!(
+            result,
+            Err(ArchiveError::TooManyEntries { limit: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_extract_enforces_size_limits() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("big.zip");
+        create_zip(&archive, &[("a.txt", &[b'a'; 64]), ("b.txt", &[b'b'; 64])]);
+
+        let entry_limit = ArchiveLimits {
+            max_entry_size: 32,
+            ..Default::default()
+        };
+        let result = extract(&archive, &temp.path().join("out1"), &entry_limit);
+        assert!(matches!(result, Err(ArchiveError::EntryTooLarge { .. })));
+
+        let total_limit = ArchiveLimits {
+            max_total_size: 100,
+            ..Default::default()
+        };
+        let result = extract(&archive, &temp.path().join("out2"), &total_limit);
+        assert!(matches!(result, Err(ArchiveError::TooLarge { limit: 100 })));
+    }
+
+    #[test]
+    fn test_extract_7z_members() {
+        let temp = TempDir::new().unwrap();
+        let source_dir = temp.path().join("src");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::write(source_dir.join("invoice.txt"), b"Invoice 42").unwrap();
+        std::fs::write(source_dir.join("tool.bin"), b"binary").unwrap();
+
+        let archive = temp.path().join("docs.7z");
+        sevenz_rust::compress_to_path(&source_dir, &archive).unwrap();
+
+        let dest = temp.path().join("out");
+        let extraction = extract(&archive, &dest, &ArchiveLimits::default()).unwrap();
+
+        assert_eq!(extraction.members.len(), 1);
+        assert_eq!(extraction.skipped, ["tool.bin"]);
+        assert_eq!(
+            std::fs::read(&extraction.members[0]).unwrap(),
+            b"Invoice 42"
+        );
+    }
+
+    #[test]
+    fn test_expand_job_creates_children() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("receipts.zip");
+        create_zip(&archive, &[("a.txt", b"A"), ("b.pdf", b"B")]);
+
+        let parent = Job::new_with_source(archive, "accountant".to_string());
+        let extract_root = temp.path().join("extract");
+        let expansion = expand_job(&parent, &extract_root, &ArchiveLimits::default(), 0).unwrap();
+
+        assert_eq!(expansion.children.len(), 2);
+        for child in &expansion.children {
+            assert_eq!(child.parent_job_id.as_deref(), Some(parent.id.as_str()));
+            assert_eq!(child.source_name.as_deref(), Some("accountant"));
+            assert!(child.source_path.starts_with(extract_root.join(&parent.id)));
+        }
+    }
+
+    #[test]
+    fn test_expand_job_enforces_depth() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("nested.zip");
+        create_zip(&archive, &[("a.txt", b"A")]);
+
+        let parent = Job::new(archive);
+        let limits = ArchiveLimits {
+            max_depth: 2,
+            ..Default::default()
+        };
+
+        assert!(expand_job(&parent, temp.path(), &limits, 1).is_ok());
+        assert!(matches!(
+            expand_job(&parent, temp.path(), &limits, 2),
+            Err(ArchiveError::TooDeep { limit: 2 })
+        ));
+    }
+}
@@ -1,4 +1,3 @@
-use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
 /// Email metadata extracted from the source email (if applicable).
@@ -17,36 +16,6 @@ pub struct EmailMetadata {
 }
 
 impl EmailMetadata {
-    /// Creates a formatted header block for prepending to extracted text.
-    /// This allows rules to match on email metadata (from, to, subject, etc.).
-    pub fn to_header_block(&self) -> String {
-        // Start with empty string - Rust's String will efficiently grow as needed
-        // Avoiding magic numbers for pre-allocation since field lengths vary
-        let mut output = String::new();
-        output.push_str("=== EMAIL METADATA ===\n");
-
-        // Note: writeln! returns a Result, but writing to a String cannot fail,
-        // so we intentionally discard the Result with `let _ = ...`
-        if let Some(from) = &self.from {
-            let _ = writeln!(output, "From: {}", from);
-        }
-        if let Some(to) = &self.to {
-            let _ = writeln!(output, "To: {}", to);
-        }
-        if let Some(subject) = &self.subject {
-            let _ = writeln!(output, "Subject: {}", subject);
-        }
-        if let Some(date) = &self.date {
-            let _ = writeln!(output, "Date: {}", date);
-        }
-        if let Some(message_id) = &self.message_id {
-            let _ = writeln!(output, "Message-ID: {}", message_id);
-        }
-
-        output.push_str("======================\n\n");
-        output
-    }
-
     /// Returns true if this metadata has any meaningful content.
     pub fn has_content(&self) -> bool {
         self.from.is_some()
@@ -79,6 +48,11 @@ pub struct Job {
     pub mime_type: Option<String>,
     /// Email metadata if this job originated from an email source.
     pub email_metadata: Option<EmailMetadata>,
+    /// ID of the archive job this document was extracted from (if any).
+    pub parent_job_id: Option<String>,
+    /// Path of the source file below the directory of the import source
+    /// that discovered it, with `/` separators.
+    pub relative_path: Option<String>,
 }
 
 impl Job {
@@ -97,9 +71,24 @@ impl Job {
             source_name,
             mime_type,
             email_metadata,
+            parent_job_id: None,
+            relative_path: None,
         }
     }
 
+    /// Records the path of the source file below `root`, the directory of
+    /// the import source that discovered it.
+    pub fn with_source_root(mut self, root: &Path) -> Self {
+        self.relative_path = self.source_path.strip_prefix(root).ok().map(|relative| {
+            relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        });
+        self
+    }
+
     /// Creates a new job without a source name.
     pub fn new(source_path: PathBuf) -> Self {
         Self::new_internal(source_path, None, None, None)
@@ -144,6 +133,21 @@ impl Job {
         )
     }
 
+    /// Creates a job for a document extracted from an archive job.
+    /// The child inherits the parent's source name, email metadata and
+    /// relative path.
+    pub fn child_of(parent: &Job, source_path: PathBuf) -> Self {
+        let mut job = Self::new_internal(
+            source_path,
+            parent.source_name.clone(),
+            None,
+            parent.email_metadata.clone(),
+        );
+        job.parent_job_id = Some(parent.id.clone());
+        job.relative_path = parent.relative_path.clone();
+        job
+    }
+
     /// Detects MIME type from file path using the mime_guess crate.
     /// Returns `None` for unknown extensions.
     fn detect_mime_type(path: &Path) -> Option<String> {
@@ -167,7 +171,7 @@ impl JobResult {
     pub fn success(
         job: &Job,
         output_path: PathBuf,
-        archive_path: PathBuf,
+        archive_path: Option<PathBuf>,
         symlinks: Vec<PathBuf>,
         category: String,
     ) -> Self {
@@ -176,13 +180,27 @@ impl JobResult {
             source_path: job.source_path.clone(),
             success: true,
             output_path: Some(output_path),
-            archive_path: Some(archive_path),
+            archive_path,
             symlinks,
             category,
             error: None,
         }
     }
 
+    /// Result for an archive job whose members were extracted into child jobs.
+    pub fn extracted(job: &Job, archive_path: Option<PathBuf>) -> Self {
+        Self {
+            job_id: job.id.clone(),
+            source_path: job.source_path.clone(),
+            success: true,
+            output_path: None,
+            archive_path,
+            symlinks: vec![],
+            category: String::new(),
+            error: None,
+        }
+    }
+
     pub fn failure(job: &Job, error: String) -> Self {
         Self {
             job_id: job.id.clone(),
@@ -221,42 +239,6 @@ mod tests {
         assert!(metadata.has_content());
     }
 
-    #[test]
-    fn test_email_metadata_header_block() {
-        let metadata = EmailMetadata {
-            subject: Some("Test Subject".to_string()),
-            from: Some("sender@example.com".to_string()),
-            to: Some("recipient@example.com".to_string()),
-            date: Some("2024-01-15T10:30:00Z".to_string()),
-            message_id: Some("<msg123@example.com>".to_string()),
-        };
-
-        let header = metadata.to_header_block();
-        assert!(header.contains("=== EMAIL METADATA ==="));
-        assert!(header.contains("From: sender@example.com"));
-        assert!(header.contains("To: recipient@example.com"));
-        assert!(header.contains("Subject: Test Subject"));
-        assert!(header.contains("Date: 2024-01-15T10:30:00Z"));
-        assert!(header.contains("Message-ID: <msg123@example.com>"));
-        assert!(header.contains("======================"));
-    }
-
-    #[test]
-    fn test_email_metadata_header_block_partial() {
-        let metadata = EmailMetadata {
-            subject: Some("Test".to_string()),
-            from: None,
-            to: None,
-            date: None,
-            message_id: None,
-        };
-
-        let header = metadata.to_header_block();
-        assert!(header.contains("Subject: Test"));
-        assert!(!header.contains("From:"));
-        assert!(!header.contains("To:"));
-    }
-
     #[test]
     fn test_job_new() {
         let job = Job::new(PathBuf::from("/test/document.pdf"));
@@ -309,6 +291,52 @@ mod tests {
         assert_eq!(meta.subject, Some("Invoice".to_string()));
     }
 
+    #[test]
+    fn test_job_child_of_inherits_source_metadata() {
+        let metadata = EmailMetadata {
+            subject: Some("Receipts".to_string()),
+            ..Default::default()
+        };
+        let parent = Job::from_email(
+            PathBuf::from("/tmp/receipts.zip"),
+            "gmail-inbox".to_string(),
+            "application/zip".to_string(),
+            metadata,
+        );
+
+        let child = Job::child_of(&parent, PathBuf::from("/tmp/extract/receipt.pdf"));
+
+        assert_ne!(child.id, parent.id);
+        assert_eq!(child.parent_job_id, Some(parent.id.clone()));
+        assert_eq!(child.source_name, Some("gmail-inbox".to_string()));
+        assert_eq!(child.mime_type, Some("application/pdf".to_string()));
+        assert_eq!(
+            child.email_metadata.unwrap().subject,
+            Some("Receipts".to_string())
+        );
+        assert!(parent.parent_job_id.is_none());
+    }
+
+    #[test]
+    fn test_job_with_source_root() {
+        let job = Job::new_with_source(
+            PathBuf::from("/scans/Insurance/2024/scan.pdf"),
+            "scanner".to_string(),
+        )
+        .with_source_root(Path::new("/scans"));
+        assert_eq!(
+            job.relative_path.as_deref(),
+            Some("Insurance/2024/scan.pdf")
+        );
+
+        let child = Job::child_of(&job, PathBuf::from("/tmp/extract/page.pdf"));
+        assert_eq!(child.relative_path, job.relative_path);
+
+        // Files outside the root have no relative path
+        let job = Job::new(PathBuf::from("/other/scan.pdf")).with_source_root(Path::new("/scans"));
+        assert_eq!(job.relative_path, None);
+    }
+
     #[test]
     fn test_job_mime_type_detection() {
         // PDF
@@ -334,7 +362,7 @@ mod tests {
         let result = JobResult::success(
             &job,
             PathBuf::from("/output/doc.pdf"),
-            PathBuf::from("/archive/doc.pdf"),
+            Some(PathBuf::from("/archive/doc.pdf")),
             vec![PathBuf::from("/link/doc.pdf")],
             "invoices".to_string(),
         );
@@ -1,8 +1,10 @@
+pub mod archive;
 pub mod job;
 pub mod multi_scanner;
 pub mod pool;
 pub mod scanner;
 
+pub use archive::{ArchiveFormat, ArchiveLimits};
 pub use job::{EmailMetadata, Job, JobResult};
 pub use multi_scanner::MultiSourceScanner;
 pub use pool::WorkerPool;
@@ -17,6 +17,7 @@ use crate::email::EmailSourceScanner;
 use crate::error::WorkerError;
 use crate::gitops::loader::LoadedConfig;
 use crate::gitops::resource::{EmailSourceConfig, ImportSourceType};
+use crate::worker::archive::ArchiveFormat;
 use crate::worker::job::Job;
 
 /// An enabled local import source with resolved configuration.
@@ -335,12 +336,14 @@ impl MultiSourceScanner {
 
             // Check if file format is supported
             if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                if DocumentFormat::from_extension(ext).is_some() {
+                if DocumentFormat::from_extension(ext).is_some()
+                    || ArchiveFormat::from_extension(ext).is_some()
+                {
                     debug!("Found document in '{}': {}", source.name, path.display());
-                    jobs.push(Job::new_with_source(
-                        path.to_path_buf(),
-                        source.name.clone(),
-                    ));
+                    jobs.push(
+                        Job::new_with_source(path.to_path_buf(), source.name.clone())
+                            .with_source_root(&source.path),
+                    );
                 } else {
                     unsupported_count += 1;
                     debug!("Unsupported format: {} (ext: {})", path.display(), ext);
@@ -618,7 +621,9 @@ impl MultiSourceScanner {
 
                                 // Check if file format is supported
                                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                                    if DocumentFormat::from_extension(ext).is_some() {
+                                    if DocumentFormat::from_extension(ext).is_some()
+                                        || ArchiveFormat::from_extension(ext).is_some()
+                                    {
                                         info!(
                                             "New document detected in '{}': {}",
                                             source_name,
@@ -703,8 +708,9 @@ mod tests {
         use crate::gitops::loader::LoadedConfig;
         use crate::gitops::resource::ResourceWithPath;
         use crate::gitops::resource::{
-            AiSettings, DefaultOutputSettings, GitSettings, ObjectMeta, OcrSettings,
-            ReleaseChannel, ResourceKind, SettingsResource, SettingsSpec, API_VERSION,
+            AiSettings, CategorizationSettings, DefaultOutputSettings, GitSettings, LimitsSettings,
+            ObjectMeta, OcrSettings, ReleaseChannel, ResourceKind, SettingsResource, SettingsSpec,
+            API_VERSION,
         };
 
         let settings = SettingsResource {
@@ -716,19 +722,17 @@ mod tests {
                 output_directory: "/data/output".to_string(),
                 worker_count: 4,
                 ocr: OcrSettings::default(),
+                limits: LimitsSettings::default(),
+                categorization: CategorizationSettings::default(),
                 defaults: DefaultOutputSettings::default(),
                 git: GitSettings::default(),
                 ai: AiSettings::default(),
                 release_channel: ReleaseChannel::default(),
+                overlay: None,
             },
         };
 
-        let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
-            import_sources: vec![],
-        };
+        let config = LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"));
 
         let scanner = MultiSourceScanner::from_config(&config);
         assert!(!scanner.has_sources());
@@ -743,9 +747,10 @@ mod tests {
         use crate::gitops::loader::LoadedConfig;
         use crate::gitops::resource::ResourceWithPath;
         use crate::gitops::resource::{
-            AiSettings, DefaultOutputSettings, FileFilters, GitSettings, ImportSourceResource,
-            ImportSourceSpec, ImportSourceType, LocalSourceConfig, ObjectMeta, OcrSettings,
-            ReleaseChannel, ResourceKind, SettingsResource, SettingsSpec, API_VERSION,
+            AiSettings, CategorizationSettings, DefaultOutputSettings, FileFilters, GitSettings,
+            ImportSourceResource, ImportSourceSpec, ImportSourceType, LimitsSettings,
+            LocalSourceConfig, ObjectMeta, OcrSettings, ReleaseChannel, ResourceKind,
+            SettingsResource, SettingsSpec, API_VERSION,
         };
 
         let temp_dir = TempDir::new().unwrap();
@@ -764,10 +769,13 @@ mod tests {
                 output_directory: "/data/output".to_string(),
                 worker_count: 4,
                 ocr: OcrSettings::default(),
+                limits: LimitsSettings::default(),
+                categorization: CategorizationSettings::default(),
                 defaults: DefaultOutputSettings::default(),
                 git: GitSettings::default(),
                 ai: AiSettings::default(),
                 release_channel: ReleaseChannel::default(),
+                overlay: None,
             },
         };
 
@@ -789,10 +797,8 @@ mod tests {
         };
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(import_source, "sources/test.yaml")],
+            ..LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"))
         };
 
         let scanner = MultiSourceScanner::from_config(&config);
@@ -814,9 +820,10 @@ mod tests {
         use crate::gitops::loader::LoadedConfig;
         use crate::gitops::resource::ResourceWithPath;
         use crate::gitops::resource::{
-            AiSettings, DefaultOutputSettings, FileFilters, GitSettings, ImportSourceResource,
-            ImportSourceSpec, ImportSourceType, LocalSourceConfig, ObjectMeta, OcrSettings,
-            ReleaseChannel, ResourceKind, SettingsResource, SettingsSpec, API_VERSION,
+            AiSettings, CategorizationSettings, DefaultOutputSettings, FileFilters, GitSettings,
+            ImportSourceResource, ImportSourceSpec, ImportSourceType, LimitsSettings,
+            LocalSourceConfig, ObjectMeta, OcrSettings, ReleaseChannel, ResourceKind,
+            SettingsResource, SettingsSpec, API_VERSION,
         };
 
         let temp_dir = TempDir::new().unwrap();
@@ -835,10 +842,13 @@ mod tests {
                 output_directory: "/data/output".to_string(),
                 worker_count: 4,
                 ocr: OcrSettings::default(),
+                limits: LimitsSettings::default(),
+                categorization: CategorizationSettings::default(),
                 defaults: DefaultOutputSettings::default(),
                 git: GitSettings::default(),
                 ai: AiSettings::default(),
                 release_channel: ReleaseChannel::default(),
+                overlay: None,
             },
         };
 
@@ -863,13 +873,11 @@ mod tests {
         };
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(
                 import_source,
                 "sources/filtered.yaml",
             )],
+            ..LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"))
         };
 
         let scanner = MultiSourceScanner::from_config(&config);
@@ -887,9 +895,10 @@ mod tests {
         use crate::gitops::loader::LoadedConfig;
         use crate::gitops::resource::ResourceWithPath;
         use crate::gitops::resource::{
-            AiSettings, DefaultOutputSettings, FileFilters, GitSettings, ImportSourceResource,
-            ImportSourceSpec, ImportSourceType, LocalSourceConfig, ObjectMeta, OcrSettings,
-            ReleaseChannel, ResourceKind, SettingsResource, SettingsSpec, API_VERSION,
+            AiSettings, CategorizationSettings, DefaultOutputSettings, FileFilters, GitSettings,
+            ImportSourceResource, ImportSourceSpec, ImportSourceType, LimitsSettings,
+            LocalSourceConfig, ObjectMeta, OcrSettings, ReleaseChannel, ResourceKind,
+            SettingsResource, SettingsSpec, API_VERSION,
         };
 
         let temp_dir = TempDir::new().unwrap();
@@ -908,10 +917,13 @@ mod tests {
                 output_directory: "/data/output".to_string(),
                 worker_count: 4,
                 ocr: OcrSettings::default(),
+                limits: LimitsSettings::default(),
+                categorization: CategorizationSettings::default(),
                 defaults: DefaultOutputSettings::default(),
                 git: GitSettings::default(),
                 ai: AiSettings::default(),
                 release_channel: ReleaseChannel::default(),
+                overlay: None,
             },
         };
 
@@ -934,13 +946,11 @@ mod tests {
         };
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings.clone(), "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(
                 import_source_non_recursive,
                 "sources/nr.yaml",
             )],
+            ..LoadedConfig::new(ResourceWithPath::new(settings.clone(), "settings.yaml"))
         };
 
         let scanner = MultiSourceScanner::from_config(&config);
@@ -966,13 +976,11 @@ mod tests {
         };
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(
                 import_source_recursive,
                 "sources/r.yaml",
             )],
+            ..LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"))
         };
 
         let scanner = MultiSourceScanner::from_config(&config);
@@ -985,9 +993,10 @@ mod tests {
         use crate::gitops::loader::LoadedConfig;
         use crate::gitops::resource::ResourceWithPath;
         use crate::gitops::resource::{
-            AiSettings, DefaultOutputSettings, FileFilters, GitSettings, ImportSourceResource,
-            ImportSourceSpec, ImportSourceType, LocalSourceConfig, ObjectMeta, OcrSettings,
-            ReleaseChannel, ResourceKind, SettingsResource, SettingsSpec, API_VERSION,
+            AiSettings, CategorizationSettings, DefaultOutputSettings, FileFilters, GitSettings,
+            ImportSourceResource, ImportSourceSpec, ImportSourceType, LimitsSettings,
+            LocalSourceConfig, ObjectMeta, OcrSettings, ReleaseChannel, ResourceKind,
+            SettingsResource, SettingsSpec, API_VERSION,
         };
 
         let temp_dir = TempDir::new().unwrap();
@@ -1002,10 +1011,13 @@ mod tests {
                 output_directory: "/data/output".to_string(),
                 worker_count: 4,
                 ocr: OcrSettings::default(),
+                limits: LimitsSettings::default(),
+                categorization: CategorizationSettings::default(),
                 defaults: DefaultOutputSettings::default(),
                 git: GitSettings::default(),
                 ai: AiSettings::default(),
                 release_channel: ReleaseChannel::default(),
+                overlay: None,
             },
         };
 
@@ -1027,13 +1039,11 @@ mod tests {
         };
 
         let config = LoadedConfig {
-            settings: ResourceWithPath::new(settings, "settings.yaml"),
-            variables: vec![],
-            rules: vec![],
             import_sources: vec![ResourceWithPath::new(
                 import_source,
                 "sources/disabled.yaml",
             )],
+            ..LoadedConfig::new(ResourceWithPath::new(settings, "settings.yaml"))
         };
 
         let scanner = MultiSourceScanner::from_config(&config);
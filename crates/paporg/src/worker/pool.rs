@@ -4,11 +4,12 @@ use std::thread::{self, JoinHandle};
 
 use crossbeam_channel::{bounded, Receiver, Sender};
 use tokio::sync::broadcast;
-use tracing::{debug, error, info, info_span};
+use tracing::{debug, error, info, info_span, warn};
 
 use crate::broadcast::job_progress::{JobPhase, JobProgressEvent};
 use crate::pipeline::progress::{BroadcastProgress, NoopProgress, ProgressReporter};
-use crate::pipeline::{Pipeline, PipelineConfig, PipelineContext};
+use crate::pipeline::{Pipeline, PipelineConfig, PipelineContext, ProgressEvent};
+use crate::worker::archive;
 use crate::worker::job::{Job, JobResult};
 
 pub struct WorkerPool {
@@ -153,40 +154,11 @@ fn run_worker(
                 .entered();
                 debug!("Worker {} processing job: {}", worker_id, filename);
 
-                let result = if let Some(ref sender) = progress_sender {
-                    let filename = job
-                        .source_path
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_else(|| "unknown".to_string());
-                    let source_path = job.source_path.to_string_lossy().to_string();
-
-                    let progress = BroadcastProgress::new(
-                        &job.id,
-                        &filename,
-                        &source_path,
-                        job.source_name.as_deref(),
-                        job.mime_type.as_deref(),
-                        Arc::clone(sender),
-                    );
-
-                    progress.report(crate::pipeline::ProgressEvent::Phase {
-                        phase: JobPhase::Queued,
-                        message: "Job queued for processing".to_string(),
-                    });
-
-                    let ctx = PipelineContext::new(job);
-                    let (result, _ctx) = pipeline.run(ctx, &progress);
-                    result
-                } else {
-                    let ctx = PipelineContext::new(job);
-                    let (result, _ctx) = pipeline.run(ctx, &NoopProgress);
-                    result
-                };
-
-                if let Err(e) = result_sender.send(result) {
-                    error!("Worker {} failed to send result: {}", worker_id, e);
-                    break;
+                for result in process_job(&pipeline, job, 0, &progress_sender) {
+                    if let Err(e) = result_sender.send(result) {
+                        error!("Worker {} failed to send result: {}", worker_id, e);
+                        return;
+                    }
                 }
             }
             Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
@@ -202,9 +174,156 @@ fn run_worker(
     debug!("Worker {} stopped", worker_id);
 }
 
+/// Builds the progress reporter for a job, broadcasting if a sender is configured.
+fn progress_for(
+    job: &Job,
+    progress_sender: &Option<Arc<broadcast::Sender<JobProgressEvent>>>,
+) -> Box<dyn ProgressReporter> {
+    match progress_sender {
+        Some(sender) => {
+            let filename = job
+                .source_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            let source_path = job.source_path.to_string_lossy().to_string();
+
+            Box::new(
+                BroadcastProgress::new(
+                    &job.id,
+                    &filename,
+                    &source_path,
+                    job.source_name.as_deref(),
+                    job.mime_type.as_deref(),
+                    Arc::clone(sender),
+                )
+                .with_parent_job(job.parent_job_id.as_deref()),
+            )
+        }
+        None => Box::new(NoopProgress),
+    }
+}
+
+/// Runs a job through the pipeline, expanding archives into child jobs.
+///
+/// Returns the results of all child jobs followed by the job's own result.
+fn process_job(
+    pipeline: &Pipeline,
+    job: Job,
+    depth: u32,
+    progress_sender: &Option<Arc<broadcast::Sender<JobProgressEvent>>>,
+) -> Vec<JobResult> {
+    let progress = progress_for(&job, progress_sender);
+    progress.report(ProgressEvent::Phase {
+        phase: JobPhase::Queued,
+        message: "Job queued for processing".to_string(),
+    });
+
+    if archive::is_archive(&job.source_path) {
+        return process_archive(pipeline, job, depth, progress.as_ref(), progress_sender);
+    }
+
+    let ctx = PipelineContext::new(job);
+    let (result, _ctx) = pipeline.run(ctx, progress.as_ref());
+    vec![result]
+}
+
+/// Unpacks an archive job, processes each member as a child job and archives the container.
+fn process_archive(
+    pipeline: &Pipeline,
+    job: Job,
+    depth: u32,
+    progress: &dyn ProgressReporter,
+    progress_sender: &Option<Arc<broadcast::Sender<JobProgressEvent>>>,
+) -> Vec<JobResult> {
+    let _span = info_span!("archive", depth).entered();
+    progress.report(ProgressEvent::Phase {
+        phase: JobPhase::Processing,
+        message: "Extracting archive...".to_string(),
+    });
+
+    let extract_root = archive::default_extract_root();
+    let expansion = match archive::expand_job(&job, &extract_root, pipeline.archive_limits(), depth)
+    {
+        Ok(expansion) => expansion,
+        Err(e) => {
+            let err_msg = e.to_string();
+            progress.report(ProgressEvent::Failed {
+                error: err_msg.clone(),
+            });
+            return vec![JobResult::failure(&job, err_msg)];
+        }
+    };
+
+    if !expansion.skipped.is_empty() {
+        info!(
+            "Skipped {} unsupported archive member(s): {}",
+            expansion.skipped.len(),
+            expansion.skipped.join(", ")
+        );
+    }
+
+    let total = expansion.children.len();
+    let mut results = Vec::new();
+    for child in expansion.children {
+        results.extend(process_job(pipeline, child, depth + 1, progress_sender));
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+    if failed > 0 {
+        warn!(
+            "{} archive member(s) of {} failed; they remain available in the archived source",
+            failed,
+            job.source_path.display()
+        );
+    }
+    // Failed members can be recovered by reprocessing the archived container,
+    // so the extracted copies are always discarded.
+    let extract_dir = extract_root.join(&job.id);
+    if let Err(e) = std::fs::remove_dir_all(&extract_dir) {
+        debug!("Failed to remove {}: {}", extract_dir.display(), e);
+    }
+
+    // A nested archive was extracted from its parent, which is archived instead.
+    let archive_path = if job.parent_job_id.is_none() {
+        progress.report(ProgressEvent::Phase {
+            phase: JobPhase::Archiving,
+            message: "Archiving source file...".to_string(),
+        });
+        match pipeline.archive_source(&job.source_path) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                let err_msg = e.to_string();
+                progress.report(ProgressEvent::Failed {
+                    error: err_msg.clone(),
+                });
+                results.push(JobResult::failure(&job, err_msg));
+                return results;
+            }
+        }
+    } else {
+        None
+    };
+
+    progress.report(ProgressEvent::Phase {
+        phase: JobPhase::Completed,
+        message: format!(
+            "Extracted {} documents from archive ({} failed, {} skipped)",
+            total,
+            failed,
+            expansion.skipped.len()
+        ),
+    });
+    results.push(JobResult::extracted(&job, archive_path));
+    results
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::schema::CategorizationConfig;
+    use crate::processor::ProcessingLimits;
+    use crate::worker::archive::ArchiveLimits;
     use std::io::Write;
     use std::path::Path;
     use tempfile::TempDir;
@@ -216,9 +335,16 @@ mod tests {
             ocr_enabled: false,
             ocr_languages: vec![],
             ocr_dpi: 300,
+            limits: ProcessingLimits::default(),
+            archive_limits: ArchiveLimits::default(),
             rules: vec![],
             defaults: crate::config::schema::DefaultsConfig::default(),
+            categorization: CategorizationConfig::default(),
             extracted_variables: vec![],
+            computed_variables: vec![],
+            lookup_tables: vec![],
+            correspondents: vec![],
+            classifier: None,
         })
     }
 
@@ -270,4 +396,85 @@ mod tests {
         pool.shutdown();
         pool.wait();
     }
+
+    #[test]
+    fn test_submit_and_process_zip_job() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::create_dir_all(&output_dir).unwrap();
+
+        let config = create_test_config(&input_dir, &output_dir);
+        let pool = WorkerPool::new(config, 1);
+
+        let zip_path = input_dir.join("bundle.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("a.txt", options).unwrap();
+        writer.write_all(b"first document").unwrap();
+        writer.start_file("b.txt", options).unwrap();
+        writer.write_all(b"second document").unwrap();
+        writer.start_file("setup.exe", options).unwrap();
+        writer.write_all(b"MZ").unwrap();
+        writer.finish().unwrap();
+
+        let job = Job::new(zip_path.clone());
+        let parent_id = job.id.clone();
+        pool.submit(job).unwrap();
+
+        let results: Vec<JobResult> = (0..3).map(|_| pool.recv_result().unwrap()).collect();
+        for result in &results {
+            assert!(result.success, "Job failed: {:?}", result.error);
+        }
+        let parent = results.last().unwrap();
+        assert_eq!(parent.job_id, parent_id);
+        assert!(parent.output_path.is_none());
+        assert!(parent.archive_path.as_ref().unwrap().exists());
+        assert!(!zip_path.exists());
+        assert!(results[..2].iter().all(|r| r.output_path.is_some()));
+        assert!(results[..2].iter().all(|r| r.archive_path.is_none()));
+        assert_eq!(
+            std::fs::read_dir(input_dir.join("archive"))
+                .unwrap()
+                .count(),
+            1
+        );
+        assert!(!archive::default_extract_root().join(&parent_id).exists());
+        assert!(pool.try_recv_result().is_none());
+
+        pool.shutdown();
+        pool.wait();
+    }
+
+    #[test]
+    fn test_zip_job_uses_configured_archive_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::create_dir_all(&output_dir).unwrap();
+
+        let mut config = Arc::into_inner(create_test_config(&input_dir, &output_dir)).unwrap();
+        config.archive_limits.max_entries = 1;
+        let pool = WorkerPool::new(Arc::new(config), 1);
+
+        let zip_path = input_dir.join("bundle.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for name in ["a.txt", "b.txt"] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(b"document").unwrap();
+        }
+        writer.finish().unwrap();
+
+        pool.submit(Job::new(zip_path.clone())).unwrap();
+        let result = pool.recv_result().unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("more than 1 files"));
+        assert!(zip_path.exists());
+
+        pool.shutdown();
+        pool.wait();
+    }
 }
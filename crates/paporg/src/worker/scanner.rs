@@ -10,6 +10,7 @@ use walkdir::WalkDir;
 
 use crate::config::DocumentFormat;
 use crate::error::WorkerError;
+use crate::worker::archive::ArchiveFormat;
 use crate::worker::job::Job;
 
 pub struct DirectoryScanner {
@@ -54,9 +55,11 @@ impl DirectoryScanner {
 
             // Check if file format is supported
             if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                if DocumentFormat::from_extension(ext).is_some() {
+                if DocumentFormat::from_extension(ext).is_some()
+                    || ArchiveFormat::from_extension(ext).is_some()
+                {
                     debug!("Found document: {}", path.display());
-                    jobs.push(Job::new(path.to_path_buf()));
+                    jobs.push(Job::new(path.to_path_buf()).with_source_root(&self.input_directory));
                 }
             }
         }
@@ -123,7 +126,9 @@ impl DirectoryScanner {
                             // Check if file exists and is a supported format
                             if path.exists() {
                                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                                    if DocumentFormat::from_extension(ext).is_some() {
+                                    if DocumentFormat::from_extension(ext).is_some()
+                                        || ArchiveFormat::from_extension(ext).is_some()
+                                    {
                                         info!("New document detected: {}", path.display());
                                         callback(path.to_path_buf());
                                     }
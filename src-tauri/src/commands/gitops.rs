@@ -4,7 +4,15 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use paporg::gitops::{AnyResource, ConfigLoader, ConfigValidator, FileTreeNode, ResourceKind};
+use paporg::config::load_config;
+use paporg::gitops::git::GitRepository;
+use paporg::gitops::progress::GitOperationType;
+use paporg::gitops::schema::resource_schema;
+use paporg::gitops::{
+    migrate_legacy_config, AnyResource, ConfigLoader, ConfigValidator, Diagnostic, FileTreeNode,
+    MigrationReport, ResourceKind, RuleHistory, RuleTestReport, RuleTestRunner, API_VERSION,
+    DEFAULT_UNUSED_AFTER_DAYS,
+};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use tokio::sync::RwLock;
@@ -41,6 +49,30 @@ pub struct ResourceDetail {
     pub name: String,
     pub path: String,
     pub yaml: String,
+    /// The rule with inheritance resolved, for rules that extend another
+    /// rule or a template, or the resource patched by the overlay.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_yaml: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationResponse {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Errors and warnings with the file and line they point at.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiVersionUpgradeResponse {
+    /// Files rewritten, relative to the config directory.
+    pub files: Vec<String>,
+    /// The commit holding the rewritten files, when git is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_hash: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -167,6 +199,58 @@ pub async fn list_gitops_resources(
                 },
             })
             .collect(),
+        ResourceKind::RuleTest => config
+            .rule_tests
+            .iter()
+            .map(|t| ResourceSummary {
+                name: t.resource.metadata.name.clone(),
+                path: t.path.to_string_lossy().to_string(),
+                labels: if t.resource.metadata.labels.is_empty() {
+                    None
+                } else {
+                    Some(t.resource.metadata.labels.clone())
+                },
+            })
+            .collect(),
+        ResourceKind::RuleTemplate => config
+            .rule_templates
+            .iter()
+            .map(|t| ResourceSummary {
+                name: t.resource.metadata.name.clone(),
+                path: t.path.to_string_lossy().to_string(),
+                labels: if t.resource.metadata.labels.is_empty() {
+                    None
+                } else {
+                    Some(t.resource.metadata.labels.clone())
+                },
+            })
+            .collect(),
+        ResourceKind::Correspondent => config
+            .correspondents
+            .iter()
+            .map(|c| ResourceSummary {
+                name: c.resource.metadata.name.clone(),
+                path: c.path.to_string_lossy().to_string(),
+                labels: if c.resource.metadata.labels.is_empty() {
+                    None
+                } else {
+                    Some(c.resource.metadata.labels.clone())
+                },
+            })
+            .collect(),
+        ResourceKind::LookupTable => config
+            .lookup_tables
+            .iter()
+            .map(|t| ResourceSummary {
+                name: t.resource.metadata.name.clone(),
+                path: t.path.to_string_lossy().to_string(),
+                labels: if t.resource.metadata.labels.is_empty() {
+                    None
+                } else {
+                    Some(t.resource.metadata.labels.clone())
+                },
+            })
+            .collect(),
     };
 
     Ok(ApiResponse::ok(ResourceListResponse { kind, items }))
@@ -212,33 +296,92 @@ pub async fn get_gitops_resource(
                 None => (None, PathBuf::new()),
             }
         }
-        ResourceKind::Rule => {
+        ResourceKind::Rule => match config.authored_rule(&name) {
+            Some(r) => (Some(serde_yaml::to_string(&r.resource)), r.path.clone()),
+            None => (None, PathBuf::new()),
+        },
+        ResourceKind::ImportSource => {
             match config
-                .rules
+                .import_sources
                 .iter()
-                .find(|r| r.resource.metadata.name == name)
+                .find(|s| s.resource.metadata.name == name)
             {
-                Some(r) => (Some(serde_yaml::to_string(&r.resource)), r.path.clone()),
+                Some(s) => (Some(serde_yaml::to_string(&s.resource)), s.path.clone()),
                 None => (None, PathBuf::new()),
             }
         }
-        ResourceKind::ImportSource => {
+        ResourceKind::RuleTest => {
             match config
-                .import_sources
+                .rule_tests
                 .iter()
-                .find(|s| s.resource.metadata.name == name)
+                .find(|t| t.resource.metadata.name == name)
             {
-                Some(s) => (Some(serde_yaml::to_string(&s.resource)), s.path.clone()),
+                Some(t) => (Some(serde_yaml::to_string(&t.resource)), t.path.clone()),
+                None => (None, PathBuf::new()),
+            }
+        }
+        ResourceKind::RuleTemplate => {
+            match config
+                .rule_templates
+                .iter()
+                .find(|t| t.resource.metadata.name == name)
+            {
+                Some(t) => (Some(serde_yaml::to_string(&t.resource)), t.path.clone()),
+                None => (None, PathBuf::new()),
+            }
+        }
+        ResourceKind::Correspondent => {
+            match config
+                .correspondents
+                .iter()
+                .find(|c| c.resource.metadata.name == name)
+            {
+                Some(c) => (Some(serde_yaml::to_string(&c.resource)), c.path.clone()),
+                None => (None, PathBuf::new()),
+            }
+        }
+        ResourceKind::LookupTable => {
+            match config
+                .lookup_tables
+                .iter()
+                .find(|t| t.resource.metadata.name == name)
+            {
+                Some(t) => (Some(serde_yaml::to_string(&t.resource)), t.path.clone()),
                 None => (None, PathBuf::new()),
             }
         }
     };
 
+    // Rules extending another rule or a template are edited as written, but
+    // run with inheritance resolved
+    let mut effective_yaml = match resource_kind {
+        ResourceKind::Rule => config
+            .rules
+            .iter()
+            .find(|r| r.resource.metadata.name == name && r.resource.spec.extends.is_some())
+            .and_then(|r| serde_yaml::to_string(&r.resource).ok()),
+        _ => None,
+    };
+
+    // Resources patched by the overlay are likewise edited as written in
+    // their file
+    let mut yaml_result = yaml_result;
+    if let (Some(overlay), Some(config_dir)) = (&config.overlay, &state.config_dir) {
+        if overlay.patch(resource_kind, &name).is_some() {
+            let loader = ConfigLoader::new(config_dir);
+            if let Ok(authored) = loader.load_authored(&path, resource_kind, &name) {
+                effective_yaml = yaml_result.take().and_then(|y| y.ok());
+                yaml_result = Some(serde_yaml::to_string(&authored));
+            }
+        }
+    }
+
     match yaml_result {
         Some(Ok(yaml)) => Ok(ApiResponse::ok(ResourceDetail {
             name,
             path: path.to_string_lossy().to_string(),
             yaml,
+            effective_yaml,
         })),
         Some(Err(e)) => Ok(ApiResponse::err(format!(
             "YAML serialization failed: {}",
@@ -306,6 +449,22 @@ pub async fn create_gitops_resource(
                 .import_sources
                 .iter()
                 .any(|s| s.resource.metadata.name == resource.name()),
+            ResourceKind::RuleTest => config
+                .rule_tests
+                .iter()
+                .any(|t| t.resource.metadata.name == resource.name()),
+            ResourceKind::RuleTemplate => config
+                .rule_templates
+                .iter()
+                .any(|t| t.resource.metadata.name == resource.name()),
+            ResourceKind::Correspondent => config
+                .correspondents
+                .iter()
+                .any(|c| c.resource.metadata.name == resource.name()),
+            ResourceKind::LookupTable => config
+                .lookup_tables
+                .iter()
+                .any(|t| t.resource.metadata.name == resource.name()),
         };
 
         if exists {
@@ -341,6 +500,7 @@ pub async fn create_gitops_resource(
         name: resource.name().to_string(),
         path: file_path.to_string_lossy().to_string(),
         yaml,
+        effective_yaml: None,
     }))
 }
 
@@ -436,6 +596,66 @@ pub async fn update_gitops_resource(
                 }
             }
         }
+        ResourceKind::RuleTest => {
+            match config
+                .rule_tests
+                .iter()
+                .find(|t| t.resource.metadata.name == name)
+            {
+                Some(t) => t.path.clone(),
+                None => {
+                    return Ok(ApiResponse::err(format!(
+                        "Resource not found: {}/{}",
+                        kind, name
+                    )))
+                }
+            }
+        }
+        ResourceKind::RuleTemplate => {
+            match config
+                .rule_templates
+                .iter()
+                .find(|t| t.resource.metadata.name == name)
+            {
+                Some(t) => t.path.clone(),
+                None => {
+                    return Ok(ApiResponse::err(format!(
+                        "Resource not found: {}/{}",
+                        kind, name
+                    )))
+                }
+            }
+        }
+        ResourceKind::Correspondent => {
+            match config
+                .correspondents
+                .iter()
+                .find(|c| c.resource.metadata.name == name)
+            {
+                Some(c) => c.path.clone(),
+                None => {
+                    return Ok(ApiResponse::err(format!(
+                        "Resource not found: {}/{}",
+                        kind, name
+                    )))
+                }
+            }
+        }
+        ResourceKind::LookupTable => {
+            match config
+                .lookup_tables
+                .iter()
+                .find(|t| t.resource.metadata.name == name)
+            {
+                Some(t) => t.path.clone(),
+                None => {
+                    return Ok(ApiResponse::err(format!(
+                        "Resource not found: {}/{}",
+                        kind, name
+                    )))
+                }
+            }
+        }
     };
 
     // Write the updated resource
@@ -457,6 +677,7 @@ pub async fn update_gitops_resource(
         name,
         path: file_path.to_string_lossy().to_string(),
         yaml,
+        effective_yaml: None,
     }))
 }
 
@@ -537,11 +758,71 @@ pub async fn delete_gitops_resource(
                 }
             }
         }
+        ResourceKind::RuleTest => {
+            match config
+                .rule_tests
+                .iter()
+                .find(|t| t.resource.metadata.name == name)
+            {
+                Some(t) => t.path.clone(),
+                None => {
+                    return Ok(ApiResponse::err(format!(
+                        "Resource not found: {}/{}",
+                        kind, name
+                    )))
+                }
+            }
+        }
+        ResourceKind::RuleTemplate => {
+            match config
+                .rule_templates
+                .iter()
+                .find(|t| t.resource.metadata.name == name)
+            {
+                Some(t) => t.path.clone(),
+                None => {
+                    return Ok(ApiResponse::err(format!(
+                        "Resource not found: {}/{}",
+                        kind, name
+                    )))
+                }
+            }
+        }
+        ResourceKind::Correspondent => {
+            match config
+                .correspondents
+                .iter()
+                .find(|c| c.resource.metadata.name == name)
+            {
+                Some(c) => c.path.clone(),
+                None => {
+                    return Ok(ApiResponse::err(format!(
+                        "Resource not found: {}/{}",
+                        kind, name
+                    )))
+                }
+            }
+        }
+        ResourceKind::LookupTable => {
+            match config
+                .lookup_tables
+                .iter()
+                .find(|t| t.resource.metadata.name == name)
+            {
+                Some(t) => t.path.clone(),
+                None => {
+                    return Ok(ApiResponse::err(format!(
+                        "Resource not found: {}/{}",
+                        kind, name
+                    )))
+                }
+            }
+        }
         ResourceKind::Settings => unreachable!(),
     };
 
     let loader = ConfigLoader::new(&config_dir);
-    if let Err(e) = loader.delete_resource(&file_path) {
+    if let Err(e) = loader.remove_resource(&file_path, resource_kind, &name) {
         return Ok(ApiResponse::err(format!(
             "Failed to delete resource: {}",
             e
@@ -567,6 +848,7 @@ pub async fn simulate_rule(
     state: State<'_, Arc<RwLock<TauriAppState>>>,
     request: SimulateRuleRequest,
 ) -> Result<ApiResponse<SimulateRuleResponse>, String> {
+    use paporg::categorizer::DocumentView;
     use paporg::config::variables::VariableEngine;
 
     let text = &request.ocr_text;
@@ -696,14 +978,22 @@ pub async fn simulate_rule(
 
     // Get variable definitions from config
     let state = state.read().await;
-    let extracted_vars = state
+    let (variables, lookup_tables) = state
         .config()
-        .map(|c| c.to_legacy_config().variables.extracted)
+        .map(|c| {
+            let legacy = c.to_legacy_config();
+            (legacy.variables, legacy.lookup_tables)
+        })
         .unwrap_or_default();
 
     // Substitute variables in output paths
-    let engine = VariableEngine::new(&extracted_vars);
-    let mut extracted = engine.extract_variables(text);
+    let engine = VariableEngine::new(&variables.extracted)
+        .with_computed_variables(&variables.computed)
+        .with_lookup_tables(&lookup_tables);
+    let mut extracted = engine.extract_document_variables(&DocumentView {
+        filename: Some(&request.filename),
+        ..DocumentView::new(text)
+    });
     extracted.insert("category".to_string(), request.category.clone());
 
     let resolved_directory =
@@ -726,7 +1016,186 @@ pub async fn simulate_rule(
 #[tauri::command]
 pub async fn validate_config(
     state: State<'_, Arc<RwLock<TauriAppState>>>,
-) -> Result<ApiResponse<Vec<String>>, String> {
+) -> Result<ApiResponse<ValidationResponse>, String> {
+    let state = state.read().await;
+
+    let config = match (state.config(), &state.config_dir) {
+        (Some(c), _) => c,
+        // Report why the configuration does not load, e.g. a YAML syntax error
+        (None, Some(dir)) => {
+            return Ok(match ConfigLoader::new(dir).load() {
+                Ok(_) => ApiResponse::err("Configuration not loaded"),
+                Err(e) => {
+                    let diagnostics = e.diagnostics();
+                    ApiResponse::ok(ValidationResponse {
+                        valid: false,
+                        errors: diagnostics.iter().map(|d| d.message.clone()).collect(),
+                        warnings: Vec::new(),
+                        diagnostics,
+                    })
+                }
+            });
+        }
+        (None, None) => return Ok(ApiResponse::err("Configuration not loaded")),
+    };
+
+    let mut validator = match &state.config_dir {
+        Some(dir) => ConfigValidator::new().with_config_dir(dir),
+        None => ConfigValidator::new(),
+    };
+
+    // Job history lets the validator warn about rules that stopped matching
+    if let Some(db) = state.job_store.get_database() {
+        match RuleHistory::from_database(&db, DEFAULT_UNUSED_AFTER_DAYS) {
+            Ok(history) => validator = validator.with_rule_history(history),
+            Err(e) => log::warn!("Failed to load rule history: {}", e),
+        }
+    }
+
+    let valid = validator.validate(config).is_ok();
+    Ok(ApiResponse::ok(ValidationResponse {
+        valid,
+        errors: validator.errors(),
+        warnings: validator.warnings(),
+        diagnostics: validator.diagnostics().to_vec(),
+    }))
+}
+
+/// Get the JSON Schema of a resource kind, for editor completion and checks.
+#[tauri::command]
+pub async fn get_resource_schema(kind: String) -> Result<ApiResponse<serde_json::Value>, String> {
+    match kind.parse::<ResourceKind>() {
+        Ok(kind) => Ok(ApiResponse::ok(resource_schema(kind))),
+        Err(_) => Ok(ApiResponse::err(format!("Unknown resource kind: {}", kind))),
+    }
+}
+
+/// Convert a legacy JSON config file into resources of the config directory.
+#[tauri::command]
+pub async fn migrate_legacy_config_file(
+    app: tauri::AppHandle,
+    state: State<'_, Arc<RwLock<TauriAppState>>>,
+    source: String,
+) -> Result<ApiResponse<MigrationReport>, String> {
+    let mut state = state.write().await;
+
+    let config_dir = match &state.config_dir {
+        Some(dir) => dir.clone(),
+        None => return Ok(ApiResponse::err("No config directory set")),
+    };
+
+    let legacy = match load_config(&source) {
+        Ok(config) => config,
+        Err(e) => return Ok(ApiResponse::err(format!("Invalid legacy config: {}", e))),
+    };
+
+    let report = match migrate_legacy_config(&legacy, &ConfigLoader::new(&config_dir)) {
+        Ok(report) => report,
+        Err(e) => return Ok(ApiResponse::err(format!("Failed to migrate config: {}", e))),
+    };
+
+    if let Err(e) = state.reload() {
+        log::error!("Failed to reload config after migration: {}", e);
+    }
+    drop(state);
+
+    crate::events::emit_config_changed(&app);
+
+    Ok(ApiResponse::ok(report))
+}
+
+/// Rewrite resources of older API versions in the current one, committing
+/// the rewritten files when git is enabled.
+#[tauri::command]
+pub async fn upgrade_resource_api_version(
+    app: tauri::AppHandle,
+    state: State<'_, Arc<RwLock<TauriAppState>>>,
+) -> Result<ApiResponse<ApiVersionUpgradeResponse>, String> {
+    let state_guard = state.read().await;
+
+    let config_dir = match &state_guard.config_dir {
+        Some(dir) => dir.clone(),
+        None => return Ok(ApiResponse::err("No config directory set")),
+    };
+
+    let git_settings = state_guard
+        .config()
+        .map(|c| c.settings.resource.spec.git.clone())
+        .unwrap_or_default();
+    let git_broadcaster = state_guard.git_broadcaster.clone();
+    drop(state_guard);
+
+    let repo = GitRepository::new(&config_dir, git_settings.clone());
+    let commit = git_settings.enabled && repo.is_git_repo();
+
+    // The upgrade is committed on its own, so other changes must not be
+    // pending
+    if commit {
+        match repo.status() {
+            Ok(status) if !status.is_clean => {
+                return Ok(ApiResponse::err(
+                    "Commit or discard pending changes before upgrading",
+                ))
+            }
+            Ok(_) => {}
+            Err(e) => return Ok(ApiResponse::err(e.to_string())),
+        }
+    }
+
+    let files: Vec<String> = match ConfigLoader::new(&config_dir).upgrade_api_version() {
+        Ok(files) => files
+            .iter()
+            .map(|f| f.to_string_lossy().to_string())
+            .collect(),
+        Err(e) => return Ok(ApiResponse::err(format!("Failed to upgrade: {}", e))),
+    };
+
+    let mut commit_hash = None;
+    if commit && !files.is_empty() {
+        let paths: Vec<&str> = files.iter().map(String::as_str).collect();
+        let progress = git_broadcaster.start_operation(GitOperationType::Commit);
+        let op_id = progress.operation_id().to_string();
+        let result = repo
+            .commit_with_progress(
+                &format!("Upgrade resources to {}", API_VERSION),
+                Some(&paths),
+                &progress,
+            )
+            .await;
+        git_broadcaster.complete_operation(&op_id);
+
+        match result {
+            Ok(result) => commit_hash = result.commit_hash,
+            Err(e) => {
+                return Ok(ApiResponse::err(format!(
+                    "Upgraded files but failed to commit: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    if !files.is_empty() {
+        let mut state_write = state.write().await;
+        if let Err(e) = state_write.reload() {
+            log::error!("Failed to reload config after upgrade: {}", e);
+        }
+        drop(state_write);
+        crate::events::emit_config_changed(&app);
+    }
+
+    Ok(ApiResponse::ok(ApiVersionUpgradeResponse {
+        files,
+        commit_hash,
+    }))
+}
+
+/// Run rule tests, optionally only the RuleTest resource with the given name.
+#[tauri::command]
+pub async fn run_rule_tests(
+    state: State<'_, Arc<RwLock<TauriAppState>>>,
+    name: Option<String>,
+) -> Result<ApiResponse<RuleTestReport>, String> {
     let state = state.read().await;
 
     let config = match state.config() {
@@ -734,9 +1203,27 @@ pub async fn validate_config(
         None => return Ok(ApiResponse::err("Configuration not loaded")),
     };
 
-    let mut validator = ConfigValidator::new();
-    match validator.validate(config) {
-        Ok(()) => Ok(ApiResponse::ok(vec![])),
-        Err(_) => Ok(ApiResponse::ok(validator.errors().to_vec())),
+    let config_dir = match &state.config_dir {
+        Some(dir) => dir.clone(),
+        None => return Ok(ApiResponse::err("No config directory set")),
+    };
+
+    let tests: Vec<_> = config
+        .rule_tests
+        .iter()
+        .filter(|t| name.as_ref().is_none_or(|n| &t.resource.metadata.name == n))
+        .cloned()
+        .collect();
+
+    if let Some(name) = &name {
+        if tests.is_empty() {
+            return Ok(ApiResponse::err(format!(
+                "Resource not found: RuleTest/{}",
+                name
+            )));
+        }
     }
+
+    let runner = RuleTestRunner::new(config, config_dir);
+    Ok(ApiResponse::ok(runner.run(&tests)))
 }
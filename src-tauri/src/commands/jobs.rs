@@ -1,9 +1,9 @@
 //! Job query and operation commands.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use paporg::broadcast::{JobListResponse, JobQueryParams, StoredJob};
+use paporg::broadcast::{CategoryCorrection, JobListResponse, JobQueryParams, StoredJob};
 use paporg::worker::job::Job;
 use serde::Serialize;
 use tauri::State;
@@ -53,6 +53,7 @@ pub async fn query_jobs(
     status: Option<String>,
     category: Option<String>,
     source_name: Option<String>,
+    tag: Option<String>,
     from_date: Option<String>,
     to_date: Option<String>,
     limit: Option<u64>,
@@ -64,6 +65,7 @@ pub async fn query_jobs(
         status,
         category,
         source_name,
+        tag,
         from_date,
         to_date,
         limit,
@@ -121,24 +123,42 @@ pub async fn get_job_ocr(
         Ok(true) => {}
     }
 
-    // Get OCR settings from config
+    match extract_archive_text(&state, &archive_path) {
+        Ok(text) => Ok(ApiResponse::ok(OcrResponse { text })),
+        Err(e) => Ok(ApiResponse::err(e)),
+    }
+}
+
+/// Extracts the text of an archived document with the configured OCR settings.
+fn extract_archive_text(state: &TauriAppState, archive_path: &Path) -> Result<String, String> {
     let config = state.config();
-    let (ocr_enabled, languages, dpi) = config
+    let (ocr_enabled, languages, dpi, limits) = config
         .map(|c| {
             let legacy = c.to_legacy_config();
-            (legacy.ocr.enabled, legacy.ocr.languages, legacy.ocr.dpi)
+            (
+                legacy.ocr.enabled,
+                legacy.ocr.languages,
+                legacy.ocr.dpi,
+                paporg::processor::ProcessingLimits::from_config(&legacy.limits),
+            )
         })
-        .unwrap_or((true, vec!["eng".to_string()], 300));
+        .unwrap_or_else(|| {
+            (
+                true,
+                vec!["eng".to_string()],
+                300,
+                paporg::processor::ProcessingLimits::default(),
+            )
+        });
 
     // Create processor and run OCR
-    let processor = paporg::processor::ProcessorRegistry::new(ocr_enabled, &languages, dpi);
+    let processor =
+        paporg::processor::ProcessorRegistry::with_limits(ocr_enabled, &languages, dpi, limits);
 
-    match processor.process(&archive_path) {
-        Ok(processed) => Ok(ApiResponse::ok(OcrResponse {
-            text: processed.text,
-        })),
-        Err(e) => Ok(ApiResponse::err(format!("OCR failed: {}", e))),
-    }
+    processor
+        .process(archive_path)
+        .map(|processed| processed.text)
+        .map_err(|e| format!("OCR failed: {}", e))
 }
 
 /// Re-run a single job.
@@ -227,6 +247,48 @@ pub async fn ignore_job(
     }
 }
 
+/// Correct the category of a job by hand and train the classifier on it.
+/// The document stays where it was filed.
+#[tauri::command]
+pub async fn correct_job_category(
+    state: State<'_, Arc<RwLock<TauriAppState>>>,
+    job_id: String,
+    category: String,
+) -> Result<ApiResponse<CategoryCorrection>, String> {
+    let state = state.read().await;
+
+    let category = category.trim();
+    if category.is_empty() {
+        return Ok(ApiResponse::err("Category is required"));
+    }
+
+    let mut correction = match state.job_store.correct_category(&job_id, category) {
+        Ok(Some(correction)) => correction,
+        Ok(None) => return Ok(ApiResponse::err(format!("Job not found: {}", job_id))),
+        Err(e) => return Ok(ApiResponse::err(format!("Database error: {}", e))),
+    };
+
+    // Jobs completed before the classifier existed have no stored text;
+    // extract it from the archive instead.
+    if !correction.learned {
+        let classifier = state.job_store.classifier();
+        let archive_path = correction.job.archive_path.as_ref().map(PathBuf::from);
+        if let (Some(classifier), Some(archive_path)) = (classifier, archive_path) {
+            if let Ok(true) = fs::try_exists(&archive_path).await {
+                match extract_archive_text(&state, &archive_path) {
+                    Ok(text) => match classifier.learn(&job_id, Some(category), &text) {
+                        Ok(()) => correction.learned = true,
+                        Err(e) => log::warn!("Failed to learn corrected category: {}", e),
+                    },
+                    Err(e) => log::warn!("Failed to extract text for classifier: {}", e),
+                }
+            }
+        }
+    }
+
+    Ok(ApiResponse::ok(correction))
+}
+
 /// Re-run all unsorted jobs.
 #[tauri::command]
 pub async fn rerun_unsorted(
@@ -164,7 +164,8 @@ pub async fn pick_and_upload_files(
         .add_filter(
             "Documents",
             &[
-                "pdf", "png", "jpg", "jpeg", "gif", "tiff", "bmp", "webp", "docx", "doc", "txt",
+                "pdf", "png", "jpg", "jpeg", "gif", "tiff", "tif", "bmp", "webp", "heic", "heif",
+                "avif", "docx", "doc", "odt", "rtf", "html", "htm", "txt", "zip", "7z",
             ],
         )
         .add_filter("All Files", &["*"])
@@ -73,6 +73,9 @@ fn init_job_store_database(job_store: &paporg::broadcast::JobStore) {
 }
 
 fn main() {
+    // Must run first: sandbox worker processes handle one document and exit here.
+    paporg::processor::sandbox::init();
+
     // Create a Tokio runtime early — the OTel batch exporter needs one to spawn
     // its background flush task. This runtime is also used by Tauri.
     #[cfg(feature = "otel")]
@@ -267,6 +270,7 @@ fn main() {
             commands::get_job_ocr,
             commands::rerun_job,
             commands::ignore_job,
+            commands::correct_job_category,
             commands::rerun_unsorted,
             // GitOps commands
             commands::get_file_tree,
@@ -277,6 +281,10 @@ fn main() {
             commands::delete_gitops_resource,
             commands::simulate_rule,
             commands::validate_config,
+            commands::get_resource_schema,
+            commands::migrate_legacy_config_file,
+            commands::upgrade_resource_api_version,
+            commands::run_rule_tests,
             // Git commands
             commands::git_status,
             commands::git_pull,
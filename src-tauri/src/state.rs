@@ -129,7 +129,10 @@ impl TauriAppState {
             .ok_or("Configuration not loaded")?;
 
         let legacy_config = config.to_legacy_config();
-        let pipeline_config = Arc::new(PipelineConfig::from_config(&legacy_config));
+        let pipeline_config = Arc::new(
+            PipelineConfig::from_config(&legacy_config)
+                .with_classifier(self.job_store.classifier()),
+        );
 
         // Create worker pool with job progress broadcaster for UI updates
         let job_sender = self.job_broadcaster.sender();
@@ -486,6 +489,14 @@ spec:
     languages:
       - eng
     dpi: 300
+  limits:
+    maxFileSizeMb: 100
+    maxPages: 500
+    jobTimeoutSecs: 900
+    ocrPageTimeoutSecs: 120
+    maxArchiveEntries: 1000
+    maxArchiveSizeMb: 500
+    maxArchiveDepth: 3
   defaults:
     output:
       directory: unsorted
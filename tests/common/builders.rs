@@ -168,7 +168,14 @@ impl RuleBuilder {
                 contains_any: None,
                 contains_all: None,
                 pattern: None,
+                fuzzy: None,
+                contains_any_fuzzy: None,
+                variable: None,
                 case_sensitive: None,
+                field: None,
+                pages: None,
+                region: None,
+                weight: None,
             }),
             category: category.to_string(),
             output: OutputConfig {
@@ -201,7 +208,14 @@ impl RuleBuilder {
             contains_any: None,
             contains_all: None,
             pattern: None,
+            fuzzy: None,
+            contains_any_fuzzy: None,
+            variable: None,
             case_sensitive: None,
+            field: None,
+            pages: None,
+            region: None,
+            weight: None,
         });
         self
     }
@@ -216,7 +230,14 @@ impl RuleBuilder {
             contains_any: Some(texts.into_iter().map(|s| s.to_string()).collect()),
             contains_all: None,
             pattern: None,
+            fuzzy: None,
+            contains_any_fuzzy: None,
+            variable: None,
             case_sensitive: None,
+            field: None,
+            pages: None,
+            region: None,
+            weight: None,
         });
         self
     }
@@ -231,7 +252,14 @@ impl RuleBuilder {
             contains_any: None,
             contains_all: Some(texts.into_iter().map(|s| s.to_string()).collect()),
             pattern: None,
+            fuzzy: None,
+            contains_any_fuzzy: None,
+            variable: None,
             case_sensitive: None,
+            field: None,
+            pages: None,
+            region: None,
+            weight: None,
         });
         self
     }
@@ -246,7 +274,14 @@ impl RuleBuilder {
             contains_any: None,
             contains_all: None,
             pattern: Some(pattern.to_string()),
+            fuzzy: None,
+            contains_any_fuzzy: None,
+            variable: None,
             case_sensitive: None,
+            field: None,
+            pages: None,
+            region: None,
+            weight: None,
         });
         self
     }
@@ -305,6 +340,7 @@ impl RuleBuilder {
             id: self.id,
             name: self.name,
             priority: self.priority,
+            exclusive: true,
             match_condition: self.match_condition,
             category: self.category,
             output: self.output,
@@ -389,6 +425,10 @@ pub fn match_all(conditions: Vec<MatchCondition>) -> MatchCondition {
         any: None,
         not: None,
         case_sensitive: None,
+        field: None,
+        pages: None,
+        region: None,
+        weight: None,
     })
 }
 
@@ -399,6 +439,10 @@ pub fn match_any(conditions: Vec<MatchCondition>) -> MatchCondition {
         any: Some(conditions),
         not: None,
         case_sensitive: None,
+        field: None,
+        pages: None,
+        region: None,
+        weight: None,
     })
 }
 
@@ -409,6 +453,10 @@ pub fn match_not(condition: MatchCondition) -> MatchCondition {
         any: None,
         not: Some(Box::new(condition)),
         case_sensitive: None,
+        field: None,
+        pages: None,
+        region: None,
+        weight: None,
     })
 }
 
@@ -419,7 +467,14 @@ pub fn simple_contains(text: &str) -> MatchCondition {
         contains_any: None,
         contains_all: None,
         pattern: None,
+        fuzzy: None,
+        contains_any_fuzzy: None,
+        variable: None,
         case_sensitive: None,
+        field: None,
+        pages: None,
+        region: None,
+        weight: None,
     })
 }
 
@@ -430,7 +485,14 @@ pub fn simple_contains_any(texts: Vec<&str>) -> MatchCondition {
         contains_any: Some(texts.into_iter().map(|s| s.to_string()).collect()),
         contains_all: None,
         pattern: None,
+        fuzzy: None,
+        contains_any_fuzzy: None,
+        variable: None,
         case_sensitive: None,
+        field: None,
+        pages: None,
+        region: None,
+        weight: None,
     })
 }
 
@@ -441,7 +503,14 @@ pub fn simple_contains_all(texts: Vec<&str>) -> MatchCondition {
         contains_any: None,
         contains_all: Some(texts.into_iter().map(|s| s.to_string()).collect()),
         pattern: None,
+        fuzzy: None,
+        contains_any_fuzzy: None,
+        variable: None,
         case_sensitive: None,
+        field: None,
+        pages: None,
+        region: None,
+        weight: None,
     })
 }
 
@@ -452,7 +521,14 @@ pub fn simple_pattern(pattern: &str) -> MatchCondition {
         contains_any: None,
         contains_all: None,
         pattern: Some(pattern.to_string()),
+        fuzzy: None,
+        contains_any_fuzzy: None,
+        variable: None,
         case_sensitive: None,
+        field: None,
+        pages: None,
+        region: None,
+        weight: None,
     })
 }
 
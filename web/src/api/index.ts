@@ -79,6 +79,12 @@ export interface OcrResponse {
   text: string;
 }
 
+export interface CategoryCorrection {
+  job: StoredJob;
+  /** Whether the classifier learned the corrected category. */
+  learned: boolean;
+}
+
 export interface RerunResponse {
   success: boolean;
   newJobId: string;
@@ -98,12 +104,30 @@ export interface ResourceInfo {
   name: string;
   path: string;
   yaml: string;
+  /** Rules extending another rule or a template, with inheritance resolved. */
+  effectiveYaml?: string;
+}
+
+export interface Diagnostic {
+  severity: 'error' | 'warning';
+  code: string;
+  kind?: string;
+  name?: string;
+  /** File relative to the config directory. */
+  path?: string;
+  /** Field within the resource, such as `spec.match.all[1]`. */
+  yamlPath?: string;
+  line?: number;
+  column?: number;
+  message: string;
+  fix?: string;
 }
 
 export interface ValidationResult {
   valid: boolean;
   errors: string[];
   warnings: string[];
+  diagnostics: Diagnostic[];
 }
 
 export interface SimulationResult {
@@ -114,6 +138,40 @@ export interface SimulationResult {
   matchedRule: string | null;
 }
 
+export interface RuleTestCaseResult {
+  test: string;
+  case: string;
+  path: string;
+  passed: boolean;
+  failures?: string[];
+  category?: string;
+  rule?: string;
+  output?: string;
+  error?: string;
+}
+
+export interface RuleTestReport {
+  passed: number;
+  failed: number;
+  results: RuleTestCaseResult[];
+}
+
+export interface MigrationNote {
+  kind: string;
+  name: string;
+  message: string;
+}
+
+export interface MigrationReport {
+  files: string[];
+  notes: MigrationNote[];
+}
+
+export interface ApiVersionUpgrade {
+  files: string[];
+  commitHash?: string;
+}
+
 // Git types
 export interface GitFileStatus {
   path: string;
@@ -337,6 +395,14 @@ export const api = {
       return unwrap(response);
     },
 
+    correctCategory: async (jobId: string, category: string): Promise<CategoryCorrection> => {
+      const response = await invoke<ApiResponse<CategoryCorrection>>('correct_job_category', {
+        jobId,
+        category,
+      });
+      return unwrap(response);
+    },
+
     rerunUnsorted: async (): Promise<{ count: number }> => {
       const response = await invoke<ApiResponse<{ count: number }>>('rerun_unsorted');
       return unwrap(response);
@@ -387,6 +453,29 @@ export const api = {
       const response = await invoke<ApiResponse<ValidationResult>>('validate_config');
       return unwrap(response);
     },
+
+    /** JSON Schema of a resource kind, such as `Rule`. */
+    getResourceSchema: async (kind: string): Promise<Record<string, unknown>> => {
+      const response = await invoke<ApiResponse<Record<string, unknown>>>('get_resource_schema', { kind });
+      return unwrap(response);
+    },
+
+    /** Convert a legacy JSON config file into resources of the config directory. */
+    migrateLegacyConfig: async (source: string): Promise<MigrationReport> => {
+      const response = await invoke<ApiResponse<MigrationReport>>('migrate_legacy_config_file', { source });
+      return unwrap(response);
+    },
+
+    /** Rewrite resources of older API versions in the current one, as one commit. */
+    upgradeApiVersion: async (): Promise<ApiVersionUpgrade> => {
+      const response = await invoke<ApiResponse<ApiVersionUpgrade>>('upgrade_resource_api_version');
+      return unwrap(response);
+    },
+
+    runRuleTests: async (name?: string): Promise<RuleTestReport> => {
+      const response = await invoke<ApiResponse<RuleTestReport>>('run_rule_tests', { name });
+      return unwrap(response);
+    },
   },
 
   // ---------------------------------------------------------------------------
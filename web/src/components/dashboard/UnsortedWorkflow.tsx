@@ -2,6 +2,7 @@ import { useState, useCallback } from 'react'
 import { Link } from '@tanstack/react-router'
 import {
   AlertTriangle,
+  Check,
   CheckCircle2,
   EyeOff,
   FileText,
@@ -26,13 +27,23 @@ interface UnsortedWorkflowProps {
   onJobRerun?: (jobId: string) => void
   /** Callback when a job is successfully ignored */
   onJobIgnored?: (jobId: string) => void
+  /** Callback when a job's suggested category is accepted */
+  onJobCorrected?: (jobId: string, category: string) => void
 }
 
-export function UnsortedWorkflow({ jobs, rulesCount, hasDocuments, onJobRerun, onJobIgnored }: UnsortedWorkflowProps) {
+export function UnsortedWorkflow({
+  jobs,
+  rulesCount,
+  hasDocuments,
+  onJobRerun,
+  onJobIgnored,
+  onJobCorrected,
+}: UnsortedWorkflowProps) {
   const [selectedJob, setSelectedJob] = useState<StoredJob | null>(null)
   const [createRuleDialogOpen, setCreateRuleDialogOpen] = useState(false)
   const [rerunningJobIds, setRerunningJobIds] = useState<Set<string>>(new Set())
   const [ignoringJobIds, setIgnoringJobIds] = useState<Set<string>>(new Set())
+  const [correctingJobIds, setCorrectingJobIds] = useState<Set<string>>(new Set())
 
   const handleCreateRule = (job: StoredJob) => {
     setSelectedJob(job)
@@ -91,6 +102,33 @@ export function UnsortedWorkflow({ jobs, rulesCount, hasDocuments, onJobRerun, o
     }
   }, [onJobIgnored])
 
+  const handleAcceptSuggestion = useCallback(async (job: StoredJob, category: string) => {
+    setCorrectingJobIds((prev) => new Set(prev).add(job.jobId))
+
+    try {
+      const { learned } = await api.jobs.correctCategory(job.jobId, category)
+      toast({
+        title: `Categorized as ${category}`,
+        description: learned
+          ? 'The classifier will learn from this document'
+          : 'The document text was not available to the classifier',
+      })
+      onJobCorrected?.(job.jobId, category)
+    } catch (e) {
+      toast({
+        title: 'Failed to categorize job',
+        description: e instanceof Error ? e.message : 'An unknown error occurred',
+        variant: 'destructive',
+      })
+    } finally {
+      setCorrectingJobIds((prev) => {
+        const next = new Set(prev)
+        next.delete(job.jobId)
+        return next
+      })
+    }
+  }, [onJobCorrected])
+
   // No documents at all
   if (!hasDocuments) {
     return (
@@ -190,7 +228,9 @@ export function UnsortedWorkflow({ jobs, rulesCount, hasDocuments, onJobRerun, o
             {jobs.map((job) => {
               const isRerunning = rerunningJobIds.has(job.jobId)
               const isIgnoring = ignoringJobIds.has(job.jobId)
-              const isDisabled = isRerunning || isIgnoring
+              const isCorrecting = correctingJobIds.has(job.jobId)
+              const isDisabled = isRerunning || isIgnoring || isCorrecting
+              const suggestion = job.explanation?.classifier
 
               return (
                 <div
@@ -207,6 +247,22 @@ export function UnsortedWorkflow({ jobs, rulesCount, hasDocuments, onJobRerun, o
                     </div>
                   </div>
                   <div className="flex items-center gap-2 shrink-0">
+                    {suggestion && (
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        onClick={() => handleAcceptSuggestion(job, suggestion.category)}
+                        disabled={isDisabled}
+                        title={`Suggested by the classifier with ${Math.round(suggestion.confidence * 100)}% confidence`}
+                      >
+                        {isCorrecting ? (
+                          <Loader2 className="h-4 w-4 mr-1 animate-spin" />
+                        ) : (
+                          <Check className="h-4 w-4 mr-1" />
+                        )}
+                        {suggestion.category}
+                      </Button>
+                    )}
                     <Button
                       variant="default"
                       size="sm"
@@ -15,10 +15,13 @@ import { Plus, Trash2, X } from 'lucide-react'
 import {
   type MatchCondition,
   type MatchConditionType,
+  type MatchField,
+  type VariableMatch,
   getMatchConditionType,
   createMatchConditionOfType,
 } from '@/schemas/resources'
 import { cn } from '@/lib/utils'
+import { PageScopeEditor, type PageScope } from './PageScopeEditor'
 
 interface MatchConditionBuilderProps {
   condition: MatchCondition
@@ -28,6 +31,70 @@ interface MatchConditionBuilderProps {
 
 const MAX_DEPTH = 4
 
+const MATCH_FIELDS: { value: MatchField; label: string }[] = [
+  { value: 'text', label: 'Document Text' },
+  { value: 'firstPage', label: 'First Page' },
+  { value: 'filename', label: 'Filename' },
+  { value: 'source', label: 'Import Source' },
+  { value: 'mimeType', label: 'MIME Type' },
+  { value: 'email.from', label: 'Email From' },
+  { value: 'email.to', label: 'Email To' },
+  { value: 'email.subject', label: 'Email Subject' },
+]
+
+/** Select value standing for "no field": the extracted text. */
+const DEFAULT_FIELD = 'default'
+
+/** Fields that pages and regions can narrow; unset stands for the text. */
+const PAGED_FIELDS: (MatchField | undefined)[] = [undefined, 'text', 'firstPage']
+
+type VariableOperator = 'eq' | 'gt' | 'lt' | 'between' | 'in' | 'exists' | 'missing'
+
+const VARIABLE_OPERATORS: { value: VariableOperator; label: string }[] = [
+  { value: 'eq', label: 'Equals' },
+  { value: 'gt', label: 'Greater Than' },
+  { value: 'lt', label: 'Less Than' },
+  { value: 'between', label: 'Between' },
+  { value: 'in', label: 'One Of' },
+  { value: 'exists', label: 'Exists' },
+  { value: 'missing', label: 'Is Missing' },
+]
+
+function getVariableOperator(variable: VariableMatch): VariableOperator {
+  if (variable.gt !== undefined) return 'gt'
+  if (variable.lt !== undefined) return 'lt'
+  if (variable.between !== undefined) return 'between'
+  if (variable.in !== undefined) return 'in'
+  if (variable.exists === true) return 'exists'
+  if (variable.exists === false) return 'missing'
+  return 'eq'
+}
+
+/** Numbers are stored as numbers; dates and other text stay strings. */
+function parseCompareValue(value: string): number | string {
+  const trimmed = value.trim()
+  return trimmed !== '' && !Number.isNaN(Number(trimmed)) ? Number(trimmed) : value
+}
+
+function createVariableMatch(name: string, operator: VariableOperator): VariableMatch {
+  switch (operator) {
+    case 'eq':
+      return { name, eq: '' }
+    case 'gt':
+      return { name, gt: 0 }
+    case 'lt':
+      return { name, lt: 0 }
+    case 'between':
+      return { name, between: [0, 0] }
+    case 'in':
+      return { name, in: [''] }
+    case 'exists':
+      return { name, exists: true }
+    case 'missing':
+      return { name, exists: false }
+  }
+}
+
 export function MatchConditionBuilder({
   condition,
   onChange,
@@ -37,17 +104,43 @@ export function MatchConditionBuilder({
   const type = getMatchConditionType(condition)
 
   const caseSensitive = 'caseSensitive' in condition ? condition.caseSensitive : undefined
+  const field = 'field' in condition ? condition.field : undefined
+  const weight = 'weight' in condition ? condition.weight : undefined
+  const pages = 'pages' in condition ? condition.pages : undefined
+  const region = 'region' in condition ? condition.region : undefined
 
-  /** Spread caseSensitive onto a new condition object if it was set. */
+  /** Spread caseSensitive, field, pages, region and weight onto a new condition object if they were set. */
   const withCaseSensitive = (cond: MatchCondition): MatchCondition => {
     if (caseSensitive !== undefined) {
       ;(cond as Record<string, unknown>).caseSensitive = caseSensitive
     }
+    if (field !== undefined) {
+      ;(cond as Record<string, unknown>).field = field
+    }
+    if (pages !== undefined) {
+      ;(cond as Record<string, unknown>).pages = pages
+    }
+    if (region !== undefined) {
+      ;(cond as Record<string, unknown>).region = region
+    }
+    if (weight !== undefined) {
+      ;(cond as Record<string, unknown>).weight = weight
+    }
     return cond
   }
 
   const handleTypeChange = (newType: MatchConditionType) => {
-    onChange(withCaseSensitive(createMatchConditionOfType(newType)))
+    const updated = withCaseSensitive(createMatchConditionOfType(newType))
+    // Variable and correspondent conditions do not read a document field
+    if (newType === 'variable' || newType === 'correspondent') {
+      delete (updated as Record<string, unknown>).field
+      delete (updated as Record<string, unknown>).pages
+      delete (updated as Record<string, unknown>).region
+    }
+    if (newType === 'correspondent') {
+      delete (updated as Record<string, unknown>).caseSensitive
+    }
+    onChange(updated)
   }
 
   const handleCaseSensitiveChange = (checked: boolean) => {
@@ -60,6 +153,43 @@ export function MatchConditionBuilder({
     onChange(updated as MatchCondition)
   }
 
+  const handleFieldChange = (value: string) => {
+    const updated = { ...condition }
+    if (value === DEFAULT_FIELD) {
+      delete (updated as Record<string, unknown>).field
+    } else {
+      ;(updated as Record<string, unknown>).field = value
+    }
+    // Only the text can be narrowed to pages
+    if (!PAGED_FIELDS.includes(value === DEFAULT_FIELD ? undefined : (value as MatchField))) {
+      delete (updated as Record<string, unknown>).pages
+      delete (updated as Record<string, unknown>).region
+    }
+    onChange(updated as MatchCondition)
+  }
+
+  const handleScopeChange = (scope: PageScope) => {
+    const updated = { ...condition } as Record<string, unknown>
+    for (const key of ['pages', 'region'] as const) {
+      if (scope[key] === undefined) {
+        delete updated[key]
+      } else {
+        updated[key] = scope[key]
+      }
+    }
+    onChange(updated as MatchCondition)
+  }
+
+  const handleWeightChange = (value: string) => {
+    const updated = { ...condition }
+    if (value === '') {
+      delete (updated as Record<string, unknown>).weight
+    } else {
+      ;(updated as Record<string, unknown>).weight = Number(value)
+    }
+    onChange(updated as MatchCondition)
+  }
+
   const renderSimpleCondition = () => {
     if ('contains' in condition) {
       return (
@@ -103,6 +233,70 @@ export function MatchConditionBuilder({
       )
     }
 
+    if ('fuzzy' in condition) {
+      const { value, maxDistance } = condition.fuzzy
+      return (
+        <div className="flex items-center gap-2">
+          <Input
+            value={value}
+            onChange={(e) =>
+              onChange(withCaseSensitive({ fuzzy: { value: e.target.value, maxDistance } }))
+            }
+            placeholder="Text to search for, OCR errors tolerated..."
+            className="font-mono"
+          />
+          <Input
+            type="number"
+            min={0}
+            value={maxDistance ?? ''}
+            onChange={(e) =>
+              onChange(
+                withCaseSensitive({
+                  fuzzy: {
+                    value,
+                    maxDistance: e.target.value === '' ? undefined : Number(e.target.value),
+                  },
+                })
+              )
+            }
+            placeholder="Auto"
+            title="Maximum edit distance"
+            className="w-24"
+          />
+        </div>
+      )
+    }
+
+    if ('containsAnyFuzzy' in condition) {
+      return (
+        <StringArrayEditor
+          values={condition.containsAnyFuzzy}
+          onChange={(values) => onChange(withCaseSensitive({ containsAnyFuzzy: values }))}
+          placeholder="Add text..."
+        />
+      )
+    }
+
+    if ('variable' in condition) {
+      return (
+        <VariableConditionEditor
+          variable={condition.variable}
+          onChange={(variable) => onChange(withCaseSensitive({ variable }))}
+        />
+      )
+    }
+
+    if ('correspondent' in condition) {
+      return (
+        <Input
+          value={condition.correspondent}
+          onChange={(e) => onChange(withCaseSensitive({ correspondent: e.target.value }))}
+          placeholder="Correspondent name, e.g. helsana"
+          className="font-mono"
+        />
+      )
+    }
+
     return null
   }
 
@@ -156,6 +350,7 @@ export function MatchConditionBuilder({
   }
 
   const isCompound = type === 'all' || type === 'any' || type === 'not'
+  const readsField = type !== 'variable' && type !== 'correspondent'
 
   return (
     <div
@@ -177,6 +372,10 @@ export function MatchConditionBuilder({
             <SelectItem value="containsAny">Contains Any</SelectItem>
             <SelectItem value="containsAll">Contains All</SelectItem>
             <SelectItem value="pattern">Regex Pattern</SelectItem>
+            <SelectItem value="fuzzy">Fuzzy</SelectItem>
+            <SelectItem value="containsAnyFuzzy">Fuzzy Any</SelectItem>
+            <SelectItem value="variable">Variable</SelectItem>
+            <SelectItem value="correspondent">Correspondent</SelectItem>
             <SelectItem value="all" disabled={depth >= MAX_DEPTH}>
               All (AND)
             </SelectItem>
@@ -189,16 +388,47 @@ export function MatchConditionBuilder({
           </SelectContent>
         </Select>
 
-        <div className="flex items-center gap-2 ml-auto">
-          <Label htmlFor={`${uniqueId}-case-sensitive`} className="text-xs text-muted-foreground cursor-pointer">
-            Case Sensitive
-          </Label>
-          <Switch
-            id={`${uniqueId}-case-sensitive`}
-            checked={caseSensitive === true}
-            onCheckedChange={handleCaseSensitiveChange}
+        {readsField && (
+          <Select value={field ?? DEFAULT_FIELD} onValueChange={handleFieldChange}>
+            <SelectTrigger className="w-40" title="Document field to match against">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value={DEFAULT_FIELD}>{depth > 0 ? 'Inherited Field' : 'All Text'}</SelectItem>
+              {MATCH_FIELDS.map(({ value, label }) => (
+                <SelectItem key={value} value={value}>
+                  {label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        )}
+
+        {depth > 0 && (
+          <Input
+            type="number"
+            min={0}
+            step={0.5}
+            value={weight ?? ''}
+            onChange={(e) => handleWeightChange(e.target.value)}
+            placeholder="Weight"
+            title="Weight among sibling conditions in scoring mode (default 1)"
+            className="w-24"
           />
-        </div>
+        )}
+
+        {type !== 'correspondent' && (
+          <div className="flex items-center gap-2 ml-auto">
+            <Label htmlFor={`${uniqueId}-case-sensitive`} className="text-xs text-muted-foreground cursor-pointer">
+              Case Sensitive
+            </Label>
+            <Switch
+              id={`${uniqueId}-case-sensitive`}
+              checked={caseSensitive === true}
+              onCheckedChange={handleCaseSensitiveChange}
+            />
+          </div>
+        )}
 
         {depth > 0 && (
           <Badge variant="outline" className="text-xs">
@@ -207,11 +437,103 @@ export function MatchConditionBuilder({
         )}
       </div>
 
+      {readsField && PAGED_FIELDS.includes(field) && (
+        <PageScopeEditor
+          scope={{ pages, region }}
+          onChange={handleScopeChange}
+          unsetLabel={depth > 0 ? 'Inherited' : undefined}
+        />
+      )}
+
       {isCompound ? renderCompoundCondition() : renderSimpleCondition()}
     </div>
   )
 }
 
+interface VariableConditionEditorProps {
+  variable: VariableMatch
+  onChange: (variable: VariableMatch) => void
+}
+
+function VariableConditionEditor({ variable, onChange }: VariableConditionEditorProps) {
+  const operator = getVariableOperator(variable)
+
+  const renderValues = () => {
+    switch (operator) {
+      case 'eq':
+      case 'gt':
+      case 'lt':
+        return (
+          <Input
+            value={String(variable[operator] ?? '')}
+            onChange={(e) => onChange({ name: variable.name, [operator]: parseCompareValue(e.target.value) })}
+            placeholder="Number or date, e.g. 1000 or 2020-01-01"
+            className="font-mono"
+          />
+        )
+      case 'between': {
+        const [low, high] = variable.between ?? [0, 0]
+        return (
+          <div className="flex items-center gap-2">
+            <Input
+              value={String(low)}
+              onChange={(e) => onChange({ name: variable.name, between: [parseCompareValue(e.target.value), high] })}
+              placeholder="From"
+              className="font-mono"
+            />
+            <span className="text-sm text-muted-foreground">and</span>
+            <Input
+              value={String(high)}
+              onChange={(e) => onChange({ name: variable.name, between: [low, parseCompareValue(e.target.value)] })}
+              placeholder="To"
+              className="font-mono"
+            />
+          </div>
+        )
+      }
+      case 'in':
+        return (
+          <StringArrayEditor
+            values={(variable.in ?? []).map(String)}
+            onChange={(values) => onChange({ name: variable.name, in: values.map(parseCompareValue) })}
+            placeholder="Add value..."
+          />
+        )
+      default:
+        return null
+    }
+  }
+
+  return (
+    <div className="space-y-2">
+      <div className="flex items-center gap-2">
+        <Input
+          value={variable.name}
+          onChange={(e) => onChange({ ...variable, name: e.target.value })}
+          placeholder="Variable name, e.g. amount"
+          className="font-mono"
+        />
+        <Select
+          value={operator}
+          onValueChange={(v) => onChange(createVariableMatch(variable.name, v as VariableOperator))}
+        >
+          <SelectTrigger className="w-40">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {VARIABLE_OPERATORS.map(({ value, label }) => (
+              <SelectItem key={value} value={value}>
+                {label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+      {renderValues()}
+    </div>
+  )
+}
+
 interface CompoundConditionListProps {
   conditions: MatchCondition[]
   onChange: (conditions: MatchCondition[]) => void
@@ -0,0 +1,114 @@
+import { Input } from '@/components/ui/input'
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select'
+import type { PageRegion, PageSelection } from '@/schemas/resources'
+
+export interface PageScope {
+  pages?: PageSelection
+  region?: PageRegion
+}
+
+interface PageScopeEditorProps {
+  scope: PageScope
+  onChange: (scope: PageScope) => void
+  /** Label of the unset options, e.g. "Inherited" inside a compound condition. */
+  unsetLabel?: string
+}
+
+type PagesMode = 'all' | 'first' | 'last' | 'numbers'
+type RegionMode = 'whole' | 'top' | 'bottom'
+
+function pagesMode(pages: PageSelection | undefined): PagesMode {
+  if (pages === undefined) return 'all'
+  return Array.isArray(pages) ? 'numbers' : pages
+}
+
+function regionMode(region: PageRegion | undefined): RegionMode {
+  if (region === undefined) return 'whole'
+  return 'top' in region ? 'top' : 'bottom'
+}
+
+function regionLines(region: PageRegion | undefined): number {
+  if (region === undefined) return 10
+  return 'top' in region ? region.top : region.bottom
+}
+
+/** Parses "1, 3" into page numbers, dropping anything that is not one. */
+function parsePageNumbers(value: string): number[] {
+  return value
+    .split(/[\s,]+/)
+    .map(Number)
+    .filter((n) => Number.isInteger(n) && n > 0)
+}
+
+/** Limits a condition or variable pattern to pages and the top or bottom lines of each page. */
+export function PageScopeEditor({ scope, onChange, unsetLabel }: PageScopeEditorProps) {
+  const mode = pagesMode(scope.pages)
+  const region = regionMode(scope.region)
+  const lines = regionLines(scope.region)
+
+  const handlePagesChange = (value: PagesMode) => {
+    const pages: PageSelection | undefined =
+      value === 'all' ? undefined : value === 'numbers' ? [1] : value
+    onChange({ ...scope, pages })
+  }
+
+  const handleRegionChange = (value: RegionMode, count = lines) => {
+    const updated: PageRegion | undefined =
+      value === 'whole' ? undefined : value === 'top' ? { top: count } : { bottom: count }
+    onChange({ ...scope, region: updated })
+  }
+
+  return (
+    <div className="flex flex-wrap items-center gap-2">
+      <Select value={mode} onValueChange={(v) => handlePagesChange(v as PagesMode)}>
+        <SelectTrigger className="w-36" title="Pages of the extracted text to search">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value="all">{unsetLabel ? `${unsetLabel} Pages` : 'All Pages'}</SelectItem>
+          <SelectItem value="first">First Page</SelectItem>
+          <SelectItem value="last">Last Page</SelectItem>
+          <SelectItem value="numbers">Page Numbers</SelectItem>
+        </SelectContent>
+      </Select>
+
+      {Array.isArray(scope.pages) && (
+        <Input
+          value={scope.pages.join(', ')}
+          onChange={(e) => onChange({ ...scope, pages: parsePageNumbers(e.target.value) })}
+          placeholder="1, 2"
+          title="Page numbers, starting at 1"
+          className="w-24 font-mono"
+        />
+      )}
+
+      <Select value={region} onValueChange={(v) => handleRegionChange(v as RegionMode)}>
+        <SelectTrigger className="w-36" title="Lines of each page to search">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value="whole">{unsetLabel ? `${unsetLabel} Region` : 'Whole Page'}</SelectItem>
+          <SelectItem value="top">Top Lines</SelectItem>
+          <SelectItem value="bottom">Bottom Lines</SelectItem>
+        </SelectContent>
+      </Select>
+
+      {scope.region !== undefined && (
+        <Input
+          type="number"
+          min={1}
+          value={lines}
+          onChange={(e) => handleRegionChange(region, Math.max(1, Number(e.target.value) || 1))}
+          title="Number of non-blank lines"
+          className="w-20"
+        />
+      )}
+    </div>
+  )
+}
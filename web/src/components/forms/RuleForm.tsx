@@ -4,10 +4,16 @@ import {
   AccordionItem,
   AccordionTrigger,
 } from '@/components/ui/accordion'
-import { TextField, NumberField, ArrayField } from '@/components/form'
+import { useStore } from '@tanstack/react-form'
+import { TextField, NumberField, SwitchField, ArrayField } from '@/components/form'
+import { Button } from '@/components/ui/button'
 import { Label } from '@/components/ui/label'
 import { MatchConditionBuilder } from './MatchConditionBuilder'
-import { type MatchCondition, type SymlinkSettings } from '@/schemas/resources'
+import {
+  type MatchCondition,
+  type SymlinkSettings,
+  createDefaultMatchCondition,
+} from '@/schemas/resources'
 import { Filter, FileOutput, Link2, Settings } from 'lucide-react'
 import type { FormInstance } from '@/lib/form-utils'
 
@@ -24,6 +30,10 @@ export function RuleForm({
   name,
   onNameChange,
 }: RuleFormProps) {
+  const exclusive: boolean = useStore(form.store, (state) => state.values.exclusive ?? true)
+  // Rules extending another rule or a template may leave inherited fields empty
+  const inherits: boolean = useStore(form.store, (state) => !!state.values.extends)
+
   return (
     <Accordion type="multiple" defaultValue={['basic', 'match', 'output']} className="w-full">
       {/* Basic Settings */}
@@ -47,14 +57,25 @@ export function RuleForm({
               />
             )}
 
+            <form.Field name="extends" children={(field: { state: { value: string | undefined; meta: { errors: string[] } }; handleChange: (v: string | undefined) => void }) => (
+              <TextField
+                label="Extends"
+                value={field.state.value ?? ''}
+                onChange={(v: string) => field.handleChange(v.trim() === '' ? undefined : v)}
+                description="Rule or template to inherit from. Empty category and output fields are inherited, symlinks are added, and the inherited match conditions must hold as well."
+                error={field.state.meta.errors?.[0]}
+                placeholder="vendor_template"
+              />
+            )} />
+
             <form.Field name="category" children={(field: { state: { value: string; meta: { errors: string[] } }; handleChange: (v: string) => void }) => (
               <TextField
                 label="Category"
                 value={field.state.value}
                 onChange={field.handleChange}
-                description="Category for this rule (used for organization)"
+                description={inherits ? 'Leave empty to inherit the category' : 'Category for this rule (used for organization)'}
                 error={field.state.meta.errors?.[0]}
-                required
+                required={!inherits}
                 placeholder="invoices"
               />
             )} />
@@ -68,6 +89,15 @@ export function RuleForm({
                 error={field.state.meta.errors?.[0]}
               />
             )} />
+
+            <form.Field name="exclusive" children={(field: { state: { value: boolean | undefined; meta: { errors?: string[] } }; handleChange: (v: boolean) => void }) => (
+              <SwitchField
+                label="Exclusive"
+                checked={field.state.value ?? true}
+                onChange={field.handleChange}
+                description="Exclusive rules compete for the document's category and output location. Turn off to add the category as a tag ($tags) and create this rule's symlinks whenever it matches."
+              />
+            )} />
           </div>
         </AccordionContent>
       </AccordionItem>
@@ -88,11 +118,39 @@ export function RuleForm({
                 Define conditions that must match for this rule to apply. Use simple conditions
                 (contains, pattern) or combine them with AND/OR/NOT logic.
               </p>
-              <form.Field name="match" children={(field: { state: { value: MatchCondition; meta: { errors: string[] } }; handleChange: (v: MatchCondition) => void }) => (
-                <MatchConditionBuilder
-                  condition={field.state.value}
-                  onChange={field.handleChange}
-                />
+              <form.Field name="match" children={(field: { state: { value: MatchCondition | undefined; meta: { errors: string[] } }; handleChange: (v: MatchCondition | undefined) => void }) => (
+                field.state.value === undefined ? (
+                  <div className="flex items-center gap-3">
+                    <p className="text-sm text-muted-foreground">
+                      {inherits ? 'Only the inherited match conditions apply.' : 'No match condition set.'}
+                    </p>
+                    <Button
+                      type="button"
+                      variant="outline"
+                      size="sm"
+                      onClick={() => field.handleChange(createDefaultMatchCondition())}
+                    >
+                      Add Condition
+                    </Button>
+                  </div>
+                ) : (
+                  <div className="space-y-2">
+                    <MatchConditionBuilder
+                      condition={field.state.value}
+                      onChange={field.handleChange}
+                    />
+                    {inherits && (
+                      <Button
+                        type="button"
+                        variant="ghost"
+                        size="sm"
+                        onClick={() => field.handleChange(undefined)}
+                      >
+                        Use Inherited Conditions Only
+                      </Button>
+                    )}
+                  </div>
+                )
               )} />
             </div>
           </div>
@@ -109,14 +167,24 @@ export function RuleForm({
         </AccordionTrigger>
         <AccordionContent>
           <div className="space-y-4 pt-4">
+            {!exclusive && (
+              <p className="text-xs text-muted-foreground">
+                Non-exclusive rules do not decide where the document is stored; output settings are optional.
+              </p>
+            )}
+            {exclusive && inherits && (
+              <p className="text-xs text-muted-foreground">
+                Empty output fields are inherited.
+              </p>
+            )}
             <form.Field name="output.directory" children={(field: { state: { value: string; meta: { errors: string[] } }; handleChange: (v: string) => void }) => (
               <TextField
                 label="Output Directory"
                 value={field.state.value}
                 onChange={field.handleChange}
-                description="Output directory template. Variables: $y (year), $l (last year), $m (month), $d (day), $h (hour), $i (minute), $s (second), $category, custom variables"
+                description="Output directory template. Variables: $y (year), $l (last year), $m (month), $d (day), $h (hour), $i (minute), $s (second), $category, $tags, $correspondent, custom variables"
                 error={field.state.meta.errors?.[0]}
-                required
+                required={exclusive && !inherits}
                 mono
                 placeholder="$y/$category"
               />
@@ -127,9 +195,9 @@ export function RuleForm({
                 label="Filename"
                 value={field.state.value}
                 onChange={field.handleChange}
-                description="Filename template. Variables: $original, $timestamp, $h (hour), $i (minute), $s (second), custom variables"
+                description="Filename template. Variables: $original, $timestamp, $h (hour), $i (minute), $s (second), $tags, $correspondent, custom variables"
                 error={field.state.meta.errors?.[0]}
-                required
+                required={exclusive && !inherits}
                 mono
                 placeholder="$original"
               />
@@ -6,7 +6,7 @@ import {
   AccordionTrigger,
 } from '@/components/ui/accordion'
 import { TextField, NumberField, SwitchField, SelectField, ArrayField, SecretField, PathField } from '@/components/form'
-import { Folder, Eye, GitBranch, Settings as SettingsIcon, Download } from 'lucide-react'
+import { Folder, Eye, GitBranch, Settings as SettingsIcon, Download, Gauge, Scale } from 'lucide-react'
 import { useStore } from '@tanstack/react-form'
 import type { FormInstance } from '@/lib/form-utils'
 import { enable, disable, isEnabled } from '@tauri-apps/plugin-autostart'
@@ -39,6 +39,8 @@ export function SettingsForm({ form }: SettingsFormProps) {
   const ocrEnabled: boolean = useStore(form.store, (state) => state.values.ocr.enabled)
   const gitEnabled: boolean = useStore(form.store, (state) => state.values.git.enabled)
   const gitAuthType: string = useStore(form.store, (state) => state.values.git.auth.type)
+  const categorizationMode: string = useStore(form.store, (state) => state.values.categorization?.mode)
+  const classifierEnabled: boolean = useStore(form.store, (state) => state.values.categorization?.classifier?.enabled ?? true)
 
   return (
     <Accordion type="multiple" defaultValue={['general', 'ocr', 'defaults']} className="w-full">
@@ -85,6 +87,17 @@ export function SettingsForm({ form }: SettingsFormProps) {
                 max={32}
               />
             )} />
+            <form.Field name="overlay" children={(field: { state: { value: string | undefined; meta: { errors: string[] } }; handleChange: (v: string | undefined) => void }) => (
+              <TextField
+                label="Overlay"
+                value={field.state.value || ''}
+                onChange={(v: string) => field.handleChange(v || undefined)}
+                description="Directory of overlays/ patching this configuration on this machine. $hostname stands for the machine name; the PAPORG_OVERLAY environment variable takes precedence."
+                error={field.state.meta.errors?.[0]}
+                placeholder="$hostname"
+                mono
+              />
+            )} />
           </div>
         </AccordionContent>
       </AccordionItem>
@@ -138,6 +151,184 @@ export function SettingsForm({ form }: SettingsFormProps) {
         </AccordionContent>
       </AccordionItem>
 
+      {/* Resource Limits */}
+      <AccordionItem value="limits">
+        <AccordionTrigger className="hover:no-underline">
+          <div className="flex items-center gap-2">
+            <Gauge className="h-4 w-4" />
+            Resource Limits
+          </div>
+        </AccordionTrigger>
+        <AccordionContent>
+          <div className="space-y-4 pt-4">
+            <form.Field name="limits.maxFileSizeMb" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+              <NumberField
+                label="Max File Size (MB)"
+                value={field.state.value}
+                onChange={field.handleChange}
+                description="Documents larger than this are rejected (0 = no limit)"
+                error={field.state.meta.errors?.[0]}
+                min={0}
+              />
+            )} />
+            <form.Field name="limits.maxPages" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+              <NumberField
+                label="Max Pages"
+                value={field.state.value}
+                onChange={field.handleChange}
+                description="Documents with more pages are rejected (0 = no limit)"
+                error={field.state.meta.errors?.[0]}
+                min={0}
+              />
+            )} />
+            <form.Field name="limits.jobTimeoutSecs" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+              <NumberField
+                label="Job Timeout (seconds)"
+                value={field.state.value}
+                onChange={field.handleChange}
+                description="Processing of a single document is aborted after this long (0 = no limit)"
+                error={field.state.meta.errors?.[0]}
+                min={0}
+              />
+            )} />
+            <form.Field name="limits.ocrPageTimeoutSecs" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+              <NumberField
+                label="Page Timeout (seconds)"
+                value={field.state.value}
+                onChange={field.handleChange}
+                description="Rendering and OCR of a single page is aborted after this long (0 = no limit)"
+                error={field.state.meta.errors?.[0]}
+                min={0}
+              />
+            )} />
+            <form.Field name="limits.maxArchiveEntries" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+              <NumberField
+                label="Max Archive Files"
+                value={field.state.value}
+                onChange={field.handleChange}
+                description="ZIP and 7z archives with more files are rejected (0 = no limit)"
+                error={field.state.meta.errors?.[0]}
+                min={0}
+              />
+            )} />
+            <form.Field name="limits.maxArchiveSizeMb" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+              <NumberField
+                label="Max Archive Size (MB)"
+                value={field.state.value}
+                onChange={field.handleChange}
+                description="Archives that unpack to more than this are rejected (0 = no limit)"
+                error={field.state.meta.errors?.[0]}
+                min={0}
+              />
+            )} />
+            <form.Field name="limits.maxArchiveDepth" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+              <NumberField
+                label="Max Archive Nesting"
+                value={field.state.value}
+                onChange={field.handleChange}
+                description="How many archives deep nested archives are unpacked (0 = no limit)"
+                error={field.state.meta.errors?.[0]}
+                min={0}
+              />
+            )} />
+          </div>
+        </AccordionContent>
+      </AccordionItem>
+
+      {/* Categorization */}
+      <AccordionItem value="categorization">
+        <AccordionTrigger className="hover:no-underline">
+          <div className="flex items-center gap-2">
+            <Scale className="h-4 w-4" />
+            Categorization
+          </div>
+        </AccordionTrigger>
+        <AccordionContent>
+          <div className="space-y-4 pt-4">
+            <form.Field name="categorization.mode" children={(field: { state: { value: string; meta: { errors: string[] } }; handleChange: (v: string) => void }) => (
+              <SelectField
+                label="Mode"
+                value={field.state.value}
+                onChange={field.handleChange}
+                options={[
+                  { value: 'firstMatch', label: 'First match — highest-priority matching rule wins' },
+                  { value: 'scoring', label: 'Scoring — best-scoring rule wins' },
+                ]}
+                description="In scoring mode every rule is scored from its weighted conditions and the document records its confidence and runner-up categories"
+              />
+            )} />
+            {categorizationMode === 'scoring' && (
+              <>
+                <form.Field name="categorization.threshold" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+                  <NumberField
+                    label="Threshold"
+                    value={field.state.value}
+                    onChange={field.handleChange}
+                    description="Lowest score (0 to 1) a rule needs; below it documents are unsorted"
+                    error={field.state.meta.errors?.[0]}
+                    min={0}
+                    max={1}
+                    step={0.05}
+                  />
+                )} />
+                <form.Field name="categorization.alternatives" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+                  <NumberField
+                    label="Alternatives"
+                    value={field.state.value}
+                    onChange={field.handleChange}
+                    description="Number of runner-up categories recorded with each document"
+                    error={field.state.meta.errors?.[0]}
+                    min={0}
+                  />
+                )} />
+              </>
+            )}
+            <form.Field name="categorization.classifier.enabled" children={(field: { state: { value: boolean; meta: { errors: string[] } }; handleChange: (v: boolean) => void }) => (
+              <SwitchField
+                label="Classifier"
+                checked={field.state.value}
+                onChange={field.handleChange}
+                description="When no rule matches, predict a category from documents filed by rules or corrected by hand"
+              />
+            )} />
+            {classifierEnabled && (
+              <>
+                <form.Field name="categorization.classifier.autoFile" children={(field: { state: { value: boolean; meta: { errors: string[] } }; handleChange: (v: boolean) => void }) => (
+                  <SwitchField
+                    label="Auto-file predictions"
+                    checked={field.state.value}
+                    onChange={field.handleChange}
+                    description="File documents under the predicted category when its confidence reaches the threshold; otherwise it is only suggested"
+                  />
+                )} />
+                <form.Field name="categorization.classifier.threshold" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+                  <NumberField
+                    label="Auto-file threshold"
+                    value={field.state.value}
+                    onChange={field.handleChange}
+                    description="Lowest confidence (0 to 1) a prediction needs to be filed"
+                    error={field.state.meta.errors?.[0]}
+                    min={0}
+                    max={1}
+                    step={0.05}
+                  />
+                )} />
+                <form.Field name="categorization.classifier.minExamples" children={(field: { state: { value: number; meta: { errors: string[] } }; handleChange: (v: number) => void }) => (
+                  <NumberField
+                    label="Minimum examples"
+                    value={field.state.value}
+                    onChange={field.handleChange}
+                    description="Documents a category needs before the classifier predicts it"
+                    error={field.state.meta.errors?.[0]}
+                    min={1}
+                  />
+                )} />
+              </>
+            )}
+          </div>
+        </AccordionContent>
+      </AccordionItem>
+
       {/* Default Output Settings */}
       <AccordionItem value="defaults">
         <AccordionTrigger className="hover:no-underline">
@@ -1,6 +1,7 @@
-import { TextField, SelectField, PatternField } from '@/components/form'
+import { TextField, SelectField, PatternField, FormField } from '@/components/form'
 import { type VariableSpec } from '@/schemas/resources'
 import type { FormInstance } from '@/lib/form-utils'
+import { PageScopeEditor } from './PageScopeEditor'
 
 interface VariableFormProps {
   form: FormInstance
@@ -28,34 +29,82 @@ export function VariableForm({
         />
       )}
 
-      <form.Field name="pattern" children={(field: { state: { value: string; meta: { errors: string[] } }; handleChange: (v: string) => void }) => (
-        <PatternField
-          label="Pattern"
-          value={field.state.value}
-          onChange={field.handleChange}
-          description="Regex pattern to extract value from document text. Use named groups like (?P<value>...)"
+      <form.Field name="template" children={(field: { state: { value: string | undefined; meta: { errors: string[] } }; handleChange: (v: string | undefined) => void }) => (
+        <TextField
+          label="Computed From"
+          value={field.state.value || ''}
+          onChange={(v: string) => field.handleChange(v || undefined)}
+          description="Build the value from other variables instead of the document, e.g. $doc_y-Q$quarter. The pattern is then optional and searches this text."
           error={field.state.meta.errors?.[0]}
-          required
-          placeholder={"(?P<value>\\w+)"}
+          placeholder="$doc_y-Q$quarter"
         />
       )} />
 
-      <form.Field name="transform" children={(field: { state: { value: VariableSpec['transform']; meta: { errors: string[] } }; handleChange: (v: VariableSpec['transform']) => void }) => (
+      <form.Field name="from" children={(field: { state: { value: VariableSpec['from']; meta: { errors: string[] } }; handleChange: (v: VariableSpec['from']) => void }) => (
         <SelectField
-          label="Transform"
-          value={field.state.value || 'none'}
-          onChange={(v: string) => field.handleChange(v === 'none' ? undefined : v as VariableSpec['transform'])}
+          label="Search In"
+          value={field.state.value || 'text'}
+          onChange={(v: string) => field.handleChange(v === 'text' ? undefined : v as VariableSpec['from'])}
           options={[
-            { value: 'none', label: 'None (keep as-is)' },
-            { value: 'slugify', label: 'Slugify (url-friendly)' },
-            { value: 'uppercase', label: 'Uppercase' },
-            { value: 'lowercase', label: 'Lowercase' },
-            { value: 'trim', label: 'Trim whitespace' },
+            { value: 'text', label: 'Document text' },
+            { value: 'filename', label: 'Filename' },
+            { value: 'sourcePath', label: 'Path below the import source' },
+            { value: 'email.subject', label: 'Email subject' },
+            { value: 'email.from', label: 'Email sender' },
+            { value: 'pdfMetadata', label: 'PDF metadata (Title, Author, ...)' },
           ]}
-          description="Optional transformation to apply to extracted value"
+          description="Input the pattern is searched in. PDF metadata is given as 'Key: value' lines."
+          error={field.state.meta.errors?.[0]}
+        />
+      )} />
+
+      <form.Field name="pattern" children={(field: { state: { value: string | undefined; meta: { errors: string[] } }; handleChange: (v: string | undefined) => void }) => (
+        <PatternField
+          label="Pattern"
+          value={field.state.value || ''}
+          onChange={(v: string) => field.handleChange(v || undefined)}
+          description="Regex pattern to extract value from document text. Use named groups like (?P<value>...). Required unless the variable is computed."
+          error={field.state.meta.errors?.[0]}
+          placeholder={"(?P<value>\\w+)"}
         />
       )} />
 
+      <form.Field name="transform" children={(field: { state: { value: VariableSpec['transform']; meta: { errors: string[] } }; handleChange: (v: VariableSpec['transform']) => void }) => {
+        const transform = field.state.value
+        const lookup = typeof transform === 'object' ? transform.lookup : undefined
+        return (
+          <div className="space-y-4">
+            <SelectField
+              label="Transform"
+              value={lookup !== undefined ? 'lookup' : transform || 'none'}
+              onChange={(v: string) => field.handleChange(
+                v === 'none' ? undefined : v === 'lookup' ? { lookup: '' } : v as VariableSpec['transform']
+              )}
+              options={[
+                { value: 'none', label: 'None (keep as-is)' },
+                { value: 'slugify', label: 'Slugify (url-friendly)' },
+                { value: 'uppercase', label: 'Uppercase' },
+                { value: 'lowercase', label: 'Lowercase' },
+                { value: 'trim', label: 'Trim whitespace' },
+                { value: 'lookup', label: 'Lookup table (normalize values)' },
+              ]}
+              description="Optional transformation to apply to extracted value"
+            />
+            {lookup !== undefined && (
+              <TextField
+                label="Lookup Table"
+                value={lookup}
+                onChange={(v: string) => field.handleChange({ lookup: v })}
+                description="Name of the LookupTable resource that maps extracted values to canonical ones"
+                error={field.state.meta.errors?.[0]}
+                required
+                placeholder="vendors"
+              />
+            )}
+          </div>
+        )
+      }} />
+
       <form.Field name="default" children={(field: { state: { value: string | undefined; meta: { errors: string[] } }; handleChange: (v: string | undefined) => void }) => (
         <TextField
           label="Default Value"
@@ -65,6 +114,23 @@ export function VariableForm({
           placeholder="unknown"
         />
       )} />
+
+      <form.Field name="pages" children={(pagesField: { state: { value: VariableSpec['pages'] }; handleChange: (v: VariableSpec['pages']) => void }) => (
+        <form.Field name="region" children={(regionField: { state: { value: VariableSpec['region'] }; handleChange: (v: VariableSpec['region']) => void }) => (
+          <FormField
+            label="Search Scope"
+            description="Only search these pages, or the top or bottom lines of each page, e.g. the sender on page 1 or the total on the last page. Email headers are not searched."
+          >
+            <PageScopeEditor
+              scope={{ pages: pagesField.state.value, region: regionField.state.value }}
+              onChange={(scope) => {
+                pagesField.handleChange(scope.pages)
+                regionField.handleChange(scope.region)
+              }}
+            />
+          </FormField>
+        )} />
+      )} />
     </div>
   )
 }
@@ -95,8 +95,11 @@ export function FileTreeItem({
         <span className="ml-1 text-sm truncate flex-1">{node.name}</span>
 
         {node.resource && (
-          <span className="text-xs text-muted-foreground px-1.5 py-0.5 bg-muted rounded">
-            {node.resource.kind}
+          <span
+            className="text-xs text-muted-foreground px-1.5 py-0.5 bg-muted rounded"
+            title={node.resource.fields?.map((f) => `${f.field}: ${f.path}`).join('\n')}
+          >
+            {node.resources ? `${node.resources.length} resources` : node.resource.kind}
           </span>
         )}
       </div>
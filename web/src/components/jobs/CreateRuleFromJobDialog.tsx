@@ -1,4 +1,4 @@
-import { useEffect, useState, useCallback } from 'react'
+import { useEffect, useState, useCallback, useMemo } from 'react'
 import yaml from 'js-yaml'
 import {
   AlertTriangle,
@@ -40,6 +40,8 @@ import { api } from '@/api'
 import { toast } from '@/components/ui/use-toast'
 import type { StoredJob } from '@/types/jobs'
 import { requiresOcr } from '@/types/jobs'
+import { highlightSegments, matchedSpans } from '@/lib/explanation'
+import { MatchExplanation } from './MatchExplanation'
 import type { RuleSuggestion, ExistingRuleSummary } from '@/types/ai'
 import { API_VERSION } from '@/types/gitops'
 
@@ -483,6 +485,13 @@ export function CreateRuleFromJobDialog({
     ? 'No OCR text available for this document'
     : 'No text content available for this document'
 
+  // Highlight the text that made the winning rule match
+  const ocrSegments = useMemo(() => {
+    const winner = job?.explanation?.winner
+    const spans = winner ? matchedSpans(winner.condition).filter(span => span.field === 'text') : []
+    return highlightSegments(ocrText, spans)
+  }, [ocrText, job?.explanation])
+
   return (
     <Dialog open={open} onOpenChange={open => !open && onClose()}>
       <DialogContent className="max-w-7xl w-[95vw] h-[90vh] flex flex-col">
@@ -523,7 +532,17 @@ export function CreateRuleFromJobDialog({
                     </div>
                   ) : hasOcrText ? (
                     <div className="border rounded-md p-3 bg-muted/30 max-h-[40vh] overflow-auto">
-                      <pre className="text-xs whitespace-pre-wrap font-mono">{ocrText}</pre>
+                      <pre className="text-xs whitespace-pre-wrap font-mono">
+                        {ocrSegments.map((segment, index) =>
+                          segment.highlighted ? (
+                            <mark key={index} className="rounded bg-yellow-200/70 dark:bg-yellow-500/30">
+                              {segment.text}
+                            </mark>
+                          ) : (
+                            segment.text
+                          )
+                        )}
+                      </pre>
                     </div>
                   ) : (
                     <div className="border rounded-md p-3 flex items-center justify-center text-muted-foreground min-h-[100px]">
@@ -535,6 +554,14 @@ export function CreateRuleFromJobDialog({
                   )}
                 </div>
 
+                {/* Match Explanation */}
+                {job?.explanation && (
+                  <div className="flex flex-col">
+                    <Label className="mb-2">Why This Category</Label>
+                    <MatchExplanation explanation={job.explanation} />
+                  </div>
+                )}
+
             {/* AI Suggestions */}
             <div className="flex flex-col">
               <div className="flex items-center justify-between mb-2">
@@ -67,6 +67,10 @@ function StatusBadge({ job }: { job: StoredJob }) {
   }
 }
 
+function formatConfidence(confidence: number): string {
+  return confidence.toFixed(2)
+}
+
 function ResultCell({ job }: { job: StoredJob }) {
   if (job.status === 'processing') {
     return (
@@ -96,9 +100,27 @@ function ResultCell({ job }: { job: StoredJob }) {
           ) : (
             <Badge variant="outline" className="text-xs">
               {job.category}
+              {job.confidence !== undefined && ` (${formatConfidence(job.confidence)})`}
             </Badge>
           )
         )}
+        {job.alternatives && job.alternatives.length > 0 && (
+          <div className="text-xs text-muted-foreground">
+            {job.alternatives.length === 1 ? 'alternative: ' : 'alternatives: '}
+            {job.alternatives
+              .map((alt) => `${alt.category} (${formatConfidence(alt.confidence)})`)
+              .join(', ')}
+          </div>
+        )}
+        {job.tags && job.tags.length > 0 && (
+          <div className="flex flex-wrap gap-1">
+            {job.tags.map((tag) => (
+              <Badge key={tag} variant="secondary" className="text-xs">
+                {tag}
+              </Badge>
+            ))}
+          </div>
+        )}
       </div>
     )
   }
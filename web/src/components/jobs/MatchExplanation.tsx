@@ -0,0 +1,136 @@
+import { CheckCircle2, XCircle } from 'lucide-react'
+import { Badge } from '@/components/ui/badge'
+import type {
+  CategorizationExplanation,
+  ConditionTrace,
+  CorrespondentEvidence,
+  RuleExplanation,
+} from '@/types/jobs'
+import { cn } from '@/lib/utils'
+
+interface MatchExplanationProps {
+  explanation: CategorizationExplanation
+}
+
+/** Shows which conditions of the evaluated rules matched. */
+export function MatchExplanation({ explanation }: MatchExplanationProps) {
+  const rejected = explanation.rejected ?? []
+  const correspondent = explanation.correspondent
+
+  return (
+    <div className="space-y-3 text-xs">
+      {correspondent && (
+        <p className="text-muted-foreground">
+          From{' '}
+          <Badge variant="outline" className="text-xs">
+            {correspondent.name}
+          </Badge>{' '}
+          ({describeEvidence(correspondent.evidence)}).
+        </p>
+      )}
+      {explanation.winner ? (
+        <RuleTrace rule={explanation.winner} />
+      ) : explanation.classifier && (explanation.classifier.filed || !correspondent?.defaultCategory) ? (
+        <p className="text-muted-foreground">
+          No rule matched; the classifier {explanation.classifier.filed ? 'filed it under' : 'suggests'}{' '}
+          <Badge variant="outline" className="text-xs">
+            {explanation.classifier.category}
+          </Badge>{' '}
+          ({Math.round(explanation.classifier.confidence * 100)}% confidence).
+        </p>
+      ) : correspondent?.defaultCategory ? (
+        <p className="text-muted-foreground">
+          No rule matched; filed under the correspondent's default category{' '}
+          <Badge variant="outline" className="text-xs">
+            {correspondent.defaultCategory}
+          </Badge>
+          .
+        </p>
+      ) : (
+        <p className="text-muted-foreground">No rule matched; the document is unsorted.</p>
+      )}
+      {rejected.length > 0 && (
+        <div className="space-y-2">
+          <p className="text-muted-foreground">Evaluated without winning:</p>
+          {rejected.map((rule) => (
+            <RuleTrace key={rule.ruleId} rule={rule} />
+          ))}
+        </div>
+      )}
+    </div>
+  )
+}
+
+function describeEvidence(evidence: CorrespondentEvidence): string {
+  switch (evidence.kind) {
+    case 'emailDomain':
+      return `sent from ${evidence.value}`
+    case 'iban':
+      return `IBAN ${evidence.value}`
+    case 'vatNumber':
+      return `VAT number ${evidence.value}`
+    case 'name':
+      return `mentions "${evidence.value}"`
+  }
+}
+
+function RuleTrace({ rule }: { rule: RuleExplanation }) {
+  return (
+    <div className="border rounded-md p-2 space-y-1">
+      <div className="flex items-center gap-2">
+        <span className="font-medium">{rule.ruleId}</span>
+        <Badge variant="outline" className="text-xs">
+          {rule.category}
+        </Badge>
+        {rule.score !== undefined && (
+          <span className="text-muted-foreground">{Math.round(rule.score * 100)}%</span>
+        )}
+      </div>
+      <ConditionNode trace={rule.condition} />
+    </div>
+  )
+}
+
+function ConditionNode({ trace }: { trace: ConditionTrace }) {
+  const Icon = trace.matched ? CheckCircle2 : XCircle
+  const values = trace.values ?? []
+  const spans = trace.spans ?? []
+
+  return (
+    <div className="space-y-1">
+      <div className="flex items-start gap-1.5">
+        <Icon
+          className={cn(
+            'h-3.5 w-3.5 mt-0.5 shrink-0',
+            trace.matched ? 'text-green-600' : 'text-muted-foreground'
+          )}
+        />
+        <span className="font-mono">
+          {trace.kind}
+          {trace.field && <span className="text-muted-foreground"> [{trace.field}]</span>}
+          {values.length > 0 && <span className="text-muted-foreground">: {values.join(', ')}</span>}
+        </span>
+      </div>
+      {spans.length > 0 && (
+        <div className="ml-5 flex flex-wrap gap-1">
+          {spans.map((span, index) => (
+            <mark
+              key={index}
+              className="rounded px-1 font-mono bg-yellow-200/70 dark:bg-yellow-500/30"
+              title={`${span.field ?? 'text'} bytes ${span.start}–${span.end}`}
+            >
+              {span.text}
+            </mark>
+          ))}
+        </div>
+      )}
+      {trace.children && trace.children.length > 0 && (
+        <div className="ml-4 pl-2 border-l space-y-1">
+          {trace.children.map((child, index) => (
+            <ConditionNode key={index} trace={child} />
+          ))}
+        </div>
+      )}
+    </div>
+  )
+}
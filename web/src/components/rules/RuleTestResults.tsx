@@ -0,0 +1,60 @@
+import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card'
+import { Badge } from '@/components/ui/badge'
+import { CheckCircle2, XCircle } from 'lucide-react'
+import type { RuleTestReport } from '@/api'
+
+interface RuleTestResultsProps {
+  report: RuleTestReport
+}
+
+export function RuleTestResults({ report }: RuleTestResultsProps) {
+  return (
+    <Card>
+      <CardHeader className="pb-3">
+        <CardTitle className="flex items-center gap-2 text-base">
+          Rule Tests
+          <Badge variant="success">{report.passed} passed</Badge>
+          {report.failed > 0 && <Badge variant="destructive">{report.failed} failed</Badge>}
+        </CardTitle>
+      </CardHeader>
+      <CardContent>
+        {report.results.length === 0 ? (
+          <p className="text-sm text-muted-foreground">
+            No rule tests found. Add RuleTest resources under tests/ to check rules against sample documents.
+          </p>
+        ) : (
+          <ul className="space-y-2">
+            {report.results.map((result) => (
+              <li key={`${result.test}/${result.case}`} className="flex items-start gap-2 text-sm">
+                {result.passed ? (
+                  <CheckCircle2 className="h-4 w-4 mt-0.5 shrink-0 text-green-500" />
+                ) : (
+                  <XCircle className="h-4 w-4 mt-0.5 shrink-0 text-destructive" />
+                )}
+                <div className="min-w-0">
+                  <div>
+                    <span className="font-medium">{result.test}</span>
+                    <span className="text-muted-foreground"> / {result.case}</span>
+                  </div>
+                  {!result.passed && (
+                    <div className="text-xs text-muted-foreground space-y-0.5">
+                      {result.error && <div className="text-destructive">{result.error}</div>}
+                      {result.failures?.map((failure) => (
+                        <div key={failure}>{failure}</div>
+                      ))}
+                      <div>
+                        Got {result.category ?? 'no category'}
+                        {result.rule && ` via ${result.rule}`}
+                        {result.output && ` → ${result.output}`}
+                      </div>
+                    </div>
+                  )}
+                </div>
+              </li>
+            ))}
+          </ul>
+        )}
+      </CardContent>
+    </Card>
+  )
+}
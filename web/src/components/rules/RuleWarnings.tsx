@@ -0,0 +1,38 @@
+import { Alert, AlertDescription, AlertTitle } from '@/components/ui/alert'
+import { AlertTriangle } from 'lucide-react'
+import type { Diagnostic } from '@/api'
+
+interface RuleWarningsProps {
+  diagnostics: Diagnostic[]
+}
+
+function location(diagnostic: Diagnostic): string | null {
+  if (!diagnostic.path) return null
+  return diagnostic.line ? `${diagnostic.path}:${diagnostic.line}` : diagnostic.path
+}
+
+export function RuleWarnings({ diagnostics }: RuleWarningsProps) {
+  const warnings = diagnostics.filter((d) => d.severity === 'warning')
+  if (warnings.length === 0) return null
+
+  return (
+    <Alert>
+      <AlertTriangle className="h-4 w-4" />
+      <AlertTitle>Rule Analysis</AlertTitle>
+      <AlertDescription>
+        <ul className="list-disc pl-4 space-y-1 text-sm">
+          {warnings.map((warning, i) => (
+            <li key={i}>
+              {warning.message}
+              {location(warning) && (
+                <span className="ml-2 font-mono text-xs text-muted-foreground">
+                  {location(warning)}
+                </span>
+              )}
+            </li>
+          ))}
+        </ul>
+      </AlertDescription>
+    </Alert>
+  )
+}
@@ -41,9 +41,8 @@ function countRulesInTree(root: FileTreeNode | null): number {
 
   while (stack.length > 0) {
     const node = stack.pop()!
-    if (node.resource?.kind === 'Rule') {
-      count += 1
-    }
+    const resources = node.resources ?? (node.resource ? [node.resource] : [])
+    count += resources.filter((r) => r.kind === 'Rule').length
     // Add children to stack in reverse order to maintain left-to-right traversal
     for (let i = node.children.length - 1; i >= 0; i--) {
       stack.push(node.children[i])
@@ -0,0 +1,46 @@
+import { describe, it, expect } from 'vitest'
+import { highlightSegments, matchedSpans } from './explanation'
+import type { ConditionTrace } from '@/types/jobs'
+
+describe('matchedSpans', () => {
+  it('collects spans of matching leaves, skipping negated ones', () => {
+    const trace: ConditionTrace = {
+      kind: 'all',
+      matched: true,
+      children: [
+        { kind: 'contains', matched: true, spans: [{ field: 'text', start: 0, end: 3, text: 'abc' }] },
+        {
+          kind: 'not',
+          matched: true,
+          children: [{ kind: 'contains', matched: false, spans: [{ start: 5, end: 6, text: 'x' }] }],
+        },
+        { kind: 'contains', matched: false, spans: [{ field: 'text', start: 7, end: 8, text: 'y' }] },
+      ],
+    }
+    expect(matchedSpans(trace).map(span => span.text)).toEqual(['abc'])
+  })
+})
+
+describe('highlightSegments', () => {
+  it('splits text around spans', () => {
+    const spans = [{ start: 4, end: 11, text: 'Invoice' }]
+    expect(highlightSegments('Our Invoice 42', spans)).toEqual([
+      { text: 'Our ', highlighted: false },
+      { text: 'Invoice', highlighted: true },
+      { text: ' 42', highlighted: false },
+    ])
+  })
+
+  it('converts UTF-8 byte offsets and merges overlaps', () => {
+    // "Zürich" is 7 bytes in UTF-8 but 6 characters
+    const spans = [
+      { start: 3, end: 10, text: 'Zürich' },
+      { start: 6, end: 10, text: 'rich' },
+    ]
+    expect(highlightSegments('in Zürich AG', spans)).toEqual([
+      { text: 'in ', highlighted: false },
+      { text: 'Zürich', highlighted: true },
+      { text: ' AG', highlighted: false },
+    ])
+  })
+})
@@ -0,0 +1,59 @@
+import type { ConditionTrace, MatchSpan } from '@/types/jobs'
+
+/** A run of text, highlighted when a condition matched it. */
+export interface TextSegment {
+  text: string
+  highlighted: boolean
+}
+
+/**
+ * Returns the spans of every matching leaf in tree order. Leaves below a
+ * `not` are skipped, since they matched against the rule.
+ */
+export function matchedSpans(trace: ConditionTrace): MatchSpan[] {
+  if (!trace.matched || trace.kind === 'not') return []
+  return [...(trace.spans ?? []), ...(trace.children ?? []).flatMap(matchedSpans)]
+}
+
+/** Number of bytes a code point takes in UTF-8. */
+function utf8Length(codePoint: number): number {
+  if (codePoint < 0x80) return 1
+  if (codePoint < 0x800) return 2
+  if (codePoint < 0x10000) return 3
+  return 4
+}
+
+/** Converts a UTF-8 byte offset into a string index. */
+function byteOffsetToIndex(text: string, offset: number): number {
+  let bytes = 0
+  let index = 0
+  for (const char of text) {
+    if (bytes >= offset) break
+    bytes += utf8Length(char.codePointAt(0)!)
+    index += char.length
+  }
+  return index
+}
+
+/**
+ * Splits text into highlighted and plain segments. Span offsets are UTF-8
+ * bytes, as reported by the categorizer; overlapping spans are merged.
+ */
+export function highlightSegments(text: string, spans: MatchSpan[]): TextSegment[] {
+  const ranges = spans
+    .map(span => [byteOffsetToIndex(text, span.start), byteOffsetToIndex(text, span.end)])
+    .filter(([start, end]) => end > start)
+    .sort((a, b) => a[0] - b[0])
+
+  const segments: TextSegment[] = []
+  let position = 0
+  for (const [start, end] of ranges) {
+    if (end <= position) continue
+    const from = Math.max(start, position)
+    if (from > position) segments.push({ text: text.slice(position, from), highlighted: false })
+    segments.push({ text: text.slice(from, end), highlighted: true })
+    position = end
+  }
+  if (position < text.length) segments.push({ text: text.slice(position), highlighted: false })
+  return segments
+}
@@ -200,3 +200,9 @@ export function useDeleteFile() {
     },
   })
 }
+
+export function useRunRuleTests() {
+  return useMutation({
+    mutationFn: async ({ name }: { name?: string } = {}) => api.gitops.runRuleTests(name),
+  })
+}
@@ -3,3 +3,4 @@ export { useGitStatus } from './use-git-status'
 export { useBranches } from './use-branches'
 export { useSettings } from './use-settings'
 export { useResource } from './use-resource'
+export { useConfigValidation } from './use-config-validation'
@@ -0,0 +1,15 @@
+import { useQuery } from '@tanstack/react-query'
+import { api, type ValidationResult } from '@/api'
+
+export function useConfigValidation(): { data: ValidationResult | null; isLoading: boolean } {
+  // Nested under the resources key so resource mutations revalidate
+  const result = useQuery({
+    queryKey: ['gitops', 'resources', 'validation'],
+    queryFn: () => api.gitops.validateConfig(),
+  })
+
+  return {
+    data: result.data ?? null,
+    isLoading: result.isLoading,
+  }
+}
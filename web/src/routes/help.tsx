@@ -43,6 +43,8 @@ const flowSteps = [
 const staticBuiltInVariables = [
   { name: '$original', description: 'Original filename without extension', example: 'invoice_scan' },
   { name: '$uuid', description: 'Unique identifier', example: 'a1b2c3d4-...' },
+  { name: '$tags', description: "Categories of the matching non-exclusive rules joined with '_' ('untagged' if none)", example: 'tax_health' },
+  { name: '$correspondent', description: "Display name of the detected correspondent ('unknown' if none)", example: 'Helsana' },
 ]
 
 const matchConditionTypes = [
@@ -50,10 +52,18 @@ const matchConditionTypes = [
   { type: 'containsAny', description: 'Contains any of the specified texts (case-insensitive by default)', example: 'containsAny: ["Invoice", "Bill"]' },
   { type: 'containsAll', description: 'Contains all of the specified texts (case-insensitive by default)', example: 'containsAll: ["Invoice", "VAT"]' },
   { type: 'pattern', description: 'Matches a regex pattern (case-insensitive by default)', example: 'pattern: "INV-\\d+"' },
+  { type: 'fuzzy', description: 'Contains text, tolerating OCR errors such as 0/O, 1/l, rn/m and stray spaces (always case-insensitive)', example: 'fuzzy: {value: "Swisscom", maxDistance: 1}' },
+  { type: 'containsAnyFuzzy', description: 'Contains any of the specified texts, tolerating OCR errors', example: 'containsAnyFuzzy: ["Krankenkasse", "Versicherung"]' },
+  { type: 'variable', description: 'Compares an extracted variable as a number or date with eq, gt, lt, between, in or exists', example: 'variable: {name: amount, gt: 1000}' },
+  { type: 'correspondent', description: 'Document comes from the named Correspondent resource', example: 'correspondent: helsana' },
   { type: 'all', description: 'All conditions must match (AND)', example: 'all: [{...}, {...}]' },
   { type: 'any', description: 'Any condition can match (OR)', example: 'any: [{...}, {...}]' },
   { type: 'not', description: 'Condition must NOT match', example: 'not: {contains: "Draft"}' },
   { type: 'caseSensitive', description: 'Optional boolean to enable case-sensitive matching (default: false)', example: 'caseSensitive: true' },
+  { type: 'field', description: 'Optional document field to match against: text, firstPage, filename, source, mimeType, email.from, email.to or email.subject (default: text; nested conditions inherit it)', example: 'field: email.from' },
+  { type: 'pages', description: "Optional pages of the extracted text to search: first, last or a list of page numbers starting at 1; nested conditions inherit it. Also available on variables", example: 'pages: first' },
+  { type: 'region', description: 'Optional top or bottom N non-blank lines of each selected page, e.g. for letterheads or totals. Nested conditions inherit it. Also available on variables', example: 'region: {top: 10}' },
+  { type: 'weight', description: "Optional weight of a condition among the siblings of an 'all' when settings use categorization mode 'scoring' (default: 1). The rule's score is the weighted share of its conditions that match", example: 'weight: 3' },
 ]
 
 const transforms = [
@@ -61,6 +71,7 @@ const transforms = [
   { name: 'uppercase', description: 'Convert to uppercase', example: 'invoice → INVOICE' },
   { name: 'lowercase', description: 'Convert to lowercase', example: 'INVOICE → invoice' },
   { name: 'trim', description: 'Remove leading/trailing whitespace', example: '  text  → text' },
+  { name: 'lookup', description: 'Map through a LookupTable, e.g. transform: {lookup: vendors}', example: 'SBB CFF FFS → SBB' },
 ]
 
 export function HelpPage() {
@@ -386,6 +397,10 @@ export function HelpPage() {
                     <Badge variant="outline">Priority</Badge>
                     <span>Higher priority rules are checked first</span>
                   </div>
+                  <div className="flex items-start gap-2">
+                    <Badge variant="outline">Exclusive</Badge>
+                    <span>Set to false to tag matching documents with the rule's category and create its symlinks, in addition to the primary category</span>
+                  </div>
                   <div className="flex items-start gap-2">
                     <Badge variant="outline">Match</Badge>
                     <span>Conditions that must be met (contains, pattern, etc.)</span>
@@ -749,6 +764,380 @@ spec:
                     </div>
                   </AccordionContent>
                 </AccordionItem>
+
+                <AccordionItem value="test">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">5</Badge>
+                      RuleTest: Guard Against Regressions
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      Rule tests live under <code className="bg-muted px-1 rounded">tests/</code> and run during
+                      validation or from the Rules page. Each case uses sample <code className="bg-muted px-1 rounded">text</code> or
+                      a <code className="bg-muted px-1 rounded">file</code> relative to the config directory.
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`apiVersion: paporg.io/v1
+kind: RuleTest
+metadata:
+  name: invoices
+spec:
+  cases:
+    - name: acme invoice
+      text: "Invoice #INV-2024-001 Total: 42.00"
+      filename: scan_march.pdf
+      expect:
+        category: Tax
+        rule: invoices
+    - name: scanned receipt
+      file: tests/fixtures/receipt.pdf
+      expect:
+        category: unsorted`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="template">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">6</Badge>
+                      RuleTemplate: Share Rule Layouts
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      Templates live under <code className="bg-muted px-1 rounded">templates/</code> and never match
+                      documents themselves. A rule with <code className="bg-muted px-1 rounded">extends</code> inherits
+                      the category and each output template it leaves out, adds its symlinks to the inherited ones, and
+                      only matches when the inherited match conditions hold too. Rules can also extend other rules;
+                      priority and exclusivity are never inherited. The rule editor shows the effective rule.
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`# templates/vendor.yaml
+apiVersion: paporg.io/v1
+kind: RuleTemplate
+metadata:
+  name: vendor
+spec:
+  category: Vendors
+  match:
+    containsAny: ["Invoice", "Rechnung"]
+  output:
+    directory: "$y/Vendors/$vendor"
+    filename: "$original"
+  symlinks:
+    - target: "ByYear/$y"
+
+# rules/acme.yaml
+apiVersion: paporg.io/v1
+kind: Rule
+metadata:
+  name: acme
+spec:
+  extends: vendor
+  match:
+    contains: "ACME GmbH"`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="correspondent">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">7</Badge>
+                      Correspondent: Recognize Senders
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      Correspondents live under <code className="bg-muted px-1 rounded">correspondents/</code>. Each
+                      document is checked against the email sender's domain first, then the IBANs and VAT numbers in
+                      its text (spaces and punctuation ignored, so QR bill details count once they are in the text),
+                      and finally the display name and aliases as whole words. The match is available as{' '}
+                      <code className="bg-muted px-1 rounded">$correspondent</code> and to rules as{' '}
+                      <code className="bg-muted px-1 rounded">correspondent: name</code>. Documents no rule matches
+                      are filed under the <code className="bg-muted px-1 rounded">defaultCategory</code>.
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`# correspondents/helsana.yaml
+apiVersion: paporg.io/v1
+kind: Correspondent
+metadata:
+  name: helsana
+spec:
+  displayName: Helsana
+  aliases: ["Helsana Versicherungen AG"]
+  ibans: ["CH93 0076 2011 6238 5295 7"]
+  vatNumbers: ["CHE-105.953.932"]
+  emailDomains: ["helsana.ch"]
+  defaultCategory: Insurance
+
+# rules/premiums.yaml
+apiVersion: paporg.io/v1
+kind: Rule
+metadata:
+  name: premiums
+spec:
+  category: Insurance
+  match:
+    all:
+      - correspondent: helsana
+      - contains: "Prämie"
+  output:
+    directory: "$y/Insurance/$correspondent"
+    filename: "$original"`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="lookup">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">8</Badge>
+                      LookupTable: Normalize Values
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      Lookup tables live under <code className="bg-muted px-1 rounded">lookups/</code> and map the
+                      spellings a variable comes in to one folder name. Entries are listed inline or as{' '}
+                      <code className="bg-muted px-1 rounded">key,value</code> rows of a CSV file in the config
+                      repository. Exact keys ignore case and spacing, <code className="bg-muted px-1 rounded">mode: regex</code>{' '}
+                      searches the keys as patterns, and the first matching entry wins. Values no entry matches become
+                      the <code className="bg-muted px-1 rounded">fallback</code>, or stay as they are without one.
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`# lookups/vendors.yaml
+apiVersion: paporg.io/v1
+kind: LookupTable
+metadata:
+  name: vendors
+spec:
+  mode: regex
+  entries:
+    - key: "SBB|CFF|FFS|Bundesbahnen"
+      value: SBB
+  file: lookups/vendors.csv
+  fallback: Other
+
+# variables/vendor.yaml
+apiVersion: paporg.io/v1
+kind: Variable
+metadata:
+  name: vendor
+spec:
+  pattern: "(?m)^(?P<vendor>.+)$"
+  transform:
+    lookup: vendors`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="variable-from">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">9</Badge>
+                      Variable: Extract From the Filename or Metadata
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      With <code className="bg-muted px-1 rounded">from</code> a variable searches something other
+                      than the text: <code className="bg-muted px-1 rounded">filename</code>,{' '}
+                      <code className="bg-muted px-1 rounded">sourcePath</code> (the path below the import source),{' '}
+                      <code className="bg-muted px-1 rounded">email.subject</code>,{' '}
+                      <code className="bg-muted px-1 rounded">email.from</code> or{' '}
+                      <code className="bg-muted px-1 rounded">pdfMetadata</code>, the PDF's Title, Author and other
+                      properties as <code className="bg-muted px-1 rounded">Key: value</code> lines. Documents
+                      without that input use the default.
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`apiVersion: paporg.io/v1
+kind: Variable
+metadata:
+  name: insurer
+spec:
+  from: sourcePath
+  # scans/Helsana/2024/policy.pdf -> Helsana
+  pattern: "^(?P<insurer>[^/]+)/"
+  default: "unsorted"`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="variable-computed">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">10</Badge>
+                      Variable: Compute From Other Variables
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      A <code className="bg-muted px-1 rounded">template</code> builds a variable from other
+                      variables and built-ins after extraction, in the order their references require. An optional
+                      pattern then picks part of the result. When a referenced variable has no value the default
+                      applies. References may not form a cycle.
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`# variables/period.yaml
+apiVersion: paporg.io/v1
+kind: Variable
+metadata:
+  name: period
+spec:
+  template: "$doc_y-Q$quarter"
+
+# variables/vendor_short.yaml: first word of $vendor
+apiVersion: paporg.io/v1
+kind: Variable
+metadata:
+  name: vendor_short
+spec:
+  template: "$vendor"
+  pattern: "^(?P<vendor_short>\\S+)"
+  transform: slugify`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="multi-document-overlays">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">11</Badge>
+                      Several Resources per File and Overlays
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      Any file may hold several resources separated by <code className="bg-muted px-1 rounded">---</code>.
+                      Files in <code className="bg-muted px-1 rounded">overlays/&lt;name&gt;/</code> patch the resources
+                      of the same kind and name on a single machine: mappings merge, <code className="bg-muted px-1 rounded">null</code>{' '}
+                      removes a field and lists are replaced. The overlay is picked by the{' '}
+                      <code className="bg-muted px-1 rounded">PAPORG_OVERLAY</code> environment variable or the{' '}
+                      <code className="bg-muted px-1 rounded">overlay</code> setting. The file tree shows which file
+                      each field of a patched resource comes from.
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`# settings.yaml
+spec:
+  overlay: "$hostname"
+  inputDirectory: ~/Scans
+
+# overlays/nas/settings.yaml: only on the machine named nas
+apiVersion: paporg.io/v1
+kind: Settings
+metadata:
+  name: nas
+spec:
+  inputDirectory: /volume1/scans`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="editor-schemas">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">12</Badge>
+                      Editing Resources in Your Editor
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      Every resource kind has a JSON Schema, published in the{' '}
+                      <code className="bg-muted px-1 rounded">schema/</code> directory of the repository. Files written
+                      by paporg start with a comment that points editors using the YAML language server at it, for
+                      completion and inline errors. Paporg checks every file against the schema when loading, so a
+                      misspelled field is reported with its file and line instead of being ignored.
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`# yaml-language-server: $schema=https://paporg.io/schemas/rule.json
+apiVersion: paporg.io/v1
+kind: Rule`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="legacy-migration">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">13</Badge>
+                      Migrating a JSON Config
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground">
+                      A config in the older single-file JSON format, like those in the{' '}
+                      <code className="bg-muted px-1 rounded">examples/</code> directory, can be converted into
+                      resources of the config directory. Rules are named after their id, made into a valid resource
+                      name, and keep their display name as the{' '}
+                      <code className="bg-muted px-1 rounded">paporg.io/display-name</code> annotation. The migration
+                      lists everything that was renamed or had no exact counterpart, and does not overwrite existing
+                      files.
+                    </p>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="api-versions">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">14</Badge>
+                      Resource Versions
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground">
+                      The <code className="bg-muted px-1 rounded">apiVersion</code> of a resource names the format it
+                      is written in. When a new version changes a field, resources of the older version keep loading
+                      and are converted as they are read. Upgrading the repository rewrites them in the current
+                      version and, with git enabled, commits the rewritten files on their own so the change can be
+                      reviewed. Comments stay with the fields they were written at, or with the parent of a field
+                      that was renamed. Overlay patches are upgraded along with the resources they patch.
+                    </p>
+                  </AccordionContent>
+                </AccordionItem>
+
+                <AccordionItem value="resource-limits">
+                  <AccordionTrigger>
+                    <div className="flex items-center gap-2">
+                      <Badge variant="outline">15</Badge>
+                      Resource Limits
+                    </div>
+                  </AccordionTrigger>
+                  <AccordionContent>
+                    <p className="text-sm text-muted-foreground mb-2">
+                      Documents are processed within limits, which also apply to settings written before the limits
+                      existed. A document fails with a limit error when it is larger than 100 MB, has more than 500
+                      pages, takes longer than 15 minutes, or a page takes longer than 2 minutes to render and read.
+                      Set a limit to 0 to turn it off:
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`spec:
+  limits:
+    maxPages: 0          # no page limit
+    jobTimeoutSecs: 3600 # one hour per document`}
+                    </pre>
+                    <p className="text-sm text-muted-foreground mt-4 mb-2">
+                      <strong>Upgrading from a version without limits:</strong> earlier versions processed every
+                      document without limits. Settings files are not changed on upgrade, so the defaults below now
+                      apply to them. Archives are limited to 1000 files, 500 MB unpacked and 3 levels of nesting. To
+                      keep the old behavior, set every limit to 0 in your Settings resource:
+                    </p>
+                    <pre className="bg-muted p-4 rounded-lg text-sm overflow-x-auto border">
+{`spec:
+  limits:
+    maxFileSizeMb: 0
+    maxPages: 0
+    jobTimeoutSecs: 0
+    ocrPageTimeoutSecs: 0
+    maxArchiveEntries: 0
+    maxArchiveSizeMb: 0
+    maxArchiveDepth: 0`}
+                    </pre>
+                  </AccordionContent>
+                </AccordionItem>
               </Accordion>
             </CardContent>
           </Card>
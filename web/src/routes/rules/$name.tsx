@@ -13,6 +13,7 @@ import { useAutoSave } from '@/hooks/useAutoSave'
 import { useForm, useStore } from '@tanstack/react-form'
 import {
   type RuleResource,
+  type RuleSpec,
   createDefaultRuleSpec,
   ruleSpecSchema,
 } from '@/schemas/resources'
@@ -21,6 +22,21 @@ import { trackEvent } from '@/utils/analytics'
 import { buildRuleShareText } from '@/utils/ruleShare'
 import yaml from 'js-yaml'
 
+/** Builds the rule resource, leaving out `extends` and `match` when unset. */
+function buildRuleResource(name: string, spec: RuleSpec): RuleResource {
+  const { extends: parent, match, ...rest } = spec
+  return {
+    apiVersion: 'paporg.io/v1',
+    kind: 'Rule',
+    metadata: { name, labels: {}, annotations: {} },
+    spec: {
+      ...rest,
+      ...(parent ? { extends: parent } : {}),
+      ...(match ? { match } : {}),
+    },
+  }
+}
+
 export function RuleEditPage() {
   const { name: urlName } = useParams({ from: '/rules/$name' })
   const { folder } = useSearch({ from: '/rules/$name' })
@@ -114,12 +130,7 @@ export function RuleEditPage() {
     if (!resourceName && !isNew) return
 
     try {
-      const resource: RuleResource = {
-        apiVersion: 'paporg.io/v1',
-        kind: 'Rule',
-        metadata: { name: resourceName || 'new_rule', labels: {}, annotations: {} },
-        spec: formValues,
-      }
+      const resource = buildRuleResource(resourceName || 'new_rule', formValues)
       setYamlContent(yaml.dump(resource, { lineWidth: -1 }))
     } catch {
       // Ignore serialization errors while editing
@@ -134,12 +145,7 @@ export function RuleEditPage() {
     const savedValues = structuredClone(formValues)
     const savedName = resourceName
 
-    const resource: RuleResource = {
-      apiVersion: 'paporg.io/v1',
-      kind: 'Rule',
-      metadata: { name: savedName, labels: {}, annotations: {} },
-      spec: savedValues,
-    }
+    const resource = buildRuleResource(savedName, savedValues)
     const newYaml = yaml.dump(resource, { lineWidth: -1 })
 
     try {
@@ -208,12 +214,7 @@ export function RuleEditPage() {
     setError(null)
 
     try {
-      const resource: RuleResource = {
-        apiVersion: 'paporg.io/v1',
-        kind: 'Rule',
-        metadata: { name: resourceName, labels: {}, annotations: {} },
-        spec: formValues,
-      }
+      const resource = buildRuleResource(resourceName, formValues)
       const newYaml = yaml.dump(resource, { lineWidth: -1 })
 
       if (isNew) {
@@ -364,6 +365,21 @@ export function RuleEditPage() {
         }
       />
 
+      {!isNew && resourceData?.effectiveYaml && (
+        <div className="space-y-2">
+          <h2 className="text-lg font-semibold">Effective Rule</h2>
+          <p className="text-sm text-muted-foreground">
+            This rule as it runs, with everything it inherits resolved.
+          </p>
+          <YamlEditor
+            value={resourceData.effectiveYaml}
+            onChange={() => {}}
+            readOnly
+            height="300px"
+          />
+        </div>
+      )}
+
       <ShareRuleDialog
         open={isShareOpen}
         onOpenChange={setIsShareOpen}
@@ -3,16 +3,21 @@ import { Link } from '@tanstack/react-router'
 import { Card, CardContent } from '@/components/ui/card'
 import { Button } from '@/components/ui/button'
 import { useFileTree } from '@/queries/use-file-tree'
-import { useCreateDirectory } from '@/mutations/use-gitops-mutations'
-import { FileText, Plus, FolderPlus, Loader2 } from 'lucide-react'
+import { useConfigValidation } from '@/queries/use-config-validation'
+import { useCreateDirectory, useRunRuleTests } from '@/mutations/use-gitops-mutations'
+import { FileText, Plus, FolderPlus, Loader2, FlaskConical } from 'lucide-react'
 import { FolderTreeView } from '@/components/organization/FolderTreeView'
 import { CreateFolderDialog } from '@/components/organization/CreateFolderDialog'
+import { RuleTestResults } from '@/components/rules/RuleTestResults'
+import { RuleWarnings } from '@/components/rules/RuleWarnings'
 import { useToast } from '@/components/ui/use-toast'
 import type { FileTreeNode } from '@/types/gitops'
 
 export function RulesPage() {
   const { data: fileTree, isLoading: isTreeLoading } = useFileTree()
   const createDirectoryMut = useCreateDirectory()
+  const runTestsMut = useRunRuleTests()
+  const { data: validation } = useConfigValidation()
   const { toast } = useToast()
   const [showFolderDialog, setShowFolderDialog] = useState(false)
 
@@ -46,6 +51,18 @@ export function RulesPage() {
     }
   }
 
+  const handleRunTests = async () => {
+    try {
+      await runTestsMut.mutateAsync({})
+    } catch (err) {
+      toast({
+        title: 'Failed to run rule tests',
+        description: err instanceof Error ? err.message : 'Unknown error',
+        variant: 'destructive',
+      })
+    }
+  }
+
   return (
     <div className="space-y-6">
       <div className="flex flex-col gap-4 sm:flex-row sm:items-center sm:justify-between">
@@ -59,6 +76,14 @@ export function RulesPage() {
           </div>
         </div>
         <div className="flex gap-2">
+          <Button variant="outline" onClick={handleRunTests} disabled={runTestsMut.isPending}>
+            {runTestsMut.isPending ? (
+              <Loader2 className="h-4 w-4 animate-spin sm:mr-2" />
+            ) : (
+              <FlaskConical className="h-4 w-4 sm:mr-2" />
+            )}
+            <span className="hidden sm:inline">Run Tests</span>
+          </Button>
           <Button variant="outline" onClick={() => setShowFolderDialog(true)}>
             <FolderPlus className="h-4 w-4 sm:mr-2" />
             <span className="hidden sm:inline">New Folder</span>
@@ -72,6 +97,10 @@ export function RulesPage() {
         </div>
       </div>
 
+      {validation && <RuleWarnings diagnostics={validation.diagnostics} />}
+
+      {runTestsMut.data && <RuleTestResults report={runTestsMut.data} />}
+
       {isTreeLoading ? (
         <div className="flex justify-center py-12">
           <Loader2 className="h-6 w-6 animate-spin" />
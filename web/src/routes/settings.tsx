@@ -78,6 +78,15 @@ export function SettingsPage() {
           ...defaults,
           ...parsed.spec,
           ocr: { ...defaults.ocr, ...parsed.spec.ocr },
+          limits: { ...defaults.limits, ...parsed.spec.limits },
+          categorization: {
+            ...defaults.categorization,
+            ...parsed.spec.categorization,
+            classifier: {
+              ...defaults.categorization.classifier,
+              ...parsed.spec.categorization?.classifier,
+            },
+          },
           defaults: {
             ...defaults.defaults,
             output: { ...defaults.defaults.output, ...parsed.spec.defaults?.output },
@@ -2,11 +2,15 @@ import { describe, it, expect } from 'vitest'
 import {
   outputSettingsSchema,
   ocrSettingsSchema,
+  classifierSettingsSchema,
   gitAuthSettingsSchema,
   gitSettingsSchema,
   settingsSpecSchema,
   variableSpecSchema,
   ruleSpecSchema,
+  ruleTemplateResourceSchema,
+  correspondentResourceSchema,
+  lookupTableResourceSchema,
   matchConditionSchema,
   simpleMatchSchema,
   objectMetaSchema,
@@ -20,6 +24,7 @@ import {
   fileFiltersSchema,
   attachmentFiltersSchema,
   importSourceResourceSchema,
+  ruleTestCaseSchema,
   releaseChannelSchema,
   symlinkSettingsSchema,
   createDefaultSettingsSpec,
@@ -132,6 +137,29 @@ describe('ocrSettingsSchema', () => {
   })
 })
 
+// ============================================
+// classifierSettingsSchema
+// ============================================
+
+describe('classifierSettingsSchema', () => {
+  it('fills in defaults', () => {
+    expect(classifierSettingsSchema.parse({})).toEqual({
+      enabled: true,
+      autoFile: false,
+      threshold: 0.9,
+      minExamples: 5,
+    })
+  })
+
+  it('rejects a threshold above 1', () => {
+    expect(classifierSettingsSchema.safeParse({ threshold: 1.5 }).success).toBe(false)
+  })
+
+  it('rejects fewer than one example', () => {
+    expect(classifierSettingsSchema.safeParse({ minExamples: 0 }).success).toBe(false)
+  })
+})
+
 // ============================================
 // gitAuthSettingsSchema
 // ============================================
@@ -219,6 +247,21 @@ describe('matchConditionSchema', () => {
     expect(matchConditionSchema.safeParse({ pattern: '\\d+' }).success).toBe(true)
   })
 
+  it('accepts { variable } comparisons', () => {
+    expect(matchConditionSchema.safeParse({ variable: { name: 'amount', gt: 1000 } }).success).toBe(true)
+    expect(matchConditionSchema.safeParse({ variable: { name: 'date', between: ['2019-01-01', '2019-12-31'] } }).success).toBe(true)
+    expect(matchConditionSchema.safeParse({ variable: { name: 'iban', exists: false } }).success).toBe(true)
+  })
+
+  it('accepts { correspondent } and rejects an empty name', () => {
+    expect(matchConditionSchema.safeParse({ correspondent: 'helsana' }).success).toBe(true)
+    expect(matchConditionSchema.safeParse({ correspondent: '' }).success).toBe(false)
+  })
+
+  it('rejects { variable } without a name', () => {
+    expect(matchConditionSchema.safeParse({ variable: { name: '', eq: 1 } }).success).toBe(false)
+  })
+
   it('accepts nested { all } with children', () => {
     const cond = {
       all: [
@@ -266,6 +309,16 @@ describe('matchConditionSchema', () => {
   it('rejects empty object', () => {
     expect(matchConditionSchema.safeParse({}).success).toBe(false)
   })
+
+  it('accepts pages and region scopes', () => {
+    expect(matchConditionSchema.safeParse({ contains: 'Acme', pages: 'first', region: { top: 10 } }).success).toBe(true)
+    expect(matchConditionSchema.safeParse({ any: [{ contains: 'Total' }], pages: [1, 2] }).success).toBe(true)
+  })
+
+  it('rejects page 0 and empty regions', () => {
+    expect(matchConditionSchema.safeParse({ contains: 'Acme', pages: [0] }).success).toBe(false)
+    expect(matchConditionSchema.safeParse({ contains: 'Acme', region: { bottom: 0 } }).success).toBe(false)
+  })
 })
 
 // ============================================
@@ -288,6 +341,10 @@ describe('simpleMatchSchema', () => {
   it('allows caseSensitive alongside a single match type', () => {
     expect(simpleMatchSchema.safeParse({ contains: 'test', caseSensitive: true }).success).toBe(true)
   })
+
+  it('allows pages and region alongside a single match type', () => {
+    expect(simpleMatchSchema.safeParse({ contains: 'test', pages: 'last', region: { bottom: 5 } }).success).toBe(true)
+  })
 })
 
 // ============================================
@@ -320,6 +377,124 @@ describe('ruleSpecSchema', () => {
   it('rejects empty category', () => {
     expect(ruleSpecSchema.safeParse({ ...validRule, category: '' }).success).toBe(false)
   })
+
+  it('defaults exclusive to true and requires output', () => {
+    expect(ruleSpecSchema.parse(validRule).exclusive).toBe(true)
+    const noOutput = { category: 'tax', match: { contains: 'Steuer' } }
+    expect(ruleSpecSchema.safeParse(noOutput).success).toBe(false)
+  })
+
+  it('accepts non-exclusive rule without output', () => {
+    const tagRule = { category: 'tax', exclusive: false, match: { contains: 'Steuer' } }
+    expect(ruleSpecSchema.safeParse(tagRule).success).toBe(true)
+  })
+
+  it('requires a match condition unless the rule extends another', () => {
+    const noMatch = { category: validRule.category, output: validRule.output }
+    expect(ruleSpecSchema.safeParse(noMatch).success).toBe(false)
+    expect(ruleSpecSchema.safeParse({ ...noMatch, extends: 'vendor' }).success).toBe(true)
+  })
+
+  it('lets an extending rule inherit category and output', () => {
+    const child = { extends: 'vendor', match: { contains: 'ACME' } }
+    const result = ruleSpecSchema.safeParse(child)
+    expect(result.success).toBe(true)
+    expect(result.data?.category).toBe('')
+  })
+})
+
+// ============================================
+// ruleTemplateResourceSchema
+// ============================================
+
+describe('ruleTemplateResourceSchema', () => {
+  it('accepts a template with only shared output and symlinks', () => {
+    const result = ruleTemplateResourceSchema.safeParse({
+      apiVersion: 'paporg.io/v1',
+      kind: 'RuleTemplate',
+      metadata: { name: 'vendor' },
+      spec: {
+        output: { directory: 'vendors/$y' },
+        symlinks: [{ target: 'by-year/$y' }],
+      },
+    })
+    expect(result.success).toBe(true)
+  })
+
+  it('rejects an empty symlink target', () => {
+    const result = ruleTemplateResourceSchema.safeParse({
+      apiVersion: 'paporg.io/v1',
+      kind: 'RuleTemplate',
+      metadata: { name: 'vendor' },
+      spec: { symlinks: [{ target: '' }] },
+    })
+    expect(result.success).toBe(false)
+  })
+})
+
+// ============================================
+// correspondentResourceSchema
+// ============================================
+
+describe('correspondentResourceSchema', () => {
+  const correspondent = (spec: Record<string, unknown>) => ({
+    apiVersion: 'paporg.io/v1',
+    kind: 'Correspondent',
+    metadata: { name: 'helsana' },
+    spec,
+  })
+
+  it('accepts identifiers in their printed form', () => {
+    const result = correspondentResourceSchema.safeParse(correspondent({
+      displayName: 'Helsana',
+      aliases: ['Helsana Versicherungen AG'],
+      ibans: ['CH93 0076 2011 6238 5295 7'],
+      vatNumbers: ['CHE-105.953.932'],
+      emailDomains: ['helsana.ch'],
+      defaultCategory: 'insurance',
+    }))
+    expect(result.success).toBe(true)
+  })
+
+  it('rejects malformed IBANs and email domains', () => {
+    expect(correspondentResourceSchema.safeParse(correspondent({ ibans: ['12345'] })).success).toBe(false)
+    expect(
+      correspondentResourceSchema.safeParse(correspondent({ emailDomains: ['billing@helsana.ch'] })).success
+    ).toBe(false)
+  })
+})
+
+// ============================================
+// lookupTableResourceSchema
+// ============================================
+
+describe('lookupTableResourceSchema', () => {
+  const table = (spec: Record<string, unknown>) => ({
+    apiVersion: 'paporg.io/v1',
+    kind: 'LookupTable',
+    metadata: { name: 'vendors' },
+    spec,
+  })
+
+  it('accepts inline entries or a CSV file', () => {
+    expect(lookupTableResourceSchema.safeParse(table({
+      entries: [{ key: 'SBB CFF FFS', value: 'SBB' }],
+      fallback: 'other',
+    })).success).toBe(true)
+    expect(lookupTableResourceSchema.safeParse(table({ file: 'lookups/vendors.csv' })).success).toBe(true)
+  })
+
+  it('requires entries or a file', () => {
+    expect(lookupTableResourceSchema.safeParse(table({ mode: 'exact' })).success).toBe(false)
+  })
+
+  it('rejects invalid regex keys and files outside the config directory', () => {
+    expect(lookupTableResourceSchema.safeParse(table({
+      mode: 'regex',
+      entries: [{ key: '(unclosed', value: 'x' }],
+    })).success).toBe(false)
+    expect(lookupTableResourceSchema.safeParse(table({ file: '../vendors.csv' })).success).toBe(false)
+  })
 })
 
 // ============================================
@@ -382,6 +557,38 @@ describe('variableResourceSchema', () => {
     }
     expect(variableResourceSchema.safeParse(resource).success).toBe(true)
   })
+
+  it('accepts a source other than the text', () => {
+    const resource = {
+      apiVersion: 'paporg.io/v1',
+      kind: 'Variable',
+      metadata: { name: 'policy' },
+      spec: { pattern: '^(?P<policy>\\d+)_', from: 'filename' },
+    }
+    expect(variableResourceSchema.safeParse(resource).success).toBe(true)
+    expect(variableResourceSchema.safeParse({
+      ...resource,
+      spec: { ...resource.spec, from: 'subject' },
+    }).success).toBe(false)
+  })
+
+  it('accepts computed variables without a pattern', () => {
+    expect(variableSpecSchema.safeParse({ template: '$doc_y-Q$quarter' }).success).toBe(true)
+    expect(variableSpecSchema.safeParse({
+      template: '$vendor',
+      pattern: '^(?P<vendor_short>\\S+)',
+    }).success).toBe(true)
+    expect(variableSpecSchema.safeParse({}).success).toBe(false)
+    expect(variableSpecSchema.safeParse({ template: '$vendor', from: 'filename' }).success).toBe(false)
+  })
+
+  it('rejects page scopes on variables not from text', () => {
+    expect(variableSpecSchema.safeParse({
+      pattern: 'Author: (?P<author>.+)',
+      from: 'pdfMetadata',
+      pages: 'first',
+    }).success).toBe(false)
+  })
 })
 
 describe('ruleResourceSchema', () => {
@@ -658,6 +865,11 @@ describe('variableSpecSchema', () => {
   it('rejects invalid transform', () => {
     expect(variableSpecSchema.safeParse({ pattern: '\\w+', transform: 'invalid' }).success).toBe(false)
   })
+
+  it('accepts a lookup transform naming a table', () => {
+    expect(variableSpecSchema.safeParse({ pattern: '\\w+', transform: { lookup: 'vendors' } }).success).toBe(true)
+    expect(variableSpecSchema.safeParse({ pattern: '\\w+', transform: { lookup: '' } }).success).toBe(false)
+  })
 })
 
 // ============================================
@@ -872,3 +1084,37 @@ describe('validateRegexPattern', () => {
     expect(validateRegexPattern('(?P<month>\\d{2})-(?P<day>\\d{2})-(?P<year>\\d{4})')).toEqual({ valid: true })
   })
 })
+
+// ============================================
+// RuleTest
+// ============================================
+
+describe('ruleTestCaseSchema', () => {
+  it('accepts a text case with an expected category', () => {
+    const result = ruleTestCaseSchema.safeParse({
+      name: 'invoice',
+      text: 'Invoice from ACME',
+      expect: { category: 'invoices' },
+    })
+    expect(result.success).toBe(true)
+  })
+
+  it('rejects a case with both text and file', () => {
+    const result = ruleTestCaseSchema.safeParse({
+      name: 'invoice',
+      text: 'Invoice',
+      file: 'tests/fixtures/invoice.pdf',
+      expect: { category: 'invoices' },
+    })
+    expect(result.success).toBe(false)
+  })
+
+  it('rejects a case without expectations', () => {
+    const result = ruleTestCaseSchema.safeParse({
+      name: 'invoice',
+      text: 'Invoice',
+      expect: {},
+    })
+    expect(result.success).toBe(false)
+  })
+})
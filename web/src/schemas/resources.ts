@@ -23,6 +23,36 @@ export const ocrSettingsSchema = z.object({
 
 export type OcrSettings = z.infer<typeof ocrSettingsSchema>
 
+export const limitsSettingsSchema = z.object({
+  maxFileSizeMb: z.number().min(0).default(100),
+  maxPages: z.number().min(0).default(500),
+  jobTimeoutSecs: z.number().min(0).default(900),
+  ocrPageTimeoutSecs: z.number().min(0).default(120),
+  maxArchiveEntries: z.number().min(0).default(1000),
+  maxArchiveSizeMb: z.number().min(0).default(500),
+  maxArchiveDepth: z.number().min(0).default(3),
+})
+
+export type LimitsSettings = z.infer<typeof limitsSettingsSchema>
+
+export const classifierSettingsSchema = z.object({
+  enabled: z.boolean().default(true),
+  autoFile: z.boolean().default(false),
+  threshold: z.number().min(0).max(1).default(0.9),
+  minExamples: z.number().int().min(1).default(5),
+})
+
+export type ClassifierSettings = z.infer<typeof classifierSettingsSchema>
+
+export const categorizationSettingsSchema = z.object({
+  mode: z.enum(['firstMatch', 'scoring']).default('firstMatch'),
+  threshold: z.number().min(0).max(1).default(0.5),
+  alternatives: z.number().int().min(0).default(3),
+  classifier: classifierSettingsSchema.default({}),
+})
+
+export type CategorizationSettings = z.infer<typeof categorizationSettingsSchema>
+
 export const gitAuthSettingsSchema = z.object({
   type: z.enum(['none', 'token', 'ssh-key']),
   tokenEnvVar: z.string().optional().default(''),
@@ -62,9 +92,12 @@ export const settingsSpecSchema = z.object({
   outputDirectory: z.string().min(1, 'Output directory is required'),
   workerCount: z.number().min(1).max(32).default(4),
   ocr: ocrSettingsSchema,
+  limits: limitsSettingsSchema.default({}),
+  categorization: categorizationSettingsSchema.default({}),
   defaults: defaultOutputSettingsSchema,
   git: gitSettingsSchema,
   releaseChannel: releaseChannelSchema,
+  overlay: z.string().optional(),
 })
 
 export type SettingsSpec = z.infer<typeof settingsSpecSchema>
@@ -73,18 +106,65 @@ export type SettingsSpec = z.infer<typeof settingsSpecSchema>
 // Variable Resource Schema
 // ============================================
 
-export const variableTransformSchema = z.enum(['slugify', 'uppercase', 'lowercase', 'trim']).optional()
+export const variableTransformSchema = z.union([
+  z.enum(['slugify', 'uppercase', 'lowercase', 'trim']),
+  z.object({ lookup: z.string().min(1, 'Lookup table is required') }).strict(),
+]).optional()
 
 export type VariableTransform = z.infer<typeof variableTransformSchema>
 
+export const pageSelectionSchema = z.union([
+  z.enum(['first', 'last']),
+  z.array(z.number().int().min(1, 'Pages are numbered from 1')).min(1, 'List at least one page'),
+])
+
+export type PageSelection = z.infer<typeof pageSelectionSchema>
+
+export const pageRegionSchema = z.union([
+  z.object({ top: z.number().int().min(1) }).strict(),
+  z.object({ bottom: z.number().int().min(1) }).strict(),
+])
+
+export type PageRegion = z.infer<typeof pageRegionSchema>
+
+// Limits a condition or variable pattern to pages of the extracted text and lines of each page
+const pageScopeShape = {
+  pages: pageSelectionSchema.optional(),
+  region: pageRegionSchema.optional(),
+}
+
+export const variableSourceSchema = z.enum([
+  'text',
+  'filename',
+  'sourcePath',
+  'email.subject',
+  'email.from',
+  'pdfMetadata',
+])
+
+export type VariableSource = z.infer<typeof variableSourceSchema>
+
 export const variableSpecSchema = z.object({
-  pattern: z.string().min(1, 'Pattern is required').refine(
-    (val) => validateRegexPattern(val).valid,
+  // Optional for computed variables, where it searches the computed text
+  pattern: z.string().refine(
+    (val) => !val || validateRegexPattern(val).valid,
     { message: 'Invalid regex pattern' }
-  ),
+  ).optional(),
+  template: z.string().min(1, 'Template is required').optional(),
   transform: variableTransformSchema,
   default: z.string().optional(),
-})
+  ...pageScopeShape,
+  from: variableSourceSchema.optional(),
+}).refine(
+  (spec) => spec.template !== undefined || !!spec.pattern,
+  { message: 'Pattern is required', path: ['pattern'] }
+).refine(
+  (spec) => !spec.from || spec.from === 'text' || (!spec.pages && !spec.region),
+  { message: 'Pages and region only apply to variables from text', path: ['from'] }
+).refine(
+  (spec) => spec.template === undefined || (!spec.from && !spec.pages && !spec.region),
+  { message: 'Search input, pages and region do not apply to computed variables', path: ['template'] }
+)
 
 export type VariableSpec = z.infer<typeof variableSpecSchema>
 
@@ -92,16 +172,53 @@ export type VariableSpec = z.infer<typeof variableSpecSchema>
 // Rule Resource Schema (with recursive match)
 // ============================================
 
+export const matchFieldSchema = z.enum([
+  'text',
+  'filename',
+  'source',
+  'mimeType',
+  'email.from',
+  'email.subject',
+  'email.to',
+  'firstPage',
+])
+
+export type MatchField = z.infer<typeof matchFieldSchema>
+
+export const compareValueSchema = z.union([z.number(), z.string()])
+
+export const variableMatchSchema = z.object({
+  name: z.string().min(1, 'Variable name is required'),
+  eq: compareValueSchema.optional(),
+  gt: compareValueSchema.optional(),
+  lt: compareValueSchema.optional(),
+  between: z.tuple([compareValueSchema, compareValueSchema]).optional(),
+  exists: z.boolean().optional(),
+  in: z.array(compareValueSchema).min(1).optional(),
+})
+
+export type VariableMatch = z.infer<typeof variableMatchSchema>
+
 // Base match condition types
 export const simpleMatchSchema = z.object({
   contains: z.string().optional(),
   containsAny: z.array(z.string()).optional(),
   containsAll: z.array(z.string()).optional(),
   pattern: z.string().optional(),
+  fuzzy: z.object({ value: z.string(), maxDistance: z.number().int().min(0).optional() }).optional(),
+  containsAnyFuzzy: z.array(z.string()).optional(),
+  variable: variableMatchSchema.optional(),
+  correspondent: z.string().min(1).optional(),
   caseSensitive: z.boolean().optional(),
+  field: matchFieldSchema.optional(),
+  ...pageScopeShape,
+  weight: z.number().min(0).optional(),
 }).refine(
   (data) => {
-    const keys = Object.keys(data).filter(k => k !== 'caseSensitive' && data[k as keyof typeof data] !== undefined)
+    const settings = ['caseSensitive', 'field', 'pages', 'region', 'weight']
+    const keys = Object.keys(data).filter(
+      k => !settings.includes(k) && data[k as keyof typeof data] !== undefined
+    )
     return keys.length === 1
   },
   { message: 'Exactly one match type must be specified' }
@@ -109,23 +226,37 @@ export const simpleMatchSchema = z.object({
 
 // Recursive match condition schema using z.lazy
 export type MatchCondition =
-  | { contains: string; caseSensitive?: boolean }
-  | { containsAny: string[]; caseSensitive?: boolean }
-  | { containsAll: string[]; caseSensitive?: boolean }
-  | { pattern: string; caseSensitive?: boolean }
-  | { all: MatchCondition[]; caseSensitive?: boolean }
-  | { any: MatchCondition[]; caseSensitive?: boolean }
-  | { not: MatchCondition; caseSensitive?: boolean }
+  | { contains: string; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
+  | { containsAny: string[]; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
+  | { containsAll: string[]; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
+  | { pattern: string; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
+  | { fuzzy: { value: string; maxDistance?: number }; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
+  | { containsAnyFuzzy: string[]; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
+  | { variable: VariableMatch; caseSensitive?: boolean; weight?: number }
+  | { correspondent: string; weight?: number }
+  | { all: MatchCondition[]; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
+  | { any: MatchCondition[]; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
+  | { not: MatchCondition; caseSensitive?: boolean; field?: MatchField; pages?: PageSelection; region?: PageRegion; weight?: number }
 
 export const matchConditionSchema: z.ZodType<MatchCondition> = z.lazy(() =>
   z.union([
-    z.object({ contains: z.string(), caseSensitive: z.boolean().optional() }),
-    z.object({ containsAny: z.array(z.string()).min(1), caseSensitive: z.boolean().optional() }),
-    z.object({ containsAll: z.array(z.string()).min(1), caseSensitive: z.boolean().optional() }),
-    z.object({ pattern: z.string(), caseSensitive: z.boolean().optional() }),
-    z.object({ all: z.array(matchConditionSchema).min(1), caseSensitive: z.boolean().optional() }),
-    z.object({ any: z.array(matchConditionSchema).min(1), caseSensitive: z.boolean().optional() }),
-    z.object({ not: matchConditionSchema, caseSensitive: z.boolean().optional() }),
+    z.object({ contains: z.string(), caseSensitive: z.boolean().optional(), field: matchFieldSchema.optional(), ...pageScopeShape, weight: z.number().min(0).optional() }),
+    z.object({ containsAny: z.array(z.string()).min(1), caseSensitive: z.boolean().optional(), field: matchFieldSchema.optional(), ...pageScopeShape, weight: z.number().min(0).optional() }),
+    z.object({ containsAll: z.array(z.string()).min(1), caseSensitive: z.boolean().optional(), field: matchFieldSchema.optional(), ...pageScopeShape, weight: z.number().min(0).optional() }),
+    z.object({ pattern: z.string(), caseSensitive: z.boolean().optional(), field: matchFieldSchema.optional(), ...pageScopeShape, weight: z.number().min(0).optional() }),
+    z.object({
+      fuzzy: z.object({ value: z.string().min(1), maxDistance: z.number().int().min(0).optional() }),
+      caseSensitive: z.boolean().optional(),
+      field: matchFieldSchema.optional(),
+      ...pageScopeShape,
+      weight: z.number().min(0).optional(),
+    }),
+    z.object({ containsAnyFuzzy: z.array(z.string()).min(1), caseSensitive: z.boolean().optional(), field: matchFieldSchema.optional(), ...pageScopeShape, weight: z.number().min(0).optional() }),
+    z.object({ variable: variableMatchSchema, caseSensitive: z.boolean().optional(), weight: z.number().min(0).optional() }),
+    z.object({ correspondent: z.string().min(1, 'Correspondent is required'), weight: z.number().min(0).optional() }),
+    z.object({ all: z.array(matchConditionSchema).min(1), caseSensitive: z.boolean().optional(), field: matchFieldSchema.optional(), ...pageScopeShape, weight: z.number().min(0).optional() }),
+    z.object({ any: z.array(matchConditionSchema).min(1), caseSensitive: z.boolean().optional(), field: matchFieldSchema.optional(), ...pageScopeShape, weight: z.number().min(0).optional() }),
+    z.object({ not: matchConditionSchema, caseSensitive: z.boolean().optional(), field: matchFieldSchema.optional(), ...pageScopeShape, weight: z.number().min(0).optional() }),
   ])
 )
 
@@ -137,10 +268,54 @@ export type SymlinkSettings = z.infer<typeof symlinkSettingsSchema>
 
 export const ruleSpecSchema = z.object({
   priority: z.number().int().default(0),
-  category: z.string().min(1, 'Category is required'),
-  match: matchConditionSchema,
-  output: outputSettingsSchema,
+  // Rule or template to inherit category, match, output and symlinks from
+  extends: z.string().min(1, 'Name of a rule or template is required').optional(),
+  category: z.string().default(''),
+  // Non-exclusive rules add their category as a tag instead of competing for it
+  exclusive: z.boolean().default(true),
+  match: matchConditionSchema.optional(),
+  // Only exclusive rules decide where the document is stored
+  output: z.object({
+    directory: z.string().default(''),
+    filename: z.string().default(''),
+  }).default({}),
   symlinks: z.array(symlinkSettingsSchema).optional().default([]),
+}).superRefine((data, ctx) => {
+  // Anything left out may be inherited; the loader reports what is still missing
+  if (data.extends !== undefined) {
+    return
+  }
+  if (data.category.length === 0) {
+    ctx.addIssue({
+      code: z.ZodIssueCode.custom,
+      message: 'Category is required',
+      path: ['category'],
+    })
+  }
+  if (data.match === undefined) {
+    ctx.addIssue({
+      code: z.ZodIssueCode.custom,
+      message: 'Match condition is required',
+      path: ['match'],
+    })
+  }
+  if (!data.exclusive) {
+    return
+  }
+  if (data.output.directory.length === 0) {
+    ctx.addIssue({
+      code: z.ZodIssueCode.custom,
+      message: 'Directory is required',
+      path: ['output', 'directory'],
+    })
+  }
+  if (data.output.filename.length === 0) {
+    ctx.addIssue({
+      code: z.ZodIssueCode.custom,
+      message: 'Filename is required',
+      path: ['output', 'filename'],
+    })
+  }
 })
 
 export type RuleSpec = z.infer<typeof ruleSpecSchema>
@@ -347,6 +522,148 @@ export const importSourceResourceSchema = z.object({
 
 export type ImportSourceResource = z.infer<typeof importSourceResourceSchema>
 
+// ============================================
+// RuleTest Resource Schema
+// ============================================
+
+export const ruleTestExpectationSchema = z.object({
+  category: z.string().optional(),
+  rule: z.string().optional(),
+  output: z.string().optional(),
+}).refine(
+  (data) => data.category !== undefined || data.rule !== undefined || data.output !== undefined,
+  { message: 'Expect a category, rule or output' }
+)
+
+export type RuleTestExpectation = z.infer<typeof ruleTestExpectationSchema>
+
+export const ruleTestCaseSchema = z.object({
+  name: z.string().min(1, 'Case name is required'),
+  text: z.string().optional(),
+  file: z.string().optional(),
+  filename: z.string().optional(),
+  source: z.string().optional(),
+  expect: ruleTestExpectationSchema,
+}).refine(
+  (data) => (data.text !== undefined) !== (data.file !== undefined),
+  { message: 'Set exactly one of text and file' }
+)
+
+export type RuleTestCase = z.infer<typeof ruleTestCaseSchema>
+
+export const ruleTestSpecSchema = z.object({
+  cases: z.array(ruleTestCaseSchema).min(1, 'At least one case is required'),
+})
+
+export type RuleTestSpec = z.infer<typeof ruleTestSpecSchema>
+
+export const ruleTestResourceSchema = z.object({
+  apiVersion: z.literal('paporg.io/v1'),
+  kind: z.literal('RuleTest'),
+  metadata: objectMetaSchema,
+  spec: ruleTestSpecSchema,
+})
+
+export type RuleTestResource = z.infer<typeof ruleTestResourceSchema>
+
+// ============================================
+// RuleTemplate Resource Schema
+// ============================================
+
+export const ruleTemplateSpecSchema = z.object({
+  extends: z.string().min(1, 'Name of a rule or template is required').optional(),
+  category: z.string().optional(),
+  match: matchConditionSchema.optional(),
+  output: z.object({
+    directory: z.string().optional(),
+    filename: z.string().optional(),
+  }).optional(),
+  symlinks: z.array(symlinkSettingsSchema).optional(),
+})
+
+export type RuleTemplateSpec = z.infer<typeof ruleTemplateSpecSchema>
+
+export const ruleTemplateResourceSchema = z.object({
+  apiVersion: z.literal('paporg.io/v1'),
+  kind: z.literal('RuleTemplate'),
+  metadata: objectMetaSchema,
+  spec: ruleTemplateSpecSchema,
+})
+
+export type RuleTemplateResource = z.infer<typeof ruleTemplateResourceSchema>
+
+// ============================================
+// Correspondent Resource Schema
+// ============================================
+
+export const correspondentSpecSchema = z.object({
+  displayName: z.string().optional(),
+  aliases: z.array(z.string().min(1, 'Alias must not be empty')).optional(),
+  ibans: z.array(
+    z.string().refine(
+      v => /^[A-Z]{2}[0-9]{2}[A-Z0-9]{11,30}$/.test(v.replace(/\s/g, '').toUpperCase()),
+      { message: 'Invalid IBAN' }
+    )
+  ).optional(),
+  vatNumbers: z.array(z.string().regex(/[A-Za-z0-9]/, 'VAT number must not be empty')).optional(),
+  emailDomains: z.array(
+    z.string().regex(/^@?[^@\s]+\.[^@\s]+$/, 'Invalid email domain')
+  ).optional(),
+  defaultCategory: z.string().min(1, 'Default category must not be empty').optional(),
+})
+
+export type CorrespondentSpec = z.infer<typeof correspondentSpecSchema>
+
+export const correspondentResourceSchema = z.object({
+  apiVersion: z.literal('paporg.io/v1'),
+  kind: z.literal('Correspondent'),
+  metadata: objectMetaSchema,
+  spec: correspondentSpecSchema,
+})
+
+export type CorrespondentResource = z.infer<typeof correspondentResourceSchema>
+
+// ============================================
+// LookupTable Resource Schema
+// ============================================
+
+export const lookupEntrySchema = z.object({
+  key: z.string().trim().min(1, 'Key is required'),
+  value: z.string(),
+})
+
+export const lookupTableSpecSchema = z.object({
+  mode: z.enum(['exact', 'regex']).optional(),
+  entries: z.array(lookupEntrySchema).optional(),
+  file: z.string().min(1, 'File must not be empty').refine(
+    v => !v.startsWith('/') && !v.split(/[\\/]/).includes('..'),
+    { message: 'File must be a relative path inside the config directory' }
+  ).optional(),
+  fallback: z.string().trim().min(1, 'Fallback must not be empty').optional(),
+}).superRefine((spec, ctx) => {
+  if (!spec.entries?.length && !spec.file) {
+    ctx.addIssue({ code: z.ZodIssueCode.custom, message: 'Add at least one entry or a file', path: ['entries'] })
+  }
+  if (spec.mode === 'regex') {
+    spec.entries?.forEach((entry, i) => {
+      if (!validateRegexPattern(entry.key).valid) {
+        ctx.addIssue({ code: z.ZodIssueCode.custom, message: 'Invalid regex pattern', path: ['entries', i, 'key'] })
+      }
+    })
+  }
+})
+
+export type LookupTableSpec = z.infer<typeof lookupTableSpecSchema>
+
+export const lookupTableResourceSchema = z.object({
+  apiVersion: z.literal('paporg.io/v1'),
+  kind: z.literal('LookupTable'),
+  metadata: objectMetaSchema,
+  spec: lookupTableSpecSchema,
+})
+
+export type LookupTableResource = z.infer<typeof lookupTableResourceSchema>
+
 // ============================================
 // Helper functions for creating defaults
 // ============================================
@@ -361,6 +678,26 @@ export function createDefaultSettingsSpec(): SettingsSpec {
       languages: ['eng'],
       dpi: 300,
     },
+    limits: {
+      maxFileSizeMb: 100,
+      maxPages: 500,
+      jobTimeoutSecs: 900,
+      ocrPageTimeoutSecs: 120,
+      maxArchiveEntries: 1000,
+      maxArchiveSizeMb: 500,
+      maxArchiveDepth: 3,
+    },
+    categorization: {
+      mode: 'firstMatch',
+      threshold: 0.5,
+      alternatives: 3,
+      classifier: {
+        enabled: true,
+        autoFile: false,
+        threshold: 0.9,
+        minExamples: 5,
+      },
+    },
     defaults: {
       output: {
         directory: '$y/unsorted',
@@ -396,6 +733,7 @@ export function createDefaultRuleSpec(): RuleSpec {
   return {
     priority: 0,
     category: '',
+    exclusive: true,
     match: { contains: '' },
     output: {
       directory: '',
@@ -461,13 +799,28 @@ export function createDefaultMatchCondition(): MatchCondition {
 // Match condition type helpers
 // ============================================
 
-export type MatchConditionType = 'contains' | 'containsAny' | 'containsAll' | 'pattern' | 'all' | 'any' | 'not'
+export type MatchConditionType =
+  | 'contains'
+  | 'containsAny'
+  | 'containsAll'
+  | 'pattern'
+  | 'fuzzy'
+  | 'containsAnyFuzzy'
+  | 'variable'
+  | 'correspondent'
+  | 'all'
+  | 'any'
+  | 'not'
 
 export function getMatchConditionType(condition: MatchCondition): MatchConditionType {
   if ('contains' in condition) return 'contains'
   if ('containsAny' in condition) return 'containsAny'
   if ('containsAll' in condition) return 'containsAll'
   if ('pattern' in condition) return 'pattern'
+  if ('fuzzy' in condition) return 'fuzzy'
+  if ('containsAnyFuzzy' in condition) return 'containsAnyFuzzy'
+  if ('variable' in condition) return 'variable'
+  if ('correspondent' in condition) return 'correspondent'
   if ('all' in condition) return 'all'
   if ('any' in condition) return 'any'
   if ('not' in condition) return 'not'
@@ -484,6 +837,14 @@ export function createMatchConditionOfType(type: MatchConditionType): MatchCondi
       return { containsAll: [''] }
     case 'pattern':
       return { pattern: '' }
+    case 'fuzzy':
+      return { fuzzy: { value: '' } }
+    case 'containsAnyFuzzy':
+      return { containsAnyFuzzy: [''] }
+    case 'variable':
+      return { variable: { name: '' } }
+    case 'correspondent':
+      return { correspondent: '' }
     case 'all':
       return { all: [{ contains: '' }] }
     case 'any':
@@ -494,7 +855,16 @@ export function createMatchConditionOfType(type: MatchConditionType): MatchCondi
 }
 
 export function isSimpleMatch(condition: MatchCondition): boolean {
-  return 'contains' in condition || 'containsAny' in condition || 'containsAll' in condition || 'pattern' in condition
+  return (
+    'contains' in condition ||
+    'containsAny' in condition ||
+    'containsAll' in condition ||
+    'pattern' in condition ||
+    'fuzzy' in condition ||
+    'containsAnyFuzzy' in condition ||
+    'variable' in condition ||
+    'correspondent' in condition
+  )
 }
 
 export function isCompoundMatch(condition: MatchCondition): boolean {
@@ -2,7 +2,15 @@
 
 export const API_VERSION = 'paporg.io/v1'
 
-export type ResourceKind = 'Settings' | 'Variable' | 'Rule' | 'ImportSource'
+export type ResourceKind =
+  | 'Settings'
+  | 'Variable'
+  | 'Rule'
+  | 'ImportSource'
+  | 'RuleTest'
+  | 'RuleTemplate'
+  | 'Correspondent'
+  | 'LookupTable'
 
 export interface ObjectMeta {
   name: string
@@ -26,9 +34,13 @@ export interface SettingsSpec {
   outputDirectory: string
   workerCount: number
   ocr: OcrSettings
+  limits?: LimitsSettings
+  categorization?: CategorizationSettings
   defaults: DefaultOutputSettings
   git: GitSettings
   releaseChannel?: ReleaseChannel
+  /** Overlay of the overlays directory to apply; `$hostname` is the machine name. */
+  overlay?: string
 }
 
 export interface OcrSettings {
@@ -37,6 +49,39 @@ export interface OcrSettings {
   dpi: number
 }
 
+/** Per-document resource limits. 0 disables a limit. */
+export interface LimitsSettings {
+  maxFileSizeMb: number
+  maxPages: number
+  jobTimeoutSecs: number
+  ocrPageTimeoutSecs: number
+  maxArchiveEntries: number
+  maxArchiveSizeMb: number
+  maxArchiveDepth: number
+}
+
+/** How a rule is chosen for a document. */
+export interface CategorizationSettings {
+  mode: 'firstMatch' | 'scoring'
+  /** Lowest score, between 0 and 1, a rule needs to win in scoring mode. */
+  threshold: number
+  /** Number of runner-up rules reported in scoring mode. */
+  alternatives: number
+  /** Classifier that predicts a category when no rule matches. */
+  classifier: ClassifierSettings
+}
+
+/** Naive Bayes classifier trained on rule-filed and corrected jobs. */
+export interface ClassifierSettings {
+  enabled: boolean
+  /** File documents under the prediction instead of only suggesting it. */
+  autoFile: boolean
+  /** Lowest confidence, between 0 and 1, for auto-filing. */
+  threshold: number
+  /** Fewest training documents a category needs before it is predicted. */
+  minExamples: number
+}
+
 export interface DefaultOutputSettings {
   output: OutputSettings
 }
@@ -59,19 +104,48 @@ export type SettingsResource = Resource<SettingsSpec>
 
 // Variable Resource
 export interface VariableSpec {
-  pattern: string
-  transform?: 'slugify' | 'uppercase' | 'lowercase' | 'trim'
+  /** Required unless the variable is computed from a template. */
+  pattern?: string
+  /**
+   * Computes the value from other variables, e.g. `$doc_y-Q$quarter`,
+   * after extraction. A pattern then searches the computed text.
+   */
+  template?: string
+  /** A named transform, or `{ lookup: table }` to map the value through a LookupTable. */
+  transform?: 'slugify' | 'uppercase' | 'lowercase' | 'trim' | { lookup: string }
   default?: string
+  pages?: PageSelection
+  region?: PageRegion
+  /** Input the pattern is searched in. Default is the text. */
+  from?: VariableSource
 }
 
+export type VariableSource =
+  | 'text'
+  | 'filename'
+  | 'sourcePath'
+  | 'email.subject'
+  | 'email.from'
+  | 'pdfMetadata'
+
 export type VariableResource = Resource<VariableSpec>
 
 // Rule Resource
 export interface RuleSpec {
   priority: number
+  /**
+   * Rule or template to inherit from. Category and output templates left
+   * empty are inherited, symlinks are added and match conditions combined.
+   */
+  extends?: string
+  /** Required unless inherited. */
   category: string
-  match: MatchCondition
-  output: OutputSettings
+  /** When false the rule only tags matching documents. Default is true. */
+  exclusive?: boolean
+  /** Required unless inherited. */
+  match?: MatchCondition
+  /** Required for exclusive rules unless inherited. */
+  output?: OutputSettings
   symlinks?: SymlinkSettings[]
 }
 
@@ -82,12 +156,63 @@ export interface SimpleMatch {
   containsAny?: string[]
   containsAll?: string[]
   pattern?: string
+  fuzzy?: FuzzyMatch
+  containsAnyFuzzy?: string[]
+  variable?: VariableMatch
+  /** Name of the Correspondent resource the document must come from. */
+  correspondent?: string
+  field?: MatchField
+  pages?: PageSelection
+  region?: PageRegion
+  /** Weight among the siblings of an `all` in scoring mode. Default is 1. */
+  weight?: number
+}
+
+export type MatchField =
+  | 'text'
+  | 'filename'
+  | 'source'
+  | 'mimeType'
+  | 'email.from'
+  | 'email.subject'
+  | 'email.to'
+  | 'firstPage'
+
+/** Pages of the extracted text, numbered from 1. */
+export type PageSelection = 'first' | 'last' | number[]
+
+/** The top or bottom lines of each selected page, ignoring blank lines. */
+export type PageRegion = { top: number } | { bottom: number }
+
+export interface FuzzyMatch {
+  value: string
+  maxDistance?: number
+}
+
+/** A number, or text holding a number or a date. */
+export type CompareValue = number | string
+
+/** Compares an extracted variable; every operator that is set must hold. */
+export interface VariableMatch {
+  name: string
+  eq?: CompareValue
+  gt?: CompareValue
+  lt?: CompareValue
+  /** Inclusive range. */
+  between?: [CompareValue, CompareValue]
+  exists?: boolean
+  in?: CompareValue[]
 }
 
 export interface CompoundMatch {
   all?: MatchCondition[]
   any?: MatchCondition[]
   not?: MatchCondition
+  field?: MatchField
+  pages?: PageSelection
+  region?: PageRegion
+  /** Weight among the siblings of an `all` in scoring mode. Default is 1. */
+  weight?: number
 }
 
 export interface OutputSettings {
@@ -167,8 +292,81 @@ export interface AttachmentFilters {
 
 export type ImportSourceResource = Resource<ImportSourceSpec>
 
+// RuleTest Resource
+export interface RuleTestSpec {
+  cases: RuleTestCase[]
+}
+
+export interface RuleTestCase {
+  name: string
+  text?: string
+  file?: string
+  filename?: string
+  source?: string
+  expect: RuleTestExpectation
+}
+
+export interface RuleTestExpectation {
+  category?: string
+  rule?: string
+  output?: string
+}
+
+export type RuleTestResource = Resource<RuleTestSpec>
+
+// RuleTemplate Resource: shared parts of rules, never matched on its own
+export interface RuleTemplateSpec {
+  extends?: string
+  category?: string
+  match?: MatchCondition
+  output?: Partial<OutputSettings>
+  symlinks?: SymlinkSettings[]
+}
+
+export type RuleTemplateResource = Resource<RuleTemplateSpec>
+
+// Correspondent Resource: a known sender, detected by its details and exposed as $correspondent
+export interface CorrespondentSpec {
+  /** Defaults to the resource name. */
+  displayName?: string
+  aliases?: string[]
+  ibans?: string[]
+  vatNumbers?: string[]
+  emailDomains?: string[]
+  /** Category for documents from this correspondent that no rule matches. */
+  defaultCategory?: string
+}
+
+export type CorrespondentResource = Resource<CorrespondentSpec>
+
+// LookupTable Resource: maps messy extracted values to canonical ones
+export interface LookupEntry {
+  key: string
+  value: string
+}
+
+export interface LookupTableSpec {
+  /** exact compares keys ignoring case and whitespace; regex searches keys as patterns. */
+  mode?: 'exact' | 'regex'
+  entries?: LookupEntry[]
+  /** CSV file with key,value rows, relative to the config directory. */
+  file?: string
+  /** Value for keys no entry matches. Without it, the value is kept. */
+  fallback?: string
+}
+
+export type LookupTableResource = Resource<LookupTableSpec>
+
 // Any resource union type
-export type AnyResource = SettingsResource | VariableResource | RuleResource | ImportSourceResource
+export type AnyResource =
+  | SettingsResource
+  | VariableResource
+  | RuleResource
+  | ImportSourceResource
+  | RuleTestResource
+  | RuleTemplateResource
+  | CorrespondentResource
+  | LookupTableResource
 
 export interface ResourceListResponse {
   kind: string
@@ -185,6 +383,11 @@ export interface ResourceDetail {
   name: string
   path: string
   yaml: string
+  /**
+   * Rules extending another rule or a template, with inheritance resolved,
+   * or resources patched by the overlay.
+   */
+  effectiveYaml?: string
 }
 
 // File Tree types
@@ -193,12 +396,23 @@ export interface FileTreeNode {
   path: string
   isDirectory: boolean
   children: FileTreeNode[]
+  /** The first resource of the file. */
   resource?: ResourceInfo
+  /** All resources of files holding several. */
+  resources?: ResourceInfo[]
 }
 
 export interface ResourceInfo {
   kind: ResourceKind
   name: string
+  /** Where each field comes from, for resources patched by an overlay. */
+  fields?: FieldOrigin[]
+}
+
+export interface FieldOrigin {
+  /** Dotted path of the field, such as `spec.inputDirectory`. */
+  field: string
+  path: string
 }
 
 // Git types
@@ -397,7 +611,10 @@ export function isSimpleMatch(condition: MatchCondition): condition is SimpleMat
     'contains' in condition ||
     'containsAny' in condition ||
     'containsAll' in condition ||
-    'pattern' in condition
+    'pattern' in condition ||
+    'fuzzy' in condition ||
+    'containsAnyFuzzy' in condition ||
+    'variable' in condition
   )
 }
 
@@ -407,7 +624,17 @@ export function isCompoundMatch(condition: MatchCondition): condition is Compoun
 
 export function getMatchConditionType(
   condition: MatchCondition
-): 'contains' | 'containsAny' | 'containsAll' | 'pattern' | 'all' | 'any' | 'not' {
+):
+  | 'contains'
+  | 'containsAny'
+  | 'containsAll'
+  | 'pattern'
+  | 'fuzzy'
+  | 'containsAnyFuzzy'
+  | 'variable'
+  | 'all'
+  | 'any'
+  | 'not' {
   if ('all' in condition) return 'all'
   if ('any' in condition) return 'any'
   if ('not' in condition) return 'not'
@@ -415,6 +642,9 @@ export function getMatchConditionType(
   if ('containsAny' in condition) return 'containsAny'
   if ('containsAll' in condition) return 'containsAll'
   if ('pattern' in condition) return 'pattern'
+  if ('fuzzy' in condition) return 'fuzzy'
+  if ('containsAnyFuzzy' in condition) return 'containsAnyFuzzy'
+  if ('variable' in condition) return 'variable'
   return 'contains'
 }
 
@@ -443,6 +673,26 @@ export function createDefaultResource(kind: ResourceKind): AnyResource {
             languages: ['eng'],
             dpi: 300,
           },
+          limits: {
+            maxFileSizeMb: 100,
+            maxPages: 500,
+            jobTimeoutSecs: 900,
+            ocrPageTimeoutSecs: 120,
+            maxArchiveEntries: 1000,
+            maxArchiveSizeMb: 500,
+            maxArchiveDepth: 3,
+          },
+          categorization: {
+            mode: 'firstMatch',
+            threshold: 0.5,
+            alternatives: 3,
+            classifier: {
+              enabled: true,
+              autoFile: false,
+              threshold: 0.9,
+              minExamples: 5,
+            },
+          },
           defaults: {
             output: {
               directory: '$y/unsorted',
@@ -508,6 +758,53 @@ export function createDefaultResource(kind: ResourceKind): AnyResource {
           },
         },
       } as ImportSourceResource
+
+    case 'RuleTest':
+      return {
+        ...base,
+        kind: 'RuleTest',
+        spec: {
+          cases: [
+            {
+              name: '',
+              text: '',
+              expect: { category: '' },
+            },
+          ],
+        },
+      } as RuleTestResource
+
+    case 'RuleTemplate':
+      return {
+        ...base,
+        kind: 'RuleTemplate',
+        spec: {
+          output: {
+            directory: '$y/$category',
+            filename: '$original',
+          },
+        },
+      } as RuleTemplateResource
+
+    case 'Correspondent':
+      return {
+        ...base,
+        kind: 'Correspondent',
+        spec: {
+          aliases: [],
+          emailDomains: [],
+        },
+      } as CorrespondentResource
+
+    case 'LookupTable':
+      return {
+        ...base,
+        kind: 'LookupTable',
+        spec: {
+          mode: 'exact',
+          entries: [],
+        },
+      } as LookupTableResource
   }
 }
 
@@ -521,6 +818,14 @@ export function resourceKindToIcon(kind: ResourceKind): string {
       return 'rule'
     case 'ImportSource':
       return 'folder-input'
+    case 'RuleTest':
+      return 'flask-conical'
+    case 'RuleTemplate':
+      return 'layout-template'
+    case 'Correspondent':
+      return 'building-2'
+    case 'LookupTable':
+      return 'table'
   }
 }
 
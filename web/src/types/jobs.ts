@@ -12,6 +12,72 @@ export type JobPhase =
 
 export type JobStatus = 'processing' | 'completed' | 'failed' | 'superseded'
 
+/** A runner-up rule when categorizing in scoring mode. */
+export interface CategoryAlternative {
+  ruleId: string
+  category: string
+  confidence: number
+}
+
+/** Text found by a condition, as a UTF-8 byte range of a document field. */
+export interface MatchSpan {
+  /** `text` for the extracted text. */
+  field?: string
+  start: number
+  end: number
+  text: string
+}
+
+/** The evaluation of one node of a rule's condition tree. */
+export interface ConditionTrace {
+  kind: string
+  matched: boolean
+  field?: string
+  values?: string[]
+  spans?: MatchSpan[]
+  children?: ConditionTrace[]
+}
+
+export interface RuleExplanation {
+  ruleId: string
+  category: string
+  matched: boolean
+  score?: number
+  condition: ConditionTrace
+}
+
+/** Category predicted by the classifier when no rule matched. */
+export interface ClassifierPrediction {
+  category: string
+  confidence: number
+  /** Whether the document was filed under the category or it is only suggested. */
+  filed: boolean
+}
+
+/** What identified a document's correspondent. */
+export type CorrespondentEvidence =
+  | { kind: 'emailDomain'; value: string }
+  | { kind: 'iban'; value: string }
+  | { kind: 'vatNumber'; value: string }
+  | { kind: 'name'; value: string }
+
+/** The sender detected for a document. */
+export interface DetectedCorrespondent {
+  id: string
+  /** Display name, exposed as `$correspondent`. */
+  name: string
+  defaultCategory?: string
+  evidence: CorrespondentEvidence
+}
+
+/** Why a document was categorized the way it was. */
+export interface CategorizationExplanation {
+  winner?: RuleExplanation
+  rejected?: RuleExplanation[]
+  classifier?: ClassifierPrediction
+  correspondent?: DetectedCorrespondent
+}
+
 export interface JobProgressEvent {
   jobId: string
   filename: string
@@ -23,10 +89,15 @@ export interface JobProgressEvent {
   archivePath?: string
   symlinks: string[]
   category?: string
+  confidence?: number
+  alternatives?: CategoryAlternative[]
+  tags?: string[]
+  explanation?: CategorizationExplanation
   error?: string
   sourcePath?: string
   sourceName?: string
   mimeType?: string
+  parentJobId?: string
 }
 
 export interface StoredJob {
@@ -40,12 +111,17 @@ export interface StoredJob {
   archivePath?: string
   symlinks: string[]
   category?: string
+  confidence?: number
+  alternatives?: CategoryAlternative[]
+  tags?: string[]
+  explanation?: CategorizationExplanation
   error?: string
   message: string
   sourcePath?: string
   sourceName?: string
   ignored?: boolean
   mimeType?: string
+  parentJobId?: string
 }
 
 export interface JobsResponse {
@@ -60,6 +136,7 @@ export interface JobQueryParams {
   status?: string
   category?: string
   sourceName?: string
+  tag?: string
   fromDate?: string
   toDate?: string
   limit?: number
@@ -127,6 +204,9 @@ const MIME_TYPE_LABELS: Record<string, string> = {
   'image/gif': 'GIF',
   'image/webp': 'WebP',
   'image/tiff': 'TIFF',
+  'image/heic': 'HEIC',
+  'image/heif': 'HEIF',
+  'image/avif': 'AVIF',
   'image/bmp': 'BMP',
   'text/plain': 'Text',
   'text/html': 'HTML',
@@ -134,8 +214,13 @@ const MIME_TYPE_LABELS: Record<string, string> = {
   'application/json': 'JSON',
   'application/msword': 'Word',
   'application/vnd.openxmlformats-officedocument.wordprocessingml.document': 'Word',
+  'application/vnd.oasis.opendocument.text': 'ODT',
+  'application/rtf': 'RTF',
+  'text/rtf': 'RTF',
   'application/vnd.ms-excel': 'Excel',
   'application/vnd.openxmlformats-officedocument.spreadsheetml.sheet': 'Excel',
+  'application/zip': 'ZIP',
+  'application/x-7z-compressed': '7z',
   'application/octet-stream': 'Binary',
 }
 